@@ -0,0 +1,83 @@
+//! Object ages and durations
+//!
+//! Every listed resource shows its age the way kubectl does: the time since
+//! its creationTimestamp in its largest whole unit.
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+/// Creation time of an object
+pub fn created_at(metadata: &ObjectMeta) -> Option<DateTime<Utc>> {
+    metadata.creation_timestamp.as_ref().map(|t| t.0)
+}
+
+/// Age of an object from its creationTimestamp (e.g. "45s", "3d")
+pub fn object_age(metadata: &ObjectMeta) -> String {
+    format_age(created_at(metadata))
+}
+
+/// Format the time elapsed since `created_at` as a short age (e.g. "45s", "3d")
+pub fn format_age(created_at: Option<DateTime<Utc>>) -> String {
+    let Some(created_at) = created_at else {
+        return "Unknown".to_string();
+    };
+
+    format_duration((Utc::now() - created_at).num_seconds())
+}
+
+/// Format a number of seconds in its largest whole unit (e.g. "45s", "3d")
+pub fn format_duration(seconds: i64) -> String {
+    match seconds.max(0) {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+/// Read back an age printed by [`format_duration`] as seconds
+pub fn parse_duration(age: &str) -> Option<i64> {
+    let unit = match age.chars().last()? {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        _ => return None,
+    };
+    let value: i64 = age[..age.len() - 1].parse().ok()?;
+    Some(value * unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc::now();
+        assert_eq!(format_age(None), "Unknown");
+        assert_eq!(format_age(Some(now - chrono::Duration::seconds(30))), "30s");
+        assert_eq!(format_age(Some(now - chrono::Duration::minutes(5))), "5m");
+        assert_eq!(format_age(Some(now - chrono::Duration::hours(7))), "7h");
+        assert_eq!(format_age(Some(now - chrono::Duration::days(12))), "12d");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45s"), Some(45));
+        assert_eq!(parse_duration(&format_duration(7 * 3600)), Some(7 * 3600));
+        assert_eq!(parse_duration("12d"), Some(12 * 86400));
+        assert_eq!(parse_duration("Unknown"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_object_age() {
+        let mut metadata = ObjectMeta::default();
+        assert_eq!(object_age(&metadata), "Unknown");
+
+        metadata.creation_timestamp = Some(Time(Utc::now() - chrono::Duration::hours(3)));
+        assert_eq!(object_age(&metadata), "3h");
+    }
+}
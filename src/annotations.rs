@@ -0,0 +1,199 @@
+//! Annotation key inventory and annotation-based resource lookup
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of example resources kept per annotation key
+const MAX_EXAMPLES: usize = 3;
+
+/// Longest annotation value shown in listings before truncation
+const MAX_VALUE_DISPLAY: usize = 60;
+
+/// Annotations of a single resource
+#[derive(Debug, Clone)]
+pub struct AnnotatedObject {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl AnnotatedObject {
+    /// `kind/namespace/name` reference used in listings
+    pub fn reference(&self) -> String {
+        match &self.namespace {
+            Some(ns) => format!("{}/{}/{}", self.kind, ns, self.name),
+            None => format!("{}/{}", self.kind, self.name),
+        }
+    }
+}
+
+/// Usage of one annotation key across resources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationKeyUsage {
+    pub key: String,
+    pub count: usize,
+    /// Combined size of all values in bytes
+    pub total_bytes: usize,
+    pub max_bytes: usize,
+    pub examples: Vec<String>,
+}
+
+/// A resource carrying a matching annotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationMatch {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub key: String,
+    pub bytes: usize,
+    pub value: String,
+}
+
+/// Distinct annotation keys with usage counts, most used first
+pub fn annotation_inventory(objects: &[AnnotatedObject]) -> Vec<AnnotationKeyUsage> {
+    let mut usage: HashMap<&str, AnnotationKeyUsage> = HashMap::new();
+
+    for object in objects {
+        for (key, value) in &object.annotations {
+            let entry = usage.entry(key).or_insert_with(|| AnnotationKeyUsage {
+                key: key.clone(),
+                count: 0,
+                total_bytes: 0,
+                max_bytes: 0,
+                examples: Vec::new(),
+            });
+            entry.count += 1;
+            entry.total_bytes += value.len();
+            entry.max_bytes = entry.max_bytes.max(value.len());
+            if entry.examples.len() < MAX_EXAMPLES {
+                entry.examples.push(object.reference());
+            }
+        }
+    }
+
+    let mut inventory: Vec<AnnotationKeyUsage> = usage.into_values().collect();
+    inventory.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    inventory
+}
+
+/// Resources with an annotation matching `key` (exact) and/or `value_regex`,
+/// largest values first
+pub fn find_annotated(
+    objects: &[AnnotatedObject],
+    key: Option<&str>,
+    value_regex: Option<&Regex>,
+) -> Vec<AnnotationMatch> {
+    let mut matches: Vec<AnnotationMatch> = objects
+        .iter()
+        .flat_map(|object| {
+            object
+                .annotations
+                .iter()
+                .filter(|(k, v)| {
+                    key.is_none_or(|key| k.as_str() == key)
+                        && value_regex.is_none_or(|re| re.is_match(v))
+                })
+                .map(|(k, v)| AnnotationMatch {
+                    kind: object.kind.clone(),
+                    namespace: object.namespace.clone(),
+                    name: object.name.clone(),
+                    key: k.clone(),
+                    bytes: v.len(),
+                    value: truncate(v),
+                })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| std::cmp::Reverse(m.bytes));
+    matches
+}
+
+fn truncate(value: &str) -> String {
+    let single_line = value.replace('\n', " ");
+    if single_line.chars().count() <= MAX_VALUE_DISPLAY {
+        single_line
+    } else {
+        let prefix: String = single_line.chars().take(MAX_VALUE_DISPLAY).collect();
+        format!("{}...", prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(kind: &str, name: &str, annotations: &[(&str, &str)]) -> AnnotatedObject {
+        AnnotatedObject {
+            kind: kind.to_string(),
+            namespace: Some("default".to_string()),
+            name: name.to_string(),
+            annotations: annotations
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn objects() -> Vec<AnnotatedObject> {
+        let applied = "{\"apiVersion\":\"apps/v1\",\"kind\":\"Deployment\"}";
+        vec![
+            object(
+                "Deployment",
+                "web",
+                &[
+                    ("kubectl.kubernetes.io/last-applied-configuration", applied),
+                    ("deployment.kubernetes.io/revision", "4"),
+                ],
+            ),
+            object(
+                "Service",
+                "web",
+                &[("kubectl.kubernetes.io/last-applied-configuration", "{}")],
+            ),
+            object("Pod", "web-1", &[]),
+        ]
+    }
+
+    #[test]
+    fn test_annotation_inventory() {
+        let inventory = annotation_inventory(&objects());
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(
+            inventory[0].key,
+            "kubectl.kubernetes.io/last-applied-configuration"
+        );
+        assert_eq!(inventory[0].count, 2);
+        assert_eq!(inventory[0].max_bytes, 44);
+        assert_eq!(inventory[0].total_bytes, 46);
+        assert_eq!(
+            inventory[0].examples,
+            vec!["Deployment/default/web", "Service/default/web"]
+        );
+    }
+
+    #[test]
+    fn test_find_annotated() {
+        let objects = objects();
+        let matches = find_annotated(
+            &objects,
+            Some("kubectl.kubernetes.io/last-applied-configuration"),
+            None,
+        );
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].kind, "Deployment");
+
+        let re = Regex::new("^[0-9]+$").unwrap();
+        let matches = find_annotated(&objects, None, Some(&re));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].key, "deployment.kubernetes.io/revision");
+    }
+
+    #[test]
+    fn test_truncate_long_values() {
+        let long = "x".repeat(100);
+        assert_eq!(truncate(&long).len(), MAX_VALUE_DISPLAY + 3);
+        assert_eq!(truncate("a\nb"), "a b");
+    }
+}
@@ -1,9 +1,135 @@
 //! Caching system for Kubernetes resource discovery
 
+use crate::age::format_duration;
+use crate::cli::parse_duration;
 use crate::discovery::*;
+use crate::disk_cache::{DiskCache, DiskCounters};
+use crate::envelope::Warnings;
+use crate::pod_index::PodIndex;
 use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// Directory for data kept between runs: `$KDX_CACHE_DIR`, else
+/// `$XDG_CACHE_HOME/kdx`, else `~/.cache/kdx`
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("KDX_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CACHE_HOME") {
+        return Some(PathBuf::from(dir).join("kdx"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache").join("kdx"))
+}
+
+/// Kinds of listings the cache holds, each with its own TTL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CacheKind {
+    Services,
+    /// Pod listings and pod indexes
+    Pods,
+    Deployments,
+    StatefulSets,
+    DaemonSets,
+    ConfigMaps,
+    Secrets,
+    Crds,
+    CustomResources,
+}
+
+impl CacheKind {
+    pub const ALL: [CacheKind; 9] = [
+        CacheKind::Services,
+        CacheKind::Pods,
+        CacheKind::Deployments,
+        CacheKind::StatefulSets,
+        CacheKind::DaemonSets,
+        CacheKind::ConfigMaps,
+        CacheKind::Secrets,
+        CacheKind::Crds,
+        CacheKind::CustomResources,
+    ];
+
+    /// Name in TTL settings, and of the kind's directory in the disk cache
+    pub fn name(self) -> &'static str {
+        match self {
+            CacheKind::Services => "services",
+            CacheKind::Pods => "pods",
+            CacheKind::Deployments => "deployments",
+            CacheKind::StatefulSets => "statefulsets",
+            CacheKind::DaemonSets => "daemonsets",
+            CacheKind::ConfigMaps => "configmaps",
+            CacheKind::Secrets => "secrets",
+            CacheKind::Crds => "crds",
+            CacheKind::CustomResources => "custom-resources",
+        }
+    }
+}
+
+impl std::fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CacheKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(value))
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "unknown cached resource '{}' (expected default or one of: {})",
+                    value,
+                    known.join(", ")
+                )
+            })
+    }
+}
+
+/// A TTL setting: `pods=15s` for one kind, or `5m` (`default=5m`) for
+/// every kind without its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheTtl {
+    /// `None` for the default TTL
+    pub kind: Option<CacheKind>,
+    pub ttl: Duration,
+}
+
+impl CacheTtl {
+    /// The setting of `kind` (`default` or a kind name) to `duration`
+    pub fn parse(kind: &str, duration: &str) -> Result<Self, String> {
+        let kind = match kind.trim() {
+            "default" => None,
+            kind => Some(kind.parse()?),
+        };
+        Ok(Self {
+            kind,
+            ttl: parse_duration(duration)?,
+        })
+    }
+}
+
+impl FromStr for CacheTtl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((kind, duration)) => Self::parse(kind, duration),
+            None => Self::parse("default", value),
+        }
+    }
+}
+
 /// Cache entry with TTL support
 #[derive(Clone)]
 pub struct CacheEntry<T> {
@@ -41,7 +167,21 @@ pub struct ResourceCache {
     secrets: DashMap<String, CacheEntry<Vec<SecretInfo>>>,
     crds: DashMap<String, CacheEntry<Vec<CRDInfo>>>,
     custom_resources: DashMap<String, CacheEntry<Vec<CustomResourceInfo>>>,
+    pod_indexes: DashMap<String, CacheEntry<Arc<PodIndex>>>,
+    /// Held while a pod index is built, so concurrent passes build it once
+    pod_index_builds: DashMap<String, Arc<tokio::sync::Mutex<()>>>,
     default_ttl: Duration,
+    /// TTLs of kinds that do not use the default
+    ttls: HashMap<CacheKind, Duration>,
+    /// Listings persisted between runs, if persistence is on
+    disk: Option<DiskCache>,
+    /// Where listings served from disk are noted for `--envelope`
+    warnings: Warnings,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    disk_hits: AtomicU64,
+    /// Entries dropped because they expired
+    evictions: AtomicU64,
 }
 
 impl ResourceCache {
@@ -57,10 +197,114 @@ impl ResourceCache {
             secrets: DashMap::new(),
             crds: DashMap::new(),
             custom_resources: DashMap::new(),
+            pod_indexes: DashMap::new(),
+            pod_index_builds: DashMap::new(),
             default_ttl,
+            ttls: HashMap::new(),
+            disk: None,
+            warnings: Warnings::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            disk_hits: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         }
     }
 
+    /// Cache listings of `kind` for `ttl` instead of the default TTL
+    pub fn with_ttl(mut self, kind: CacheKind, ttl: Duration) -> Self {
+        self.ttls.insert(kind, ttl);
+        self
+    }
+
+    /// How long listings of `kind` are served from the cache
+    pub fn ttl(&self, kind: CacheKind) -> Duration {
+        self.ttls.get(&kind).copied().unwrap_or(self.default_ttl)
+    }
+
+    /// Also read listings from, and write them to, `disk`
+    pub fn with_disk(mut self, disk: DiskCache) -> Self {
+        self.disk = Some(disk);
+        self
+    }
+
+    /// Note listings served from disk in `warnings`
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Fresh entry for `key` in memory. Expired entries are evicted.
+    fn fresh<T: Clone>(&self, map: &DashMap<String, CacheEntry<T>>, key: &str) -> Option<T> {
+        // The read guard is released before an expired entry is removed
+        let fresh = map
+            .get(key)
+            .map(|entry| (!entry.is_expired()).then(|| entry.data().clone()));
+        match fresh {
+            Some(Some(data)) => Some(data),
+            Some(None) => {
+                map.remove(key);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn count(&self, found: bool) {
+        let counter = if found { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Fresh entry for `key`, counting the lookup as a hit or miss
+    fn lookup<T: Clone>(&self, map: &DashMap<String, CacheEntry<T>>, key: &str) -> Option<T> {
+        let found = self.fresh(map, key);
+        self.count(found.is_some());
+        found
+    }
+
+    /// Fresh entry for `key` in memory, or else on disk under `kind`. A
+    /// listing read from disk is kept in memory for the rest of its TTL.
+    fn lookup_persisted<T>(
+        &self,
+        map: &DashMap<String, CacheEntry<T>>,
+        kind: CacheKind,
+        key: &str,
+    ) -> Option<T>
+    where
+        T: Clone + DeserializeOwned,
+    {
+        let found = self.fresh(map, key).or_else(|| {
+            let (data, remaining) = self.disk.as_ref()?.load::<T>(kind.name(), key)?;
+            map.insert(key.to_string(), CacheEntry::new(data.clone(), remaining));
+            self.disk_hits.fetch_add(1, Ordering::Relaxed);
+            self.warnings.warn(format!(
+                "{} ({}) served from the persisted cache, stored about {} ago",
+                kind,
+                key,
+                format_duration(self.ttl(kind).saturating_sub(remaining).as_secs() as i64)
+            ));
+            Some(data)
+        });
+        self.count(found.is_some());
+        found
+    }
+
+    /// Cache `data` under `key` for the TTL of `kind`, on disk too when
+    /// persistence is on
+    fn insert<T: Serialize>(
+        &self,
+        map: &DashMap<String, CacheEntry<T>>,
+        kind: CacheKind,
+        key: String,
+        data: T,
+    ) {
+        let ttl = self.ttl(kind);
+        if let Some(disk) = &self.disk {
+            disk.store(kind.name(), &key, &data, ttl);
+        }
+        map.insert(key, CacheEntry::new(data, ttl));
+    }
+
     /// Generate cache key for namespace-scoped resources
     fn namespace_key(namespace: Option<&str>, selector: Option<&str>) -> String {
         match (namespace, selector) {
@@ -78,15 +322,7 @@ impl ResourceCache {
         selector: Option<&str>,
     ) -> Option<Vec<ServiceInfo>> {
         let key = Self::namespace_key(namespace, selector);
-        if let Some(entry) = self.services.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                // Remove expired entry
-                self.services.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.services, CacheKind::Services, &key)
     }
 
     /// Set services in cache
@@ -97,8 +333,7 @@ impl ResourceCache {
         data: Vec<ServiceInfo>,
     ) {
         let key = Self::namespace_key(namespace, selector);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.services.insert(key, entry);
+        self.insert(&self.services, CacheKind::Services, key, data);
     }
 
     /// Get pods from cache
@@ -108,149 +343,86 @@ impl ResourceCache {
         selector: Option<&str>,
     ) -> Option<Vec<PodInfo>> {
         let key = Self::namespace_key(namespace, selector);
-        if let Some(entry) = self.pods.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.pods.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.pods, CacheKind::Pods, &key)
     }
 
     /// Set pods in cache
     pub fn set_pods(&self, namespace: Option<&str>, selector: Option<&str>, data: Vec<PodInfo>) {
         let key = Self::namespace_key(namespace, selector);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.pods.insert(key, entry);
+        self.insert(&self.pods, CacheKind::Pods, key, data);
     }
 
     /// Get deployments from cache
     pub fn get_deployments(&self, namespace: Option<&str>) -> Option<Vec<DeploymentInfo>> {
         let key = Self::namespace_key(namespace, None);
-        if let Some(entry) = self.deployments.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.deployments.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.deployments, CacheKind::Deployments, &key)
     }
 
     /// Set deployments in cache
     pub fn set_deployments(&self, namespace: Option<&str>, data: Vec<DeploymentInfo>) {
         let key = Self::namespace_key(namespace, None);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.deployments.insert(key, entry);
+        self.insert(&self.deployments, CacheKind::Deployments, key, data);
     }
 
     /// Get statefulsets from cache
-    #[allow(dead_code)]
     pub fn get_statefulsets(&self, namespace: Option<&str>) -> Option<Vec<StatefulSetInfo>> {
         let key = Self::namespace_key(namespace, None);
-        if let Some(entry) = self.statefulsets.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.statefulsets.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.statefulsets, CacheKind::StatefulSets, &key)
     }
 
     /// Set statefulsets in cache
-    #[allow(dead_code)]
     pub fn set_statefulsets(&self, namespace: Option<&str>, data: Vec<StatefulSetInfo>) {
         let key = Self::namespace_key(namespace, None);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.statefulsets.insert(key, entry);
+        self.insert(&self.statefulsets, CacheKind::StatefulSets, key, data);
     }
 
     /// Get daemonsets from cache
-    #[allow(dead_code)]
     pub fn get_daemonsets(&self, namespace: Option<&str>) -> Option<Vec<DaemonSetInfo>> {
         let key = Self::namespace_key(namespace, None);
-        if let Some(entry) = self.daemonsets.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.daemonsets.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.daemonsets, CacheKind::DaemonSets, &key)
     }
 
     /// Set daemonsets in cache
-    #[allow(dead_code)]
     pub fn set_daemonsets(&self, namespace: Option<&str>, data: Vec<DaemonSetInfo>) {
         let key = Self::namespace_key(namespace, None);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.daemonsets.insert(key, entry);
+        self.insert(&self.daemonsets, CacheKind::DaemonSets, key, data);
     }
 
     /// Get configmaps from cache
     pub fn get_configmaps(&self, namespace: Option<&str>) -> Option<Vec<ConfigMapInfo>> {
         let key = Self::namespace_key(namespace, None);
-        if let Some(entry) = self.configmaps.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.configmaps.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.configmaps, CacheKind::ConfigMaps, &key)
     }
 
     /// Set configmaps in cache
     pub fn set_configmaps(&self, namespace: Option<&str>, data: Vec<ConfigMapInfo>) {
         let key = Self::namespace_key(namespace, None);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.configmaps.insert(key, entry);
+        self.insert(&self.configmaps, CacheKind::ConfigMaps, key, data);
     }
 
     /// Get secrets from cache
-    #[allow(dead_code)]
     pub fn get_secrets(&self, namespace: Option<&str>) -> Option<Vec<SecretInfo>> {
         let key = Self::namespace_key(namespace, None);
-        if let Some(entry) = self.secrets.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.secrets.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.secrets, CacheKind::Secrets, &key)
     }
 
     /// Set secrets in cache
-    #[allow(dead_code)]
     pub fn set_secrets(&self, namespace: Option<&str>, data: Vec<SecretInfo>) {
         let key = Self::namespace_key(namespace, None);
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.secrets.insert(key, entry);
+        self.insert(&self.secrets, CacheKind::Secrets, key, data);
     }
 
     /// Get custom resources from cache
-    #[allow(dead_code)]
     pub fn get_custom_resources(
         &self,
         crd_name: &str,
         namespace: Option<&str>,
     ) -> Option<Vec<CustomResourceInfo>> {
         let key = format!("{}:{}", crd_name, Self::namespace_key(namespace, None));
-        if let Some(entry) = self.custom_resources.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.custom_resources.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.custom_resources, CacheKind::CustomResources, &key)
     }
 
     /// Set custom resources in cache
-    #[allow(dead_code)]
     pub fn set_custom_resources(
         &self,
         crd_name: &str,
@@ -258,30 +430,43 @@ impl ResourceCache {
         data: Vec<CustomResourceInfo>,
     ) {
         let key = format!("{}:{}", crd_name, Self::namespace_key(namespace, None));
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.custom_resources.insert(key, entry);
+        self.insert(
+            &self.custom_resources,
+            CacheKind::CustomResources,
+            key,
+            data,
+        );
     }
 
     /// Get CRDs from cache
-    #[allow(dead_code)]
     pub fn get_crds(&self) -> Option<Vec<CRDInfo>> {
         let key = "all".to_string();
-        if let Some(entry) = self.crds.get(&key) {
-            if !entry.is_expired() {
-                return Some(entry.data().clone());
-            } else {
-                self.crds.remove(&key);
-            }
-        }
-        None
+        self.lookup_persisted(&self.crds, CacheKind::Crds, &key)
     }
 
     /// Set CRDs in cache
-    #[allow(dead_code)]
     pub fn set_crds(&self, data: Vec<CRDInfo>) {
         let key = "all".to_string();
-        let entry = CacheEntry::new(data, self.default_ttl);
-        self.crds.insert(key, entry);
+        self.insert(&self.crds, CacheKind::Crds, key, data);
+    }
+
+    /// Get the pod index of a namespace, or of all namespaces, from cache
+    pub fn get_pod_index(&self, namespace: Option<&str>) -> Option<Arc<PodIndex>> {
+        let key = Self::namespace_key(namespace, None);
+        self.lookup(&self.pod_indexes, &key)
+    }
+
+    /// Set the pod index of a namespace, or of all namespaces, in cache
+    pub fn set_pod_index(&self, namespace: Option<&str>, data: Arc<PodIndex>) {
+        let key = Self::namespace_key(namespace, None);
+        let entry = CacheEntry::new(data, self.ttl(CacheKind::Pods));
+        self.pod_indexes.insert(key, entry);
+    }
+
+    /// Lock to hold while building the pod index of `namespace`
+    pub fn pod_index_lock(&self, namespace: Option<&str>) -> Arc<tokio::sync::Mutex<()>> {
+        let key = Self::namespace_key(namespace, None);
+        self.pod_index_builds.entry(key).or_default().clone()
     }
 
     /// Clear all cached data
@@ -295,6 +480,23 @@ impl ResourceCache {
         self.secrets.clear();
         self.crds.clear();
         self.custom_resources.clear();
+        self.pod_indexes.clear();
+        if let Some(disk) = &self.disk {
+            disk.clear();
+        }
+    }
+
+    /// Add this run's lookups to the counters kept on disk, when
+    /// persistence is on
+    pub fn persist_counters(&self) {
+        if let Some(disk) = &self.disk {
+            disk.add_counters(DiskCounters {
+                hits: self.hits.load(Ordering::Relaxed),
+                misses: self.misses.load(Ordering::Relaxed),
+                disk_hits: self.disk_hits.load(Ordering::Relaxed),
+                evictions: self.evictions.load(Ordering::Relaxed),
+            });
+        }
     }
 
     /// Get cache statistics
@@ -309,28 +511,48 @@ impl ResourceCache {
             secrets_entries: self.secrets.len(),
             crds_entries: self.crds.len(),
             custom_resources_entries: self.custom_resources.len(),
+            pod_index_entries: self.pod_indexes.len(),
             default_ttl: self.default_ttl,
+            ttls: CacheKind::ALL
+                .into_iter()
+                .map(|kind| (kind, self.ttl(kind)))
+                .collect(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
         }
     }
 
     /// Clean up expired entries
     #[allow(dead_code)]
     pub fn cleanup_expired(&self) {
-        // Clean services
-        self.services.retain(|_, entry| !entry.is_expired());
-        self.pods.retain(|_, entry| !entry.is_expired());
-        self.deployments.retain(|_, entry| !entry.is_expired());
-        self.statefulsets.retain(|_, entry| !entry.is_expired());
-        self.daemonsets.retain(|_, entry| !entry.is_expired());
-        self.configmaps.retain(|_, entry| !entry.is_expired());
-        self.secrets.retain(|_, entry| !entry.is_expired());
-        self.crds.retain(|_, entry| !entry.is_expired());
-        self.custom_resources.retain(|_, entry| !entry.is_expired());
+        fn evict<T>(map: &DashMap<String, CacheEntry<T>>) -> usize {
+            let mut evicted = 0;
+            map.retain(|_, entry| {
+                let expired = entry.is_expired();
+                evicted += usize::from(expired);
+                !expired
+            });
+            evicted
+        }
+
+        let evicted = evict(&self.services)
+            + evict(&self.pods)
+            + evict(&self.deployments)
+            + evict(&self.statefulsets)
+            + evict(&self.daemonsets)
+            + evict(&self.configmaps)
+            + evict(&self.secrets)
+            + evict(&self.crds)
+            + evict(&self.custom_resources)
+            + evict(&self.pod_indexes);
+        self.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
     }
 }
 
 /// Cache statistics
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct CacheStats {
     pub services_entries: usize,
     pub pods_entries: usize,
@@ -341,7 +563,18 @@ pub struct CacheStats {
     pub secrets_entries: usize,
     pub crds_entries: usize,
     pub custom_resources_entries: usize,
+    pub pod_index_entries: usize,
     pub default_ttl: Duration,
+    /// TTL of every kind, the default included
+    pub ttls: BTreeMap<CacheKind, Duration>,
+    /// Lookups answered from the cache since it was created
+    pub hits: u64,
+    /// Lookups that found no fresh entry
+    pub misses: u64,
+    /// Hits answered from the disk cache
+    pub disk_hits: u64,
+    /// Entries dropped because they expired
+    pub evictions: u64,
 }
 
 impl CacheStats {
@@ -355,6 +588,7 @@ impl CacheStats {
             + self.secrets_entries
             + self.crds_entries
             + self.custom_resources_entries
+            + self.pod_index_entries
     }
 }
 
@@ -371,6 +605,8 @@ mod tests {
             cluster_ip: Some("10.0.0.1".to_string()),
             ports: vec![],
             selector: Some(BTreeMap::new()),
+            created_at: None,
+            annotations: BTreeMap::new(),
         }
     }
 
@@ -402,6 +638,43 @@ mod tests {
         let stats = cache.stats();
         assert_eq!(stats.services_entries, 1);
         assert_eq!(stats.total_entries(), 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn test_expired_entry_counts_as_miss() {
+        let cache = ResourceCache::new(Duration::from_millis(1));
+        cache.set_services(Some("default"), None, vec![create_test_service()]);
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(cache.get_services(Some("default"), None).is_none());
+        assert_eq!(cache.stats().services_entries, 0);
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn test_per_kind_ttl_and_evictions() {
+        let cache = ResourceCache::new(Duration::from_secs(300))
+            .with_ttl(CacheKind::Services, Duration::from_millis(1));
+        assert_eq!(cache.ttl(CacheKind::Pods), Duration::from_secs(300));
+
+        cache.set_services(Some("default"), None, vec![create_test_service()]);
+        cache.set_pods(Some("default"), None, vec![]);
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(cache.get_services(Some("default"), None).is_none());
+        assert!(cache.get_pods(Some("default"), None).is_some());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 1, 1));
+        assert_eq!(stats.ttls[&CacheKind::Services], Duration::from_millis(1));
+        assert_eq!(stats.ttls[&CacheKind::Crds], Duration::from_secs(300));
+
+        let setting: CacheTtl = "custom-resources=1h".parse().unwrap();
+        assert_eq!(setting.kind, Some(CacheKind::CustomResources));
+        assert_eq!(setting.ttl, Duration::from_secs(3600));
+        assert_eq!("90".parse::<CacheTtl>().unwrap().kind, None);
+        assert!("nodes=1m".parse::<CacheTtl>().is_err());
     }
 
     #[test]
@@ -448,6 +721,9 @@ mod tests {
             age: "1d".to_string(),
             labels: std::collections::BTreeMap::new(),
             selector: std::collections::BTreeMap::new(),
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         }];
 
         // Test set and get
@@ -472,6 +748,8 @@ mod tests {
             labels: std::collections::BTreeMap::new(),
             used_by: vec![],
             mount_paths: vec![],
+            created_at: None,
+            annotations: BTreeMap::new(),
         }];
 
         cache.set_configmaps(Some("default"), configmaps.clone());
@@ -494,6 +772,9 @@ mod tests {
             labels: std::collections::BTreeMap::new(),
             used_by: vec![],
             mount_paths: vec![],
+            service_account: None,
+            created_at: None,
+            annotations: BTreeMap::new(),
         }];
 
         cache.set_secrets(Some("default"), secrets.clone());
@@ -520,6 +801,7 @@ mod tests {
             spec_summary: "{}".to_string(),
             status_summary: None,
             related_resources: vec![],
+            created_at: None,
         }];
 
         cache.set_custom_resources("testresources", Some("default"), custom_resources.clone());
@@ -552,6 +834,9 @@ mod tests {
             instance_count: 0,
             versions: vec![],
             description: None,
+            controller: None,
+            created_at: None,
+            annotations: BTreeMap::new(),
         }];
 
         cache.set_crds(crds.clone());
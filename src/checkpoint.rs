@@ -0,0 +1,159 @@
+//! Per-namespace progress checkpoints for resumable all-namespace scans
+//!
+//! Each completed namespace is appended to a JSON Lines file as soon as it
+//! finishes, so an interrupted or rate-limited scan can be continued with
+//! `--resume` without fetching the same namespaces again.
+
+use crate::cache::cache_dir;
+use crate::clusters;
+use crate::disk_cache::encode;
+use crate::error::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// One line of the checkpoint file
+#[derive(Deserialize)]
+struct CompletedNamespace<T> {
+    namespace: String,
+    items: Vec<T>,
+}
+
+/// Borrowed form of [`CompletedNamespace`] used when writing
+#[derive(Serialize)]
+struct CompletedNamespaceRef<'a, T> {
+    namespace: &'a str,
+    items: &'a [T],
+}
+
+/// Progress of a single all-namespace scan
+pub struct ScanCheckpoint<T> {
+    path: Option<PathBuf>,
+    completed: BTreeMap<String, Vec<T>>,
+}
+
+impl<T: Serialize + DeserializeOwned> ScanCheckpoint<T> {
+    /// Open the checkpoint for `key` in the cache directory. With `resume` the
+    /// namespaces completed by a previous run are loaded; otherwise any earlier
+    /// checkpoint is discarded. Without a usable cache directory progress is
+    /// not recorded.
+    pub fn open(key: &str, resume: bool) -> Self {
+        let path = cache_dir().map(|dir| dir.join("checkpoints").join(file_name(key)));
+        Self::open_at(path, resume)
+    }
+
+    fn open_at(path: Option<PathBuf>, resume: bool) -> Self {
+        let mut completed = BTreeMap::new();
+
+        if let Some(path) = &path {
+            if resume {
+                // A line cut short by an interruption is skipped; that namespace is fetched again
+                let content = fs::read_to_string(path).unwrap_or_default();
+                for line in content.lines() {
+                    if let Ok(entry) = serde_json::from_str::<CompletedNamespace<T>>(line) {
+                        completed.insert(entry.namespace, entry.items);
+                    }
+                }
+            } else {
+                let _ = fs::remove_file(path);
+            }
+        }
+
+        Self { path, completed }
+    }
+
+    /// Items of a namespace completed by a previous run
+    pub fn completed(&self, namespace: &str) -> Option<&Vec<T>> {
+        self.completed.get(namespace)
+    }
+
+    /// Append a finished namespace to the checkpoint file
+    pub fn record(&self, namespace: &str, items: &[T]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let entry = CompletedNamespaceRef { namespace, items };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// Remove the checkpoint once the scan has completed
+    pub fn finish(&self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Checkpoint key identifying a scan by cluster (see
+/// [`clusters::cache_key`]), resource type and selector
+pub fn checkpoint_key(context: Option<&str>, resource: &str, selector: Option<&str>) -> String {
+    scan_key(&clusters::cache_key(context), resource, selector)
+}
+
+/// Parts are percent-encoded, which never yields `_`, so distinct scans
+/// never share a key
+fn scan_key(cluster: &str, resource: &str, selector: Option<&str>) -> String {
+    [cluster, resource, selector.unwrap_or("all")]
+        .map(encode)
+        .join("_")
+}
+
+fn file_name(key: &str) -> String {
+    format!("{}.jsonl", encode(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("kdx-{}-{}.jsonl", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let path = temp_path("resume");
+
+        let checkpoint: ScanCheckpoint<String> = ScanCheckpoint::open_at(Some(path.clone()), false);
+        checkpoint
+            .record("default", &["web".to_string(), "api".to_string()])
+            .unwrap();
+        checkpoint.record("kube-system", &[]).unwrap();
+        // Simulate an interrupted write
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"namespace\":\"prod\",\"items\":[\"db").unwrap();
+
+        let resumed: ScanCheckpoint<String> = ScanCheckpoint::open_at(Some(path.clone()), true);
+        assert!(resumed.completed("kube-system").unwrap().is_empty());
+        assert_eq!(resumed.completed("default").unwrap().len(), 2);
+        assert!(resumed.completed("prod").is_none());
+
+        // Starting without --resume discards earlier progress
+        let fresh: ScanCheckpoint<String> = ScanCheckpoint::open_at(Some(path.clone()), false);
+        assert!(fresh.completed("default").is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_checkpoint_file_name() {
+        let key = scan_key("https://prod:6443", "pods", Some("app=web,tier!=cache"));
+        assert_eq!(
+            file_name(&key),
+            "https%253A%252F%252Fprod%253A6443%5Fpods%5Fapp%253Dweb%252Ctier%2521%253Dcache.jsonl"
+        );
+        assert_eq!(scan_key("prod", "services", None), "prod_services_all");
+        assert_ne!(
+            scan_key("prod", "pods", Some("app=web")),
+            scan_key("prod", "pods", Some("app_web"))
+        );
+    }
+}
@@ -1,6 +1,21 @@
 //! Command-line interface definitions
 
+use crate::cache::CacheTtl;
+use crate::envelope;
+use crate::filtering::{FieldSelector, LabelSelector, NameFilter};
+use crate::jsonpath::{Column, JsonPath};
+use crate::network::{Cidr, NodePortRange};
+use crate::portforward::PortMapping;
+use crate::rbac::ResourceQuery;
+use crate::resource::{ObjectRef, ResourceKind};
+use crate::template::Template;
+use crate::visibility::ServiceAccount;
+use crate::wait::WaitCondition;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[clap(name = "kdx", version = env!("CARGO_PKG_VERSION"))]
@@ -12,17 +27,73 @@ pub struct Cli {
     #[clap(subcommand)]
     pub command: Commands,
 
-    /// Kubernetes context to use
-    #[clap(long, global = true)]
-    pub context: Option<String>,
+    /// Kubernetes context to use; list commands query every context given
+    /// when repeated
+    #[clap(long = "context", global = true, value_name = "CONTEXT")]
+    pub contexts: Vec<String>,
+
+    /// Query the clusters of every kubeconfig context (list commands)
+    #[clap(long, global = true, conflicts_with = "contexts")]
+    pub all_contexts: bool,
+
+    /// Config file to use instead of ~/.config/kdx/config.toml (or
+    /// config.yaml)
+    #[clap(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
 
     /// Default namespace to use
     #[clap(long, short = 'n', global = true)]
     pub namespace: Option<String>,
 
-    /// Output format
-    #[clap(long, global = true, default_value = "table")]
-    pub output: OutputFormat,
+    /// Output format: table, json, yaml, name, ns/name, csv, or
+    /// jsonpath=TEMPLATE (e.g. 'jsonpath={[*].name}')
+    #[clap(
+        long,
+        short = 'o',
+        global = true,
+        default_value = "table",
+        value_parser = parse_output
+    )]
+    pub output: Output,
+
+    /// Print a table of just these fields of every resource, as paths into
+    /// its JSON output, optionally with a header: name,namespace,APP:labels.app
+    #[clap(long, global = true, value_delimiter = ',', value_name = "COLUMNS")]
+    pub columns: Vec<String>,
+
+    /// Sort list output by a field, as a path into its JSON output or one of
+    /// name, namespace, age, status, ready, replicas, restarts, type, node;
+    /// append :desc to reverse
+    #[clap(long, global = true, value_name = "FIELD[:desc]")]
+    pub sort_by: Option<String>,
+
+    /// Only list resources created at least this long ago (e.g. 30m, 7d)
+    #[clap(long, global = true, value_parser = parse_duration, value_name = "AGE")]
+    pub older_than: Option<Duration>,
+
+    /// Only list resources created less than this long ago (e.g. 2h)
+    #[clap(long, global = true, value_parser = parse_duration, value_name = "AGE")]
+    pub newer_than: Option<Duration>,
+
+    /// Only list resources whose name matches a regular expression; a plain
+    /// word matches anywhere in the name (e.g. '^payments-.*-canary$')
+    #[clap(long, global = true, value_parser = NameFilter::parse, value_name = "REGEX")]
+    pub name_filter: Option<NameFilter>,
+
+    /// Only list resources whose annotations match a selector, written like
+    /// a label selector (e.g. 'cert-manager.io/cluster-issuer=letsencrypt')
+    #[clap(long, global = true, value_parser = LabelSelector::parse, value_name = "SELECTOR")]
+    pub annotation_selector: Option<LabelSelector>,
+
+    /// Filter pods on the API server by field, so only matching pods are
+    /// downloaded (e.g. 'status.phase=Running,spec.nodeName=node-1')
+    #[clap(long, global = true, value_parser = FieldSelector::parse, value_name = "SELECTOR")]
+    pub field_selector: Option<FieldSelector>,
+
+    /// Print one line per resource using a named template from the
+    /// [templates] table of the config file
+    #[clap(long, global = true, value_name = "NAME")]
+    pub template_preset: Option<String>,
 
     /// Enable verbose logging
     #[clap(long, short = 'v', global = true)]
@@ -47,16 +118,143 @@ pub struct Cli {
     /// Enable memory optimization for large clusters
     #[clap(long, global = true)]
     pub memory_optimized: bool,
+
+    /// Continue an interrupted all-namespace scan from its last checkpoint
+    #[clap(long, global = true)]
+    pub resume: bool,
+
+    /// Print a run summary to stderr: API requests, bytes received, cache
+    /// hits and misses, namespaces scanned and time per phase
+    #[clap(long, global = true)]
+    pub stats: bool,
+
+    /// Answer from the recorded snapshot closest to this time instead of the
+    /// live cluster (e.g. 2024-05-01T12:00, UTC)
+    #[clap(long, global = true, value_parser = parse_timestamp)]
+    pub as_of: Option<DateTime<Utc>>,
+
+    /// Compress JSON/YAML output and recorded snapshots
+    #[clap(long, global = true, value_enum)]
+    pub compress: Option<CompressFormat>,
+
+    /// Print JSON on a single line instead of pretty-printed (with -o json)
+    #[clap(long, global = true)]
+    pub json_compact: bool,
+
+    /// Wrap JSON/YAML output in an object with the items, the query, the
+    /// warnings raised and timing, telling partial from complete results
+    #[clap(long, global = true)]
+    pub envelope: bool,
+
+    /// Keep list commands running and show changes as they happen: tables
+    /// are redrawn in place, JSON becomes one ADDED/MODIFIED/DELETED event
+    /// per line
+    #[clap(long, short = 'w', global = true)]
+    pub watch: bool,
+
+    /// Refuse any API request other than get, list or watch before it is
+    /// sent, and log the verb of every request to stderr
+    #[clap(long, global = true)]
+    pub read_only_verify: bool,
+
+    /// How long listings are cached: KIND=DURATION for one kind (pods=15s,
+    /// crds=1h) or DURATION for the default. Overrides [cache.ttl] in the
+    /// config file.
+    #[clap(
+        long,
+        global = true,
+        value_name = "[KIND=]DURATION",
+        value_delimiter = ','
+    )]
+    pub cache_ttl: Vec<CacheTtl>,
+
+    /// Include the namespaces [namespaces] in the config file excludes
+    /// from listings across namespaces
+    #[clap(long, global = true)]
+    pub system: bool,
+
+    /// Namespaces listed at once by scans across namespaces (default: 20)
+    #[clap(long, global = true, value_name = "N")]
+    pub concurrency: Option<usize>,
+
+    /// When to color output
+    #[clap(long, global = true, value_enum, value_name = "WHEN")]
+    pub color: Option<ColorMode>,
 }
 
+impl Cli {
+    /// The kubeconfig context to connect through, if one was given
+    pub fn context(&self) -> Option<&str> {
+        self.contexts.first().map(String::as_str)
+    }
+
+    /// Whether list results are merged from several clusters
+    pub fn fans_out(&self) -> bool {
+        self.all_contexts || self.contexts.len() > 1
+    }
+}
+
+// Commands are grouped into flattened enums: the command line and help stay
+// flat, while each derived parser builds a bounded number of subcommands. One
+// enum of every command needs more than the 2 MiB stack of a thread in
+// unoptimized builds.
 #[derive(Subcommand)]
 pub enum Commands {
-    /// List services in the cluster
-    Services {
-        /// Show services from a specific namespace
+    #[clap(flatten)]
+    Resources(ResourceCommands),
+
+    #[clap(flatten)]
+    Analysis(AnalysisCommands),
+
+    #[clap(flatten)]
+    Tools(ToolCommands),
+
+    #[clap(flatten)]
+    Reports(ReportCommands),
+
+    #[clap(flatten)]
+    Views(ViewCommands),
+
+    #[clap(flatten)]
+    State(StateCommands),
+}
+
+/// Listing resources by kind
+#[derive(Subcommand)]
+pub enum ResourceCommands {
+    /// List services, workloads, pods, configmaps and secrets of a namespace together
+    All {
+        /// Namespace to list (defaults to the current namespace)
         #[clap(long, short = 'n')]
         namespace: Option<String>,
 
+        /// List all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Filter by label selector (e.g., app=web,tier!=cache)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+
+        /// Only list these kinds (e.g., deploy,svc,cm)
+        #[clap(long, value_delimiter = ',')]
+        include_types: Vec<String>,
+
+        /// Leave out these kinds (e.g., secrets)
+        #[clap(long, value_delimiter = ',')]
+        exclude_types: Vec<String>,
+
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
+        #[clap(long, short = 'g')]
+        group_by: Option<String>,
+    },
+
+    /// List services in the cluster
+    Services {
+        /// Show services from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
         /// Show all namespaces
         #[clap(long, short = 'A')]
         all_namespaces: bool,
@@ -65,16 +263,16 @@ pub enum Commands {
         #[clap(long, short = 's')]
         selector: Option<String>,
 
-        /// Group resources by criteria (app, tier, helm-release, namespace)
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
         #[clap(long, short = 'g')]
         group_by: Option<String>,
     },
 
     /// List pods in the cluster
     Pods {
-        /// Show pods from a specific namespace
-        #[clap(long, short = 'n')]
-        namespace: Option<String>,
+        /// Show pods from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
 
         /// Filter by label selector (e.g., app=nginx,version=v1)
         #[clap(long, short = 's')]
@@ -88,16 +286,43 @@ pub enum Commands {
         #[clap(long)]
         status: Option<String>,
 
-        /// Group resources by criteria (app, tier, helm-release, namespace)
+        /// Filter by QoS class (Guaranteed, Burstable, BestEffort)
+        #[clap(long)]
+        qos: Option<String>,
+
+        /// Show QoS class and priority class columns
+        #[clap(long)]
+        show_qos: bool,
+
+        /// Show service mesh sidecar column
+        #[clap(long)]
+        show_mesh: bool,
+
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
         #[clap(long, short = 'g')]
         group_by: Option<String>,
     },
 
+    /// List pods without a controller (created directly or left behind)
+    BarePods {
+        /// Show bare pods from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Filter by label selector (e.g., app=web,tier!=cache)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+    },
+
     /// List deployments in the cluster
     Deployments {
-        /// Show deployments from a specific namespace
-        #[clap(long, short = 'n')]
-        namespace: Option<String>,
+        /// Show deployments from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
 
         /// Show all namespaces
         #[clap(long, short = 'A')]
@@ -111,14 +336,165 @@ pub enum Commands {
         #[clap(long)]
         status: Option<String>,
 
-        /// Group resources by criteria (app, tier, helm-release, namespace)
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
         #[clap(long, short = 'g')]
         group_by: Option<String>,
     },
 
     /// List statefulsets in the cluster
     Statefulsets {
-        /// Show statefulsets from a specific namespace
+        /// Show statefulsets from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// List daemonsets in the cluster
+    Daemonsets {
+        /// Show daemonsets from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// List replicasets with their owning deployment and rollout revision
+    Replicasets {
+        /// Show replicasets from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Show the rollout history of a deployment, reconstructed from its replicasets
+        #[clap(long, value_name = "DEPLOYMENT", conflicts_with = "all_namespaces")]
+        history: Option<String>,
+    },
+
+    /// List every container with its pod, role, image, resources and restarts
+    Containers {
+        /// Show containers from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Only containers whose image contains this text
+        #[clap(long)]
+        image: Option<String>,
+
+        /// Only init, sidecar or main containers
+        #[clap(long, value_parser = ["init", "sidecar", "main"], ignore_case = true)]
+        role: Option<String>,
+    },
+
+    /// List HorizontalPodAutoscalers with their metrics and scale targets
+    Hpas {
+        /// Show autoscalers from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// List recent events with their type, reason, count and involved object
+    Events {
+        /// Show events from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Only show events seen within this long (e.g. 30m, 2h)
+        #[clap(long, value_parser = parse_duration)]
+        since: Option<Duration>,
+
+        /// Only show events of this type
+        #[clap(long = "type", value_name = "TYPE", value_parser = ["Normal", "Warning"], ignore_case = true)]
+        event_type: Option<String>,
+    },
+
+    /// List configmaps in the cluster
+    Configmaps {
+        /// Show configmaps from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Filter by label selector (e.g., app=web,tier!=cache)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
+        #[clap(long, short = 'g')]
+        group_by: Option<String>,
+
+        /// Show unused configmaps (not referenced by any resource)
+        #[clap(long)]
+        unused: bool,
+    },
+
+    /// List secrets in the cluster
+    Secrets {
+        /// Show secrets from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Filter by label selector (e.g., app=web,tier!=cache)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
+        #[clap(long, short = 'g')]
+        group_by: Option<String>,
+
+        /// Show unused secrets (not referenced by any resource)
+        #[clap(long)]
+        unused: bool,
+
+        /// Filter by secret type (Opaque, kubernetes.io/tls, etc.)
+        #[clap(long)]
+        secret_type: Option<String>,
+
+        /// Count secrets by type and flag token and pull secret anomalies
+        #[clap(long, conflicts_with_all = ["group_by", "unused"])]
+        summary: bool,
+    },
+
+    /// List service accounts with the roles bound to them
+    Serviceaccounts {
+        /// Show service accounts from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// List Roles with their rules and the subjects bound to them
+    Roles {
+        /// Show Roles from a specific namespace
         #[clap(long, short = 'n')]
         namespace: Option<String>,
 
@@ -127,117 +503,752 @@ pub enum Commands {
         all_namespaces: bool,
     },
 
-    /// List daemonsets in the cluster
-    Daemonsets {
-        /// Show daemonsets from a specific namespace
+    /// List ClusterRoles with their rules and the subjects bound to them
+    Clusterroles,
+
+    /// List RoleBindings with their role and subjects
+    Rolebindings {
+        /// Show RoleBindings from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// List ClusterRoleBindings with their role and subjects
+    Clusterrolebindings,
+
+    /// List the users, groups and service accounts allowed to perform a
+    /// verb on a resource, and the bindings allowing it
+    WhoCan {
+        /// Verb (e.g. get, list, create, delete, escalate)
+        verb: String,
+
+        /// Resource, optionally with API group and subresource (e.g. pods,
+        /// pods/exec, deployments.apps)
+        resource: ResourceQuery,
+
+        /// Check a specific namespace (cluster-wide grants included)
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Check all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// List nodes with roles, versions, allocatable capacity and conditions
+    Nodes {
+        /// Filter by role (e.g., control-plane, worker)
+        #[clap(long)]
+        role: Option<String>,
+
+        /// Filter by condition, true unless a status is given (e.g., Ready=False, DiskPressure)
+        #[clap(long)]
+        condition: Option<String>,
+
+        /// Filter by label selector (e.g., node.kubernetes.io/instance-type=m5.large)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+    },
+
+    /// List persistent volumes with their claims and the pods mounting them
+    Pvs {
+        /// Filter by phase (Available, Bound, Released, Failed)
+        #[clap(long)]
+        status: Option<String>,
+
+        /// Filter by storage class
+        #[clap(long)]
+        storage_class: Option<String>,
+    },
+
+    /// List persistent volume claims with their volumes and the pods mounting them
+    Pvcs {
+        /// Show claims from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Filter by label selector (e.g., app=db)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+
+        /// Filter by phase (Pending, Bound, Lost)
+        #[clap(long)]
+        status: Option<String>,
+
+        /// Filter by storage class
+        #[clap(long)]
+        storage_class: Option<String>,
+    },
+
+    /// List Custom Resource Definitions (CRDs) in the cluster
+    Crds {
+        /// Filter by label selector (e.g., app=web,tier!=cache)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
+        #[clap(long, short = 'g')]
+        group_by: Option<String>,
+
+        /// Show only CRDs with instances
+        #[clap(long)]
+        with_instances: bool,
+
+        /// Show detailed version information
+        #[clap(long)]
+        show_versions: bool,
+    },
+
+    /// List Custom Resource instances of one CRD, or of every CRD when none
+    /// is named
+    CustomResources {
+        /// Name of the CRD to list instances for (default: every CRD)
+        #[clap(value_name = "CRD_NAME")]
+        crd_name: Option<String>,
+
+        /// Show custom resources from these namespaces (repeat -n or separate with commas)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Filter by label selector (e.g., app=web,tier!=cache)
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+
+        /// Group resources by criteria (app, tier, helm-release, kustomization, namespace)
+        #[clap(long, short = 'g')]
+        group_by: Option<String>,
+
+        /// Instances shown per CRD when listing every CRD
+        #[clap(long, default_value = "10")]
+        max_per_crd: usize,
+    },
+}
+
+/// Inventory, placement, networking and reliability analysis
+#[derive(Subcommand)]
+pub enum AnalysisCommands {
+    /// List annotation keys in use, or resources carrying a given annotation
+    Annotations {
+        /// Show resources from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Only inspect these kinds, comma separated (default: workloads, pods,
+        /// services, configmaps and secrets)
+        #[clap(long, value_delimiter = ',')]
+        kind: Vec<ResourceKind>,
+
+        /// List resources carrying this annotation key
+        #[clap(long)]
+        key: Option<String>,
+
+        /// List resources whose annotation value matches this regular expression
+        #[clap(long)]
+        value_regex: Option<String>,
+    },
+
+    /// Inventory label values across resources
+    Labels {
+        #[clap(subcommand)]
+        action: LabelsAction,
+    },
+
+    /// Find objects stuck terminating because finalizers were never removed
+    Stuck {
+        /// Show resources from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Check every resource type the API server serves, including custom resources
+        #[clap(long)]
+        all_kinds: bool,
+
+        /// Minimum time since deletion was requested (e.g. 30s, 10m, 1h)
+        #[clap(long, default_value = "5m", value_parser = parse_duration, value_name = "AGE")]
+        stuck_for: Duration,
+    },
+
+    /// List namespaces with counts of the objects in each; --sort-by pods or
+    /// services orders them by count, largest first
+    Namespaces,
+
+    /// Diagnose a namespace stuck in Terminating
+    NamespaceStuck {
+        /// Namespace to inspect
+        namespace: String,
+    },
+
+    /// Report workloads inside and outside the service mesh per namespace
+    Mesh {
+        /// Show mesh coverage for a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// Show node, workload and service distribution across zones
+    Zones {
+        /// Show workloads from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// Report workloads running only on spot/preemptible nodes
+    Spot {
+        /// Show workloads from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// Report the images cached on each node, their footprint, and images no
+    /// running workload uses
+    NodeImages {
+        /// List the images cached on this node
+        node: Option<String>,
+    },
+
+    /// Report out-of-cluster dependencies: ExternalName services, manual
+    /// endpoints, egress annotations and external addresses in pod env
+    Externals {
+        /// Show dependencies of a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Only show one category (database, cache, queue, cloud API, external)
+        #[clap(long)]
+        category: Option<String>,
+    },
+
+    /// Find Ingresses claiming the same host and path with different
+    /// backends, wildcard hosts shadowed by specific hosts, and missing TLS
+    /// secrets
+    IngressCheck {
+        /// Check Ingresses in a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Check all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// Report what would break if a workload moved to another namespace:
+    /// missing configuration, Services, Ingresses, role bindings and
+    /// NetworkPolicies
+    MoveCheck {
+        /// Workload to move (deployment/NAME, statefulset/NAME or daemonset/NAME)
+        target: ObjectRef,
+
+        /// Namespace of the workload
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Namespace the workload would move to
+        #[clap(long)]
+        to: String,
+    },
+
+    /// Check for duplicate pod IPs, stale endpoints, misplaced Service IPs
+    /// and NodePorts outside the expected range
+    NetworkCheck {
+        /// Expected service CIDR, repeatable for dual-stack clusters
+        /// (enables the cluster IP range checks)
+        #[clap(long, value_parser = Cidr::parse, value_name = "CIDR")]
+        service_cidr: Vec<Cidr>,
+
+        /// Expected NodePort range
+        #[clap(long, default_value = "30000-32767", value_parser = NodePortRange::parse)]
+        node_port_range: NodePortRange,
+    },
+
+    /// List NetworkPolicies with the pods they select and their ingress and
+    /// egress rules
+    Networkpolicies {
+        /// List policies in a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// List policies in all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+
+    /// Check whether NetworkPolicies allow traffic from one pod or workload
+    /// to another
+    CanReach {
+        /// Source: a pod name, or deployment/NAME, statefulset/NAME or daemonset/NAME
+        from: String,
+
+        /// Destination, in the same forms
+        to: String,
+
+        /// Namespace of the source, and of the destination unless --to-namespace is given
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Namespace of the destination
+        #[clap(long)]
+        to_namespace: Option<String>,
+
+        /// Destination port, by number or container port name (default: any port)
+        #[clap(long)]
+        port: Option<String>,
+
+        /// Protocol of the traffic
+        #[clap(long, default_value = "TCP", requires = "port")]
+        protocol: String,
+    },
+
+    /// Rank workloads by restarts, readiness flaps and pod turnover
+    Stability {
+        /// Score workloads in a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Score workloads in all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// Period to score over (e.g. 6h, 24h, 7d)
+        #[clap(long, default_value = "24h", value_parser = parse_duration)]
+        window: Duration,
+
+        /// Compare the snapshots recorded by `kdx history record` in the window
+        /// instead of reading current pod state
+        #[clap(long)]
+        from_history: bool,
+
+        /// Show only the N least stable workloads
+        #[clap(long)]
+        top: Option<usize>,
+    },
+
+    /// Show recent replica changes of workloads, by autoscalers or by hand,
+    /// with their time and reason
+    ScalingEvents {
+        /// Show changes in a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show changes in all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// How far back to look (e.g. 6h, 24h, 7d)
+        #[clap(long, default_value = "24h", value_parser = parse_duration)]
+        window: Duration,
+
+        /// Also compare replica counts of the snapshots recorded by
+        /// `kdx history record` in the window
+        #[clap(long)]
+        from_history: bool,
+    },
+
+    /// List workloads whose containers were OOMKilled or whose pods were
+    /// evicted recently, with memory limits against current usage
+    Oomkills {
+        /// Report a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Report all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// How far back to look (e.g. 6h, 24h, 7d)
+        #[clap(long, default_value = "24h", value_parser = parse_duration)]
+        window: Duration,
+    },
+
+    /// Report released, failed and unclaimed persistent volumes
+    DanglingPvs,
+
+    /// Roll up requested storage by namespace, storage class and owning
+    /// workload, against ResourceQuota storage limits
+    Storage {
+        /// Roll up claims in a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Roll up all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+    },
+}
+
+/// Waiting, port-forwarding, comparing and exporting
+#[derive(Subcommand)]
+pub enum ToolCommands {
+    /// Wait until a workload, service or pod reaches a condition
+    ///
+    /// Exits 0 when the condition is met, 2 on timeout, and 3 when the
+    /// condition can no longer be met (e.g. the pod failed).
+    Wait {
+        /// Resource to wait for (e.g. deployment/web, svc/api, pod/job-x)
+        target: ObjectRef,
+
+        /// Condition: ready, endpoints, or phase=<Phase> for pods
+        #[clap(long = "for", default_value = "ready")]
+        condition: WaitCondition,
+
+        /// Give up after this long (e.g. 30s, 5m, 1h)
+        #[clap(long, default_value = "5m", value_parser = parse_duration)]
+        timeout: Duration,
+
+        /// Polling interval
+        #[clap(long, default_value = "2s", value_parser = parse_duration)]
+        interval: Duration,
+
+        /// Namespace of the resource
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// Forward a local port to a service through one of its ready pods
+    PortForward {
+        /// Service to forward to
+        service: String,
+
+        /// Local and service port, e.g. 8080:80 (default: the first service port)
+        #[clap(value_name = "[LOCAL:]REMOTE")]
+        ports: Option<PortMapping>,
+
+        /// Namespace of the service
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// Probe the ports of several services through port-forwards at once
+    /// and print a latency and status matrix
+    ProbeMatrix {
+        /// Services to probe, comma-separated
+        #[clap(long, value_delimiter = ',', required = true)]
+        services: Vec<String>,
+
+        /// Namespace of the services
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Time allowed for each probe (e.g. 2s, 10s)
+        #[clap(long, default_value = "5s", value_parser = parse_duration)]
+        timeout: Duration,
+
+        /// Send an HTTP GET for this path (default: /) instead of only
+        /// opening a TCP connection
+        #[clap(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "/")]
+        http: Option<String>,
+    },
+
+    /// Follow the endpoints behind a service
+    Endpoints {
+        #[clap(subcommand)]
+        action: EndpointsAction,
+    },
+
+    /// Validate live custom resources against their CRD schema
+    Validate {
+        /// Name of the CRD whose instances to validate
+        #[clap(value_name = "CRD_NAME")]
+        crd_name: String,
+
+        /// Validate custom resources from a specific namespace
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show all namespaces
+        #[clap(long, short = 'A')]
+        all_namespaces: bool,
+
+        /// CRD version to validate against (default: storage version)
+        #[clap(long)]
+        version: Option<String>,
+    },
+
+    /// Report CRDs with objects that may still be stored at old versions and
+    /// need storage migration before those versions are removed
+    StorageVersions,
+
+    /// Show the effective environment of each container in a workload or pod
+    Env {
+        /// Workload or pod (e.g. deployment/web, sts/db, pod/web-abc12)
+        target: ObjectRef,
+
+        /// Namespace of the resource
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// List a deployment's revisions from its replicasets, or diff the pod
+    /// templates of two of them
+    Revisions {
+        /// Deployment (e.g. deployment/web)
+        target: ObjectRef,
+
+        /// Namespace of the deployment
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Show the pod template changes from one revision to another
+        #[clap(long, num_args = 2, value_names = ["FROM", "TO"])]
+        diff: Option<Vec<i64>>,
+    },
+
+    /// Show the ownership chain of a resource and the tool managing it
+    Owners {
+        /// Resource to start from (e.g. pod/web-abc12, rs/web-5d4f8, job/backup-123)
+        target: ObjectRef,
+
+        /// Namespace of the resource
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// Show the owners of an object and everything it owns as a tree
+    Tree {
+        /// Kind of the object, or kind/name (e.g. deployment, certificate)
+        #[clap(value_name = "KIND|KIND/NAME")]
+        kind: String,
+
+        /// Name of the object, when the kind is given on its own
+        name: Option<String>,
+
+        /// Namespace of the object
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// Diff a namespace across two clusters or kubeconfig contexts
+    Compare {
+        /// First kubeconfig context (e.g. prod)
+        #[clap(long)]
+        context_a: String,
+
+        /// Second kubeconfig context (e.g. staging)
+        #[clap(long)]
+        context_b: String,
+
+        /// Namespace to compare in both contexts
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// Diff two namespaces or two snapshot files: objects added or removed
+    /// and changed replicas, images, labels and config keys
+    Diff {
+        /// Namespace, or snapshot file written by `kdx snapshot save`
+        /// (`file:PATH` when the name could be a namespace)
+        #[clap(long)]
+        from: String,
+
+        /// Namespace, or snapshot file written by `kdx snapshot save`
+        /// (`file:PATH` when the name could be a namespace)
+        #[clap(long)]
+        to: String,
+
+        /// Namespace to compare within snapshot files; by default the
+        /// namespace on the other side, or every namespace when both sides
+        /// are snapshots
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// Export a namespace as cleaned YAML manifests, without status,
+    /// managedFields and cluster-assigned fields
+    Extract {
+        /// Namespace to export
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Directory the manifests are written to
+        #[clap(long, default_value = "./manifests")]
+        output_dir: PathBuf,
+
+        /// Export Secrets too, with their data
+        #[clap(long)]
+        include_secrets: bool,
+    },
+
+    /// Check whether the workloads in manifests would fit before applying
+    /// them: pod requests, node headroom and ResourceQuotas
+    Simulate {
+        /// Manifest file, or directory searched for .yaml, .yml and .json files
+        #[clap(long, value_name = "PATH")]
+        from_manifests: PathBuf,
+
+        /// Place the new pods on the cluster's nodes and compare them with
+        /// its quotas; without it only the manifest totals are printed
+        #[clap(long)]
+        against_cluster: bool,
+
+        /// Namespace for objects that do not set one
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+
+    /// List objects missing the kustomize labels the other objects of their
+    /// namespace carry
+    KustomizeDrift {
+        /// Check a specific namespace
         #[clap(long, short = 'n')]
         namespace: Option<String>,
 
-        /// Show all namespaces
+        /// Check all namespaces
         #[clap(long, short = 'A')]
         all_namespaces: bool,
     },
+}
 
-    /// List configmaps in the cluster
-    Configmaps {
-        /// Show configmaps from a specific namespace
+/// Reports, policy checks and scores
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// Generate a combined health report for platform reviews
+    Report {
+        /// Limit the report to a namespace (default: all namespaces)
         #[clap(long, short = 'n')]
         namespace: Option<String>,
 
-        /// Show all namespaces
-        #[clap(long, short = 'A')]
-        all_namespaces: bool,
-
-        /// Filter by label selector (e.g., app=web,tier!=cache)
-        #[clap(long, short = 's')]
-        selector: Option<String>,
+        /// Sections to include, comma separated (default: all)
+        #[clap(long, value_enum, value_delimiter = ',')]
+        sections: Vec<ReportSection>,
 
-        /// Group resources by criteria (app, tier, helm-release, namespace)
-        #[clap(long, short = 'g')]
-        group_by: Option<String>,
+        /// Report format
+        #[clap(long, value_enum, default_value = "markdown")]
+        format: ReportFormat,
 
-        /// Show unused configmaps (not referenced by any resource)
+        /// Replace namespaces and object names with salted hashes, so the
+        /// report can be shared outside the organization
         #[clap(long)]
-        unused: bool,
+        redact: bool,
     },
 
-    /// List secrets in the cluster
-    Secrets {
-        /// Show secrets from a specific namespace
+    /// Export everything carrying a tenant label for review by the owning team
+    TenantReport {
+        /// Tenant label selector (e.g., team=payments)
+        #[clap(long, short = 'l')]
+        label: String,
+
+        /// Bundle format
+        #[clap(long, value_enum, default_value = "json")]
+        format: ReportFormat,
+    },
+
+    /// Check workloads against built-in and organization-specific policy rules
+    Lint {
+        /// Check resources in a specific namespace
         #[clap(long, short = 'n')]
         namespace: Option<String>,
 
-        /// Show all namespaces
+        /// Check all namespaces
         #[clap(long, short = 'A')]
         all_namespaces: bool,
 
-        /// Filter by label selector (e.g., app=web,tier!=cache)
-        #[clap(long, short = 's')]
-        selector: Option<String>,
-
-        /// Group resources by criteria (app, tier, helm-release, namespace)
-        #[clap(long, short = 'g')]
-        group_by: Option<String>,
-
-        /// Show unused secrets (not referenced by any resource)
+        /// Rules file (YAML, or TOML with a .toml extension) with custom rules
+        /// and built-in rules switched on or off
         #[clap(long)]
-        unused: bool,
+        rules: Option<PathBuf>,
 
-        /// Filter by secret type (Opaque, kubernetes.io/tls, etc.)
+        /// Print the effective rules instead of checking resources
         #[clap(long)]
-        secret_type: Option<String>,
+        list_rules: bool,
     },
 
-    /// List Custom Resource Definitions (CRDs) in the cluster
-    Crds {
-        /// Filter by label selector (e.g., app=web,tier!=cache)
-        #[clap(long, short = 's')]
-        selector: Option<String>,
+    /// Score the production readiness of a workload, or of every workload
+    /// in a namespace, from lint, PDB, probe, spread, resource and
+    /// monitoring checks
+    Scorecard {
+        /// Workload as kind/name (deployment, statefulset or daemonset), or
+        /// a namespace to score all of its workloads
+        target: String,
 
-        /// Group resources by criteria (app, tier, helm-release, namespace)
-        #[clap(long, short = 'g')]
-        group_by: Option<String>,
+        /// Namespace of the workload
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
 
-        /// Show only CRDs with instances
-        #[clap(long)]
-        with_instances: bool,
+    /// Report which namespaces and resources a service account can see, by
+    /// running discovery while impersonating it
+    Visibility {
+        /// Service account to impersonate, as <namespace>/<name>
+        #[clap(long = "as-sa", value_name = "NAMESPACE/NAME")]
+        as_sa: ServiceAccount,
 
-        /// Show detailed version information
-        #[clap(long)]
-        show_versions: bool,
+        /// Check only these namespaces (default: every namespace)
+        #[clap(long, short = 'n', visible_alias = "namespaces", value_delimiter = ',')]
+        namespace: Vec<String>,
     },
 
-    /// List Custom Resource instances for a specific CRD
-    CustomResources {
-        /// Name of the CRD to list instances for
-        #[clap(value_name = "CRD_NAME")]
-        crd_name: String,
+    /// Show the scheduling, image pull, startup and restart timeline of a pod
+    Timeline {
+        /// Pod to show, as pod/<name>
+        #[clap(value_name = "pod/NAME")]
+        target: ObjectRef,
 
-        /// Show custom resources from a specific namespace
+        /// Namespace of the pod
         #[clap(long, short = 'n')]
         namespace: Option<String>,
-
-        /// Show all namespaces
-        #[clap(long, short = 'A')]
-        all_namespaces: bool,
-
-        /// Filter by label selector (e.g., app=web,tier!=cache)
-        #[clap(long, short = 's')]
-        selector: Option<String>,
-
-        /// Group resources by criteria (app, tier, helm-release, namespace)
-        #[clap(long, short = 'g')]
-        group_by: Option<String>,
     },
+}
 
-    /// Describe a service and its relationships
+/// Single-object and relationship views
+#[derive(Subcommand)]
+pub enum ViewCommands {
+    /// Describe any supported kind by kind/name: a service and its
+    /// relationships, a pod and its containers, a workload, configmap,
+    /// secret or custom resource
     Describe {
-        /// Service name to describe
-        service: String,
+        /// Service name, kind/name, or a kind followed by a name: any built-in
+        /// kind, configmap, secret, or a custom resource kind (e.g.
+        /// certificate, certificates.cert-manager.io)
+        #[clap(value_name = "SERVICE|KIND/NAME|KIND")]
+        target: String,
 
-        /// Namespace of the service
+        /// Name of the object, when the kind is given on its own
+        #[clap(value_name = "NAME")]
+        name: Option<String>,
+
+        /// Namespace of the resource
         #[clap(long, short = 'n')]
         namespace: Option<String>,
+
+        /// For a service, also gather its ingresses, configuration, endpoints,
+        /// ServiceMonitors, admission webhooks and health
+        #[clap(long)]
+        deep: bool,
     },
 
     /// Show service topology and relationships
@@ -264,9 +1275,73 @@ pub enum Commands {
         #[clap(long)]
         include_pods: bool,
 
+        /// Include custom resources, linked to the services and pods they own or select
+        #[clap(long)]
+        include_custom_resources: bool,
+
+        /// Include persistent volume claims and volumes, linked to the pods mounting them
+        #[clap(long)]
+        include_storage: bool,
+
+        /// Include out-of-cluster dependencies (databases, SaaS endpoints),
+        /// linked to the services and pods referencing them
+        #[clap(long)]
+        include_externals: bool,
+
+        /// Include Deployments, StatefulSets and DaemonSets, linked to the
+        /// services selecting them and the pods they own
+        #[clap(long)]
+        include_workloads: bool,
+
+        /// Include the ConfigMaps and Secrets consumed by workloads and pods
+        #[clap(long)]
+        include_config: bool,
+
+        /// Include NetworkPolicies, linked to the pods they isolate and the
+        /// pods their rules allow
+        #[clap(long)]
+        include_network_policies: bool,
+
         /// Highlight a specific service
         #[clap(long)]
         highlight: Option<String>,
+
+        /// Draw groups (app, tier, helm-release, kustomization, namespace, or a
+        /// label key) as boxes with resource counts, linked by the services
+        /// their pods use
+        #[clap(long, short = 'g', conflicts_with_all = ["include_pods", "include_custom_resources", "include_storage", "include_externals", "include_workloads", "include_config", "include_network_policies"])]
+        group_by: Option<String>,
+
+        /// Draw only the services, workloads, configs and ingresses of one
+        /// Helm release in the namespace (default namespace: default)
+        #[clap(long, value_name = "RELEASE", conflicts_with_all = ["include_pods", "include_custom_resources", "include_storage", "include_externals", "include_workloads", "include_config", "include_network_policies", "group_by"])]
+        helm_release: Option<String>,
+
+        /// Collapse the largest namespaces into single nodes until the graph
+        /// has at most this many nodes (0 for no limit)
+        #[clap(long, default_value_t = 1000)]
+        max_nodes: usize,
+
+        /// Collapse the largest namespaces into single nodes until the graph
+        /// has at most this many edges (0 for no limit)
+        #[clap(long, default_value_t = 3000)]
+        max_edges: usize,
+    },
+}
+
+/// History, snapshots, cache, configuration and benchmarks
+#[derive(Subcommand)]
+pub enum StateCommands {
+    /// Record and list cluster snapshots used by --as-of
+    History {
+        #[clap(subcommand)]
+        action: HistoryAction,
+    },
+
+    /// Save the cluster to a file, or answer commands from a saved file offline
+    Snapshot {
+        #[clap(subcommand)]
+        action: SnapshotAction,
     },
 
     /// Cache management operations
@@ -275,6 +1350,12 @@ pub enum Commands {
         action: CacheAction,
     },
 
+    /// Show or create the config file
+    Config {
+        #[clap(subcommand)]
+        action: ConfigAction,
+    },
+
     /// Performance benchmarking and testing
     Benchmark {
         /// Number of iterations to run
@@ -292,23 +1373,191 @@ pub enum Commands {
     },
 }
 
+impl Commands {
+    /// The label selector flag of the command, if it has one
+    pub fn selector_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            Commands::Resources(ResourceCommands::All { selector, .. })
+            | Commands::Resources(ResourceCommands::Services { selector, .. })
+            | Commands::Resources(ResourceCommands::Pods { selector, .. })
+            | Commands::Resources(ResourceCommands::BarePods { selector, .. })
+            | Commands::Resources(ResourceCommands::Deployments { selector, .. })
+            | Commands::Resources(ResourceCommands::Configmaps { selector, .. })
+            | Commands::Resources(ResourceCommands::Secrets { selector, .. })
+            | Commands::Resources(ResourceCommands::Nodes { selector, .. })
+            | Commands::Resources(ResourceCommands::Pvcs { selector, .. })
+            | Commands::Resources(ResourceCommands::Crds { selector, .. })
+            | Commands::Resources(ResourceCommands::CustomResources { selector, .. })
+            | Commands::State(StateCommands::Cache {
+                action: CacheAction::Warm { selector, .. },
+            }) => Some(selector),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Parser)]
 pub enum CacheAction {
     /// Show cache statistics
     Stats,
-    /// Clear all cached data
+    /// Clear all cached data, including listings persisted on disk
     Clear,
-    /// Warm cache by pre-loading common resources
+    /// Keep cached listings on disk between runs (on), or stop and delete
+    /// them (off)
+    Persist {
+        #[clap(value_enum)]
+        state: PersistState,
+    },
+    /// Warm cache by pre-loading resources and report what was loaded
     Warm {
         /// Namespaces to warm (default: all)
-        #[clap(long)]
+        #[clap(long, num_args = 1.., value_delimiter = ',')]
         namespaces: Vec<String>,
         /// Resource types to warm (default: all)
+        #[clap(long, value_enum, num_args = 1.., value_delimiter = ',')]
+        resources: Vec<WarmResource>,
+        /// Label selector to cache services and pods under
+        #[clap(long, short = 's')]
+        selector: Option<String>,
+        /// Maximum namespaces warmed at once
+        #[clap(long, default_value = "10")]
+        parallelism: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PersistState {
+    On,
+    Off,
+}
+
+/// Orders of `kdx namespaces` that need object counts, taken from --sort-by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NamespaceSort {
+    Name,
+    Pods,
+    Services,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WarmResource {
+    Services,
+    Pods,
+    Deployments,
+    Statefulsets,
+    Daemonsets,
+    Configmaps,
+    Secrets,
+    /// Custom Resource Definitions (cluster-scoped, warmed once)
+    Crds,
+    /// Instances of every CRD
+    CustomResources,
+}
+
+impl WarmResource {
+    pub const ALL: [WarmResource; 9] = [
+        WarmResource::Services,
+        WarmResource::Pods,
+        WarmResource::Deployments,
+        WarmResource::Statefulsets,
+        WarmResource::Daemonsets,
+        WarmResource::Configmaps,
+        WarmResource::Secrets,
+        WarmResource::Crds,
+        WarmResource::CustomResources,
+    ];
+
+    pub fn is_cluster_scoped(&self) -> bool {
+        matches!(self, WarmResource::Crds)
+    }
+}
+
+impl std::fmt::Display for WarmResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => write!(f, "{}", value.get_name()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Parser)]
+pub enum ConfigAction {
+    /// Print the settings in effect and the file they were read from
+    Show,
+    /// Write a commented starter config file
+    Init {
+        /// Overwrite an existing file
         #[clap(long)]
-        resources: Vec<String>,
+        force: bool,
     },
 }
 
+#[derive(Parser)]
+pub enum EndpointsAction {
+    /// Log every endpoint added, removed or changing readiness until stopped
+    Watch {
+        /// Service whose EndpointSlices to watch
+        service: String,
+
+        /// Namespace of the service
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+
+        /// Stop after this long instead of waiting for Ctrl-C (e.g. 30s, 5m)
+        #[clap(long, value_parser = parse_duration)]
+        duration: Option<Duration>,
+    },
+}
+
+#[derive(Parser)]
+pub enum LabelsAction {
+    /// List the distinct values of a label key with resource counts per value,
+    /// across every resource type the API server serves
+    Values {
+        /// Label key, e.g. app.kubernetes.io/name
+        key: String,
+
+        /// Only scan namespaced resources in this namespace (default: all
+        /// namespaces)
+        #[clap(long, short = 'n')]
+        namespace: Option<String>,
+    },
+}
+
+#[derive(Parser)]
+pub enum HistoryAction {
+    /// Record a snapshot of services, pods, workloads and configuration in
+    /// all namespaces
+    Record,
+    /// List recorded snapshots
+    List,
+}
+
+#[derive(Parser)]
+pub enum SnapshotAction {
+    /// Save services, pods, workloads, configmaps and secrets in all
+    /// namespaces to a compressed file (gzip unless --compress zstd)
+    Save {
+        /// File to write
+        file: PathBuf,
+
+        /// Replace names, namespaces, label values and image repositories
+        /// with salted hashes, keeping relationships intact, so the file can
+        /// be shared outside the organization
+        #[clap(long)]
+        redact: bool,
+    },
+    /// Answer list, describe, topology and graph commands from a saved file
+    /// until `kdx snapshot unload`
+    Load {
+        /// File written by `kdx snapshot save`
+        file: PathBuf,
+    },
+    /// Go back to answering commands from the cluster
+    Unload,
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum OutputFormat {
     /// Human-readable table format
@@ -317,6 +1566,25 @@ pub enum OutputFormat {
     Json,
     /// YAML format
     Yaml,
+    /// Resource names, one per line
+    Name,
+    /// namespace/name, one per line
+    #[value(name = "ns/name")]
+    NsName,
+    /// CSV (RFC 4180) with the table's columns
+    Csv,
+    /// One line per resource from a --template-preset template
+    #[value(skip)]
+    Template(Template),
+    /// A table of the fields chosen with --columns
+    #[value(skip)]
+    Columns(Vec<Column>),
+    /// A JSONPath template rendered against the JSON output
+    #[value(skip)]
+    JsonPath(JsonPath),
+    /// A format added with `output::register_renderer`, by name
+    #[value(skip)]
+    Custom(&'static str),
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -325,16 +1593,214 @@ impl std::fmt::Display for OutputFormat {
             OutputFormat::Table => write!(f, "table"),
             OutputFormat::Json => write!(f, "json"),
             OutputFormat::Yaml => write!(f, "yaml"),
+            OutputFormat::Name => write!(f, "name"),
+            OutputFormat::NsName => write!(f, "ns/name"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Template(_) => write!(f, "template"),
+            OutputFormat::Columns(_) => write!(f, "columns"),
+            OutputFormat::JsonPath(_) => write!(f, "jsonpath"),
+            OutputFormat::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// How results are printed: the `--output` format and the flags that change
+/// how its documents are written
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub format: OutputFormat,
+    /// `--compress`, for every format but tables
+    pub compress: Option<CompressFormat>,
+    /// `--json-compact`: JSON documents and streamed items on single lines
+    pub json_compact: bool,
+    /// `--envelope`: JSON and YAML documents wrapped with the query and
+    /// its warnings
+    pub envelope: Option<envelope::Settings>,
+}
+
+impl From<OutputFormat> for Output {
+    fn from(format: OutputFormat) -> Self {
+        Self {
+            format,
+            compress: None,
+            json_compact: false,
+            envelope: None,
         }
     }
 }
 
+impl Output {
+    /// Where warnings for the envelope are collected; without `--envelope`
+    /// a list nobody reads
+    pub fn warnings(&self) -> envelope::Warnings {
+        self.envelope
+            .as_ref()
+            .map(|envelope| envelope.warnings.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.format.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum GraphFormat {
     /// DOT format (Graphviz)
     Dot,
     /// SVG format
     Svg,
+    /// Mermaid flowchart, for GitHub/GitLab Markdown and wikis
+    Mermaid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportSection {
+    /// Resource counts, pod phases and restarts
+    Overview,
+    /// ConfigMaps and Secrets without consumers
+    Unused,
+    /// Privileged pods, host access and missing limits
+    Audit,
+    /// TLS secret certificate expiry
+    Certs,
+    /// Deprecated CRD versions still served
+    Deprecations,
+    /// Workloads with fewer ready replicas than desired
+    Rollouts,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ReportFormat {
+    /// Markdown document
+    Markdown,
+    /// Standalone HTML page
+    Html,
+    /// JSON document
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Color unless NO_COLOR is set
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CompressFormat {
+    /// gzip, readable with gunzip and most tools
+    Gzip,
+    /// Zstandard, faster and smaller for large exports
+    Zstd,
+}
+
+/// Namespaces selected by a list command's `-n`/`--namespaces` and `-A` flags
+#[derive(Debug, Clone, PartialEq)]
+pub enum NamespaceTarget {
+    /// Every namespace (`-A`)
+    All,
+    /// One namespace, or a single cluster-wide list when `None`
+    One(Option<String>),
+    /// An explicit set of namespaces, listed concurrently
+    Many(Vec<String>),
+}
+
+impl NamespaceTarget {
+    /// Resolve the flags of a command, falling back to the global namespace
+    pub fn resolve(namespaces: &[String], all_namespaces: bool, global: Option<&str>) -> Self {
+        if all_namespaces {
+            return NamespaceTarget::All;
+        }
+
+        let mut unique: Vec<String> = Vec::new();
+        for namespace in namespaces {
+            if !unique.contains(namespace) {
+                unique.push(namespace.clone());
+            }
+        }
+        match unique.len() {
+            0 => NamespaceTarget::One(global.map(str::to_string)),
+            1 => NamespaceTarget::One(unique.pop()),
+            _ => NamespaceTarget::Many(unique),
+        }
+    }
+
+    /// Namespace to pass to a single list call; `None` lists every namespace
+    pub fn namespace(&self) -> Option<&str> {
+        match self {
+            NamespaceTarget::One(namespace) => namespace.as_deref(),
+            NamespaceTarget::All | NamespaceTarget::Many(_) => None,
+        }
+    }
+}
+
+/// Parse a duration such as "90", "30s", "5m", "2h" or "1d" (bare numbers are seconds)
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split_at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split_at);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", value))?;
+    let unit_seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("invalid duration unit in '{}'", value)),
+    };
+    let seconds = number
+        .checked_mul(unit_seconds)
+        .ok_or_else(|| format!("duration '{}' is too large", value))?;
+
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Parse an output format name, or `jsonpath=TEMPLATE`
+pub fn parse_output_format(value: &str) -> Result<OutputFormat, String> {
+    if let Some(template) = value.strip_prefix("jsonpath=") {
+        return JsonPath::parse(template)
+            .map(OutputFormat::JsonPath)
+            .map_err(|e| e.to_string());
+    }
+    OutputFormat::from_str(value, true).map_err(|_| {
+        format!(
+            "invalid output format '{}' (expected table, json, yaml, name, ns/name, csv \
+             or jsonpath=TEMPLATE)",
+            value
+        )
+    })
+}
+
+fn parse_output(value: &str) -> Result<Output, String> {
+    parse_output_format(value).map(Output::from)
+}
+
+/// Parse a point in time: RFC 3339, or `YYYY-MM-DD[THH:MM[:SS]]` in UTC
+pub fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(time.and_utc());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Err(format!(
+        "invalid time '{}' (expected e.g. 2024-05-01T12:00 or RFC 3339)",
+        value
+    ))
 }
 
 #[cfg(test)]
@@ -342,13 +1808,74 @@ mod tests {
     use super::*;
     use clap::Parser;
 
+    #[test]
+    fn test_parse_timestamp() {
+        use chrono::TimeZone;
+
+        let expected = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        assert_eq!(parse_timestamp("2024-05-01T12:00").unwrap(), expected);
+        assert_eq!(
+            parse_timestamp("2024-05-01T14:00:00+02:00").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp("2024-05-01").unwrap(),
+            Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()
+        );
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("999999999999999d").is_err());
+        assert!(parse_duration(&u64::MAX.to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_cli_parsing_wait() {
+        let cli = Cli::try_parse_from([
+            "kdx",
+            "wait",
+            "deployment/web",
+            "--for",
+            "ready",
+            "--timeout",
+            "5m",
+        ])
+        .unwrap();
+
+        if let Commands::Tools(ToolCommands::Wait {
+            target,
+            condition,
+            timeout,
+            interval,
+            ..
+        }) = cli.command
+        {
+            assert_eq!(target.name, "web");
+            assert_eq!(condition, WaitCondition::Ready);
+            assert_eq!(timeout, Duration::from_secs(300));
+            assert_eq!(interval, Duration::from_secs(2));
+        } else {
+            panic!("Expected Wait command");
+        }
+
+        assert!(Cli::try_parse_from(["kdx", "wait", "web"]).is_err());
+    }
+
     #[test]
     fn test_output_format_default() {
         let format = OutputFormat::Table;
         assert!(matches!(format, OutputFormat::Table));
     }
 
-    #[test]
     #[test]
     fn test_output_format_debug() {
         let format = OutputFormat::Table;
@@ -361,7 +1888,6 @@ mod tests {
         assert!(matches!(format, GraphFormat::Dot));
     }
 
-    #[test]
     #[test]
     fn test_graph_format_debug() {
         let format = GraphFormat::Dot;
@@ -370,13 +1896,16 @@ mod tests {
     }
     #[test]
     fn test_cli_parsing_services() {
-        let cli = Cli::try_parse_from(&["kdx", "services"]).unwrap();
-        assert!(matches!(cli.command, Commands::Services { .. }));
+        let cli = Cli::try_parse_from(["kdx", "services"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Resources(ResourceCommands::Services { .. })
+        ));
     }
 
     #[test]
     fn test_cli_parsing_graph_with_options() {
-        let cli = Cli::try_parse_from(&[
+        let cli = Cli::try_parse_from([
             "kdx",
             "graph",
             "--namespace",
@@ -389,13 +1918,32 @@ mod tests {
         ])
         .unwrap();
 
-        if let Commands::Graph {
+        if let Commands::Views(ViewCommands::Graph {
             namespace,
             format,
             include_pods,
+            include_custom_resources,
+            include_storage,
+            include_externals,
+            include_workloads,
+            include_config,
+            include_network_policies,
             highlight,
-        } = cli.command
+            group_by,
+            helm_release,
+            max_nodes,
+            max_edges,
+        }) = cli.command
         {
+            assert_eq!((max_nodes, max_edges), (1000, 3000));
+            assert!(!include_workloads);
+            assert!(!include_config);
+            assert!(!include_network_policies);
+            assert!(!include_custom_resources);
+            assert!(!include_storage);
+            assert!(!include_externals);
+            assert_eq!(group_by, None);
+            assert_eq!(helm_release, None);
             assert_eq!(namespace, Some("test".to_string()));
             assert!(matches!(format, GraphFormat::Svg));
             assert!(include_pods);
@@ -403,11 +1951,53 @@ mod tests {
         } else {
             panic!("Expected Graph command");
         }
+
+        let cli = Cli::try_parse_from(["kdx", "graph", "--group-by", "app"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Views(ViewCommands::Graph { group_by: Some(ref g), .. }) if g == "app"
+        ));
+        assert!(Cli::try_parse_from(["kdx", "graph", "-g", "app", "--include-pods"]).is_err());
+        assert!(
+            Cli::try_parse_from(["kdx", "graph", "--helm-release", "grafana", "-g", "app"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_multiple_namespaces() {
+        let namespaces = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).unwrap();
+            match cli.command {
+                Commands::Resources(ResourceCommands::Pods {
+                    namespace,
+                    all_namespaces,
+                    ..
+                }) => {
+                    NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref())
+                }
+                _ => panic!("Expected Pods command"),
+            }
+        };
+
+        let many = NamespaceTarget::Many(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(namespaces(&["kdx", "pods", "-n", "a", "-n", "b"]), many);
+        assert_eq!(namespaces(&["kdx", "pods", "--namespaces", "a,b,a"]), many);
+        assert_eq!(
+            namespaces(&["kdx", "pods", "-n", "a"]),
+            NamespaceTarget::One(Some("a".to_string()))
+        );
+        assert_eq!(namespaces(&["kdx", "pods"]), NamespaceTarget::One(None));
+        assert_eq!(
+            namespaces(&["kdx", "pods", "-A", "-n", "a"]),
+            NamespaceTarget::All
+        );
+        assert_eq!(many.namespace(), None);
     }
 
     #[test]
     fn test_cli_global_options() {
-        let cli = Cli::try_parse_from(&[
+        let cli = Cli::try_parse_from([
             "kdx",
             "--verbose",
             "--output",
@@ -419,8 +2009,44 @@ mod tests {
         .unwrap();
 
         assert!(cli.verbose);
-        assert!(matches!(cli.output, OutputFormat::Json));
-        assert_eq!(cli.context, Some("test-context".to_string()));
+        assert!(matches!(cli.output.format, OutputFormat::Json));
+        assert_eq!(cli.context(), Some("test-context"));
+        assert!(!cli.fans_out());
+
+        let cli = Cli::try_parse_from(["kdx", "pods", "--context", "prod", "--context", "staging"])
+            .unwrap();
+        assert_eq!(cli.contexts, ["prod", "staging"]);
+        assert!(cli.fans_out());
+        assert!(
+            Cli::try_parse_from(["kdx", "pods", "--context", "prod", "--all-contexts"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_name_output_presets() {
+        let cli = Cli::try_parse_from(["kdx", "pods", "-o", "ns/name"]).unwrap();
+        assert!(matches!(cli.output.format, OutputFormat::NsName));
+
+        let cli = Cli::try_parse_from(["kdx", "-o", "name", "services"]).unwrap();
+        assert!(matches!(cli.output.format, OutputFormat::Name));
+
+        assert!(Cli::try_parse_from(["kdx", "-o", "template", "services"]).is_err());
+
+        let cli = Cli::try_parse_from(["kdx", "-o", "jsonpath={[*].name}", "pods"]).unwrap();
+        assert!(matches!(
+            cli.output.format,
+            OutputFormat::JsonPath(ref t) if *t == JsonPath::parse("{[*].name}").unwrap()
+        ));
+        assert!(Cli::try_parse_from(["kdx", "-o", "jsonpath={[*].name", "pods"]).is_err());
+        let cli = Cli::try_parse_from(["kdx", "pods", "--columns", "name,STATUS:status"]).unwrap();
+        assert_eq!(cli.columns, ["name", "STATUS:status"]);
+
+        let cli = Cli::try_parse_from(["kdx", "namespaces", "--sort-by", "pods"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Analysis(AnalysisCommands::Namespaces)
+        ));
+        assert_eq!(cli.sort_by.as_deref(), Some("pods"));
     }
 
     #[test]
@@ -449,7 +2075,7 @@ mod tests {
         // Test cache stats
         let args = vec!["kdx", "cache", "stats"];
         let cli = Cli::try_parse_from(args).unwrap();
-        if let Commands::Cache { action } = cli.command {
+        if let Commands::State(StateCommands::Cache { action }) = cli.command {
             assert!(matches!(action, CacheAction::Stats));
         } else {
             panic!("Expected Cache command");
@@ -458,12 +2084,26 @@ mod tests {
         // Test cache clear
         let args = vec!["kdx", "cache", "clear"];
         let cli = Cli::try_parse_from(args).unwrap();
-        if let Commands::Cache { action } = cli.command {
+        if let Commands::State(StateCommands::Cache { action }) = cli.command {
             assert!(matches!(action, CacheAction::Clear));
         } else {
             panic!("Expected Cache command");
         }
 
+        // Test cache persist
+        let args = vec!["kdx", "cache", "persist", "off"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        if let Commands::State(StateCommands::Cache { action }) = cli.command {
+            assert!(matches!(
+                action,
+                CacheAction::Persist {
+                    state: PersistState::Off
+                }
+            ));
+        } else {
+            panic!("Expected Cache command");
+        }
+
         // Test cache warm
         let args = vec![
             "kdx",
@@ -475,15 +2115,62 @@ mod tests {
             "kube-system",
         ];
         let cli = Cli::try_parse_from(args).unwrap();
-        if let Commands::Cache { action } = cli.command {
-            if let CacheAction::Warm { namespaces, .. } = action {
+        if let Commands::State(StateCommands::Cache { action }) = cli.command {
+            if let CacheAction::Warm {
+                namespaces,
+                resources,
+                parallelism,
+                ..
+            } = action
+            {
                 assert_eq!(namespaces, vec!["default", "kube-system"]);
+                assert!(resources.is_empty());
+                assert_eq!(parallelism, 10);
             } else {
                 panic!("Expected Warm action");
             }
         } else {
             panic!("Expected Cache command");
         }
+
+        let args = vec![
+            "kdx",
+            "cache",
+            "warm",
+            "--resources",
+            "secrets,custom-resources",
+            "crds",
+            "-s",
+            "app=web",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        if let Commands::State(StateCommands::Cache {
+            action:
+                CacheAction::Warm {
+                    resources,
+                    selector,
+                    ..
+                },
+        }) = cli.command
+        {
+            assert_eq!(
+                resources,
+                vec![
+                    WarmResource::Secrets,
+                    WarmResource::CustomResources,
+                    WarmResource::Crds
+                ]
+            );
+            assert_eq!(
+                WarmResource::CustomResources.to_string(),
+                "custom-resources"
+            );
+            assert_eq!(selector.as_deref(), Some("app=web"));
+        } else {
+            panic!("Expected Warm action");
+        }
+
+        assert!(Cli::try_parse_from(["kdx", "cache", "warm", "--resources", "nodes"]).is_err());
     }
 
     #[test]
@@ -501,12 +2188,12 @@ mod tests {
             "--test-concurrent",
         ];
         let cli = Cli::try_parse_from(args).unwrap();
-        if let Commands::Benchmark {
+        if let Commands::State(StateCommands::Benchmark {
             iterations,
             resources,
             test_memory,
             test_concurrent,
-        } = cli.command
+        }) = cli.command
         {
             assert_eq!(iterations, 10);
             assert_eq!(resources, vec!["services", "pods"]);
@@ -516,4 +2203,23 @@ mod tests {
             panic!("Expected Benchmark command");
         }
     }
+
+    #[test]
+    fn test_cli_stuck_threshold() {
+        let cli = Cli::try_parse_from(["kdx", "stuck", "-A", "--older-than", "7d"]).unwrap();
+        assert_eq!(cli.older_than, Some(Duration::from_secs(7 * 86400)));
+        if let Commands::Analysis(AnalysisCommands::Stuck { stuck_for, .. }) = cli.command {
+            assert_eq!(stuck_for, Duration::from_secs(300));
+        } else {
+            panic!("Expected Stuck command");
+        }
+
+        let cli = Cli::try_parse_from(["kdx", "stuck", "--stuck-for", "1h"]).unwrap();
+        assert_eq!(cli.older_than, None);
+        if let Commands::Analysis(AnalysisCommands::Stuck { stuck_for, .. }) = cli.command {
+            assert_eq!(stuck_for, Duration::from_secs(3600));
+        } else {
+            panic!("Expected Stuck command");
+        }
+    }
 }
@@ -0,0 +1,213 @@
+//! Queries fanned out over several kubeconfig contexts
+//!
+//! With `--context` given more than once, or `--all-contexts`, list commands
+//! connect to every cluster, list it as they would a single one, and merge
+//! the results with the context each item came from. Each cluster gets its
+//! own discovery engine, so cached listings never mix between clusters; the
+//! persisted cache is kept per context already. A cluster that cannot be
+//! reached is reported and left out, and the merged result is marked
+//! incomplete.
+
+use crate::discovery::DiscoveryEngine;
+use crate::envelope::Warnings;
+use crate::error::{ExplorerError, Result};
+use futures::{StreamExt, TryFutureExt};
+use serde::Serialize;
+use std::future::Future;
+
+/// Clusters connected to and listed at once
+pub const CLUSTER_CONCURRENCY: usize = 4;
+
+/// An item listed from the cluster of kubeconfig context `cluster`
+#[derive(Debug, Clone, Serialize)]
+pub struct Clustered<T> {
+    pub cluster: String,
+    #[serde(flatten)]
+    pub item: T,
+}
+
+/// Names of every context in the kubeconfig
+pub fn all_contexts() -> Result<Vec<String>> {
+    let kubeconfig =
+        kube::config::Kubeconfig::read().map_err(|e| ExplorerError::Config(e.to_string()))?;
+    let contexts: Vec<String> = kubeconfig
+        .contexts
+        .into_iter()
+        .map(|context| context.name)
+        .collect();
+    if contexts.is_empty() {
+        return Err(ExplorerError::Config(
+            "the kubeconfig defines no contexts".to_string(),
+        ));
+    }
+    Ok(contexts)
+}
+
+/// Name the state kdx keeps on disk for the cluster of `context` (or the
+/// current context) is stored under: the API server URL of the context's
+/// cluster, so switching the current context or KUBECONFIG never serves
+/// another cluster's listings. Falls back to the context name, then to
+/// `current`, when the kubeconfig cannot be read.
+pub fn cache_key(context: Option<&str>) -> String {
+    let kubeconfig = kube::config::Kubeconfig::read().ok();
+    let name = context
+        .map(str::to_string)
+        .or_else(|| kubeconfig.as_ref()?.current_context.clone());
+    let server = kubeconfig
+        .as_ref()
+        .and_then(|kubeconfig| server_of(kubeconfig, name.as_deref()?));
+    server.or(name).unwrap_or_else(|| "current".to_string())
+}
+
+/// API server URL of the cluster `context` points at
+fn server_of(kubeconfig: &kube::config::Kubeconfig, context: &str) -> Option<String> {
+    let cluster = kubeconfig
+        .contexts
+        .iter()
+        .find(|c| c.name == context)?
+        .context
+        .as_ref()?
+        .cluster
+        .clone();
+    kubeconfig
+        .clusters
+        .iter()
+        .find(|c| c.name == cluster)?
+        .cluster
+        .as_ref()?
+        .server
+        .clone()
+}
+
+/// Tag the items of each cluster with its context, keeping the order of
+/// `contexts`. Failed clusters are reported, noted in `warnings` and left
+/// out; when every cluster failed, the first error is returned.
+fn merge<T>(
+    contexts: &[String],
+    warnings: &Warnings,
+    mut results: Vec<(usize, Result<Vec<T>>)>,
+) -> Result<Vec<Clustered<T>>> {
+    results.sort_by_key(|(index, _)| *index);
+    let mut merged = Vec::new();
+    let mut first_error = None;
+    let mut succeeded = 0;
+    for (index, result) in results {
+        let cluster = &contexts[index];
+        match result {
+            Ok(items) => {
+                succeeded += 1;
+                merged.extend(items.into_iter().map(|item| Clustered {
+                    cluster: cluster.clone(),
+                    item,
+                }));
+            }
+            Err(e) => {
+                let message = format!("cluster '{}' could not be listed: {}", cluster, e);
+                eprintln!("Warning: {}", message);
+                warnings.warn_incomplete(message);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) if succeeded == 0 => Err(e),
+        _ => Ok(merged),
+    }
+}
+
+/// Connect to every context with `connect` and list each cluster with
+/// `list`, at most [`CLUSTER_CONCURRENCY`] clusters at a time
+pub async fn list_across<T, C, CF, L, LF>(
+    contexts: &[String],
+    warnings: &Warnings,
+    connect: C,
+    list: L,
+) -> Result<Vec<Clustered<T>>>
+where
+    C: Fn(String) -> CF,
+    CF: Future<Output = Result<DiscoveryEngine>>,
+    L: Fn(DiscoveryEngine) -> LF,
+    LF: Future<Output = Result<Vec<T>>>,
+{
+    let results = futures::stream::iter(contexts.iter().enumerate())
+        .map(|(index, context)| {
+            let listed = connect(context.clone()).and_then(&list);
+            async move { (index, listed.await) }
+        })
+        .buffer_unordered(CLUSTER_CONCURRENCY)
+        .collect()
+        .await;
+    merge(contexts, warnings, results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_of() {
+        let kubeconfig: kube::config::Kubeconfig = serde_yaml::from_str(
+            "
+clusters:
+- name: prod
+  cluster:
+    server: https://prod.example:6443
+contexts:
+- name: prod-admin
+  context:
+    cluster: prod
+    user: admin
+- name: broken
+  context:
+    cluster: missing
+    user: admin
+",
+        )
+        .unwrap();
+        assert_eq!(
+            server_of(&kubeconfig, "prod-admin").as_deref(),
+            Some("https://prod.example:6443")
+        );
+        assert_eq!(server_of(&kubeconfig, "broken"), None);
+        assert_eq!(server_of(&kubeconfig, "dev"), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let contexts = vec!["prod".to_string(), "staging".to_string(), "dev".to_string()];
+        let results = vec![
+            (2, Ok(vec!["web"])),
+            (
+                1,
+                Err(ExplorerError::Config("connection refused".to_string())),
+            ),
+            (0, Ok(vec!["web", "db"])),
+        ];
+        let warnings = Warnings::default();
+        let merged: Vec<(String, &str)> = merge(&contexts, &warnings, results)
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.cluster, c.item))
+            .collect();
+        assert_eq!(
+            merged,
+            [
+                ("prod".to_string(), "web"),
+                ("prod".to_string(), "db"),
+                ("dev".to_string(), "web"),
+            ]
+        );
+
+        let failed: Vec<(usize, Result<Vec<&str>>)> =
+            vec![(0, Err(ExplorerError::Config("unreachable".to_string())))];
+        assert_eq!(warnings.list().len(), 1);
+        assert!(merge(&contexts, &warnings, failed).is_err());
+
+        let value = serde_json::to_value(Clustered {
+            cluster: "prod".to_string(),
+            item: serde_json::json!({"name": "web"}),
+        })
+        .unwrap();
+        assert_eq!(value, serde_json::json!({"cluster": "prod", "name": "web"}));
+    }
+}
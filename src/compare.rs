@@ -0,0 +1,196 @@
+//! Diffing the contents of one namespace across two clusters or contexts
+
+use crate::discovery::container_images;
+use k8s_openapi::api::core::v1::PodSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Replica count and container images of a single workload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkloadSummary {
+    /// Desired replicas; `None` for DaemonSets, which run one pod per node
+    pub replicas: Option<i32>,
+    /// Image per container name, init containers included
+    pub images: BTreeMap<String, String>,
+}
+
+impl WorkloadSummary {
+    pub fn new(replicas: Option<i32>, spec: Option<&PodSpec>) -> Self {
+        let images = container_images(spec);
+        WorkloadSummary { replicas, images }
+    }
+}
+
+/// Comparable contents of a namespace in one cluster
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceInventory {
+    /// Workloads keyed by (kind, name)
+    pub workloads: BTreeMap<(String, String), WorkloadSummary>,
+    /// Data keys keyed by (kind, name) for ConfigMaps and Secrets
+    pub config: BTreeMap<(String, String), BTreeSet<String>>,
+}
+
+/// A single difference between the two sides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Difference {
+    pub kind: String,
+    pub name: String,
+    pub field: String,
+    pub a: Option<String>,
+    pub b: Option<String>,
+}
+
+/// All differences found for a namespace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceDiff {
+    pub namespace: String,
+    pub context_a: String,
+    pub context_b: String,
+    pub differences: Vec<Difference>,
+}
+
+/// Compare two inventories; objects missing on one side are reported once
+/// rather than field by field
+pub fn diff_inventories(a: &NamespaceInventory, b: &NamespaceInventory) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    for key in union(a.workloads.keys(), b.workloads.keys()) {
+        let (kind, name) = key;
+        let diff = |field: &str, a: Option<String>, b: Option<String>| Difference {
+            kind: kind.clone(),
+            name: name.clone(),
+            field: field.to_string(),
+            a,
+            b,
+        };
+
+        match (a.workloads.get(key), b.workloads.get(key)) {
+            (Some(left), Some(right)) => {
+                if left.replicas != right.replicas {
+                    differences.push(diff(
+                        "replicas",
+                        left.replicas.map(|r| r.to_string()),
+                        right.replicas.map(|r| r.to_string()),
+                    ));
+                }
+                for container in union(left.images.keys(), right.images.keys()) {
+                    let left_image = left.images.get(container);
+                    let right_image = right.images.get(container);
+                    if left_image != right_image {
+                        differences.push(diff(
+                            &format!("image[{}]", container),
+                            left_image.cloned(),
+                            right_image.cloned(),
+                        ));
+                    }
+                }
+            }
+            (left, right) => {
+                differences.push(presence(kind, name, left.is_some(), right.is_some()))
+            }
+        }
+    }
+
+    for key in union(a.config.keys(), b.config.keys()) {
+        let (kind, name) = key;
+        match (a.config.get(key), b.config.get(key)) {
+            (Some(left), Some(right)) if left != right => {
+                let only = |x: &BTreeSet<String>, y: &BTreeSet<String>| {
+                    let keys: Vec<&str> = x.difference(y).map(String::as_str).collect();
+                    (!keys.is_empty()).then(|| keys.join(", "))
+                };
+                differences.push(Difference {
+                    kind: kind.clone(),
+                    name: name.clone(),
+                    field: "keys".to_string(),
+                    a: only(left, right),
+                    b: only(right, left),
+                });
+            }
+            (Some(_), Some(_)) => {}
+            (left, right) => {
+                differences.push(presence(kind, name, left.is_some(), right.is_some()))
+            }
+        }
+    }
+
+    differences
+}
+
+fn presence(kind: &str, name: &str, in_a: bool, in_b: bool) -> Difference {
+    let state = |present: bool| Some(if present { "present" } else { "missing" }.to_string());
+    Difference {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        field: "exists".to_string(),
+        a: state(in_a),
+        b: state(in_b),
+    }
+}
+
+fn union<'a, T: Ord + 'a>(
+    a: impl Iterator<Item = &'a T>,
+    b: impl Iterator<Item = &'a T>,
+) -> BTreeSet<&'a T> {
+    a.chain(b).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workload(replicas: i32, images: &[(&str, &str)]) -> WorkloadSummary {
+        WorkloadSummary {
+            replicas: Some(replicas),
+            images: images
+                .iter()
+                .map(|(c, i)| (c.to_string(), i.to_string()))
+                .collect(),
+        }
+    }
+
+    fn key(kind: &str, name: &str) -> (String, String) {
+        (kind.to_string(), name.to_string())
+    }
+
+    #[test]
+    fn test_diff_workloads() {
+        let mut a = NamespaceInventory::default();
+        let mut b = NamespaceInventory::default();
+        a.workloads
+            .insert(key("Deployment", "api"), workload(3, &[("api", "api:1.4")]));
+        b.workloads.insert(
+            key("Deployment", "api"),
+            workload(1, &[("api", "api:1.5"), ("proxy", "envoy:1.29")]),
+        );
+        a.workloads
+            .insert(key("StatefulSet", "db"), workload(1, &[("db", "pg:16")]));
+
+        let diff = diff_inventories(&a, &b);
+        let fields: Vec<&str> = diff.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["replicas", "image[api]", "image[proxy]", "exists"]
+        );
+        assert_eq!(diff[2].a, None);
+        assert_eq!(diff[3].b.as_deref(), Some("missing"));
+    }
+
+    #[test]
+    fn test_diff_config_keys() {
+        let mut a = NamespaceInventory::default();
+        let mut b = NamespaceInventory::default();
+        let keys = |k: &[&str]| k.iter().map(|s| s.to_string()).collect::<BTreeSet<_>>();
+        a.config
+            .insert(key("ConfigMap", "app"), keys(&["LOG_LEVEL", "REGION"]));
+        b.config
+            .insert(key("ConfigMap", "app"), keys(&["LOG_LEVEL", "FEATURE_X"]));
+        a.config.insert(key("Secret", "db"), keys(&["password"]));
+        b.config.insert(key("Secret", "db"), keys(&["password"]));
+
+        let diff = diff_inventories(&a, &b);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].a.as_deref(), Some("REGION"));
+        assert_eq!(diff[0].b.as_deref(), Some("FEATURE_X"));
+    }
+}
@@ -0,0 +1,109 @@
+//! Compression of large JSON/YAML exports and recorded snapshots
+
+use crate::cli::CompressFormat;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{self, Read, Write};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// File extension for data written with `format`
+pub fn extension(format: CompressFormat) -> &'static str {
+    match format {
+        CompressFormat::Gzip => "gz",
+        CompressFormat::Zstd => "zst",
+    }
+}
+
+/// Writer compressing everything written to the inner writer. Call
+/// [`Compressor::finish`] to write the trailer and surface any error.
+pub enum Compressor<W: Write> {
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Compressor<W> {
+    pub fn new(format: CompressFormat, writer: W) -> io::Result<Self> {
+        Ok(match format {
+            CompressFormat::Gzip => {
+                Compressor::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+            }
+            CompressFormat::Zstd => Compressor::Zstd(zstd::Encoder::new(writer, 0)?),
+        })
+    }
+
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Compressor::Gzip(encoder) => encoder.finish(),
+            Compressor::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Compressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Compressor::Gzip(encoder) => encoder.write(buf),
+            Compressor::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Compressor::Gzip(encoder) => encoder.flush(),
+            Compressor::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Compress a complete buffer
+pub fn compress(format: CompressFormat, data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut compressor = Compressor::new(format, Vec::new())?;
+    compressor.write_all(data)?;
+    compressor.finish()
+}
+
+/// Decompress gzip or zstd data, recognised by its magic bytes; anything
+/// else is returned unchanged
+pub fn decompress(data: Vec<u8>) -> io::Result<Vec<u8>> {
+    if data.starts_with(&GZIP_MAGIC) {
+        let mut decoded = Vec::new();
+        MultiGzDecoder::new(data.as_slice()).read_to_end(&mut decoded)?;
+        Ok(decoded)
+    } else if data.starts_with(&ZSTD_MAGIC) {
+        zstd::decode_all(data.as_slice())
+    } else {
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = br#"[{"name":"web","namespace":"default"}]"#.repeat(100);
+
+        for format in [CompressFormat::Gzip, CompressFormat::Zstd] {
+            let compressed = compress(format, &data).unwrap();
+            assert!(compressed.len() < data.len());
+            assert_eq!(decompress(compressed).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_plain_data_passes_through() {
+        let data = b"{\"items\":[]}".to_vec();
+        assert_eq!(decompress(data.clone()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_concatenated_gzip_members() {
+        // Each printed document is a separate member when output is compressed
+        let mut data = compress(CompressFormat::Gzip, b"first\n").unwrap();
+        data.extend(compress(CompressFormat::Gzip, b"second\n").unwrap());
+        assert_eq!(decompress(data).unwrap(), b"first\nsecond\n");
+    }
+}
@@ -0,0 +1,473 @@
+//! User configuration file (`~/.config/kdx/config.toml`)
+//!
+//! The file sets defaults that command-line flags override. It is TOML,
+//! or YAML when its name ends in `.yaml` or `.yml`; `kdx config init`
+//! writes a commented starter file.
+//!
+//! ```toml
+//! output = "yaml"
+//! concurrency = 10
+//! color = "never"
+//!
+//! [selectors]
+//! frontend = "app=web,tier=frontend"
+//!
+//! [templates]
+//! images = "{namespace}/{name} {containers.0.image}"
+//!
+//! [cache.ttl]
+//! default = "5m"
+//! pods = "15s"
+//!
+//! [namespaces]
+//! default = "shop"
+//! exclude = ["kube-*"]
+//!
+//! [namespaces.commands.custom-resources]
+//! all-namespaces = true
+//! ```
+
+use crate::cache::CacheTtl;
+use crate::cli::{parse_output_format, ColorMode, OutputFormat};
+use crate::error::{ExplorerError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Location of the config file: `$KDX_CONFIG`, else `config.toml` in
+/// `$XDG_CONFIG_HOME/kdx` or `~/.config/kdx`, or `config.yaml` there if
+/// only that exists
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("KDX_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    }
+    .join("kdx");
+    let toml = dir.join("config.toml");
+    Some(
+        ["config.yaml", "config.yml"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|yaml| !toml.exists() && yaml.exists())
+            .unwrap_or(toml),
+    )
+}
+
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml")
+}
+
+const STARTER_TOML: &str = r#"# kdx configuration; command-line flags override these settings
+
+# Output format used when -o is not given
+# output = "table"
+
+# Namespaces listed at once by scans across namespaces
+# concurrency = 20
+
+# When to color output: auto, always or never
+# color = "auto"
+
+# Label selectors used as -s @name
+[selectors]
+# frontend = "app=web,tier=frontend"
+
+# One-line output templates used with --template-preset
+[templates]
+# images = "{namespace}/{name} {containers.0.image}"
+
+# How long listings are cached, by kind
+[cache.ttl]
+# default = "5m"
+# pods = "15s"
+
+[namespaces]
+# Namespace used when -n is not given
+# default = "default"
+# Left out of listings across namespaces unless --system is passed
+# exclude = ["kube-*"]
+"#;
+
+const STARTER_YAML: &str = r#"# kdx configuration; command-line flags override these settings
+
+# Output format used when -o is not given
+# output: table
+
+# Namespaces listed at once by scans across namespaces
+# concurrency: 20
+
+# When to color output: auto, always or never
+# color: auto
+
+# Label selectors used as -s @name
+selectors: {}
+#   frontend: app=web,tier=frontend
+
+# One-line output templates used with --template-preset
+templates: {}
+#   images: "{namespace}/{name} {containers.0.image}"
+
+cache:
+  # How long listings are cached, by kind
+  ttl: {}
+  #   default: 5m
+  #   pods: 15s
+
+namespaces:
+  # Namespace used when -n is not given
+  # default: default
+  # Left out of listings across namespaces unless --system is passed
+  exclude: []
+  #   - kube-*
+"#;
+
+/// Write a commented starter file at `path`, in YAML if its name says so
+pub fn init(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(ExplorerError::InvalidArgument(format!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        )));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let starter = if is_yaml(path) {
+        STARTER_YAML
+    } else {
+        STARTER_TOML
+    };
+    std::fs::write(path, starter)?;
+    Ok(())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Output format when `-o` is not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Namespaces listed at once when `--concurrency` is not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// When to color output when `--color` is not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorMode>,
+    /// Named label selectors used as `-s @name`
+    pub selectors: BTreeMap<String, String>,
+    /// Named output templates selected with `--template-preset`
+    pub templates: BTreeMap<String, String>,
+    pub cache: CacheConfig,
+    pub namespaces: NamespacesConfig,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// TTL by cached kind, or `default`, as a duration such as "15s"
+    pub ttl: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamespacesConfig {
+    /// Namespace commands use when none is given with `-n`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Namespaces, or patterns such as `kube-*`, left out of listings
+    /// across namespaces unless `--system` is passed
+    pub exclude: Vec<String>,
+    /// Rules of single commands, by subcommand name
+    pub commands: BTreeMap<String, CommandNamespaces>,
+}
+
+/// Namespace rules of one command, overriding the `[namespaces]` table
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CommandNamespaces {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// List every namespace when none is given, ignoring the default
+    pub all_namespaces: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+}
+
+/// Namespace rules in effect for one command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceRules {
+    /// Namespace to use when none is given; `None` lists every namespace
+    pub namespace: Option<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Config {
+    /// Load the file given with `--config`, or else the config file at its
+    /// default location, where a missing file is an empty config
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            return Self::load_from(path);
+        }
+        match config_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ExplorerError::InvalidArgument(format!(
+                "cannot read config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(&content, is_yaml(path)).map_err(|e| {
+            ExplorerError::InvalidArgument(format!("invalid config file {}: {}", path.display(), e))
+        })
+    }
+
+    fn parse(content: &str, yaml: bool) -> std::result::Result<Self, String> {
+        if yaml {
+            // An empty YAML document is an empty config
+            if content.trim().is_empty() {
+                return Ok(Self::default());
+            }
+            serde_yaml::from_str(content).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(content).map_err(|e| e.to_string())
+        }
+    }
+
+    /// The config as it would be written to `path`
+    pub fn render(&self, path: &Path) -> Result<String> {
+        if is_yaml(path) {
+            Ok(serde_yaml::to_string(self)?)
+        } else {
+            toml::to_string(self).map_err(|e| ExplorerError::OutputFormat(e.to_string()))
+        }
+    }
+
+    /// The `output` setting
+    pub fn output_format(&self) -> Result<Option<OutputFormat>> {
+        self.output
+            .as_deref()
+            .map(|name| {
+                parse_output_format(name).map_err(|_| {
+                    ExplorerError::InvalidArgument(format!(
+                        "invalid output setting '{}' in config file",
+                        name
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// A label selector given on the command line, with `@name` replaced
+    /// by the `[selectors]` preset of that name
+    pub fn expand_selector(&self, selector: &str) -> Result<String> {
+        let Some(name) = selector.strip_prefix('@') else {
+            return Ok(selector.to_string());
+        };
+        self.selectors.get(name).cloned().ok_or_else(|| {
+            let known: Vec<&str> = self.selectors.keys().map(String::as_str).collect();
+            ExplorerError::InvalidArgument(if known.is_empty() {
+                format!(
+                    "unknown selector preset '{}': no [selectors] in config",
+                    name
+                )
+            } else {
+                format!(
+                    "unknown selector preset '{}' (available: {})",
+                    name,
+                    known.join(", ")
+                )
+            })
+        })
+    }
+
+    /// TTL settings of the `[cache.ttl]` table
+    pub fn cache_ttls(&self) -> Result<Vec<CacheTtl>> {
+        self.cache
+            .ttl
+            .iter()
+            .map(|(kind, duration)| {
+                CacheTtl::parse(kind, duration).map_err(|e| {
+                    ExplorerError::InvalidArgument(format!("invalid [cache.ttl] setting: {}", e))
+                })
+            })
+            .collect()
+    }
+
+    /// Namespace rules of the subcommand `command`: its own entry in
+    /// `[namespaces.commands]`, falling back to the `[namespaces]` table
+    pub fn namespace_rules(&self, command: &str) -> NamespaceRules {
+        let namespaces = &self.namespaces;
+        let rule = namespaces.commands.get(command);
+        let namespace = match rule {
+            Some(rule) if rule.all_namespaces => None,
+            Some(CommandNamespaces {
+                namespace: Some(namespace),
+                ..
+            }) => Some(namespace.clone()),
+            _ => namespaces.default.clone(),
+        };
+        NamespaceRules {
+            namespace,
+            exclude: rule
+                .and_then(|rule| rule.exclude.clone())
+                .unwrap_or_else(|| namespaces.exclude.clone()),
+        }
+    }
+
+    /// Template source for a preset name
+    pub fn template_preset(&self, name: &str) -> Result<&str> {
+        self.templates.get(name).map(String::as_str).ok_or_else(|| {
+            let known: Vec<&str> = self.templates.keys().map(String::as_str).collect();
+            ExplorerError::InvalidArgument(if known.is_empty() {
+                format!(
+                    "unknown template preset '{}': no [templates] in config",
+                    name
+                )
+            } else {
+                format!(
+                    "unknown template preset '{}' (available: {})",
+                    name,
+                    known.join(", ")
+                )
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_presets() {
+        let config: Config = toml::from_str(
+            r#"
+            [templates]
+            images = "{name} {containers.0.image}"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.template_preset("images").unwrap(),
+            "{name} {containers.0.image}"
+        );
+        let err = config.template_preset("owners").unwrap_err().to_string();
+        assert!(err.contains("available: images"));
+
+        assert!(toml::from_str::<Config>("[template]\nx = \"{name}\"").is_err());
+    }
+
+    #[test]
+    fn test_cache_ttls() {
+        let config: Config = toml::from_str(
+            r#"
+            [cache.ttl]
+            default = "10m"
+            pods = "15s"
+            "#,
+        )
+        .unwrap();
+        let ttls = config.cache_ttls().unwrap();
+        assert_eq!(ttls.len(), 2);
+        assert_eq!(ttls[0].kind, None);
+        assert_eq!(ttls[0].ttl.as_secs(), 600);
+        assert_eq!(ttls[1].kind, Some(crate::cache::CacheKind::Pods));
+
+        let config: Config = toml::from_str("[cache.ttl]\nnodes = \"1m\"").unwrap();
+        let err = config.cache_ttls().unwrap_err().to_string();
+        assert!(err.contains("unknown cached resource 'nodes'"));
+    }
+
+    #[test]
+    fn test_namespace_rules() {
+        let config: Config = toml::from_str(
+            r#"
+            [namespaces]
+            default = "shop"
+            exclude = ["kube-*"]
+
+            [namespaces.commands.custom-resources]
+            all-namespaces = true
+
+            [namespaces.commands.events]
+            namespace = "ops"
+            exclude = []
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.namespace_rules("pods"),
+            NamespaceRules {
+                namespace: Some("shop".to_string()),
+                exclude: vec!["kube-*".to_string()],
+            }
+        );
+        assert_eq!(config.namespace_rules("custom-resources").namespace, None);
+        assert_eq!(
+            config.namespace_rules("custom-resources").exclude,
+            ["kube-*"]
+        );
+        assert_eq!(
+            config.namespace_rules("events"),
+            NamespaceRules {
+                namespace: Some("ops".to_string()),
+                exclude: Vec::new(),
+            }
+        );
+        assert_eq!(
+            Config::default().namespace_rules("pods"),
+            NamespaceRules::default()
+        );
+    }
+
+    #[test]
+    fn test_yaml_config() {
+        let yaml = r#"
+output: json
+concurrency: 8
+color: never
+selectors:
+  frontend: app=web,tier=frontend
+namespaces:
+  exclude: [kube-system]
+"#;
+        let config = Config::parse(yaml, true).unwrap();
+        assert!(matches!(
+            config.output_format(),
+            Ok(Some(OutputFormat::Json))
+        ));
+        assert_eq!(config.concurrency, Some(8));
+        assert_eq!(config.color, Some(ColorMode::Never));
+        assert_eq!(
+            config.expand_selector("@frontend").unwrap(),
+            "app=web,tier=frontend"
+        );
+        assert_eq!(config.expand_selector("app=db").unwrap(), "app=db");
+        assert!(config
+            .expand_selector("@backend")
+            .unwrap_err()
+            .to_string()
+            .contains("available: frontend"));
+        assert!(Config::parse("colour: never", true).is_err());
+
+        // Both starter files parse, and a config survives rendering
+        assert!(Config::parse(STARTER_TOML, false).is_ok());
+        assert!(Config::parse(STARTER_YAML, true).is_ok());
+        let rendered = config.render(Path::new("config.toml")).unwrap();
+        let reparsed = Config::parse(&rendered, false).unwrap();
+        assert_eq!(reparsed.namespaces.exclude, ["kube-system"]);
+        assert_eq!(reparsed.selectors, config.selectors);
+    }
+}
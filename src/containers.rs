@@ -0,0 +1,246 @@
+//! Every container of every pod, with the role it plays in its pod
+//!
+//! Kubernetes only distinguishes init containers from app containers, and
+//! since 1.28 native sidecars (init containers with `restartPolicy: Always`).
+//! Sidecars declared as app containers are recognized by name or image:
+//! mesh proxies, log shippers, secret agents and auth proxies. The container
+//! named by the `kubectl.kubernetes.io/default-container` annotation is
+//! always the main one.
+
+use crate::discovery::{pod_workload, LazyConvert, PodInfo};
+use crate::error::Result;
+use crate::pod_detail::container_details;
+use k8s_openapi::api::core::v1::{Pod, PodSpec};
+use kube::api::ListParams;
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const DEFAULT_CONTAINER_ANNOTATION: &str = "kubectl.kubernetes.io/default-container";
+
+/// Container names, or image repository names, of well-known sidecars
+const KNOWN_SIDECARS: &[&str] = &[
+    "istio-proxy",
+    "linkerd-proxy",
+    "envoy",
+    "cloud-sql-proxy",
+    "cloudsql-proxy",
+    "vault-agent",
+    "fluent-bit",
+    "fluentd",
+    "filebeat",
+    "promtail",
+    "oauth2-proxy",
+    "datadog-agent",
+    "otel-collector",
+    "opentelemetry-collector",
+    "config-reloader",
+    "configmap-reload",
+];
+
+/// What a container does for its pod
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRole {
+    /// Runs to completion before the app containers start
+    Init,
+    /// Runs alongside the main container to support it
+    Sidecar,
+    Main,
+}
+
+impl std::fmt::Display for ContainerRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerRole::Init => write!(f, "init"),
+            ContainerRole::Sidecar => write!(f, "sidecar"),
+            ContainerRole::Main => write!(f, "main"),
+        }
+    }
+}
+
+/// One container with its pod and workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerInfo {
+    pub namespace: String,
+    pub pod: String,
+    /// Owning workload as Kind/name
+    pub workload: String,
+    pub name: String,
+    pub role: ContainerRole,
+    pub image: String,
+    pub state: String,
+    pub ready: bool,
+    pub restart_count: i32,
+    pub requests: BTreeMap<String, String>,
+    pub limits: BTreeMap<String, String>,
+}
+
+/// Image repository name without registry, path and tag:
+/// `ghcr.io/org/envoy:v1.29` is `envoy`
+fn image_name(image: &str) -> &str {
+    let image = image.split('@').next().unwrap_or(image);
+    let name = image.rsplit('/').next().unwrap_or(image);
+    name.split(':').next().unwrap_or(name)
+}
+
+fn is_known_sidecar(name: &str, image: &str) -> bool {
+    let image = image_name(image);
+    KNOWN_SIDECARS.contains(&name) || KNOWN_SIDECARS.contains(&image)
+}
+
+/// Role of each container of `spec` by name, init containers first
+pub fn container_roles(
+    spec: &PodSpec,
+    annotations: &BTreeMap<String, String>,
+) -> Vec<(String, ContainerRole)> {
+    let init = spec.init_containers.iter().flatten().map(|c| {
+        let role = if c.restart_policy.as_deref() == Some("Always") {
+            ContainerRole::Sidecar
+        } else {
+            ContainerRole::Init
+        };
+        (c.name.clone(), role)
+    });
+
+    let default = annotations.get(DEFAULT_CONTAINER_ANNOTATION);
+    let mut app: Vec<(String, ContainerRole)> = spec
+        .containers
+        .iter()
+        .map(|c| {
+            let sidecar = match default {
+                Some(default) => c.name != *default,
+                None => is_known_sidecar(&c.name, c.image.as_deref().unwrap_or_default()),
+            };
+            let role = if sidecar {
+                ContainerRole::Sidecar
+            } else {
+                ContainerRole::Main
+            };
+            (c.name.clone(), role)
+        })
+        .collect();
+    // A pod always has a main container; without a better guess it is the first
+    if !app.iter().any(|(_, role)| *role == ContainerRole::Main) {
+        if let Some((_, role)) = app.first_mut() {
+            *role = ContainerRole::Main;
+        }
+    }
+
+    init.chain(app).collect()
+}
+
+/// The containers of `pod`, init containers first
+pub fn pod_containers(pod: &Pod) -> Vec<ContainerInfo> {
+    let (Some(spec), Some(info)) = (&pod.spec, LazyConvert::<PodInfo>::lazy_convert(pod)) else {
+        return Vec::new();
+    };
+    let annotations = pod.metadata.annotations.clone().unwrap_or_default();
+    let roles = container_roles(spec, &annotations);
+    let (kind, name) = pod_workload(&info);
+    let workload = format!("{}/{}", kind, name);
+
+    container_details(pod)
+        .into_iter()
+        .zip(roles)
+        .map(|(detail, (_, role))| ContainerInfo {
+            namespace: info.namespace.clone(),
+            pod: info.name.clone(),
+            workload: workload.clone(),
+            name: detail.name,
+            role,
+            image: detail.image,
+            state: detail.state,
+            ready: detail.ready,
+            restart_count: detail.restart_count,
+            requests: detail.requests,
+            limits: detail.limits,
+        })
+        .collect()
+}
+
+/// Containers whose image contains `image` and whose role is `role`
+pub fn filter_containers(
+    containers: Vec<ContainerInfo>,
+    image: Option<&str>,
+    role: Option<&str>,
+) -> Vec<ContainerInfo> {
+    containers
+        .into_iter()
+        .filter(|c| image.is_none_or(|image| c.image.contains(image)))
+        .filter(|c| role.is_none_or(|role| c.role.to_string().eq_ignore_ascii_case(role)))
+        .collect()
+}
+
+/// Containers of the pods in `namespace` (or all namespaces if None)
+pub async fn list_containers(
+    client: &Client,
+    namespace: Option<&str>,
+) -> Result<Vec<ContainerInfo>> {
+    let api: Api<Pod> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    Ok(api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .iter()
+        .flat_map(pod_containers)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(annotations: serde_json::Value) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "web-0", "namespace": "shop", "annotations": annotations},
+            "spec": {
+                "initContainers": [
+                    {"name": "migrate", "image": "web:1.2"},
+                    {"name": "vault", "image": "hashicorp/vault:1.15", "restartPolicy": "Always"},
+                ],
+                "containers": [
+                    {"name": "web", "image": "registry.example.com/shop/web:1.2"},
+                    {"name": "proxy", "image": "docker.io/envoyproxy/envoy:v1.29"},
+                    {"name": "istio-proxy", "image": "docker.io/istio/proxyv2:1.20"},
+                ],
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_container_roles() {
+        let containers = pod_containers(&pod(serde_json::json!({})));
+        let roles: Vec<(&str, ContainerRole)> = containers
+            .iter()
+            .map(|c| (c.name.as_str(), c.role))
+            .collect();
+        assert_eq!(
+            roles,
+            [
+                ("migrate", ContainerRole::Init),
+                ("vault", ContainerRole::Sidecar),
+                ("web", ContainerRole::Main),
+                ("proxy", ContainerRole::Sidecar),
+                ("istio-proxy", ContainerRole::Sidecar),
+            ]
+        );
+        assert_eq!(containers[0].workload, "Pod/web-0");
+
+        let envoy = filter_containers(containers, Some("envoy"), Some("Sidecar"));
+        assert_eq!(envoy.len(), 1);
+
+        // The default container annotation overrides the name heuristic
+        let pod = pod(serde_json::json!({DEFAULT_CONTAINER_ANNOTATION: "proxy"}));
+        let main: Vec<String> = pod_containers(&pod)
+            .into_iter()
+            .filter(|c| c.role == ContainerRole::Main)
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(main, ["proxy"]);
+    }
+}
@@ -0,0 +1,282 @@
+//! Attribution of CRDs to the operator or controller serving them
+
+use crate::owners::detect_manager;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Evidence linking a CRD to its controller, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Attribution {
+    /// The CRD's conversion webhook service selects the workload's pods
+    ConversionWebhook,
+    /// The CRD and workload belong to the same Helm release, Argo CD
+    /// application or Flux object
+    ManagedBy,
+    /// The CRD and workload share an `app.kubernetes.io/part-of` or
+    /// `app.kubernetes.io/instance` label
+    Labels,
+    /// The workload is named after the CRD's API group
+    GroupName,
+}
+
+impl fmt::Display for Attribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Attribution::ConversionWebhook => write!(f, "conversion webhook"),
+            Attribution::ManagedBy => write!(f, "managed-by"),
+            Attribution::Labels => write!(f, "labels"),
+            Attribution::GroupName => write!(f, "group name"),
+        }
+    }
+}
+
+/// Workload identified as serving a CRD
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrdController {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub via: Attribution,
+}
+
+impl fmt::Display for CrdController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} ({})", self.namespace, self.name, self.via)
+    }
+}
+
+/// Deployment or StatefulSet that may run a controller
+#[derive(Debug, Clone, Default)]
+pub struct ControllerCandidate {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    /// Labels of the pod template, matched against service selectors
+    pub pod_labels: BTreeMap<String, String>,
+}
+
+/// Parts of a CRD used for attribution
+#[derive(Debug, Clone, Default)]
+pub struct CrdHints {
+    pub group: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    /// Namespace and name of the conversion webhook service
+    pub webhook_service: Option<(String, String)>,
+}
+
+impl CrdHints {
+    pub fn from_crd(crd: &CustomResourceDefinition) -> Self {
+        let webhook_service = crd
+            .spec
+            .conversion
+            .as_ref()
+            .and_then(|c| c.webhook.as_ref())
+            .and_then(|w| w.client_config.as_ref())
+            .and_then(|c| c.service.as_ref())
+            .map(|s| (s.namespace.clone(), s.name.clone()));
+
+        Self {
+            group: crd.spec.group.clone(),
+            labels: crd.metadata.labels.clone().unwrap_or_default(),
+            annotations: crd.metadata.annotations.clone().unwrap_or_default(),
+            webhook_service,
+        }
+    }
+}
+
+/// Labels shared by the resources of one application
+const APP_LABELS: &[&str] = &["app.kubernetes.io/part-of", "app.kubernetes.io/instance"];
+
+/// Find the workload serving a CRD, trying each kind of evidence in order.
+/// `service_selectors` maps (namespace, name) of services to their selector.
+pub fn attribute(
+    crd: &CrdHints,
+    candidates: &[ControllerCandidate],
+    service_selectors: &BTreeMap<(String, String), BTreeMap<String, String>>,
+) -> Option<CrdController> {
+    let found = |matching: Vec<&ControllerCandidate>, via| {
+        best(matching).map(|c| CrdController {
+            kind: c.kind.clone(),
+            namespace: c.namespace.clone(),
+            name: c.name.clone(),
+            via,
+        })
+    };
+
+    let webhook = crd.webhook_service.as_ref().and_then(|service| {
+        let selector = service_selectors.get(service)?;
+        (!selector.is_empty()).then_some((&service.0, selector))
+    });
+    if let Some((namespace, selector)) = webhook {
+        let matching = candidates
+            .iter()
+            .filter(|c| &c.namespace == namespace)
+            .filter(|c| selector.iter().all(|(k, v)| c.pod_labels.get(k) == Some(v)))
+            .collect();
+        if let Some(controller) = found(matching, Attribution::ConversionWebhook) {
+            return Some(controller);
+        }
+    }
+
+    if let Some(manager) = detect_manager(&crd.labels, &crd.annotations) {
+        let matching = candidates
+            .iter()
+            .filter(|c| {
+                detect_manager(&c.labels, &c.annotations).is_some_and(|m| {
+                    m.tool == manager.tool
+                        && m.name == manager.name
+                        && (m.namespace.is_none()
+                            || manager.namespace.is_none()
+                            || m.namespace == manager.namespace)
+                })
+            })
+            .collect();
+        if let Some(controller) = found(matching, Attribution::ManagedBy) {
+            return Some(controller);
+        }
+    }
+
+    for key in APP_LABELS {
+        if let Some(value) = crd.labels.get(*key) {
+            let matching = candidates
+                .iter()
+                .filter(|c| c.labels.get(*key) == Some(value))
+                .collect();
+            if let Some(controller) = found(matching, Attribution::Labels) {
+                return Some(controller);
+            }
+        }
+    }
+
+    // cert-manager.io is served by cert-manager, keda.sh by keda-operator
+    let prefix = crd.group.split('.').next().unwrap_or_default();
+    if prefix.is_empty() {
+        return None;
+    }
+    let matching = candidates
+        .iter()
+        .filter(|c| c.name == prefix || c.name.starts_with(&format!("{}-", prefix)))
+        .collect();
+    found(matching, Attribution::GroupName)
+}
+
+/// Prefer workloads named like a controller over the webhooks and helpers
+/// deployed next to it, then the shortest name
+fn best(candidates: Vec<&ControllerCandidate>) -> Option<&ControllerCandidate> {
+    let score = |c: &ControllerCandidate| {
+        let name = c.name.as_str();
+        let helper = ["webhook", "cainjector", "admission"]
+            .iter()
+            .any(|s| name.contains(s));
+        let controller = ["operator", "controller", "manager"]
+            .iter()
+            .any(|s| name.contains(s));
+        i32::from(controller) - 2 * i32::from(helper)
+    };
+    candidates.into_iter().min_by(|a, b| {
+        score(b)
+            .cmp(&score(a))
+            .then(a.name.len().cmp(&b.name.len()))
+            .then(a.name.cmp(&b.name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn deployment(namespace: &str, name: &str) -> ControllerCandidate {
+        ControllerCandidate {
+            kind: "Deployment".to_string(),
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_conversion_webhook_service() {
+        let mut webhook = deployment("cert-manager", "cert-manager-webhook");
+        webhook.pod_labels = map(&[("app", "webhook")]);
+        let candidates = vec![deployment("cert-manager", "cert-manager"), webhook];
+        let services = BTreeMap::from([(
+            (
+                "cert-manager".to_string(),
+                "cert-manager-webhook".to_string(),
+            ),
+            map(&[("app", "webhook")]),
+        )]);
+        let crd = CrdHints {
+            group: "cert-manager.io".to_string(),
+            webhook_service: Some((
+                "cert-manager".to_string(),
+                "cert-manager-webhook".to_string(),
+            )),
+            ..Default::default()
+        };
+
+        let controller = attribute(&crd, &candidates, &services).unwrap();
+        assert_eq!(controller.name, "cert-manager-webhook");
+        assert_eq!(controller.via, Attribution::ConversionWebhook);
+
+        // Without the service the group name points at the controller itself
+        let controller = attribute(&crd, &candidates, &BTreeMap::new()).unwrap();
+        assert_eq!(controller.name, "cert-manager");
+        assert_eq!(controller.via, Attribution::GroupName);
+    }
+
+    #[test]
+    fn test_helm_release_and_labels() {
+        let release = map(&[
+            ("meta.helm.sh/release-name", "keda"),
+            ("meta.helm.sh/release-namespace", "keda"),
+        ]);
+        let mut operator = deployment("keda", "keda-operator");
+        operator.annotations = release.clone();
+        let mut metrics = deployment("keda", "keda-metrics-apiserver");
+        metrics.annotations = release.clone();
+        let candidates = vec![metrics, operator];
+
+        let crd = CrdHints {
+            group: "keda.sh".to_string(),
+            annotations: release,
+            ..Default::default()
+        };
+        let controller = attribute(&crd, &candidates, &BTreeMap::new()).unwrap();
+        assert_eq!(controller.name, "keda-operator");
+        assert_eq!(controller.via, Attribution::ManagedBy);
+
+        let mut prometheus = deployment("monitoring", "prometheus-operator");
+        prometheus.labels = map(&[("app.kubernetes.io/part-of", "kube-prometheus")]);
+        let crd = CrdHints {
+            group: "monitoring.coreos.com".to_string(),
+            labels: map(&[("app.kubernetes.io/part-of", "kube-prometheus")]),
+            ..Default::default()
+        };
+        let controller = attribute(&crd, &[prometheus], &BTreeMap::new()).unwrap();
+        assert_eq!(controller.via, Attribution::Labels);
+        assert_eq!(
+            controller.to_string(),
+            "monitoring/prometheus-operator (labels)"
+        );
+
+        let unknown = CrdHints {
+            group: "example.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(attribute(&unknown, &candidates, &BTreeMap::new()), None);
+    }
+}
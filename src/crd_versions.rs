@@ -0,0 +1,225 @@
+//! CRD storage version migration checks
+//!
+//! `status.storedVersions` lists every version objects of a CRD may still be
+//! persisted at in etcd. Versions other than the current storage version need
+//! their objects rewritten before they can be dropped from the CRD, and a
+//! version that is no longer served must not be removed from the spec while
+//! objects are stored at it.
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+/// Stored version other than the current storage version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleVersion {
+    pub version: String,
+    /// Still served by the API server
+    pub served: bool,
+    /// Marked deprecated in the CRD spec
+    pub deprecated: bool,
+}
+
+/// CRD with objects that may still be stored at old versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMigration {
+    pub crd_name: String,
+    pub storage_version: String,
+    pub stale_versions: Vec<StaleVersion>,
+    /// Instances of the CRD, if they could be listed
+    pub instances: Option<usize>,
+    /// Instances not written since the CRD versions last changed, which may
+    /// still be stored at an old version. Unknown without managed fields.
+    pub possibly_stale: Option<usize>,
+}
+
+impl StorageMigration {
+    /// An old stored version is no longer served
+    pub fn blocking(&self) -> bool {
+        self.stale_versions.iter().any(|v| !v.served)
+    }
+}
+
+/// Current storage version of a CRD
+pub fn storage_version(crd: &CustomResourceDefinition) -> Option<&str> {
+    crd.spec
+        .versions
+        .iter()
+        .find(|v| v.storage)
+        .map(|v| v.name.as_str())
+}
+
+/// Stored versions other than the storage version, with their serving state
+pub fn stale_versions(crd: &CustomResourceDefinition) -> Vec<StaleVersion> {
+    let storage = storage_version(crd);
+    let stored = crd
+        .status
+        .as_ref()
+        .and_then(|s| s.stored_versions.as_ref())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    stored
+        .iter()
+        .filter(|version| Some(version.as_str()) != storage)
+        .map(|version| {
+            let spec = crd.spec.versions.iter().find(|v| &v.name == version);
+            StaleVersion {
+                version: version.clone(),
+                served: spec.is_some_and(|v| v.served),
+                deprecated: spec.and_then(|v| v.deprecated).unwrap_or(false),
+            }
+        })
+        .collect()
+}
+
+/// When the CRD spec (and so possibly its storage version) last changed,
+/// from its managed fields
+pub fn spec_changed_at(crd: &CustomResourceDefinition) -> Option<DateTime<Utc>> {
+    crd.metadata
+        .managed_fields
+        .as_ref()?
+        .iter()
+        .filter(|entry| {
+            entry
+                .fields_v1
+                .as_ref()
+                .is_some_and(|fields| fields.0.get("f:spec").is_some())
+        })
+        .filter_map(|entry| entry.time.as_ref().map(|t| t.0))
+        .max()
+}
+
+/// Time of the last write to an object: its newest managed field entry, else
+/// its creation
+pub fn last_written(metadata: &ObjectMeta) -> Option<DateTime<Utc>> {
+    metadata
+        .managed_fields
+        .as_ref()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .filter_map(|e| e.time.as_ref().map(|t| t.0))
+                .max()
+        })
+        .or_else(|| metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+/// Assess a CRD given the metadata of its instances (`None` if they could
+/// not be listed); returns `None` when no migration is pending
+pub fn assess(
+    crd: &CustomResourceDefinition,
+    instances: Option<&[ObjectMeta]>,
+) -> Option<StorageMigration> {
+    let stale_versions = stale_versions(crd);
+    if stale_versions.is_empty() {
+        return None;
+    }
+
+    let possibly_stale = instances.and_then(|objects| {
+        let changed = spec_changed_at(crd)?;
+        let mut count = 0;
+        for object in objects {
+            // Objects written since the change were stored at the new version
+            if last_written(object)? <= changed {
+                count += 1;
+            }
+        }
+        Some(count)
+    });
+
+    Some(StorageMigration {
+        crd_name: crd.metadata.name.clone().unwrap_or_default(),
+        storage_version: storage_version(crd).unwrap_or_default().to_string(),
+        stale_versions,
+        instances: instances.map(<[ObjectMeta]>::len),
+        possibly_stale,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{FieldsV1, ManagedFieldsEntry, Time};
+
+    fn crd(versions: &[(&str, bool, bool)], stored: &[&str]) -> CustomResourceDefinition {
+        let versions: Vec<serde_json::Value> = versions
+            .iter()
+            .map(|(name, served, storage)| {
+                serde_json::json!({"name": name, "served": served, "storage": storage})
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinition",
+            "metadata": {"name": "widgets.example.com"},
+            "spec": {
+                "group": "example.com",
+                "names": {"kind": "Widget", "plural": "widgets"},
+                "scope": "Namespaced",
+                "versions": versions,
+            },
+            "status": {"storedVersions": stored},
+        }))
+        .unwrap()
+    }
+
+    fn written(time: &str) -> ObjectMeta {
+        ObjectMeta {
+            managed_fields: Some(vec![ManagedFieldsEntry {
+                time: Some(Time(time.parse().unwrap())),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stale_versions() {
+        let current = crd(&[("v1", true, true)], &["v1"]);
+        assert!(assess(&current, None).is_none());
+
+        let migrating = crd(
+            &[
+                ("v1alpha1", false, false),
+                ("v1beta1", true, false),
+                ("v1", true, true),
+            ],
+            &["v1alpha1", "v1beta1", "v1"],
+        );
+        let migration = assess(&migrating, None).unwrap();
+        assert_eq!(migration.storage_version, "v1");
+        assert_eq!(migration.stale_versions.len(), 2);
+        assert!(!migration.stale_versions[0].served);
+        assert!(migration.stale_versions[1].served);
+        assert!(migration.blocking());
+        assert_eq!(migration.instances, None);
+    }
+
+    #[test]
+    fn test_possibly_stale_instances() {
+        let mut migrating = crd(
+            &[("v1beta1", true, false), ("v1", true, true)],
+            &["v1beta1", "v1"],
+        );
+        let objects = [
+            written("2024-01-01T00:00:00Z"),
+            written("2024-06-01T00:00:00Z"),
+        ];
+
+        // Without managed fields on the CRD the switch time is unknown
+        let migration = assess(&migrating, Some(&objects)).unwrap();
+        assert_eq!(migration.instances, Some(2));
+        assert_eq!(migration.possibly_stale, None);
+        assert!(!migration.blocking());
+
+        migrating.metadata.managed_fields = Some(vec![ManagedFieldsEntry {
+            fields_v1: Some(FieldsV1(serde_json::json!({"f:spec": {}}))),
+            time: Some(Time("2024-03-01T00:00:00Z".parse().unwrap())),
+            ..Default::default()
+        }]);
+        let migration = assess(&migrating, Some(&objects)).unwrap();
+        assert_eq!(migration.possibly_stale, Some(1));
+    }
+}
@@ -0,0 +1,128 @@
+//! Service dependencies read from pod specs
+//!
+//! A pod depends on a service when a literal environment variable names it
+//! by DNS: `name.namespace`, optionally followed by `.svc` or
+//! `.svc.cluster.local`. A bare service name only counts for services in the
+//! pod's own namespace, and only in URLs or in variables whose name marks
+//! them as an address (`*_HOST`, `*_URL`, ...), since short names like
+//! `redis` are common values elsewhere.
+
+use crate::discovery::ServiceInfo;
+use k8s_openapi::api::core::v1::PodSpec;
+use std::collections::{BTreeSet, HashSet};
+
+/// Suffixes of variable names that hold a host or address
+pub const ADDRESS_SUFFIXES: &[&str] = &[
+    "_HOST",
+    "_HOSTNAME",
+    "_ADDR",
+    "_ADDRESS",
+    "_URL",
+    "_URI",
+    "_ENDPOINT",
+    "_SERVICE",
+];
+
+/// Services (as namespace and name) referenced by the environment of the
+/// containers in `spec`, for a pod in `namespace`
+pub fn service_references(
+    spec: &PodSpec,
+    namespace: &str,
+    services: &[ServiceInfo],
+) -> BTreeSet<(String, String)> {
+    let known: HashSet<(&str, &str)> = services
+        .iter()
+        .map(|s| (s.namespace.as_str(), s.name.as_str()))
+        .collect();
+
+    let containers = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&spec.containers);
+    let mut references = BTreeSet::new();
+    for var in containers.flat_map(|c| c.env.iter().flatten()) {
+        let Some(value) = &var.value else {
+            continue;
+        };
+        let name = var.name.to_uppercase();
+        let bare_allowed =
+            value.contains("://") || ADDRESS_SUFFIXES.iter().any(|s| name.ends_with(s));
+
+        for host in hosts(value) {
+            let host = host
+                .strip_suffix(".svc.cluster.local")
+                .or_else(|| host.strip_suffix(".svc"))
+                .unwrap_or(host);
+            let reference = match host.split_once('.') {
+                Some((service, ns)) if !ns.contains('.') => (ns, service),
+                Some(_) => continue,
+                None if bare_allowed => (namespace, host),
+                None => continue,
+            };
+            if known.contains(&reference) {
+                references.insert((reference.0.to_string(), reference.1.to_string()));
+            }
+        }
+    }
+    references
+}
+
+/// Candidate host names in a value: runs of characters valid in DNS names
+fn hosts(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '.'))
+        .map(|token| token.trim_matches('.'))
+        .filter(|token| !token.is_empty() && !token.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(namespace: &str, name: &str) -> ServiceInfo {
+        ServiceInfo {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            ports: Vec::new(),
+            cluster_ip: None,
+            service_type: "ClusterIP".to_string(),
+            selector: None,
+            created_at: None,
+            annotations: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_service_references() {
+        let spec: PodSpec = serde_json::from_value(serde_json::json!({
+            "containers": [{
+                "name": "api",
+                "env": [
+                    {"name": "DATABASE_URL", "value": "postgres://app@db.data.svc.cluster.local:5432/app"},
+                    {"name": "CACHE_HOST", "value": "redis"},
+                    {"name": "QUEUE", "value": "rabbit.queue:5672"},
+                    {"name": "MODE", "value": "search"},
+                    {"name": "UPSTREAM", "value": "missing.other"},
+                    {"name": "PASSWORD", "valueFrom": {"secretKeyRef": {"name": "db", "key": "pw"}}},
+                ],
+            }],
+        }))
+        .unwrap();
+        let services = [
+            service("data", "db"),
+            service("shop", "redis"),
+            service("queue", "rabbit"),
+            service("shop", "search"),
+        ];
+
+        let references = service_references(&spec, "shop", &services);
+        let expected: BTreeSet<(String, String)> =
+            [("data", "db"), ("queue", "rabbit"), ("shop", "redis")]
+                .iter()
+                .map(|(ns, name)| (ns.to_string(), name.to_string()))
+                .collect();
+        // "search" is a bare name outside a URL or address variable
+        assert_eq!(references, expected);
+    }
+}
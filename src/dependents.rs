@@ -0,0 +1,280 @@
+//! Cluster objects that depend on a service without being part of it
+//!
+//! A ServiceMonitor (Prometheus operator) scrapes the services its selector
+//! matches in the namespaces its namespace selector allows, and an admission
+//! webhook sends API requests to the service named in its client config.
+//! Neither shows up in the service's own spec, yet both break when the
+//! service does: metrics go missing, or writes to the API server are rejected
+//! when the webhook fails closed.
+
+use crate::error::Result;
+use k8s_openapi::api::admissionregistration::v1::{
+    MutatingWebhookConfiguration, ServiceReference, ValidatingWebhookConfiguration,
+};
+use k8s_openapi::api::core::v1::Service;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams};
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A ServiceMonitor scraping the service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorRef {
+    pub namespace: String,
+    pub name: String,
+    /// Service ports scraped, by name or number
+    pub ports: Vec<String>,
+}
+
+/// An admission webhook calling the service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRef {
+    /// Validating or Mutating
+    pub kind: String,
+    pub configuration: String,
+    pub webhook: String,
+    pub path: Option<String>,
+    /// `Fail` rejects API requests while the service is down
+    pub failure_policy: String,
+}
+
+/// Whether `labels` satisfy a label selector given as JSON. A missing
+/// selector matches nothing; an empty one matches everything.
+pub fn selector_matches(selector: &Value, labels: &BTreeMap<String, String>) -> bool {
+    let Some(selector) = selector.as_object() else {
+        return false;
+    };
+    let match_labels = selector
+        .get("matchLabels")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .all(|(key, value)| labels.get(key).map(String::as_str) == value.as_str());
+    let match_expressions = selector
+        .get("matchExpressions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .all(|expression| {
+            let key = expression["key"].as_str().unwrap_or_default();
+            let values: Vec<&str> = expression["values"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            let value = labels.get(key).map(String::as_str);
+            match expression["operator"].as_str() {
+                Some("In") => value.is_some_and(|v| values.contains(&v)),
+                Some("NotIn") => value.is_none_or(|v| !values.contains(&v)),
+                Some("Exists") => value.is_some(),
+                Some("DoesNotExist") => value.is_none(),
+                _ => false,
+            }
+        });
+    match_labels && match_expressions
+}
+
+/// Whether the ServiceMonitor `monitor` scrapes the service in `namespace`
+/// with `labels`; a PodMonitor selects pods the same way
+pub fn monitor_selects(
+    monitor: &DynamicObject,
+    namespace: &str,
+    labels: &BTreeMap<String, String>,
+) -> bool {
+    let spec = &monitor.data["spec"];
+    let namespaces = &spec["namespaceSelector"];
+    let match_names: Vec<&str> = namespaces["matchNames"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let in_namespace = if namespaces["any"].as_bool() == Some(true) {
+        true
+    } else if !match_names.is_empty() {
+        match_names.contains(&namespace)
+    } else {
+        monitor.metadata.namespace.as_deref() == Some(namespace)
+    };
+    in_namespace && selector_matches(&spec["selector"], labels)
+}
+
+/// Prometheus operator monitors of `kind` (ServiceMonitor or PodMonitor) in
+/// every namespace; none when the operator is not installed
+pub async fn list_monitors(client: &Client, kind: &str) -> Result<Vec<DynamicObject>> {
+    let gvk = GroupVersionKind::gvk("monitoring.coreos.com", "v1", kind);
+    let plural = format!("{}s", kind.to_lowercase());
+    let resource = ApiResource::from_gvk_with_plural(&gvk, &plural);
+    let monitors: Api<DynamicObject> = Api::all_with(client.clone(), &resource);
+    match monitors.list(&ListParams::default()).await {
+        Ok(list) => Ok(list.items),
+        Err(kube::Error::Api(e)) if e.code == 404 => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// ServiceMonitors scraping the service `name` in `namespace`. Clusters
+/// without the Prometheus operator have none.
+pub async fn service_monitors(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<MonitorRef>> {
+    let services: Api<Service> = Api::namespaced(client.clone(), namespace);
+    let labels = services
+        .get_metadata(name)
+        .await?
+        .metadata
+        .labels
+        .unwrap_or_default();
+
+    let monitors = list_monitors(client, "ServiceMonitor").await?;
+    Ok(monitors
+        .iter()
+        .filter(|m| monitor_selects(m, namespace, &labels))
+        .map(|m| MonitorRef {
+            namespace: m.metadata.namespace.clone().unwrap_or_default(),
+            name: m.metadata.name.clone().unwrap_or_default(),
+            ports: m.data["spec"]["endpoints"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|e| match (&e["port"], &e["targetPort"]) {
+                    (Value::String(port), _) => Some(port.clone()),
+                    (_, Value::Number(port)) => Some(port.to_string()),
+                    (_, Value::String(port)) => Some(port.clone()),
+                    _ => None,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// The webhook named `webhook` of `configuration`, if it calls `service`
+/// in `namespace`
+fn webhook_ref(
+    kind: &str,
+    configuration: &ObjectMeta,
+    webhook: &str,
+    client_service: Option<&ServiceReference>,
+    failure_policy: Option<&String>,
+    (namespace, service): (&str, &str),
+) -> Option<WebhookRef> {
+    let client_service =
+        client_service.filter(|s| s.namespace == namespace && s.name == service)?;
+    Some(WebhookRef {
+        kind: kind.to_string(),
+        configuration: configuration.name.clone().unwrap_or_default(),
+        webhook: webhook.to_string(),
+        path: client_service.path.clone(),
+        // Fail is the API server default
+        failure_policy: failure_policy
+            .cloned()
+            .unwrap_or_else(|| "Fail".to_string()),
+    })
+}
+
+/// Admission webhooks whose client config points at the service `name` in
+/// `namespace`
+pub async fn service_webhooks(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<WebhookRef>> {
+    let params = ListParams::default();
+    let validating: Api<ValidatingWebhookConfiguration> = Api::all(client.clone());
+    let mutating: Api<MutatingWebhookConfiguration> = Api::all(client.clone());
+    let (validating, mutating) =
+        tokio::try_join!(validating.list(&params), mutating.list(&params))?;
+
+    let target = (namespace, name);
+    let validating = validating.items.iter().flat_map(|configuration| {
+        configuration
+            .webhooks
+            .iter()
+            .flatten()
+            .filter_map(move |w| {
+                webhook_ref(
+                    "Validating",
+                    &configuration.metadata,
+                    &w.name,
+                    w.client_config.service.as_ref(),
+                    w.failure_policy.as_ref(),
+                    target,
+                )
+            })
+    });
+    let mutating = mutating.items.iter().flat_map(|configuration| {
+        configuration
+            .webhooks
+            .iter()
+            .flatten()
+            .filter_map(move |w| {
+                webhook_ref(
+                    "Mutating",
+                    &configuration.metadata,
+                    &w.name,
+                    w.client_config.service.as_ref(),
+                    w.failure_policy.as_ref(),
+                    target,
+                )
+            })
+    });
+    Ok(validating.chain(mutating).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_selects() {
+        let monitor = |namespace: &str, spec: Value| -> DynamicObject {
+            serde_json::from_value(serde_json::json!({
+                "apiVersion": "monitoring.coreos.com/v1",
+                "kind": "ServiceMonitor",
+                "metadata": {"name": "web", "namespace": namespace},
+                "spec": spec,
+            }))
+            .unwrap()
+        };
+        let labels: BTreeMap<String, String> = [("app", "web"), ("tier", "frontend")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let same_namespace = monitor(
+            "shop",
+            serde_json::json!({"selector": {"matchLabels": {"app": "web"}}}),
+        );
+        assert!(monitor_selects(&same_namespace, "shop", &labels));
+        assert!(!monitor_selects(&same_namespace, "other", &labels));
+
+        let central = monitor(
+            "monitoring",
+            serde_json::json!({
+                "namespaceSelector": {"matchNames": ["shop"]},
+                "selector": {"matchExpressions": [
+                    {"key": "tier", "operator": "In", "values": ["frontend", "edge"]},
+                    {"key": "legacy", "operator": "DoesNotExist"},
+                ]},
+            }),
+        );
+        assert!(monitor_selects(&central, "shop", &labels));
+
+        let any = monitor(
+            "monitoring",
+            serde_json::json!({"namespaceSelector": {"any": true}, "selector": {"matchLabels": {"app": "api"}}}),
+        );
+        assert!(!monitor_selects(&any, "shop", &labels));
+        // Without a selector nothing is scraped
+        assert!(!monitor_selects(
+            &monitor("shop", serde_json::json!({})),
+            "shop",
+            &labels
+        ));
+    }
+}
@@ -0,0 +1,896 @@
+//! Detail views for kinds without a dedicated description
+//!
+//! Services, pods and StatefulSets have their own views. Every other kind,
+//! built-in or custom, is read as a dynamic object and described with the
+//! fields that matter for its kind, its status conditions, its ownership
+//! chain, what it manages, what uses it and its recent events. Kinds are
+//! matched by kind, plural or `plural.group` against the resources the API
+//! server serves, so custom resources need no registration. From a recorded
+//! snapshot, pods, workloads, ConfigMaps and Secrets are described with what
+//! the snapshot keeps of them.
+
+use crate::age::format_age;
+use crate::discovery::{
+    pod_workload, ConfigMapInfo, DiscoveryEngine, PodInfo, ResourceReference, SecretInfo,
+};
+use crate::error::{ExplorerError, Result};
+use crate::events::{self, EventInfo};
+use crate::hpa;
+use crate::kinds;
+use crate::owners::{ownership_chain_of, OwnershipChain};
+use crate::resource::ResourceKind;
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::discovery::Scope;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Annotations too long or too noisy to show
+const HIDDEN_ANNOTATIONS: &[&str] = &["kubectl.kubernetes.io/last-applied-configuration"];
+
+/// What `kdx describe <kind> <name>` describes
+#[derive(Debug, Clone)]
+pub enum DescribeKind {
+    /// A kind with its own view or listing support
+    Builtin(ResourceKind),
+    /// Any other kind the API server serves
+    Other(ApiResource, Scope),
+}
+
+impl DescribeKind {
+    /// Kubernetes kind name, such as `Deployment`
+    pub fn name(&self) -> &str {
+        match self {
+            DescribeKind::Builtin(kind) => kind.as_str(),
+            DescribeKind::Other(resource, _) => &resource.kind,
+        }
+    }
+}
+
+/// Resolve `kind` as a built-in kind, or else among the served resources
+/// (see [`kinds::find_resource`])
+pub async fn resolve_kind(discovery: &DiscoveryEngine, kind: &str) -> Result<DescribeKind> {
+    if let Ok(builtin) = kind.parse::<ResourceKind>() {
+        return Ok(DescribeKind::Builtin(builtin));
+    }
+    match kinds::builtin_kind(kind) {
+        Some("ConfigMap") => {
+            return Ok(DescribeKind::Other(
+                ApiResource::erase::<ConfigMap>(&()),
+                Scope::Namespaced,
+            ))
+        }
+        Some("Secret") => {
+            return Ok(DescribeKind::Other(
+                ApiResource::erase::<Secret>(&()),
+                Scope::Namespaced,
+            ))
+        }
+        _ => {}
+    }
+    let discovered = discovery.discover_listable_resources().await?;
+    kinds::find_resource(&discovered, kind)
+        .map(|(resource, scope)| DescribeKind::Other(resource, scope))
+        .ok_or_else(|| {
+            ExplorerError::InvalidArgument(format!("the server has no resource kind '{}'", kind))
+        })
+}
+
+/// A named value shown in a description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+}
+
+/// Any object with kind-specific fields and what surrounds it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectDescription {
+    pub kind: String,
+    pub api_version: String,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub age: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    /// Fields that matter for the kind, in display order
+    pub fields: Vec<Field>,
+    /// Status conditions as `Type=Status`, with the reason when not true
+    pub conditions: Vec<String>,
+    pub owners: Option<OwnershipChain>,
+    /// Objects it manages, as kind/name: pods of a workload, jobs of a CronJob
+    pub manages: Vec<String>,
+    /// Objects using it, as kind/name: services and autoscalers of a
+    /// workload, pods reading a ConfigMap or Secret
+    pub used_by: Vec<String>,
+    pub events: Vec<EventInfo>,
+}
+
+fn field(name: &str, value: impl Into<String>) -> Field {
+    Field {
+        name: name.to_string(),
+        value: value.into(),
+    }
+}
+
+fn number(value: &Value) -> i64 {
+    value.as_i64().unwrap_or(0)
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn joined(values: impl IntoIterator<Item = String>) -> String {
+    let values: Vec<String> = values.into_iter().collect();
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+/// Images of the containers in a pod template
+fn template_images(template: &Value) -> String {
+    joined(
+        template["spec"]["containers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|c| Some(format!("{}={}", c["name"].as_str()?, c["image"].as_str()?))),
+    )
+}
+
+fn label_selector(selector: &Value) -> String {
+    joined(
+        selector["matchLabels"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| format!("{}={}", k, text(v))),
+    )
+}
+
+/// Data keys of a ConfigMap or Secret, never their values
+fn data_keys(object: &Value) -> String {
+    joined(
+        ["data", "binaryData", "stringData"]
+            .iter()
+            .filter_map(|key| object[*key].as_object())
+            .flat_map(|data| data.keys().cloned()),
+    )
+}
+
+/// Fields that matter for `kind`, read from `object`
+pub fn kind_fields(kind: &str, object: &Value) -> Vec<Field> {
+    let spec = &object["spec"];
+    let status = &object["status"];
+    match kind {
+        "Deployment" => vec![
+            field(
+                "Replicas",
+                format!(
+                    "{}/{} ready, {} updated, {} available",
+                    number(&status["readyReplicas"]),
+                    number(&spec["replicas"]),
+                    number(&status["updatedReplicas"]),
+                    number(&status["availableReplicas"])
+                ),
+            ),
+            field("Strategy", text(&spec["strategy"]["type"])),
+            field("Selector", label_selector(&spec["selector"])),
+            field("Images", template_images(&spec["template"])),
+            field(
+                "Revision",
+                text(&object["metadata"]["annotations"]["deployment.kubernetes.io/revision"]),
+            ),
+        ],
+        "DaemonSet" => vec![
+            field(
+                "Nodes",
+                format!(
+                    "{}/{} ready, {} updated, {} available",
+                    number(&status["numberReady"]),
+                    number(&status["desiredNumberScheduled"]),
+                    number(&status["updatedNumberScheduled"]),
+                    number(&status["numberAvailable"])
+                ),
+            ),
+            field("Update Strategy", text(&spec["updateStrategy"]["type"])),
+            field("Selector", label_selector(&spec["selector"])),
+            field("Images", template_images(&spec["template"])),
+        ],
+        "ReplicaSet" => vec![
+            field(
+                "Replicas",
+                format!(
+                    "{}/{} ready",
+                    number(&status["readyReplicas"]),
+                    number(&spec["replicas"])
+                ),
+            ),
+            field("Selector", label_selector(&spec["selector"])),
+            field("Images", template_images(&spec["template"])),
+        ],
+        "Job" => vec![
+            field(
+                "Completions",
+                format!(
+                    "{}/{}",
+                    number(&status["succeeded"]),
+                    spec["completions"].as_i64().unwrap_or(1)
+                ),
+            ),
+            field("Active", number(&status["active"]).to_string()),
+            field("Failed", number(&status["failed"]).to_string()),
+            field("Started", text(&status["startTime"])),
+            field("Completed", text(&status["completionTime"])),
+            field("Images", template_images(&spec["template"])),
+        ],
+        "CronJob" => vec![
+            field("Schedule", text(&spec["schedule"])),
+            field(
+                "Suspended",
+                spec["suspend"].as_bool().unwrap_or(false).to_string(),
+            ),
+            field("Concurrency", text(&spec["concurrencyPolicy"])),
+            field("Last Schedule", text(&status["lastScheduleTime"])),
+            field("Last Success", text(&status["lastSuccessfulTime"])),
+            field(
+                "Images",
+                template_images(&spec["jobTemplate"]["spec"]["template"]),
+            ),
+        ],
+        "ConfigMap" => vec![
+            field("Data Keys", data_keys(object)),
+            field(
+                "Immutable",
+                object["immutable"].as_bool().unwrap_or(false).to_string(),
+            ),
+        ],
+        "Secret" => vec![
+            field("Type", text(&object["type"])),
+            field("Data Keys", data_keys(object)),
+        ],
+        // Custom resources: the top-level spec and status fields
+        _ => {
+            let keys = |value: &Value| {
+                joined(
+                    value
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(k, _)| k.clone()),
+                )
+            };
+            let mut fields = Vec::new();
+            if spec.is_object() {
+                fields.push(field("Spec", keys(spec)));
+            }
+            for key in ["phase", "state", "message"] {
+                if let Some(value) = status[key].as_str() {
+                    fields.push(field(&format!("Status {}", key), value));
+                }
+            }
+            fields
+        }
+    }
+}
+
+/// Status conditions as `Type=Status`, with the reason when not true
+pub fn conditions(object: &Value) -> Vec<String> {
+    object["status"]["conditions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| {
+            let kind = c["type"].as_str()?;
+            let status = c["status"].as_str().unwrap_or("Unknown");
+            Some(match c["reason"].as_str() {
+                Some(reason) if status != "True" => format!("{}={} ({})", kind, status, reason),
+                _ => format!("{}={}", kind, status),
+            })
+        })
+        .collect()
+}
+
+/// Pods and jobs managed by a workload, and the services and autoscalers
+/// using it
+async fn workload_relations(
+    discovery: &DiscoveryEngine,
+    kind: &str,
+    name: &str,
+    namespace: &str,
+    object: &Value,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut manages = Vec::new();
+    let mut used_by = Vec::new();
+
+    if kind == "CronJob" {
+        let jobs: Api<Job> = Api::namespaced(discovery.client().clone(), namespace);
+        for job in jobs.list_metadata(&ListParams::default()).await?.items {
+            let owned = job
+                .metadata
+                .owner_references
+                .iter()
+                .flatten()
+                .any(|o| o.kind == "CronJob" && o.name == name);
+            if owned {
+                manages.push(format!("job/{}", job.metadata.name.unwrap_or_default()));
+            }
+        }
+        return Ok((manages, used_by));
+    }
+
+    let pods = discovery.list_pods(Some(namespace), None).await?;
+    for pod in &pods {
+        let (workload_kind, workload_name) = pod_workload(pod);
+        let owned = (workload_kind == kind && workload_name == name)
+            || (pod.owner_kind.as_deref() == Some(kind) && pod.owner_name.as_deref() == Some(name));
+        if owned {
+            manages.push(format!("pod/{}", pod.name));
+        }
+    }
+
+    // Services whose selector matches the pod template
+    let template_labels: BTreeMap<String, String> = object["spec"]["template"]["metadata"]
+        ["labels"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(k, v)| (k.clone(), text(v)))
+        .collect();
+    for service in discovery.list_services(Some(namespace)).await? {
+        let selects = service.selector.as_ref().is_some_and(|selector| {
+            !selector.is_empty()
+                && selector
+                    .iter()
+                    .all(|(k, v)| template_labels.get(k) == Some(v))
+        });
+        if selects {
+            used_by.push(format!("service/{}", service.name));
+        }
+    }
+    match hpa::list_autoscalers(discovery.client(), Some(namespace)).await {
+        Ok(hpas) => used_by.extend(
+            hpas.iter()
+                .filter(|h| h.scales(kind, name))
+                .map(|h| format!("horizontalpodautoscaler/{}", h.name)),
+        ),
+        Err(ExplorerError::Kubernetes(kube::Error::Api(e))) if (403..=405).contains(&e.code) => {}
+        Err(e) => return Err(e),
+    }
+    Ok((manages, used_by))
+}
+
+/// Pods reading a ConfigMap or Secret, and how
+async fn config_consumers(
+    discovery: &DiscoveryEngine,
+    kind: &str,
+    name: &str,
+    namespace: &str,
+) -> Result<Vec<String>> {
+    let index = discovery.pod_index(Some(namespace)).await?;
+    let used_by = if kind == "ConfigMap" {
+        let mut infos = vec![ConfigMapInfo {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            data_keys: Vec::new(),
+            age: String::new(),
+            labels: BTreeMap::new(),
+            used_by: Vec::new(),
+            mount_paths: Vec::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }];
+        index.attach_configmaps(&mut infos);
+        infos.remove(0).used_by
+    } else {
+        let mut infos = vec![SecretInfo {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            secret_type: String::new(),
+            data_keys: Vec::new(),
+            age: String::new(),
+            labels: BTreeMap::new(),
+            used_by: Vec::new(),
+            mount_paths: Vec::new(),
+            service_account: None,
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }];
+        index.attach_secrets(&mut infos);
+        infos.remove(0).used_by
+    };
+    Ok(consumer_names(&used_by))
+}
+
+/// Pods reading a ConfigMap or Secret as kind/name with how they read it
+fn consumer_names(used_by: &[ResourceReference]) -> Vec<String> {
+    used_by
+        .iter()
+        .map(|r| {
+            format!(
+                "{}/{} ({:?})",
+                r.kind.to_lowercase(),
+                r.name,
+                r.reference_type
+            )
+        })
+        .collect()
+}
+
+/// Describe the object of `resource` named `name`, in `namespace` unless
+/// the kind is cluster-scoped
+pub async fn describe_object(
+    discovery: &DiscoveryEngine,
+    resource: &ApiResource,
+    scope: &Scope,
+    name: &str,
+    namespace: &str,
+) -> Result<ObjectDescription> {
+    let client = discovery.client();
+    let api: Api<DynamicObject> = match scope {
+        Scope::Namespaced => Api::namespaced_with(client.clone(), namespace, resource),
+        Scope::Cluster => Api::all_with(client.clone(), resource),
+    };
+    let object = api
+        .get_opt(name)
+        .await?
+        .ok_or_else(|| ExplorerError::ResourceNotFound {
+            kind: resource.kind.clone(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+        })?;
+    let metadata = object.metadata.clone();
+    let value = serde_json::to_value(&object)?;
+    let kind = resource.kind.as_str();
+
+    // Owners, relations and events are extra context; an object without
+    // them still describes
+    let owners = match scope {
+        Scope::Namespaced => ownership_chain_of(client, resource, name, namespace)
+            .await
+            .ok(),
+        Scope::Cluster => None,
+    };
+    let (manages, used_by) = match kind {
+        "Deployment" | "DaemonSet" | "ReplicaSet" | "Job" | "CronJob" => {
+            workload_relations(discovery, kind, name, namespace, &value).await?
+        }
+        "ConfigMap" | "Secret" => (
+            Vec::new(),
+            config_consumers(discovery, kind, name, namespace).await?,
+        ),
+        _ => (Vec::new(), Vec::new()),
+    };
+    let fields = format!("involvedObject.kind={},involvedObject.name={}", kind, name);
+    let event_namespace = match scope {
+        Scope::Namespaced => Some(namespace),
+        Scope::Cluster => None,
+    };
+    let events = events::list_events(client, event_namespace, Some(&fields))
+        .await
+        .map(|events| events::filter_events(events, None, None))
+        .unwrap_or_default();
+
+    Ok(ObjectDescription {
+        kind: resource.kind.clone(),
+        api_version: resource.api_version.clone(),
+        name: name.to_string(),
+        namespace: metadata.namespace,
+        age: format_age(metadata.creation_timestamp.map(|t| t.0)),
+        labels: metadata.labels.unwrap_or_default(),
+        annotations: metadata
+            .annotations
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(k, _)| !HIDDEN_ANNOTATIONS.contains(&k.as_str()))
+            .collect(),
+        fields: kind_fields(kind, &value),
+        conditions: conditions(&value),
+        owners,
+        manages,
+        used_by,
+        events,
+    })
+}
+
+fn pairs(map: &BTreeMap<String, String>) -> String {
+    joined(map.iter().map(|(k, v)| format!("{}={}", k, v)))
+}
+
+/// Describe the `kind` named `name` from the snapshot queries are answered
+/// from (`--as-of`, `kdx snapshot load`). A snapshot keeps no conditions,
+/// ownership chains or events, so those stay empty.
+pub fn describe_recorded(
+    discovery: &DiscoveryEngine,
+    kind: &DescribeKind,
+    name: &str,
+    namespace: &str,
+) -> Result<ObjectDescription> {
+    let snapshot = discovery.recorded().ok_or_else(|| {
+        ExplorerError::InvalidArgument("no snapshot is being answered from".to_string())
+    })?;
+    let not_found = || ExplorerError::ResourceNotFound {
+        kind: kind.name().to_string(),
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+    };
+    let pods = snapshot.pods(Some(namespace), None)?;
+    let services = snapshot.services(Some(namespace), None)?;
+
+    // Pods of a workload, and the services selecting any of them
+    let managed = |kind: &str| -> Vec<&PodInfo> {
+        pods.iter()
+            .filter(|pod| pod_workload(pod) == (kind.to_string(), name.to_string()))
+            .collect()
+    };
+    let selecting = |selected: &[&PodInfo]| -> Vec<String> {
+        services
+            .iter()
+            .filter(|s| {
+                s.selector.as_ref().is_some_and(|selector| {
+                    !selector.is_empty()
+                        && selected
+                            .iter()
+                            .any(|pod| selector.iter().all(|(k, v)| pod.labels.get(k) == Some(v)))
+                })
+            })
+            .map(|s| format!("service/{}", s.name))
+            .collect()
+    };
+    let pod_names = |pods: &[&PodInfo]| -> Vec<String> {
+        pods.iter().map(|p| format!("pod/{}", p.name)).collect()
+    };
+
+    match kind.name() {
+        "Pod" => {
+            let pod = pods.iter().find(|p| p.name == name).ok_or_else(not_found)?;
+            let owner = match (&pod.owner_kind, &pod.owner_name) {
+                (Some(kind), Some(owner)) => format!("{}/{}", kind.to_lowercase(), owner),
+                _ => "-".to_string(),
+            };
+            let fields = vec![
+                field("Phase", pod.phase.clone()),
+                field(
+                    "Node",
+                    pod.node_name.clone().unwrap_or_else(|| "-".to_string()),
+                ),
+                field("IP", pod.pod_ip.clone().unwrap_or_else(|| "-".to_string())),
+                field(
+                    "Containers",
+                    format!("{}/{} ready", pod.ready_containers, pod.total_containers),
+                ),
+                field("Restarts", pod.restart_count.to_string()),
+                field("QoS Class", pod.qos_class.clone()),
+                field(
+                    "Priority Class",
+                    pod.priority_class
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                field("Owner", owner),
+            ];
+            Ok(recorded(
+                kind,
+                "v1",
+                name,
+                namespace,
+                &pod.age,
+                &pod.labels,
+                &pod.annotations,
+                fields,
+                Vec::new(),
+                selecting(&[pod]),
+            ))
+        }
+        "Deployment" => {
+            let deployment = snapshot
+                .deployments(Some(namespace))
+                .into_iter()
+                .find(|d| d.name == name)
+                .ok_or_else(not_found)?;
+            let fields = vec![
+                field(
+                    "Replicas",
+                    format!(
+                        "{}/{} ready, {} available",
+                        deployment.ready_replicas,
+                        deployment.replicas,
+                        deployment.available_replicas
+                    ),
+                ),
+                field("Strategy", deployment.strategy.clone()),
+                field("Selector", pairs(&deployment.selector)),
+                field("Images", pairs(&deployment.images)),
+            ];
+            let pods = managed("Deployment");
+            Ok(recorded(
+                kind,
+                "apps/v1",
+                name,
+                namespace,
+                &deployment.age,
+                &deployment.labels,
+                &deployment.annotations,
+                fields,
+                pod_names(&pods),
+                selecting(&pods),
+            ))
+        }
+        "StatefulSet" => {
+            let statefulset = snapshot
+                .statefulsets(Some(namespace))
+                .into_iter()
+                .find(|s| s.name == name)
+                .ok_or_else(not_found)?;
+            let fields = vec![
+                field(
+                    "Replicas",
+                    format!(
+                        "{}/{} ready, {} current",
+                        statefulset.ready_replicas,
+                        statefulset.replicas,
+                        statefulset.current_replicas
+                    ),
+                ),
+                field("Selector", pairs(&statefulset.selector)),
+                field("Images", pairs(&statefulset.images)),
+                field(
+                    "Volume Claim Templates",
+                    joined(statefulset.volume_claim_templates.clone()),
+                ),
+            ];
+            let pods = managed("StatefulSet");
+            Ok(recorded(
+                kind,
+                "apps/v1",
+                name,
+                namespace,
+                &statefulset.age,
+                &statefulset.labels,
+                &statefulset.annotations,
+                fields,
+                pod_names(&pods),
+                selecting(&pods),
+            ))
+        }
+        "DaemonSet" => {
+            let daemonset = snapshot
+                .daemonsets(Some(namespace))
+                .into_iter()
+                .find(|d| d.name == name)
+                .ok_or_else(not_found)?;
+            let fields = vec![
+                field(
+                    "Nodes",
+                    format!(
+                        "{}/{} ready, {} updated",
+                        daemonset.ready, daemonset.desired, daemonset.up_to_date
+                    ),
+                ),
+                field("Selector", pairs(&daemonset.selector)),
+                field("Images", pairs(&daemonset.images)),
+            ];
+            let pods = managed("DaemonSet");
+            Ok(recorded(
+                kind,
+                "apps/v1",
+                name,
+                namespace,
+                &daemonset.age,
+                &daemonset.labels,
+                &daemonset.annotations,
+                fields,
+                pod_names(&pods),
+                selecting(&pods),
+            ))
+        }
+        "ConfigMap" => {
+            let configmap = snapshot
+                .configmaps(Some(namespace))
+                .into_iter()
+                .find(|c| c.name == name)
+                .ok_or_else(not_found)?;
+            let fields = vec![field("Data Keys", joined(configmap.data_keys.clone()))];
+            Ok(recorded(
+                kind,
+                "v1",
+                name,
+                namespace,
+                &configmap.age,
+                &configmap.labels,
+                &configmap.annotations,
+                fields,
+                Vec::new(),
+                consumer_names(&configmap.used_by),
+            ))
+        }
+        "Secret" => {
+            let secret = snapshot
+                .secrets(Some(namespace))
+                .into_iter()
+                .find(|s| s.name == name)
+                .ok_or_else(not_found)?;
+            let fields = vec![
+                field("Type", secret.secret_type.clone()),
+                field("Data Keys", joined(secret.data_keys.clone())),
+            ];
+            Ok(recorded(
+                kind,
+                "v1",
+                name,
+                namespace,
+                &secret.age,
+                &secret.labels,
+                &secret.annotations,
+                fields,
+                Vec::new(),
+                consumer_names(&secret.used_by),
+            ))
+        }
+        other => Err(ExplorerError::InvalidArgument(format!(
+            "snapshots hold no {}; they describe services, pods, deployments, \
+             statefulsets, daemonsets, configmaps and secrets",
+            other
+        ))),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn recorded(
+    kind: &DescribeKind,
+    api_version: &str,
+    name: &str,
+    namespace: &str,
+    age: &str,
+    labels: &BTreeMap<String, String>,
+    annotations: &BTreeMap<String, String>,
+    fields: Vec<Field>,
+    manages: Vec<String>,
+    used_by: Vec<String>,
+) -> ObjectDescription {
+    ObjectDescription {
+        kind: kind.name().to_string(),
+        api_version: api_version.to_string(),
+        name: name.to_string(),
+        namespace: Some(namespace.to_string()),
+        age: age.to_string(),
+        labels: labels.clone(),
+        annotations: annotations.clone(),
+        fields,
+        conditions: Vec::new(),
+        owners: None,
+        manages,
+        used_by,
+        events: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_fields() {
+        let deployment = serde_json::json!({
+            "metadata": {"annotations": {"deployment.kubernetes.io/revision": "4"}},
+            "spec": {
+                "replicas": 3,
+                "strategy": {"type": "RollingUpdate"},
+                "selector": {"matchLabels": {"app": "web"}},
+                "template": {"spec": {"containers": [{"name": "web", "image": "nginx:1.25"}]}},
+            },
+            "status": {
+                "readyReplicas": 2, "updatedReplicas": 3, "availableReplicas": 2,
+                "conditions": [
+                    {"type": "Available", "status": "False", "reason": "MinimumReplicasUnavailable"},
+                    {"type": "Progressing", "status": "True", "reason": "NewReplicaSetAvailable"},
+                ],
+            },
+        });
+        let fields = kind_fields("Deployment", &deployment);
+        assert_eq!(
+            fields[0].value,
+            "2/3 ready, 3 updated, 2 available".to_string()
+        );
+        assert_eq!(fields[3], field("Images", "web=nginx:1.25"));
+        assert_eq!(fields[4].value, "4");
+        assert_eq!(
+            conditions(&deployment),
+            [
+                "Available=False (MinimumReplicasUnavailable)",
+                "Progressing=True"
+            ]
+        );
+
+        let secret = serde_json::json!({
+            "type": "kubernetes.io/tls",
+            "data": {"tls.crt": "c2VjcmV0", "tls.key": "c2VjcmV0"},
+        });
+        assert_eq!(
+            kind_fields("Secret", &secret)[1],
+            field("Data Keys", "tls.crt, tls.key")
+        );
+
+        let certificate = ApiResource::from_gvk_with_plural(
+            &kube::api::GroupVersionKind::gvk("cert-manager.io", "v1", "Certificate"),
+            "certificates",
+        );
+        let discovered = crate::discovery::ResourceDiscovery {
+            resources: vec![(certificate, Scope::Namespaced)],
+            ..Default::default()
+        };
+        assert!(kinds::find_resource(&discovered, "certificate").is_some());
+        assert!(kinds::find_resource(&discovered, "certificates.cert-manager.io").is_some());
+        assert!(kinds::find_resource(&discovered, "issuer").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_describe_recorded() {
+        use crate::discovery::{DeploymentInfo, ServiceInfo};
+        use crate::history::ClusterSnapshot;
+
+        let app = BTreeMap::from([("app".to_string(), "web".to_string())]);
+        let snapshot = ClusterSnapshot {
+            taken_at: chrono::Utc::now(),
+            context: None,
+            namespaces: vec!["shop".to_string()],
+            services: vec![ServiceInfo {
+                name: "web".to_string(),
+                namespace: "shop".to_string(),
+                ports: Vec::new(),
+                cluster_ip: None,
+                service_type: "ClusterIP".to_string(),
+                selector: Some(app.clone()),
+                created_at: None,
+                annotations: BTreeMap::new(),
+            }],
+            service_labels: BTreeMap::new(),
+            pods: vec![PodInfo {
+                labels: BTreeMap::from([
+                    ("app".to_string(), "web".to_string()),
+                    ("pod-template-hash".to_string(), "5d4".to_string()),
+                ]),
+                owner_kind: Some("ReplicaSet".to_string()),
+                owner_name: Some("web-5d4".to_string()),
+                ..PodInfo::fixture("shop", "web-5d4-x2k")
+            }],
+            deployments: vec![DeploymentInfo {
+                selector: app,
+                ..DeploymentInfo::fixture("shop", "web", 1)
+            }],
+            statefulsets: Vec::new(),
+            daemonsets: Vec::new(),
+            configmaps: Vec::new(),
+            secrets: Vec::new(),
+        };
+        let discovery = DiscoveryEngine::builder()
+            .snapshot(snapshot)
+            .build()
+            .await
+            .unwrap();
+
+        let deployment = DescribeKind::Builtin(ResourceKind::Deployment);
+        let description = describe_recorded(&discovery, &deployment, "web", "shop").unwrap();
+        assert_eq!(description.fields[0].value, "1/1 ready, 1 available");
+        assert_eq!(description.manages, ["pod/web-5d4-x2k"]);
+        assert_eq!(description.used_by, ["service/web"]);
+
+        let pod = DescribeKind::Builtin(ResourceKind::Pod);
+        let description = describe_recorded(&discovery, &pod, "web-5d4-x2k", "shop").unwrap();
+        assert_eq!(description.used_by, ["service/web"]);
+
+        assert!(describe_recorded(&discovery, &deployment, "api", "shop").is_err());
+        let job = DescribeKind::Builtin(ResourceKind::Job);
+        assert!(describe_recorded(&discovery, &job, "web", "shop").is_err());
+    }
+}
@@ -0,0 +1,420 @@
+//! Diffing two namespaces or two saved snapshots
+//!
+//! Either side of `kdx diff` is a live namespace or a file written by `kdx
+//! snapshot save`. Both are reduced to the same per-object fingerprint built
+//! from the info structs, so a namespace can be compared with its own state
+//! from last week as easily as with its staging counterpart.
+
+use crate::discovery::{AllResources, DiscoveryEngine};
+use crate::error::{ExplorerError, Result};
+use crate::snapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// One side of a diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffSide {
+    Namespace(String),
+    Snapshot(PathBuf),
+}
+
+impl DiffSide {
+    /// `file:PATH` is always a snapshot file. Namespace names cannot contain
+    /// dots or slashes, so anything else that does is one too, as is the
+    /// name of an existing file.
+    pub fn parse(value: &str) -> Self {
+        if let Some(path) = value.strip_prefix("file:") {
+            DiffSide::Snapshot(PathBuf::from(path))
+        } else if value.contains(['.', '/']) || Path::new(value).is_file() {
+            DiffSide::Snapshot(PathBuf::from(value))
+        } else {
+            DiffSide::Namespace(value.to_string())
+        }
+    }
+
+    pub fn is_snapshot(&self) -> bool {
+        matches!(self, DiffSide::Snapshot(_))
+    }
+}
+
+impl fmt::Display for DiffSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffSide::Namespace(ns) => write!(f, "namespace {}", ns),
+            DiffSide::Snapshot(path) => write!(f, "snapshot {}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added => write!(f, "added"),
+            Change::Removed => write!(f, "removed"),
+            Change::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// An object that exists on one side only, or one field that differs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceChange {
+    pub change: Change,
+    pub kind: String,
+    /// `namespace/name` when whole snapshots are compared
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceDiff {
+    pub from: String,
+    pub to: String,
+    pub changes: Vec<ResourceChange>,
+}
+
+impl ResourceDiff {
+    pub fn count(&self, change: Change) -> usize {
+        self.changes.iter().filter(|c| c.change == change).count()
+    }
+}
+
+/// The compared state of a single object
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fingerprint {
+    /// Scalar fields such as `replicas` and `label[app]`
+    pub fields: BTreeMap<String, String>,
+    /// Image per container; empty in snapshots saved before images were
+    /// recorded, in which case images are not compared
+    pub images: BTreeMap<String, String>,
+    /// Data keys of ConfigMaps and Secrets
+    pub keys: BTreeSet<String>,
+}
+
+/// Fingerprints keyed by (kind, name)
+pub type Inventory = BTreeMap<(String, String), Fingerprint>;
+
+/// Build the inventory of `resources`; pods are left out since their names
+/// change on every rollout. With `qualify`, names carry their namespace.
+pub fn inventory(resources: &AllResources, qualify: bool) -> Inventory {
+    let mut inventory = Inventory::new();
+    let mut add = |kind: &str, namespace: &str, name: &str, print: Fingerprint| {
+        let name = if qualify {
+            format!("{}/{}", namespace, name)
+        } else {
+            name.to_string()
+        };
+        inventory.insert((kind.to_string(), name), print);
+    };
+    let labelled = |labels: &BTreeMap<String, String>| Fingerprint {
+        fields: labels
+            .iter()
+            .map(|(k, v)| (format!("label[{}]", k), v.clone()))
+            .collect(),
+        ..Default::default()
+    };
+    let joined = |map: &BTreeMap<String, String>| {
+        map.iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    for svc in &resources.services {
+        let mut print = Fingerprint::default();
+        print
+            .fields
+            .insert("type".to_string(), svc.service_type.clone());
+        let ports: Vec<String> = svc
+            .ports
+            .iter()
+            .map(|p| format!("{}/{}", p.port, p.protocol))
+            .collect();
+        print.fields.insert("ports".to_string(), ports.join(","));
+        if let Some(selector) = &svc.selector {
+            print
+                .fields
+                .insert("selector".to_string(), joined(selector));
+        }
+        add("Service", &svc.namespace, &svc.name, print);
+    }
+    for d in &resources.deployments {
+        let mut print = labelled(&d.labels);
+        print
+            .fields
+            .insert("replicas".to_string(), d.replicas.to_string());
+        print.images = d.images.clone();
+        add("Deployment", &d.namespace, &d.name, print);
+    }
+    for s in &resources.statefulsets {
+        let mut print = labelled(&s.labels);
+        print
+            .fields
+            .insert("replicas".to_string(), s.replicas.to_string());
+        print.images = s.images.clone();
+        add("StatefulSet", &s.namespace, &s.name, print);
+    }
+    for d in &resources.daemonsets {
+        let mut print = labelled(&d.labels);
+        print.images = d.images.clone();
+        add("DaemonSet", &d.namespace, &d.name, print);
+    }
+    for cm in &resources.configmaps {
+        let mut print = labelled(&cm.labels);
+        print.keys = cm.data_keys.iter().cloned().collect();
+        add("ConfigMap", &cm.namespace, &cm.name, print);
+    }
+    for secret in &resources.secrets {
+        let mut print = labelled(&secret.labels);
+        print
+            .fields
+            .insert("type".to_string(), secret.secret_type.clone());
+        print.keys = secret.data_keys.iter().cloned().collect();
+        add("Secret", &secret.namespace, &secret.name, print);
+    }
+
+    inventory
+}
+
+/// Compare two inventories; objects on one side only are reported once
+/// rather than field by field
+pub fn diff_inventories(from: &Inventory, to: &Inventory) -> Vec<ResourceChange> {
+    let mut changes = Vec::new();
+
+    for key in union(from.keys(), to.keys()) {
+        let (kind, name) = key;
+        let change = |change: Change, field: Option<String>, a: Option<&str>, b: Option<&str>| {
+            ResourceChange {
+                change,
+                kind: kind.clone(),
+                name: name.clone(),
+                field,
+                from: a.map(str::to_string),
+                to: b.map(str::to_string),
+            }
+        };
+
+        let (left, right) = match (from.get(key), to.get(key)) {
+            (Some(left), Some(right)) => (left, right),
+            (Some(_), None) => {
+                changes.push(change(Change::Removed, None, None, None));
+                continue;
+            }
+            (None, _) => {
+                changes.push(change(Change::Added, None, None, None));
+                continue;
+            }
+        };
+
+        for field in union(left.fields.keys(), right.fields.keys()) {
+            let (a, b) = (left.fields.get(field), right.fields.get(field));
+            if a != b {
+                changes.push(change(
+                    Change::Changed,
+                    Some(field.clone()),
+                    a.map(String::as_str),
+                    b.map(String::as_str),
+                ));
+            }
+        }
+        if !left.images.is_empty() && !right.images.is_empty() {
+            for container in union(left.images.keys(), right.images.keys()) {
+                let (a, b) = (left.images.get(container), right.images.get(container));
+                if a != b {
+                    changes.push(change(
+                        Change::Changed,
+                        Some(format!("image[{}]", container)),
+                        a.map(String::as_str),
+                        b.map(String::as_str),
+                    ));
+                }
+            }
+        }
+        if left.keys != right.keys {
+            let only = |x: &BTreeSet<String>, y: &BTreeSet<String>| {
+                let keys: Vec<&str> = x.difference(y).map(String::as_str).collect();
+                (!keys.is_empty()).then(|| keys.join(", "))
+            };
+            changes.push(change(
+                Change::Changed,
+                Some("keys".to_string()),
+                only(&left.keys, &right.keys).as_deref(),
+                only(&right.keys, &left.keys).as_deref(),
+            ));
+        }
+    }
+
+    changes
+}
+
+/// Diff two sides. Live namespaces need `discovery`; a snapshot side is
+/// narrowed to `namespace`, or else to the namespace on the other side, and
+/// two whole snapshots are compared across all their namespaces.
+pub async fn diff_sides(
+    discovery: Option<&DiscoveryEngine>,
+    from: &DiffSide,
+    to: &DiffSide,
+    namespace: Option<&str>,
+    page_size: usize,
+) -> Result<ResourceDiff> {
+    let other_ns = |side: &DiffSide| match side {
+        DiffSide::Namespace(ns) => Some(ns.clone()),
+        DiffSide::Snapshot(_) => None,
+    };
+    let from_ns = namespace.map(str::to_string).or_else(|| other_ns(to));
+    let to_ns = namespace.map(str::to_string).or_else(|| other_ns(from));
+    let qualify = from_ns.is_none() && to_ns.is_none();
+
+    let (a, b) = tokio::try_join!(
+        resources(discovery, from, from_ns.as_deref(), page_size),
+        resources(discovery, to, to_ns.as_deref(), page_size)
+    )?;
+
+    Ok(ResourceDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        changes: diff_inventories(&inventory(&a, qualify), &inventory(&b, qualify)),
+    })
+}
+
+async fn resources(
+    discovery: Option<&DiscoveryEngine>,
+    side: &DiffSide,
+    namespace: Option<&str>,
+    page_size: usize,
+) -> Result<AllResources> {
+    match side {
+        DiffSide::Namespace(ns) => {
+            let discovery = discovery.ok_or_else(|| {
+                ExplorerError::InvalidArgument(format!(
+                    "namespace {} needs a cluster connection",
+                    ns
+                ))
+            })?;
+            discovery
+                .list_all(Some(ns), None, page_size, |kind| kind != "Pod")
+                .await
+        }
+        DiffSide::Snapshot(path) => {
+            let snapshot = snapshot::read(path)?;
+            Ok(AllResources {
+                services: snapshot.services(namespace, None)?,
+                deployments: snapshot.deployments(namespace),
+                statefulsets: snapshot.statefulsets(namespace),
+                daemonsets: snapshot.daemonsets(namespace),
+                pods: Vec::new(),
+                configmaps: snapshot.configmaps(namespace),
+                secrets: snapshot.secrets(namespace),
+            })
+        }
+    }
+}
+
+fn union<'a, T: Ord + 'a>(
+    a: impl Iterator<Item = &'a T>,
+    b: impl Iterator<Item = &'a T>,
+) -> BTreeSet<&'a T> {
+    a.chain(b).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{ConfigMapInfo, DeploymentInfo};
+
+    fn deployment(namespace: &str, replicas: i32, image: &str) -> DeploymentInfo {
+        DeploymentInfo {
+            labels: BTreeMap::from([("app".to_string(), "api".to_string())]),
+            images: BTreeMap::from([("api".to_string(), image.to_string())]),
+            ..DeploymentInfo::fixture(namespace, "api", replicas)
+        }
+    }
+
+    fn configmap(namespace: &str, name: &str, keys: &[&str]) -> ConfigMapInfo {
+        ConfigMapInfo {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            data_keys: keys.iter().map(|k| k.to_string()).collect(),
+            age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            labels: BTreeMap::new(),
+            used_by: Vec::new(),
+            mount_paths: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_diff_namespaces() {
+        let staging = AllResources {
+            deployments: vec![deployment("staging", 1, "api:1.5")],
+            configmaps: vec![
+                configmap("staging", "app", &["LOG_LEVEL", "FEATURE_X"]),
+                configmap("staging", "canary", &["WEIGHT"]),
+            ],
+            ..Default::default()
+        };
+        let mut prod = AllResources {
+            deployments: vec![deployment("prod", 3, "api:1.4")],
+            configmaps: vec![
+                configmap("prod", "app", &["LOG_LEVEL", "REGION"]),
+                configmap("prod", "legacy", &["URL"]),
+            ],
+            ..Default::default()
+        };
+        prod.deployments[0]
+            .labels
+            .insert("tier".to_string(), "backend".to_string());
+
+        let changes = diff_inventories(&inventory(&staging, false), &inventory(&prod, false));
+        let summary: Vec<(Change, &str, Option<&str>)> = changes
+            .iter()
+            .map(|c| (c.change, c.name.as_str(), c.field.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Change::Changed, "app", Some("keys")),
+                (Change::Removed, "canary", None),
+                (Change::Added, "legacy", None),
+                (Change::Changed, "api", Some("label[tier]")),
+                (Change::Changed, "api", Some("replicas")),
+                (Change::Changed, "api", Some("image[api]")),
+            ]
+        );
+        assert_eq!(changes[0].from.as_deref(), Some("FEATURE_X"));
+        assert_eq!(changes[0].to.as_deref(), Some("REGION"));
+        assert_eq!(changes[4].to.as_deref(), Some("3"));
+
+        // Whole snapshots keep objects of different namespaces apart
+        let qualified = inventory(&prod, true);
+        assert!(qualified.contains_key(&("ConfigMap".to_string(), "prod/legacy".to_string())));
+
+        // Images missing on one side, as in older snapshots, are not compared
+        prod.deployments[0].images.clear();
+        let changes = diff_inventories(&inventory(&staging, false), &inventory(&prod, false));
+        assert!(changes
+            .iter()
+            .all(|c| c.field.as_deref() != Some("image[api]")));
+
+        assert_eq!(DiffSide::parse("prod"), DiffSide::Namespace("prod".into()));
+        assert!(DiffSide::parse("./prod.kdx.gz").is_snapshot());
+        assert_eq!(
+            DiffSide::parse("file:prod"),
+            DiffSide::Snapshot(PathBuf::from("prod"))
+        );
+    }
+}
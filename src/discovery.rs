@@ -1,18 +1,54 @@
 //! Kubernetes resource discovery and analysis
 
-use crate::cache::ResourceCache;
+use crate::age::{format_age, object_age};
+use crate::annotations::AnnotatedObject;
+use crate::cache::{CacheKind, ResourceCache};
+use crate::checkpoint::ScanCheckpoint;
+use crate::compare::{NamespaceInventory, WorkloadSummary};
+use crate::controllers::{self, ControllerCandidate, CrdController, CrdHints};
+use crate::dependents::{self, MonitorRef, WebhookRef};
+use crate::disk_cache::DiskCache;
+use crate::endpoints::{self, ServiceEndpoint};
+use crate::env::EnvSources;
+use crate::envelope::Warnings;
 use crate::error::{ExplorerError, Result};
-use crate::progress::ProgressTracker;
-use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
-use k8s_openapi::api::core::v1::{ConfigMap, Pod, Secret, Service};
+use crate::events::{self, EventInfo};
+use crate::filtering::FieldSelector;
+use crate::history::ClusterSnapshot;
+use crate::hpa::{self, HpaInfo};
+use crate::owners::{ownership_chain, OwnershipChain};
+use crate::pod_detail::{container_details, ContainerDetail};
+use crate::pod_index::PodIndex;
+use crate::progress::{NamespaceFailure, ProgressTracker};
+use crate::quantity::parse_quantity;
+use crate::resource::{ObjectRef, ResourceKind};
+use crate::scope::NamespaceScope;
+use crate::session::ClientSession;
+use crate::statefulset::{self, StatefulSetOrdinal};
+use crate::stats::RunStats;
+use crate::storage::{self, ClaimMounts, PodStorage};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Node, PersistentVolume, PersistentVolumeClaim, Pod, PodSpec, PodStatus, Secret,
+    Service, ServiceAccount,
+};
 use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::api::rbac::v1::{
+    ClusterRole, ClusterRoleBinding, PolicyRule, Role, RoleBinding, Subject,
+};
 use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
 use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams};
+use kube::discovery::Scope;
 use kube::{Api, Client};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::task::JoinSet;
 
 /// Trait for lazy resource conversion to reduce memory usage
@@ -56,29 +92,285 @@ where
         }
     }
 }
+/// How long listed resources are served from the cache by default
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Annotation in which `kubectl apply` keeps the last applied manifest
+const LAST_APPLIED_ANNOTATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// Load the client configuration for a kubeconfig context, or infer it (the
+/// current context, or the in-cluster service account) when `context` is
+/// `None`
+pub async fn load_config(context: Option<&str>) -> Result<kube::Config> {
+    let config = match context {
+        Some(context) => kube::Config::from_kubeconfig(&kube::config::KubeConfigOptions {
+            context: Some(context.to_string()),
+            cluster: None,
+            user: None,
+        })
+        .await
+        .map_err(|e| ExplorerError::Config(e.to_string()))?,
+        None => kube::Config::infer()
+            .await
+            .map_err(|e| ExplorerError::Config(e.to_string()))?,
+    };
+    Ok(config)
+}
+
+/// Builder for a [`DiscoveryEngine`]
+///
+/// ```no_run
+/// use kdx::DiscoveryEngine;
+/// use std::time::Duration;
+///
+/// # async fn example() -> kdx::Result<()> {
+/// let discovery = DiscoveryEngine::builder()
+///     .context("staging")
+///     .cache_ttl(Duration::from_secs(30))
+///     .build()
+///     .await?;
+/// let deployments = discovery.list_deployments(Some("default")).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct DiscoveryEngineBuilder {
+    context: Option<String>,
+    client: Option<Client>,
+    cache_ttl: Option<Duration>,
+    resource_ttls: Vec<(CacheKind, Duration)>,
+    stats: Option<Arc<RunStats>>,
+    disk_cache: Option<DiskCache>,
+    scope: NamespaceScope,
+    pod_field_selector: Option<FieldSelector>,
+    snapshot: Option<ClusterSnapshot>,
+    warnings: Warnings,
+}
+
+impl DiscoveryEngineBuilder {
+    /// Connect through this kubeconfig context instead of the current one
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    /// Use an existing client; the context is then ignored
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// How long listed resources are served from the cache (default: 5 minutes)
+    pub fn cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = Some(cache_ttl);
+        self
+    }
+
+    /// How long listings of `kind` are served from the cache, overriding
+    /// the default TTL
+    pub fn resource_cache_ttl(mut self, kind: CacheKind, cache_ttl: Duration) -> Self {
+        self.resource_ttls.push((kind, cache_ttl));
+        self
+    }
+
+    /// Record API traffic, namespace counts and phase timings in `stats`.
+    /// Only requests of a client the builder creates are counted.
+    pub fn stats(mut self, stats: Arc<RunStats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Keep cached listings on disk too, so later runs can be answered
+    /// from them
+    pub fn disk_cache(mut self, disk_cache: DiskCache) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
+    /// Leave the namespaces `scope` excludes out of listings across
+    /// namespaces
+    pub fn namespace_scope(mut self, scope: NamespaceScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Send `selector` as the field selector of every pod listing
+    pub fn pod_field_selector(mut self, selector: FieldSelector) -> Self {
+        self.pod_field_selector = Some(selector);
+        self
+    }
+
+    /// Collect warnings about failed namespaces and cached listings in
+    /// `warnings`, for `--envelope`
+    pub fn warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Answer queries from `snapshot` instead of the API. Unless a client
+    /// is given, no kubeconfig is read and the cluster is never contacted.
+    pub fn snapshot(mut self, snapshot: ClusterSnapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    pub async fn build(self) -> Result<DiscoveryEngine> {
+        let stats = self.stats.unwrap_or_default();
+        let (client, session) = match self.client {
+            Some(client) => (client.clone(), ClientSession::fixed(client)),
+            None if self.snapshot.is_some() => {
+                // A client for an address that never resolves, so a query
+                // the snapshot cannot answer fails instead of reaching a cluster
+                let config =
+                    kube::Config::new("http://snapshot.invalid".parse().expect("valid URL"));
+                let client = Client::try_from(config)?;
+                (client.clone(), ClientSession::fixed(client))
+            }
+            None => {
+                let client = stats.client(load_config(self.context.as_deref()).await?)?;
+                let session =
+                    ClientSession::from_kubeconfig(client.clone(), self.context, stats.clone());
+                (client, session)
+            }
+        };
+
+        let mut cache = ResourceCache::new(self.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL));
+        for (kind, ttl) in self.resource_ttls {
+            cache = cache.with_ttl(kind, ttl);
+        }
+        if let Some(disk_cache) = self.disk_cache {
+            cache = cache.with_disk(disk_cache);
+        }
+        cache = cache.with_warnings(self.warnings.clone());
+
+        Ok(DiscoveryEngine {
+            client,
+            session: Arc::new(session),
+            cache: Arc::new(cache),
+            stats,
+            snapshot: self.snapshot.map(Arc::new),
+            scope: self.scope,
+            pod_field_selector: self.pod_field_selector,
+            warnings: self.warnings,
+        })
+    }
+}
+
 /// Main discovery engine for Kubernetes resources
 #[derive(Clone)]
 pub struct DiscoveryEngine {
     client: Client,
+    /// The client for long-running commands, rebuilt when its credentials
+    /// are rejected
+    session: Arc<ClientSession>,
     cache: Arc<ResourceCache>,
+    stats: Arc<RunStats>,
+    /// Recorded state answering queries instead of the live cluster
+    /// (`--as-of`, `kdx snapshot load`)
+    snapshot: Option<Arc<ClusterSnapshot>>,
+    /// Namespaces left out of listings across namespaces
+    scope: NamespaceScope,
+    /// Field selector sent with pod listings (`--field-selector`)
+    pod_field_selector: Option<FieldSelector>,
+    /// Warnings raised while answering, for `--envelope`
+    warnings: Warnings,
 }
 
 impl DiscoveryEngine {
     pub fn new(client: Client) -> Self {
         Self {
+            session: Arc::new(ClientSession::fixed(client.clone())),
             client,
-            cache: Arc::new(ResourceCache::new(Duration::from_secs(300))), // 5 minute default TTL
+            cache: Arc::new(ResourceCache::new(DEFAULT_CACHE_TTL)),
+            stats: Arc::default(),
+            snapshot: None,
+            scope: NamespaceScope::default(),
+            pod_field_selector: None,
+            warnings: Warnings::default(),
         }
     }
 
-    #[allow(dead_code)]
+    /// Configure an engine step by step, connecting through a kubeconfig
+    /// context unless a client is given
+    pub fn builder() -> DiscoveryEngineBuilder {
+        DiscoveryEngineBuilder::default()
+    }
+
     pub fn with_cache_ttl(client: Client, cache_ttl: Duration) -> Self {
         Self {
+            session: Arc::new(ClientSession::fixed(client.clone())),
             client,
             cache: Arc::new(ResourceCache::new(cache_ttl)),
+            stats: Arc::default(),
+            snapshot: None,
+            scope: NamespaceScope::default(),
+            pod_field_selector: None,
+            warnings: Warnings::default(),
         }
     }
 
+    /// Warnings raised while answering so far
+    pub fn warnings(&self) -> &Warnings {
+        &self.warnings
+    }
+
+    /// Record namespace counts and phase timings in `stats`
+    pub fn with_stats(mut self, stats: Arc<RunStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Collect warnings about failed namespaces in `warnings`
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Answer service, pod and workload queries from a recorded snapshot
+    pub fn with_snapshot(mut self, snapshot: ClusterSnapshot) -> Self {
+        self.snapshot = Some(Arc::new(snapshot));
+        self
+    }
+
+    /// The recorded snapshot queries are answered from, if any
+    pub fn recorded(&self) -> Option<&ClusterSnapshot> {
+        self.snapshot.as_deref()
+    }
+
+    /// Leave the namespaces `scope` excludes out of listings across
+    /// namespaces
+    pub fn with_namespace_scope(mut self, scope: NamespaceScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Send `selector` as the field selector of every pod listing
+    pub fn with_pod_field_selector(mut self, selector: FieldSelector) -> Self {
+        self.pod_field_selector = Some(selector);
+        self
+    }
+
+    /// Namespaces left out of listings across namespaces
+    pub fn namespace_scope(&self) -> &NamespaceScope {
+        &self.scope
+    }
+
+    /// Kubernetes client used for discovery
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Client session for long-running commands, which outlive the
+    /// credentials `client` was built with
+    pub fn session(&self) -> Arc<ClientSession> {
+        self.session.clone()
+    }
+
+    /// Cache of listed resources
+    pub fn cache(&self) -> &ResourceCache {
+        &self.cache
+    }
+
     /// Get cache statistics
     pub fn cache_stats(&self) -> crate::cache::CacheStats {
         self.cache.stats()
@@ -93,8 +385,14 @@ impl DiscoveryEngine {
     pub async fn get_all_namespaces(&self) -> Result<Vec<String>> {
         use k8s_openapi::api::core::v1::Namespace;
 
+        if let Some(snapshot) = &self.snapshot {
+            return Ok(snapshot.namespaces.clone());
+        }
+
+        let started = Instant::now();
         let namespaces: Api<Namespace> = Api::all(self.client.clone());
         let namespace_list = namespaces.list(&Default::default()).await?;
+        self.stats.record_phase("list namespaces", started);
 
         let mut namespace_names = Vec::new();
         for namespace in namespace_list.items {
@@ -106,6 +404,14 @@ impl DiscoveryEngine {
         Ok(namespace_names)
     }
 
+    /// Namespaces a listing across namespaces covers: every namespace but
+    /// those the namespace scope excludes
+    pub async fn namespaces_in_scope(&self) -> Result<Vec<String>> {
+        let mut namespaces = self.get_all_namespaces().await?;
+        namespaces.retain(|ns| self.scope.includes(ns));
+        Ok(namespaces)
+    }
+
     /// List services in the specified namespace (or all namespaces if None)
     pub async fn list_services(&self, namespace: Option<&str>) -> Result<Vec<ServiceInfo>> {
         self.list_services_with_options(namespace, None, None, 100, false)
@@ -121,6 +427,32 @@ impl DiscoveryEngine {
         page_size: usize,
         use_cache: bool,
     ) -> Result<Vec<ServiceInfo>> {
+        let mut items = self
+            .fetch_services(namespace, selector, limit, page_size, use_cache)
+            .await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |s| Some(s.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_services(
+        &self,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        limit: Option<usize>,
+        page_size: usize,
+        use_cache: bool,
+    ) -> Result<Vec<ServiceInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            let services = snapshot.services(namespace, selector)?;
+            return Ok(services
+                .into_iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .collect());
+        }
+
         // Check cache first if enabled
         if use_cache {
             if let Some(cached) = self.cache.get_services(namespace, selector) {
@@ -173,8 +505,9 @@ impl DiscoveryEngine {
             }
         }
 
-        // Cache the results if caching is enabled
-        if use_cache {
+        // Cache the results if caching is enabled; a listing --limit cut
+        // short is not stored where a full listing would be read from
+        if use_cache && limit.is_none_or(|limit| fetched < limit) {
             self.cache
                 .set_services(namespace, selector, all_services.clone());
         }
@@ -182,6 +515,15 @@ impl DiscoveryEngine {
         Ok(all_services)
     }
 
+    /// Cache key selector of pod listings: listings with a field selector
+    /// are cached apart from full ones
+    fn pod_cache_selector(&self, selector: Option<&str>) -> Option<String> {
+        match &self.pod_field_selector {
+            Some(fields) => Some(format!("{};{}", selector.unwrap_or_default(), fields)),
+            None => selector.map(str::to_string),
+        }
+    }
+
     /// List pods in the specified namespace with optional label selector
     pub async fn list_pods(
         &self,
@@ -201,9 +543,46 @@ impl DiscoveryEngine {
         page_size: usize,
         use_cache: bool,
     ) -> Result<Vec<PodInfo>> {
+        let mut items = self
+            .fetch_pods(namespace, selector, limit, page_size, use_cache)
+            .await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |p| Some(p.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_pods(
+        &self,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        limit: Option<usize>,
+        page_size: usize,
+        use_cache: bool,
+    ) -> Result<Vec<PodInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            let mut pods = snapshot.pods(namespace, selector)?;
+            if let Some(fields) = &self.pod_field_selector {
+                let mut error = None;
+                pods.retain(|pod| {
+                    fields.matches_pod(pod).unwrap_or_else(|e| {
+                        error = Some(e);
+                        false
+                    })
+                });
+                if let Some(error) = error {
+                    return Err(ExplorerError::InvalidArgument(error));
+                }
+            }
+            return Ok(pods.into_iter().take(limit.unwrap_or(usize::MAX)).collect());
+        }
+
+        let cache_selector = self.pod_cache_selector(selector);
+
         // Check cache first if enabled
         if use_cache {
-            if let Some(cached) = self.cache.get_pods(namespace, selector) {
+            if let Some(cached) = self.cache.get_pods(namespace, cache_selector.as_deref()) {
                 return Ok(if let Some(limit) = limit {
                     cached.into_iter().take(limit).collect()
                 } else {
@@ -228,6 +607,10 @@ impl DiscoveryEngine {
                 list_params = list_params.labels(sel);
             }
 
+            if let Some(fields) = &self.pod_field_selector {
+                list_params = list_params.fields(&fields.to_string());
+            }
+
             if let Some(token) = continue_token {
                 list_params = list_params.continue_token(&token);
             }
@@ -253,9 +636,11 @@ impl DiscoveryEngine {
             }
         }
 
-        // Cache the results if caching is enabled
-        if use_cache {
-            self.cache.set_pods(namespace, selector, all_pods.clone());
+        // Cache the results if caching is enabled; a listing --limit cut
+        // short is not stored where a full listing would be read from
+        if use_cache && limit.is_none_or(|limit| fetched < limit) {
+            self.cache
+                .set_pods(namespace, cache_selector.as_deref(), all_pods.clone());
         }
 
         Ok(all_pods)
@@ -325,6 +710,10 @@ impl DiscoveryEngine {
         loop {
             let mut list_params = kube::api::ListParams::default().limit(page_size as u32);
 
+            if let Some(fields) = &self.pod_field_selector {
+                list_params = list_params.fields(&fields.to_string());
+            }
+
             if let Some(token) = continue_token {
                 list_params = list_params.continue_token(&token);
             }
@@ -362,11 +751,13 @@ impl DiscoveryEngine {
         use_cache: bool,
         concurrency_limit: usize,
         progress: Option<&ProgressTracker>,
+        checkpoint: Option<&ScanCheckpoint<ServiceInfo>>,
     ) -> Result<Vec<ServiceInfo>> {
         if namespaces.is_empty() {
             return Ok(Vec::new());
         }
 
+        let started = Instant::now();
         let mut join_set = JoinSet::new();
         let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency_limit));
         let total_namespaces = namespaces.len();
@@ -381,6 +772,21 @@ impl DiscoveryEngine {
         // Calculate per-namespace limit if global limit is specified
         let per_namespace_limit = limit.map(|l| l.div_ceil(namespaces.len()));
 
+        // Namespaces completed by an earlier, interrupted run are not fetched again
+        let mut all_services = Vec::new();
+        let mut namespaces = namespaces;
+        let mut resumed = 0;
+        if let Some(checkpoint) = checkpoint {
+            namespaces.retain(|ns| match checkpoint.completed(ns) {
+                Some(items) => {
+                    all_services.extend(items.iter().cloned());
+                    resumed += 1;
+                    false
+                }
+                None => true,
+            });
+        }
+
         for (index, namespace) in namespaces.into_iter().enumerate() {
             let engine = self.clone();
             let selector = selector.map(|s| s.to_string());
@@ -401,8 +807,11 @@ impl DiscoveryEngine {
             });
         }
 
-        let mut all_services = Vec::new();
-        let mut completed = 0;
+        let mut completed = resumed;
+        let mut failed = 0;
+        // Namespaces left unfetched once `limit` items were listed
+        let mut skipped = 0;
+        let mut errors = Vec::new();
 
         while let Some(result) = join_set.join_next().await {
             completed += 1;
@@ -416,29 +825,105 @@ impl DiscoveryEngine {
             }
 
             match result {
-                Ok((_, _namespace, Ok(services))) => {
+                Ok((_, namespace, Ok(services))) => {
+                    if let Some(checkpoint) = checkpoint {
+                        if let Err(e) = checkpoint.record(&namespace, &services) {
+                            eprintln!("Warning: Failed to write checkpoint: {}", e);
+                        }
+                    }
                     all_services.extend(services);
+                    self.stats.add_namespaces_scanned(1);
                 }
-                Ok((_, namespace, Err(e))) => {
-                    eprintln!(
-                        "Warning: Failed to fetch services from namespace '{}': {}",
-                        namespace, e
-                    );
-                }
+                Ok((_, namespace, Err(e))) => errors.push((namespace, e)),
                 Err(e) => {
+                    failed += 1;
                     eprintln!("Warning: Task failed: {}", e);
+                    self.warnings.warn_incomplete(format!("Task failed: {}", e));
                 }
             }
 
-            // Apply global limit if specified
+            // Apply global limit if specified; namespaces still being
+            // fetched are dropped, and are not failures to resume
             if let Some(limit) = limit {
                 if all_services.len() >= limit {
                     all_services.truncate(limit);
+                    skipped += join_set.len();
                     break;
                 }
             }
         }
 
+        // Transient failures get one more attempt once the rest are done
+        for (namespace, error) in errors {
+            if limit.is_some_and(|l| all_services.len() >= l) {
+                skipped += 1;
+                continue;
+            }
+            let retried = error.is_transient();
+            let error = if retried {
+                match self
+                    .list_services_with_options(
+                        Some(&namespace),
+                        selector,
+                        per_namespace_limit,
+                        page_size,
+                        use_cache,
+                    )
+                    .await
+                {
+                    Ok(items) => {
+                        if let Some(checkpoint) = checkpoint {
+                            if let Err(e) = checkpoint.record(&namespace, &items) {
+                                eprintln!("Warning: Failed to write checkpoint: {}", e);
+                            }
+                        }
+                        all_services.extend(items);
+                        self.stats.add_namespaces_scanned(1);
+                        continue;
+                    }
+                    Err(e) => e,
+                }
+            } else {
+                error
+            };
+
+            failed += 1;
+            report_namespace_failure(
+                &self.warnings,
+                progress,
+                NamespaceFailure {
+                    namespace,
+                    resource: "services".to_string(),
+                    error: error.to_string(),
+                    retried,
+                },
+            );
+        }
+        if let Some(limit) = limit {
+            all_services.truncate(limit);
+        }
+
+        if skipped > 0 {
+            self.warnings.warn(format!(
+                "{} namespaces not fetched once --limit was reached",
+                skipped
+            ));
+        }
+        if let Some(checkpoint) = checkpoint {
+            if failed == 0 {
+                checkpoint.finish();
+            } else {
+                eprintln!(
+                    "Warning: {} namespaces failed; re-run with --resume to retry only those",
+                    failed
+                );
+            }
+        }
+
+        // Namespaces finish in any order; sort so repeated runs print the same
+        all_services.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+        self.stats.record_phase("fetch services", started);
         Ok(all_services)
     }
 
@@ -453,11 +938,13 @@ impl DiscoveryEngine {
         use_cache: bool,
         concurrency_limit: usize,
         progress: Option<&ProgressTracker>,
+        checkpoint: Option<&ScanCheckpoint<PodInfo>>,
     ) -> Result<Vec<PodInfo>> {
         if namespaces.is_empty() {
             return Ok(Vec::new());
         }
 
+        let started = Instant::now();
         let mut join_set = JoinSet::new();
         let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency_limit));
         let total_namespaces = namespaces.len();
@@ -472,6 +959,21 @@ impl DiscoveryEngine {
         // Calculate per-namespace limit if global limit is specified
         let per_namespace_limit = limit.map(|l| l.div_ceil(namespaces.len()));
 
+        // Namespaces completed by an earlier, interrupted run are not fetched again
+        let mut all_pods = Vec::new();
+        let mut namespaces = namespaces;
+        let mut resumed = 0;
+        if let Some(checkpoint) = checkpoint {
+            namespaces.retain(|ns| match checkpoint.completed(ns) {
+                Some(items) => {
+                    all_pods.extend(items.iter().cloned());
+                    resumed += 1;
+                    false
+                }
+                None => true,
+            });
+        }
+
         for (index, namespace) in namespaces.into_iter().enumerate() {
             let engine = self.clone();
             let selector = selector.map(|s| s.to_string());
@@ -492,8 +994,11 @@ impl DiscoveryEngine {
             });
         }
 
-        let mut all_pods = Vec::new();
-        let mut completed = 0;
+        let mut completed = resumed;
+        let mut failed = 0;
+        // Namespaces left unfetched once `limit` items were listed
+        let mut skipped = 0;
+        let mut errors = Vec::new();
 
         while let Some(result) = join_set.join_next().await {
             completed += 1;
@@ -507,98 +1012,540 @@ impl DiscoveryEngine {
             }
 
             match result {
-                Ok((_, _namespace, Ok(pods))) => {
+                Ok((_, namespace, Ok(pods))) => {
+                    if let Some(checkpoint) = checkpoint {
+                        if let Err(e) = checkpoint.record(&namespace, &pods) {
+                            eprintln!("Warning: Failed to write checkpoint: {}", e);
+                        }
+                    }
                     all_pods.extend(pods);
+                    self.stats.add_namespaces_scanned(1);
                 }
-                Ok((_, namespace, Err(e))) => {
-                    eprintln!(
-                        "Warning: Failed to fetch pods from namespace '{}': {}",
-                        namespace, e
-                    );
-                }
+                Ok((_, namespace, Err(e))) => errors.push((namespace, e)),
                 Err(e) => {
+                    failed += 1;
                     eprintln!("Warning: Task failed: {}", e);
+                    self.warnings.warn_incomplete(format!("Task failed: {}", e));
                 }
             }
 
-            // Apply global limit if specified
+            // Apply global limit if specified; namespaces still being
+            // fetched are dropped, and are not failures to resume
             if let Some(limit) = limit {
                 if all_pods.len() >= limit {
                     all_pods.truncate(limit);
+                    skipped += join_set.len();
                     break;
                 }
             }
         }
 
+        // Transient failures get one more attempt once the rest are done
+        for (namespace, error) in errors {
+            if limit.is_some_and(|l| all_pods.len() >= l) {
+                skipped += 1;
+                continue;
+            }
+            let retried = error.is_transient();
+            let error = if retried {
+                match self
+                    .list_pods_with_options(
+                        Some(&namespace),
+                        selector,
+                        per_namespace_limit,
+                        page_size,
+                        use_cache,
+                    )
+                    .await
+                {
+                    Ok(items) => {
+                        if let Some(checkpoint) = checkpoint {
+                            if let Err(e) = checkpoint.record(&namespace, &items) {
+                                eprintln!("Warning: Failed to write checkpoint: {}", e);
+                            }
+                        }
+                        all_pods.extend(items);
+                        self.stats.add_namespaces_scanned(1);
+                        continue;
+                    }
+                    Err(e) => e,
+                }
+            } else {
+                error
+            };
+
+            failed += 1;
+            report_namespace_failure(
+                &self.warnings,
+                progress,
+                NamespaceFailure {
+                    namespace,
+                    resource: "pods".to_string(),
+                    error: error.to_string(),
+                    retried,
+                },
+            );
+        }
+        if let Some(limit) = limit {
+            all_pods.truncate(limit);
+        }
+
+        if skipped > 0 {
+            self.warnings.warn(format!(
+                "{} namespaces not fetched once --limit was reached",
+                skipped
+            ));
+        }
+        if let Some(checkpoint) = checkpoint {
+            if failed == 0 {
+                checkpoint.finish();
+            } else {
+                eprintln!(
+                    "Warning: {} namespaces failed; re-run with --resume to retry only those",
+                    failed
+                );
+            }
+        }
+
+        // Namespaces finish in any order; sort so repeated runs print the same
+        all_pods.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+        self.stats.record_phase("fetch pods", started);
         Ok(all_pods)
     }
 
+    /// List resources in each of the given namespaces concurrently with
+    /// `fetch`. Results keep the order of `namespaces`; namespaces that fail
+    /// after a retry of transient errors are reported and left out.
+    pub async fn list_in_namespaces<T, F, Fut>(
+        &self,
+        namespaces: Vec<String>,
+        resource: &str,
+        concurrency_limit: usize,
+        progress: Option<&ProgressTracker>,
+        fetch: F,
+    ) -> Result<Vec<T>>
+    where
+        T: Send + 'static,
+        F: Fn(DiscoveryEngine, String) -> Fut,
+        Fut: Future<Output = Result<Vec<T>>> + Send + 'static,
+    {
+        let started = Instant::now();
+        let mut join_set = JoinSet::new();
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency_limit));
+        let total_namespaces = namespaces.len();
+
+        if let Some(progress) = progress {
+            progress.set_message(&format!(
+                "Discovering {} across {} namespaces...",
+                resource, total_namespaces
+            ));
+        }
+
+        for (index, namespace) in namespaces.into_iter().enumerate() {
+            let task = fetch(self.clone(), namespace.clone());
+            let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+            join_set.spawn(async move {
+                let _permit = permit;
+                (index, namespace, task.await)
+            });
+        }
+
+        let mut results: Vec<(usize, Vec<T>)> = Vec::new();
+        let mut errors = Vec::new();
+        let mut completed = 0;
+        while let Some(result) = join_set.join_next().await {
+            completed += 1;
+            if let Some(progress) = progress {
+                progress.set_position(completed);
+                progress.set_message(&format!(
+                    "Completed {}/{} namespaces",
+                    completed, total_namespaces
+                ));
+            }
+
+            match result {
+                Ok((index, _, Ok(items))) => {
+                    results.push((index, items));
+                    self.stats.add_namespaces_scanned(1);
+                }
+                Ok((index, namespace, Err(e))) => errors.push((index, namespace, e)),
+                Err(e) => {
+                    eprintln!("Warning: Task failed: {}", e);
+                    self.warnings.warn_incomplete(format!("Task failed: {}", e));
+                }
+            }
+        }
+
+        // Transient failures get one more attempt once the rest are done
+        for (index, namespace, error) in errors {
+            let retried = error.is_transient();
+            let error = if retried {
+                match fetch(self.clone(), namespace.clone()).await {
+                    Ok(items) => {
+                        results.push((index, items));
+                        self.stats.add_namespaces_scanned(1);
+                        continue;
+                    }
+                    Err(e) => e,
+                }
+            } else {
+                error
+            };
+
+            report_namespace_failure(
+                &self.warnings,
+                progress,
+                NamespaceFailure {
+                    namespace,
+                    resource: resource.to_string(),
+                    error: error.to_string(),
+                    retried,
+                },
+            );
+        }
+
+        results.sort_by_key(|(index, _)| *index);
+        self.stats
+            .record_phase(&format!("fetch {}", resource), started);
+        Ok(results.into_iter().flat_map(|(_, items)| items).collect())
+    }
+
     /// Get detailed information about a specific service
     pub async fn describe_service(
         &self,
         name: &str,
         namespace: &str,
     ) -> Result<ServiceDescription> {
-        let services: Api<Service> = Api::namespaced(self.client.clone(), namespace);
-        let service = services
-            .get(name)
-            .await
-            .map_err(|_| ExplorerError::ResourceNotFound {
-                kind: "Service".to_string(),
-                name: name.to_string(),
-                namespace: namespace.to_string(),
-            })?;
+        let not_found = || ExplorerError::ResourceNotFound {
+            kind: "Service".to_string(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+        };
 
         // Get basic service info
-        let service_info = self.convert_service_to_info(service).await.ok_or_else(|| {
-            ExplorerError::ResourceNotFound {
-                kind: "Service".to_string(),
-                name: name.to_string(),
-                namespace: namespace.to_string(),
-            }
-        })?;
+        let service_info = if let Some(snapshot) = &self.snapshot {
+            snapshot
+                .services(Some(namespace), None)?
+                .into_iter()
+                .find(|s| s.name == name)
+                .ok_or_else(not_found)?
+        } else if let Some(service) = self.cached_service(name, namespace) {
+            service
+        } else {
+            let services: Api<Service> = Api::namespaced(self.client.clone(), namespace);
+            let service = services.get(name).await.map_err(|_| not_found())?;
+            self.convert_service_to_info(service)
+                .await
+                .ok_or_else(not_found)?
+        };
 
         // Get related pods
-        let related_pods = if let Some(selector) = &service_info.selector {
-            let selector_string = selector
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join(",");
-            self.list_pods(Some(namespace), Some(&selector_string))
+        let related_pods = match &service_info.selector {
+            Some(selector) => self
+                .selected_pods(namespace, selector)
                 .await
-                .unwrap_or_default()
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        // Snapshots record neither events nor autoscalers, so both are only
+        // shown live
+        let live = self.snapshot.is_none() && !related_pods.is_empty();
+        let (events, hpas) = if live {
+            tokio::join!(
+                events::list_events(&self.client, Some(namespace), Some("type=Warning")),
+                hpa::list_autoscalers(&self.client, Some(namespace)),
+            )
         } else {
-            Vec::new()
+            (Ok(Vec::new()), Ok(Vec::new()))
+        };
+
+        let warnings = match events {
+            Ok(events) => {
+                let pods: Vec<&str> = related_pods.iter().map(|p| p.name.as_str()).collect();
+                events::pod_warnings(&events, &pods)
+            }
+            Err(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                if (403..=405).contains(&e.code) =>
+            {
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Autoscalers of the workloads behind the service
+        let autoscalers = match hpas {
+            Ok(hpas) => {
+                let workloads: HashSet<(String, String)> =
+                    related_pods.iter().map(pod_workload).collect();
+                hpas.into_iter()
+                    .filter(|h| workloads.iter().any(|(kind, name)| h.scales(kind, name)))
+                    .map(|h| HpaInfo {
+                        target_found: Some(true),
+                        ..h
+                    })
+                    .collect()
+            }
+            Err(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                if (403..=405).contains(&e.code) =>
+            {
+                Vec::new()
+            }
+            Err(e) => return Err(e),
         };
 
         Ok(ServiceDescription {
             service: service_info,
             related_pods,
+            warnings,
+            autoscalers,
         })
     }
 
-    /// Analyze service topology and relationships
-    pub async fn analyze_service_topology(
-        &self,
+    /// A service from a cached listing of its namespace or of all namespaces
+    fn cached_service(&self, name: &str, namespace: &str) -> Option<ServiceInfo> {
+        self.cache
+            .get_services(Some(namespace), None)
+            .or_else(|| self.cache.get_services(None, None))?
+            .into_iter()
+            .find(|s| s.name == name && s.namespace == namespace)
+    }
+
+    /// Pods in `namespace` matching every pair of `selector`. A cached pod
+    /// listing or pod index covering the namespace answers from memory;
+    /// otherwise only the selected pods are listed, and cached.
+    async fn selected_pods(
+        &self,
+        namespace: &str,
+        selector: &BTreeMap<String, String>,
+    ) -> Result<Vec<PodInfo>> {
+        let selects = |pod: &PodInfo| {
+            !selector.is_empty()
+                && pod.namespace == namespace
+                && selector.iter().all(|(k, v)| pod.labels.get(k) == Some(v))
+        };
+
+        if self.snapshot.is_none() {
+            let full = self.pod_cache_selector(None);
+            let listed = self
+                .cache
+                .get_pods(Some(namespace), full.as_deref())
+                .or_else(|| self.cache.get_pods(None, full.as_deref()));
+            if let Some(pods) = listed {
+                return Ok(pods.into_iter().filter(|p| selects(p)).collect());
+            }
+            // Indexes hold every pod, so they only answer without --field-selector
+            let index = self
+                .cache
+                .get_pod_index(Some(namespace))
+                .or_else(|| self.cache.get_pod_index(None))
+                .filter(|_| self.pod_field_selector.is_none());
+            if let Some(index) = index {
+                return Ok(index.selected_by(namespace, selector).cloned().collect());
+            }
+        }
+
+        let selector_string = selector
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.list_pods_with_options(Some(namespace), Some(&selector_string), None, 100, true)
+            .await
+    }
+
+    /// Get a pod with container-level detail, the services selecting it, its
+    /// ownership chain and the node it runs on
+    pub async fn describe_pod(&self, name: &str, namespace: &str) -> Result<PodDescription> {
+        if self.snapshot.is_some() {
+            return Err(ExplorerError::InvalidArgument(
+                "describe pod/<name> is not available with --as-of".to_string(),
+            ));
+        }
+
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let pod = pods
+            .get_opt(name)
+            .await?
+            .ok_or_else(|| ExplorerError::ResourceNotFound {
+                kind: "Pod".to_string(),
+                name: name.to_string(),
+                namespace: namespace.to_string(),
+            })?;
+
+        let containers = container_details(&pod);
+        let service_account = pod
+            .spec
+            .as_ref()
+            .and_then(|s| s.service_account_name.clone());
+        let pod_info =
+            self.convert_pod_to_info(pod)
+                .await
+                .ok_or_else(|| ExplorerError::ResourceNotFound {
+                    kind: "Pod".to_string(),
+                    name: name.to_string(),
+                    namespace: namespace.to_string(),
+                })?;
+
+        let services = self
+            .list_services(Some(namespace))
+            .await?
+            .into_iter()
+            .filter(|s| {
+                s.selector.as_ref().is_some_and(|selector| {
+                    !selector.is_empty()
+                        && selector
+                            .iter()
+                            .all(|(k, v)| pod_info.labels.get(k) == Some(v))
+                })
+            })
+            .map(|s| s.name)
+            .collect();
+
+        // Owners and node are extra context; a pod without them still describes
+        let target = ObjectRef {
+            kind: ResourceKind::Pod,
+            name: name.to_string(),
+        };
+        let owners = ownership_chain(&self.client, &target, namespace).await.ok();
+        let node = match &pod_info.node_name {
+            Some(node_name) => {
+                let nodes: Api<Node> = Api::all(self.client.clone());
+                match nodes.get_opt(node_name).await {
+                    Ok(node) => node.and_then(convert_node_to_info),
+                    Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => None,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            None => None,
+        };
+
+        let fields = format!("involvedObject.kind=Pod,involvedObject.name={}", name);
+        let pod_events = events::list_events(&self.client, Some(namespace), Some(&fields))
+            .await
+            .map(|events| events::filter_events(events, None, None))
+            .unwrap_or_default();
+
+        Ok(PodDescription {
+            pod: pod_info,
+            service_account,
+            containers,
+            services,
+            owners,
+            node,
+            events: pod_events,
+        })
+    }
+
+    /// Get a statefulset with the pod and claims of every ordinal
+    pub async fn describe_statefulset(
+        &self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<StatefulSetInfo> {
+        if self.snapshot.is_some() {
+            return Err(ExplorerError::InvalidArgument(
+                "describe statefulset/<name> is not available with --as-of".to_string(),
+            ));
+        }
+
+        let not_found = || ExplorerError::ResourceNotFound {
+            kind: "StatefulSet".to_string(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+        };
+        let statefulsets: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+        let statefulset = statefulsets.get_opt(name).await?.ok_or_else(not_found)?;
+
+        let selector = statefulset
+            .spec
+            .as_ref()
+            .and_then(|s| s.selector.match_labels.as_ref())
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default();
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let pods = pods.list(&ListParams::default().labels(&selector)).await?;
+        let claims: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), namespace);
+        let claims = claims.list(&Default::default()).await?;
+
+        let ordinals = statefulset::ordinal_status(&statefulset, &pods.items, &claims.items);
+        let mut info = self
+            .convert_statefulset_to_info(statefulset)
+            .await
+            .ok_or_else(not_found)?;
+        info.ordinals = ordinals;
+        Ok(info)
+    }
+
+    /// Analyze service topology and relationships
+    pub async fn analyze_service_topology(
+        &self,
         name: &str,
         namespace: &str,
     ) -> Result<ServiceTopology> {
         let description = self.describe_service(name, namespace).await?;
 
+        let pods: Vec<String> = description
+            .related_pods
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        let (storage, ingress_routes) = tokio::join!(
+            async {
+                // Snapshots do not record volumes, so storage is only shown live
+                if self.snapshot.is_some() {
+                    return Vec::new();
+                }
+                self.pod_storage(&pods, namespace).await.unwrap_or_default()
+            },
+            self.get_ingress_routes_for_topology(name, namespace),
+        );
+
         // For now, this is a simplified topology
         // In the future, we could add ingress analysis, network policies, etc.
         Ok(ServiceTopology {
             service: description.service,
             backend_pods: description.related_pods,
-            ingress_routes: self
-                .get_ingress_routes_for_topology(name, namespace)
-                .await
-                .unwrap_or_default(),
+            ingress_routes: ingress_routes.unwrap_or_default(),
             dependencies: Vec::new(), // Basic dependency analysis could be added here
+            storage,
+            warnings: description.warnings,
+            autoscalers: description.autoscalers,
         })
     }
 
+    /// Claims mounted by `pods` in `namespace` and the volumes bound to them.
+    /// Volumes are cluster-scoped; if they may not be listed the chain stops
+    /// at the claim.
+    async fn pod_storage(&self, pods: &[String], namespace: &str) -> Result<Vec<PodStorage>> {
+        let (mounts, claims, volumes) = tokio::join!(
+            self.list_claim_mounts(Some(namespace)),
+            self.list_persistent_volume_claims(Some(namespace)),
+            self.list_persistent_volumes(),
+        );
+        let (mounts, claims) = (mounts?, claims?);
+        let volumes = match volumes {
+            Ok(volumes) => volumes,
+            Err(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                if (403..=405).contains(&e.code) =>
+            {
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(storage::pod_storage(
+            pods, namespace, &mounts, &claims, &volumes,
+        ))
+    }
+
     async fn get_ingress_routes_for_topology(
         &self,
         service_name: &str,
@@ -642,43 +1589,163 @@ impl DiscoveryEngine {
         Ok(matching_ingresses)
     }
 
-    /// Discover ConfigMaps and Secrets used by a service (placeholder implementation)
+    /// ConfigMaps and Secrets in `namespace` used by any of `pods`
     pub async fn discover_service_configuration(
         &self,
-        service_name: &str,
+        pods: &[PodInfo],
         namespace: &str,
     ) -> Result<(Vec<ConfigMapInfo>, Vec<SecretInfo>)> {
-        // Basic implementation: check if common ConfigMaps and Secrets exist
-        let configmaps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
-        let secrets: Api<Secret> = Api::namespaced(self.client.clone(), namespace);
-
-        let mut found_configmaps = Vec::new();
-        let mut found_secrets = Vec::new();
-
-        // Look for common configuration patterns
-        let common_config_names = vec![
-            service_name.to_string(),
-            format!("{}-config", service_name),
-            format!("{}-configuration", service_name),
-        ];
+        let (configmaps, secrets) = tokio::try_join!(
+            self.list_configmaps(Some(namespace)),
+            self.list_secrets(Some(namespace)),
+        )?;
+        let used = |used_by: &[ResourceReference]| {
+            used_by
+                .iter()
+                .any(|r| r.kind == "Pod" && pods.iter().any(|p| p.name == r.name))
+        };
+        Ok((
+            configmaps
+                .into_iter()
+                .filter(|c| used(&c.used_by))
+                .collect(),
+            secrets.into_iter().filter(|s| used(&s.used_by)).collect(),
+        ))
+    }
 
-        for config_name in common_config_names {
-            // Check for ConfigMap
-            if let Ok(configmap) = configmaps.get(&config_name).await {
-                if let Some(configmap_info) = self.convert_configmap_to_info(configmap).await {
-                    found_configmaps.push(configmap_info);
-                }
+    /// Services, workloads, pods, ConfigMaps and Secrets of `namespace`
+    /// (every namespace when `None`), listed concurrently and from the cache
+    /// where possible. Kinds `include` rejects are left empty unrequested
+    pub async fn list_all(
+        &self,
+        namespace: Option<&str>,
+        limit: Option<usize>,
+        page_size: usize,
+        include: impl Fn(&str) -> bool,
+    ) -> Result<AllResources> {
+        async fn when<T>(
+            wanted: bool,
+            list: impl std::future::Future<Output = Result<Vec<T>>>,
+        ) -> Result<Vec<T>> {
+            if wanted {
+                list.await
+            } else {
+                Ok(Vec::new())
             }
+        }
 
-            // Check for Secret
-            if let Ok(secret) = secrets.get(&config_name).await {
-                if let Some(secret_info) = self.convert_secret_to_info(secret).await {
-                    found_secrets.push(secret_info);
-                }
-            }
+        let (services, deployments, statefulsets, daemonsets, pods, configmaps, secrets) = tokio::try_join!(
+            when(
+                include("Service"),
+                self.list_services_with_options(namespace, None, limit, page_size, true)
+            ),
+            when(
+                include("Deployment"),
+                self.list_deployments_with_options(namespace, limit, page_size, true)
+            ),
+            when(
+                include("StatefulSet"),
+                self.list_statefulsets_with_options(namespace, true)
+            ),
+            when(
+                include("DaemonSet"),
+                self.list_daemonsets_with_options(namespace, true)
+            ),
+            when(
+                include("Pod"),
+                self.list_pods_with_options(namespace, None, limit, page_size, true)
+            ),
+            when(
+                include("ConfigMap"),
+                self.list_configmaps_with_options(namespace, limit, page_size, true)
+            ),
+            when(
+                include("Secret"),
+                self.list_secrets_with_options(namespace, true)
+            ),
+        )?;
+
+        Ok(AllResources {
+            services,
+            deployments,
+            statefulsets,
+            daemonsets,
+            pods,
+            configmaps,
+            secrets,
+        })
+    }
+
+    /// Describe a service together with its ingresses, configuration,
+    /// endpoints, ServiceMonitors, admission webhooks and health, gathered
+    /// concurrently. A section that cannot be read is left empty and its
+    /// error recorded, so one forbidden kind does not hide the rest.
+    pub async fn describe_service_deep(
+        &self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<DeepServiceDescription> {
+        if self.snapshot.is_some() {
+            return Err(ExplorerError::InvalidArgument(
+                "describe --deep is not available with --as-of".to_string(),
+            ));
         }
+        let description = self.describe_service(name, namespace).await?;
 
-        Ok((found_configmaps, found_secrets))
+        let (ingresses, configuration, endpoints, monitors, webhooks, health) = tokio::join!(
+            self.discover_ingress_for_service(name, namespace),
+            self.discover_service_configuration(&description.related_pods, namespace),
+            endpoints::service_endpoints(&self.client, name, namespace),
+            dependents::service_monitors(&self.client, namespace, name),
+            dependents::service_webhooks(&self.client, namespace, name),
+            self.check_service_health(name, namespace),
+        );
+
+        let mut unavailable = BTreeMap::new();
+        let mut section = |section: &str, error: ExplorerError| {
+            unavailable.insert(section.to_string(), error.to_string());
+        };
+        let ingresses = ingresses.unwrap_or_else(|e| {
+            section("ingresses", e);
+            Vec::new()
+        });
+        let (configmaps, secrets) = configuration.unwrap_or_else(|e| {
+            section("configuration", e);
+            (Vec::new(), Vec::new())
+        });
+        let endpoints = endpoints.unwrap_or_else(|e| {
+            section("endpoints", e);
+            Vec::new()
+        });
+        let monitors = monitors.unwrap_or_else(|e| {
+            section("monitors", e);
+            Vec::new()
+        });
+        let webhooks = webhooks.unwrap_or_else(|e| {
+            section("webhooks", e);
+            Vec::new()
+        });
+        let health = health.unwrap_or_else(|e| {
+            section("health", e);
+            ServiceHealth {
+                service_name: name.to_string(),
+                namespace: namespace.to_string(),
+                overall_healthy: false,
+                checked_at: "Error checking health".to_string(),
+            }
+        });
+
+        Ok(DeepServiceDescription {
+            description,
+            ingresses,
+            configmaps,
+            secrets,
+            endpoints,
+            monitors,
+            webhooks,
+            health,
+            unavailable,
+        })
     }
 
     /// List deployments in the specified namespace (or all namespaces if None)
@@ -696,6 +1763,31 @@ impl DiscoveryEngine {
         page_size: usize,
         use_cache: bool,
     ) -> Result<Vec<DeploymentInfo>> {
+        let mut items = self
+            .fetch_deployments(namespace, limit, page_size, use_cache)
+            .await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |d| Some(d.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_deployments(
+        &self,
+        namespace: Option<&str>,
+        limit: Option<usize>,
+        page_size: usize,
+        use_cache: bool,
+    ) -> Result<Vec<DeploymentInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            let deployments = snapshot.deployments(namespace);
+            return Ok(deployments
+                .into_iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .collect());
+        }
+
         // Check cache first if enabled
         if use_cache {
             if let Some(cached) = self.cache.get_deployments(namespace) {
@@ -744,8 +1836,9 @@ impl DiscoveryEngine {
             }
         }
 
-        // Cache the results if caching is enabled
-        if use_cache {
+        // Cache the results if caching is enabled; a listing --limit cut
+        // short is not stored where a full listing would be read from
+        if use_cache && limit.is_none_or(|limit| fetched < limit) {
             self.cache
                 .set_deployments(namespace, all_deployments.clone());
         }
@@ -755,6 +1848,38 @@ impl DiscoveryEngine {
 
     /// List statefulsets in the specified namespace (or all namespaces if None)
     pub async fn list_statefulsets(&self, namespace: Option<&str>) -> Result<Vec<StatefulSetInfo>> {
+        self.list_statefulsets_with_options(namespace, false).await
+    }
+
+    /// List statefulsets with caching support
+    pub async fn list_statefulsets_with_options(
+        &self,
+        namespace: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Vec<StatefulSetInfo>> {
+        let mut items = self.fetch_statefulsets(namespace, use_cache).await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |s| Some(s.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_statefulsets(
+        &self,
+        namespace: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Vec<StatefulSetInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            return Ok(snapshot.statefulsets(namespace));
+        }
+
+        if use_cache {
+            if let Some(cached) = self.cache.get_statefulsets(namespace) {
+                return Ok(cached);
+            }
+        }
+
         let statefulsets: Api<StatefulSet> = match namespace {
             Some(ns) => Api::namespaced(self.client.clone(), ns),
             None => Api::all(self.client.clone()),
@@ -769,11 +1894,48 @@ impl DiscoveryEngine {
             }
         }
 
+        if use_cache {
+            self.cache
+                .set_statefulsets(namespace, statefulset_infos.clone());
+        }
+
         Ok(statefulset_infos)
     }
 
     /// List daemonsets in the specified namespace (or all namespaces if None)
     pub async fn list_daemonsets(&self, namespace: Option<&str>) -> Result<Vec<DaemonSetInfo>> {
+        self.list_daemonsets_with_options(namespace, false).await
+    }
+
+    /// List daemonsets with caching support
+    pub async fn list_daemonsets_with_options(
+        &self,
+        namespace: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Vec<DaemonSetInfo>> {
+        let mut items = self.fetch_daemonsets(namespace, use_cache).await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |d| Some(d.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_daemonsets(
+        &self,
+        namespace: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Vec<DaemonSetInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            return Ok(snapshot.daemonsets(namespace));
+        }
+
+        if use_cache {
+            if let Some(cached) = self.cache.get_daemonsets(namespace) {
+                return Ok(cached);
+            }
+        }
+
         let daemonsets: Api<DaemonSet> = match namespace {
             Some(ns) => Api::namespaced(self.client.clone(), ns),
             None => Api::all(self.client.clone()),
@@ -788,9 +1950,215 @@ impl DiscoveryEngine {
             }
         }
 
+        if use_cache {
+            self.cache
+                .set_daemonsets(namespace, daemonset_infos.clone());
+        }
+
         Ok(daemonset_infos)
     }
 
+    /// List replicasets in the specified namespace (or all namespaces if None)
+    pub async fn list_replicasets(&self, namespace: Option<&str>) -> Result<Vec<ReplicaSetInfo>> {
+        let mut items = self.fetch_replicasets(namespace).await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |r| Some(r.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_replicasets(&self, namespace: Option<&str>) -> Result<Vec<ReplicaSetInfo>> {
+        let replicasets: Api<ReplicaSet> = match namespace {
+            Some(ns) => Api::namespaced(self.client.clone(), ns),
+            None => Api::all(self.client.clone()),
+        };
+
+        let replicaset_list = replicasets.list(&Default::default()).await?;
+
+        let mut replicaset_infos = Vec::new();
+        for replicaset in replicaset_list.items {
+            if let Some(replicaset_info) = self.convert_replicaset_to_info(replicaset).await {
+                replicaset_infos.push(replicaset_info);
+            }
+        }
+
+        Ok(replicaset_infos)
+    }
+
+    /// List nodes in the cluster
+    pub async fn list_nodes(&self) -> Result<Vec<NodeInfo>> {
+        let nodes: Api<Node> = Api::all(self.client.clone());
+        let node_list = nodes.list(&Default::default()).await?;
+
+        Ok(node_list
+            .items
+            .into_iter()
+            .filter_map(convert_node_to_info)
+            .collect())
+    }
+
+    /// List persistent volumes in the cluster
+    pub async fn list_persistent_volumes(&self) -> Result<Vec<PersistentVolumeInfo>> {
+        let volumes: Api<PersistentVolume> = Api::all(self.client.clone());
+        let volume_list = volumes.list(&Default::default()).await?;
+
+        Ok(volume_list
+            .items
+            .into_iter()
+            .filter_map(convert_persistent_volume_to_info)
+            .collect())
+    }
+
+    /// List persistent volume claims in the specified namespace (or all namespaces if None)
+    pub async fn list_persistent_volume_claims(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<Vec<PersistentVolumeClaimInfo>> {
+        let mut items = self.fetch_persistent_volume_claims(namespace).await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |c| Some(c.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_persistent_volume_claims(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<Vec<PersistentVolumeClaimInfo>> {
+        let claims: Api<PersistentVolumeClaim> = match namespace {
+            Some(ns) => Api::namespaced(self.client.clone(), ns),
+            None => Api::all(self.client.clone()),
+        };
+        let claim_list = claims.list(&Default::default()).await?;
+
+        Ok(claim_list
+            .items
+            .into_iter()
+            .filter_map(convert_persistent_volume_claim_to_info)
+            .collect())
+    }
+
+    /// Pods mounting each claim, read from the pod index of the run
+    pub async fn list_claim_mounts(&self, namespace: Option<&str>) -> Result<ClaimMounts> {
+        if self.snapshot.is_some() {
+            // Snapshot indexes have no volumes; claims are only read live
+            let pods: Api<Pod> = match namespace {
+                Some(ns) => Api::namespaced(self.client.clone(), ns),
+                None => Api::all(self.client.clone()),
+            };
+            let pod_list = pods.list(&Default::default()).await?;
+            return Ok(storage::claim_mounts(&pod_list.items));
+        }
+        Ok(self.pod_index(namespace).await?.claim_mounts(namespace))
+    }
+
+    /// List service accounts in the specified namespace (or all namespaces if None)
+    pub async fn list_service_accounts(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<Vec<ServiceAccountInfo>> {
+        let accounts: Api<ServiceAccount> = match namespace {
+            Some(ns) => Api::namespaced(self.client.clone(), ns),
+            None => Api::all(self.client.clone()),
+        };
+        let mut items: Vec<ServiceAccountInfo> = accounts
+            .list(&Default::default())
+            .await?
+            .items
+            .into_iter()
+            .filter_map(convert_service_account_to_info)
+            .collect();
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |a| Some(a.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    /// List Roles in the specified namespace (or all namespaces if None)
+    pub async fn list_roles(&self, namespace: Option<&str>) -> Result<Vec<RoleInfo>> {
+        let roles: Api<Role> = match namespace {
+            Some(ns) => Api::namespaced(self.client.clone(), ns),
+            None => Api::all(self.client.clone()),
+        };
+        let mut items: Vec<RoleInfo> = roles
+            .list(&Default::default())
+            .await?
+            .items
+            .into_iter()
+            .filter_map(|role| convert_role_to_info(role.metadata, role.rules, false))
+            .collect();
+        if namespace.is_none() {
+            self.scope.retain(&mut items, |r| r.namespace.as_deref());
+        }
+        Ok(items)
+    }
+
+    /// List ClusterRoles
+    pub async fn list_cluster_roles(&self) -> Result<Vec<RoleInfo>> {
+        let roles: Api<ClusterRole> = Api::all(self.client.clone());
+        Ok(roles
+            .list(&Default::default())
+            .await?
+            .items
+            .into_iter()
+            .filter_map(|role| {
+                let aggregated = role.aggregation_rule.is_some();
+                convert_role_to_info(role.metadata, role.rules, aggregated)
+            })
+            .collect())
+    }
+
+    /// List RoleBindings in the specified namespace (or all namespaces if None)
+    pub async fn list_role_bindings(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<Vec<RoleBindingInfo>> {
+        let bindings: Api<RoleBinding> = match namespace {
+            Some(ns) => Api::namespaced(self.client.clone(), ns),
+            None => Api::all(self.client.clone()),
+        };
+        let mut items: Vec<RoleBindingInfo> = bindings
+            .list(&Default::default())
+            .await?
+            .items
+            .into_iter()
+            .filter_map(|b| {
+                convert_role_binding_to_info(
+                    b.metadata,
+                    &b.role_ref.kind,
+                    b.role_ref.name,
+                    b.subjects,
+                )
+            })
+            .collect();
+        if namespace.is_none() {
+            self.scope.retain(&mut items, |b| b.namespace.as_deref());
+        }
+        Ok(items)
+    }
+
+    /// List ClusterRoleBindings
+    pub async fn list_cluster_role_bindings(&self) -> Result<Vec<RoleBindingInfo>> {
+        let bindings: Api<ClusterRoleBinding> = Api::all(self.client.clone());
+        Ok(bindings
+            .list(&Default::default())
+            .await?
+            .items
+            .into_iter()
+            .filter_map(|b| {
+                convert_role_binding_to_info(
+                    b.metadata,
+                    &b.role_ref.kind,
+                    b.role_ref.name,
+                    b.subjects,
+                )
+            })
+            .collect())
+    }
+
     /// List configmaps in the specified namespace (or all namespaces if None)
     #[allow(dead_code)]
     pub async fn list_configmaps(&self, namespace: Option<&str>) -> Result<Vec<ConfigMapInfo>> {
@@ -806,6 +2174,29 @@ impl DiscoveryEngine {
         page_size: usize,
         use_cache: bool,
     ) -> Result<Vec<ConfigMapInfo>> {
+        let mut items = self
+            .fetch_configmaps(namespace, limit, page_size, use_cache)
+            .await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |c| Some(c.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_configmaps(
+        &self,
+        namespace: Option<&str>,
+        limit: Option<usize>,
+        page_size: usize,
+        use_cache: bool,
+    ) -> Result<Vec<ConfigMapInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            let mut configmaps = snapshot.configmaps(namespace);
+            configmaps.truncate(limit.unwrap_or(usize::MAX));
+            return Ok(configmaps);
+        }
+
         // Check cache first if enabled
         if use_cache {
             if let Some(cached) = self.cache.get_configmaps(namespace) {
@@ -822,44 +2213,50 @@ impl DiscoveryEngine {
             None => Api::all(self.client.clone()),
         };
 
-        let mut all_configmaps = Vec::new();
-        let mut continue_token: Option<String> = None;
-        let mut fetched = 0;
+        // The pod index is built (or taken from cache) while ConfigMaps are listed
+        let list = async {
+            let mut all_configmaps = Vec::new();
+            let mut continue_token: Option<String> = None;
+            let mut fetched = 0;
 
-        loop {
-            let mut list_params = kube::api::ListParams::default().limit(page_size as u32);
+            loop {
+                let mut list_params = kube::api::ListParams::default().limit(page_size as u32);
 
-            if let Some(token) = continue_token {
-                list_params = list_params.continue_token(&token);
-            }
+                if let Some(token) = continue_token {
+                    list_params = list_params.continue_token(&token);
+                }
 
-            let configmap_list = configmaps.list(&list_params).await?;
+                let configmap_list = configmaps.list(&list_params).await?;
 
-            for configmap in configmap_list.items {
-                if let Some(limit) = limit {
-                    if fetched >= limit {
-                        break;
+                for configmap in configmap_list.items {
+                    if let Some(limit) = limit {
+                        if fetched >= limit {
+                            break;
+                        }
                     }
-                }
 
-                if let Some(configmap_info) = self.convert_configmap_to_info(configmap).await {
-                    all_configmaps.push(configmap_info);
-                    fetched += 1;
+                    if let Some(configmap_info) = self.convert_configmap_to_info(configmap).await {
+                        all_configmaps.push(configmap_info);
+                        fetched += 1;
+                    }
                 }
-            }
 
-            continue_token = configmap_list.metadata.continue_;
-            if continue_token.is_none() || (limit.is_some() && fetched >= limit.unwrap()) {
-                break;
+                continue_token = configmap_list.metadata.continue_;
+                if continue_token.is_none() || (limit.is_some() && fetched >= limit.unwrap()) {
+                    break;
+                }
             }
-        }
+            Ok((all_configmaps, fetched))
+        };
+        let ((mut all_configmaps, fetched), pod_index) =
+            tokio::try_join!(list, self.pod_index(namespace))?;
 
-        // Find associations with other resources
-        self.find_configmap_associations(&mut all_configmaps)
-            .await?;
+        // Record the pods using each ConfigMap
+        pod_index.attach_configmaps(&mut all_configmaps);
 
-        // Cache the results if caching is enabled
-        if use_cache {
+        // Cache the results if caching is enabled; a listing --limit cut
+        // short is not stored where a full listing would be read from
+        if use_cache && limit.is_none_or(|limit| fetched < limit) {
             self.cache.set_configmaps(namespace, all_configmaps.clone());
         }
 
@@ -868,12 +2265,48 @@ impl DiscoveryEngine {
 
     /// List secrets in the specified namespace (or all namespaces if None)
     pub async fn list_secrets(&self, namespace: Option<&str>) -> Result<Vec<SecretInfo>> {
+        self.list_secrets_with_options(namespace, false).await
+    }
+
+    /// List secrets with caching support
+    pub async fn list_secrets_with_options(
+        &self,
+        namespace: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Vec<SecretInfo>> {
+        let mut items = self.fetch_secrets(namespace, use_cache).await?;
+        if namespace.is_none() {
+            self.scope
+                .retain(&mut items, |s| Some(s.namespace.as_str()));
+        }
+        Ok(items)
+    }
+
+    async fn fetch_secrets(
+        &self,
+        namespace: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Vec<SecretInfo>> {
+        if let Some(snapshot) = &self.snapshot {
+            return Ok(snapshot.secrets(namespace));
+        }
+
+        if use_cache {
+            if let Some(cached) = self.cache.get_secrets(namespace) {
+                return Ok(cached);
+            }
+        }
+
         let secrets: Api<Secret> = match namespace {
             Some(ns) => Api::namespaced(self.client.clone(), ns),
             None => Api::all(self.client.clone()),
         };
 
-        let secret_list = secrets.list(&Default::default()).await?;
+        // The pod index is built (or taken from cache) while secrets are listed
+        let (secret_list, pod_index) = tokio::try_join!(
+            async { Ok(secrets.list(&Default::default()).await?) },
+            self.pod_index(namespace),
+        )?;
 
         let mut secret_infos = Vec::new();
         for secret in secret_list.items {
@@ -882,65 +2315,707 @@ impl DiscoveryEngine {
             }
         }
 
-        // Find associations with other resources
-        self.find_secret_associations(&mut secret_infos).await?;
+        // Record the pods using each Secret
+        pod_index.attach_secrets(&mut secret_infos);
+
+        if use_cache {
+            self.cache.set_secrets(namespace, secret_infos.clone());
+        }
+
+        Ok(secret_infos)
+    }
+
+    /// List Custom Resource Definitions in the cluster
+    pub async fn list_crds(&self) -> Result<Vec<CRDInfo>> {
+        self.list_crds_with_options(false).await
+    }
+
+    /// List Custom Resource Definitions with caching support
+    pub async fn list_crds_with_options(&self, use_cache: bool) -> Result<Vec<CRDInfo>> {
+        if use_cache {
+            if let Some(cached) = self.cache.get_crds() {
+                return Ok(cached);
+            }
+        }
+
+        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+
+        let crd_list = crds.list(&Default::default()).await?;
+        let (candidates, service_selectors) = self.controller_candidates().await?;
+
+        let mut crd_infos = Vec::new();
+        for crd in crd_list.items {
+            let hints = CrdHints::from_crd(&crd);
+            if let Some(mut crd_info) = self.convert_crd_to_info(crd).await {
+                crd_info.controller =
+                    controllers::attribute(&hints, &candidates, &service_selectors);
+                crd_infos.push(crd_info);
+            }
+        }
+
+        // Find instance counts for each CRD
+        self.find_crd_instance_counts(&mut crd_infos).await?;
+
+        if use_cache {
+            self.cache.set_crds(crd_infos.clone());
+        }
+
+        Ok(crd_infos)
+    }
+
+    /// Deployments and StatefulSets that may serve CRDs, with the selectors of
+    /// all services for matching conversion webhooks. Kinds the caller may not
+    /// list are left out.
+    async fn controller_candidates(
+        &self,
+    ) -> Result<(
+        Vec<ControllerCandidate>,
+        BTreeMap<(String, String), BTreeMap<String, String>>,
+    )> {
+        fn candidate(
+            kind: &str,
+            metadata: ObjectMeta,
+            pod_labels: Option<BTreeMap<String, String>>,
+        ) -> ControllerCandidate {
+            ControllerCandidate {
+                kind: kind.to_string(),
+                namespace: metadata.namespace.unwrap_or_default(),
+                name: metadata.name.unwrap_or_default(),
+                labels: metadata.labels.unwrap_or_default(),
+                annotations: metadata.annotations.unwrap_or_default(),
+                pod_labels: pod_labels.unwrap_or_default(),
+            }
+        }
+
+        let mut candidates = Vec::new();
+
+        match Api::<Deployment>::all(self.client.clone())
+            .list(&Default::default())
+            .await
+        {
+            Ok(list) => candidates.extend(list.items.into_iter().map(|d| {
+                let pod_labels = d
+                    .spec
+                    .and_then(|s| s.template.metadata)
+                    .and_then(|m| m.labels);
+                candidate("Deployment", d.metadata, pod_labels)
+            })),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => {}
+            Err(e) => return Err(e.into()),
+        }
+        match Api::<StatefulSet>::all(self.client.clone())
+            .list(&Default::default())
+            .await
+        {
+            Ok(list) => candidates.extend(list.items.into_iter().map(|s| {
+                let pod_labels = s
+                    .spec
+                    .and_then(|s| s.template.metadata)
+                    .and_then(|m| m.labels);
+                candidate("StatefulSet", s.metadata, pod_labels)
+            })),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut service_selectors = BTreeMap::new();
+        match Api::<Service>::all(self.client.clone())
+            .list(&Default::default())
+            .await
+        {
+            Ok(list) => {
+                for service in list.items {
+                    let selector = service.spec.and_then(|s| s.selector).unwrap_or_default();
+                    service_selectors.insert(
+                        (
+                            service.metadata.namespace.unwrap_or_default(),
+                            service.metadata.name.unwrap_or_default(),
+                        ),
+                        selector,
+                    );
+                }
+            }
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok((candidates, service_selectors))
+    }
+
+    /// List Custom Resource instances for a specific CRD
+    pub async fn list_custom_resources(
+        &self,
+        crd_name: &str,
+        namespace: Option<&str>,
+    ) -> Result<Vec<CustomResourceInfo>> {
+        let mut items = self.fetch_custom_resources(crd_name, namespace).await?;
+        if namespace.is_none() {
+            self.scope.retain(&mut items, |r| r.namespace.as_deref());
+        }
+        Ok(items)
+    }
+
+    async fn fetch_custom_resources(
+        &self,
+        crd_name: &str,
+        namespace: Option<&str>,
+    ) -> Result<Vec<CustomResourceInfo>> {
+        // First, get the CRD to understand its structure
+        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+        let crd = match crds.get(crd_name).await {
+            Ok(crd) => crd,
+            Err(_) => return Ok(Vec::new()), // CRD doesn't exist
+        };
+
+        self.list_crd_instances(&crd, namespace).await
+    }
+
+    /// List custom resources of every CRD concurrently, one entry per CRD
+    /// sorted by CRD name. CRDs whose instances cannot be listed are reported
+    /// and left out.
+    pub async fn list_all_custom_resources(
+        &self,
+        namespace: Option<&str>,
+        concurrency_limit: usize,
+    ) -> Result<Vec<CrdInstances>> {
+        self.list_all_custom_resources_with_options(namespace, concurrency_limit, false)
+            .await
+    }
+
+    /// List custom resources of every CRD with caching support, keyed per CRD
+    pub async fn list_all_custom_resources_with_options(
+        &self,
+        namespace: Option<&str>,
+        concurrency_limit: usize,
+        use_cache: bool,
+    ) -> Result<Vec<CrdInstances>> {
+        let started = Instant::now();
+        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+        let crd_list = crds.list(&Default::default()).await?;
+
+        let mut join_set = JoinSet::new();
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency_limit));
+        for crd in crd_list.items {
+            let engine = self.clone();
+            let namespace = namespace.map(|ns| ns.to_string());
+            let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+            join_set.spawn(async move {
+                let _permit = permit;
+                let crd_name = crd.metadata.name.clone().unwrap_or_default();
+                let namespace = namespace.as_deref();
+                if use_cache {
+                    if let Some(cached) = engine.cache.get_custom_resources(&crd_name, namespace) {
+                        return (crd, Ok(cached));
+                    }
+                }
+                let result = engine.list_crd_instances(&crd, namespace).await;
+                if let (true, Ok(items)) = (use_cache, &result) {
+                    engine
+                        .cache
+                        .set_custom_resources(&crd_name, namespace, items.clone());
+                }
+                (crd, result)
+            });
+        }
+
+        let mut all = Vec::new();
+        while let Some(result) = join_set.join_next().await {
+            match result {
+                Ok((crd, Ok(items))) => all.push(CrdInstances {
+                    crd_name: crd.metadata.name.unwrap_or_default(),
+                    group: crd.spec.group,
+                    kind: crd.spec.names.kind,
+                    scope: crd.spec.scope,
+                    instance_count: items.len(),
+                    items,
+                }),
+                Ok((crd, Err(e))) => {
+                    let message = format!(
+                        "Failed to list instances of CRD '{}': {}",
+                        crd.metadata.name.unwrap_or_default(),
+                        e
+                    );
+                    eprintln!("Warning: {}", message);
+                    self.warnings.warn_incomplete(message);
+                }
+                Err(e) => {
+                    eprintln!("Warning: Task failed: {}", e);
+                    self.warnings.warn_incomplete(format!("Task failed: {}", e));
+                }
+            }
+        }
+
+        if namespace.is_none() {
+            for crd in &mut all {
+                self.scope
+                    .retain(&mut crd.items, |r| r.namespace.as_deref());
+                crd.instance_count = crd.items.len();
+            }
+        }
+        all.sort_by(|a, b| a.crd_name.cmp(&b.crd_name));
+        self.stats.record_phase("fetch custom resources", started);
+        Ok(all)
+    }
+
+    /// List the instances of a CRD at its storage version. Instances the
+    /// caller may not list, or of versions no longer served, come back empty.
+    async fn list_crd_instances(
+        &self,
+        crd: &CustomResourceDefinition,
+        namespace: Option<&str>,
+    ) -> Result<Vec<CustomResourceInfo>> {
+        let crd_name = crd.metadata.name.clone().unwrap_or_default();
+        let version = crd
+            .spec
+            .versions
+            .iter()
+            .find(|v| v.storage)
+            .or_else(|| crd.spec.versions.first())
+            .map(|v| v.name.clone())
+            .unwrap_or_else(|| "v1".to_string());
+
+        let objects = match self.list_dynamic_objects(crd, &version, namespace).await {
+            Ok(objects) => objects,
+            Err(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                if (403..=405).contains(&e.code) =>
+            {
+                return Ok(Vec::new())
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(objects
+            .into_iter()
+            .map(|object| CustomResourceInfo {
+                name: object.metadata.name.unwrap_or_default(),
+                namespace: object.metadata.namespace,
+                crd_name: crd_name.clone(),
+                group: crd.spec.group.clone(),
+                version: version.clone(),
+                kind: crd.spec.names.kind.clone(),
+                age: format_age(object.metadata.creation_timestamp.as_ref().map(|t| t.0)),
+                labels: object.metadata.labels.unwrap_or_default(),
+                annotations: object.metadata.annotations.unwrap_or_default(),
+                spec_summary: summarize_spec(object.data.get("spec")),
+                status_summary: summarize_status(object.data.get("status")),
+                related_resources: Vec::new(),
+                created_at: object.metadata.creation_timestamp.map(|t| t.0),
+            })
+            .collect())
+    }
+
+    /// Fetch the pod spec of a pod or the pod template of a workload
+    pub async fn get_pod_template(&self, target: &ObjectRef, namespace: &str) -> Result<PodSpec> {
+        let name = target.name.as_str();
+        let not_found = || ExplorerError::ResourceNotFound {
+            kind: target.kind.as_str().to_string(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+        };
+
+        let spec = match target.kind {
+            ResourceKind::Pod => {
+                let api: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+                api.get_opt(name).await?.ok_or_else(not_found)?.spec
+            }
+            ResourceKind::Deployment => {
+                let api: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+                let deployment = api.get_opt(name).await?.ok_or_else(not_found)?;
+                deployment.spec.and_then(|s| s.template.spec)
+            }
+            ResourceKind::StatefulSet => {
+                let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+                let statefulset = api.get_opt(name).await?.ok_or_else(not_found)?;
+                statefulset.spec.and_then(|s| s.template.spec)
+            }
+            ResourceKind::DaemonSet => {
+                let api: Api<DaemonSet> = Api::namespaced(self.client.clone(), namespace);
+                let daemonset = api.get_opt(name).await?.ok_or_else(not_found)?;
+                daemonset.spec.and_then(|s| s.template.spec)
+            }
+            ResourceKind::ReplicaSet => {
+                let api: Api<ReplicaSet> = Api::namespaced(self.client.clone(), namespace);
+                let replicaset = api.get_opt(name).await?.ok_or_else(not_found)?;
+                replicaset
+                    .spec
+                    .and_then(|s| s.template)
+                    .and_then(|t| t.spec)
+            }
+            ResourceKind::Job => {
+                let api: Api<Job> = Api::namespaced(self.client.clone(), namespace);
+                let job = api.get_opt(name).await?.ok_or_else(not_found)?;
+                job.spec.and_then(|s| s.template.spec)
+            }
+            ResourceKind::CronJob => {
+                let api: Api<CronJob> = Api::namespaced(self.client.clone(), namespace);
+                let cronjob = api.get_opt(name).await?.ok_or_else(not_found)?;
+                cronjob
+                    .spec
+                    .and_then(|s| s.job_template.spec)
+                    .and_then(|s| s.template.spec)
+            }
+            ResourceKind::Service => {
+                return Err(ExplorerError::InvalidArgument(
+                    "services have no pod template".to_string(),
+                ))
+            }
+        };
+
+        spec.ok_or_else(not_found)
+    }
+
+    /// Fetch the ConfigMap data and Secret key names referenced by a pod spec.
+    ///
+    /// Sources that are missing or cannot be read are left out of the result.
+    pub async fn get_env_sources(&self, spec: &PodSpec, namespace: &str) -> EnvSources {
+        let (configmap_names, secret_names) = crate::env::referenced_sources(spec);
+        let mut sources = EnvSources::default();
+
+        let configmaps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        for name in configmap_names {
+            if let Ok(Some(configmap)) = configmaps.get_opt(&name).await {
+                sources
+                    .configmaps
+                    .insert(name, configmap.data.unwrap_or_default());
+            }
+        }
+
+        let secrets: Api<Secret> = Api::namespaced(self.client.clone(), namespace);
+        for name in secret_names {
+            if let Ok(Some(secret)) = secrets.get_opt(&name).await {
+                let keys = secret
+                    .data
+                    .unwrap_or_default()
+                    .into_keys()
+                    .chain(secret.string_data.unwrap_or_default().into_keys())
+                    .collect();
+                sources.secrets.insert(name, keys);
+            }
+        }
+
+        sources
+    }
+
+    /// Fetch a single CRD by name
+    pub async fn get_crd(&self, crd_name: &str) -> Result<CustomResourceDefinition> {
+        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+        crds.get_opt(crd_name)
+            .await?
+            .ok_or_else(|| ExplorerError::ResourceNotFound {
+                kind: "CustomResourceDefinition".to_string(),
+                name: crd_name.to_string(),
+                namespace: "cluster".to_string(),
+            })
+    }
+
+    /// List all CustomResourceDefinitions as returned by the API server
+    pub async fn list_crd_definitions(&self) -> Result<Vec<CustomResourceDefinition>> {
+        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+        Ok(crds.list(&Default::default()).await?.items)
+    }
+
+    /// List the metadata of all instances of a CRD at the given version, or
+    /// `None` if the caller may not list them
+    pub async fn list_crd_instance_metadata(
+        &self,
+        crd: &CustomResourceDefinition,
+        version: &str,
+    ) -> Result<Option<Vec<ObjectMeta>>> {
+        let gvk = GroupVersionKind::gvk(&crd.spec.group, version, &crd.spec.names.kind);
+        let resource = ApiResource::from_gvk_with_plural(&gvk, &crd.spec.names.plural);
+        let api: Api<DynamicObject> = Api::all_with(self.client.clone(), &resource);
+
+        match api.list_metadata(&Default::default()).await {
+            Ok(list) => Ok(Some(list.items.into_iter().map(|i| i.metadata).collect())),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// List raw instances of a CRD at the given version
+    pub async fn list_dynamic_objects(
+        &self,
+        crd: &CustomResourceDefinition,
+        version: &str,
+        namespace: Option<&str>,
+    ) -> Result<Vec<DynamicObject>> {
+        let gvk = GroupVersionKind::gvk(&crd.spec.group, version, &crd.spec.names.kind);
+        let resource = ApiResource::from_gvk_with_plural(&gvk, &crd.spec.names.plural);
+
+        let api: Api<DynamicObject> = match namespace {
+            Some(ns) if crd.spec.scope == "Namespaced" => {
+                Api::namespaced_with(self.client.clone(), ns, &resource)
+            }
+            _ => Api::all_with(self.client.clone(), &resource),
+        };
+
+        Ok(api.list(&Default::default()).await?.items)
+    }
+
+    /// Collect the workloads and config keys of a namespace for cross-cluster comparison
+    pub async fn get_namespace_inventory(&self, namespace: &str) -> Result<NamespaceInventory> {
+        let mut inventory = NamespaceInventory::default();
+        let params = Default::default();
+        let key = |kind: &str, meta: &ObjectMeta| {
+            (kind.to_string(), meta.name.clone().unwrap_or_default())
+        };
+
+        let deployments: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+        for d in deployments.list(&params).await?.items {
+            let spec = d.spec.as_ref();
+            let summary = WorkloadSummary::new(
+                Some(spec.and_then(|s| s.replicas).unwrap_or(1)),
+                spec.and_then(|s| s.template.spec.as_ref()),
+            );
+            inventory
+                .workloads
+                .insert(key("Deployment", &d.metadata), summary);
+        }
+
+        let statefulsets: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+        for s in statefulsets.list(&params).await?.items {
+            let spec = s.spec.as_ref();
+            let summary = WorkloadSummary::new(
+                Some(spec.and_then(|s| s.replicas).unwrap_or(1)),
+                spec.and_then(|s| s.template.spec.as_ref()),
+            );
+            inventory
+                .workloads
+                .insert(key("StatefulSet", &s.metadata), summary);
+        }
+
+        let daemonsets: Api<DaemonSet> = Api::namespaced(self.client.clone(), namespace);
+        for d in daemonsets.list(&params).await?.items {
+            let summary =
+                WorkloadSummary::new(None, d.spec.as_ref().and_then(|s| s.template.spec.as_ref()));
+            inventory
+                .workloads
+                .insert(key("DaemonSet", &d.metadata), summary);
+        }
+
+        let configmaps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        for cm in configmaps.list(&params).await?.items {
+            let keys = cm
+                .data
+                .unwrap_or_default()
+                .into_keys()
+                .chain(cm.binary_data.unwrap_or_default().into_keys())
+                .collect();
+            inventory
+                .config
+                .insert(key("ConfigMap", &cm.metadata), keys);
+        }
+
+        let secrets: Api<Secret> = Api::namespaced(self.client.clone(), namespace);
+        for secret in secrets.list(&params).await?.items {
+            // Service account tokens differ per cluster by design
+            if secret.type_.as_deref() == Some("kubernetes.io/service-account-token") {
+                continue;
+            }
+            let keys = secret.data.unwrap_or_default().into_keys().collect();
+            inventory
+                .config
+                .insert(key("Secret", &secret.metadata), keys);
+        }
+
+        Ok(inventory)
+    }
+
+    /// List the annotations of workloads, pods, services, ConfigMaps and Secrets.
+    ///
+    /// Only object metadata is requested, so Secret data is never fetched.
+    pub async fn list_annotated_objects(
+        &self,
+        namespace: Option<&str>,
+        kinds: &[ResourceKind],
+    ) -> Result<Vec<AnnotatedObject>> {
+        let mut resources: Vec<(ApiResource, Scope)> = kinds
+            .iter()
+            .map(|k| (k.api_resource(), Scope::Namespaced))
+            .collect();
+        if kinds.is_empty() {
+            resources = ResourceKind::ALL
+                .iter()
+                .map(|k| (k.api_resource(), Scope::Namespaced))
+                .collect();
+            resources.push((ApiResource::erase::<ConfigMap>(&()), Scope::Namespaced));
+            resources.push((ApiResource::erase::<Secret>(&()), Scope::Namespaced));
+        }
+
+        let objects = self
+            .list_object_metadata(namespace, &resources)
+            .await?
+            .into_iter()
+            .map(|(kind, metadata)| AnnotatedObject {
+                kind,
+                namespace: metadata.namespace,
+                name: metadata.name.unwrap_or_default(),
+                annotations: metadata.annotations.unwrap_or_default(),
+            })
+            .collect();
 
-        Ok(secret_infos)
+        Ok(objects)
     }
 
-    /// List Custom Resource Definitions in the cluster
-    pub async fn list_crds(&self) -> Result<Vec<CRDInfo>> {
-        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+    /// Index every namespaced object in `namespace` by the owners its
+    /// ownerReferences name. Events are left out: nothing owns them, and
+    /// there are many.
+    pub async fn owner_index(&self, namespace: &str) -> Result<OwnerIndex> {
+        let resources: Vec<(ApiResource, Scope)> = self
+            .discover_listable_resources()
+            .await?
+            .resources
+            .into_iter()
+            .filter(|(resource, scope)| {
+                matches!(scope, Scope::Namespaced) && resource.kind != "Event"
+            })
+            .collect();
+        let objects = self
+            .list_object_metadata(Some(namespace), &resources)
+            .await?;
+        Ok(OwnerIndex::from_objects(objects))
+    }
 
-        let crd_list = crds.list(&Default::default()).await?;
+    /// List full objects of the given kinds as JSON, for checks that address
+    /// arbitrary fields. Kinds the caller may not list are skipped.
+    pub async fn list_objects_json(
+        &self,
+        namespace: Option<&str>,
+        kinds: &[ResourceKind],
+    ) -> Result<Vec<(ResourceKind, serde_json::Value)>> {
+        let mut objects = Vec::new();
+
+        for kind in kinds {
+            let resource = kind.api_resource();
+            let api: Api<DynamicObject> = match namespace {
+                Some(ns) => Api::namespaced_with(self.client.clone(), ns, &resource),
+                None => Api::all_with(self.client.clone(), &resource),
+            };
 
-        let mut crd_infos = Vec::new();
-        for crd in crd_list.items {
-            if let Some(crd_info) = self.convert_crd_to_info(crd).await {
-                crd_infos.push(crd_info);
+            let list = match api.list(&Default::default()).await {
+                Ok(list) => list,
+                Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for item in list.items {
+                objects.push((*kind, serde_json::to_value(item)?));
             }
         }
 
-        // Find instance counts for each CRD
-        self.find_crd_instance_counts(&mut crd_infos).await?;
+        Ok(objects)
+    }
 
-        Ok(crd_infos)
+    /// List object metadata for each resource, paired with the resource kind.
+    ///
+    /// Cluster-scoped resources are listed cluster-wide regardless of `namespace`.
+    /// Resources the caller may not list (403) or that the server does not serve
+    /// (404, 405) are skipped.
+    pub async fn list_object_metadata(
+        &self,
+        namespace: Option<&str>,
+        resources: &[(ApiResource, Scope)],
+    ) -> Result<Vec<(String, ObjectMeta)>> {
+        self.list_object_metadata_with(namespace, resources, &ListParams::default())
+            .await
     }
 
-    /// List Custom Resource instances for a specific CRD
-    pub async fn list_custom_resources(
+    /// Like [`Self::list_object_metadata`], narrowed by label or field
+    /// selectors in `params`
+    pub async fn list_object_metadata_with(
         &self,
-        crd_name: &str,
         namespace: Option<&str>,
-    ) -> Result<Vec<CustomResourceInfo>> {
-        // First, get the CRD to understand its structure
-        let crds: Api<CustomResourceDefinition> = Api::all(self.client.clone());
-        let crd = match crds.get(crd_name).await {
-            Ok(crd) => crd,
-            Err(_) => return Ok(Vec::new()), // CRD doesn't exist
-        };
+        resources: &[(ApiResource, Scope)],
+        params: &ListParams,
+    ) -> Result<Vec<(String, ObjectMeta)>> {
+        let mut objects = Vec::new();
+
+        for (resource, scope) in resources {
+            let api: Api<DynamicObject> = match (scope, namespace) {
+                (Scope::Namespaced, Some(ns)) => {
+                    Api::namespaced_with(self.client.clone(), ns, resource)
+                }
+                _ => Api::all_with(self.client.clone(), resource),
+            };
 
-        // Extract CRD metadata for custom resource discovery
-        let spec = crd.spec;
-        let group = spec.group;
-        let names = spec.names;
-        let plural = names.plural;
+            let list = match api.list_metadata(params).await {
+                Ok(list) => list,
+                Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            objects.extend(
+                list.items
+                    .into_iter()
+                    .map(|item| (resource.kind.clone(), item.metadata)),
+            );
+        }
 
-        // Get the preferred version (storage version)
-        let version = spec
-            .versions
-            .iter()
-            .find(|v| v.storage)
-            .or_else(|| spec.versions.first())
-            .map(|v| v.name.clone())
-            .unwrap_or_else(|| "v1".to_string());
+        Ok(objects)
+    }
 
-        // Use dynamic client to list custom resources
-        let custom_resources = self
-            .list_dynamic_resources(&group, &version, &plural, namespace)
-            .await?;
+    /// Every resource type the API server can list, using preferred versions.
+    ///
+    /// Groups are queried one at a time so that an unavailable aggregated API
+    /// is reported as a failure instead of aborting the whole discovery.
+    pub async fn discover_listable_resources(&self) -> Result<ResourceDiscovery> {
+        let mut discovery = ResourceDiscovery::default();
+
+        let mut group_versions = vec!["v1".to_string()];
+        for group in self.client.list_api_groups().await?.groups {
+            let preferred = group
+                .preferred_version
+                .or_else(|| group.versions.into_iter().next());
+            if let Some(version) = preferred {
+                group_versions.push(version.group_version);
+            }
+        }
+
+        for group_version in group_versions {
+            let list = if group_version == "v1" {
+                self.client.list_core_api_resources("v1").await
+            } else {
+                self.client.list_api_group_resources(&group_version).await
+            };
+            let list = match list {
+                Ok(list) => list,
+                Err(e) => {
+                    discovery.failures.push(DiscoveryFailure {
+                        group_version,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            for resource in list.resources {
+                // Subresources such as pods/log are not listable objects
+                if resource.name.contains('/') || !resource.verbs.iter().any(|v| v == "list") {
+                    continue;
+                }
+                let (group, version) = match group_version.split_once('/') {
+                    Some((group, version)) => (group, version),
+                    None => ("", group_version.as_str()),
+                };
+                for short_name in resource.short_names.iter().flatten() {
+                    discovery
+                        .short_names
+                        .entry(short_name.to_lowercase())
+                        .or_insert_with(|| (group.to_string(), resource.name.clone()));
+                }
+                let gvk = GroupVersionKind::gvk(group, version, &resource.kind);
+                let scope = if resource.namespaced {
+                    Scope::Namespaced
+                } else {
+                    Scope::Cluster
+                };
+                discovery.resources.push((
+                    ApiResource::from_gvk_with_plural(&gvk, &resource.name),
+                    scope,
+                ));
+            }
+        }
 
-        Ok(custom_resources)
+        Ok(discovery)
     }
 
     /// Check the health of a service by testing its cluster IP endpoints
@@ -976,7 +3051,7 @@ impl DiscoveryEngine {
                 .to_string(),
         })
     }
-    async fn convert_service_to_info(&self, service: Service) -> Option<ServiceInfo> {
+    pub async fn convert_service_to_info(&self, service: Service) -> Option<ServiceInfo> {
         let metadata = service.metadata;
         let spec = service.spec?;
 
@@ -1006,13 +3081,17 @@ impl DiscoveryEngine {
             cluster_ip: spec.cluster_ip,
             service_type: spec.type_.unwrap_or_else(|| "ClusterIP".to_string()),
             selector: spec.selector,
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
         })
     }
 
-    async fn convert_pod_to_info(&self, pod: Pod) -> Option<PodInfo> {
+    pub async fn convert_pod_to_info(&self, pod: Pod) -> Option<PodInfo> {
         let metadata = pod.metadata;
         let spec = pod.spec?;
         let status = pod.status;
+        let (owner_kind, owner_name) = controller_owner(&metadata);
+        let created_at = metadata.creation_timestamp.as_ref().map(|t| t.0);
 
         let name = metadata.name?;
         let namespace = metadata.namespace.unwrap_or_else(|| "default".to_string());
@@ -1024,6 +3103,13 @@ impl DiscoveryEngine {
 
         let pod_ip = status.as_ref().and_then(|s| s.pod_ip.clone());
 
+        let (ready_containers, total_containers, restart_count) =
+            container_counts(&spec, status.as_ref());
+        let qos_class = pod_qos_class(&spec, status.as_ref());
+        let labels = metadata.labels.unwrap_or_default();
+        let mesh = crate::mesh::detect_sidecar(&spec);
+        let annotations = summary_annotations(metadata.annotations.as_ref());
+        let mesh_injection = crate::mesh::injection_setting(&annotations, &labels);
         let node_name = spec.node_name;
 
         Some(PodInfo {
@@ -1032,11 +3118,20 @@ impl DiscoveryEngine {
             phase,
             pod_ip,
             node_name,
-            labels: metadata.labels.unwrap_or_default(),
-            ready_containers: 0,        // TODO: Calculate from container statuses
-            total_containers: 0,        // TODO: Calculate from spec.containers
-            restart_count: 0,           // TODO: Calculate from container statuses
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            labels,
+            ready_containers,
+            total_containers,
+            restart_count,
+            age: format_age(created_at),
+            created_at,
+            annotations,
+            qos_class,
+            priority_class: spec.priority_class_name,
+            priority: spec.priority,
+            owner_kind,
+            owner_name,
+            mesh,
+            mesh_injection,
         })
     }
 
@@ -1106,8 +3201,12 @@ impl DiscoveryEngine {
         }
     }
 
-    async fn convert_deployment_to_info(&self, deployment: Deployment) -> Option<DeploymentInfo> {
+    pub async fn convert_deployment_to_info(
+        &self,
+        deployment: Deployment,
+    ) -> Option<DeploymentInfo> {
         let metadata = deployment.metadata;
+        let age = object_age(&metadata);
         let spec = deployment.spec?;
         let status = deployment.status;
 
@@ -1129,6 +3228,7 @@ impl DiscoveryEngine {
             .unwrap_or(&"RollingUpdate".to_string())
             .clone();
 
+        let images = container_images(spec.template.spec.as_ref());
         let selector = spec.selector.match_labels.unwrap_or_default();
 
         Some(DeploymentInfo {
@@ -1138,17 +3238,22 @@ impl DiscoveryEngine {
             ready_replicas,
             available_replicas,
             strategy,
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            age,
             labels,
             selector,
+            images,
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
         })
     }
 
-    async fn convert_statefulset_to_info(
+    pub async fn convert_statefulset_to_info(
         &self,
         statefulset: StatefulSet,
     ) -> Option<StatefulSetInfo> {
+        let volume_claim_templates = statefulset::claim_templates(&statefulset);
         let metadata = statefulset.metadata;
+        let age = object_age(&metadata);
         let spec = statefulset.spec?;
         let status = statefulset.status;
 
@@ -1163,6 +3268,7 @@ impl DiscoveryEngine {
             .and_then(|s| s.current_replicas)
             .unwrap_or(0);
 
+        let images = container_images(spec.template.spec.as_ref());
         let selector = spec.selector.match_labels.unwrap_or_default();
 
         Some(StatefulSetInfo {
@@ -1171,14 +3277,21 @@ impl DiscoveryEngine {
             replicas,
             ready_replicas,
             current_replicas,
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            age,
             labels,
             selector,
+            images,
+            volume_claim_templates,
+            update_revision: status.and_then(|s| s.update_revision),
+            ordinals: Vec::new(),
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
         })
     }
 
-    async fn convert_daemonset_to_info(&self, daemonset: DaemonSet) -> Option<DaemonSetInfo> {
+    pub async fn convert_daemonset_to_info(&self, daemonset: DaemonSet) -> Option<DaemonSetInfo> {
         let metadata = daemonset.metadata;
+        let age = object_age(&metadata);
         let spec = daemonset.spec?;
         let status = daemonset.status;
 
@@ -1200,6 +3313,7 @@ impl DiscoveryEngine {
             .and_then(|s| s.updated_number_scheduled)
             .unwrap_or(0);
 
+        let images = container_images(spec.template.spec.as_ref());
         let selector = spec.selector.match_labels.unwrap_or_default();
 
         Some(DaemonSetInfo {
@@ -1209,14 +3323,80 @@ impl DiscoveryEngine {
             current,
             ready,
             up_to_date,
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            age,
             labels,
             selector,
+            images,
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
+        })
+    }
+
+    pub async fn convert_replicaset_to_info(
+        &self,
+        replicaset: ReplicaSet,
+    ) -> Option<ReplicaSetInfo> {
+        let metadata = replicaset.metadata;
+        let age = object_age(&metadata);
+        let created_at = metadata.creation_timestamp.as_ref().map(|t| t.0);
+        let spec = replicaset.spec?;
+        let status = replicaset.status;
+
+        let (owner_kind, owner_name) = controller_owner(&metadata);
+        let deployment = owner_name.filter(|_| owner_kind.as_deref() == Some("Deployment"));
+
+        let name = metadata.name?;
+        let namespace = metadata.namespace.unwrap_or_else(|| "default".to_string());
+        let labels = metadata.labels.unwrap_or_default();
+        let annotations = metadata.annotations.unwrap_or_default();
+
+        let revision = annotations
+            .get("deployment.kubernetes.io/revision")
+            .and_then(|r| r.parse().ok());
+        let revision_history = annotations
+            .get("deployment.kubernetes.io/revision-history")
+            .map(|history| {
+                history
+                    .split(',')
+                    .filter_map(|r| r.trim().parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let images = spec
+            .template
+            .as_ref()
+            .and_then(|t| t.spec.as_ref())
+            .map(|s| {
+                s.containers
+                    .iter()
+                    .filter_map(|c| c.image.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(ReplicaSetInfo {
+            name,
+            namespace,
+            desired: spec.replicas.unwrap_or(1),
+            current: status.as_ref().map(|s| s.replicas).unwrap_or(0),
+            ready: status.as_ref().and_then(|s| s.ready_replicas).unwrap_or(0),
+            deployment,
+            revision,
+            revision_history,
+            pod_template_hash: labels.get("pod-template-hash").cloned(),
+            change_cause: annotations.get("kubernetes.io/change-cause").cloned(),
+            images,
+            age,
+            created_at,
+            annotations: summary_annotations(Some(&annotations)),
+            labels,
         })
     }
 
-    async fn convert_configmap_to_info(&self, configmap: ConfigMap) -> Option<ConfigMapInfo> {
+    pub async fn convert_configmap_to_info(&self, configmap: ConfigMap) -> Option<ConfigMapInfo> {
         let metadata = configmap.metadata;
+        let age = object_age(&metadata);
         let data = configmap.data.unwrap_or_default();
 
         let name = metadata.name?;
@@ -1229,15 +3409,18 @@ impl DiscoveryEngine {
             name,
             namespace,
             data_keys,
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            age,
             labels,
-            used_by: Vec::new(),     // Will be populated by association finding
-            mount_paths: Vec::new(), // Will be populated by association finding
+            used_by: Vec::new(),     // Populated from pod specs by the caller
+            mount_paths: Vec::new(), // Populated from pod specs by the caller
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
         })
     }
 
-    async fn convert_secret_to_info(&self, secret: Secret) -> Option<SecretInfo> {
+    pub async fn convert_secret_to_info(&self, secret: Secret) -> Option<SecretInfo> {
         let metadata = secret.metadata;
+        let age = object_age(&metadata);
         let data = secret.data.unwrap_or_default();
 
         let name = metadata.name?;
@@ -1246,97 +3429,68 @@ impl DiscoveryEngine {
         let secret_type = secret.type_.unwrap_or_else(|| "Opaque".to_string());
 
         let data_keys: Vec<String> = data.keys().cloned().collect();
+        let service_account = metadata
+            .annotations
+            .as_ref()
+            .and_then(|a| a.get("kubernetes.io/service-account.name"))
+            .cloned();
 
         Some(SecretInfo {
             name,
             namespace,
             secret_type,
             data_keys,
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            age,
             labels,
-            used_by: Vec::new(),     // Will be populated by association finding
-            mount_paths: Vec::new(), // Will be populated by association finding
+            used_by: Vec::new(),     // Populated from pod specs by the caller
+            mount_paths: Vec::new(), // Populated from pod specs by the caller
+            service_account,
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
         })
     }
 
-    async fn find_configmap_associations(&self, configmaps: &mut [ConfigMapInfo]) -> Result<()> {
-        // Find all pods that reference these ConfigMaps
-        let pods = self.list_pods(None, None).await?;
-
-        for configmap in configmaps.iter_mut() {
-            for pod in &pods {
-                self.check_pod_configmap_references(pod, configmap);
-            }
+    /// Index of the pods in the specified namespace (or all namespaces if
+    /// None), built once and shared by the association passes of a run. An
+    /// index of all namespaces answers for any single namespace.
+    pub async fn pod_index(&self, namespace: Option<&str>) -> Result<Arc<PodIndex>> {
+        if let Some(snapshot) = &self.snapshot {
+            let pods = snapshot.pods(namespace, None)?;
+            return Ok(Arc::new(PodIndex::from_infos(pods)));
         }
 
-        Ok(())
-    }
-
-    async fn find_secret_associations(&self, secrets: &mut [SecretInfo]) -> Result<()> {
-        // Find all pods that reference these Secrets
-        let pods = self.list_pods(None, None).await?;
-
-        for secret in secrets.iter_mut() {
-            for pod in &pods {
-                self.check_pod_secret_references(pod, secret);
-            }
+        let cached = || {
+            namespace
+                .and_then(|ns| self.cache.get_pod_index(Some(ns)))
+                .or_else(|| self.cache.get_pod_index(None))
+        };
+        if let Some(index) = cached() {
+            return Ok(index);
         }
 
-        Ok(())
-    }
-
-    fn check_pod_configmap_references(&self, pod: &PodInfo, configmap: &mut ConfigMapInfo) {
-        // This is a simplified implementation
-        // In a real implementation, we would need to access the Pod spec
-        // to check for volume mounts and environment variable references
-
-        // For now, we'll add a placeholder reference if the pod is in the same namespace
-        if pod.namespace == configmap.namespace {
-            let reference = ResourceReference {
-                kind: "Pod".to_string(),
-                name: pod.name.clone(),
-                namespace: pod.namespace.clone(),
-                reference_type: ReferenceType::VolumeMount, // Placeholder
-            };
-
-            // Only add if not already present
-            if !configmap
-                .used_by
-                .iter()
-                .any(|r| r.name == reference.name && r.kind == reference.kind)
-            {
-                configmap.used_by.push(reference);
-            }
+        // Concurrent passes wait for the first one's index instead of
+        // listing pods again
+        let lock = self.cache.pod_index_lock(namespace);
+        let _building = lock.lock().await;
+        if let Some(index) = cached() {
+            return Ok(index);
         }
-    }
-
-    fn check_pod_secret_references(&self, pod: &PodInfo, secret: &mut SecretInfo) {
-        // This is a simplified implementation
-        // In a real implementation, we would need to access the Pod spec
-        // to check for volume mounts, environment variables, and imagePullSecrets
 
-        // For now, we'll add a placeholder reference if the pod is in the same namespace
-        if pod.namespace == secret.namespace {
-            let reference = ResourceReference {
-                kind: "Pod".to_string(),
-                name: pod.name.clone(),
-                namespace: pod.namespace.clone(),
-                reference_type: ReferenceType::VolumeMount, // Placeholder
-            };
-
-            // Only add if not already present
-            if !secret
-                .used_by
-                .iter()
-                .any(|r| r.name == reference.name && r.kind == reference.kind)
-            {
-                secret.used_by.push(reference);
-            }
-        }
+        let started = Instant::now();
+        let pods: Api<Pod> = match namespace {
+            Some(ns) => Api::namespaced(self.client.clone(), ns),
+            None => Api::all(self.client.clone()),
+        };
+        let pods = pods.list(&Default::default()).await?.items;
+        let index = Arc::new(PodIndex::from_pods(&pods));
+        self.cache.set_pod_index(namespace, index.clone());
+        self.stats.record_phase("index pods", started);
+        Ok(index)
     }
 
     async fn convert_crd_to_info(&self, crd: CustomResourceDefinition) -> Option<CRDInfo> {
         let metadata = crd.metadata;
+        let age = object_age(&metadata);
         let spec = crd.spec;
 
         let name = metadata.name?;
@@ -1385,11 +3539,14 @@ impl DiscoveryEngine {
             kind,
             plural,
             scope,
-            age: "Unknown".to_string(), // TODO: Calculate from creation timestamp
+            age,
             labels,
             instance_count: 0, // Will be populated by instance counting
             versions,
             description: None, // TODO: Extract from CRD description
+            controller: None,  // Attributed once workloads are known
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+            annotations: summary_annotations(metadata.annotations.as_ref()),
         })
     }
 
@@ -1401,22 +3558,29 @@ impl DiscoveryEngine {
         }
         Ok(())
     }
+}
 
-    async fn list_dynamic_resources(
-        &self,
-        _group: &str,
-        _version: &str,
-        _plural: &str,
-        _namespace: Option<&str>,
-    ) -> Result<Vec<CustomResourceInfo>> {
-        // This is a simplified implementation
-        // In a real implementation, we would use kube::discovery and dynamic client
-        // For now, we'll return an empty list as a placeholder
-
-        // TODO: Implement dynamic resource discovery using kube::discovery::Discovery
-        // and kube::api::DynamicObject
+/// The main kinds of a namespace, as `kdx all` lists them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AllResources {
+    pub services: Vec<ServiceInfo>,
+    pub deployments: Vec<DeploymentInfo>,
+    pub statefulsets: Vec<StatefulSetInfo>,
+    pub daemonsets: Vec<DaemonSetInfo>,
+    pub pods: Vec<PodInfo>,
+    pub configmaps: Vec<ConfigMapInfo>,
+    pub secrets: Vec<SecretInfo>,
+}
 
-        Ok(Vec::new())
+impl AllResources {
+    pub fn total(&self) -> usize {
+        self.services.len()
+            + self.deployments.len()
+            + self.statefulsets.len()
+            + self.daemonsets.len()
+            + self.pods.len()
+            + self.configmaps.len()
+            + self.secrets.len()
     }
 }
 
@@ -1428,6 +3592,11 @@ pub struct ServiceInfo {
     pub cluster_ip: Option<String>,
     pub service_type: String,
     pub selector: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1438,7 +3607,7 @@ pub struct ServicePort {
     pub protocol: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PodInfo {
     pub name: String,
     pub namespace: String,
@@ -1450,6 +3619,17 @@ pub struct PodInfo {
     pub total_containers: u32,
     pub restart_count: u32,
     pub age: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub qos_class: String,
+    pub priority_class: Option<String>,
+    pub priority: Option<i32>,
+    pub owner_kind: Option<String>,
+    pub owner_name: Option<String>,
+    pub mesh: Option<String>,
+    pub mesh_injection: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1461,88 +3641,391 @@ pub struct IngressInfo {
     pub tls_enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IngressPath {
-    pub path: String,
-    pub service_name: String,
-    pub service_port: String,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngressPath {
+    pub path: String,
+    pub service_name: String,
+    pub service_port: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigMapInfo {
+    pub name: String,
+    pub namespace: String,
+    pub data_keys: Vec<String>,
+    pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+    pub used_by: Vec<ResourceReference>,
+    pub mount_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub namespace: String,
+    pub secret_type: String,
+    pub data_keys: Vec<String>,
+    pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+    pub used_by: Vec<ResourceReference>,
+    pub mount_paths: Vec<String>,
+    /// Account a `kubernetes.io/service-account-token` secret belongs to
+    #[serde(default)]
+    pub service_account: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceReference {
+    pub kind: String,
+    pub name: String,
+    pub namespace: String,
+    pub reference_type: ReferenceType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferenceType {
+    VolumeMount,
+    Environment,
+    EnvironmentFrom,
+    ImagePullSecret,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeploymentInfo {
+    pub name: String,
+    pub namespace: String,
+    pub replicas: i32,
+    pub ready_replicas: i32,
+    pub available_replicas: i32,
+    pub strategy: String,
+    pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+    pub selector: BTreeMap<String, String>,
+    /// Image of each container of the pod template, init containers included
+    #[serde(default)]
+    pub images: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+impl PodInfo {
+    /// A running BestEffort pod with one ready container, for tests
+    pub(crate) fn fixture(namespace: &str, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            phase: "Running".to_string(),
+            ready_containers: 1,
+            total_containers: 1,
+            age: "1d".to_string(),
+            qos_class: "BestEffort".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl DeploymentInfo {
+    /// A rolled out RollingUpdate Deployment, for tests
+    pub(crate) fn fixture(namespace: &str, name: &str, replicas: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            replicas,
+            ready_replicas: replicas,
+            available_replicas: replicas,
+            strategy: "RollingUpdate".to_string(),
+            age: "1d".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatefulSetInfo {
+    pub name: String,
+    pub namespace: String,
+    pub replicas: i32,
+    pub ready_replicas: i32,
+    pub current_replicas: i32,
+    pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+    pub selector: BTreeMap<String, String>,
+    /// Image of each container of the pod template, init containers included
+    #[serde(default)]
+    pub images: BTreeMap<String, String>,
+    /// Names of the volume claim templates
+    #[serde(default)]
+    pub volume_claim_templates: Vec<String>,
+    /// Revision pods are being updated to
+    #[serde(default)]
+    pub update_revision: Option<String>,
+    /// Pod and claims of each ordinal; only filled in by describe
+    #[serde(default)]
+    pub ordinals: Vec<StatefulSetOrdinal>,
+}
+
+impl StatefulSetInfo {
+    /// Problems across all ordinals: missing or unready pods, pods on an old
+    /// revision, and claims that are missing or not bound
+    pub fn gaps(&self) -> Vec<String> {
+        self.ordinals
+            .iter()
+            .flat_map(|o| o.gaps(self.update_revision.as_deref()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonSetInfo {
+    pub name: String,
+    pub namespace: String,
+    pub desired: i32,
+    pub current: i32,
+    pub ready: i32,
+    pub up_to_date: i32,
+    pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+    pub selector: BTreeMap<String, String>,
+    /// Image of each container of the pod template, init containers included
+    #[serde(default)]
+    pub images: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaSetInfo {
+    pub name: String,
+    pub namespace: String,
+    pub desired: i32,
+    pub current: i32,
+    pub ready: i32,
+    /// Deployment owning the ReplicaSet through its controller reference
+    pub deployment: Option<String>,
+    /// `deployment.kubernetes.io/revision` of the owning Deployment's rollout
+    pub revision: Option<i64>,
+    /// Earlier revisions this ReplicaSet served before a rollback to it
+    #[serde(default)]
+    pub revision_history: Vec<i64>,
+    pub pod_template_hash: Option<String>,
+    /// `kubernetes.io/change-cause` recorded for the rollout
+    pub change_cause: Option<String>,
+    pub images: Vec<String>,
+    pub age: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub name: String,
+    /// From `node-role.kubernetes.io/<role>` labels
+    pub roles: Vec<String>,
+    pub kubelet_version: String,
+    pub os: String,
+    pub arch: String,
+    pub zone: Option<String>,
+    pub region: Option<String>,
+    pub spot: bool,
+    /// Cordoned: new pods are not scheduled onto the node
+    pub unschedulable: bool,
+    pub taints: Vec<String>,
+    pub allocatable_cpu: Option<String>,
+    pub allocatable_memory: Option<String>,
+    /// Condition type to status, e.g. "Ready" -> "True"
+    pub conditions: BTreeMap<String, String>,
+    pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+impl NodeInfo {
+    /// Status of the Ready condition: "Ready", "NotReady" or "Unknown"
+    pub fn ready_status(&self) -> &str {
+        match self.conditions.get("Ready").map(String::as_str) {
+            Some("True") => "Ready",
+            Some("False") => "NotReady",
+            _ => "Unknown",
+        }
+    }
+
+    /// Conditions other than Ready that are currently true, such as
+    /// MemoryPressure or DiskPressure
+    pub fn problems(&self) -> Vec<&str> {
+        self.conditions
+            .iter()
+            .filter(|(kind, status)| *kind != "Ready" && *status == "True")
+            .map(|(kind, _)| kind.as_str())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConfigMapInfo {
+pub struct PersistentVolumeInfo {
     pub name: String,
-    pub namespace: String,
-    pub data_keys: Vec<String>,
+    pub capacity: Option<String>,
+    pub access_modes: Vec<String>,
+    pub reclaim_policy: String,
+    pub phase: String,
+    pub storage_class: Option<String>,
+    pub claim_namespace: Option<String>,
+    pub claim_name: Option<String>,
     pub age: String,
-    pub labels: BTreeMap<String, String>,
-    pub used_by: Vec<ResourceReference>,
-    pub mount_paths: Vec<String>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Pods mounting the bound claim
+    #[serde(default)]
+    pub mounted_by: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SecretInfo {
+pub struct PersistentVolumeClaimInfo {
     pub name: String,
     pub namespace: String,
-    pub secret_type: String,
-    pub data_keys: Vec<String>,
+    pub phase: String,
+    pub volume_name: Option<String>,
+    pub capacity: Option<String>,
+    /// Storage requested in the claim spec
+    #[serde(default)]
+    pub requested: Option<String>,
+    pub access_modes: Vec<String>,
+    pub storage_class: Option<String>,
     pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
     pub labels: BTreeMap<String, String>,
-    pub used_by: Vec<ResourceReference>,
-    pub mount_paths: Vec<String>,
+    /// Pods mounting the claim
+    #[serde(default)]
+    pub mounted_by: Vec<String>,
 }
 
+/// A ServiceAccount
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResourceReference {
-    pub kind: String,
+pub struct ServiceAccountInfo {
     pub name: String,
     pub namespace: String,
-    pub reference_type: ReferenceType,
+    /// `automountServiceAccountToken`, unset when pods decide
+    pub automount_token: Option<bool>,
+    pub image_pull_secrets: Vec<String>,
+    pub age: String,
+    pub labels: BTreeMap<String, String>,
+    /// Roles bound to the account, directly or through its groups
+    #[serde(default)]
+    pub roles: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ReferenceType {
-    VolumeMount,
-    Environment,
-    EnvironmentFrom,
-    ImagePullSecret,
+/// One rule of a Role or ClusterRole
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRuleInfo {
+    pub verbs: Vec<String>,
+    pub api_groups: Vec<String>,
+    pub resources: Vec<String>,
+    pub resource_names: Vec<String>,
+    pub non_resource_urls: Vec<String>,
 }
 
+/// A Role, or a ClusterRole when `namespace` is None
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeploymentInfo {
+pub struct RoleInfo {
     pub name: String,
-    pub namespace: String,
-    pub replicas: i32,
-    pub ready_replicas: i32,
-    pub available_replicas: i32,
-    pub strategy: String,
+    pub namespace: Option<String>,
+    pub rules: Vec<PolicyRuleInfo>,
+    /// ClusterRole whose rules are aggregated from other ClusterRoles
+    pub aggregated: bool,
     pub age: String,
     pub labels: BTreeMap<String, String>,
-    pub selector: BTreeMap<String, String>,
+    /// Subjects bound to the role
+    #[serde(default)]
+    pub subjects: Vec<SubjectInfo>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StatefulSetInfo {
+impl RoleInfo {
+    /// `Role` or `ClusterRole`
+    pub fn kind(&self) -> &'static str {
+        if self.namespace.is_some() {
+            "Role"
+        } else {
+            "ClusterRole"
+        }
+    }
+}
+
+/// User, group or service account named in a binding
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SubjectInfo {
+    pub kind: String,
     pub name: String,
-    pub namespace: String,
-    pub replicas: i32,
-    pub ready_replicas: i32,
-    pub current_replicas: i32,
-    pub age: String,
-    pub labels: BTreeMap<String, String>,
-    pub selector: BTreeMap<String, String>,
+    /// Namespace of a ServiceAccount subject
+    pub namespace: Option<String>,
+}
+
+impl std::fmt::Display for SubjectInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.namespace {
+            Some(ns) => write!(f, "{}/{}/{}", self.kind, ns, self.name),
+            None => write!(f, "{}/{}", self.kind, self.name),
+        }
+    }
 }
 
+/// A RoleBinding, or a ClusterRoleBinding when `namespace` is None
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DaemonSetInfo {
+pub struct RoleBindingInfo {
     pub name: String,
-    pub namespace: String,
-    pub desired: i32,
-    pub current: i32,
-    pub ready: i32,
-    pub up_to_date: i32,
+    pub namespace: Option<String>,
+    /// `Role` or `ClusterRole`
+    pub role_kind: String,
+    pub role_name: String,
+    pub subjects: Vec<SubjectInfo>,
     pub age: String,
     pub labels: BTreeMap<String, String>,
-    pub selector: BTreeMap<String, String>,
+}
+
+impl RoleBindingInfo {
+    /// `RoleBinding` or `ClusterRoleBinding`
+    pub fn kind(&self) -> &'static str {
+        if self.namespace.is_some() {
+            "RoleBinding"
+        } else {
+            "ClusterRoleBinding"
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1554,10 +4037,34 @@ pub struct CRDInfo {
     pub plural: String,
     pub scope: String, // Namespaced or Cluster
     pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Annotations, without kubectl's last-applied-configuration
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
     pub labels: BTreeMap<String, String>,
     pub instance_count: u32,
     pub versions: Vec<CRDVersion>,
     pub description: Option<String>,
+    /// Operator or controller serving the CRD, when it could be identified
+    pub controller: Option<CrdController>,
+}
+
+/// Listable resource types and the API groups that could not be queried
+#[derive(Debug, Default)]
+pub struct ResourceDiscovery {
+    pub resources: Vec<(ApiResource, Scope)>,
+    pub failures: Vec<DiscoveryFailure>,
+    /// Short names declared by the resources (e.g. `cert`), each with the
+    /// group and plural of the resource it stands for
+    pub short_names: BTreeMap<String, (String, String)>,
+}
+
+/// An API group version whose resources could not be discovered or listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryFailure {
+    pub group_version: String,
+    pub error: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1577,6 +4084,8 @@ pub struct CustomResourceInfo {
     pub version: String,
     pub kind: String,
     pub age: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
     pub labels: BTreeMap<String, String>,
     pub annotations: BTreeMap<String, String>,
     pub spec_summary: String,           // Simplified representation of spec
@@ -1584,6 +4093,67 @@ pub struct CustomResourceInfo {
     pub related_resources: Vec<ResourceReference>,
 }
 
+/// Instances of one CRD, as listed by `kdx custom-resources` without a CRD name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrdInstances {
+    pub crd_name: String,
+    pub group: String,
+    pub kind: String,
+    pub scope: String,
+    /// Number of matching instances; `items` may be cut short of this
+    pub instance_count: usize,
+    pub items: Vec<CustomResourceInfo>,
+}
+
+/// An object as recorded in an [`OwnerIndex`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnedObject {
+    pub kind: String,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub uid: String,
+    pub age: String,
+}
+
+/// Objects by the UID of each owner their ownerReferences name, for walking
+/// ownership downwards
+#[derive(Debug, Clone, Default)]
+pub struct OwnerIndex {
+    dependents: HashMap<String, Vec<OwnedObject>>,
+}
+
+impl OwnerIndex {
+    /// Index `objects` as returned by `list_object_metadata`; dependents of
+    /// each owner are sorted by kind, then name
+    pub fn from_objects(objects: Vec<(String, ObjectMeta)>) -> Self {
+        let mut dependents: HashMap<String, Vec<OwnedObject>> = HashMap::new();
+        for (kind, metadata) in objects {
+            let object = OwnedObject {
+                kind,
+                name: metadata.name.clone().unwrap_or_default(),
+                namespace: metadata.namespace.clone(),
+                uid: metadata.uid.clone().unwrap_or_default(),
+                age: object_age(&metadata),
+            };
+            for owner in metadata.owner_references.iter().flatten() {
+                dependents
+                    .entry(owner.uid.clone())
+                    .or_default()
+                    .push(object.clone());
+            }
+        }
+        for objects in dependents.values_mut() {
+            objects.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+        }
+        OwnerIndex { dependents }
+    }
+
+    /// Objects naming the object with `uid` as an owner
+    pub fn dependents(&self, uid: &str) -> &[OwnedObject] {
+        self.dependents.get(uid).map_or(&[], Vec::as_slice)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceHealth {
     pub service_name: String,
@@ -1595,6 +4165,46 @@ pub struct ServiceHealth {
 pub struct ServiceDescription {
     pub service: ServiceInfo,
     pub related_pods: Vec<PodInfo>,
+    /// Warning events of the related pods, most recent first
+    #[serde(default)]
+    pub warnings: Vec<EventInfo>,
+    /// Autoscalers scaling the workloads of the related pods
+    #[serde(default)]
+    pub autoscalers: Vec<HpaInfo>,
+}
+
+/// A service with what routes to it, configures it, scrapes it and calls it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepServiceDescription {
+    #[serde(flatten)]
+    pub description: ServiceDescription,
+    pub ingresses: Vec<IngressInfo>,
+    /// ConfigMaps and Secrets used by the related pods
+    pub configmaps: Vec<ConfigMapInfo>,
+    pub secrets: Vec<SecretInfo>,
+    pub endpoints: Vec<ServiceEndpoint>,
+    /// ServiceMonitors scraping the service
+    pub monitors: Vec<MonitorRef>,
+    /// Admission webhooks served by the service
+    pub webhooks: Vec<WebhookRef>,
+    pub health: ServiceHealth,
+    /// Sections that could not be read, with the error
+    pub unavailable: BTreeMap<String, String>,
+}
+
+/// A pod with its containers, the services selecting it, its owners and node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodDescription {
+    pub pod: PodInfo,
+    pub service_account: Option<String>,
+    pub containers: Vec<ContainerDetail>,
+    /// Services in the pod's namespace whose selector matches it
+    pub services: Vec<String>,
+    pub owners: Option<OwnershipChain>,
+    pub node: Option<NodeInfo>,
+    /// Events about the pod, oldest first
+    #[serde(default)]
+    pub events: Vec<EventInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1603,6 +4213,446 @@ pub struct ServiceTopology {
     pub backend_pods: Vec<PodInfo>,
     pub ingress_routes: Vec<String>, // TODO: Define proper ingress types
     pub dependencies: Vec<String>,   // TODO: Define proper dependency types
+    /// Claims mounted by the backend pods and the volumes bound to them
+    #[serde(default)]
+    pub storage: Vec<PodStorage>,
+    /// Warning events of the backend pods, most recent first
+    #[serde(default)]
+    pub warnings: Vec<EventInfo>,
+    /// Autoscalers scaling the workloads of the backend pods
+    #[serde(default)]
+    pub autoscalers: Vec<HpaInfo>,
+}
+
+/// Ready containers, total containers and restarts of a pod, counted the way
+/// `kubectl get pods` does: app containers plus sidecars (init containers
+/// with `restartPolicy: Always`)
+pub fn container_counts(spec: &PodSpec, status: Option<&PodStatus>) -> (u32, u32, u32) {
+    let sidecars: Vec<&str> = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .filter(|c| c.restart_policy.as_deref() == Some("Always"))
+        .map(|c| c.name.as_str())
+        .collect();
+    let total = spec.containers.len() + sidecars.len();
+
+    let statuses = status.into_iter().flat_map(|s| {
+        let sidecar_statuses = s
+            .init_container_statuses
+            .iter()
+            .flatten()
+            .filter(|cs| sidecars.contains(&cs.name.as_str()));
+        s.container_statuses
+            .iter()
+            .flatten()
+            .chain(sidecar_statuses)
+    });
+    let (ready, restarts) = statuses.fold((0, 0), |(ready, restarts), cs| {
+        (
+            ready + u32::from(cs.ready),
+            restarts + cs.restart_count.max(0) as u32,
+        )
+    });
+
+    (ready, total as u32, restarts)
+}
+
+/// Determine the QoS class of a pod.
+///
+/// The class reported by the API server in `status.qosClass` is preferred; when it
+/// is missing the class is derived from container requests and limits using the
+/// same rules as the kubelet.
+pub fn pod_qos_class(spec: &PodSpec, status: Option<&PodStatus>) -> String {
+    if let Some(qos) = status.and_then(|s| s.qos_class.clone()) {
+        return qos;
+    }
+
+    let containers = spec
+        .containers
+        .iter()
+        .chain(spec.init_containers.iter().flatten());
+
+    let mut has_any_resources = false;
+    let mut guaranteed = true;
+
+    for container in containers {
+        let requests = container
+            .resources
+            .as_ref()
+            .and_then(|r| r.requests.clone())
+            .unwrap_or_default();
+        let limits = container
+            .resources
+            .as_ref()
+            .and_then(|r| r.limits.clone())
+            .unwrap_or_default();
+
+        // Only cpu and memory count toward the QoS class
+        if ["cpu", "memory"]
+            .iter()
+            .any(|r| requests.contains_key(*r) || limits.contains_key(*r))
+        {
+            has_any_resources = true;
+        }
+
+        for resource in ["cpu", "memory"] {
+            let limit = limits.get(resource).and_then(|q| parse_quantity(&q.0));
+            // Requests default to limits when only limits are specified
+            let request = requests
+                .get(resource)
+                .and_then(|q| parse_quantity(&q.0))
+                .or(limit);
+
+            match (request, limit) {
+                (Some(request), Some(limit)) if request == limit => {}
+                _ => guaranteed = false,
+            }
+        }
+    }
+
+    if !has_any_resources {
+        "BestEffort".to_string()
+    } else if guaranteed {
+        "Guaranteed".to_string()
+    } else {
+        "Burstable".to_string()
+    }
+}
+
+/// Record a failed namespace on the progress tracker, or warn right away when
+/// there is none
+fn report_namespace_failure(
+    warnings: &Warnings,
+    progress: Option<&ProgressTracker>,
+    failure: NamespaceFailure,
+) {
+    warnings.warn_incomplete(format!(
+        "failed to fetch {} from namespace '{}': {}",
+        failure.resource, failure.namespace, failure.error
+    ));
+    match progress {
+        Some(progress) => progress.record_failure(failure),
+        None => eprintln!(
+            "Warning: Failed to fetch {} from namespace '{}': {}",
+            failure.resource, failure.namespace, failure.error
+        ),
+    }
+}
+
+/// Top-level fields of a custom resource spec, e.g. "dnsNames, issuerRef, secretName"
+fn summarize_spec(spec: Option<&serde_json::Value>) -> String {
+    const MAX_FIELDS: usize = 5;
+    let Some(fields) = spec.and_then(|s| s.as_object()) else {
+        return String::new();
+    };
+    let mut summary: Vec<&str> = fields.keys().take(MAX_FIELDS).map(String::as_str).collect();
+    if fields.len() > MAX_FIELDS {
+        summary.push("...");
+    }
+    summary.join(", ")
+}
+
+/// Readiness of a custom resource from its Ready condition, else its phase or state
+fn summarize_status(status: Option<&serde_json::Value>) -> Option<String> {
+    let status = status?;
+    let ready = status["conditions"].as_array().and_then(|conditions| {
+        conditions
+            .iter()
+            .find(|c| c["type"] == "Ready" || c["type"] == "Available")
+    });
+    if let Some(condition) = ready {
+        return Some(
+            match (condition["status"].as_str(), condition["reason"].as_str()) {
+                (Some("True"), _) => "Ready".to_string(),
+                (_, Some(reason)) => format!("NotReady: {}", reason),
+                _ => "NotReady".to_string(),
+            },
+        );
+    }
+    status["phase"]
+        .as_str()
+        .or_else(|| status["state"].as_str())
+        .map(|s| s.to_string())
+}
+
+/// Convert a node into its summary, reading zone and region from the well-known
+/// topology labels (falling back to the deprecated failure-domain labels)
+pub fn convert_node_to_info(node: Node) -> Option<NodeInfo> {
+    let metadata = node.metadata;
+    let name = metadata.name?;
+    let labels = metadata.labels.unwrap_or_default();
+
+    let label = |key: &str, legacy: &str| labels.get(key).or_else(|| labels.get(legacy)).cloned();
+    let zone = label(
+        "topology.kubernetes.io/zone",
+        "failure-domain.beta.kubernetes.io/zone",
+    );
+    let region = label(
+        "topology.kubernetes.io/region",
+        "failure-domain.beta.kubernetes.io/region",
+    );
+
+    let spec = node.spec.unwrap_or_default();
+    let status = node.status.unwrap_or_default();
+    let taints: Vec<String> = spec
+        .taints
+        .unwrap_or_default()
+        .into_iter()
+        .map(|taint| match taint.value {
+            Some(value) => format!("{}={}:{}", taint.key, value, taint.effect),
+            None => format!("{}:{}", taint.key, taint.effect),
+        })
+        .collect();
+    let spot = crate::spot::is_spot_node(&labels, &taints);
+
+    let mut roles: Vec<String> = labels
+        .keys()
+        .filter_map(|key| key.strip_prefix("node-role.kubernetes.io/"))
+        .filter(|role| !role.is_empty())
+        .map(String::from)
+        .collect();
+    if let Some(role) = labels.get("kubernetes.io/role") {
+        if !roles.contains(role) {
+            roles.push(role.clone());
+        }
+    }
+
+    let system = status.node_info.unwrap_or_default();
+    let allocatable = status.allocatable.unwrap_or_default();
+    let conditions = status
+        .conditions
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| (c.type_, c.status))
+        .collect();
+
+    Some(NodeInfo {
+        name,
+        roles,
+        kubelet_version: system.kubelet_version,
+        os: system.operating_system,
+        arch: system.architecture,
+        zone,
+        region,
+        spot,
+        unschedulable: spec.unschedulable.unwrap_or(false),
+        taints,
+        allocatable_cpu: allocatable.get("cpu").map(|q| q.0.clone()),
+        allocatable_memory: allocatable.get("memory").map(|q| q.0.clone()),
+        conditions,
+        age: format_age(metadata.creation_timestamp.as_ref().map(|t| t.0)),
+        labels,
+        created_at: metadata.creation_timestamp.map(|t| t.0),
+        annotations: summary_annotations(metadata.annotations.as_ref()),
+    })
+}
+
+fn convert_persistent_volume_to_info(volume: PersistentVolume) -> Option<PersistentVolumeInfo> {
+    let metadata = volume.metadata;
+    let name = metadata.name?;
+    let spec = volume.spec.unwrap_or_default();
+
+    let claim = spec.claim_ref.as_ref();
+
+    Some(PersistentVolumeInfo {
+        name,
+        capacity: spec
+            .capacity
+            .as_ref()
+            .and_then(|c| c.get("storage"))
+            .map(|q| q.0.clone()),
+        access_modes: spec.access_modes.clone().unwrap_or_default(),
+        reclaim_policy: spec
+            .persistent_volume_reclaim_policy
+            .clone()
+            .unwrap_or_else(|| "Retain".to_string()),
+        phase: volume
+            .status
+            .and_then(|s| s.phase)
+            .unwrap_or_else(|| "Unknown".to_string()),
+        storage_class: spec.storage_class_name.clone(),
+        claim_namespace: claim.and_then(|c| c.namespace.clone()),
+        claim_name: claim.and_then(|c| c.name.clone()),
+        age: format_age(metadata.creation_timestamp.as_ref().map(|t| t.0)),
+        mounted_by: Vec::new(),
+        created_at: metadata.creation_timestamp.map(|t| t.0),
+        annotations: summary_annotations(metadata.annotations.as_ref()),
+    })
+}
+
+fn convert_persistent_volume_claim_to_info(
+    claim: PersistentVolumeClaim,
+) -> Option<PersistentVolumeClaimInfo> {
+    let metadata = claim.metadata;
+    let name = metadata.name?;
+    let namespace = metadata.namespace.unwrap_or_else(|| "default".to_string());
+    let spec = claim.spec.unwrap_or_default();
+    let status = claim.status.unwrap_or_default();
+
+    let requested = spec
+        .resources
+        .as_ref()
+        .and_then(|r| r.requests.as_ref())
+        .and_then(|r| r.get("storage"))
+        .map(|q| q.0.clone());
+    // Prefer the provisioned capacity, falling back to the requested size
+    let capacity = status
+        .capacity
+        .as_ref()
+        .and_then(|c| c.get("storage"))
+        .map(|q| q.0.clone())
+        .or_else(|| requested.clone());
+
+    Some(PersistentVolumeClaimInfo {
+        name,
+        namespace,
+        phase: status.phase.unwrap_or_else(|| "Unknown".to_string()),
+        volume_name: spec.volume_name,
+        capacity,
+        requested,
+        access_modes: spec.access_modes.unwrap_or_default(),
+        storage_class: spec.storage_class_name,
+        age: format_age(metadata.creation_timestamp.as_ref().map(|t| t.0)),
+        labels: metadata.labels.unwrap_or_default(),
+        mounted_by: Vec::new(),
+        created_at: metadata.creation_timestamp.map(|t| t.0),
+        annotations: summary_annotations(metadata.annotations.as_ref()),
+    })
+}
+
+fn convert_service_account_to_info(account: ServiceAccount) -> Option<ServiceAccountInfo> {
+    let metadata = account.metadata;
+    Some(ServiceAccountInfo {
+        name: metadata.name?,
+        namespace: metadata.namespace.unwrap_or_else(|| "default".to_string()),
+        automount_token: account.automount_service_account_token,
+        image_pull_secrets: account
+            .image_pull_secrets
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|s| s.name)
+            .collect(),
+        age: format_age(metadata.creation_timestamp.map(|t| t.0)),
+        labels: metadata.labels.unwrap_or_default(),
+        roles: Vec::new(),
+    })
+}
+
+fn convert_role_to_info(
+    metadata: ObjectMeta,
+    rules: Option<Vec<PolicyRule>>,
+    aggregated: bool,
+) -> Option<RoleInfo> {
+    Some(RoleInfo {
+        name: metadata.name?,
+        namespace: metadata.namespace,
+        rules: rules
+            .unwrap_or_default()
+            .into_iter()
+            .map(|rule| PolicyRuleInfo {
+                verbs: rule.verbs,
+                api_groups: rule.api_groups.unwrap_or_default(),
+                resources: rule.resources.unwrap_or_default(),
+                resource_names: rule.resource_names.unwrap_or_default(),
+                non_resource_urls: rule.non_resource_urls.unwrap_or_default(),
+            })
+            .collect(),
+        aggregated,
+        age: format_age(metadata.creation_timestamp.map(|t| t.0)),
+        labels: metadata.labels.unwrap_or_default(),
+        subjects: Vec::new(),
+    })
+}
+
+fn convert_role_binding_to_info(
+    metadata: ObjectMeta,
+    role_kind: &str,
+    role_name: String,
+    subjects: Option<Vec<Subject>>,
+) -> Option<RoleBindingInfo> {
+    let namespace = metadata.namespace;
+    Some(RoleBindingInfo {
+        name: metadata.name?,
+        subjects: subjects
+            .unwrap_or_default()
+            .into_iter()
+            .map(|subject| SubjectInfo {
+                // A service account subject of a RoleBinding defaults to
+                // the binding's namespace
+                namespace: match subject.kind.as_str() {
+                    "ServiceAccount" => subject.namespace.or_else(|| namespace.clone()),
+                    _ => None,
+                },
+                kind: subject.kind,
+                name: subject.name,
+            })
+            .collect(),
+        namespace,
+        role_kind: role_kind.to_string(),
+        role_name,
+        age: format_age(metadata.creation_timestamp.map(|t| t.0)),
+        labels: metadata.labels.unwrap_or_default(),
+    })
+}
+
+/// Return the kind and name of the controlling owner reference, if any
+pub fn controller_owner(metadata: &ObjectMeta) -> (Option<String>, Option<String>) {
+    metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .find(|owner| owner.controller == Some(true))
+        .map(|owner| (Some(owner.kind.clone()), Some(owner.name.clone())))
+        .unwrap_or((None, None))
+}
+
+/// Annotations kept on summaries: all but kubectl's copy of the last applied
+/// manifest, which can be as large as the object itself
+pub fn summary_annotations(
+    annotations: Option<&BTreeMap<String, String>>,
+) -> BTreeMap<String, String> {
+    annotations
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| key.as_str() != LAST_APPLIED_ANNOTATION)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Image of each container of a pod spec by container name, init
+/// containers included
+pub fn container_images(spec: Option<&PodSpec>) -> BTreeMap<String, String> {
+    spec.into_iter()
+        .flat_map(|spec| {
+            spec.init_containers
+                .iter()
+                .flatten()
+                .chain(&spec.containers)
+        })
+        .map(|c| (c.name.clone(), c.image.clone().unwrap_or_default()))
+        .collect()
+}
+
+/// Resolve the workload a pod belongs to as (kind, name).
+///
+/// Pods owned by a Deployment-managed ReplicaSet resolve to the Deployment, and
+/// pods without a controller are treated as their own workload.
+pub fn pod_workload(pod: &PodInfo) -> (String, String) {
+    match (pod.owner_kind.as_deref(), pod.owner_name.as_deref()) {
+        (Some("ReplicaSet"), Some(name)) => {
+            let deployment = pod
+                .labels
+                .get("pod-template-hash")
+                .and_then(|hash| name.strip_suffix(&format!("-{}", hash)));
+            match deployment {
+                Some(deployment) => ("Deployment".to_string(), deployment.to_string()),
+                None => ("ReplicaSet".to_string(), name.to_string()),
+            }
+        }
+        (Some(kind), Some(name)) => (kind.to_string(), name.to_string()),
+        _ => ("Pod".to_string(), pod.name.clone()),
+    }
 }
 
 /// Lazy conversion implementations for memory efficiency
@@ -1640,6 +4690,8 @@ impl LazyConvert<ServiceInfo> for Service {
             cluster_ip,
             ports,
             selector: Some(selector),
+            created_at: self.metadata.creation_timestamp.as_ref().map(|t| t.0),
+            annotations: summary_annotations(self.metadata.annotations.as_ref()),
         })
     }
 }
@@ -1662,16 +4714,12 @@ impl LazyConvert<PodInfo> for Pod {
 
         let labels = self.metadata.labels.clone().unwrap_or_default();
 
-        let containers = spec.containers.len() as i32;
-        let ready_containers = status
-            .and_then(|s| s.container_statuses.as_ref())
-            .map(|statuses| statuses.iter().filter(|cs| cs.ready).count() as i32)
-            .unwrap_or(0);
-
-        let restart_count = status
-            .and_then(|s| s.container_statuses.as_ref())
-            .map(|statuses| statuses.iter().map(|cs| cs.restart_count).sum())
-            .unwrap_or(0);
+        let (ready_containers, total_containers, restart_count) = container_counts(spec, status);
+        let qos_class = pod_qos_class(spec, status);
+        let (owner_kind, owner_name) = controller_owner(&self.metadata);
+        let created_at = self.metadata.creation_timestamp.as_ref().map(|t| t.0);
+        let annotations = summary_annotations(self.metadata.annotations.as_ref());
+        let mesh_injection = crate::mesh::injection_setting(&annotations, &labels);
 
         Some(PodInfo {
             name,
@@ -1680,10 +4728,19 @@ impl LazyConvert<PodInfo> for Pod {
             pod_ip,
             node_name,
             labels,
-            ready_containers: ready_containers as u32,
-            total_containers: containers as u32,
-            restart_count: restart_count as u32,
-            age: "Unknown".to_string(), // Would need creation timestamp calculation
+            ready_containers,
+            total_containers,
+            restart_count,
+            age: format_age(created_at),
+            created_at,
+            annotations,
+            qos_class,
+            priority_class: spec.priority_class_name.clone(),
+            priority: spec.priority,
+            owner_kind,
+            owner_name,
+            mesh: crate::mesh::detect_sidecar(spec),
+            mesh_injection,
         })
     }
 }
@@ -1701,6 +4758,8 @@ mod tests {
             cluster_ip: Some("10.0.0.1".to_string()),
             service_type: "ClusterIP".to_string(),
             selector: Some(std::collections::BTreeMap::new()),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert_eq!(service.name, "test-service");
@@ -1722,6 +4781,15 @@ mod tests {
             total_containers: 2,
             restart_count: 0,
             age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            qos_class: "Burstable".to_string(),
+            priority_class: None,
+            priority: None,
+            owner_kind: None,
+            owner_name: None,
+            mesh: None,
+            mesh_injection: None,
         };
 
         assert_eq!(pod.name, "test-pod");
@@ -1731,6 +4799,48 @@ mod tests {
         assert_eq!(pod.restart_count, 0);
     }
 
+    #[test]
+    fn test_pod_workload() {
+        let mut labels = std::collections::BTreeMap::new();
+        labels.insert("pod-template-hash".to_string(), "5d8f7c9b6".to_string());
+        let mut pod = PodInfo {
+            name: "web-5d8f7c9b6-x2k4p".to_string(),
+            namespace: "default".to_string(),
+            phase: "Running".to_string(),
+            pod_ip: None,
+            node_name: None,
+            labels,
+            ready_containers: 1,
+            total_containers: 1,
+            restart_count: 0,
+            age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            qos_class: "BestEffort".to_string(),
+            priority_class: None,
+            priority: None,
+            owner_kind: Some("ReplicaSet".to_string()),
+            owner_name: Some("web-5d8f7c9b6".to_string()),
+            mesh: None,
+            mesh_injection: None,
+        };
+        assert_eq!(
+            pod_workload(&pod),
+            ("Deployment".to_string(), "web".to_string())
+        );
+
+        pod.owner_kind = Some("Job".to_string());
+        pod.owner_name = Some("migrate".to_string());
+        assert_eq!(
+            pod_workload(&pod),
+            ("Job".to_string(), "migrate".to_string())
+        );
+
+        pod.owner_kind = None;
+        pod.owner_name = None;
+        assert_eq!(pod_workload(&pod).0, "Pod");
+    }
+
     #[test]
     fn test_ingress_info_creation() {
         let ingress = IngressInfo {
@@ -1781,6 +4891,9 @@ mod tests {
             age: "5d".to_string(),
             labels: labels.clone(),
             selector: selector.clone(),
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert_eq!(deployment.name, "test-deployment");
@@ -1811,6 +4924,12 @@ mod tests {
             age: "10d".to_string(),
             labels: labels.clone(),
             selector: selector.clone(),
+            images: BTreeMap::new(),
+            volume_claim_templates: Vec::new(),
+            update_revision: None,
+            ordinals: Vec::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert_eq!(statefulset.name, "test-statefulset");
@@ -1841,6 +4960,9 @@ mod tests {
             age: "30d".to_string(),
             labels: labels.clone(),
             selector: selector.clone(),
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert_eq!(daemonset.name, "test-daemonset");
@@ -1868,6 +4990,9 @@ mod tests {
             age: "2d".to_string(),
             labels: BTreeMap::new(),
             selector: BTreeMap::new(),
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         // Test JSON serialization
@@ -1895,6 +5020,9 @@ mod tests {
             age: "1h".to_string(),
             labels: BTreeMap::new(),
             selector: BTreeMap::new(),
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert!(deployment.labels.is_empty());
@@ -1919,6 +5047,12 @@ mod tests {
             age: "7d".to_string(),
             labels: labels.clone(),
             selector: labels.clone(),
+            images: BTreeMap::new(),
+            volume_claim_templates: Vec::new(),
+            update_revision: None,
+            ordinals: Vec::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert_eq!(statefulset.labels.len(), 4);
@@ -1936,18 +5070,13 @@ mod tests {
         use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
         use std::collections::BTreeMap;
 
-        let mut service = Service::default();
-        service.metadata = ObjectMeta {
-            name: Some("test-service".to_string()),
-            namespace: Some("default".to_string()),
+        let mut spec = ServiceSpec {
+            type_: Some("ClusterIP".to_string()),
+            cluster_ip: Some("10.0.0.1".to_string()),
+            selector: Some(BTreeMap::new()),
             ..Default::default()
         };
 
-        let mut spec = ServiceSpec::default();
-        spec.type_ = Some("ClusterIP".to_string());
-        spec.cluster_ip = Some("10.0.0.1".to_string());
-        spec.selector = Some(BTreeMap::new());
-
         let port = K8sServicePort {
             name: Some("http".to_string()),
             port: 80,
@@ -1960,7 +5089,16 @@ mod tests {
             ..Default::default()
         };
         spec.ports = Some(vec![port]);
-        service.spec = Some(spec);
+
+        let service = Service {
+            metadata: ObjectMeta {
+                name: Some("test-service".to_string()),
+                namespace: Some("default".to_string()),
+                ..Default::default()
+            },
+            spec: Some(spec),
+            ..Default::default()
+        };
 
         let converted = service.lazy_convert().unwrap();
         assert_eq!(converted.name, "test-service");
@@ -1978,13 +5116,6 @@ mod tests {
         use k8s_openapi::api::core::v1::{Container, ContainerStatus, Pod, PodSpec, PodStatus};
         use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
 
-        let mut pod = Pod::default();
-        pod.metadata = ObjectMeta {
-            name: Some("test-pod".to_string()),
-            namespace: Some("default".to_string()),
-            ..Default::default()
-        };
-
         let spec = PodSpec {
             containers: vec![Container {
                 name: "test-container".to_string(),
@@ -1993,7 +5124,6 @@ mod tests {
             node_name: Some("test-node".to_string()),
             ..Default::default()
         };
-        pod.spec = Some(spec);
 
         let status = PodStatus {
             phase: Some("Running".to_string()),
@@ -2006,7 +5136,16 @@ mod tests {
             }]),
             ..Default::default()
         };
-        pod.status = Some(status);
+
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("test-pod".to_string()),
+                namespace: Some("default".to_string()),
+                ..Default::default()
+            },
+            spec: Some(spec),
+            status: Some(status),
+        };
 
         let converted = pod.lazy_convert().unwrap();
         assert_eq!(converted.name, "test-pod");
@@ -2060,4 +5199,121 @@ mod tests {
         assert_eq!(converted[1].name, "service2");
         assert_eq!(converted[1].service_type, "NodePort");
     }
+
+    #[test]
+    fn test_container_counts() {
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "spec": {
+                "initContainers": [
+                    {"name": "migrate"},
+                    {"name": "proxy", "restartPolicy": "Always"},
+                ],
+                "containers": [{"name": "web"}, {"name": "worker"}],
+            },
+            "status": {
+                "initContainerStatuses": [
+                    {"name": "migrate", "ready": false, "restartCount": 4, "image": "", "imageID": ""},
+                    {"name": "proxy", "ready": true, "restartCount": 1, "image": "", "imageID": ""},
+                ],
+                "containerStatuses": [
+                    {"name": "web", "ready": true, "restartCount": 2, "image": "", "imageID": ""},
+                    {"name": "worker", "ready": false, "restartCount": 0, "image": "", "imageID": ""},
+                ],
+            },
+        }))
+        .unwrap();
+
+        let counts = container_counts(pod.spec.as_ref().unwrap(), pod.status.as_ref());
+        assert_eq!(counts, (2, 3, 3));
+        assert_eq!(
+            container_counts(pod.spec.as_ref().unwrap(), None),
+            (0, 3, 0)
+        );
+    }
+
+    #[test]
+    fn test_pod_qos_class() {
+        use k8s_openapi::api::core::v1::{Container, ResourceRequirements};
+        use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+        let resources = |requests: &[(&str, &str)], limits: &[(&str, &str)]| {
+            let to_map = |pairs: &[(&str, &str)]| {
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), Quantity(v.to_string())))
+                    .collect::<BTreeMap<_, _>>()
+            };
+            Some(ResourceRequirements {
+                requests: Some(to_map(requests)),
+                limits: Some(to_map(limits)),
+                ..Default::default()
+            })
+        };
+        let spec_with = |resources: Option<ResourceRequirements>| PodSpec {
+            containers: vec![Container {
+                name: "app".to_string(),
+                resources,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(pod_qos_class(&spec_with(None), None), "BestEffort");
+        assert_eq!(
+            pod_qos_class(
+                &spec_with(resources(&[], &[("cpu", "1"), ("memory", "1Gi")])),
+                None
+            ),
+            "Guaranteed"
+        );
+        assert_eq!(
+            pod_qos_class(
+                &spec_with(resources(
+                    &[("cpu", "1000m"), ("memory", "1Gi")],
+                    &[("cpu", "1"), ("memory", "1Gi")]
+                )),
+                None
+            ),
+            "Guaranteed"
+        );
+        assert_eq!(
+            pod_qos_class(&spec_with(resources(&[("cpu", "100m")], &[])), None),
+            "Burstable"
+        );
+        // Other resources do not lift a pod out of BestEffort
+        assert_eq!(
+            pod_qos_class(
+                &spec_with(resources(&[("ephemeral-storage", "1Gi")], &[])),
+                None
+            ),
+            "BestEffort"
+        );
+
+        // The API server's own classification wins
+        let status = PodStatus {
+            qos_class: Some("Guaranteed".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(pod_qos_class(&spec_with(None), Some(&status)), "Guaranteed");
+    }
+
+    #[test]
+    fn test_custom_resource_summaries() {
+        let spec = serde_json::json!({"secretName": "tls", "issuerRef": {"name": "le"}});
+        assert_eq!(summarize_spec(Some(&spec)), "issuerRef, secretName");
+        assert_eq!(summarize_spec(None), "");
+
+        let ready = serde_json::json!({"conditions": [{"type": "Ready", "status": "True"}]});
+        assert_eq!(summarize_status(Some(&ready)).as_deref(), Some("Ready"));
+        let failing = serde_json::json!({
+            "conditions": [{"type": "Ready", "status": "False", "reason": "Pending"}]
+        });
+        assert_eq!(
+            summarize_status(Some(&failing)).as_deref(),
+            Some("NotReady: Pending")
+        );
+        let phase = serde_json::json!({"phase": "Running"});
+        assert_eq!(summarize_status(Some(&phase)).as_deref(), Some("Running"));
+        assert_eq!(summarize_status(None), None);
+    }
 }
@@ -0,0 +1,280 @@
+//! Disk-backed layer of the resource cache (`kdx cache persist on`)
+//!
+//! With persistence on, every listing cached in memory is also written to
+//! `<cache dir>/resources/<cluster>/<kind>/` as JSON together with the time it
+//! expires, so the next kdx invocation in the same shell session is answered
+//! from disk instead of listing the cluster again. Persistence is a setting
+//! of the cache directory rather than of a run; turning it off deletes the
+//! files. Cache hits and misses are added up across runs in `counters.json`
+//! so `kdx cache stats` can report them.
+
+use crate::cache::cache_dir;
+use crate::clusters;
+use crate::error::{ExplorerError, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File whose presence in the resources directory turns persistence on
+const ENABLED_MARKER: &str = "enabled";
+const COUNTERS_FILE: &str = "counters.json";
+
+/// A cached listing as stored on disk
+#[derive(Serialize, Deserialize)]
+struct StoredEntry<T> {
+    expires_at: DateTime<Utc>,
+    data: T,
+}
+
+/// Cache lookups counted across runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskCounters {
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits answered from disk rather than from memory
+    pub disk_hits: u64,
+    /// Entries dropped from memory because they expired
+    pub evictions: u64,
+}
+
+/// Size of the persisted cache, for `kdx cache stats`
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub dir: PathBuf,
+    pub entries: usize,
+    pub bytes: u64,
+    pub counters: DiskCounters,
+}
+
+fn root() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("resources"))
+}
+
+/// Whether cached listings are persisted
+pub fn is_enabled() -> bool {
+    root().is_some_and(|root| root.join(ENABLED_MARKER).exists())
+}
+
+/// Turn persistence on, or turn it off and delete everything persisted
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    let root = root().ok_or_else(|| {
+        ExplorerError::InvalidArgument(
+            "no cache directory; set KDX_CACHE_DIR to persist the cache".to_string(),
+        )
+    })?;
+    if enabled {
+        fs::create_dir_all(&root)?;
+        fs::write(root.join(ENABLED_MARKER), "")?;
+    } else if root.exists() {
+        fs::remove_dir_all(&root)?;
+    }
+    Ok(())
+}
+
+/// Entries and bytes persisted for every context, or `None` when
+/// persistence is off
+pub fn usage() -> Option<DiskUsage> {
+    is_enabled().then(|| usage_at(&root()?)).flatten()
+}
+
+fn usage_at(root: &Path) -> Option<DiskUsage> {
+    fn walk(dir: &Path, usage: &mut DiskUsage) {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, usage);
+            } else if path.extension().is_some_and(|e| e == "json")
+                && path.file_name().is_some_and(|n| n != COUNTERS_FILE)
+            {
+                usage.entries += 1;
+                usage.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+        }
+    }
+
+    let mut usage = DiskUsage {
+        dir: root.to_path_buf(),
+        entries: 0,
+        bytes: 0,
+        counters: read_counters(root),
+    };
+    walk(root, &mut usage);
+    Some(usage)
+}
+
+fn read_counters(root: &Path) -> DiskCounters {
+    fs::read(root.join(COUNTERS_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Keys contain namespaces and label selectors; anything else than
+/// alphanumerics, `-` and `.` is percent-encoded so distinct keys never
+/// share a file
+pub(crate) fn encode(key: &str) -> String {
+    let mut name = String::new();
+    for c in key.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+            name.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                name.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    name
+}
+
+fn file_name(key: &str) -> String {
+    format!("{}.json", encode(key))
+}
+
+/// Persisted listings of one cluster
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Listings of the cluster of `context` (or the current context), if
+    /// persistence is on. They are kept per API server, see
+    /// [`clusters::cache_key`].
+    pub fn open(context: Option<&str>) -> Option<Self> {
+        if !is_enabled() {
+            return None;
+        }
+        Some(Self::at(root()?, &clusters::cache_key(context)))
+    }
+
+    fn at(root: PathBuf, cluster: &str) -> Self {
+        let dir = root.join(encode(cluster));
+        Self { root, dir }
+    }
+
+    fn path(&self, kind: &str, key: &str) -> PathBuf {
+        self.dir.join(kind).join(file_name(key))
+    }
+
+    /// The listing of `kind` stored under `key` and how long it stays fresh.
+    /// Expired and unreadable files are removed.
+    pub fn load<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Option<(T, Duration)> {
+        let path = self.path(kind, key);
+        let data = fs::read(&path).ok()?;
+        let fresh = serde_json::from_slice::<StoredEntry<T>>(&data)
+            .ok()
+            .and_then(|entry| {
+                let remaining = (entry.expires_at - Utc::now()).to_std().ok()?;
+                (!remaining.is_zero()).then_some((entry.data, remaining))
+            });
+        if fresh.is_none() {
+            let _ = fs::remove_file(&path);
+        }
+        fresh
+    }
+
+    /// Store a listing for `ttl`. Failures are ignored: the memory cache
+    /// still holds the listing, and the next run lists the cluster again.
+    pub fn store<T: Serialize>(&self, kind: &str, key: &str, data: &T, ttl: Duration) {
+        let Ok(ttl) = chrono::Duration::from_std(ttl) else {
+            return;
+        };
+        let entry = StoredEntry {
+            expires_at: Utc::now() + ttl,
+            data,
+        };
+        let path = self.path(kind, key);
+        let Some(dir) = path.parent() else {
+            return;
+        };
+        let Ok(json) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        // Written aside and renamed, so a concurrent run never reads half a file
+        let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+        let written = fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&partial, json))
+            .and_then(|_| fs::rename(&partial, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+    }
+
+    /// Delete every listing of the context
+    pub fn clear(&self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+
+    /// Add the lookups of this run to the counters kept across runs
+    pub fn add_counters(&self, run: DiskCounters) {
+        let mut counters = read_counters(&self.root);
+        counters.hits += run.hits;
+        counters.misses += run.misses;
+        counters.disk_hits += run.disk_hits;
+        counters.evictions += run.evictions;
+        if let Ok(json) = serde_json::to_vec(&counters) {
+            let _ = fs::write(self.root.join(COUNTERS_FILE), json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_load() {
+        let root = std::env::temp_dir().join(format!("kdx-disk-cache-{}", std::process::id()));
+        let cache = DiskCache::at(root.clone(), "prod/eu");
+        assert_eq!(cache.dir, root.join("prod%2Feu"));
+
+        let names = vec!["web".to_string(), "db".to_string()];
+        cache.store("services", "shop:app=web", &names, Duration::from_secs(60));
+        let (loaded, remaining) = cache
+            .load::<Vec<String>>("services", "shop:app=web")
+            .unwrap();
+        assert_eq!(loaded, names);
+        assert!(remaining <= Duration::from_secs(60));
+        assert!(cache
+            .load::<Vec<String>>("services", "shop:app_web")
+            .is_none());
+
+        // Expired listings are removed when read
+        cache.store("pods", "shop", &names, Duration::ZERO);
+        assert!(cache.load::<Vec<String>>("pods", "shop").is_none());
+        assert!(!cache.path("pods", "shop").exists());
+
+        cache.add_counters(DiskCounters {
+            hits: 2,
+            misses: 1,
+            disk_hits: 1,
+            evictions: 2,
+        });
+        cache.add_counters(DiskCounters {
+            hits: 1,
+            ..Default::default()
+        });
+        let usage = usage_at(&root).unwrap();
+        assert_eq!(usage.entries, 1);
+        assert!(usage.bytes > 0);
+        assert_eq!(
+            usage.counters,
+            DiskCounters {
+                hits: 3,
+                misses: 1,
+                disk_hits: 1,
+                evictions: 2,
+            }
+        );
+
+        cache.clear();
+        assert_eq!(usage_at(&root).unwrap().entries, 0);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
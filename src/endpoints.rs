@@ -0,0 +1,420 @@
+//! Watching the EndpointSlices of a service for churn
+//!
+//! Endpoints are tracked per slice and keyed by address. Every watch event is
+//! compared with the previous state of its slice, producing one change per
+//! endpoint added, removed or flipping between ready and not ready.
+
+use crate::error::{ExplorerError, Result};
+use crate::session::ClientSession;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::Service;
+use k8s_openapi::api::discovery::v1::EndpointSlice;
+use kube::api::{ListParams, WatchEvent, WatchParams};
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Label linking an EndpointSlice to its service
+const SERVICE_NAME_LABEL: &str = "kubernetes.io/service-name";
+
+/// State of one endpoint address in a slice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointState {
+    /// Pod (or other target) behind the address
+    pub target: Option<String>,
+    pub node: Option<String>,
+    pub ready: bool,
+    pub terminating: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    BecameReady,
+    BecameNotReady,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::BecameReady => write!(f, "ready"),
+            ChangeKind::BecameNotReady => write!(f, "not ready"),
+        }
+    }
+}
+
+/// One observed change to the endpoints of a service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointChange {
+    pub time: DateTime<Utc>,
+    pub change: ChangeKind,
+    pub address: String,
+    pub target: Option<String>,
+    pub node: Option<String>,
+    /// Readiness after the change (before it, for removals)
+    pub ready: bool,
+    pub terminating: bool,
+}
+
+/// One current endpoint address of a service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEndpoint {
+    pub address: String,
+    pub target: Option<String>,
+    pub node: Option<String>,
+    pub ready: bool,
+    pub terminating: bool,
+}
+
+/// Changes seen for one address over a watch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EndpointChurn {
+    pub address: String,
+    pub target: Option<String>,
+    pub added: usize,
+    pub removed: usize,
+    pub ready_flips: usize,
+}
+
+/// Endpoints of a slice keyed by address
+pub fn slice_endpoints(slice: &EndpointSlice) -> BTreeMap<String, EndpointState> {
+    let mut endpoints = BTreeMap::new();
+    for endpoint in &slice.endpoints {
+        let conditions = endpoint.conditions.as_ref();
+        let state = EndpointState {
+            target: endpoint.target_ref.as_ref().and_then(|t| t.name.clone()),
+            node: endpoint.node_name.clone(),
+            // An unset ready condition means ready
+            ready: conditions.and_then(|c| c.ready).unwrap_or(true),
+            terminating: conditions.and_then(|c| c.terminating).unwrap_or(false),
+        };
+        for address in &endpoint.addresses {
+            endpoints.insert(address.clone(), state.clone());
+        }
+    }
+    endpoints
+}
+
+/// Changes between two states of the same slice
+pub fn diff(
+    before: &BTreeMap<String, EndpointState>,
+    after: &BTreeMap<String, EndpointState>,
+    time: DateTime<Utc>,
+) -> Vec<EndpointChange> {
+    let change = |kind, address: &str, state: &EndpointState| EndpointChange {
+        time,
+        change: kind,
+        address: address.to_string(),
+        target: state.target.clone(),
+        node: state.node.clone(),
+        ready: state.ready,
+        terminating: state.terminating,
+    };
+
+    let mut changes = Vec::new();
+    for (address, state) in after {
+        match before.get(address) {
+            None => changes.push(change(ChangeKind::Added, address, state)),
+            Some(old) if old.ready != state.ready => {
+                let kind = if state.ready {
+                    ChangeKind::BecameReady
+                } else {
+                    ChangeKind::BecameNotReady
+                };
+                changes.push(change(kind, address, state));
+            }
+            Some(_) => {}
+        }
+    }
+    for (address, state) in before {
+        if !after.contains_key(address) {
+            changes.push(change(ChangeKind::Removed, address, state));
+        }
+    }
+    changes
+}
+
+/// Tally changes per address, busiest first
+pub fn churn(changes: &[EndpointChange]) -> Vec<EndpointChurn> {
+    let mut by_address: BTreeMap<&str, EndpointChurn> = BTreeMap::new();
+    for change in changes {
+        let entry = by_address
+            .entry(&change.address)
+            .or_insert_with(|| EndpointChurn {
+                address: change.address.clone(),
+                ..Default::default()
+            });
+        if change.target.is_some() {
+            entry.target = change.target.clone();
+        }
+        match change.change {
+            ChangeKind::Added => entry.added += 1,
+            ChangeKind::Removed => entry.removed += 1,
+            ChangeKind::BecameReady | ChangeKind::BecameNotReady => entry.ready_flips += 1,
+        }
+    }
+
+    let mut churn: Vec<EndpointChurn> = by_address.into_values().collect();
+    churn.sort_by_key(|c| std::cmp::Reverse(c.added + c.removed + c.ready_flips));
+    churn
+}
+
+/// Endpoints of every slice of a service, with the changes seen so far
+#[derive(Default)]
+struct SliceTracker {
+    slices: BTreeMap<String, BTreeMap<String, EndpointState>>,
+    changes: Vec<EndpointChange>,
+}
+
+impl SliceTracker {
+    /// Replace the endpoints of `slice`, reporting what changed
+    fn update<F>(
+        &mut self,
+        slice: &str,
+        after: BTreeMap<String, EndpointState>,
+        on_change: &mut F,
+    ) -> Result<()>
+    where
+        F: FnMut(&EndpointChange) -> Result<()>,
+    {
+        let before = self.slices.remove(slice).unwrap_or_default();
+        for change in diff(&before, &after, Utc::now()) {
+            on_change(&change)?;
+            self.changes.push(change);
+        }
+        if !after.is_empty() {
+            self.slices.insert(slice.to_string(), after);
+        }
+        Ok(())
+    }
+
+    /// Replace every slice with a fresh listing
+    fn relist<F>(&mut self, slices: &[EndpointSlice], on_change: &mut F) -> Result<()>
+    where
+        F: FnMut(&EndpointChange) -> Result<()>,
+    {
+        let mut current: BTreeMap<String, BTreeMap<String, EndpointState>> = slices
+            .iter()
+            .map(|s| {
+                (
+                    s.metadata.name.clone().unwrap_or_default(),
+                    slice_endpoints(s),
+                )
+            })
+            .collect();
+        let names: BTreeSet<String> = self.slices.keys().chain(current.keys()).cloned().collect();
+        for name in names {
+            let after = current.remove(&name).unwrap_or_default();
+            self.update(&name, after, on_change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Current endpoints of `service` across its EndpointSlices, by address
+pub async fn service_endpoints(
+    client: &Client,
+    service: &str,
+    namespace: &str,
+) -> Result<Vec<ServiceEndpoint>> {
+    let api: Api<EndpointSlice> = Api::namespaced(client.clone(), namespace);
+    let selector = format!("{}={}", SERVICE_NAME_LABEL, service);
+    let slices = api.list(&ListParams::default().labels(&selector)).await?;
+
+    let mut endpoints: BTreeMap<String, EndpointState> = BTreeMap::new();
+    for slice in &slices.items {
+        endpoints.extend(slice_endpoints(slice));
+    }
+    Ok(endpoints
+        .into_iter()
+        .map(|(address, state)| ServiceEndpoint {
+            address,
+            target: state.target,
+            node: state.node,
+            ready: state.ready,
+            terminating: state.terminating,
+        })
+        .collect())
+}
+
+/// Watch the EndpointSlices of `service`, calling `on_change` for every change
+/// and returning all changes seen once `duration` elapses or Ctrl-C is
+/// pressed. The endpoints present at the start are the baseline and are not
+/// reported. The watch is resumed when the API server closes it; if its
+/// resource version has expired the slices are listed again and anything
+/// that changed in between is reported. When the credentials are rejected
+/// the watch resumes with a client rebuilt by `session`.
+pub async fn watch_endpoints<F>(
+    session: &ClientSession,
+    service: &str,
+    namespace: &str,
+    duration: Option<Duration>,
+    mut on_change: F,
+) -> Result<Vec<EndpointChange>>
+where
+    F: FnMut(&EndpointChange) -> Result<()>,
+{
+    let (mut generation, client) = session.client().await;
+    // Fail early on a mistyped service rather than watching nothing
+    Api::<Service>::namespaced(client.clone(), namespace)
+        .get(service)
+        .await?;
+
+    let mut api: Api<EndpointSlice> = Api::namespaced(client, namespace);
+    let selector = format!("{}={}", SERVICE_NAME_LABEL, service);
+    let deadline = duration.map(|d| Instant::now() + d);
+
+    let list = api.list(&ListParams::default().labels(&selector)).await?;
+    let mut resource_version = list.metadata.resource_version.unwrap_or_default();
+    let mut tracker = SliceTracker::default();
+    tracker.relist(&list.items, &mut |_| Ok(()))?;
+
+    let endpoints: Vec<&EndpointState> = tracker.slices.values().flat_map(|s| s.values()).collect();
+    eprintln!(
+        "Watching {} endpoints of service {}/{} ({} ready)",
+        endpoints.len(),
+        namespace,
+        service,
+        endpoints.iter().filter(|e| e.ready).count()
+    );
+
+    let mut refreshed = false;
+    loop {
+        let params = WatchParams::default().labels(&selector);
+        let failure: Option<ExplorerError> = match api.watch(&params, &resource_version).await {
+            Err(e) => Some(e.into()),
+            Ok(stream) => {
+                refreshed = false;
+                let mut stream = stream.boxed();
+                loop {
+                    let next = tokio::select! {
+                        next = stream.next() => next,
+                        _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                            if deadline.is_some() => return Ok(tracker.changes),
+                        _ = tokio::signal::ctrl_c() => return Ok(tracker.changes),
+                    };
+
+                    match next {
+                        // The server ended the watch; resume from the last version
+                        None => break None,
+                        Some(Ok(WatchEvent::Added(slice) | WatchEvent::Modified(slice))) => {
+                            resource_version = slice
+                                .metadata
+                                .resource_version
+                                .clone()
+                                .unwrap_or(resource_version);
+                            let name = slice.metadata.name.clone().unwrap_or_default();
+                            tracker.update(&name, slice_endpoints(&slice), &mut on_change)?;
+                        }
+                        Some(Ok(WatchEvent::Deleted(slice))) => {
+                            resource_version = slice
+                                .metadata
+                                .resource_version
+                                .clone()
+                                .unwrap_or(resource_version);
+                            let name = slice.metadata.name.clone().unwrap_or_default();
+                            tracker.update(&name, BTreeMap::new(), &mut on_change)?;
+                        }
+                        Some(Ok(WatchEvent::Bookmark(bookmark))) => {
+                            resource_version = bookmark.metadata.resource_version;
+                        }
+                        // 410 Gone: the version is too old to resume from
+                        Some(Ok(WatchEvent::Error(e))) if e.code == 410 => {
+                            match api.list(&ListParams::default().labels(&selector)).await {
+                                Ok(list) => {
+                                    resource_version =
+                                        list.metadata.resource_version.unwrap_or_default();
+                                    tracker.relist(&list.items, &mut on_change)?;
+                                    break None;
+                                }
+                                Err(e) => break Some(e.into()),
+                            }
+                        }
+                        Some(Ok(WatchEvent::Error(e))) => {
+                            break Some(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                        }
+                        Some(Err(e)) => break Some(e.into()),
+                    }
+                }
+            }
+        };
+
+        if let Some(e) = failure {
+            if !e.is_unauthorized() || refreshed {
+                return Err(e);
+            }
+            let (rebuilt, client) = session.refresh(generation).await?;
+            generation = rebuilt;
+            api = Api::namespaced(client, namespace);
+            refreshed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slice(endpoints: serde_json::Value) -> EndpointSlice {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "web-abc12"},
+            "addressType": "IPv4",
+            "endpoints": endpoints,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_endpoints() {
+        let before = slice_endpoints(&slice(serde_json::json!([
+            {"addresses": ["10.0.0.1"], "conditions": {"ready": true},
+             "targetRef": {"kind": "Pod", "name": "web-1"}},
+            {"addresses": ["10.0.0.2"], "conditions": {"ready": true},
+             "targetRef": {"kind": "Pod", "name": "web-2"}},
+        ])));
+        let after = slice_endpoints(&slice(serde_json::json!([
+            {"addresses": ["10.0.0.2"], "conditions": {"ready": false, "terminating": true},
+             "targetRef": {"kind": "Pod", "name": "web-2"}},
+            {"addresses": ["10.0.0.3"], "targetRef": {"kind": "Pod", "name": "web-3"}},
+        ])));
+
+        let changes = diff(&before, &after, Utc::now());
+        let summary: Vec<(ChangeKind, &str)> = changes
+            .iter()
+            .map(|c| (c.change, c.address.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeKind::BecameNotReady, "10.0.0.2"),
+                (ChangeKind::Added, "10.0.0.3"),
+                (ChangeKind::Removed, "10.0.0.1"),
+            ]
+        );
+        assert!(changes[0].terminating);
+        // No ready condition counts as ready
+        assert!(changes[1].ready);
+        assert_eq!(changes[2].target.as_deref(), Some("web-1"));
+
+        assert!(diff(&after, &after, Utc::now()).is_empty());
+
+        let flapping = [changes.clone(), diff(&after, &before, Utc::now())].concat();
+        let churn = churn(&flapping);
+        assert_eq!(churn.len(), 3);
+        let flipped = churn.iter().find(|c| c.address == "10.0.0.2").unwrap();
+        assert_eq!(
+            (flipped.added, flipped.removed, flipped.ready_flips),
+            (0, 0, 2)
+        );
+        let replaced = churn.iter().find(|c| c.address == "10.0.0.1").unwrap();
+        assert_eq!((replaced.added, replaced.removed), (1, 1));
+    }
+}
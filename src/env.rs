@@ -0,0 +1,309 @@
+//! Effective container environment resolution
+
+use k8s_openapi::api::core::v1::{Container, PodSpec};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Placeholder shown instead of secret values
+const REDACTED: &str = "<redacted>";
+
+/// A resolved environment variable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvVar {
+    pub name: String,
+    /// Resolved value; `None` when it is only known at runtime or cannot be resolved
+    pub value: Option<String>,
+    /// Where the value comes from (e.g. "literal", "configmap/app:LOG_LEVEL")
+    pub source: String,
+}
+
+/// Effective environment of a single container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerEnv {
+    pub container: String,
+    pub init: bool,
+    pub variables: Vec<EnvVar>,
+}
+
+/// ConfigMap data and Secret key names referenced by a pod spec
+#[derive(Debug, Default)]
+pub struct EnvSources {
+    pub configmaps: HashMap<String, BTreeMap<String, String>>,
+    pub secrets: HashMap<String, BTreeSet<String>>,
+}
+
+/// Names of ConfigMaps and Secrets referenced from env and envFrom
+pub fn referenced_sources(spec: &PodSpec) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut configmaps = BTreeSet::new();
+    let mut secrets = BTreeSet::new();
+
+    for container in all_containers(spec).map(|(c, _)| c) {
+        for env_from in container.env_from.iter().flatten() {
+            if let Some(cm) = &env_from.config_map_ref {
+                configmaps.insert(cm.name.clone().unwrap_or_default());
+            }
+            if let Some(secret) = &env_from.secret_ref {
+                secrets.insert(secret.name.clone().unwrap_or_default());
+            }
+        }
+        for var in container.env.iter().flatten() {
+            let Some(value_from) = &var.value_from else {
+                continue;
+            };
+            if let Some(cm) = &value_from.config_map_key_ref {
+                configmaps.insert(cm.name.clone().unwrap_or_default());
+            }
+            if let Some(secret) = &value_from.secret_key_ref {
+                secrets.insert(secret.name.clone().unwrap_or_default());
+            }
+        }
+    }
+
+    (configmaps, secrets)
+}
+
+/// Resolve the effective environment of every container in the pod spec
+pub fn resolve_env(spec: &PodSpec, sources: &EnvSources) -> Vec<ContainerEnv> {
+    all_containers(spec)
+        .map(|(container, init)| ContainerEnv {
+            container: container.name.clone(),
+            init,
+            variables: resolve_container_env(container, sources),
+        })
+        .collect()
+}
+
+fn all_containers(spec: &PodSpec) -> impl Iterator<Item = (&Container, bool)> {
+    spec.init_containers
+        .iter()
+        .flatten()
+        .map(|c| (c, true))
+        .chain(spec.containers.iter().map(|c| (c, false)))
+}
+
+/// envFrom entries are applied first in order; explicit env entries override them
+fn resolve_container_env(container: &Container, sources: &EnvSources) -> Vec<EnvVar> {
+    let mut resolved: Vec<EnvVar> = Vec::new();
+    let mut set = |var: EnvVar| match resolved.iter_mut().find(|v| v.name == var.name) {
+        Some(existing) => *existing = var,
+        None => resolved.push(var),
+    };
+
+    for env_from in container.env_from.iter().flatten() {
+        let prefix = env_from.prefix.clone().unwrap_or_default();
+
+        if let Some(cm) = &env_from.config_map_ref {
+            let name = cm.name.clone().unwrap_or_default();
+            match sources.configmaps.get(&name) {
+                Some(data) => {
+                    for (key, value) in data {
+                        set(EnvVar {
+                            name: format!("{}{}", prefix, key),
+                            value: Some(value.clone()),
+                            source: format!("configmap/{} (envFrom)", name),
+                        });
+                    }
+                }
+                None => set(EnvVar {
+                    name: format!("{}*", prefix),
+                    value: None,
+                    source: format!("configmap/{} (envFrom, not found)", name),
+                }),
+            }
+        }
+
+        if let Some(secret) = &env_from.secret_ref {
+            let name = secret.name.clone().unwrap_or_default();
+            match sources.secrets.get(&name) {
+                Some(keys) => {
+                    for key in keys {
+                        set(EnvVar {
+                            name: format!("{}{}", prefix, key),
+                            value: Some(REDACTED.to_string()),
+                            source: format!("secret/{} (envFrom)", name),
+                        });
+                    }
+                }
+                None => set(EnvVar {
+                    name: format!("{}*", prefix),
+                    value: None,
+                    source: format!("secret/{} (envFrom, not found)", name),
+                }),
+            }
+        }
+    }
+
+    for var in container.env.iter().flatten() {
+        let Some(value_from) = &var.value_from else {
+            set(EnvVar {
+                name: var.name.clone(),
+                value: Some(var.value.clone().unwrap_or_default()),
+                source: "literal".to_string(),
+            });
+            continue;
+        };
+
+        let (value, source) = if let Some(cm) = &value_from.config_map_key_ref {
+            let name = cm.name.clone().unwrap_or_default();
+            let value = sources
+                .configmaps
+                .get(&name)
+                .and_then(|data| data.get(&cm.key))
+                .cloned();
+            let missing = if value.is_none() { ", not found" } else { "" };
+            (value, format!("configmap/{}:{}{}", name, cm.key, missing))
+        } else if let Some(secret) = &value_from.secret_key_ref {
+            let name = secret.name.clone().unwrap_or_default();
+            let found = sources
+                .secrets
+                .get(&name)
+                .is_some_and(|keys| keys.contains(&secret.key));
+            if found {
+                (
+                    Some(REDACTED.to_string()),
+                    format!("secret/{}:{}", name, secret.key),
+                )
+            } else {
+                (None, format!("secret/{}:{}, not found", name, secret.key))
+            }
+        } else if let Some(field) = &value_from.field_ref {
+            (None, format!("field {}", field.field_path))
+        } else if let Some(resource) = &value_from.resource_field_ref {
+            (None, format!("resource {}", resource.resource))
+        } else {
+            (None, "unknown".to_string())
+        };
+
+        set(EnvVar {
+            name: var.name.clone(),
+            value,
+            source,
+        });
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{
+        ConfigMapEnvSource, ConfigMapKeySelector, EnvFromSource, EnvVar as K8sEnvVar, EnvVarSource,
+        SecretEnvSource, SecretKeySelector,
+    };
+
+    fn spec() -> PodSpec {
+        let env = vec![
+            K8sEnvVar {
+                name: "LOG_LEVEL".to_string(),
+                value: Some("debug".to_string()),
+                ..Default::default()
+            },
+            K8sEnvVar {
+                name: "REGION".to_string(),
+                value_from: Some(EnvVarSource {
+                    config_map_key_ref: Some(ConfigMapKeySelector {
+                        name: Some("app-config".to_string()),
+                        key: "region".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            K8sEnvVar {
+                name: "DB_PASSWORD".to_string(),
+                value_from: Some(EnvVarSource {
+                    secret_key_ref: Some(SecretKeySelector {
+                        name: Some("db".to_string()),
+                        key: "password".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ];
+        let env_from = vec![
+            EnvFromSource {
+                config_map_ref: Some(ConfigMapEnvSource {
+                    name: Some("app-config".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            EnvFromSource {
+                prefix: Some("DB_".to_string()),
+                secret_ref: Some(SecretEnvSource {
+                    name: Some("db".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ];
+
+        PodSpec {
+            containers: vec![Container {
+                name: "web".to_string(),
+                env: Some(env),
+                env_from: Some(env_from),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn sources() -> EnvSources {
+        let mut sources = EnvSources::default();
+        sources.configmaps.insert(
+            "app-config".to_string(),
+            [
+                ("region".to_string(), "eu-west-1".to_string()),
+                ("LOG_LEVEL".to_string(), "info".to_string()),
+            ]
+            .into(),
+        );
+        sources.secrets.insert(
+            "db".to_string(),
+            ["password".to_string(), "USER".to_string()].into(),
+        );
+        sources
+    }
+
+    #[test]
+    fn test_referenced_sources() {
+        let (configmaps, secrets) = referenced_sources(&spec());
+        assert_eq!(
+            configmaps.into_iter().collect::<Vec<_>>(),
+            vec!["app-config"]
+        );
+        assert_eq!(secrets.into_iter().collect::<Vec<_>>(), vec!["db"]);
+    }
+
+    #[test]
+    fn test_resolve_env() {
+        let env = resolve_env(&spec(), &sources());
+        assert_eq!(env.len(), 1);
+        let vars = &env[0].variables;
+        let get = |name: &str| vars.iter().find(|v| v.name == name).unwrap();
+
+        // Explicit env overrides the envFrom value
+        assert_eq!(get("LOG_LEVEL").value.as_deref(), Some("debug"));
+        assert_eq!(get("LOG_LEVEL").source, "literal");
+
+        assert_eq!(get("REGION").value.as_deref(), Some("eu-west-1"));
+        assert_eq!(get("region").source, "configmap/app-config (envFrom)");
+        assert_eq!(get("DB_USER").value.as_deref(), Some(REDACTED));
+        assert_eq!(get("DB_PASSWORD").source, "secret/db:password");
+        assert_eq!(get("DB_PASSWORD").value.as_deref(), Some(REDACTED));
+    }
+
+    #[test]
+    fn test_missing_sources() {
+        let env = resolve_env(&spec(), &EnvSources::default());
+        let vars = &env[0].variables;
+        let region = vars.iter().find(|v| v.name == "REGION").unwrap();
+        assert_eq!(region.value, None);
+        assert!(region.source.ends_with("not found"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Envelope around JSON and YAML output (`--envelope`)
+//!
+//! A bare list cannot say whether it is complete. With `--envelope` the
+//! output is an object holding the items together with the query that
+//! produced them, the warnings raised while answering it, and when and how
+//! fast it was answered. Warnings about namespaces that could not be listed
+//! mark the result incomplete; others, such as listings served from the
+//! persisted cache, are informational.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Parameters of the command whose output is wrapped
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Query {
+    pub command: String,
+    pub context: Option<String>,
+    /// Global namespace, from `-n` or the config file
+    pub namespace: Option<String>,
+    pub selector: Option<String>,
+    pub limit: Option<usize>,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Something that went wrong or is worth knowing about the result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub message: String,
+    /// Whether items are missing because of it
+    pub incomplete: bool,
+}
+
+/// When and how the output was produced
+#[derive(Debug, Clone, Serialize)]
+pub struct Metadata {
+    pub generated_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// False when a warning left items out
+    pub complete: bool,
+}
+
+/// Output wrapped with `--envelope`
+#[derive(Debug, Serialize)]
+pub struct Envelope<'a, T: Serialize + ?Sized> {
+    pub items: &'a T,
+    pub query: &'a Query,
+    pub warnings: Vec<Warning>,
+    pub metadata: Metadata,
+}
+
+/// Warnings raised while answering a command, collected by the discovery
+/// engine. Clones share the same list.
+#[derive(Debug, Clone, Default)]
+pub struct Warnings(Arc<Mutex<Vec<Warning>>>);
+
+impl Warnings {
+    fn record(&self, message: String, incomplete: bool) {
+        if let Ok(mut warnings) = self.0.lock() {
+            let warning = Warning {
+                message,
+                incomplete,
+            };
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+
+    /// Add an informational warning
+    pub fn warn(&self, message: impl Into<String>) {
+        self.record(message.into(), false);
+    }
+
+    /// Add a warning about items left out of the result
+    pub fn warn_incomplete(&self, message: impl Into<String>) {
+        self.record(message.into(), true);
+    }
+
+    /// Warnings recorded so far, in the order they were raised
+    pub fn list(&self) -> Vec<Warning> {
+        self.0.lock().map(|w| w.clone()).unwrap_or_default()
+    }
+}
+
+/// What `--envelope` wraps output with: the query, when it started and the
+/// warnings raised while answering it
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub query: Query,
+    pub started: Instant,
+    pub warnings: Warnings,
+}
+
+impl Settings {
+    /// Envelope for `query`, started now, collecting into `warnings`
+    pub fn new(query: Query, warnings: Warnings) -> Self {
+        Self {
+            query,
+            started: Instant::now(),
+            warnings,
+        }
+    }
+
+    /// `items` in an envelope with the warnings recorded so far
+    pub fn wrap<'a, T: Serialize + ?Sized>(&'a self, items: &'a T) -> Envelope<'a, T> {
+        let warnings = self.warnings.list();
+        Envelope {
+            items,
+            query: &self.query,
+            metadata: Metadata {
+                generated_at: Utc::now(),
+                duration_ms: self.started.elapsed().as_millis() as u64,
+                complete: !warnings.iter().any(|w| w.incomplete),
+            },
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope() {
+        let warnings = Warnings::default();
+        let settings = Settings::new(
+            Query {
+                command: "pods".to_string(),
+                namespace: Some("shop".to_string()),
+                ..Default::default()
+            },
+            warnings.clone(),
+        );
+        warnings.warn("pods served from the persisted cache");
+        let items = vec!["web-0", "web-1"];
+        assert!(settings.wrap(&items).metadata.complete);
+
+        let skipped = "namespace kube-system skipped: forbidden";
+        warnings.warn_incomplete(skipped);
+        warnings.warn_incomplete(skipped);
+        let value = serde_json::to_value(settings.wrap(&items)).unwrap();
+        assert_eq!(value["items"], serde_json::json!(["web-0", "web-1"]));
+        assert_eq!(value["query"]["namespace"], "shop");
+        assert_eq!(value["warnings"].as_array().unwrap().len(), 2);
+        assert_eq!(value["metadata"]["complete"], false);
+    }
+}
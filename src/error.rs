@@ -7,6 +7,9 @@ pub enum ExplorerError {
     #[error("Kubernetes API error: {0}")]
     Kubernetes(#[from] kube::Error),
 
+    #[error("Kubeconfig error: {0}")]
+    Config(String),
+
     #[error("Resource not found: {kind} '{name}' in namespace '{namespace}'")]
     ResourceNotFound {
         kind: String,
@@ -19,10 +22,38 @@ pub enum ExplorerError {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Condition cannot be met: {0}")]
+    ConditionFailed(String),
 }
 
 pub type Result<T> = std::result::Result<T, ExplorerError>;
 
+impl ExplorerError {
+    /// Whether retrying the request may succeed: throttling, server errors
+    /// and connection failures, but not denied or invalid requests
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ExplorerError::Kubernetes(kube::Error::Api(e)) => e.code == 429 || e.code >= 500,
+            ExplorerError::Kubernetes(_) => true,
+            ExplorerError::Timeout(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the API server rejected the client's credentials, which
+    /// fresh credentials may fix
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, ExplorerError::Kubernetes(kube::Error::Api(e)) if e.code == 401)
+    }
+}
+
 impl From<serde_json::Error> for ExplorerError {
     fn from(err: serde_json::Error) -> Self {
         ExplorerError::OutputFormat(err.to_string())
@@ -58,6 +89,33 @@ mod tests {
         assert_eq!(error.to_string(), "Output formatting error: Invalid JSON");
     }
 
+    #[test]
+    fn test_timeout_error_display() {
+        let error = ExplorerError::Timeout("deployment/web not ready after 5m".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Timed out: deployment/web not ready after 5m"
+        );
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        let api = |code| {
+            ExplorerError::Kubernetes(kube::Error::Api(kube::core::ErrorResponse {
+                status: "Failure".to_string(),
+                message: String::new(),
+                reason: String::new(),
+                code,
+            }))
+        };
+        assert!(api(503).is_transient());
+        assert!(api(429).is_transient());
+        assert!(!api(403).is_transient());
+        assert!(!ExplorerError::InvalidArgument("x".to_string()).is_transient());
+        assert!(api(401).is_unauthorized());
+        assert!(!api(403).is_unauthorized());
+    }
+
     #[test]
     fn test_error_is_send_and_sync() {
         fn assert_send<T: Send>() {}
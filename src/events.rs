@@ -0,0 +1,195 @@
+//! Cluster events and their correlation with pods
+//!
+//! Events are the API server's record of what happened to an object:
+//! scheduling failures, image pulls, probe failures, back-offs. They expire
+//! after an hour by default, so they describe recent trouble only. A service
+//! whose pods have Warning events (FailedScheduling, BackOff, Unhealthy) is
+//! usually why the service misbehaves, so `describe` shows them alongside it.
+
+use crate::age::format_age;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::Event;
+use kube::api::ListParams;
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// An event with the object it is about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventInfo {
+    pub namespace: String,
+    /// Normal or Warning
+    pub event_type: String,
+    pub reason: String,
+    pub message: String,
+    /// How many times the event occurred
+    pub count: i32,
+    pub object_kind: String,
+    pub object_name: String,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Time since the event last occurred
+    pub age: String,
+}
+
+impl EventInfo {
+    pub fn is_warning(&self) -> bool {
+        self.event_type == "Warning"
+    }
+
+    /// The involved object as kind/name
+    pub fn object(&self) -> String {
+        format!("{}/{}", self.object_kind.to_lowercase(), self.object_name)
+    }
+}
+
+/// Summarize `event`. Older clients set firstTimestamp/lastTimestamp/count;
+/// events.k8s.io clients set eventTime and a series instead.
+pub fn event_info(event: &Event) -> EventInfo {
+    let created = event.metadata.creation_timestamp.as_ref().map(|t| t.0);
+    let event_time = event.event_time.as_ref().map(|t| t.0);
+    let first_seen = event
+        .first_timestamp
+        .as_ref()
+        .map(|t| t.0)
+        .or(event_time)
+        .or(created);
+    let last_seen = event
+        .last_timestamp
+        .as_ref()
+        .map(|t| t.0)
+        .or_else(|| {
+            event
+                .series
+                .as_ref()
+                .and_then(|s| s.last_observed_time.as_ref())
+                .map(|t| t.0)
+        })
+        .or(first_seen);
+    let count = event
+        .count
+        .or_else(|| event.series.as_ref().and_then(|s| s.count))
+        .unwrap_or(1);
+
+    EventInfo {
+        namespace: event.metadata.namespace.clone().unwrap_or_default(),
+        event_type: event.type_.clone().unwrap_or_else(|| "Normal".to_string()),
+        reason: event.reason.clone().unwrap_or_default(),
+        message: event.message.clone().unwrap_or_default(),
+        count,
+        object_kind: event.involved_object.kind.clone().unwrap_or_default(),
+        object_name: event.involved_object.name.clone().unwrap_or_default(),
+        first_seen,
+        last_seen,
+        age: format_age(last_seen),
+    }
+}
+
+/// Events last seen at or after `since` and of `event_type` (compared without
+/// case), oldest first like `kubectl get events`
+pub fn filter_events(
+    events: Vec<EventInfo>,
+    since: Option<DateTime<Utc>>,
+    event_type: Option<&str>,
+) -> Vec<EventInfo> {
+    let mut events: Vec<EventInfo> = events
+        .into_iter()
+        .filter(|e| match (since, e.last_seen) {
+            (Some(since), Some(seen)) => seen >= since,
+            _ => true,
+        })
+        .filter(|e| event_type.is_none_or(|t| e.event_type.eq_ignore_ascii_case(t)))
+        .collect();
+    events.sort_by_key(|e| e.last_seen);
+    events
+}
+
+/// Warning events of the pods named `pods`, most recent first
+pub fn pod_warnings(events: &[EventInfo], pods: &[&str]) -> Vec<EventInfo> {
+    let pods: HashSet<&str> = pods.iter().copied().collect();
+    let mut warnings: Vec<EventInfo> = events
+        .iter()
+        .filter(|e| e.is_warning() && e.object_kind == "Pod")
+        .filter(|e| pods.contains(e.object_name.as_str()))
+        .cloned()
+        .collect();
+    warnings.sort_by_key(|e| std::cmp::Reverse(e.last_seen));
+    warnings
+}
+
+/// Events in `namespace` (or all namespaces if None) matching the field
+/// selector, if any
+pub async fn list_events(
+    client: &Client,
+    namespace: Option<&str>,
+    fields: Option<&str>,
+) -> Result<Vec<EventInfo>> {
+    let api: Api<Event> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    let mut params = ListParams::default();
+    if let Some(fields) = fields {
+        params = params.fields(fields);
+    }
+    Ok(api
+        .list(&params)
+        .await?
+        .items
+        .iter()
+        .map(event_info)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, type_: &str, reason: &str, last_seen: &str) -> Event {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": format!("{}.17a", name), "namespace": "shop"},
+            "involvedObject": {"kind": "Pod", "name": name, "namespace": "shop"},
+            "type": type_,
+            "reason": reason,
+            "message": "",
+            "count": 3,
+            "firstTimestamp": "2024-05-01T10:00:00Z",
+            "lastTimestamp": last_seen,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_and_correlate_events() {
+        let events: Vec<EventInfo> = [
+            event("web-1", "Warning", "BackOff", "2024-05-01T10:30:00Z"),
+            event("web-2", "Normal", "Pulled", "2024-05-01T10:20:00Z"),
+            event("web-2", "Warning", "Unhealthy", "2024-05-01T10:40:00Z"),
+            event(
+                "api-1",
+                "Warning",
+                "FailedScheduling",
+                "2024-05-01T09:00:00Z",
+            ),
+        ]
+        .iter()
+        .map(event_info)
+        .collect();
+        assert_eq!(events[0].count, 3);
+        assert_eq!(events[0].object(), "pod/web-1");
+
+        let since = "2024-05-01T10:00:00Z".parse().ok();
+        let reasons: Vec<String> = filter_events(events.clone(), since, Some("warning"))
+            .into_iter()
+            .map(|e| e.reason)
+            .collect();
+        assert_eq!(reasons, ["BackOff", "Unhealthy"]);
+
+        let warnings: Vec<String> = pod_warnings(&events, &["web-1", "web-2"])
+            .into_iter()
+            .map(|e| e.reason)
+            .collect();
+        assert_eq!(warnings, ["Unhealthy", "BackOff"]);
+    }
+}
@@ -0,0 +1,572 @@
+//! Dependencies on systems outside the cluster
+//!
+//! Workloads reach out-of-cluster systems in a few recognizable ways:
+//! ExternalName services, services without a selector whose Endpoints list
+//! addresses by hand, egress-oriented annotations (keys mentioning `egress`
+//! or `outbound`) and literal environment variables holding a URL or an
+//! address outside the cluster's DNS. Each host is reported once, with every
+//! place it is referenced from.
+
+use crate::dependencies::ADDRESS_SUFFIXES;
+use crate::discovery::{pod_workload, DiscoveryEngine};
+use crate::error::{ExplorerError, Result};
+use crate::resource::ResourceKind;
+use k8s_openapi::api::core::v1::{Endpoints, Pod, PodSpec, Service};
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::net::IpAddr;
+
+/// URL schemes naming the kind of system behind a dependency
+const SCHEME_CATEGORIES: &[(&str, &str)] = &[
+    ("postgres", "database"),
+    ("postgresql", "database"),
+    ("mysql", "database"),
+    ("sqlserver", "database"),
+    ("mongodb", "database"),
+    ("mongodb+srv", "database"),
+    ("redis", "cache"),
+    ("rediss", "cache"),
+    ("memcached", "cache"),
+    ("amqp", "queue"),
+    ("amqps", "queue"),
+    ("kafka", "queue"),
+    ("nats", "queue"),
+];
+
+/// Host suffixes of managed services
+const HOST_CATEGORIES: &[(&str, &str)] = &[
+    (".rds.amazonaws.com", "database"),
+    (".database.windows.net", "database"),
+    (".documents.azure.com", "database"),
+    (".mongodb.net", "database"),
+    (".cache.amazonaws.com", "cache"),
+    (".redis.cache.windows.net", "cache"),
+    (".servicebus.windows.net", "queue"),
+    (".amazonaws.com", "cloud API"),
+    (".googleapis.com", "cloud API"),
+    (".azure.com", "cloud API"),
+];
+
+/// Well-known ports of databases, caches and brokers
+const PORT_CATEGORIES: &[(u16, &str)] = &[
+    (5432, "database"),
+    (3306, "database"),
+    (1433, "database"),
+    (1521, "database"),
+    (27017, "database"),
+    (9042, "database"),
+    (6379, "cache"),
+    (11211, "cache"),
+    (5672, "queue"),
+    (9092, "queue"),
+    (4222, "queue"),
+];
+
+/// How an external dependency was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExternalSource {
+    ExternalName,
+    Endpoints,
+    Annotation,
+    Environment,
+}
+
+impl fmt::Display for ExternalSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalSource::ExternalName => write!(f, "ExternalName"),
+            ExternalSource::Endpoints => write!(f, "endpoints"),
+            ExternalSource::Annotation => write!(f, "annotation"),
+            ExternalSource::Environment => write!(f, "env"),
+        }
+    }
+}
+
+/// An object referencing an external host
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ExternalReference {
+    pub source: ExternalSource,
+    pub namespace: String,
+    /// Service, or the workload owning the pod
+    pub kind: String,
+    pub name: String,
+    /// Variable or annotation key the host was found in
+    pub detail: Option<String>,
+}
+
+impl ExternalReference {
+    /// `kind/name` of the referencing object
+    pub fn object(&self) -> String {
+        format!("{}/{}", self.kind, self.name)
+    }
+}
+
+/// A host outside the cluster and everything referencing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalDependency {
+    pub host: String,
+    pub ports: Vec<u16>,
+    /// database, cache, queue, cloud API or external
+    pub category: String,
+    pub references: Vec<ExternalReference>,
+}
+
+/// An address parsed from a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    pub host: String,
+    pub port: Option<u16>,
+    pub scheme: Option<String>,
+}
+
+/// Addresses in a value: comma or whitespace separated URLs, `host:port`
+/// pairs or plain hosts
+pub fn addresses(value: &str) -> Vec<Address> {
+    value
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter_map(|token| {
+            let (scheme, rest) = match token.split_once("://") {
+                Some((scheme, rest)) => (Some(scheme.to_lowercase()), rest),
+                None => (None, token),
+            };
+            let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+            let authority = authority.rsplit_once('@').map_or(authority, |(_, a)| a);
+            let (host, port) = match authority.rsplit_once(':') {
+                Some((host, port)) if !host.contains(':') => (host, port.parse().ok()),
+                _ => (authority, None),
+            };
+            let valid = !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+            valid.then(|| Address {
+                host: host.to_lowercase(),
+                port,
+                scheme,
+            })
+        })
+        .collect()
+}
+
+/// Whether `host` is outside the cluster. Hosts without a dot, cluster DNS
+/// names and `service.namespace` names of `namespaces` are in-cluster.
+pub fn is_external(host: &str, namespaces: &HashSet<String>) -> bool {
+    if host.parse::<IpAddr>().is_ok() {
+        return true;
+    }
+    let Some((_, domain)) = host.split_once('.') else {
+        return false;
+    };
+    let internal_suffixes = [".svc", ".cluster.local", ".local"];
+    if internal_suffixes.iter().any(|s| host.ends_with(s)) {
+        return false;
+    }
+    // `name.namespace` resolves to a service through the search path
+    !namespaces.contains(domain)
+}
+
+/// Category of a dependency from its URL scheme, host or port
+pub fn categorize(host: &str, scheme: Option<&str>, port: Option<u16>) -> &'static str {
+    let by_scheme = scheme.and_then(|scheme| {
+        SCHEME_CATEGORIES
+            .iter()
+            .find(|(s, _)| *s == scheme)
+            .map(|(_, c)| *c)
+    });
+    let by_host = || {
+        HOST_CATEGORIES
+            .iter()
+            .find(|(suffix, _)| host.ends_with(suffix))
+            .map(|(_, c)| *c)
+    };
+    let by_port = || {
+        port.and_then(|port| {
+            PORT_CATEGORIES
+                .iter()
+                .find(|(p, _)| *p == port)
+                .map(|(_, c)| *c)
+        })
+    };
+    by_scheme
+        .or_else(by_host)
+        .or_else(by_port)
+        .unwrap_or("external")
+}
+
+/// External addresses in the literal environment of the containers in
+/// `spec`, with the variable each was found in. Only URLs and variables
+/// whose name marks them as an address are read.
+pub fn environment_addresses(
+    spec: &PodSpec,
+    namespaces: &HashSet<String>,
+) -> Vec<(String, Address)> {
+    let containers = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&spec.containers);
+    let mut found = Vec::new();
+    for var in containers.flat_map(|c| c.env.iter().flatten()) {
+        let Some(value) = &var.value else {
+            continue;
+        };
+        let name = var.name.to_uppercase();
+        if !value.contains("://") && !ADDRESS_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+            continue;
+        }
+        for address in addresses(value) {
+            if is_external(&address.host, namespaces) {
+                found.push((var.name.clone(), address));
+            }
+        }
+    }
+    found
+}
+
+/// External addresses in egress-oriented annotations, with their keys
+pub fn annotation_addresses(
+    annotations: &BTreeMap<String, String>,
+    namespaces: &HashSet<String>,
+) -> Vec<(String, Address)> {
+    annotations
+        .iter()
+        .filter(|(key, _)| {
+            let key = key.to_lowercase();
+            key.contains("egress") || key.contains("outbound")
+        })
+        .flat_map(|(key, value)| {
+            addresses(value)
+                .into_iter()
+                .chain(cidrs(value))
+                .filter(|a| is_external(&a.host, namespaces))
+                .map(move |a| (key.clone(), a))
+        })
+        .collect()
+}
+
+/// CIDR ranges in a value, e.g. from an outbound IP range annotation
+fn cidrs(value: &str) -> Vec<Address> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| {
+            token.split_once('/').is_some_and(|(ip, bits)| {
+                ip.parse::<IpAddr>().is_ok() && bits.parse::<u8>().is_ok()
+            })
+        })
+        .map(|token| Address {
+            host: token.to_string(),
+            port: None,
+            scheme: None,
+        })
+        .collect()
+}
+
+/// Addresses a service without a selector sends traffic to: its
+/// ExternalName, or Endpoints addresses that are not pods
+pub fn service_addresses(service: &Service, endpoints: Option<&Endpoints>) -> Vec<Address> {
+    let Some(spec) = &service.spec else {
+        return Vec::new();
+    };
+    if spec.type_.as_deref() == Some("ExternalName") {
+        return spec
+            .external_name
+            .iter()
+            .map(|name| Address {
+                host: name.trim_end_matches('.').to_lowercase(),
+                port: spec.ports.iter().flatten().next().map(|p| p.port as u16),
+                scheme: None,
+            })
+            .collect();
+    }
+    if spec.selector.as_ref().is_some_and(|s| !s.is_empty()) {
+        return Vec::new();
+    }
+
+    let mut found = Vec::new();
+    for subset in endpoints.iter().flat_map(|e| e.subsets.iter().flatten()) {
+        let port = subset.ports.iter().flatten().next().map(|p| p.port as u16);
+        for address in subset.addresses.iter().flatten() {
+            if address.target_ref.is_none() {
+                found.push(Address {
+                    host: address
+                        .hostname
+                        .clone()
+                        .unwrap_or_else(|| address.ip.clone()),
+                    port,
+                    scheme: None,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Merge addresses found across objects into one dependency per host, most
+/// referenced first
+pub fn merge(found: Vec<(ExternalReference, Address)>) -> Vec<ExternalDependency> {
+    let mut by_host: BTreeMap<String, (BTreeSet<u16>, Option<&'static str>, BTreeSet<_>)> =
+        BTreeMap::new();
+    for (reference, address) in found {
+        let entry = by_host.entry(address.host.clone()).or_default();
+        entry.0.extend(address.port);
+        let category = categorize(&address.host, address.scheme.as_deref(), address.port);
+        if entry.1.is_none_or(|c| c == "external") {
+            entry.1 = Some(category);
+        }
+        entry.2.insert(reference);
+    }
+
+    let mut dependencies: Vec<ExternalDependency> = by_host
+        .into_iter()
+        .map(|(host, (ports, category, references))| ExternalDependency {
+            host,
+            ports: ports.into_iter().collect(),
+            category: category.unwrap_or("external").to_string(),
+            references: references.into_iter().collect(),
+        })
+        .collect();
+    dependencies.sort_by_key(|d| std::cmp::Reverse(d.references.len()));
+    dependencies
+}
+
+/// Find the external dependencies of services and pods in `namespace` (all
+/// namespaces when `None`). Pods are reported by their owning workload.
+pub async fn find_externals(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<ExternalDependency>> {
+    let client = discovery.client().clone();
+    let (services, endpoints): (Api<Service>, Api<Endpoints>) = match namespace {
+        Some(ns) => (
+            Api::namespaced(client.clone(), ns),
+            Api::namespaced(client, ns),
+        ),
+        None => (Api::all(client.clone()), Api::all(client)),
+    };
+    let services = services.list(&ListParams::default()).await?.items;
+    let endpoints = match endpoints.list(&ListParams::default()).await {
+        Ok(list) => list.items,
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut pods = Vec::new();
+    for (_, object) in discovery
+        .list_objects_json(namespace, &[ResourceKind::Pod])
+        .await?
+    {
+        pods.push(serde_json::from_value::<Pod>(object)?);
+    }
+    let workloads: HashMap<(String, String), (String, String)> = discovery
+        .list_pods(namespace, None)
+        .await?
+        .iter()
+        .map(|pod| ((pod.namespace.clone(), pod.name.clone()), pod_workload(pod)))
+        .collect();
+
+    // Namespaces that may be listed, plus those seen, tell service names apart
+    let mut namespaces: HashSet<String> = match discovery.get_all_namespaces().await {
+        Ok(namespaces) => namespaces.into_iter().collect(),
+        Err(ExplorerError::Kubernetes(kube::Error::Api(e))) if (403..=405).contains(&e.code) => {
+            HashSet::new()
+        }
+        Err(e) => return Err(e),
+    };
+    namespaces.extend(services.iter().filter_map(|s| s.metadata.namespace.clone()));
+
+    Ok(collect_externals(
+        &services,
+        &endpoints,
+        &pods,
+        &workloads,
+        &namespaces,
+    ))
+}
+
+/// The external dependencies of `services` and `pods`; `workloads` maps a
+/// pod (namespace, name) to the workload (kind, name) reported for it
+pub fn collect_externals(
+    services: &[Service],
+    endpoints: &[Endpoints],
+    pods: &[Pod],
+    workloads: &HashMap<(String, String), (String, String)>,
+    namespaces: &HashSet<String>,
+) -> Vec<ExternalDependency> {
+    let mut found = Vec::new();
+
+    for service in services {
+        let namespace = service.metadata.namespace.clone().unwrap_or_default();
+        let name = service.metadata.name.clone().unwrap_or_default();
+        let reference = |source, detail: Option<String>| ExternalReference {
+            source,
+            namespace: namespace.clone(),
+            kind: "Service".to_string(),
+            name: name.clone(),
+            detail,
+        };
+
+        let matching = endpoints.iter().find(|e| {
+            e.metadata.namespace == service.metadata.namespace
+                && e.metadata.name == service.metadata.name
+        });
+        let is_external_name = service
+            .spec
+            .as_ref()
+            .is_some_and(|s| s.type_.as_deref() == Some("ExternalName"));
+        for address in service_addresses(service, matching) {
+            let source = if is_external_name {
+                ExternalSource::ExternalName
+            } else {
+                ExternalSource::Endpoints
+            };
+            found.push((reference(source, None), address));
+        }
+        for (key, address) in annotation_addresses(
+            &service.metadata.annotations.clone().unwrap_or_default(),
+            namespaces,
+        ) {
+            found.push((reference(ExternalSource::Annotation, Some(key)), address));
+        }
+    }
+
+    for pod in pods {
+        let namespace = pod.metadata.namespace.clone().unwrap_or_default();
+        let name = pod.metadata.name.clone().unwrap_or_default();
+        let (kind, workload) = workloads
+            .get(&(namespace.clone(), name.clone()))
+            .cloned()
+            .unwrap_or_else(|| ("Pod".to_string(), name));
+        let reference = |source, detail: String| ExternalReference {
+            source,
+            namespace: namespace.clone(),
+            kind: kind.clone(),
+            name: workload.clone(),
+            detail: Some(detail),
+        };
+
+        if let Some(spec) = &pod.spec {
+            for (var, address) in environment_addresses(spec, namespaces) {
+                found.push((reference(ExternalSource::Environment, var), address));
+            }
+        }
+        for (key, address) in annotation_addresses(
+            &pod.metadata.annotations.clone().unwrap_or_default(),
+            namespaces,
+        ) {
+            found.push((reference(ExternalSource::Annotation, key), address));
+        }
+    }
+
+    merge(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addresses_and_categories() {
+        let namespaces: HashSet<String> = ["data".to_string()].into_iter().collect();
+        let parsed =
+            addresses("postgres://app:pw@orders.abc.eu-west-1.rds.amazonaws.com:5432/orders");
+        assert_eq!(
+            parsed,
+            [Address {
+                host: "orders.abc.eu-west-1.rds.amazonaws.com".to_string(),
+                port: Some(5432),
+                scheme: Some("postgres".to_string()),
+            }]
+        );
+        assert_eq!(
+            categorize(&parsed[0].host, Some("postgres"), Some(5432)),
+            "database"
+        );
+        assert_eq!(
+            categorize("api.stripe.com", Some("https"), None),
+            "external"
+        );
+        assert_eq!(categorize("10.20.0.5", None, Some(6379)), "cache");
+
+        let brokers = addresses("b-1.kafka.example.com:9092,b-2.kafka.example.com:9092");
+        assert_eq!(brokers.len(), 2);
+
+        assert!(is_external("api.stripe.com", &namespaces));
+        assert!(is_external("10.20.0.5", &namespaces));
+        assert!(!is_external("db.data", &namespaces));
+        assert!(!is_external("db.data.svc.cluster.local", &namespaces));
+        assert!(!is_external("redis", &namespaces));
+    }
+
+    #[test]
+    fn test_collect_externals() {
+        let service =
+            |value: serde_json::Value| -> Service { serde_json::from_value(value).unwrap() };
+        let services = [
+            service(serde_json::json!({
+                "metadata": {"name": "payments", "namespace": "shop"},
+                "spec": {"type": "ExternalName", "externalName": "api.stripe.com."},
+            })),
+            service(serde_json::json!({
+                "metadata": {"name": "legacy-db", "namespace": "shop"},
+                "spec": {"ports": [{"port": 5432}]},
+            })),
+            service(serde_json::json!({
+                "metadata": {"name": "web", "namespace": "shop"},
+                "spec": {"selector": {"app": "web"}},
+            })),
+        ];
+        let endpoints: Vec<Endpoints> = vec![serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "legacy-db", "namespace": "shop"},
+            "subsets": [{"addresses": [{"ip": "192.168.10.4"}], "ports": [{"port": 5432}]}],
+        }))
+        .unwrap()];
+        let pods: Vec<Pod> = vec![serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": "web-7d9f-abcde",
+                "namespace": "shop",
+                "annotations": {"egress.example.com/allowed-hosts": "api.stripe.com, hooks.slack.com"},
+            },
+            "spec": {"containers": [{
+                "name": "web",
+                "env": [
+                    {"name": "PAYMENTS_URL", "value": "https://api.stripe.com/v1"},
+                    {"name": "CATALOG_URL", "value": "http://catalog.shop:8080"},
+                    {"name": "LOG_LEVEL", "value": "info.debug"},
+                ],
+            }]},
+        }))
+        .unwrap()];
+        let workloads: HashMap<_, _> = [(
+            ("shop".to_string(), "web-7d9f-abcde".to_string()),
+            ("Deployment".to_string(), "web".to_string()),
+        )]
+        .into_iter()
+        .collect();
+        let namespaces: HashSet<String> = ["shop".to_string()].into_iter().collect();
+
+        let externals = collect_externals(&services, &endpoints, &pods, &workloads, &namespaces);
+        let hosts: Vec<&str> = externals.iter().map(|d| d.host.as_str()).collect();
+        assert_eq!(hosts, ["api.stripe.com", "192.168.10.4", "hooks.slack.com"]);
+
+        let stripe = &externals[0];
+        let sources: Vec<(ExternalSource, String)> = stripe
+            .references
+            .iter()
+            .map(|r| (r.source, r.object()))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                (ExternalSource::ExternalName, "Service/payments".to_string()),
+                (ExternalSource::Annotation, "Deployment/web".to_string()),
+                (ExternalSource::Environment, "Deployment/web".to_string()),
+            ]
+        );
+        assert_eq!(externals[1].category, "database");
+        assert_eq!(externals[1].ports, [5432]);
+    }
+}
@@ -0,0 +1,314 @@
+//! Export of a namespace as cleaned manifests (`kdx extract`)
+//!
+//! Objects read from the API carry everything the cluster added to them:
+//! status, managedFields, resource versions, allocated cluster IPs, bound
+//! volume names. This module lists the objects people write by hand in a
+//! namespace, strips those fields, and writes one YAML manifest per object
+//! that can be applied to a fresh namespace or committed to a GitOps repo.
+//! Objects created by controllers (anything with an owner) and the ones
+//! Kubernetes creates in every namespace are left out.
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+use k8s_openapi::api::batch::v1::CronJob;
+use k8s_openapi::api::core::v1::{
+    ConfigMap, PersistentVolumeClaim, Secret, Service, ServiceAccount,
+};
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
+use k8s_openapi::api::policy::v1::PodDisruptionBudget;
+use k8s_openapi::api::rbac::v1::{Role, RoleBinding};
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Annotations the cluster or kubectl maintain on objects
+const CLUSTER_ANNOTATIONS: &[&str] = &[
+    "kubectl.kubernetes.io/last-applied-configuration",
+    "deployment.kubernetes.io/revision",
+    "autoscaling.alpha.kubernetes.io/",
+    "control-plane.alpha.kubernetes.io/",
+    "pv.kubernetes.io/",
+    "volume.beta.kubernetes.io/",
+    "volume.kubernetes.io/",
+];
+
+/// Secret types created and owned by the cluster or by tools
+const GENERATED_SECRET_TYPES: &[&str] =
+    &["kubernetes.io/service-account-token", "helm.sh/release.v1"];
+
+/// Kinds exported, in the order they would be applied
+pub(crate) fn exported_kinds(include_secrets: bool) -> Vec<ApiResource> {
+    let mut kinds = vec![
+        ApiResource::erase::<ServiceAccount>(&()),
+        ApiResource::erase::<Role>(&()),
+        ApiResource::erase::<RoleBinding>(&()),
+        ApiResource::erase::<ConfigMap>(&()),
+    ];
+    if include_secrets {
+        kinds.push(ApiResource::erase::<Secret>(&()));
+    }
+    kinds.extend([
+        ApiResource::erase::<PersistentVolumeClaim>(&()),
+        ApiResource::erase::<Service>(&()),
+        ApiResource::erase::<Deployment>(&()),
+        ApiResource::erase::<StatefulSet>(&()),
+        ApiResource::erase::<DaemonSet>(&()),
+        ApiResource::erase::<CronJob>(&()),
+        ApiResource::erase::<HorizontalPodAutoscaler>(&()),
+        ApiResource::erase::<PodDisruptionBudget>(&()),
+        ApiResource::erase::<Ingress>(&()),
+        ApiResource::erase::<NetworkPolicy>(&()),
+    ]);
+    kinds
+}
+
+/// A manifest written by `kdx extract`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedManifest {
+    pub kind: String,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Everything `kdx extract` wrote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractSummary {
+    pub namespace: String,
+    pub output_dir: PathBuf,
+    pub manifests: Vec<ExtractedManifest>,
+    /// Objects left out: owned by controllers or created by Kubernetes
+    pub skipped: usize,
+}
+
+/// Whether Kubernetes creates the object in every namespace or owns it
+pub(crate) fn is_generated(kind: &str, object: &Value) -> bool {
+    let name = object["metadata"]["name"].as_str().unwrap_or_default();
+    let namespace = object["metadata"]["namespace"].as_str().unwrap_or_default();
+    let owned = object["metadata"]["ownerReferences"]
+        .as_array()
+        .is_some_and(|owners| !owners.is_empty());
+    owned
+        || match kind {
+            "ServiceAccount" => name == "default",
+            "ConfigMap" => name == "kube-root-ca.crt",
+            "Secret" => object["type"]
+                .as_str()
+                .is_some_and(|t| GENERATED_SECRET_TYPES.contains(&t)),
+            "Service" => name == "kubernetes" && namespace == "default",
+            _ => false,
+        }
+}
+
+/// Remove `"creationTimestamp": null`, which the API adds to every pod
+/// template
+fn remove_null_timestamps(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if map.get("creationTimestamp").is_some_and(Value::is_null) {
+                map.remove("creationTimestamp");
+            }
+            map.values_mut().for_each(remove_null_timestamps);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_null_timestamps),
+        _ => {}
+    }
+}
+
+/// `object` of `resource` as a manifest to recreate it from, or `None` if
+/// it should not be exported. Keys come in manifest order: apiVersion,
+/// kind, metadata, then the rest.
+pub fn clean_manifest(resource: &ApiResource, mut object: Value) -> Option<serde_yaml::Value> {
+    if is_generated(&resource.kind, &object) {
+        return None;
+    }
+    let map = object.as_object_mut()?;
+    map.remove("status");
+    map.remove("apiVersion");
+    map.remove("kind");
+
+    let metadata = map.remove("metadata").unwrap_or_default();
+    let mut cleaned = serde_yaml::Mapping::new();
+    for key in ["name", "namespace", "labels"] {
+        if let Some(value) = metadata.get(key) {
+            cleaned.insert(key.into(), serde_yaml::to_value(value).ok()?);
+        }
+    }
+    if let Some(annotations) = metadata["annotations"].as_object() {
+        let kept: serde_json::Map<String, Value> = annotations
+            .iter()
+            .filter(|(key, _)| {
+                !CLUSTER_ANNOTATIONS
+                    .iter()
+                    .any(|cluster| key.starts_with(cluster))
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if !kept.is_empty() {
+            cleaned.insert("annotations".into(), serde_yaml::to_value(kept).ok()?);
+        }
+    }
+
+    match resource.kind.as_str() {
+        "Service" => {
+            if let Some(spec) = map.get_mut("spec").and_then(Value::as_object_mut) {
+                for field in ["clusterIP", "clusterIPs", "healthCheckNodePort"] {
+                    spec.remove(field);
+                }
+                for port in spec
+                    .get_mut("ports")
+                    .and_then(Value::as_array_mut)
+                    .into_iter()
+                    .flatten()
+                {
+                    if let Some(port) = port.as_object_mut() {
+                        port.remove("nodePort");
+                    }
+                }
+            }
+        }
+        "PersistentVolumeClaim" => {
+            if let Some(spec) = map.get_mut("spec").and_then(Value::as_object_mut) {
+                spec.remove("volumeName");
+            }
+        }
+        // Token secrets listed here are generated per cluster
+        "ServiceAccount" => {
+            map.remove("secrets");
+        }
+        _ => {}
+    }
+    remove_null_timestamps(&mut object);
+
+    let mut manifest = serde_yaml::Mapping::new();
+    manifest.insert("apiVersion".into(), resource.api_version.clone().into());
+    manifest.insert("kind".into(), resource.kind.clone().into());
+    manifest.insert("metadata".into(), cleaned.into());
+    for (key, value) in object.as_object()? {
+        manifest.insert(key.clone().into(), serde_yaml::to_value(value).ok()?);
+    }
+    Some(serde_yaml::Value::Mapping(manifest))
+}
+
+/// File a manifest is written to, e.g. `deployment-web.yaml`
+fn manifest_file(kind: &str, name: &str) -> String {
+    format!("{}-{}.yaml", kind.to_lowercase(), name)
+}
+
+/// Write the objects of `namespace` as manifests into `output_dir`,
+/// replacing manifests of the same name
+pub async fn extract_namespace(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    output_dir: &Path,
+    include_secrets: bool,
+) -> Result<ExtractSummary> {
+    let mut summary = ExtractSummary {
+        namespace: namespace.to_string(),
+        output_dir: output_dir.to_path_buf(),
+        manifests: Vec::new(),
+        skipped: 0,
+    };
+    let mut manifests = Vec::new();
+    for resource in exported_kinds(include_secrets) {
+        let api: Api<DynamicObject> =
+            Api::namespaced_with(discovery.client().clone(), namespace, &resource);
+        let list = match api.list(&ListParams::default()).await {
+            Ok(list) => list,
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for object in list.items {
+            let name = object.metadata.name.clone().unwrap_or_default();
+            match clean_manifest(&resource, serde_json::to_value(object)?) {
+                Some(manifest) => manifests.push((resource.kind.clone(), name, manifest)),
+                None => summary.skipped += 1,
+            }
+        }
+    }
+
+    if manifests.is_empty() {
+        return Ok(summary);
+    }
+    fs::create_dir_all(output_dir)?;
+    for (kind, name, manifest) in manifests {
+        let path = output_dir.join(manifest_file(&kind, &name));
+        fs::write(&path, serde_yaml::to_string(&manifest)?)?;
+        summary
+            .manifests
+            .push(ExtractedManifest { kind, name, path });
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_manifest() {
+        let service = serde_json::json!({
+            "metadata": {
+                "name": "web", "namespace": "shop", "uid": "1234", "resourceVersion": "99",
+                "creationTimestamp": "2024-05-01T10:00:00Z",
+                "managedFields": [{"manager": "kubectl"}],
+                "labels": {"app": "web"},
+                "annotations": {
+                    "kubectl.kubernetes.io/last-applied-configuration": "{}",
+                    "team": "shop",
+                },
+            },
+            "spec": {
+                "type": "NodePort", "clusterIP": "10.0.0.12", "clusterIPs": ["10.0.0.12"],
+                "selector": {"app": "web"},
+                "ports": [{"port": 80, "targetPort": 8080, "nodePort": 31234}],
+            },
+            "status": {"loadBalancer": {}},
+        });
+        let manifest = clean_manifest(&ApiResource::erase::<Service>(&()), service).unwrap();
+        let yaml = serde_yaml::to_string(&manifest).unwrap();
+        assert!(yaml.starts_with("apiVersion: v1\nkind: Service\nmetadata:\n  name: web\n"));
+        assert_eq!(
+            serde_yaml::to_value(&manifest).unwrap(),
+            serde_yaml::from_str::<serde_yaml::Value>(
+                "apiVersion: v1
+kind: Service
+metadata:
+  name: web
+  namespace: shop
+  labels: {app: web}
+  annotations: {team: shop}
+spec:
+  type: NodePort
+  selector: {app: web}
+  ports: [{port: 80, targetPort: 8080}]
+"
+            )
+            .unwrap()
+        );
+
+        let deployment = serde_json::json!({
+            "metadata": {"name": "web", "namespace": "shop"},
+            "spec": {"template": {"metadata": {"creationTimestamp": null, "labels": {"app": "web"}}}},
+        });
+        let manifest = clean_manifest(&ApiResource::erase::<Deployment>(&()), deployment).unwrap();
+        assert!(!serde_yaml::to_string(&manifest)
+            .unwrap()
+            .contains("creationTimestamp"));
+
+        let owned = serde_json::json!({
+            "metadata": {"name": "web-abc12", "ownerReferences": [{"kind": "Deployment", "name": "web"}]},
+        });
+        assert!(clean_manifest(&ApiResource::erase::<Deployment>(&()), owned).is_none());
+        let token = serde_json::json!({
+            "metadata": {"name": "builder-token"},
+            "type": "kubernetes.io/service-account-token",
+        });
+        assert!(clean_manifest(&ApiResource::erase::<Secret>(&()), token).is_none());
+        assert_eq!(manifest_file("ConfigMap", "web"), "configmap-web.yaml");
+    }
+}
@@ -1,11 +1,17 @@
 //! Advanced filtering and grouping capabilities for Kubernetes resources
 
 use crate::discovery::{
-    CRDInfo, ConfigMapInfo, CustomResourceInfo, DaemonSetInfo, DeploymentInfo, PodInfo, SecretInfo,
+    AllResources, CRDInfo, ConfigMapInfo, CustomResourceInfo, DaemonSetInfo, DeploymentInfo,
+    NodeInfo, PersistentVolumeClaimInfo, PersistentVolumeInfo, PodInfo, ReplicaSetInfo, SecretInfo,
     ServiceInfo, StatefulSetInfo,
 };
+use crate::kinds;
+use crate::kustomize;
+use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fmt;
 use std::time::Duration;
 
 /// Filter criteria for resources
@@ -15,17 +21,28 @@ pub struct FilterCriteria {
     pub label_selector: Option<String>,
     /// Status filter (Running, Pending, Failed, etc.)
     pub status_filter: Option<String>,
-    /// Age filter - resources newer than this duration
-    #[allow(dead_code)]
+    /// Pod QoS class filter (Guaranteed, Burstable, BestEffort), case-insensitive
+    pub qos_class: Option<String>,
+    /// Only pods without a controller owner reference
+    pub bare_only: bool,
+    /// Node role filter (e.g. "control-plane", "worker"), case-insensitive
+    pub node_role: Option<String>,
+    /// Node condition filter: "Type" (status True) or "Type=Status",
+    /// case-insensitive (e.g. "Ready=False", "MemoryPressure")
+    pub node_condition: Option<String>,
+    /// Storage class filter for volumes and claims
+    pub storage_class: Option<String>,
+    /// Age filter - resources created less than this long ago
     pub newer_than: Option<Duration>,
-    /// Age filter - resources older than this duration
-    #[allow(dead_code)]
+    /// Age filter - resources created at least this long ago
     pub older_than: Option<Duration>,
-    /// Resource type inclusion filter
-    #[allow(dead_code)]
+    /// Name filter - resources whose name matches the pattern
+    pub name_filter: Option<NameFilter>,
+    /// Selector expressions evaluated against annotations instead of labels
+    pub annotation_selector: Option<LabelSelector>,
+    /// Resource type inclusion filter, as kinds, plurals or short names
     pub include_types: Vec<String>,
-    /// Resource type exclusion filter
-    #[allow(dead_code)]
+    /// Resource type exclusion filter, as kinds, plurals or short names
     pub exclude_types: Vec<String>,
 }
 
@@ -40,12 +57,42 @@ pub enum GroupBy {
     HelmRelease,
     /// Group by namespace
     Namespace,
+    /// Group by the kustomization that rendered the resource
+    Kustomization,
     /// Group by custom label key
     CustomLabel(String),
     /// No grouping
     None,
 }
 
+/// What a resource's group is read from
+#[derive(Debug, Clone, Copy)]
+enum GroupKey<'a> {
+    /// The value of a label
+    Label(&'a str),
+    /// The kustomization named by the resource's labels
+    Kustomization,
+}
+
+impl GroupKey<'_> {
+    /// Group of a resource with `labels`, "unknown" when it has none
+    fn group_of(&self, labels: &BTreeMap<String, String>) -> String {
+        match self {
+            GroupKey::Label(key) => labels.get(*key).cloned(),
+            GroupKey::Kustomization => kustomize::kustomization(labels, None),
+        }
+        .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Grouping criteria recorded on each group
+    fn criteria(&self) -> String {
+        match self {
+            GroupKey::Label(key) => key.to_string(),
+            GroupKey::Kustomization => "kustomization".to_string(),
+        }
+    }
+}
+
 /// Grouped resource collection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupedResources {
@@ -101,6 +148,7 @@ impl ResourceGroup {
 }
 
 /// Label selector parser and evaluator
+#[derive(Debug, Clone)]
 pub struct LabelSelector {
     expressions: Vec<LabelExpression>,
 }
@@ -263,10 +311,121 @@ impl LabelSelector {
     }
 }
 
+/// A `--name-filter` pattern, compiled once and shared by every resource
+/// it is matched against
+#[derive(Debug, Clone)]
+pub struct NameFilter(Regex);
+
+impl NameFilter {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern)
+            .map(Self)
+            .map_err(|e| format!("invalid name filter '{}': {}", pattern, e))
+    }
+
+    /// Whether the pattern matches anywhere in `name`
+    pub fn matches(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+}
+
+/// A `--field-selector` such as `status.phase=Running,spec.nodeName=node-1`,
+/// sent to the API server with pod listings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelector {
+    /// Field, whether it must equal the value, and the value
+    requirements: Vec<(String, bool, String)>,
+}
+
+impl FieldSelector {
+    pub fn parse(selector: &str) -> Result<Self, String> {
+        let mut requirements = Vec::new();
+        for requirement in selector.split(',').map(str::trim) {
+            let (field, equals, value) = if let Some((f, v)) = requirement.split_once("!=") {
+                (f, false, v)
+            } else if let Some((f, v)) = requirement.split_once("==") {
+                (f, true, v)
+            } else if let Some((f, v)) = requirement.split_once('=') {
+                (f, true, v)
+            } else {
+                return Err(format!(
+                    "invalid field selector '{}': expected field=value or field!=value",
+                    requirement
+                ));
+            };
+            if field.trim().is_empty() {
+                return Err(format!(
+                    "invalid field selector '{}': empty field",
+                    requirement
+                ));
+            }
+            requirements.push((field.trim().to_string(), equals, value.trim().to_string()));
+        }
+        Ok(Self { requirements })
+    }
+
+    /// Evaluate the selector against a pod summary, for listings that do not
+    /// come from the API server. Only the fields a summary carries can be
+    /// evaluated.
+    pub fn matches_pod(&self, pod: &PodInfo) -> Result<bool, String> {
+        for (field, equals, value) in &self.requirements {
+            let actual = match field.as_str() {
+                "metadata.name" => Some(pod.name.as_str()),
+                "metadata.namespace" => Some(pod.namespace.as_str()),
+                "spec.nodeName" => pod.node_name.as_deref(),
+                "status.phase" => Some(pod.phase.as_str()),
+                "status.podIP" => pod.pod_ip.as_deref(),
+                _ => {
+                    return Err(format!(
+                        "field selector on '{}' is only supported by the API server",
+                        field
+                    ))
+                }
+            };
+            if (actual.unwrap_or_default() == value) != *equals {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl fmt::Display for FieldSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let requirements: Vec<String> = self
+            .requirements
+            .iter()
+            .map(|(field, equals, value)| {
+                format!("{}{}{}", field, if *equals { "=" } else { "!=" }, value)
+            })
+            .collect();
+        write!(f, "{}", requirements.join(","))
+    }
+}
+
 /// Resource filtering utilities
 pub struct ResourceFilter;
 
 impl ResourceFilter {
+    /// Keep the items whose name matches `filter`, for lists that take no
+    /// other criteria
+    pub fn retain_names<T>(
+        items: &mut Vec<T>,
+        filter: Option<&NameFilter>,
+        name: impl Fn(&T) -> &str,
+    ) {
+        if let Some(filter) = filter {
+            items.retain(|item| filter.matches(name(item)));
+        }
+    }
+
+    /// Whether objects of `kind` pass the include and exclude type filters,
+    /// which may name kinds by plural or short name (e.g. `deploy`, `cm`)
+    pub fn includes_kind(kind: &str, criteria: &FilterCriteria) -> bool {
+        (criteria.include_types.is_empty() || kinds::kind_in(kind, &criteria.include_types))
+            && !kinds::kind_in(kind, &criteria.exclude_types)
+    }
+
     /// Filter services based on criteria
     pub fn filter_services(
         services: Vec<ServiceInfo>,
@@ -289,6 +448,63 @@ impl ResourceFilter {
             .collect()
     }
 
+    /// Filter statefulsets based on criteria
+    pub fn filter_statefulsets(
+        statefulsets: Vec<StatefulSetInfo>,
+        criteria: &FilterCriteria,
+    ) -> Vec<StatefulSetInfo> {
+        statefulsets
+            .into_iter()
+            .filter(|s| {
+                Self::matches_workload_criteria(
+                    &s.name,
+                    &s.labels,
+                    &s.annotations,
+                    s.created_at,
+                    criteria,
+                )
+            })
+            .collect()
+    }
+
+    /// Filter daemonsets based on criteria
+    pub fn filter_daemonsets(
+        daemonsets: Vec<DaemonSetInfo>,
+        criteria: &FilterCriteria,
+    ) -> Vec<DaemonSetInfo> {
+        daemonsets
+            .into_iter()
+            .filter(|d| {
+                Self::matches_workload_criteria(
+                    &d.name,
+                    &d.labels,
+                    &d.annotations,
+                    d.created_at,
+                    criteria,
+                )
+            })
+            .collect()
+    }
+
+    /// Filter replicasets based on criteria
+    pub fn filter_replicasets(
+        replicasets: Vec<ReplicaSetInfo>,
+        criteria: &FilterCriteria,
+    ) -> Vec<ReplicaSetInfo> {
+        replicasets
+            .into_iter()
+            .filter(|r| {
+                Self::matches_workload_criteria(
+                    &r.name,
+                    &r.labels,
+                    &r.annotations,
+                    r.created_at,
+                    criteria,
+                )
+            })
+            .collect()
+    }
+
     /// Filter pods based on criteria
     pub fn filter_pods(pods: Vec<PodInfo>, criteria: &FilterCriteria) -> Vec<PodInfo> {
         pods.into_iter()
@@ -296,6 +512,60 @@ impl ResourceFilter {
             .collect()
     }
 
+    /// Filter nodes based on criteria
+    pub fn filter_nodes(nodes: Vec<NodeInfo>, criteria: &FilterCriteria) -> Vec<NodeInfo> {
+        nodes
+            .into_iter()
+            .filter(|node| Self::matches_node_criteria(node, criteria))
+            .collect()
+    }
+
+    /// Filter persistent volumes based on criteria
+    pub fn filter_persistent_volumes(
+        volumes: Vec<PersistentVolumeInfo>,
+        criteria: &FilterCriteria,
+    ) -> Vec<PersistentVolumeInfo> {
+        volumes
+            .into_iter()
+            .filter(|volume| {
+                Self::matches_storage_criteria(
+                    &volume.phase,
+                    volume.storage_class.as_deref(),
+                    criteria,
+                ) && Self::matches_name(&volume.name, criteria)
+                    && Self::matches_annotations(&volume.annotations, criteria)
+                    && Self::matches_age(volume.created_at, criteria)
+            })
+            .collect()
+    }
+
+    /// Filter persistent volume claims based on criteria
+    pub fn filter_persistent_volume_claims(
+        claims: Vec<PersistentVolumeClaimInfo>,
+        criteria: &FilterCriteria,
+    ) -> Vec<PersistentVolumeClaimInfo> {
+        claims
+            .into_iter()
+            .filter(|claim| {
+                // Label selector check
+                if let Some(selector_str) = &criteria.label_selector {
+                    if let Ok(selector) = LabelSelector::parse(selector_str) {
+                        if !selector.matches(&claim.labels) {
+                            return false;
+                        }
+                    }
+                }
+                Self::matches_storage_criteria(
+                    &claim.phase,
+                    claim.storage_class.as_deref(),
+                    criteria,
+                ) && Self::matches_name(&claim.name, criteria)
+                    && Self::matches_annotations(&claim.annotations, criteria)
+                    && Self::matches_age(claim.created_at, criteria)
+            })
+            .collect()
+    }
+
     /// Filter configmaps based on criteria
     pub fn filter_configmaps(
         configmaps: Vec<ConfigMapInfo>,
@@ -347,10 +617,11 @@ impl ResourceFilter {
             }
         }
 
-        // TODO: Add age filtering when we implement proper timestamp parsing
         // TODO: Add status filtering for services
 
-        true
+        Self::matches_name(&service.name, criteria)
+            && Self::matches_annotations(&service.annotations, criteria)
+            && Self::matches_age(service.created_at, criteria)
     }
 
     fn matches_deployment_criteria(deployment: &DeploymentInfo, criteria: &FilterCriteria) -> bool {
@@ -378,7 +649,9 @@ impl ResourceFilter {
             }
         }
 
-        true
+        Self::matches_name(&deployment.name, criteria)
+            && Self::matches_annotations(&deployment.annotations, criteria)
+            && Self::matches_age(deployment.created_at, criteria)
     }
 
     fn matches_pod_criteria(pod: &PodInfo, criteria: &FilterCriteria) -> bool {
@@ -398,6 +671,130 @@ impl ResourceFilter {
             }
         }
 
+        // QoS class filter
+        if let Some(qos) = &criteria.qos_class {
+            if !pod.qos_class.eq_ignore_ascii_case(qos) {
+                return false;
+            }
+        }
+
+        // Bare pod filter
+        if criteria.bare_only && pod.owner_kind.is_some() {
+            return false;
+        }
+
+        Self::matches_name(&pod.name, criteria)
+            && Self::matches_annotations(&pod.annotations, criteria)
+            && Self::matches_age(pod.created_at, criteria)
+    }
+
+    fn matches_node_criteria(node: &NodeInfo, criteria: &FilterCriteria) -> bool {
+        // Label selector check
+        if let Some(selector_str) = &criteria.label_selector {
+            if let Ok(selector) = LabelSelector::parse(selector_str) {
+                if !selector.matches(&node.labels) {
+                    return false;
+                }
+            }
+        }
+
+        // Role filter
+        if let Some(role) = &criteria.node_role {
+            if !node.roles.iter().any(|r| r.eq_ignore_ascii_case(role)) {
+                return false;
+            }
+        }
+
+        // Condition filter
+        if let Some(condition) = &criteria.node_condition {
+            let (kind, wanted) = condition.split_once('=').unwrap_or((condition, "True"));
+            let status = node
+                .conditions
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(kind.trim()))
+                .map(|(_, status)| status.as_str());
+            if !status.is_some_and(|s| s.eq_ignore_ascii_case(wanted.trim())) {
+                return false;
+            }
+        }
+
+        Self::matches_name(&node.name, criteria)
+            && Self::matches_annotations(&node.annotations, criteria)
+            && Self::matches_age(node.created_at, criteria)
+    }
+
+    fn matches_workload_criteria(
+        name: &str,
+        labels: &BTreeMap<String, String>,
+        annotations: &BTreeMap<String, String>,
+        created_at: Option<DateTime<Utc>>,
+        criteria: &FilterCriteria,
+    ) -> bool {
+        // Label selector check
+        if let Some(selector_str) = &criteria.label_selector {
+            if let Ok(selector) = LabelSelector::parse(selector_str) {
+                if !selector.matches(labels) {
+                    return false;
+                }
+            }
+        }
+
+        Self::matches_name(name, criteria)
+            && Self::matches_annotations(annotations, criteria)
+            && Self::matches_age(created_at, criteria)
+    }
+
+    fn matches_annotations(
+        annotations: &BTreeMap<String, String>,
+        criteria: &FilterCriteria,
+    ) -> bool {
+        criteria
+            .annotation_selector
+            .as_ref()
+            .is_none_or(|selector| selector.matches(annotations))
+    }
+
+    fn matches_name(name: &str, criteria: &FilterCriteria) -> bool {
+        criteria
+            .name_filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(name))
+    }
+
+    /// Age filters; a resource without a creation time only passes when
+    /// neither is set
+    pub fn matches_age(created_at: Option<DateTime<Utc>>, criteria: &FilterCriteria) -> bool {
+        if criteria.newer_than.is_none() && criteria.older_than.is_none() {
+            return true;
+        }
+        let Some(created_at) = created_at else {
+            return false;
+        };
+        let age = (Utc::now() - created_at).to_std().unwrap_or_default();
+
+        criteria.newer_than.is_none_or(|limit| age < limit)
+            && criteria.older_than.is_none_or(|limit| age >= limit)
+    }
+
+    fn matches_storage_criteria(
+        phase: &str,
+        storage_class: Option<&str>,
+        criteria: &FilterCriteria,
+    ) -> bool {
+        // Phase filter (Bound, Available, Released, Pending, Lost, Failed)
+        if let Some(status) = &criteria.status_filter {
+            if !phase.eq_ignore_ascii_case(status) {
+                return false;
+            }
+        }
+
+        // Storage class filter
+        if let Some(class) = &criteria.storage_class {
+            if storage_class != Some(class.as_str()) {
+                return false;
+            }
+        }
+
         true
     }
 
@@ -411,9 +808,9 @@ impl ResourceFilter {
             }
         }
 
-        // TODO: Add age filtering when we implement proper timestamp parsing
-
-        true
+        Self::matches_name(&configmap.name, criteria)
+            && Self::matches_annotations(&configmap.annotations, criteria)
+            && Self::matches_age(configmap.created_at, criteria)
     }
 
     fn matches_secret_criteria(secret: &SecretInfo, criteria: &FilterCriteria) -> bool {
@@ -426,9 +823,9 @@ impl ResourceFilter {
             }
         }
 
-        // TODO: Add age filtering when we implement proper timestamp parsing
-
-        true
+        Self::matches_name(&secret.name, criteria)
+            && Self::matches_annotations(&secret.annotations, criteria)
+            && Self::matches_age(secret.created_at, criteria)
     }
 
     fn matches_crd_criteria(crd: &CRDInfo, criteria: &FilterCriteria) -> bool {
@@ -441,9 +838,9 @@ impl ResourceFilter {
             }
         }
 
-        // TODO: Add age filtering when we implement proper timestamp parsing
-
-        true
+        Self::matches_name(&crd.name, criteria)
+            && Self::matches_annotations(&crd.annotations, criteria)
+            && Self::matches_age(crd.created_at, criteria)
     }
 
     fn matches_custom_resource_criteria(
@@ -459,9 +856,9 @@ impl ResourceFilter {
             }
         }
 
-        // TODO: Add age filtering when we implement proper timestamp parsing
-
-        true
+        Self::matches_name(&cr.name, criteria)
+            && Self::matches_annotations(&cr.annotations, criteria)
+            && Self::matches_age(cr.created_at, criteria)
     }
 }
 
@@ -489,7 +886,7 @@ impl ResourceGrouper {
                     deployments,
                     statefulsets,
                     daemonsets,
-                    "app",
+                    GroupKey::Label("app"),
                 );
             }
             GroupBy::Tier => {
@@ -500,7 +897,7 @@ impl ResourceGrouper {
                     deployments,
                     statefulsets,
                     daemonsets,
-                    "tier",
+                    GroupKey::Label("tier"),
                 );
             }
             GroupBy::HelmRelease => {
@@ -513,6 +910,17 @@ impl ResourceGrouper {
                     daemonsets,
                 );
             }
+            GroupBy::Kustomization => {
+                Self::group_by_label(
+                    &mut groups,
+                    services,
+                    pods,
+                    deployments,
+                    statefulsets,
+                    daemonsets,
+                    GroupKey::Kustomization,
+                );
+            }
             GroupBy::Namespace => {
                 Self::group_by_namespace(
                     &mut groups,
@@ -531,7 +939,7 @@ impl ResourceGrouper {
                     deployments,
                     statefulsets,
                     daemonsets,
-                    label_key,
+                    GroupKey::Label(label_key),
                 );
             }
             GroupBy::None => {
@@ -548,6 +956,42 @@ impl ResourceGrouper {
         GroupedResources { groups }
     }
 
+    /// Group everything `kdx all` lists, with ConfigMaps and Secrets in the
+    /// same groups as the workloads that share their label or namespace
+    pub fn group_all(all: AllResources, group_by: &GroupBy) -> GroupedResources {
+        let mut grouped = Self::group_resources(
+            all.services,
+            all.pods,
+            all.deployments,
+            all.statefulsets,
+            all.daemonsets,
+            group_by,
+        );
+
+        if let GroupBy::None = group_by {
+            if let Some(group) = grouped.groups.get_mut("all") {
+                group.configmaps = all.configmaps;
+                group.secrets = all.secrets;
+            }
+            return grouped;
+        }
+
+        let config = Self::group_configmaps(all.configmaps, group_by)
+            .groups
+            .into_iter()
+            .chain(Self::group_secrets(all.secrets, group_by).groups);
+        for (key, group) in config {
+            let target = grouped
+                .groups
+                .entry(key)
+                .or_insert_with(|| ResourceGroup::new(group.name, group.group_type));
+            target.configmaps.extend(group.configmaps);
+            target.secrets.extend(group.secrets);
+        }
+
+        grouped
+    }
+
     /// Group configmaps by the specified criteria
     pub fn group_configmaps(
         configmaps: Vec<ConfigMapInfo>,
@@ -557,23 +1001,30 @@ impl ResourceGrouper {
 
         match group_by {
             GroupBy::App => {
-                Self::group_configmaps_by_label(&mut groups, configmaps, "app");
+                Self::group_configmaps_by_label(&mut groups, configmaps, GroupKey::Label("app"));
             }
             GroupBy::Tier => {
-                Self::group_configmaps_by_label(&mut groups, configmaps, "tier");
+                Self::group_configmaps_by_label(&mut groups, configmaps, GroupKey::Label("tier"));
             }
             GroupBy::HelmRelease => {
                 Self::group_configmaps_by_label(
                     &mut groups,
                     configmaps,
-                    "app.kubernetes.io/instance",
+                    GroupKey::Label("app.kubernetes.io/instance"),
                 );
             }
+            GroupBy::Kustomization => {
+                Self::group_configmaps_by_label(&mut groups, configmaps, GroupKey::Kustomization);
+            }
             GroupBy::Namespace => {
                 Self::group_configmaps_by_namespace(&mut groups, configmaps);
             }
             GroupBy::CustomLabel(label_key) => {
-                Self::group_configmaps_by_label(&mut groups, configmaps, label_key);
+                Self::group_configmaps_by_label(
+                    &mut groups,
+                    configmaps,
+                    GroupKey::Label(label_key),
+                );
             }
             GroupBy::None => {
                 let group = ResourceGroup::new("All ConfigMaps".to_string(), "none".to_string());
@@ -591,19 +1042,26 @@ impl ResourceGrouper {
 
         match group_by {
             GroupBy::App => {
-                Self::group_secrets_by_label(&mut groups, secrets, "app");
+                Self::group_secrets_by_label(&mut groups, secrets, GroupKey::Label("app"));
             }
             GroupBy::Tier => {
-                Self::group_secrets_by_label(&mut groups, secrets, "tier");
+                Self::group_secrets_by_label(&mut groups, secrets, GroupKey::Label("tier"));
             }
             GroupBy::HelmRelease => {
-                Self::group_secrets_by_label(&mut groups, secrets, "app.kubernetes.io/instance");
+                Self::group_secrets_by_label(
+                    &mut groups,
+                    secrets,
+                    GroupKey::Label("app.kubernetes.io/instance"),
+                );
+            }
+            GroupBy::Kustomization => {
+                Self::group_secrets_by_label(&mut groups, secrets, GroupKey::Kustomization);
             }
             GroupBy::Namespace => {
                 Self::group_secrets_by_namespace(&mut groups, secrets);
             }
             GroupBy::CustomLabel(label_key) => {
-                Self::group_secrets_by_label(&mut groups, secrets, label_key);
+                Self::group_secrets_by_label(&mut groups, secrets, GroupKey::Label(label_key));
             }
             GroupBy::None => {
                 let group = ResourceGroup::new("All Secrets".to_string(), "none".to_string());
@@ -621,20 +1079,27 @@ impl ResourceGrouper {
 
         match group_by {
             GroupBy::App => {
-                Self::group_crds_by_label(&mut groups, crds, "app");
+                Self::group_crds_by_label(&mut groups, crds, GroupKey::Label("app"));
             }
             GroupBy::Tier => {
-                Self::group_crds_by_label(&mut groups, crds, "tier");
+                Self::group_crds_by_label(&mut groups, crds, GroupKey::Label("tier"));
             }
             GroupBy::HelmRelease => {
-                Self::group_crds_by_label(&mut groups, crds, "app.kubernetes.io/instance");
+                Self::group_crds_by_label(
+                    &mut groups,
+                    crds,
+                    GroupKey::Label("app.kubernetes.io/instance"),
+                );
+            }
+            GroupBy::Kustomization => {
+                Self::group_crds_by_label(&mut groups, crds, GroupKey::Kustomization);
             }
             GroupBy::Namespace => {
                 // CRDs are cluster-scoped, so group by scope instead
                 Self::group_crds_by_scope(&mut groups, crds);
             }
             GroupBy::CustomLabel(label_key) => {
-                Self::group_crds_by_label(&mut groups, crds, label_key);
+                Self::group_crds_by_label(&mut groups, crds, GroupKey::Label(label_key));
             }
             GroupBy::None => {
                 let group = ResourceGroup::new("All CRDs".to_string(), "none".to_string());
@@ -655,23 +1120,42 @@ impl ResourceGrouper {
 
         match group_by {
             GroupBy::App => {
-                Self::group_custom_resources_by_label(&mut groups, custom_resources, "app");
+                Self::group_custom_resources_by_label(
+                    &mut groups,
+                    custom_resources,
+                    GroupKey::Label("app"),
+                );
             }
             GroupBy::Tier => {
-                Self::group_custom_resources_by_label(&mut groups, custom_resources, "tier");
+                Self::group_custom_resources_by_label(
+                    &mut groups,
+                    custom_resources,
+                    GroupKey::Label("tier"),
+                );
             }
             GroupBy::HelmRelease => {
                 Self::group_custom_resources_by_label(
                     &mut groups,
                     custom_resources,
-                    "app.kubernetes.io/instance",
+                    GroupKey::Label("app.kubernetes.io/instance"),
+                );
+            }
+            GroupBy::Kustomization => {
+                Self::group_custom_resources_by_label(
+                    &mut groups,
+                    custom_resources,
+                    GroupKey::Kustomization,
                 );
             }
             GroupBy::Namespace => {
                 Self::group_custom_resources_by_namespace(&mut groups, custom_resources);
             }
             GroupBy::CustomLabel(label_key) => {
-                Self::group_custom_resources_by_label(&mut groups, custom_resources, label_key);
+                Self::group_custom_resources_by_label(
+                    &mut groups,
+                    custom_resources,
+                    GroupKey::Label(label_key),
+                );
             }
             GroupBy::None => {
                 let group =
@@ -691,74 +1175,53 @@ impl ResourceGrouper {
         deployments: Vec<DeploymentInfo>,
         statefulsets: Vec<StatefulSetInfo>,
         daemonsets: Vec<DaemonSetInfo>,
-        label_key: &str,
+        key: GroupKey<'_>,
     ) {
         // Group services
         for service in services {
-            let group_name = service
-                .selector
-                .as_ref()
-                .and_then(|labels| labels.get(label_key))
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(service.selector.as_ref().unwrap_or(&BTreeMap::new()));
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.services.push(service);
         }
 
         // Group other resources similarly...
         for deployment in deployments {
-            let group_name = deployment
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&deployment.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.deployments.push(deployment);
         }
 
         // Continue for other resource types...
         for pod in pods {
-            let group_name = pod
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&pod.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.pods.push(pod);
         }
 
         for statefulset in statefulsets {
-            let group_name = statefulset
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&statefulset.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.statefulsets.push(statefulset);
         }
 
         for daemonset in daemonsets {
-            let group_name = daemonset
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&daemonset.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.daemonsets.push(daemonset);
         }
     }
@@ -783,7 +1246,7 @@ impl ResourceGrouper {
             deployments,
             statefulsets,
             daemonsets,
-            HELM_RELEASE_LABEL,
+            GroupKey::Label(HELM_RELEASE_LABEL),
         );
 
         // Add Helm metadata to groups
@@ -848,18 +1311,14 @@ impl ResourceGrouper {
     fn group_configmaps_by_label(
         groups: &mut BTreeMap<String, ResourceGroup>,
         configmaps: Vec<ConfigMapInfo>,
-        label_key: &str,
+        key: GroupKey<'_>,
     ) {
         for configmap in configmaps {
-            let group_name = configmap
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&configmap.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.configmaps.push(configmap);
         }
     }
@@ -881,18 +1340,14 @@ impl ResourceGrouper {
     fn group_secrets_by_label(
         groups: &mut BTreeMap<String, ResourceGroup>,
         secrets: Vec<SecretInfo>,
-        label_key: &str,
+        key: GroupKey<'_>,
     ) {
         for secret in secrets {
-            let group_name = secret
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&secret.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.secrets.push(secret);
         }
     }
@@ -912,18 +1367,14 @@ impl ResourceGrouper {
     fn group_crds_by_label(
         groups: &mut BTreeMap<String, ResourceGroup>,
         crds: Vec<CRDInfo>,
-        label_key: &str,
+        key: GroupKey<'_>,
     ) {
         for crd in crds {
-            let group_name = crd
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&crd.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.crds.push(crd);
         }
     }
@@ -940,18 +1391,14 @@ impl ResourceGrouper {
     fn group_custom_resources_by_label(
         groups: &mut BTreeMap<String, ResourceGroup>,
         custom_resources: Vec<CustomResourceInfo>,
-        label_key: &str,
+        key: GroupKey<'_>,
     ) {
         for cr in custom_resources {
-            let group_name = cr
-                .labels
-                .get(label_key)
-                .unwrap_or(&"unknown".to_string())
-                .clone();
+            let group_name = key.group_of(&cr.labels);
 
             let group = groups
                 .entry(group_name.clone())
-                .or_insert_with(|| ResourceGroup::new(group_name, label_key.to_string()));
+                .or_insert_with(|| ResourceGroup::new(group_name, key.criteria()));
             group.custom_resources.push(cr);
         }
     }
@@ -1089,10 +1536,339 @@ mod tests {
         let criteria = FilterCriteria::default();
         assert!(criteria.label_selector.is_none());
         assert!(criteria.status_filter.is_none());
+        assert!(criteria.qos_class.is_none());
+        assert!(!criteria.bare_only);
+        assert!(criteria.node_role.is_none());
+        assert!(criteria.node_condition.is_none());
+        assert!(criteria.storage_class.is_none());
         assert!(criteria.newer_than.is_none());
         assert!(criteria.older_than.is_none());
         assert!(criteria.include_types.is_empty());
         assert!(criteria.exclude_types.is_empty());
+        assert!(ResourceFilter::includes_kind("Secret", &criteria));
+
+        let criteria = FilterCriteria {
+            include_types: vec![
+                "deploy".to_string(),
+                "cm".to_string(),
+                "secrets".to_string(),
+            ],
+            exclude_types: vec!["Secret".to_string()],
+            ..Default::default()
+        };
+        assert!(ResourceFilter::includes_kind("Deployment", &criteria));
+        assert!(ResourceFilter::includes_kind("ConfigMap", &criteria));
+        assert!(!ResourceFilter::includes_kind("Secret", &criteria));
+        assert!(!ResourceFilter::includes_kind("Service", &criteria));
+    }
+
+    #[test]
+    fn test_filter_pods_by_qos_class() {
+        let pod = |name: &str, qos: &str| PodInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            phase: "Running".to_string(),
+            pod_ip: None,
+            node_name: None,
+            labels: BTreeMap::new(),
+            ready_containers: 1,
+            total_containers: 1,
+            restart_count: 0,
+            age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            qos_class: qos.to_string(),
+            priority_class: None,
+            priority: None,
+            owner_kind: None,
+            owner_name: None,
+            mesh: None,
+            mesh_injection: None,
+        };
+        let pods = vec![pod("batch", "BestEffort"), pod("api", "Guaranteed")];
+
+        let criteria = FilterCriteria {
+            qos_class: Some("besteffort".to_string()),
+            ..Default::default()
+        };
+        let filtered = ResourceFilter::filter_pods(pods, &criteria);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name, "batch");
+    }
+
+    #[test]
+    fn test_filter_bare_pods() {
+        let pod = |name: &str, owner: Option<&str>| PodInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            phase: "Running".to_string(),
+            pod_ip: None,
+            node_name: None,
+            labels: BTreeMap::new(),
+            ready_containers: 1,
+            total_containers: 1,
+            restart_count: 0,
+            age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            qos_class: "BestEffort".to_string(),
+            priority_class: None,
+            priority: None,
+            owner_kind: owner.map(|_| "ReplicaSet".to_string()),
+            owner_name: owner.map(|o| o.to_string()),
+            mesh: None,
+            mesh_injection: None,
+        };
+        let pods = vec![pod("web-abc12", Some("web-7f9c")), pod("debug-shell", None)];
+
+        let criteria = FilterCriteria {
+            bare_only: true,
+            ..Default::default()
+        };
+        let filtered = ResourceFilter::filter_pods(pods, &criteria);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name, "debug-shell");
+    }
+
+    #[test]
+    fn test_filter_pods_by_age() {
+        let pod = |name: &str, created_at: Option<DateTime<Utc>>| PodInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            phase: "Running".to_string(),
+            pod_ip: None,
+            node_name: None,
+            labels: BTreeMap::new(),
+            ready_containers: 1,
+            total_containers: 1,
+            restart_count: 0,
+            age: String::new(),
+            created_at,
+            annotations: BTreeMap::new(),
+            qos_class: "BestEffort".to_string(),
+            priority_class: None,
+            priority: None,
+            owner_kind: None,
+            owner_name: None,
+            mesh: None,
+            mesh_injection: None,
+        };
+        let now = Utc::now();
+        let pods = vec![
+            pod("fresh", Some(now - chrono::Duration::minutes(10))),
+            pod("day-old", Some(now - chrono::Duration::hours(30))),
+            pod("ancient", Some(now - chrono::Duration::days(40))),
+            pod("unknown", None),
+        ];
+        let names = |criteria: &FilterCriteria| -> Vec<String> {
+            ResourceFilter::filter_pods(pods.clone(), criteria)
+                .into_iter()
+                .map(|p| p.name)
+                .collect()
+        };
+
+        let newer = FilterCriteria {
+            newer_than: Some(Duration::from_secs(2 * 3600)),
+            ..Default::default()
+        };
+        assert_eq!(names(&newer), ["fresh"]);
+        let older = FilterCriteria {
+            older_than: Some(Duration::from_secs(86400)),
+            ..Default::default()
+        };
+        assert_eq!(names(&older), ["day-old", "ancient"]);
+        let between = FilterCriteria {
+            older_than: Some(Duration::from_secs(86400)),
+            newer_than: Some(Duration::from_secs(7 * 86400)),
+            ..Default::default()
+        };
+        assert_eq!(names(&between), ["day-old"]);
+        assert_eq!(names(&FilterCriteria::default()).len(), 4);
+    }
+
+    #[test]
+    fn test_name_filter() {
+        let deployment = |name: &str| DeploymentInfo {
+            name: name.to_string(),
+            namespace: "shop".to_string(),
+            replicas: 1,
+            ready_replicas: 1,
+            available_replicas: 1,
+            strategy: "RollingUpdate".to_string(),
+            age: String::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            labels: BTreeMap::new(),
+            selector: BTreeMap::new(),
+            images: BTreeMap::new(),
+        };
+        let deployments = vec![
+            deployment("payments-api"),
+            deployment("payments-api-canary"),
+            deployment("legacy-payments-canary"),
+        ];
+        let names = |pattern: &str| -> Vec<String> {
+            let criteria = FilterCriteria {
+                name_filter: Some(NameFilter::parse(pattern).unwrap()),
+                ..Default::default()
+            };
+            ResourceFilter::filter_deployments(deployments.clone(), &criteria)
+                .into_iter()
+                .map(|d| d.name)
+                .collect()
+        };
+
+        assert_eq!(names("^payments-.*-canary"), ["payments-api-canary"]);
+        assert_eq!(
+            names("canary"),
+            ["payments-api-canary", "legacy-payments-canary"]
+        );
+
+        let err = NameFilter::parse("payments-(").unwrap_err();
+        assert!(err.starts_with("invalid name filter 'payments-('"));
+    }
+
+    #[test]
+    fn test_field_selector() {
+        let selector =
+            FieldSelector::parse("status.phase==Running, spec.nodeName!=node-2").unwrap();
+        assert_eq!(
+            selector.to_string(),
+            "status.phase=Running,spec.nodeName!=node-2"
+        );
+
+        let pod = |phase: &str, node: &str| PodInfo {
+            name: "web".to_string(),
+            namespace: "default".to_string(),
+            phase: phase.to_string(),
+            pod_ip: None,
+            node_name: Some(node.to_string()),
+            labels: BTreeMap::new(),
+            ready_containers: 1,
+            total_containers: 1,
+            restart_count: 0,
+            age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            qos_class: "BestEffort".to_string(),
+            priority_class: None,
+            priority: None,
+            owner_kind: None,
+            owner_name: None,
+            mesh: None,
+            mesh_injection: None,
+        };
+        assert_eq!(selector.matches_pod(&pod("Running", "node-1")), Ok(true));
+        assert_eq!(selector.matches_pod(&pod("Running", "node-2")), Ok(false));
+        assert_eq!(selector.matches_pod(&pod("Pending", "node-1")), Ok(false));
+
+        let unsupported = FieldSelector::parse("spec.restartPolicy=Always").unwrap();
+        assert!(unsupported.matches_pod(&pod("Running", "node-1")).is_err());
+        assert!(FieldSelector::parse("status.phase").is_err());
+        assert!(FieldSelector::parse("=Running").is_err());
+    }
+
+    #[test]
+    fn test_annotation_selector() {
+        let secret = |name: &str, issuer: Option<&str>| SecretInfo {
+            name: name.to_string(),
+            namespace: "shop".to_string(),
+            secret_type: "kubernetes.io/tls".to_string(),
+            data_keys: vec![],
+            age: "1d".to_string(),
+            labels: BTreeMap::new(),
+            used_by: vec![],
+            mount_paths: vec![],
+            service_account: None,
+            created_at: None,
+            annotations: issuer
+                .map(|issuer| {
+                    BTreeMap::from([(
+                        "cert-manager.io/cluster-issuer".to_string(),
+                        issuer.to_string(),
+                    )])
+                })
+                .unwrap_or_default(),
+        };
+        let secrets = vec![
+            secret("web-tls", Some("letsencrypt")),
+            secret("api-tls", Some("internal-ca")),
+            secret("manual-tls", None),
+        ];
+        let names = |selector: &str| -> Vec<String> {
+            let criteria = FilterCriteria {
+                annotation_selector: Some(LabelSelector::parse(selector).unwrap()),
+                ..Default::default()
+            };
+            ResourceFilter::filter_secrets(secrets.clone(), &criteria)
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        assert_eq!(
+            names("cert-manager.io/cluster-issuer=letsencrypt"),
+            ["web-tls"]
+        );
+        assert_eq!(
+            names("cert-manager.io/cluster-issuer"),
+            ["web-tls", "api-tls"]
+        );
+        assert_eq!(names("!cert-manager.io/cluster-issuer"), ["manual-tls"]);
+    }
+
+    #[test]
+    fn test_filter_nodes() {
+        let node = |name: &str, role: &str, ready: &str, disk_pressure: &str| NodeInfo {
+            name: name.to_string(),
+            roles: vec![role.to_string()],
+            conditions: [("Ready", ready), ("DiskPressure", disk_pressure)]
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            labels: [("pool".to_string(), role.to_string())].into(),
+            ..Default::default()
+        };
+        let nodes = vec![
+            node("cp-1", "control-plane", "True", "False"),
+            node("worker-1", "worker", "True", "True"),
+            node("worker-2", "worker", "False", "False"),
+        ];
+        let names = |criteria: FilterCriteria| -> Vec<String> {
+            ResourceFilter::filter_nodes(nodes.clone(), &criteria)
+                .into_iter()
+                .map(|n| n.name)
+                .collect()
+        };
+
+        assert_eq!(
+            names(FilterCriteria {
+                node_role: Some("Worker".to_string()),
+                ..Default::default()
+            }),
+            ["worker-1", "worker-2"]
+        );
+        assert_eq!(
+            names(FilterCriteria {
+                node_condition: Some("ready=false".to_string()),
+                ..Default::default()
+            }),
+            ["worker-2"]
+        );
+        assert_eq!(
+            names(FilterCriteria {
+                node_condition: Some("DiskPressure".to_string()),
+                ..Default::default()
+            }),
+            ["worker-1"]
+        );
+        assert_eq!(
+            names(FilterCriteria {
+                label_selector: Some("pool=control-plane".to_string()),
+                ..Default::default()
+            }),
+            ["cp-1"]
+        );
     }
 
     #[test]
@@ -1158,6 +1934,8 @@ mod tests {
                 reference_type: ReferenceType::VolumeMount,
             }],
             mount_paths: vec!["/etc/config".to_string()],
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         let configmaps = vec![configmap];
@@ -1200,6 +1978,9 @@ mod tests {
                 reference_type: ReferenceType::Environment,
             }],
             mount_paths: vec![],
+            service_account: None,
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         let secrets = vec![secret];
@@ -1240,6 +2021,8 @@ mod tests {
                 labels: web_labels,
                 used_by: vec![],
                 mount_paths: vec![],
+                created_at: None,
+                annotations: BTreeMap::new(),
             },
             ConfigMapInfo {
                 name: "api-config".to_string(),
@@ -1249,6 +2032,8 @@ mod tests {
                 labels: api_labels,
                 used_by: vec![],
                 mount_paths: vec![],
+                created_at: None,
+                annotations: BTreeMap::new(),
             },
         ];
 
@@ -1263,6 +2048,51 @@ mod tests {
         assert_eq!(web_group.configmaps[0].name, "web-config");
     }
 
+    #[test]
+    fn test_group_all_by_app() {
+        let labels = BTreeMap::from([("app".to_string(), "web".to_string())]);
+        let configmap = |name: &str, labels: BTreeMap<String, String>| ConfigMapInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            data_keys: vec![],
+            age: "1d".to_string(),
+            labels,
+            used_by: vec![],
+            mount_paths: vec![],
+            created_at: None,
+            annotations: BTreeMap::new(),
+        };
+        let all = AllResources {
+            secrets: vec![SecretInfo {
+                name: "web-tls".to_string(),
+                namespace: "default".to_string(),
+                secret_type: "kubernetes.io/tls".to_string(),
+                data_keys: vec![],
+                age: "1d".to_string(),
+                labels: labels.clone(),
+                used_by: vec![],
+                mount_paths: vec![],
+                service_account: None,
+                created_at: None,
+                annotations: BTreeMap::new(),
+            }],
+            configmaps: vec![
+                configmap("web-config", labels),
+                configmap("db-config", BTreeMap::from([("app".into(), "db".into())])),
+            ],
+            ..Default::default()
+        };
+
+        let grouped = ResourceGrouper::group_all(all.clone(), &GroupBy::App);
+        assert_eq!(grouped.groups.len(), 2);
+        assert_eq!(grouped.groups["web"].secrets.len(), 1);
+        assert_eq!(grouped.groups["web"].configmaps[0].name, "web-config");
+        assert_eq!(grouped.groups["db"].total_resources(), 1);
+
+        let grouped = ResourceGrouper::group_all(all, &GroupBy::None);
+        assert_eq!(grouped.groups["all"].total_resources(), 3);
+    }
+
     #[test]
     fn test_group_secrets_by_namespace() {
         use crate::discovery::SecretInfo;
@@ -1277,6 +2107,9 @@ mod tests {
                 labels: BTreeMap::new(),
                 used_by: vec![],
                 mount_paths: vec![],
+                service_account: None,
+                created_at: None,
+                annotations: BTreeMap::new(),
             },
             SecretInfo {
                 name: "secret2".to_string(),
@@ -1287,6 +2120,9 @@ mod tests {
                 labels: BTreeMap::new(),
                 used_by: vec![],
                 mount_paths: vec![],
+                service_account: None,
+                created_at: None,
+                annotations: BTreeMap::new(),
             },
         ];
 
@@ -1351,6 +2187,9 @@ mod tests {
                 schema_properties: vec!["spec".to_string(), "status".to_string()],
             }],
             description: Some("Prometheus monitoring instances".to_string()),
+            controller: None,
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         let crds = vec![crd];
@@ -1404,6 +2243,7 @@ mod tests {
                 namespace: "monitoring".to_string(),
                 reference_type: ReferenceType::VolumeMount,
             }],
+            created_at: None,
         };
 
         let custom_resources = vec![custom_resource];
@@ -1427,7 +2267,7 @@ mod tests {
 
     #[test]
     fn test_group_crds_by_scope() {
-        use crate::discovery::{CRDInfo, CRDVersion};
+        use crate::discovery::CRDInfo;
 
         let crds = vec![
             CRDInfo {
@@ -1442,6 +2282,9 @@ mod tests {
                 instance_count: 3,
                 versions: vec![],
                 description: None,
+                controller: None,
+                created_at: None,
+                annotations: BTreeMap::new(),
             },
             CRDInfo {
                 name: "prometheuses.monitoring.coreos.com".to_string(),
@@ -1455,6 +2298,9 @@ mod tests {
                 instance_count: 2,
                 versions: vec![],
                 description: None,
+                controller: None,
+                created_at: None,
+                annotations: BTreeMap::new(),
             },
         ];
 
@@ -1491,6 +2337,7 @@ mod tests {
                 spec_summary: "replicas: 2".to_string(),
                 status_summary: None,
                 related_resources: vec![],
+                created_at: None,
             },
             CustomResourceInfo {
                 name: "cluster-issuer".to_string(),
@@ -1505,6 +2352,7 @@ mod tests {
                 spec_summary: "acme: letsencrypt".to_string(),
                 status_summary: Some("ready: true".to_string()),
                 related_resources: vec![],
+                created_at: None,
             },
         ];
 
@@ -1585,6 +2433,9 @@ mod tests {
             instance_count: 10,
             versions,
             description: Some("TLS certificates managed by cert-manager".to_string()),
+            controller: None,
+            created_at: None,
+            annotations: BTreeMap::new(),
         };
 
         assert_eq!(crd.versions.len(), 2);
@@ -1,9 +1,71 @@
-use crate::discovery::{DiscoveryEngine, IngressInfo, PodInfo, ServiceInfo};
-use crate::error::Result;
+use crate::crd_versions::storage_version;
+use crate::dependencies::service_references;
+use crate::discovery::{
+    pod_workload, DiscoveryEngine, IngressInfo, PersistentVolumeClaimInfo, PersistentVolumeInfo,
+    PodInfo, ServiceInfo,
+};
+use crate::error::{ExplorerError, Result};
+use crate::externals::{find_externals, ExternalDependency};
+use crate::filtering::{GroupBy, GroupedResources, ResourceGrouper};
+use crate::helm;
+use crate::netpol::{self, Endpoint};
+use crate::pod_index::PodIndex;
+use crate::references::{pod_config_references, ConfigKind, PodConfigReference};
+use crate::resource::ResourceKind;
+use crate::storage::ClaimMounts;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{ConfigMap, Pod, PodTemplateSpec, Secret, Service};
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy, NetworkPolicyPeer};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{Api, DynamicObject, ListParams};
+use kube::discovery::Scope;
 use petgraph::graph::{NodeIndex, UnGraph};
 use petgraph::Graph;
-use std::collections::HashMap;
-use std::fmt::Write;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Debug, Write};
+
+/// Node shapes assigned to API groups of custom resources, in order of group name
+const CUSTOM_RESOURCE_SHAPES: &[&str] = &[
+    "hexagon",
+    "octagon",
+    "cylinder",
+    "parallelogram",
+    "component",
+    "house",
+    "trapezium",
+    "tab",
+    "folder",
+    "note",
+];
+
+/// Mermaid class names and styles, matching the DOT fill colors
+const MERMAID_CLASSES: &[(&str, &str)] = &[
+    ("service", "fill:#add8e6,stroke:#4682b4"),
+    ("highlight", "fill:#ff6347,stroke:#b22222,color:#fff"),
+    ("pod", "fill:#90ee90,stroke:#2e8b57"),
+    ("ingress", "fill:#ffa500,stroke:#cd8500"),
+    ("custom", "fill:#dda0dd,stroke:#8b668b"),
+    ("claim", "fill:#f0e68c,stroke:#8b864e"),
+    ("volume", "fill:#d3d3d3,stroke:#696969"),
+    ("external", "fill:#ffb6c1,stroke:#cd8c95"),
+    ("workload", "fill:#ffffe0,stroke:#8b8b7a"),
+    ("config", "fill:#e0ffff,stroke:#7a8b8b"),
+    ("secret", "fill:#d3d3d3,stroke:#696969"),
+    ("netpol", "fill:#ffdab9,stroke:#cd853f"),
+    ("namespace", "fill:#fff,stroke:#000,stroke-dasharray:4"),
+];
+
+/// Escape text for a quoted Mermaid label
+fn mermaid_text(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+/// Limit on ownerReference hops from a service or pod up to a custom resource
+const MAX_OWNER_DEPTH: usize = 8;
 
 #[derive(Debug, Clone)]
 pub struct ServiceNode {
@@ -13,11 +75,47 @@ pub struct ServiceNode {
     pub is_highlighted: bool,
 }
 
+impl ServiceNode {
+    /// Name with a kind prefix for kinds that would otherwise be ambiguous
+    fn label(&self) -> String {
+        match &self.node_type {
+            NodeType::CustomResource { kind, .. } => format!("{}/{}", kind, self.name),
+            NodeType::PersistentVolumeClaim => format!("pvc/{}", self.name),
+            NodeType::PersistentVolume => format!("pv/{}", self.name),
+            NodeType::Workload { kind } => format!("{}/{}", kind.to_lowercase(), self.name),
+            NodeType::ConfigMap => format!("cm/{}", self.name),
+            NodeType::Secret => format!("secret/{}", self.name),
+            NodeType::NetworkPolicy => format!("netpol/{}", self.name),
+            NodeType::Namespace { objects } => format!("{} objects", objects),
+            _ => self.name.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum NodeType {
     Service,
     Pod,
     Ingress,
+    CustomResource {
+        group: String,
+        kind: String,
+    },
+    PersistentVolumeClaim,
+    PersistentVolume,
+    /// A host outside the cluster; the node's namespace holds its category
+    External,
+    /// A Deployment, StatefulSet or DaemonSet
+    Workload {
+        kind: String,
+    },
+    ConfigMap,
+    Secret,
+    NetworkPolicy,
+    /// The objects of a namespace collapsed into one node by a size limit
+    Namespace {
+        objects: usize,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -25,15 +123,120 @@ pub struct ServiceEdge {
     pub relationship: EdgeType,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum EdgeType {
     ServiceToPod,
     IngressToService,
+    /// A custom resource owns the object, possibly through workloads
+    Owns,
+    /// A custom resource's label selector matches the object
+    Selects,
+    /// A pod mounts a persistent volume claim
+    Mounts,
+    /// A persistent volume claim is bound to a volume
+    BoundTo,
+    /// A service or pod sends traffic to an external host
+    DependsOn,
+    /// A workload or ingress uses a ConfigMap or Secret
+    Uses,
+    /// A NetworkPolicy selects the pod and isolates it
+    Isolates,
+    /// A NetworkPolicy rule lets the pod send or receive the traffic
+    Allows,
+    /// Edges between a collapsed namespace and another node, merged
+    Collapsed {
+        edges: usize,
+    },
+}
+
+/// Custom resource with what is needed to relate it to graph nodes
+#[derive(Debug, Clone)]
+pub struct CustomResourceRef {
+    pub group: String,
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub uid: String,
+    /// `spec.selector.matchLabels`, or `spec.selector` when it is a plain label map
+    pub selector: BTreeMap<String, String>,
+}
+
+impl CustomResourceRef {
+    pub fn from_object(group: &str, kind: &str, object: &DynamicObject) -> Self {
+        let selector = &object.data["spec"]["selector"];
+        let labels = selector
+            .get("matchLabels")
+            .unwrap_or(selector)
+            .as_object()
+            .map(|map| {
+                map.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            group: group.to_string(),
+            kind: kind.to_string(),
+            namespace: object.metadata.namespace.clone(),
+            name: object.metadata.name.clone().unwrap_or_default(),
+            uid: object.metadata.uid.clone().unwrap_or_default(),
+            selector: labels,
+        }
+    }
+}
+
+/// Deployment, StatefulSet or DaemonSet with what is needed from its pod
+/// template to relate it to graph nodes
+#[derive(Debug, Clone)]
+pub struct WorkloadRef {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// Labels of the pod template, matched against service selectors
+    pub template_labels: BTreeMap<String, String>,
+    /// ConfigMaps and Secrets the pod template references
+    pub references: Vec<PodConfigReference>,
+}
+
+impl WorkloadRef {
+    pub fn new(kind: &str, metadata: &ObjectMeta, template: Option<&PodTemplateSpec>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            namespace: metadata.namespace.clone().unwrap_or_default(),
+            name: metadata.name.clone().unwrap_or_default(),
+            template_labels: template
+                .and_then(|t| t.metadata.as_ref())
+                .and_then(|m| m.labels.clone())
+                .unwrap_or_default(),
+            references: template
+                .and_then(|t| t.spec.as_ref())
+                .map(pod_config_references)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn node_id(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.kind.to_lowercase(),
+            self.namespace,
+            self.name
+        )
+    }
 }
 
 pub struct ServiceGraph {
     graph: UnGraph<ServiceNode, ServiceEdge>,
     node_map: HashMap<String, NodeIndex>,
+    /// Caption drawn above the graph
+    title: Option<String>,
+}
+
+impl Default for ServiceGraph {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ServiceGraph {
@@ -41,9 +244,43 @@ impl ServiceGraph {
         Self {
             graph: Graph::new_undirected(),
             node_map: HashMap::new(),
+            title: None,
         }
     }
 
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graph.node_count() == 0
+    }
+
+    /// Add a node of any type under `node_id`, or return the existing one
+    fn add_object_node(
+        &mut self,
+        node_id: String,
+        namespace: &str,
+        name: &str,
+        node_type: NodeType,
+    ) -> NodeIndex {
+        if let Some(&existing_idx) = self.node_map.get(&node_id) {
+            return existing_idx;
+        }
+
+        let node = ServiceNode {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            node_type,
+            is_highlighted: false,
+        };
+
+        let idx = self.graph.add_node(node);
+        self.node_map.insert(node_id, idx);
+        idx
+    }
+
     pub fn add_service_node(&mut self, service: &ServiceInfo, is_highlighted: bool) -> NodeIndex {
         let node_id = format!("service:{}:{}", service.namespace, service.name);
 
@@ -101,6 +338,300 @@ impl ServiceGraph {
         idx
     }
 
+    pub fn add_custom_resource_node(&mut self, resource: &CustomResourceRef) -> NodeIndex {
+        let namespace = resource.namespace.as_deref().unwrap_or("cluster");
+        let node_id = format!(
+            "{}.{}:{}:{}",
+            resource.kind, resource.group, namespace, resource.name
+        );
+
+        if let Some(&existing_idx) = self.node_map.get(&node_id) {
+            return existing_idx;
+        }
+
+        let node = ServiceNode {
+            name: resource.name.clone(),
+            namespace: namespace.to_string(),
+            node_type: NodeType::CustomResource {
+                group: resource.group.clone(),
+                kind: resource.kind.clone(),
+            },
+            is_highlighted: false,
+        };
+
+        let idx = self.graph.add_node(node);
+        self.node_map.insert(node_id, idx);
+        idx
+    }
+
+    /// Add a claim, or a volume when `namespace` is `None`
+    fn add_storage_node(&mut self, namespace: Option<&str>, name: &str) -> NodeIndex {
+        let (node_id, node_type) = match namespace {
+            Some(ns) => (
+                format!("pvc:{}:{}", ns, name),
+                NodeType::PersistentVolumeClaim,
+            ),
+            None => (format!("pv:cluster:{}", name), NodeType::PersistentVolume),
+        };
+
+        if let Some(&existing_idx) = self.node_map.get(&node_id) {
+            return existing_idx;
+        }
+
+        let node = ServiceNode {
+            name: name.to_string(),
+            namespace: namespace.unwrap_or("cluster").to_string(),
+            node_type,
+            is_highlighted: false,
+        };
+
+        let idx = self.graph.add_node(node);
+        self.node_map.insert(node_id, idx);
+        idx
+    }
+
+    /// Add the claims mounted by `pods` with edges from each pod, and the
+    /// volumes bound to them. Mounted claims that do not exist are shown too,
+    /// since a missing claim is often why a pod is stuck.
+    pub fn add_storage(
+        &mut self,
+        pods: &[PodInfo],
+        claims: &[PersistentVolumeClaimInfo],
+        volumes: &[PersistentVolumeInfo],
+        mounts: &ClaimMounts,
+    ) {
+        for ((namespace, claim_name), mounted_by) in mounts {
+            let mounting: Vec<&PodInfo> = pods
+                .iter()
+                .filter(|p| p.namespace == *namespace && mounted_by.contains(&p.name))
+                .collect();
+            if mounting.is_empty() {
+                continue;
+            }
+
+            let claim_idx = self.add_storage_node(Some(namespace), claim_name);
+            for pod in mounting {
+                let pod_idx = self.add_pod_node(pod);
+                self.add_edge(pod_idx, claim_idx, EdgeType::Mounts);
+            }
+
+            let volume = claims
+                .iter()
+                .find(|c| c.namespace == *namespace && c.name == *claim_name)
+                .and_then(|c| c.volume_name.as_deref());
+            // Volumes that may not be listed are left out
+            if let Some(volume) = volume.filter(|v| volumes.iter().any(|pv| pv.name == *v)) {
+                let volume_idx = self.add_storage_node(None, volume);
+                self.add_edge(claim_idx, volume_idx, EdgeType::BoundTo);
+            }
+        }
+    }
+
+    fn add_external_node(&mut self, dependency: &ExternalDependency) -> NodeIndex {
+        let node_id = format!("external:{}", dependency.host);
+
+        if let Some(&existing_idx) = self.node_map.get(&node_id) {
+            return existing_idx;
+        }
+
+        let node = ServiceNode {
+            name: dependency.host.clone(),
+            namespace: dependency.category.clone(),
+            node_type: NodeType::External,
+            is_highlighted: false,
+        };
+
+        let idx = self.graph.add_node(node);
+        self.node_map.insert(node_id, idx);
+        idx
+    }
+
+    /// Add out-of-cluster dependencies with edges from the services
+    /// referencing them, and from the pods of referencing workloads
+    pub fn add_externals(&mut self, externals: &[ExternalDependency], pods: &[PodInfo]) {
+        for dependency in externals {
+            let external_idx = self.add_external_node(dependency);
+            for reference in &dependency.references {
+                if reference.kind == "Service" {
+                    let service_id = format!("service:{}:{}", reference.namespace, reference.name);
+                    if let Some(&service_idx) = self.node_map.get(&service_id) {
+                        self.add_edge(service_idx, external_idx, EdgeType::DependsOn);
+                    }
+                    continue;
+                }
+
+                let referencing = pods.iter().filter(|p| {
+                    p.namespace == reference.namespace
+                        && pod_workload(p) == (reference.kind.clone(), reference.name.clone())
+                });
+                for pod in referencing {
+                    let pod_idx = self.add_pod_node(pod);
+                    if self.graph.find_edge(pod_idx, external_idx).is_none() {
+                        self.add_edge(pod_idx, external_idx, EdgeType::DependsOn);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add workloads with edges from the services selecting their pod
+    /// template, and to the pods in the graph they own. Pods are matched to
+    /// workloads through their owner references, Deployments through the
+    /// ReplicaSet in between.
+    pub fn add_workloads(
+        &mut self,
+        workloads: &[WorkloadRef],
+        services: &[ServiceInfo],
+        index: &PodIndex,
+    ) {
+        for workload in workloads {
+            let workload_idx = self.add_object_node(
+                workload.node_id(),
+                &workload.namespace,
+                &workload.name,
+                NodeType::Workload {
+                    kind: workload.kind.clone(),
+                },
+            );
+
+            let selecting = services.iter().filter(|s| {
+                s.namespace == workload.namespace
+                    && s.selector.as_ref().is_some_and(|selector| {
+                        !selector.is_empty()
+                            && selector
+                                .iter()
+                                .all(|(k, v)| workload.template_labels.get(k) == Some(v))
+                    })
+            });
+            for service in selecting {
+                let service_id = format!("service:{}:{}", service.namespace, service.name);
+                if let Some(&service_idx) = self.node_map.get(&service_id) {
+                    self.add_edge(service_idx, workload_idx, EdgeType::Selects);
+                }
+            }
+
+            let owned = index.pods().iter().filter(|p| {
+                p.info.namespace == workload.namespace
+                    && p.workload.0 == workload.kind
+                    && p.workload.1 == workload.name
+            });
+            for pod in owned {
+                let pod_id = format!("pod:{}:{}", pod.info.namespace, pod.info.name);
+                if let Some(&pod_idx) = self.node_map.get(&pod_id) {
+                    self.add_edge(workload_idx, pod_idx, EdgeType::Owns);
+                }
+            }
+        }
+    }
+
+    /// Add the ConfigMaps and Secrets referenced by `workloads` and by the
+    /// pods of `index` no workload of the graph owns, with edges from each
+    /// consumer. Referenced objects that do not exist are shown too, since a
+    /// missing ConfigMap or Secret keeps pods from starting.
+    pub fn add_config(&mut self, workloads: &[WorkloadRef], index: &PodIndex) {
+        let mut consumers: Vec<(NodeIndex, &str, &[PodConfigReference])> = workloads
+            .iter()
+            .filter_map(|w| {
+                let idx = *self.node_map.get(&w.node_id())?;
+                Some((idx, w.namespace.as_str(), w.references.as_slice()))
+            })
+            .collect();
+        for pod in index.pods() {
+            let owned = workloads.iter().any(|w| {
+                w.namespace == pod.info.namespace
+                    && w.kind == pod.workload.0
+                    && w.name == pod.workload.1
+            });
+            if !owned && !pod.references.is_empty() {
+                let pod_idx = self.add_pod_node(&pod.info);
+                consumers.push((pod_idx, &pod.info.namespace, &pod.references));
+            }
+        }
+
+        for (consumer_idx, namespace, references) in consumers {
+            let mut used = HashSet::new();
+            for reference in references {
+                let (kind, node_type) = match reference.kind {
+                    ConfigKind::ConfigMap => ("configmap", NodeType::ConfigMap),
+                    ConfigKind::Secret => ("secret", NodeType::Secret),
+                };
+                let config_idx = self.add_object_node(
+                    format!("{}:{}:{}", kind, namespace, reference.name),
+                    namespace,
+                    &reference.name,
+                    node_type,
+                );
+                if used.insert(config_idx) {
+                    self.add_edge(consumer_idx, config_idx, EdgeType::Uses);
+                }
+            }
+        }
+    }
+
+    /// Add NetworkPolicies with edges to the pods they isolate and from the
+    /// pods their rules allow traffic to or from. Rules allowing every peer,
+    /// or only IP blocks, get no edges.
+    pub fn add_network_policies(
+        &mut self,
+        policies: &[NetworkPolicy],
+        pods: &[PodInfo],
+        namespace_labels: &BTreeMap<String, BTreeMap<String, String>>,
+    ) {
+        let endpoints: Vec<(&PodInfo, Endpoint)> = pods
+            .iter()
+            .map(|pod| {
+                let labels = namespace_labels.get(&pod.namespace).cloned();
+                (
+                    pod,
+                    Endpoint::from_pod_info(pod, labels.unwrap_or_default()),
+                )
+            })
+            .collect();
+        for policy in policies {
+            let namespace = policy.metadata.namespace.clone().unwrap_or_default();
+            let name = policy.metadata.name.clone().unwrap_or_default();
+            let policy_idx = self.add_object_node(
+                format!("netpol:{}:{}", namespace, name),
+                &namespace,
+                &name,
+                NodeType::NetworkPolicy,
+            );
+            let Some(spec) = &policy.spec else {
+                continue;
+            };
+            let peers: Vec<&NetworkPolicyPeer> = spec
+                .ingress
+                .iter()
+                .flatten()
+                .filter_map(|rule| rule.from.as_ref())
+                .chain(
+                    spec.egress
+                        .iter()
+                        .flatten()
+                        .filter_map(|rule| rule.to.as_ref()),
+                )
+                .flatten()
+                .filter(|peer| peer.ip_block.is_none())
+                .collect();
+
+            for (pod, endpoint) in &endpoints {
+                let isolated = netpol::policy_selects(policy, endpoint);
+                let allowed = peers
+                    .iter()
+                    .any(|peer| netpol::peer_matches(peer, &namespace, endpoint));
+                if isolated || allowed {
+                    let pod_idx = self.add_pod_node(pod);
+                    if isolated {
+                        self.add_edge(policy_idx, pod_idx, EdgeType::Isolates);
+                    }
+                    if allowed {
+                        self.add_edge(pod_idx, policy_idx, EdgeType::Allows);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn add_edge(&mut self, from: NodeIndex, to: NodeIndex, edge_type: EdgeType) {
         let edge = ServiceEdge {
             relationship: edge_type,
@@ -113,12 +644,30 @@ impl ServiceGraph {
         writeln!(dot, "graph ServiceDependencies {{").unwrap();
         writeln!(dot, "  rankdir=TB;").unwrap();
         writeln!(dot, "  node [shape=box, style=rounded];").unwrap();
+        if let Some(title) = &self.title {
+            writeln!(dot, "  label=\"{}\";", title).unwrap();
+            writeln!(dot, "  labelloc=t;").unwrap();
+        }
         writeln!(dot).unwrap();
 
+        let groups: BTreeSet<&str> = self
+            .graph
+            .node_weights()
+            .filter_map(|node| match &node.node_type {
+                NodeType::CustomResource { group, .. } => Some(group.as_str()),
+                _ => None,
+            })
+            .collect();
+        let group_shape = |group: &str| {
+            let index = groups.iter().position(|g| *g == group).unwrap_or(0);
+            CUSTOM_RESOURCE_SHAPES[index % CUSTOM_RESOURCE_SHAPES.len()]
+        };
+
         // Add nodes
         for node_idx in self.graph.node_indices() {
             if let Some(node) = self.graph.node_weight(node_idx) {
-                let (shape, color, style) = match node.node_type {
+                let label = node.label();
+                let (shape, color, style) = match &node.node_type {
                     NodeType::Service => (
                         "box",
                         if node.is_highlighted {
@@ -130,13 +679,28 @@ impl ServiceGraph {
                     ),
                     NodeType::Pod => ("ellipse", "lightgreen", "filled"),
                     NodeType::Ingress => ("diamond", "orange", "filled"),
+                    NodeType::CustomResource { group, .. } => {
+                        (group_shape(group), "plum", "filled")
+                    }
+                    NodeType::PersistentVolumeClaim => ("cylinder", "khaki", "filled"),
+                    NodeType::PersistentVolume => ("cylinder", "lightgrey", "filled"),
+                    NodeType::External => ("box3d", "lightpink", "filled"),
+                    NodeType::Workload { .. } => ("box", "lightyellow", "filled"),
+                    NodeType::ConfigMap => ("note", "lightcyan", "filled"),
+                    NodeType::Secret => ("note", "lightgrey", "filled"),
+                    NodeType::NetworkPolicy => ("octagon", "peachpuff", "filled"),
+                    NodeType::Namespace { .. } => (
+                        "folder",
+                        if node.is_highlighted { "red" } else { "white" },
+                        "filled",
+                    ),
                 };
 
                 writeln!(
                     dot,
                     "  \"{}\" [label=\"{}\\n({})\", shape={}, fillcolor={}, style=\"{}\"];",
                     node_idx.index(),
-                    node.name,
+                    label,
                     node.namespace,
                     shape,
                     color,
@@ -152,9 +716,22 @@ impl ServiceGraph {
         for edge_idx in self.graph.edge_indices() {
             if let Some((from, to)) = self.graph.edge_endpoints(edge_idx) {
                 if let Some(edge) = self.graph.edge_weight(edge_idx) {
+                    let merged;
                     let (style, label) = match edge.relationship {
                         EdgeType::ServiceToPod => ("solid", "manages"),
                         EdgeType::IngressToService => ("bold", "exposes"),
+                        EdgeType::Owns => ("dashed", "owns"),
+                        EdgeType::Selects => ("dotted", "selects"),
+                        EdgeType::Mounts => ("solid", "mounts"),
+                        EdgeType::BoundTo => ("bold", "bound to"),
+                        EdgeType::DependsOn => ("solid", "depends on"),
+                        EdgeType::Uses => ("dashed", "uses"),
+                        EdgeType::Isolates => ("bold", "isolates"),
+                        EdgeType::Allows => ("dotted", "allows"),
+                        EdgeType::Collapsed { edges } => {
+                            merged = links(edges);
+                            ("dashed", merged.as_str())
+                        }
                     };
 
                     writeln!(
@@ -174,6 +751,87 @@ impl ServiceGraph {
         dot
     }
 
+    /// Mermaid flowchart of the graph, for Markdown renderers that draw
+    /// `mermaid` code blocks
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        if let Some(title) = &self.title {
+            writeln!(mermaid, "---\ntitle: {}\n---", mermaid_text(title)).unwrap();
+        }
+        writeln!(mermaid, "graph TD").unwrap();
+        for (class, style) in MERMAID_CLASSES {
+            writeln!(mermaid, "  classDef {} {};", class, style).unwrap();
+        }
+
+        for node_idx in self.graph.node_indices() {
+            let node = &self.graph[node_idx];
+            let text = format!(
+                "{}<br/>({})",
+                mermaid_text(&node.label()),
+                mermaid_text(&node.namespace)
+            );
+            let (open, close, class) = match &node.node_type {
+                NodeType::Service if node.is_highlighted => ("[\"", "\"]", "highlight"),
+                NodeType::Service => ("[\"", "\"]", "service"),
+                NodeType::Pod => ("([\"", "\"])", "pod"),
+                NodeType::Ingress => ("{\"", "\"}", "ingress"),
+                NodeType::CustomResource { .. } => ("{{\"", "\"}}", "custom"),
+                NodeType::PersistentVolumeClaim => ("[(\"", "\")]", "claim"),
+                NodeType::PersistentVolume => ("[(\"", "\")]", "volume"),
+                NodeType::External => ("[[\"", "\"]]", "external"),
+                NodeType::Workload { .. } => ("[\"", "\"]", "workload"),
+                NodeType::ConfigMap => (">\"", "\"]", "config"),
+                NodeType::Secret => (">\"", "\"]", "secret"),
+                NodeType::NetworkPolicy => ("{{\"", "\"}}", "netpol"),
+                NodeType::Namespace { .. } if node.is_highlighted => ("[\"", "\"]", "highlight"),
+                NodeType::Namespace { .. } => ("[\"", "\"]", "namespace"),
+            };
+            writeln!(
+                mermaid,
+                "  n{}{}{}{}:::{}",
+                node_idx.index(),
+                open,
+                text,
+                close,
+                class
+            )
+            .unwrap();
+        }
+
+        for edge_idx in self.graph.edge_indices() {
+            let Some((from, to)) = self.graph.edge_endpoints(edge_idx) else {
+                continue;
+            };
+            let merged;
+            let (link, label) = match self.graph[edge_idx].relationship {
+                EdgeType::ServiceToPod => ("---", "manages"),
+                EdgeType::IngressToService => ("===", "exposes"),
+                EdgeType::Owns => ("-.-", "owns"),
+                EdgeType::Selects => ("-.-", "selects"),
+                EdgeType::Mounts => ("---", "mounts"),
+                EdgeType::BoundTo => ("===", "bound to"),
+                EdgeType::DependsOn => ("---", "depends on"),
+                EdgeType::Uses => ("-.-", "uses"),
+                EdgeType::Isolates => ("===", "isolates"),
+                EdgeType::Allows => ("-.-", "allows"),
+                EdgeType::Collapsed { edges } => {
+                    merged = links(edges);
+                    ("-.-", merged.as_str())
+                }
+            };
+            writeln!(
+                mermaid,
+                "  n{} {}|{}| n{}",
+                from.index(),
+                link,
+                label,
+                to.index()
+            )
+            .unwrap();
+        }
+        mermaid
+    }
+
     pub fn to_svg(&self) -> Result<String> {
         // For now, we'll generate DOT and suggest using Graphviz to convert to SVG
         let dot = self.to_dot();
@@ -183,12 +841,418 @@ impl ServiceGraph {
             dot
         ))
     }
+
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// Collapse namespaces, the one with the most objects first, until the
+    /// graph has at most `max_nodes` nodes and `max_edges` edges (0 for no
+    /// limit). External hosts are never collapsed, so a graph may stay over
+    /// the limits once every namespace is a single node.
+    pub fn limit(&mut self, max_nodes: usize, max_edges: usize) -> Vec<CollapsedNamespace> {
+        let mut collapsed = Vec::new();
+        loop {
+            let over = (max_nodes > 0 && self.graph.node_count() > max_nodes)
+                || (max_edges > 0 && self.graph.edge_count() > max_edges);
+            if !over {
+                break;
+            }
+            let mut sizes: BTreeMap<&str, usize> = BTreeMap::new();
+            for node in self.graph.node_weights() {
+                if !matches!(
+                    node.node_type,
+                    NodeType::External | NodeType::Namespace { .. }
+                ) {
+                    *sizes.entry(node.namespace.as_str()).or_default() += 1;
+                }
+            }
+            // Ties go to the first namespace by name
+            let Some(namespace) = sizes
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| **count)
+                .map(|(namespace, _)| namespace.to_string())
+            else {
+                break;
+            };
+            collapsed.push(self.collapse(&namespace));
+        }
+        collapsed
+    }
+
+    /// Replace the nodes of `namespace` by one node, merging their edges to
+    /// each other node into one
+    fn collapse(&mut self, namespace: &str) -> CollapsedNamespace {
+        let in_namespace = |node: &ServiceNode| {
+            node.namespace == namespace
+                && !matches!(
+                    node.node_type,
+                    NodeType::External | NodeType::Namespace { .. }
+                )
+        };
+        let objects = self
+            .graph
+            .node_weights()
+            .filter(|n| in_namespace(n))
+            .count();
+        let is_highlighted = self
+            .graph
+            .node_weights()
+            .any(|n| in_namespace(n) && n.is_highlighted);
+
+        let mut graph = Graph::new_undirected();
+        let namespace_idx = graph.add_node(ServiceNode {
+            name: namespace.to_string(),
+            namespace: namespace.to_string(),
+            node_type: NodeType::Namespace { objects },
+            is_highlighted,
+        });
+        let mut moved = HashMap::new();
+        for idx in self.graph.node_indices() {
+            let node = &self.graph[idx];
+            let new_idx = if in_namespace(node) {
+                namespace_idx
+            } else {
+                graph.add_node(node.clone())
+            };
+            moved.insert(idx, new_idx);
+        }
+
+        let mut merged: BTreeMap<NodeIndex, usize> = BTreeMap::new();
+        for edge_idx in self.graph.edge_indices() {
+            let Some((from, to)) = self.graph.edge_endpoints(edge_idx) else {
+                continue;
+            };
+            let (from, to) = (moved[&from], moved[&to]);
+            let edges = match &self.graph[edge_idx].relationship {
+                EdgeType::Collapsed { edges } => *edges,
+                _ => 1,
+            };
+            match (from == namespace_idx, to == namespace_idx) {
+                (true, true) => {}
+                (true, false) => *merged.entry(to).or_default() += edges,
+                (false, true) => *merged.entry(from).or_default() += edges,
+                (false, false) => {
+                    graph.add_edge(from, to, self.graph[edge_idx].clone());
+                }
+            }
+        }
+        for (other, edges) in merged {
+            graph.add_edge(
+                namespace_idx,
+                other,
+                ServiceEdge {
+                    relationship: EdgeType::Collapsed { edges },
+                },
+            );
+        }
+
+        let namespace_id = format!("namespace:{}", namespace);
+        self.node_map = self
+            .node_map
+            .drain()
+            .map(|(id, idx)| (id, moved[&idx]))
+            .chain([(namespace_id, namespace_idx)])
+            .collect();
+        self.graph = graph;
+        CollapsedNamespace {
+            namespace: namespace.to_string(),
+            objects,
+        }
+    }
+}
+
+/// Label of an edge standing for `edges` merged edges
+fn links(edges: usize) -> String {
+    format!("{} link{}", edges, if edges == 1 { "" } else { "s" })
 }
 
+/// A namespace drawn as a single node to keep a graph within its limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollapsedNamespace {
+    pub namespace: String,
+    /// Objects the node stands for
+    pub objects: usize,
+}
+
+/// Resource counts of one group in a grouped graph
+#[derive(Debug, Clone)]
+pub struct GroupBox {
+    pub name: String,
+    pub services: usize,
+    pub pods: usize,
+    pub deployments: usize,
+    pub statefulsets: usize,
+    pub daemonsets: usize,
+    pub is_highlighted: bool,
+}
+
+/// Application-level graph: each group is a cluster box with aggregate
+/// counts, and edges between groups count the workloads of one group that
+/// depend on services of the other
+pub struct GroupedGraph {
+    group_type: String,
+    groups: Vec<GroupBox>,
+    /// Dependencies keyed by (from group, to group) index
+    edges: BTreeMap<(usize, usize), usize>,
+}
+
+impl GroupedGraph {
+    /// Build the graph from grouped resources and pod-to-service references,
+    /// given as (pod namespace, pod name, service namespace, service name)
+    pub fn new(
+        grouped: &GroupedResources,
+        references: &[(String, String, String, String)],
+        highlight_service: Option<&str>,
+    ) -> Self {
+        let mut groups = Vec::new();
+        let mut service_group = HashMap::new();
+        let mut pod_group = HashMap::new();
+        for (index, group) in grouped.groups.values().enumerate() {
+            for service in &group.services {
+                service_group.insert((service.namespace.as_str(), service.name.as_str()), index);
+            }
+            for pod in &group.pods {
+                pod_group.insert((pod.namespace.as_str(), pod.name.as_str()), (index, pod));
+            }
+            groups.push(GroupBox {
+                name: group.name.clone(),
+                services: group.services.len(),
+                pods: group.pods.len(),
+                deployments: group.deployments.len(),
+                statefulsets: group.statefulsets.len(),
+                daemonsets: group.daemonsets.len(),
+                is_highlighted: highlight_service
+                    .is_some_and(|h| group.services.iter().any(|s| s.name == h)),
+            });
+        }
+
+        // Replicas of a workload count once per service they depend on
+        let mut dependencies = BTreeSet::new();
+        for (pod_ns, pod_name, service_ns, service_name) in references {
+            let Some(&(from, pod)) = pod_group.get(&(pod_ns.as_str(), pod_name.as_str())) else {
+                continue;
+            };
+            let Some(&to) = service_group.get(&(service_ns.as_str(), service_name.as_str())) else {
+                continue;
+            };
+            if from != to {
+                let workload = pod_workload(pod);
+                dependencies.insert((from, to, pod_ns, workload, service_ns, service_name));
+            }
+        }
+        let mut edges = BTreeMap::new();
+        for (from, to, ..) in dependencies {
+            *edges.entry((from, to)).or_insert(0) += 1;
+        }
+
+        Self {
+            group_type: grouped
+                .groups
+                .values()
+                .next()
+                .map(|g| g.group_type.clone())
+                .unwrap_or_default(),
+            groups,
+            edges,
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph ApplicationArchitecture {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        writeln!(
+            dot,
+            "  node [shape=box, style=\"rounded,filled\", fillcolor=white];"
+        )
+        .unwrap();
+        writeln!(dot).unwrap();
+
+        for (index, group) in self.groups.iter().enumerate() {
+            let counts: Vec<String> = [
+                (group.services, "service"),
+                (group.pods, "pod"),
+                (group.deployments, "deployment"),
+                (group.statefulsets, "statefulset"),
+                (group.daemonsets, "daemonset"),
+            ]
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, kind)| {
+                format!("{} {}{}", count, kind, if *count == 1 { "" } else { "s" })
+            })
+            .collect();
+            let (color, fill) = if group.is_highlighted {
+                ("red", "mistyrose")
+            } else {
+                ("steelblue", "aliceblue")
+            };
+
+            writeln!(dot, "  subgraph cluster_{} {{", index).unwrap();
+            writeln!(dot, "    label=\"{}: {}\";", self.group_type, group.name).unwrap();
+            writeln!(
+                dot,
+                "    style=\"rounded,filled\"; color={}; fillcolor={};",
+                color, fill
+            )
+            .unwrap();
+            writeln!(
+                dot,
+                "    \"group_{}\" [label=\"{}\"];",
+                index,
+                counts.join("\\n")
+            )
+            .unwrap();
+            writeln!(dot, "  }}").unwrap();
+        }
+
+        writeln!(dot).unwrap();
+
+        for ((from, to), count) in &self.edges {
+            writeln!(
+                dot,
+                "  \"group_{}\" -> \"group_{}\" [label=\"{} {}\"];",
+                from,
+                to,
+                count,
+                if *count == 1 {
+                    "dependency"
+                } else {
+                    "dependencies"
+                }
+            )
+            .unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    /// Mermaid flowchart of the groups and the dependencies between them
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        writeln!(mermaid, "graph LR").unwrap();
+        for (class, style) in MERMAID_CLASSES {
+            writeln!(mermaid, "  classDef {} {};", class, style).unwrap();
+        }
+
+        for (index, group) in self.groups.iter().enumerate() {
+            let counts: Vec<String> = [
+                (group.services, "service"),
+                (group.pods, "pod"),
+                (group.deployments, "deployment"),
+                (group.statefulsets, "statefulset"),
+                (group.daemonsets, "daemonset"),
+            ]
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, kind)| {
+                format!("{} {}{}", count, kind, if *count == 1 { "" } else { "s" })
+            })
+            .collect();
+            writeln!(
+                mermaid,
+                "  group_{}[\"<b>{}: {}</b><br/>{}\"]:::{}",
+                index,
+                mermaid_text(&self.group_type),
+                mermaid_text(&group.name),
+                counts.join("<br/>"),
+                if group.is_highlighted {
+                    "highlight"
+                } else {
+                    "service"
+                }
+            )
+            .unwrap();
+        }
+
+        for ((from, to), count) in &self.edges {
+            writeln!(
+                mermaid,
+                "  group_{} -->|{} {}| group_{}",
+                from,
+                count,
+                if *count == 1 {
+                    "dependency"
+                } else {
+                    "dependencies"
+                },
+                to
+            )
+            .unwrap();
+        }
+        mermaid
+    }
+
+    pub fn to_svg(&self) -> Result<String> {
+        let dot = self.to_dot();
+        Ok(format!(
+            "<!-- SVG generation requires Graphviz. Use: echo '{}' | dot -Tsvg -->\n{}",
+            dot.replace('\n', "\\n"),
+            dot
+        ))
+    }
+}
+
+/// Group services, pods and workloads and relate the groups through the
+/// services their pods reference in environment variables
+pub async fn generate_grouped_graph(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+    group_by: &GroupBy,
+    highlight_service: Option<&str>,
+) -> Result<GroupedGraph> {
+    let services = discovery.list_services(namespace).await?;
+    let pods = discovery.list_pods(namespace, None).await?;
+    let deployments = discovery.list_deployments(namespace).await?;
+    let statefulsets = discovery.list_statefulsets(namespace).await?;
+    let daemonsets = discovery.list_daemonsets(namespace).await?;
+
+    // Pod specs carry the environment dependencies are read from
+    let mut references = Vec::new();
+    for (_, object) in discovery
+        .list_objects_json(namespace, &[ResourceKind::Pod])
+        .await?
+    {
+        let pod: Pod = serde_json::from_value(object)?;
+        let (Some(spec), Some(pod_ns), Some(pod_name)) =
+            (&pod.spec, &pod.metadata.namespace, &pod.metadata.name)
+        else {
+            continue;
+        };
+        for (service_ns, service_name) in service_references(spec, pod_ns, &services) {
+            references.push((pod_ns.clone(), pod_name.clone(), service_ns, service_name));
+        }
+    }
+
+    let grouped = ResourceGrouper::group_resources(
+        services,
+        pods,
+        deployments,
+        statefulsets,
+        daemonsets,
+        group_by,
+    );
+    Ok(GroupedGraph::new(&grouped, &references, highlight_service))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_service_graph(
     discovery: &DiscoveryEngine,
     namespace: Option<&str>,
     include_pods: bool,
+    include_custom_resources: bool,
+    include_storage: bool,
+    include_externals: bool,
+    include_workloads: bool,
+    include_config: bool,
+    include_network_policies: bool,
     highlight_service: Option<&str>,
 ) -> Result<ServiceGraph> {
     let mut graph = ServiceGraph::new();
@@ -208,16 +1272,16 @@ pub async fn generate_service_graph(
 
     // Add pod relationships if requested
     if include_pods {
-        for service in &services {
-            // Get pods for this service (simplified - in reality we'd use selectors)
-            if let Ok(pods) = discovery.list_pods(Some(&service.namespace), None).await {
-                for pod in pods {
-                    let pod_idx = graph.add_pod_node(&pod);
-                    if let Some(&service_idx) =
-                        service_nodes.get(&format!("{}:{}", service.namespace, service.name))
-                    {
-                        graph.add_edge(service_idx, pod_idx, EdgeType::ServiceToPod);
-                    }
+        // One pod listing answers every service's selector
+        if let Ok(index) = discovery.pod_index(namespace).await {
+            for service in &services {
+                let Some(selector) = &service.selector else {
+                    continue;
+                };
+                let service_idx = service_nodes[&format!("{}:{}", service.namespace, service.name)];
+                for pod in index.selected_by(&service.namespace, selector) {
+                    let pod_idx = graph.add_pod_node(pod);
+                    graph.add_edge(service_idx, pod_idx, EdgeType::ServiceToPod);
                 }
             }
         }
@@ -240,9 +1304,438 @@ pub async fn generate_service_graph(
         }
     }
 
+    if include_custom_resources {
+        add_custom_resources(&mut graph, discovery, namespace).await?;
+    }
+
+    if include_storage {
+        let pods = discovery.list_pods(namespace, None).await?;
+        let claims = discovery.list_persistent_volume_claims(namespace).await?;
+        let volumes = match discovery.list_persistent_volumes().await {
+            Ok(volumes) => volumes,
+            Err(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                if (403..=405).contains(&e.code) =>
+            {
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+        let mounts = discovery.list_claim_mounts(namespace).await?;
+        graph.add_storage(&pods, &claims, &volumes, &mounts);
+    }
+
+    if include_externals {
+        let pods = discovery.list_pods(namespace, None).await?;
+        let externals = find_externals(discovery, namespace).await?;
+        graph.add_externals(&externals, &pods);
+    }
+
+    if include_workloads || include_config {
+        let workloads = if include_workloads {
+            list_workloads(discovery, namespace).await?
+        } else {
+            Vec::new()
+        };
+        let index = discovery.pod_index(namespace).await?;
+        if include_workloads {
+            graph.add_workloads(&workloads, &services, &index);
+        }
+        if include_config {
+            graph.add_config(&workloads, &index);
+        }
+    }
+
+    if include_network_policies {
+        let policies = list_in_scope::<NetworkPolicy>(discovery, namespace).await?;
+        let pods = discovery.list_pods(namespace, None).await?;
+        let namespace_labels = netpol::namespace_labels(discovery, &[]).await?;
+        graph.add_network_policies(&policies, &pods, &namespace_labels);
+    }
+
+    Ok(graph)
+}
+
+/// Objects of kind `K` in `namespace`, or in all namespaces if None
+async fn list_in_scope<K>(discovery: &DiscoveryEngine, namespace: Option<&str>) -> Result<Vec<K>>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    K::DynamicType: Default,
+{
+    match namespace {
+        Some(ns) => list_namespaced(discovery, ns).await,
+        None => {
+            let api: Api<K> = Api::all(discovery.client().clone());
+            Ok(api.list(&ListParams::default()).await?.items)
+        }
+    }
+}
+
+/// Deployments, StatefulSets and DaemonSets in `namespace` (or all
+/// namespaces if None)
+async fn list_workloads(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<WorkloadRef>> {
+    let (deployments, statefulsets, daemonsets) = tokio::try_join!(
+        list_in_scope::<Deployment>(discovery, namespace),
+        list_in_scope::<StatefulSet>(discovery, namespace),
+        list_in_scope::<DaemonSet>(discovery, namespace),
+    )?;
+    let deployments = deployments.iter().map(|d| {
+        WorkloadRef::new(
+            "Deployment",
+            &d.metadata,
+            d.spec.as_ref().map(|s| &s.template),
+        )
+    });
+    let statefulsets = statefulsets.iter().map(|s| {
+        WorkloadRef::new(
+            "StatefulSet",
+            &s.metadata,
+            s.spec.as_ref().map(|s| &s.template),
+        )
+    });
+    let daemonsets = daemonsets.iter().map(|d| {
+        WorkloadRef::new(
+            "DaemonSet",
+            &d.metadata,
+            d.spec.as_ref().map(|s| &s.template),
+        )
+    });
+    Ok(deployments.chain(statefulsets).chain(daemonsets).collect())
+}
+
+/// Objects of kind `K` in `namespace`
+async fn list_namespaced<K>(discovery: &DiscoveryEngine, namespace: &str) -> Result<Vec<K>>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    K::DynamicType: Default,
+{
+    let api: Api<K> = Api::namespaced(discovery.client().clone(), namespace);
+    Ok(api.list(&ListParams::default()).await?.items)
+}
+
+/// Graph of the objects belonging to Helm release `release` in `namespace`:
+/// its ingresses, services, workloads and the ConfigMaps and Secrets they
+/// use, with the release and chart as caption
+pub async fn generate_release_graph(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    release: &str,
+) -> Result<ServiceGraph> {
+    let release = helm::find_release(discovery, namespace, release).await?;
+    let (services, deployments, statefulsets, daemonsets, configmaps, ingresses) = tokio::try_join!(
+        list_namespaced::<Service>(discovery, namespace),
+        list_namespaced::<Deployment>(discovery, namespace),
+        list_namespaced::<StatefulSet>(discovery, namespace),
+        list_namespaced::<DaemonSet>(discovery, namespace),
+        list_namespaced::<ConfigMap>(discovery, namespace),
+        list_namespaced::<Ingress>(discovery, namespace),
+    )?;
+    // Secret names only; the graph never needs their data
+    let secrets: Api<Secret> = Api::namespaced(discovery.client().clone(), namespace);
+    let secrets = match secrets.list_metadata(&ListParams::default()).await {
+        Ok(list) => list.items,
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut title = format!("Helm release {}", release.name);
+    if let Some(chart) = &release.chart {
+        write!(title, " ({}, revision {})", chart, release.revision).unwrap();
+    }
+    let mut graph = ServiceGraph::new().with_title(title);
+
+    let node_id = |kind: &str, name: &str| format!("{}:{}:{}", kind, namespace, name);
+    for cm in configmaps
+        .iter()
+        .filter(|c| release.contains("ConfigMap", &c.metadata))
+    {
+        let name = cm.metadata.name.as_deref().unwrap_or_default();
+        graph.add_object_node(
+            node_id("configmap", name),
+            namespace,
+            name,
+            NodeType::ConfigMap,
+        );
+    }
+    for secret in secrets
+        .iter()
+        .filter(|s| release.contains("Secret", &s.metadata))
+    {
+        let name = secret.metadata.name.as_deref().unwrap_or_default();
+        graph.add_object_node(node_id("secret", name), namespace, name, NodeType::Secret);
+    }
+    let config_node = |graph: &ServiceGraph, kind: ConfigKind, name: &str| {
+        let kind = match kind {
+            ConfigKind::ConfigMap => "configmap",
+            ConfigKind::Secret => "secret",
+        };
+        graph.node_map.get(&node_id(kind, name)).copied()
+    };
+
+    let workloads: Vec<(&str, &ObjectMeta, Option<&PodTemplateSpec>)> = deployments
+        .iter()
+        .map(|d| {
+            (
+                "Deployment",
+                &d.metadata,
+                d.spec.as_ref().map(|s| &s.template),
+            )
+        })
+        .chain(statefulsets.iter().map(|s| {
+            (
+                "StatefulSet",
+                &s.metadata,
+                s.spec.as_ref().map(|s| &s.template),
+            )
+        }))
+        .chain(daemonsets.iter().map(|d| {
+            (
+                "DaemonSet",
+                &d.metadata,
+                d.spec.as_ref().map(|s| &s.template),
+            )
+        }))
+        .filter(|(kind, metadata, _)| release.contains(kind, metadata))
+        .collect();
+    let mut workload_nodes = Vec::new();
+    for (kind, metadata, template) in &workloads {
+        let name = metadata.name.as_deref().unwrap_or_default();
+        let workload_idx = graph.add_object_node(
+            node_id(&kind.to_lowercase(), name),
+            namespace,
+            name,
+            NodeType::Workload {
+                kind: kind.to_string(),
+            },
+        );
+        let spec = template.and_then(|t| t.spec.as_ref());
+        let mut used = HashSet::new();
+        for reference in spec.map(pod_config_references).unwrap_or_default() {
+            if let Some(config_idx) = config_node(&graph, reference.kind, &reference.name) {
+                if used.insert(config_idx) {
+                    graph.add_edge(workload_idx, config_idx, EdgeType::Uses);
+                }
+            }
+        }
+        let labels = template
+            .and_then(|t| t.metadata.as_ref())
+            .and_then(|m| m.labels.clone())
+            .unwrap_or_default();
+        workload_nodes.push((workload_idx, labels));
+    }
+
+    for service in services
+        .iter()
+        .filter(|s| release.contains("Service", &s.metadata))
+    {
+        let name = service.metadata.name.as_deref().unwrap_or_default();
+        let service_idx =
+            graph.add_object_node(node_id("service", name), namespace, name, NodeType::Service);
+        let selector = service
+            .spec
+            .as_ref()
+            .and_then(|s| s.selector.clone())
+            .unwrap_or_default();
+        if selector.is_empty() {
+            continue;
+        }
+        for (workload_idx, labels) in &workload_nodes {
+            if selector.iter().all(|(k, v)| labels.get(k) == Some(v)) {
+                graph.add_edge(service_idx, *workload_idx, EdgeType::Selects);
+            }
+        }
+    }
+
+    for ingress in ingresses
+        .iter()
+        .filter(|i| release.contains("Ingress", &i.metadata))
+    {
+        let name = ingress.metadata.name.as_deref().unwrap_or_default();
+        let ingress_idx =
+            graph.add_object_node(node_id("ingress", name), namespace, name, NodeType::Ingress);
+        let Some(spec) = &ingress.spec else {
+            continue;
+        };
+        let backends: BTreeSet<&str> = spec
+            .rules
+            .iter()
+            .flatten()
+            .flat_map(|rule| rule.http.iter().flat_map(|http| &http.paths))
+            .map(|path| &path.backend)
+            .chain(&spec.default_backend)
+            .filter_map(|backend| Some(backend.service.as_ref()?.name.as_str()))
+            .collect();
+        for backend in backends {
+            if let Some(&service_idx) = graph.node_map.get(&node_id("service", backend)) {
+                graph.add_edge(ingress_idx, service_idx, EdgeType::IngressToService);
+            }
+        }
+        let tls_secrets: BTreeSet<&str> = spec
+            .tls
+            .iter()
+            .flatten()
+            .filter_map(|tls| tls.secret_name.as_deref())
+            .collect();
+        for secret in tls_secrets {
+            if let Some(secret_idx) = config_node(&graph, ConfigKind::Secret, secret) {
+                graph.add_edge(ingress_idx, secret_idx, EdgeType::Uses);
+            }
+        }
+    }
+
+    if graph.is_empty() {
+        return Err(ExplorerError::ResourceNotFound {
+            kind: "HelmRelease".to_string(),
+            name: release.name,
+            namespace: namespace.to_string(),
+        });
+    }
     Ok(graph)
 }
 
+/// Add the custom resources in scope with edges to the services and pods
+/// they own or select. Custom resources that may not be listed are skipped.
+async fn add_custom_resources(
+    graph: &mut ServiceGraph,
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<()> {
+    let mut resources = Vec::new();
+    for crd in discovery.list_crd_definitions().await? {
+        if namespace.is_some() && crd.spec.scope != "Namespaced" {
+            continue;
+        }
+        let Some(version) = storage_version(&crd) else {
+            continue;
+        };
+        let objects = match discovery
+            .list_dynamic_objects(&crd, version, namespace)
+            .await
+        {
+            Ok(objects) => objects,
+            Err(ExplorerError::Kubernetes(kube::Error::Api(e)))
+                if (403..=405).contains(&e.code) =>
+            {
+                continue
+            }
+            Err(e) => return Err(e),
+        };
+        resources.extend(
+            objects
+                .iter()
+                .map(|o| CustomResourceRef::from_object(&crd.spec.group, &crd.spec.names.kind, o)),
+        );
+    }
+    if resources.is_empty() {
+        return Ok(());
+    }
+
+    // Services, pods and the workloads in between, for ownership and labels
+    let kinds = [
+        ResourceKind::Service,
+        ResourceKind::Pod,
+        ResourceKind::Deployment,
+        ResourceKind::ReplicaSet,
+        ResourceKind::StatefulSet,
+        ResourceKind::DaemonSet,
+        ResourceKind::Job,
+    ];
+    let kinds: Vec<_> = kinds
+        .iter()
+        .map(|kind| (kind.api_resource(), Scope::Namespaced))
+        .collect();
+    let objects = discovery.list_object_metadata(namespace, &kinds).await?;
+
+    let nodes: Vec<NodeIndex> = resources
+        .iter()
+        .map(|resource| graph.add_custom_resource_node(resource))
+        .collect();
+    for (index, node_id, edge_type) in custom_resource_edges(&resources, &objects) {
+        // Pods are only in the graph with --include-pods
+        if let Some(&target) = graph.node_map.get(&node_id) {
+            graph.add_edge(nodes[index], target, edge_type);
+        }
+    }
+
+    Ok(())
+}
+
+/// Edges from custom resources (by index) to the graph node ids of services
+/// and pods they own, directly or through workloads, or select by label
+fn custom_resource_edges(
+    resources: &[CustomResourceRef],
+    objects: &[(String, ObjectMeta)],
+) -> Vec<(usize, String, EdgeType)> {
+    let by_uid: HashMap<&str, usize> = resources
+        .iter()
+        .enumerate()
+        .map(|(index, r)| (r.uid.as_str(), index))
+        .collect();
+    let owners: HashMap<&str, Vec<&str>> = objects
+        .iter()
+        .filter_map(|(_, meta)| {
+            let owners = meta
+                .owner_references
+                .iter()
+                .flatten()
+                .map(|o| o.uid.as_str())
+                .collect();
+            Some((meta.uid.as_deref()?, owners))
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for (kind, meta) in objects {
+        let prefix = match kind.as_str() {
+            "Service" => "service",
+            "Pod" => "pod",
+            _ => continue,
+        };
+        let namespace = meta.namespace.as_deref().unwrap_or_default();
+        let name = meta.name.as_deref().unwrap_or_default();
+        let node_id = format!("{}:{}:{}", prefix, namespace, name);
+
+        // Walk ownerReferences upwards until a custom resource is reached
+        let mut owning = BTreeSet::new();
+        let mut seen = HashSet::new();
+        let mut frontier: Vec<&str> = meta.uid.as_deref().into_iter().collect();
+        for _ in 0..MAX_OWNER_DEPTH {
+            frontier = frontier
+                .into_iter()
+                .flat_map(|uid| owners.get(uid).into_iter().flatten().copied())
+                .filter(|uid| seen.insert(*uid))
+                .collect();
+            for uid in &frontier {
+                if let Some(&index) = by_uid.get(uid) {
+                    owning.insert(index);
+                }
+            }
+            if frontier.is_empty() {
+                break;
+            }
+        }
+        for index in &owning {
+            edges.push((*index, node_id.clone(), EdgeType::Owns));
+        }
+
+        let labels = meta.labels.clone().unwrap_or_default();
+        for (index, resource) in resources.iter().enumerate() {
+            let selects = !resource.selector.is_empty()
+                && resource.namespace.as_deref() == Some(namespace)
+                && resource
+                    .selector
+                    .iter()
+                    .all(|(k, v)| labels.get(k) == Some(v));
+            if selects && !owning.contains(&index) {
+                edges.push((index, node_id.clone(), EdgeType::Selects));
+            }
+        }
+    }
+    edges
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +1775,420 @@ mod tests {
         assert!(!node.is_highlighted);
     }
 
+    fn object(kind: &str, name: &str, uid: &str, owner: Option<&str>) -> (String, ObjectMeta) {
+        let owner_references = owner.map(|uid| {
+            vec![
+                k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference {
+                    uid: uid.to_string(),
+                    ..Default::default()
+                },
+            ]
+        });
+        (
+            kind.to_string(),
+            ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("db".to_string()),
+                uid: Some(uid.to_string()),
+                owner_references,
+                labels: Some(BTreeMap::from([("app".to_string(), name.to_string())])),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_custom_resource_edges() {
+        let cluster = CustomResourceRef {
+            group: "postgresql.cnpg.io".to_string(),
+            kind: "Cluster".to_string(),
+            namespace: Some("db".to_string()),
+            name: "main".to_string(),
+            uid: "cr-1".to_string(),
+            selector: BTreeMap::new(),
+        };
+        let monitor = CustomResourceRef {
+            group: "monitoring.coreos.com".to_string(),
+            kind: "ServiceMonitor".to_string(),
+            namespace: Some("db".to_string()),
+            name: "main-metrics".to_string(),
+            uid: "cr-2".to_string(),
+            selector: BTreeMap::from([("app".to_string(), "main-rw".to_string())]),
+        };
+        let objects = vec![
+            object("Service", "main-rw", "svc-1", Some("cr-1")),
+            object("StatefulSet", "main", "sts-1", Some("cr-1")),
+            object("Pod", "main-0", "pod-1", Some("sts-1")),
+            object("Service", "other", "svc-2", None),
+        ];
+
+        let edges = custom_resource_edges(&[cluster, monitor], &objects);
+        let edges: Vec<(usize, &str, &EdgeType)> = edges
+            .iter()
+            .map(|(index, id, edge)| (*index, id.as_str(), edge))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (0, "service:db:main-rw", &EdgeType::Owns),
+                (1, "service:db:main-rw", &EdgeType::Selects),
+                (0, "pod:db:main-0", &EdgeType::Owns),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_resource_shapes_per_group() {
+        let mut graph = ServiceGraph::new();
+        for (group, name) in [("a.example.com", "one"), ("b.example.com", "two")] {
+            graph.add_custom_resource_node(&CustomResourceRef {
+                group: group.to_string(),
+                kind: "Widget".to_string(),
+                namespace: None,
+                name: name.to_string(),
+                uid: name.to_string(),
+                selector: BTreeMap::new(),
+            });
+        }
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"Widget/one\\n(cluster)\", shape=hexagon"));
+        assert!(dot.contains("label=\"Widget/two\\n(cluster)\", shape=octagon"));
+    }
+
+    #[test]
+    fn test_grouped_graph() {
+        let service = |ns: &str, name: &str, app: &str| -> ServiceInfo {
+            serde_json::from_value(serde_json::json!({
+                "name": name, "namespace": ns, "ports": [], "service_type": "ClusterIP",
+                "selector": {"app": app},
+            }))
+            .unwrap()
+        };
+        let pod = |name: &str, app: &str, owner: &str| -> PodInfo {
+            serde_json::from_value(serde_json::json!({
+                "name": name, "namespace": "shop", "phase": "Running",
+                "labels": {"app": app, "pod-template-hash": "abc12"},
+                "ready_containers": 1, "total_containers": 1, "restart_count": 0,
+                "age": "1d", "qos_class": "BestEffort",
+                "owner_kind": "ReplicaSet", "owner_name": owner,
+            }))
+            .unwrap()
+        };
+        let grouped = ResourceGrouper::group_resources(
+            vec![service("shop", "web", "web"), service("shop", "db", "db")],
+            vec![
+                pod("web-abc12-x", "web", "web-abc12"),
+                pod("web-abc12-y", "web", "web-abc12"),
+                pod("db-abc12-z", "db", "db-abc12"),
+            ],
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            &GroupBy::App,
+        );
+        let reference = |pod: &str, service: &str| {
+            (
+                "shop".to_string(),
+                pod.to_string(),
+                "shop".to_string(),
+                service.to_string(),
+            )
+        };
+        let references = [
+            reference("web-abc12-x", "db"),
+            reference("web-abc12-y", "db"),
+            // Calls within a group are not drawn
+            reference("db-abc12-z", "db"),
+        ];
+
+        let graph = GroupedGraph::new(&grouped, &references, Some("web"));
+        let dot = graph.to_dot();
+        // Groups are ordered by name: db is group_0, web is group_1
+        assert!(dot.contains("label=\"app: web\";"));
+        assert!(dot.contains("\"group_1\" [label=\"1 service\\n2 pods\"];"));
+        assert!(dot.contains("color=red"));
+        // Both replicas belong to one deployment
+        assert!(dot.contains("\"group_1\" -> \"group_0\" [label=\"1 dependency\"];"));
+        assert!(graph
+            .to_mermaid()
+            .contains("  group_1 -->|1 dependency| group_0\n"));
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_storage_nodes() {
+        let pod: PodInfo = serde_json::from_value(serde_json::json!({
+            "name": "db-0", "namespace": "data", "phase": "Running", "labels": {},
+            "ready_containers": 1, "total_containers": 1, "restart_count": 0,
+            "age": "1d", "qos_class": "BestEffort",
+        }))
+        .unwrap();
+        let claim: PersistentVolumeClaimInfo = serde_json::from_value(serde_json::json!({
+            "name": "data-db-0", "namespace": "data", "phase": "Bound",
+            "volume_name": "pvc-123", "access_modes": [], "age": "1d", "labels": {},
+        }))
+        .unwrap();
+        let volume: PersistentVolumeInfo = serde_json::from_value(serde_json::json!({
+            "name": "pvc-123", "access_modes": [], "reclaim_policy": "Delete",
+            "phase": "Bound", "age": "1d",
+        }))
+        .unwrap();
+        let mounts: ClaimMounts = [
+            (
+                ("data".to_string(), "data-db-0".to_string()),
+                vec!["db-0".to_string()],
+            ),
+            (
+                ("data".to_string(), "missing".to_string()),
+                vec!["db-0".to_string()],
+            ),
+            (
+                ("data".to_string(), "other".to_string()),
+                vec!["gone".to_string()],
+            ),
+        ]
+        .into();
+
+        let mut graph = ServiceGraph::new();
+        graph.add_storage(&[pod], &[claim], &[volume], &mounts);
+        // db-0, both claims it mounts and the bound volume
+        assert_eq!(graph.graph.node_count(), 4);
+        assert_eq!(graph.graph.edge_count(), 3);
+        assert!(graph.node_map.contains_key("pv:cluster:pvc-123"));
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"pvc/data-db-0\\n(data)\", shape=cylinder"));
+        assert!(dot.contains("label=\"bound to\""));
+    }
+
+    #[test]
+    fn test_external_nodes() {
+        let pod: PodInfo = serde_json::from_value(serde_json::json!({
+            "name": "worker", "namespace": "shop", "phase": "Running", "labels": {},
+            "ready_containers": 1, "total_containers": 1, "restart_count": 0,
+            "age": "1d", "qos_class": "BestEffort",
+        }))
+        .unwrap();
+        let external: ExternalDependency = serde_json::from_value(serde_json::json!({
+            "host": "orders.abc.rds.amazonaws.com", "ports": [5432], "category": "database",
+            "references": [
+                {"source": "environment", "namespace": "shop", "kind": "Pod", "name": "worker", "detail": "DB_HOST"},
+                {"source": "external-name", "namespace": "shop", "kind": "Service", "name": "orders-db", "detail": null},
+            ],
+        }))
+        .unwrap();
+
+        let mut graph = ServiceGraph::new();
+        graph.add_externals(&[external], &[pod]);
+        // The service is not in the graph, so only the pod links to the host
+        assert_eq!(graph.graph.node_count(), 2);
+        assert_eq!(graph.graph.edge_count(), 1);
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"orders.abc.rds.amazonaws.com\\n(database)\", shape=box3d"));
+        assert!(dot.contains("label=\"depends on\""));
+    }
+
+    #[test]
+    fn test_workload_and_config_nodes() {
+        let pod = |name: &str, owner: Option<&str>, spec: serde_json::Value| -> Pod {
+            let owner_references: Vec<serde_json::Value> = owner
+                .map(|owner| serde_json::json!({"apiVersion": "apps/v1", "kind": "ReplicaSet", "name": owner, "uid": "rs", "controller": true}))
+                .into_iter()
+                .collect();
+            serde_json::from_value(serde_json::json!({
+                "metadata": {
+                    "name": name, "namespace": "shop",
+                    "labels": {"app": "web", "pod-template-hash": "5d9f"},
+                    "ownerReferences": owner_references,
+                },
+                "spec": spec,
+                "status": {"phase": "Running"},
+            }))
+            .unwrap()
+        };
+        let index = PodIndex::from_pods(&[
+            pod(
+                "web-5d9f-x2k4p",
+                Some("web-5d9f"),
+                serde_json::json!({"containers": [{"name": "web", "envFrom": [{"configMapRef": {"name": "web-config"}}]}]}),
+            ),
+            pod(
+                "debug",
+                None,
+                serde_json::json!({"containers": [{"name": "debug", "env": [{"name": "TOKEN", "valueFrom": {"secretKeyRef": {"name": "api-token", "key": "token"}}}]}]}),
+            ),
+        ]);
+        let template: PodTemplateSpec = serde_json::from_value(serde_json::json!({
+            "metadata": {"labels": {"app": "web"}},
+            "spec": {"containers": [{"name": "web", "envFrom": [{"configMapRef": {"name": "web-config"}}]}]},
+        }))
+        .unwrap();
+        let metadata = ObjectMeta {
+            name: Some("web".to_string()),
+            namespace: Some("shop".to_string()),
+            ..Default::default()
+        };
+        let workloads = [WorkloadRef::new("Deployment", &metadata, Some(&template))];
+        let service: ServiceInfo = serde_json::from_value(serde_json::json!({
+            "name": "web", "namespace": "shop", "ports": [], "cluster_ip": null,
+            "service_type": "ClusterIP", "selector": {"app": "web"},
+        }))
+        .unwrap();
+
+        let mut graph = ServiceGraph::new();
+        graph.add_service_node(&service, false);
+        graph.add_pod_node(&index.pods()[0].info);
+        graph.add_workloads(&workloads, &[service], &index);
+        graph.add_config(&workloads, &index);
+
+        let edge = |from: &str, to: &str| {
+            graph
+                .graph
+                .find_edge(graph.node_map[from], graph.node_map[to])
+                .map(|e| graph.graph[e].relationship.clone())
+        };
+        assert_eq!(
+            edge("service:shop:web", "deployment:shop:web"),
+            Some(EdgeType::Selects)
+        );
+        assert_eq!(
+            edge("deployment:shop:web", "pod:shop:web-5d9f-x2k4p"),
+            Some(EdgeType::Owns)
+        );
+        assert_eq!(
+            edge("deployment:shop:web", "configmap:shop:web-config"),
+            Some(EdgeType::Uses)
+        );
+        // The pod without a workload consumes its Secret directly
+        assert_eq!(
+            edge("pod:shop:debug", "secret:shop:api-token"),
+            Some(EdgeType::Uses)
+        );
+        assert_eq!(graph.graph.node_count(), 6);
+        assert_eq!(graph.graph.edge_count(), 4);
+    }
+
+    #[test]
+    fn test_release_nodes() {
+        let mut graph =
+            ServiceGraph::new().with_title("Helm release grafana (grafana-7.3.0, revision 3)");
+        assert!(graph.is_empty());
+        let workload = graph.add_object_node(
+            "deployment:monitoring:grafana".to_string(),
+            "monitoring",
+            "grafana",
+            NodeType::Workload {
+                kind: "Deployment".to_string(),
+            },
+        );
+        let config = graph.add_object_node(
+            "configmap:monitoring:grafana".to_string(),
+            "monitoring",
+            "grafana",
+            NodeType::ConfigMap,
+        );
+        let again = graph.add_object_node(
+            "configmap:monitoring:grafana".to_string(),
+            "monitoring",
+            "grafana",
+            NodeType::ConfigMap,
+        );
+        assert_eq!(config, again);
+        graph.add_edge(workload, config, EdgeType::Uses);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"Helm release grafana (grafana-7.3.0, revision 3)\";"));
+        assert!(dot.contains("label=\"deployment/grafana\\n(monitoring)\""));
+        assert!(dot.contains("label=\"cm/grafana\\n(monitoring)\", shape=note"));
+        assert!(dot.contains("[style=dashed, label=\"uses\"]"));
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with(
+            "---\ntitle: Helm release grafana (grafana-7.3.0, revision 3)\n---\ngraph TD\n"
+        ));
+        assert!(mermaid.contains("  n0[\"deployment/grafana<br/>(monitoring)\"]:::workload\n"));
+        assert!(mermaid.contains("  n1>\"cm/grafana<br/>(monitoring)\"]:::config\n"));
+        assert!(mermaid.contains("  n0 -.-|uses| n1\n"));
+    }
+
+    #[test]
+    fn test_network_policy_nodes() {
+        let pod = |name: &str, app: &str| -> PodInfo {
+            serde_json::from_value(serde_json::json!({
+                "name": name, "namespace": "shop", "phase": "Running", "labels": {"app": app},
+                "ready_containers": 1, "total_containers": 1, "restart_count": 0,
+                "age": "1d", "qos_class": "BestEffort",
+            }))
+            .unwrap()
+        };
+        let policy: NetworkPolicy = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "db-from-web", "namespace": "shop"},
+            "spec": {
+                "podSelector": {"matchLabels": {"app": "db"}},
+                "ingress": [{"from": [{"podSelector": {"matchLabels": {"app": "web"}}}]}],
+            },
+        }))
+        .unwrap();
+
+        let mut graph = ServiceGraph::new();
+        let pods = [
+            pod("web-0", "web"),
+            pod("db-0", "db"),
+            pod("cache-0", "cache"),
+        ];
+        graph.add_network_policies(&[policy], &pods, &BTreeMap::new());
+        // The cache pod is neither isolated nor allowed by the policy
+        assert_eq!(graph.graph.node_count(), 3);
+        assert_eq!(graph.graph.edge_count(), 2);
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"netpol/db-from-web\\n(shop)\", shape=octagon"));
+        assert!(dot.contains("label=\"isolates\""));
+        assert!(dot.contains("label=\"allows\""));
+    }
+
+    #[test]
+    fn test_limit_collapses_largest_namespace() {
+        let mut graph = ServiceGraph::new();
+        let mut node = |namespace: &str, name: &str, node_type: NodeType| {
+            let id = format!("{}:{}", namespace, name);
+            graph.add_object_node(id, namespace, name, node_type)
+        };
+        let web = node("shop", "web", NodeType::Service);
+        let pods: Vec<NodeIndex> = ["web-1", "web-2", "web-3"]
+            .iter()
+            .map(|name| node("shop", name, NodeType::Pod))
+            .collect();
+        let db = node("platform", "db", NodeType::Service);
+        let db_pod = node("platform", "db-0", NodeType::Pod);
+        let host = node("database", "db.example.com", NodeType::External);
+        for pod in &pods {
+            graph.add_edge(web, *pod, EdgeType::ServiceToPod);
+        }
+        graph.add_edge(db, db_pod, EdgeType::ServiceToPod);
+        graph.add_edge(pods[0], host, EdgeType::DependsOn);
+        graph.add_edge(pods[1], host, EdgeType::DependsOn);
+        graph.add_edge(web, db, EdgeType::DependsOn);
+
+        assert!(graph.limit(0, 0).is_empty());
+        assert!(graph.limit(7, 7).is_empty());
+        let collapsed = graph.limit(5, 0);
+        assert_eq!(
+            collapsed,
+            [CollapsedNamespace {
+                namespace: "shop".to_string(),
+                objects: 4,
+            }]
+        );
+        assert_eq!((graph.node_count(), graph.edge_count()), (4, 3));
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"4 objects\\n(shop)\", shape=folder"));
+        assert!(dot.contains("label=\"2 links\""));
+
+        // External hosts stay, so a graph can remain over the limit
+        let collapsed = graph.limit(1, 0);
+        assert_eq!(collapsed.len(), 1);
+        assert_eq!(graph.node_count(), 3);
+    }
+
     #[test]
     fn test_service_edge_creation() {
         let edge = ServiceEdge {
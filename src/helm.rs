@@ -0,0 +1,226 @@
+//! Helm releases and the objects belonging to them
+//!
+//! Helm 3 stores each revision of a release in a Secret named
+//! `sh.helm.release.v1.<release>.v<revision>`, labeled `owner=helm` and
+//! `name=<release>`. Its `release` key holds the release as base64-encoded,
+//! gzipped JSON, including the rendered manifest. The objects a release
+//! manages are the ones in the manifest of its latest revision, plus objects
+//! carrying the release in the `app.kubernetes.io/instance` label or the
+//! `meta.helm.sh/release-name` annotation (hooks and objects created by
+//! operators the chart installs).
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::{ExplorerError, Result};
+use base64::Engine;
+use flate2::read::GzDecoder;
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::ListParams;
+use kube::Api;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+
+const INSTANCE_LABEL: &str = "app.kubernetes.io/instance";
+const RELEASE_ANNOTATION: &str = "meta.helm.sh/release-name";
+
+/// The latest revision of a Helm release
+#[derive(Debug, Clone)]
+pub struct HelmRelease {
+    pub name: String,
+    pub namespace: String,
+    pub revision: i64,
+    pub status: String,
+    /// Chart name and version, e.g. `grafana-7.3.0`
+    pub chart: Option<String>,
+    /// Objects in the release manifest as (kind, namespace, name)
+    pub manifest: HashSet<(String, String, String)>,
+}
+
+impl HelmRelease {
+    /// Whether the object of `kind` with `metadata` belongs to the release
+    pub fn contains(&self, kind: &str, metadata: &ObjectMeta) -> bool {
+        let namespace = metadata.namespace.as_deref().unwrap_or(&self.namespace);
+        let name = metadata.name.clone().unwrap_or_default();
+        let key = (kind.to_string(), namespace.to_string(), name);
+        let label = |map: &Option<BTreeMap<String, String>>, key: &str| {
+            map.as_ref().and_then(|m| m.get(key)).map(String::as_str) == Some(&self.name)
+        };
+        self.manifest.contains(&key)
+            || (namespace == self.namespace
+                && (label(&metadata.labels, INSTANCE_LABEL)
+                    || label(&metadata.annotations, RELEASE_ANNOTATION)))
+    }
+}
+
+#[derive(Deserialize)]
+struct StoredRelease {
+    name: String,
+    namespace: String,
+    version: i64,
+    #[serde(default)]
+    info: Option<StoredInfo>,
+    #[serde(default)]
+    chart: Option<StoredChart>,
+    #[serde(default)]
+    manifest: String,
+}
+
+#[derive(Deserialize)]
+struct StoredInfo {
+    #[serde(default)]
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct StoredChart {
+    metadata: Option<StoredChartMetadata>,
+}
+
+#[derive(Deserialize)]
+struct StoredChartMetadata {
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+/// Decode the `release` key of a release Secret
+pub fn decode_release(data: &[u8]) -> Result<HelmRelease> {
+    let invalid =
+        |what: &str| ExplorerError::InvalidArgument(format!("invalid Helm release: {}", what));
+    let compressed = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|_| invalid("not base64"))?;
+    // Releases stored by Helm are gzipped; accept plain JSON too
+    let json = if compressed.starts_with(&[0x1f, 0x8b]) {
+        let mut json = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut json)
+            .map_err(|_| invalid("not gzip"))?;
+        json
+    } else {
+        compressed
+    };
+    let stored: StoredRelease = serde_json::from_slice(&json).map_err(|_| invalid("not JSON"))?;
+
+    Ok(HelmRelease {
+        manifest: manifest_objects(&stored.manifest, &stored.namespace),
+        chart: stored
+            .chart
+            .and_then(|c| c.metadata)
+            .map(|m| format!("{}-{}", m.name, m.version)),
+        status: stored.info.map(|i| i.status).unwrap_or_default(),
+        name: stored.name,
+        namespace: stored.namespace,
+        revision: stored.version,
+    })
+}
+
+/// (kind, namespace, name) of every object in a rendered manifest. Objects
+/// without a namespace are placed in the release namespace.
+pub fn manifest_objects(manifest: &str, namespace: &str) -> HashSet<(String, String, String)> {
+    serde_yaml::Deserializer::from_str(manifest)
+        .filter_map(|document| serde_yaml::Value::deserialize(document).ok())
+        .filter_map(|object| {
+            let kind = object.get("kind")?.as_str()?.to_string();
+            let metadata = object.get("metadata")?;
+            let name = metadata.get("name")?.as_str()?.to_string();
+            let namespace = metadata
+                .get("namespace")
+                .and_then(|n| n.as_str())
+                .unwrap_or(namespace)
+                .to_string();
+            Some((kind, namespace, name))
+        })
+        .collect()
+}
+
+/// Latest revision of release `name` in `namespace`, read from its release
+/// Secrets. Without permission to read Secrets, the release is known by its
+/// labels and annotations alone.
+pub async fn find_release(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    name: &str,
+) -> Result<HelmRelease> {
+    let secrets: Api<Secret> = Api::namespaced(discovery.client().clone(), namespace);
+    let selector = format!("owner=helm,name={}", name);
+    let secrets = match secrets.list(&ListParams::default().labels(&selector)).await {
+        Ok(list) => list.items,
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let latest = secrets
+        .iter()
+        .filter_map(|s| s.data.as_ref()?.get("release"))
+        .filter_map(|data| decode_release(&data.0).ok())
+        .max_by_key(|r| r.revision);
+    Ok(latest.unwrap_or_else(|| HelmRelease {
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        revision: 0,
+        status: "unknown".to_string(),
+        chart: None,
+        manifest: HashSet::new(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn test_decode_release() {
+        let release = serde_json::json!({
+            "name": "grafana",
+            "namespace": "monitoring",
+            "version": 3,
+            "info": {"status": "deployed"},
+            "chart": {"metadata": {"name": "grafana", "version": "7.3.0"}},
+            "manifest": "---\n# Source: grafana/templates/service.yaml\napiVersion: v1\nkind: Service\nmetadata:\n  name: grafana\n---\napiVersion: rbac.authorization.k8s.io/v1\nkind: ClusterRole\nmetadata:\n  name: grafana-clusterrole\n---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: grafana-dashboards\n  namespace: dashboards\n",
+        });
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(release.to_string().as_bytes()).unwrap();
+        let data = base64::engine::general_purpose::STANDARD.encode(gzip.finish().unwrap());
+
+        let release = decode_release(data.as_bytes()).unwrap();
+        assert_eq!(release.revision, 3);
+        assert_eq!(release.status, "deployed");
+        assert_eq!(release.chart.as_deref(), Some("grafana-7.3.0"));
+        assert_eq!(release.manifest.len(), 3);
+
+        let metadata = |namespace: &str, name: &str, labels: &[(&str, &str)]| ObjectMeta {
+            name: Some(name.to_string()),
+            namespace: Some(namespace.to_string()),
+            labels: Some(
+                labels
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        assert!(release.contains("Service", &metadata("monitoring", "grafana", &[])));
+        assert!(release.contains(
+            "ConfigMap",
+            &metadata("dashboards", "grafana-dashboards", &[])
+        ));
+        assert!(release.contains(
+            "Secret",
+            &metadata(
+                "monitoring",
+                "grafana-admin",
+                &[(INSTANCE_LABEL, "grafana")]
+            )
+        ));
+        assert!(!release.contains(
+            "Secret",
+            &metadata("other", "grafana-admin", &[(INSTANCE_LABEL, "grafana")])
+        ));
+        assert!(!release.contains("Service", &metadata("monitoring", "loki", &[])));
+    }
+}
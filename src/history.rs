@@ -0,0 +1,385 @@
+//! Recorded cluster snapshots for answering queries about the past
+//!
+//! `kdx history record` stores the converted services, pods, workloads and
+//! configuration of the whole cluster under the cache directory, one JSON
+//! file per snapshot and context. With `--as-of`, list, describe and graph commands are answered
+//! from the snapshot closest to the requested time instead of the live cluster.
+
+use crate::cache::cache_dir;
+use crate::cli::CompressFormat;
+use crate::compress;
+use crate::discovery::{
+    ConfigMapInfo, DaemonSetInfo, DeploymentInfo, DiscoveryEngine, PodInfo, SecretInfo,
+    ServiceInfo, StatefulSetInfo,
+};
+use crate::error::{ExplorerError, Result};
+use crate::filtering::LabelSelector;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use k8s_openapi::api::core::v1::Service;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// File name format of a snapshot, sortable by time
+const SNAPSHOT_FILE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// State of the cluster at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub context: Option<String>,
+    pub namespaces: Vec<String>,
+    pub services: Vec<ServiceInfo>,
+    /// Service labels keyed by `namespace/name`, for label selectors
+    #[serde(default)]
+    pub service_labels: BTreeMap<String, BTreeMap<String, String>>,
+    pub pods: Vec<PodInfo>,
+    pub deployments: Vec<DeploymentInfo>,
+    pub statefulsets: Vec<StatefulSetInfo>,
+    pub daemonsets: Vec<DaemonSetInfo>,
+    /// Absent from snapshots recorded before they were included
+    #[serde(default)]
+    pub configmaps: Vec<ConfigMapInfo>,
+    #[serde(default)]
+    pub secrets: Vec<SecretInfo>,
+}
+
+impl ClusterSnapshot {
+    pub fn services(
+        &self,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+    ) -> Result<Vec<ServiceInfo>> {
+        let selector = parse_selector(selector)?;
+        let no_labels = BTreeMap::new();
+
+        Ok(self
+            .services
+            .iter()
+            .filter(|s| in_namespace(&s.namespace, namespace))
+            .filter(|s| {
+                let key = format!("{}/{}", s.namespace, s.name);
+                let labels = self.service_labels.get(&key).unwrap_or(&no_labels);
+                selector.as_ref().is_none_or(|sel| sel.matches(labels))
+            })
+            .cloned()
+            .collect())
+    }
+
+    pub fn pods(&self, namespace: Option<&str>, selector: Option<&str>) -> Result<Vec<PodInfo>> {
+        let selector = parse_selector(selector)?;
+
+        Ok(self
+            .pods
+            .iter()
+            .filter(|p| in_namespace(&p.namespace, namespace))
+            .filter(|p| selector.as_ref().is_none_or(|sel| sel.matches(&p.labels)))
+            .cloned()
+            .collect())
+    }
+
+    pub fn deployments(&self, namespace: Option<&str>) -> Vec<DeploymentInfo> {
+        self.deployments
+            .iter()
+            .filter(|d| in_namespace(&d.namespace, namespace))
+            .cloned()
+            .collect()
+    }
+
+    pub fn statefulsets(&self, namespace: Option<&str>) -> Vec<StatefulSetInfo> {
+        self.statefulsets
+            .iter()
+            .filter(|s| in_namespace(&s.namespace, namespace))
+            .cloned()
+            .collect()
+    }
+
+    pub fn daemonsets(&self, namespace: Option<&str>) -> Vec<DaemonSetInfo> {
+        self.daemonsets
+            .iter()
+            .filter(|d| in_namespace(&d.namespace, namespace))
+            .cloned()
+            .collect()
+    }
+
+    pub fn configmaps(&self, namespace: Option<&str>) -> Vec<ConfigMapInfo> {
+        self.configmaps
+            .iter()
+            .filter(|c| in_namespace(&c.namespace, namespace))
+            .cloned()
+            .collect()
+    }
+
+    pub fn secrets(&self, namespace: Option<&str>) -> Vec<SecretInfo> {
+        self.secrets
+            .iter()
+            .filter(|s| in_namespace(&s.namespace, namespace))
+            .cloned()
+            .collect()
+    }
+}
+
+fn in_namespace(resource_namespace: &str, namespace: Option<&str>) -> bool {
+    namespace.is_none_or(|ns| ns == resource_namespace)
+}
+
+fn parse_selector(selector: Option<&str>) -> Result<Option<LabelSelector>> {
+    selector
+        .map(LabelSelector::parse)
+        .transpose()
+        .map_err(ExplorerError::InvalidArgument)
+}
+
+/// Capture the current state of the cluster
+pub async fn take_snapshot(
+    discovery: &DiscoveryEngine,
+    context: Option<&str>,
+) -> Result<ClusterSnapshot> {
+    let taken_at = Utc::now();
+
+    let services: Api<Service> = Api::all(discovery.client().clone());
+    let service_labels = services
+        .list_metadata(&Default::default())
+        .await?
+        .items
+        .into_iter()
+        .map(|s| {
+            let key = format!(
+                "{}/{}",
+                s.metadata.namespace.unwrap_or_default(),
+                s.metadata.name.unwrap_or_default()
+            );
+            (key, s.metadata.labels.unwrap_or_default())
+        })
+        .collect();
+
+    Ok(ClusterSnapshot {
+        taken_at,
+        context: context.map(String::from),
+        namespaces: discovery.get_all_namespaces().await?,
+        services: discovery.list_services(None).await?,
+        service_labels,
+        pods: discovery.list_pods(None, None).await?,
+        deployments: discovery.list_deployments(None).await?,
+        statefulsets: discovery.list_statefulsets(None).await?,
+        daemonsets: discovery.list_daemonsets(None).await?,
+        configmaps: discovery.list_configmaps(None).await?,
+        secrets: discovery.list_secrets(None).await?,
+    })
+}
+
+/// Snapshots of one kubeconfig context, stored as JSON files
+pub struct HistoryStore {
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    /// Store for `context` (or the current context) in the cache directory
+    pub fn open(context: Option<&str>) -> Result<Self> {
+        let dir = cache_dir().ok_or_else(|| {
+            ExplorerError::InvalidArgument(
+                "no cache directory; set KDX_CACHE_DIR to record history".to_string(),
+            )
+        })?;
+        let context: String = context
+            .unwrap_or("current")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            dir: dir.join("history").join(context),
+        })
+    }
+
+    /// Write a snapshot, optionally compressed, and return its path
+    pub fn save(
+        &self,
+        snapshot: &ClusterSnapshot,
+        compression: Option<CompressFormat>,
+    ) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let mut name = format!("{}.json", snapshot.taken_at.format(SNAPSHOT_FILE_FORMAT));
+        let mut data = serde_json::to_vec(snapshot)?;
+        if let Some(format) = compression {
+            name = format!("{}.{}", name, compress::extension(format));
+            data = compress::compress(format, &data)?;
+        }
+
+        let path = self.dir.join(name);
+        fs::write(&path, data)?;
+        Ok(path)
+    }
+
+    /// Times of all stored snapshots, oldest first
+    pub fn list(&self) -> Result<Vec<DateTime<Utc>>> {
+        Ok(self.entries()?.into_iter().map(|(time, _)| time).collect())
+    }
+
+    /// Stored snapshot files with their times, oldest first
+    fn entries(&self) -> Result<Vec<(DateTime<Utc>, PathBuf)>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut snapshots: Vec<(DateTime<Utc>, PathBuf)> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_name()?.to_str()?;
+                let stem = name
+                    .strip_suffix(".json")
+                    .or_else(|| name.strip_suffix(".json.gz"))
+                    .or_else(|| name.strip_suffix(".json.zst"))?;
+                let time = NaiveDateTime::parse_from_str(stem, SNAPSHOT_FILE_FORMAT).ok()?;
+                Some((time.and_utc(), path))
+            })
+            .collect();
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
+    /// Load the snapshot taken closest to `as_of`, decompressing it if needed
+    pub fn load_nearest(&self, as_of: DateTime<Utc>) -> Result<ClusterSnapshot> {
+        let entries = self.entries()?;
+        let times: Vec<DateTime<Utc>> = entries.iter().map(|(time, _)| *time).collect();
+        let (_, path) = nearest(&times, as_of)
+            .and_then(|taken_at| entries.iter().find(|(time, _)| *time == taken_at))
+            .ok_or_else(|| {
+                ExplorerError::InvalidArgument(
+                    "no recorded snapshots; run `kdx history record` first".to_string(),
+                )
+            })?;
+        let data = compress::decompress(fs::read(path)?)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Load every snapshot taken between `from` and `to`, oldest first
+    pub fn load_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ClusterSnapshot>> {
+        self.entries()?
+            .into_iter()
+            .filter(|(time, _)| (from..=to).contains(time))
+            .map(|(_, path)| {
+                let data = compress::decompress(fs::read(path)?)?;
+                Ok(serde_json::from_slice(&data)?)
+            })
+            .collect()
+    }
+}
+
+/// The time in `times` closest to `as_of`; ties go to the earlier snapshot
+pub fn nearest(times: &[DateTime<Utc>], as_of: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    times
+        .iter()
+        .min_by_key(|t| ((**t - as_of).abs(), **t > as_of))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn pod(name: &str, namespace: &str, app: &str) -> PodInfo {
+        PodInfo {
+            labels: BTreeMap::from([("app".to_string(), app.to_string())]),
+            age: "1h".to_string(),
+            ..PodInfo::fixture(namespace, name)
+        }
+    }
+
+    #[test]
+    fn test_nearest_snapshot() {
+        let at = |h| Utc.with_ymd_and_hms(2024, 5, 1, h, 0, 0).unwrap();
+        let times = vec![at(8), at(12), at(16)];
+
+        assert_eq!(nearest(&times, at(13)), Some(at(12)));
+        assert_eq!(nearest(&times, at(15)), Some(at(16)));
+        // Equally close: prefer the state before the requested time
+        assert_eq!(nearest(&times, at(14)), Some(at(12)));
+        assert_eq!(nearest(&times, at(1)), Some(at(8)));
+        assert_eq!(nearest(&[], at(1)), None);
+    }
+
+    #[test]
+    fn test_snapshot_queries() {
+        let snapshot = ClusterSnapshot {
+            taken_at: Utc::now(),
+            context: None,
+            namespaces: vec!["shop".to_string(), "ops".to_string()],
+            services: Vec::new(),
+            service_labels: BTreeMap::new(),
+            pods: vec![
+                pod("web-1", "shop", "web"),
+                pod("db-0", "shop", "db"),
+                pod("agent-x", "ops", "agent"),
+            ],
+            deployments: Vec::new(),
+            statefulsets: Vec::new(),
+            daemonsets: Vec::new(),
+            configmaps: Vec::new(),
+            secrets: Vec::new(),
+        };
+
+        assert_eq!(snapshot.pods(None, None).unwrap().len(), 3);
+        assert_eq!(snapshot.pods(Some("shop"), None).unwrap().len(), 2);
+        let web = snapshot.pods(Some("shop"), Some("app=web")).unwrap();
+        assert_eq!(web.len(), 1);
+        assert_eq!(web[0].name, "web-1");
+        assert!(snapshot.pods(None, Some("app in (")).is_err());
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kdx-history-{}", std::process::id()));
+        let store = HistoryStore { dir: dir.clone() };
+        assert!(store.list().unwrap().is_empty());
+
+        let taken_at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let snapshot = ClusterSnapshot {
+            taken_at,
+            context: Some("prod".to_string()),
+            namespaces: vec!["default".to_string()],
+            services: Vec::new(),
+            service_labels: BTreeMap::new(),
+            pods: vec![pod("web-1", "default", "web")],
+            deployments: Vec::new(),
+            statefulsets: Vec::new(),
+            daemonsets: Vec::new(),
+            configmaps: Vec::new(),
+            secrets: Vec::new(),
+        };
+        store.save(&snapshot, None).unwrap();
+
+        let later = ClusterSnapshot {
+            taken_at: taken_at + chrono::Duration::hours(1),
+            pods: Vec::new(),
+            ..snapshot.clone()
+        };
+        let path = store.save(&later, Some(CompressFormat::Zstd)).unwrap();
+        assert!(path.to_string_lossy().ends_with(".json.zst"));
+
+        assert_eq!(store.list().unwrap(), vec![taken_at, later.taken_at]);
+        let loaded = store.load_nearest(taken_at).unwrap();
+        assert_eq!(loaded.pods.len(), 1);
+        assert_eq!(loaded.context.as_deref(), Some("prod"));
+        // Compressed snapshots are decompressed transparently
+        let loaded = store.load_nearest(Utc::now()).unwrap();
+        assert!(loaded.pods.is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
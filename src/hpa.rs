@@ -0,0 +1,327 @@
+//! HorizontalPodAutoscalers and the workloads they scale
+//!
+//! An autoscaler names its scale target by kind and name only, so nothing
+//! stops it from pointing at a workload that was renamed or deleted. Targets
+//! of kind Deployment and StatefulSet are looked up to tell the two apart;
+//! other kinds (custom resources with a scale subresource) are not checked.
+
+use crate::age::format_age;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
+use k8s_openapi::api::autoscaling::v2::{
+    HorizontalPodAutoscaler, MetricSpec, MetricStatus, MetricTarget, MetricValueStatus,
+};
+use kube::api::ListParams;
+use kube::{Api, Client, Resource};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// One metric an autoscaler scales on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HpaMetric {
+    /// Resource name (`cpu`) or metric name, with the container for
+    /// container resource metrics
+    pub name: String,
+    /// Last observed value, if the autoscaler has reported one
+    pub current: Option<String>,
+    pub target: String,
+}
+
+impl std::fmt::Display for HpaMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let current = self.current.as_deref().unwrap_or("<unknown>");
+        write!(f, "{}: {}/{}", self.name, current, self.target)
+    }
+}
+
+/// An autoscaler with its scale target and metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HpaInfo {
+    pub name: String,
+    pub namespace: String,
+    pub target_kind: String,
+    pub target_name: String,
+    /// Whether the scale target exists; `None` when it was not checked
+    pub target_found: Option<bool>,
+    pub min_replicas: i32,
+    pub max_replicas: i32,
+    pub current_replicas: Option<i32>,
+    pub desired_replicas: Option<i32>,
+    pub metrics: Vec<HpaMetric>,
+    pub age: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl HpaInfo {
+    /// The scale target as Kind/name
+    pub fn target(&self) -> String {
+        format!("{}/{}", self.target_kind, self.target_name)
+    }
+
+    /// Whether the autoscaler scales the workload of `kind` named `name`
+    pub fn scales(&self, kind: &str, name: &str) -> bool {
+        self.target_kind == kind && self.target_name == name
+    }
+
+    /// Whether the autoscaler cannot add replicas any more
+    pub fn at_max(&self) -> bool {
+        self.current_replicas
+            .is_some_and(|replicas| replicas >= self.max_replicas)
+    }
+}
+
+fn format_target(target: &MetricTarget) -> String {
+    if let Some(utilization) = target.average_utilization {
+        format!("{}%", utilization)
+    } else if let Some(value) = &target.average_value {
+        value.0.clone()
+    } else if let Some(value) = &target.value {
+        value.0.clone()
+    } else {
+        "<unset>".to_string()
+    }
+}
+
+fn format_current(current: &MetricValueStatus) -> Option<String> {
+    current
+        .average_utilization
+        .map(|utilization| format!("{}%", utilization))
+        .or_else(|| current.average_value.as_ref().map(|v| v.0.clone()))
+        .or_else(|| current.value.as_ref().map(|v| v.0.clone()))
+}
+
+/// Name and target of a metric in the spec
+fn spec_metric(metric: &MetricSpec) -> Option<(String, &MetricTarget)> {
+    match metric.type_.as_str() {
+        "Resource" => metric
+            .resource
+            .as_ref()
+            .map(|r| (r.name.clone(), &r.target)),
+        "ContainerResource" => metric
+            .container_resource
+            .as_ref()
+            .map(|r| (format!("{} ({})", r.name, r.container), &r.target)),
+        "Pods" => metric
+            .pods
+            .as_ref()
+            .map(|p| (p.metric.name.clone(), &p.target)),
+        "Object" => metric.object.as_ref().map(|o| {
+            let object = format!("{}/{}", o.described_object.kind, o.described_object.name);
+            (format!("{} on {}", o.metric.name, object), &o.target)
+        }),
+        "External" => metric
+            .external
+            .as_ref()
+            .map(|e| (e.metric.name.clone(), &e.target)),
+        _ => None,
+    }
+}
+
+/// Name and last observed value of a metric in the status
+fn status_metric(metric: &MetricStatus) -> Option<(String, Option<String>)> {
+    match metric.type_.as_str() {
+        "Resource" => metric
+            .resource
+            .as_ref()
+            .map(|r| (r.name.clone(), format_current(&r.current))),
+        "ContainerResource" => metric.container_resource.as_ref().map(|r| {
+            (
+                format!("{} ({})", r.name, r.container),
+                format_current(&r.current),
+            )
+        }),
+        "Pods" => metric
+            .pods
+            .as_ref()
+            .map(|p| (p.metric.name.clone(), format_current(&p.current))),
+        "Object" => metric.object.as_ref().map(|o| {
+            let object = format!("{}/{}", o.described_object.kind, o.described_object.name);
+            (
+                format!("{} on {}", o.metric.name, object),
+                format_current(&o.current),
+            )
+        }),
+        "External" => metric
+            .external
+            .as_ref()
+            .map(|e| (e.metric.name.clone(), format_current(&e.current))),
+        _ => None,
+    }
+}
+
+/// Summarize `hpa`, pairing each metric target with its last observed value
+pub fn hpa_info(hpa: &HorizontalPodAutoscaler) -> Option<HpaInfo> {
+    let spec = hpa.spec.as_ref()?;
+    let status = hpa.status.as_ref();
+    let current: Vec<(String, Option<String>)> = status
+        .and_then(|s| s.current_metrics.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(status_metric)
+        .collect();
+    let metrics = spec
+        .metrics
+        .iter()
+        .flatten()
+        .filter_map(spec_metric)
+        .map(|(name, target)| HpaMetric {
+            current: current
+                .iter()
+                .find(|(observed, _)| *observed == name)
+                .and_then(|(_, value)| value.clone()),
+            target: format_target(target),
+            name,
+        })
+        .collect();
+    let created_at = hpa.metadata.creation_timestamp.as_ref().map(|t| t.0);
+
+    Some(HpaInfo {
+        name: hpa.metadata.name.clone()?,
+        namespace: hpa.metadata.namespace.clone().unwrap_or_default(),
+        target_kind: spec.scale_target_ref.kind.clone(),
+        target_name: spec.scale_target_ref.name.clone(),
+        target_found: None,
+        // The API server defaults minReplicas to 1
+        min_replicas: spec.min_replicas.unwrap_or(1),
+        max_replicas: spec.max_replicas,
+        current_replicas: status.and_then(|s| s.current_replicas),
+        desired_replicas: status.map(|s| s.desired_replicas),
+        metrics,
+        age: format_age(created_at),
+        created_at,
+    })
+}
+
+/// Mark whether each autoscaler's target exists, given the (namespace, name)
+/// of the deployments and statefulsets that could be listed
+pub fn resolve_targets(
+    hpas: &mut [HpaInfo],
+    deployments: Option<&HashSet<(String, String)>>,
+    statefulsets: Option<&HashSet<(String, String)>>,
+) {
+    for hpa in hpas {
+        let workloads = match hpa.target_kind.as_str() {
+            "Deployment" => deployments,
+            "StatefulSet" => statefulsets,
+            _ => None,
+        };
+        hpa.target_found =
+            workloads.map(|w| w.contains(&(hpa.namespace.clone(), hpa.target_name.clone())));
+    }
+}
+
+/// Autoscalers in `namespace` (or all namespaces if None), without their
+/// targets resolved
+pub async fn list_autoscalers(client: &Client, namespace: Option<&str>) -> Result<Vec<HpaInfo>> {
+    let api: Api<HorizontalPodAutoscaler> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    let mut hpas: Vec<HpaInfo> = api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .iter()
+        .filter_map(hpa_info)
+        .collect();
+    hpas.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+    Ok(hpas)
+}
+
+/// (namespace, name) of every `K`, or `None` if they may not be listed
+async fn workload_names<K>(
+    client: &Client,
+    namespace: Option<&str>,
+) -> Result<Option<HashSet<(String, String)>>>
+where
+    K: Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + std::fmt::Debug,
+    <K as Resource>::DynamicType: Default,
+{
+    let api: Api<K> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    match api.list_metadata(&ListParams::default()).await {
+        Ok(list) => Ok(Some(
+            list.items
+                .into_iter()
+                .filter_map(|w| Some((w.metadata.namespace?, w.metadata.name?)))
+                .collect(),
+        )),
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Autoscalers in `namespace` (or all namespaces if None) with their
+/// Deployment and StatefulSet targets resolved
+pub async fn find_autoscalers(client: &Client, namespace: Option<&str>) -> Result<Vec<HpaInfo>> {
+    let (mut hpas, deployments, statefulsets) = tokio::try_join!(
+        list_autoscalers(client, namespace),
+        workload_names::<Deployment>(client, namespace),
+        workload_names::<StatefulSet>(client, namespace),
+    )?;
+    resolve_targets(&mut hpas, deployments.as_ref(), statefulsets.as_ref());
+    Ok(hpas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hpa_info_and_targets() {
+        let hpa: HorizontalPodAutoscaler = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "web", "namespace": "shop"},
+            "spec": {
+                "scaleTargetRef": {"apiVersion": "apps/v1", "kind": "Deployment", "name": "web"},
+                "maxReplicas": 10,
+                "metrics": [
+                    {"type": "Resource", "resource": {
+                        "name": "cpu",
+                        "target": {"type": "Utilization", "averageUtilization": 80},
+                    }},
+                    {"type": "External", "external": {
+                        "metric": {"name": "queue_depth"},
+                        "target": {"type": "AverageValue", "averageValue": "30"},
+                    }},
+                ],
+            },
+            "status": {
+                "currentReplicas": 10,
+                "desiredReplicas": 10,
+                "currentMetrics": [{"type": "Resource", "resource": {
+                    "name": "cpu",
+                    "current": {"averageUtilization": 95, "averageValue": "950m"},
+                }}],
+            },
+        }))
+        .unwrap();
+
+        let info = hpa_info(&hpa).unwrap();
+        assert_eq!(info.min_replicas, 1);
+        assert!(info.at_max());
+        assert!(info.scales("Deployment", "web"));
+        let metrics: Vec<String> = info.metrics.iter().map(|m| m.to_string()).collect();
+        assert_eq!(metrics, ["cpu: 95%/80%", "queue_depth: <unknown>/30"]);
+
+        let orphan = HpaInfo {
+            name: "api".to_string(),
+            target_name: "api".to_string(),
+            ..info.clone()
+        };
+        let custom = HpaInfo {
+            target_kind: "Rollout".to_string(),
+            ..info.clone()
+        };
+        let mut hpas = [info, orphan, custom];
+        let deployments = HashSet::from([("shop".to_string(), "web".to_string())]);
+        resolve_targets(&mut hpas, Some(&deployments), None);
+        let found: Vec<Option<bool>> = hpas.iter().map(|h| h.target_found).collect();
+        assert_eq!(found, [Some(true), Some(false), None]);
+    }
+}
@@ -0,0 +1,385 @@
+//! Ingress host and path collision checks
+//!
+//! Controllers resolve Ingresses that claim the same host and path in
+//! different ways: some merge them, some take the oldest, some the first
+//! in name order. Either way one backend silently loses. This module finds
+//! such collisions, wildcard hosts shadowed by specific hosts on the same
+//! path, and TLS sections naming secrets that do not exist.
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Annotation naming the ingress class before `spec.ingressClassName`
+const CLASS_ANNOTATION: &str = "kubernetes.io/ingress.class";
+
+/// Kind of ingress problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IngressIssueKind {
+    /// Several Ingresses route the same host and path to different backends
+    DuplicateRoute,
+    /// A wildcard host and a specific host it covers route the same path to
+    /// different backends; the specific host wins
+    WildcardOverlap,
+    /// A TLS section names a secret missing from the Ingress namespace
+    MissingTlsSecret,
+}
+
+impl std::fmt::Display for IngressIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IngressIssueKind::DuplicateRoute => write!(f, "duplicate route"),
+            IngressIssueKind::WildcardOverlap => write!(f, "wildcard overlap"),
+            IngressIssueKind::MissingTlsSecret => write!(f, "missing TLS secret"),
+        }
+    }
+}
+
+/// One host and path of an Ingress with the backend it routes to
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct IngressRoute {
+    pub namespace: String,
+    pub ingress: String,
+    /// Ingress class, from `spec.ingressClassName` or the legacy annotation
+    pub class: Option<String>,
+    /// Host, or `*` for rules without a host
+    pub host: String,
+    pub path: String,
+    /// `service:port`, or the default backend / resource backend
+    pub backend: String,
+}
+
+/// A problem and the routes involved in it, side by side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngressIssue {
+    pub kind: IngressIssueKind,
+    pub host: String,
+    pub path: Option<String>,
+    /// The conflicting routes, or the route of the Ingress missing a secret
+    pub routes: Vec<IngressRoute>,
+    /// Missing secret name, for missing TLS secrets
+    pub secret: Option<String>,
+}
+
+/// Every host and path routed by `ingress`
+pub fn ingress_routes(ingress: &Ingress) -> Vec<IngressRoute> {
+    let namespace = ingress.metadata.namespace.clone().unwrap_or_default();
+    let name = ingress.metadata.name.clone().unwrap_or_default();
+    let class = ingress_class(ingress);
+    let Some(spec) = &ingress.spec else {
+        return Vec::new();
+    };
+
+    let route = |host: &str, path: &str, backend: String| IngressRoute {
+        namespace: namespace.clone(),
+        ingress: name.clone(),
+        class: class.clone(),
+        host: host.to_lowercase(),
+        path: path.to_string(),
+        backend,
+    };
+
+    let mut routes = Vec::new();
+    for rule in spec.rules.iter().flatten() {
+        let host = rule.host.as_deref().unwrap_or("*");
+        for path in rule.http.iter().flat_map(|http| &http.paths) {
+            routes.push(route(
+                host,
+                path.path.as_deref().unwrap_or("/"),
+                describe_backend(&path.backend),
+            ));
+        }
+    }
+    if let Some(backend) = &spec.default_backend {
+        routes.push(route("*", "/", describe_backend(backend)));
+    }
+    routes
+}
+
+fn ingress_class(ingress: &Ingress) -> Option<String> {
+    ingress
+        .spec
+        .as_ref()
+        .and_then(|s| s.ingress_class_name.clone())
+        .or_else(|| {
+            ingress
+                .metadata
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get(CLASS_ANNOTATION).cloned())
+        })
+}
+
+fn describe_backend(backend: &k8s_openapi::api::networking::v1::IngressBackend) -> String {
+    if let Some(service) = &backend.service {
+        let port = service
+            .port
+            .as_ref()
+            .and_then(|p| p.number.map(|n| n.to_string()).or_else(|| p.name.clone()));
+        return match port {
+            Some(port) => format!("{}:{}", service.name, port),
+            None => service.name.clone(),
+        };
+    }
+    match &backend.resource {
+        Some(resource) => format!("{}/{}", resource.kind, resource.name),
+        None => "-".to_string(),
+    }
+}
+
+/// Whether wildcard `pattern` (e.g. `*.example.com`) covers `host`. As in
+/// the Ingress spec, the wildcard matches exactly one DNS label.
+pub fn wildcard_covers(pattern: &str, host: &str) -> bool {
+    let Some(suffix) = pattern.strip_prefix("*.") else {
+        return false;
+    };
+    host.strip_suffix(suffix)
+        .and_then(|label| label.strip_suffix('.'))
+        .is_some_and(|label| !label.is_empty() && !label.contains('.') && label != "*")
+}
+
+/// Routes claiming the same host and path within one ingress class, with
+/// more than one backend across different Ingresses
+fn duplicate_routes(routes: &[IngressRoute]) -> Vec<IngressIssue> {
+    let mut by_route: BTreeMap<(&Option<String>, &str, &str), Vec<&IngressRoute>> = BTreeMap::new();
+    for route in routes {
+        by_route
+            .entry((&route.class, &route.host, &route.path))
+            .or_default()
+            .push(route);
+    }
+
+    by_route
+        .into_iter()
+        .filter(|(_, claims)| {
+            let ingresses: HashSet<_> = claims.iter().map(|r| (&r.namespace, &r.ingress)).collect();
+            let backends: HashSet<_> = claims.iter().map(|r| (&r.namespace, &r.backend)).collect();
+            ingresses.len() > 1 && backends.len() > 1
+        })
+        .map(|((_, host, path), claims)| IngressIssue {
+            kind: IngressIssueKind::DuplicateRoute,
+            host: host.to_string(),
+            path: Some(path.to_string()),
+            routes: claims.into_iter().cloned().collect(),
+            secret: None,
+        })
+        .collect()
+}
+
+/// Specific hosts shadowing a wildcard host of another Ingress on the same
+/// path with a different backend
+fn wildcard_overlaps(routes: &[IngressRoute]) -> Vec<IngressIssue> {
+    let mut issues = Vec::new();
+    for wildcard in routes.iter().filter(|r| r.host.starts_with("*.")) {
+        let shadowing: Vec<&IngressRoute> = routes
+            .iter()
+            .filter(|r| {
+                r.class == wildcard.class
+                    && r.path == wildcard.path
+                    && wildcard_covers(&wildcard.host, &r.host)
+                    && (r.namespace != wildcard.namespace || r.ingress != wildcard.ingress)
+                    && (r.namespace != wildcard.namespace || r.backend != wildcard.backend)
+            })
+            .collect();
+        if shadowing.is_empty() {
+            continue;
+        }
+
+        issues.push(IngressIssue {
+            kind: IngressIssueKind::WildcardOverlap,
+            host: wildcard.host.clone(),
+            path: Some(wildcard.path.clone()),
+            routes: std::iter::once(wildcard)
+                .chain(shadowing)
+                .cloned()
+                .collect(),
+            secret: None,
+        });
+    }
+    issues
+}
+
+/// TLS sections naming secrets not in `secrets` (namespace and name pairs)
+fn missing_tls_secrets(
+    ingresses: &[Ingress],
+    secrets: &HashSet<(String, String)>,
+) -> Vec<IngressIssue> {
+    let mut issues = Vec::new();
+    for ingress in ingresses {
+        let namespace = ingress.metadata.namespace.clone().unwrap_or_default();
+        let routes = ingress_routes(ingress);
+        let tls = ingress.spec.iter().flat_map(|s| s.tls.iter().flatten());
+        for section in tls {
+            let Some(secret) = &section.secret_name else {
+                continue;
+            };
+            if secrets.contains(&(namespace.clone(), secret.clone())) {
+                continue;
+            }
+            let hosts: Vec<String> = section.hosts.clone().unwrap_or_default();
+            issues.push(IngressIssue {
+                kind: IngressIssueKind::MissingTlsSecret,
+                host: if hosts.is_empty() {
+                    "*".to_string()
+                } else {
+                    hosts.join(", ")
+                },
+                path: None,
+                routes: routes
+                    .iter()
+                    .filter(|r| hosts.is_empty() || hosts.contains(&r.host))
+                    .cloned()
+                    .collect(),
+                secret: Some(secret.clone()),
+            });
+        }
+    }
+    issues
+}
+
+/// Check `ingresses` for duplicate routes and wildcard overlaps, and for
+/// missing TLS secrets when the existing `secrets` are known
+pub fn check_ingresses(
+    ingresses: &[Ingress],
+    secrets: Option<&HashSet<(String, String)>>,
+) -> Vec<IngressIssue> {
+    let routes: Vec<IngressRoute> = ingresses.iter().flat_map(ingress_routes).collect();
+    let mut issues = duplicate_routes(&routes);
+    issues.extend(wildcard_overlaps(&routes));
+    if let Some(secrets) = secrets {
+        issues.extend(missing_tls_secrets(ingresses, secrets));
+    }
+    issues
+}
+
+/// List the Ingresses in `namespace` (all namespaces when `None`) and check
+/// them. TLS secrets are only checked when secrets may be listed.
+pub async fn find_ingress_issues(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<IngressIssue>> {
+    let client = discovery.client().clone();
+    let (ingresses, secrets): (Api<Ingress>, Api<Secret>) = match namespace {
+        Some(ns) => (
+            Api::namespaced(client.clone(), ns),
+            Api::namespaced(client, ns),
+        ),
+        None => (Api::all(client.clone()), Api::all(client)),
+    };
+
+    let ingresses = ingresses.list(&ListParams::default()).await?.items;
+    let secrets: Option<HashSet<(String, String)>> =
+        match secrets.list_metadata(&ListParams::default()).await {
+            Ok(list) => Some(
+                list.items
+                    .into_iter()
+                    .filter_map(|s| Some((s.metadata.namespace?, s.metadata.name?)))
+                    .collect(),
+            ),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+    Ok(check_ingresses(&ingresses, secrets.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingress(namespace: &str, name: &str, spec: serde_json::Value) -> Ingress {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": name, "namespace": namespace},
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+
+    fn rule(host: &str, path: &str, service: &str) -> serde_json::Value {
+        serde_json::json!({
+            "host": host,
+            "http": {"paths": [{
+                "path": path,
+                "pathType": "Prefix",
+                "backend": {"service": {"name": service, "port": {"number": 80}}},
+            }]},
+        })
+    }
+
+    #[test]
+    fn test_wildcard_covers() {
+        assert!(wildcard_covers("*.example.com", "shop.example.com"));
+        assert!(!wildcard_covers("*.example.com", "a.shop.example.com"));
+        assert!(!wildcard_covers("*.example.com", "example.com"));
+        assert!(!wildcard_covers("shop.example.com", "shop.example.com"));
+    }
+
+    #[test]
+    fn test_check_ingresses() {
+        let ingresses = [
+            ingress(
+                "shop",
+                "web",
+                serde_json::json!({
+                    "rules": [rule("shop.example.com", "/", "web")],
+                    "tls": [{"hosts": ["shop.example.com"], "secretName": "shop-tls"}],
+                }),
+            ),
+            ingress(
+                "legacy",
+                "storefront",
+                serde_json::json!({"rules": [rule("shop.example.com", "/", "storefront")]}),
+            ),
+            // Same backend as web: merged by controllers, not a conflict
+            ingress(
+                "shop",
+                "web-canary",
+                serde_json::json!({"rules": [rule("shop.example.com", "/", "web")]}),
+            ),
+            ingress(
+                "platform",
+                "catch-all",
+                serde_json::json!({
+                    "rules": [rule("*.example.com", "/", "default-site")],
+                    "tls": [{"hosts": ["*.example.com"], "secretName": "wildcard-tls"}],
+                }),
+            ),
+            // Different ingress class: routed by another controller
+            ingress(
+                "other",
+                "internal",
+                serde_json::json!({
+                    "ingressClassName": "internal",
+                    "rules": [rule("shop.example.com", "/", "internal-web")],
+                }),
+            ),
+        ];
+        let secrets: HashSet<(String, String)> =
+            [("platform".to_string(), "wildcard-tls".to_string())].into();
+
+        let issues = check_ingresses(&ingresses, Some(&secrets));
+        let summary: Vec<(IngressIssueKind, &str, usize)> = issues
+            .iter()
+            .map(|i| (i.kind, i.host.as_str(), i.routes.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (IngressIssueKind::DuplicateRoute, "shop.example.com", 3),
+                (IngressIssueKind::WildcardOverlap, "*.example.com", 4),
+                (IngressIssueKind::MissingTlsSecret, "shop.example.com", 1),
+            ]
+        );
+        assert_eq!(issues[0].routes[0].backend, "web:80");
+        assert_eq!(issues[2].secret.as_deref(), Some("shop-tls"));
+
+        // Without secret access, TLS secrets are not checked
+        assert_eq!(check_ingresses(&ingresses, None).len(), 2);
+    }
+}
@@ -0,0 +1,559 @@
+//! JSONPath templates rendered against serialized resources
+//!
+//! The kubectl dialect: literal text with `{expression}` blocks, where an
+//! expression is a path into the JSON form of the output (`{[*].name}`,
+//! `{[0].labels.app}`), a quoted string (`{"\n"}`), or `{range path}` ...
+//! `{end}` repeating its body for every match. Paths support `.field`,
+//! `['field']`, `[index]` (negative from the end), `*` and `[*]`, `..` for
+//! recursive descent and filters such as `[?(@.status=="Running")]` with
+//! `==`, `!=`, `<`, `<=`, `>`, `>=` or just a path to test for presence.
+//!
+//! Paths start at the current object: the whole output at the top level, the
+//! element being repeated inside a range. A leading `$` always refers to the
+//! whole output. Several matches are printed separated by spaces; strings
+//! print without quotes and other values as compact JSON.
+
+use crate::error::{ExplorerError, Result};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Field(String),
+    Index(i64),
+    Wildcard,
+    /// The current values and everything below them
+    Descend,
+    Filter(Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    path: Path,
+    comparison: Option<(Comparison, Value)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A path to values inside a JSON document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    /// Starts at the whole document (`$`) rather than the current object
+    absolute: bool,
+    steps: Vec<Step>,
+}
+
+impl Path {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            source,
+            chars: source.char_indices().peekable(),
+        };
+        let path = parser.path()?;
+        match parser.chars.next() {
+            None => Ok(path),
+            Some((_, c)) => Err(parser.error(&format!("unexpected '{}'", c))),
+        }
+    }
+
+    /// Values the path matches, starting at `current`
+    pub fn evaluate<'a>(&self, current: &'a Value, root: &'a Value) -> Vec<&'a Value> {
+        let start = if self.absolute { root } else { current };
+        let mut values = vec![start];
+        for step in &self.steps {
+            values = values
+                .into_iter()
+                .flat_map(|value| apply(step, value, root))
+                .collect();
+        }
+        values
+    }
+}
+
+fn apply<'a>(step: &Step, value: &'a Value, root: &'a Value) -> Vec<&'a Value> {
+    match step {
+        Step::Field(name) => value.get(name).into_iter().collect(),
+        Step::Index(index) => {
+            let Value::Array(items) = value else {
+                return Vec::new();
+            };
+            let index = if *index < 0 {
+                items.len() as i64 + index
+            } else {
+                *index
+            };
+            usize::try_from(index)
+                .ok()
+                .and_then(|i| items.get(i))
+                .into_iter()
+                .collect()
+        }
+        Step::Wildcard => children(value),
+        Step::Descend => {
+            let mut values = vec![value];
+            let mut i = 0;
+            while i < values.len() {
+                values.extend(children(values[i]));
+                i += 1;
+            }
+            values
+        }
+        Step::Filter(condition) => children(value)
+            .into_iter()
+            .filter(|item| condition.holds(item, root))
+            .collect(),
+    }
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl Condition {
+    fn holds(&self, item: &Value, root: &Value) -> bool {
+        let values = self.path.evaluate(item, root);
+        match &self.comparison {
+            None => values.iter().any(|v| !v.is_null()),
+            Some((comparison, expected)) => values.iter().any(|value| {
+                let ordering = match (value, expected) {
+                    (Value::Number(a), Value::Number(b)) => a
+                        .as_f64()
+                        .zip(b.as_f64())
+                        .and_then(|(a, b)| a.partial_cmp(&b)),
+                    (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+                    (a, b) => (*a == b).then_some(Ordering::Equal),
+                };
+                match comparison {
+                    Comparison::Eq => ordering == Some(Ordering::Equal),
+                    Comparison::Ne => ordering != Some(Ordering::Equal),
+                    Comparison::Lt => ordering == Some(Ordering::Less),
+                    Comparison::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    Comparison::Gt => ordering == Some(Ordering::Greater),
+                    Comparison::Ge => {
+                        matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+                    }
+                }
+            }),
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: &str) -> ExplorerError {
+        ExplorerError::InvalidArgument(format!("jsonpath {:?}: {}", self.source, reason))
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.chars.next();
+        }
+    }
+
+    fn path(&mut self) -> Result<Path> {
+        self.skip_spaces();
+        let absolute = self.eat('$');
+        if !absolute {
+            self.eat('@');
+        }
+        let mut steps = Vec::new();
+        // A bare leading name, as in `name` or `labels.app`
+        if self.peek().is_some_and(is_name_char) {
+            steps.push(Step::Field(self.name()));
+        }
+        loop {
+            match self.peek() {
+                Some('.') => {
+                    self.chars.next();
+                    if self.eat('.') {
+                        steps.push(Step::Descend);
+                        if self.peek() == Some('[') {
+                            continue;
+                        }
+                    }
+                    if self.eat('*') {
+                        steps.push(Step::Wildcard);
+                    } else if self.peek().is_some_and(is_name_char) {
+                        steps.push(Step::Field(self.name()));
+                    } else if !matches!(self.peek(), None | Some('[')) {
+                        return Err(self.error("expected a field name after '.'"));
+                    }
+                }
+                Some('[') => {
+                    self.chars.next();
+                    steps.push(self.bracket()?);
+                }
+                _ => break,
+            }
+        }
+        Ok(Path { absolute, steps })
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek().filter(|c| is_name_char(*c)) {
+            name.push(c);
+            self.chars.next();
+        }
+        name
+    }
+
+    /// The step of a `[...]` whose `[` was consumed
+    fn bracket(&mut self) -> Result<Step> {
+        self.skip_spaces();
+        let step = match self.peek() {
+            Some('*') => {
+                self.chars.next();
+                Step::Wildcard
+            }
+            Some('\'' | '"') => match self.literal()? {
+                Value::String(name) => Step::Field(name),
+                _ => unreachable!(),
+            },
+            Some('?') => {
+                self.chars.next();
+                if !self.eat('(') {
+                    return Err(self.error("expected '(' after '?'"));
+                }
+                let path = self.path()?;
+                self.skip_spaces();
+                let comparison = match self.comparison() {
+                    Some(comparison) => {
+                        self.skip_spaces();
+                        Some((comparison, self.literal()?))
+                    }
+                    None => None,
+                };
+                self.skip_spaces();
+                if !self.eat(')') {
+                    return Err(self.error("unclosed filter"));
+                }
+                Step::Filter(Box::new(Condition { path, comparison }))
+            }
+            _ => {
+                let mut number = String::new();
+                while let Some(c) = self.peek().filter(|c| c.is_ascii_digit() || *c == '-') {
+                    number.push(c);
+                    self.chars.next();
+                }
+                Step::Index(
+                    number
+                        .parse()
+                        .map_err(|_| self.error("expected an index, '*', a name or a filter"))?,
+                )
+            }
+        };
+        self.skip_spaces();
+        if !self.eat(']') {
+            return Err(self.error("unclosed '['"));
+        }
+        Ok(step)
+    }
+
+    fn comparison(&mut self) -> Option<Comparison> {
+        let comparison = match self.peek()? {
+            '=' => Comparison::Eq,
+            '!' => Comparison::Ne,
+            '<' => Comparison::Lt,
+            '>' => Comparison::Gt,
+            _ => return None,
+        };
+        self.chars.next();
+        let equals = self.eat('=');
+        Some(match (comparison, equals) {
+            (Comparison::Lt, true) => Comparison::Le,
+            (Comparison::Gt, true) => Comparison::Ge,
+            (comparison, _) => comparison,
+        })
+    }
+
+    /// A quoted string, number or boolean
+    fn literal(&mut self) -> Result<Value> {
+        match self.peek() {
+            Some(quote @ ('\'' | '"')) => {
+                self.chars.next();
+                let mut text = String::new();
+                loop {
+                    match self.chars.next().map(|(_, c)| c) {
+                        Some(c) if c == quote => break,
+                        Some('\\') => match self.chars.next().map(|(_, c)| c) {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some('r') => text.push('\r'),
+                            Some(c) => text.push(c),
+                            None => return Err(self.error("unclosed string")),
+                        },
+                        Some(c) => text.push(c),
+                        None => return Err(self.error("unclosed string")),
+                    }
+                }
+                Ok(Value::String(text))
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = self
+                    .peek()
+                    .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+                {
+                    word.push(c);
+                    self.chars.next();
+                }
+                serde_json::from_str(&word).map_err(|_| self.error("expected a value"))
+            }
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+/// A `--columns` column: a header and the path of its values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub header: String,
+    pub path: Path,
+}
+
+impl Column {
+    /// `HEADER:path`, or just `path` with the path uppercased as header
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (header, path) = match spec.split_once(':') {
+            Some((header, path)) if !header.contains(['.', '[', '$', '@']) => {
+                (header.to_string(), path)
+            }
+            _ => (
+                spec.trim_start_matches(['$', '@', '.']).to_uppercase(),
+                spec,
+            ),
+        };
+        Ok(Self {
+            header,
+            path: Path::parse(path)?,
+        })
+    }
+
+    /// The column's cell for `item`: its matches separated by commas, or
+    /// `<none>`
+    pub fn cell(&self, item: &Value) -> String {
+        let values: Vec<String> = self
+            .path
+            .evaluate(item, item)
+            .into_iter()
+            .filter(|v| !v.is_null())
+            .map(display)
+            .collect();
+        if values.is_empty() {
+            "<none>".to_string()
+        } else {
+            values.join(",")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Path(Path),
+    Range(Path, Vec<Node>),
+}
+
+/// A parsed `-o jsonpath=` template
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    nodes: Vec<Node>,
+}
+
+impl JsonPath {
+    pub fn parse(source: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            ExplorerError::InvalidArgument(format!("jsonpath {:?}: {}", source, reason))
+        };
+
+        // Nodes of the template and of each open range
+        let mut open: Vec<(Option<Path>, Vec<Node>)> = vec![(None, Vec::new())];
+        let mut text = String::new();
+        let mut chars = source.chars();
+        while let Some(c) = chars.next() {
+            if c != '{' {
+                text.push(c);
+                continue;
+            }
+            let mut expression = String::new();
+            let mut quote = None;
+            loop {
+                match chars.next() {
+                    Some('}') if quote.is_none() => break,
+                    Some(c) => {
+                        if quote == Some(c) {
+                            quote = None;
+                        } else if quote.is_none() && matches!(c, '\'' | '"') {
+                            quote = Some(c);
+                        }
+                        expression.push(c);
+                    }
+                    None => return Err(invalid("unclosed '{'")),
+                }
+            }
+            let nodes = &mut open.last_mut().unwrap().1;
+            if !text.is_empty() {
+                nodes.push(Node::Text(std::mem::take(&mut text)));
+            }
+
+            let expression = expression.trim();
+            if let Some(path) = expression.strip_prefix("range ") {
+                open.push((Some(Path::parse(path)?), Vec::new()));
+            } else if expression == "end" {
+                let (Some(path), body) = open.pop().unwrap() else {
+                    return Err(invalid("'end' without 'range'"));
+                };
+                open.last_mut().unwrap().1.push(Node::Range(path, body));
+            } else if expression.starts_with(['"', '\'']) {
+                let mut parser = Parser {
+                    source: expression,
+                    chars: expression.char_indices().peekable(),
+                };
+                match parser.literal()? {
+                    Value::String(literal) => nodes.push(Node::Text(literal)),
+                    _ => unreachable!(),
+                }
+            } else {
+                nodes.push(Node::Path(Path::parse(expression)?));
+            }
+        }
+        if open.len() > 1 {
+            return Err(invalid("'range' without 'end'"));
+        }
+        let mut nodes = open.pop().unwrap().1;
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(Self { nodes })
+    }
+
+    /// Render the template against the output document `root`
+    pub fn render(&self, root: &Value) -> String {
+        let mut rendered = String::new();
+        render(&self.nodes, root, root, &mut rendered);
+        rendered
+    }
+}
+
+fn render(nodes: &[Node], current: &Value, root: &Value, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Path(path) => {
+                let values: Vec<String> = path
+                    .evaluate(current, root)
+                    .into_iter()
+                    .map(display)
+                    .collect();
+                out.push_str(&values.join(" "));
+            }
+            Node::Range(path, body) => {
+                for item in path.evaluate(current, root) {
+                    render(body, item, root, out);
+                }
+            }
+        }
+    }
+}
+
+/// A matched value as printed: strings unquoted, anything else as JSON
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pods() -> Value {
+        json!([
+            {"name": "web-1", "namespace": "shop", "status": "Running", "restarts": 0,
+             "labels": {"app": "web", "app.kubernetes.io/name": "web"}},
+            {"name": "db-0", "namespace": "shop", "status": "Pending", "restarts": 4,
+             "labels": {"app": "db"}},
+        ])
+    }
+
+    #[test]
+    fn test_paths() {
+        let pods = pods();
+        let names = |source: &str| {
+            Path::parse(source)
+                .unwrap()
+                .evaluate(&pods, &pods)
+                .into_iter()
+                .map(display)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("[*].name"), ["web-1", "db-0"]);
+        assert_eq!(names("$[-1].labels.app"), ["db"]);
+        assert_eq!(names("[0].labels['app.kubernetes.io/name']"), ["web"]);
+        assert_eq!(names("[?(@.status==\"Running\")].name"), ["web-1"]);
+        assert_eq!(names("[?(@.restarts > 1)].name"), ["db-0"]);
+        assert_eq!(names("..app"), ["web", "db"]);
+        assert!(Path::parse("[0").is_err());
+        assert!(Path::parse("[?(@.x==)]").is_err());
+    }
+
+    #[test]
+    fn test_render_templates() {
+        let pods = pods();
+        let render = |source: &str| JsonPath::parse(source).unwrap().render(&pods);
+        assert_eq!(render("{[*].name}"), "web-1 db-0");
+        assert_eq!(
+            render("{range [*]}{.namespace}/{.name}{\"\\t\"}{.restarts}{\"\\n\"}{end}"),
+            "shop/web-1\t0\nshop/db-0\t4\n"
+        );
+        assert_eq!(
+            render("first: {[0].labels}"),
+            "first: {\"app\":\"web\",\"app.kubernetes.io/name\":\"web\"}"
+        );
+        assert!(JsonPath::parse("{range [*]}{.name}").is_err());
+        assert!(JsonPath::parse("{end}").is_err());
+        assert!(JsonPath::parse("{.name").is_err());
+
+        let column = Column::parse("APP:labels.app").unwrap();
+        assert_eq!(column.header, "APP");
+        assert_eq!(column.cell(&pods[1]), "db");
+        let column = Column::parse(".labels.tier").unwrap();
+        assert_eq!(column.header, "LABELS.TIER");
+        assert_eq!(column.cell(&pods[0]), "<none>");
+    }
+}
@@ -0,0 +1,199 @@
+//! Kind names as kubectl accepts them
+//!
+//! Commands and filters take a kind as its name (`Deployment`), plural
+//! (`deployments`) or short name (`deploy`), in any case. Built-in kinds
+//! resolve through the table below; other kinds through API discovery,
+//! which also reports the short names CRDs declare (`cert` for
+//! cert-manager's Certificate, for example).
+
+use crate::discovery::ResourceDiscovery;
+use kube::api::ApiResource;
+use kube::discovery::Scope;
+
+/// A built-in kind with its plural and kubectl short names
+struct BuiltinKind {
+    kind: &'static str,
+    plural: &'static str,
+    short_names: &'static [&'static str],
+}
+
+const fn builtin(
+    kind: &'static str,
+    plural: &'static str,
+    short_names: &'static [&'static str],
+) -> BuiltinKind {
+    BuiltinKind {
+        kind,
+        plural,
+        short_names,
+    }
+}
+
+/// Built-in kinds, with the short names `kubectl api-resources` lists
+const BUILTIN_KINDS: &[BuiltinKind] = &[
+    builtin("Pod", "pods", &["po"]),
+    builtin("Service", "services", &["svc"]),
+    builtin("Deployment", "deployments", &["deploy"]),
+    builtin("StatefulSet", "statefulsets", &["sts"]),
+    builtin("DaemonSet", "daemonsets", &["ds"]),
+    builtin("ReplicaSet", "replicasets", &["rs"]),
+    builtin("ReplicationController", "replicationcontrollers", &["rc"]),
+    builtin("Job", "jobs", &[]),
+    builtin("CronJob", "cronjobs", &["cj"]),
+    builtin("ConfigMap", "configmaps", &["cm"]),
+    builtin("Secret", "secrets", &[]),
+    builtin("ServiceAccount", "serviceaccounts", &["sa"]),
+    builtin("Namespace", "namespaces", &["ns"]),
+    builtin("Node", "nodes", &["no"]),
+    builtin("PersistentVolume", "persistentvolumes", &["pv"]),
+    builtin("PersistentVolumeClaim", "persistentvolumeclaims", &["pvc"]),
+    builtin("StorageClass", "storageclasses", &["sc"]),
+    builtin("Endpoints", "endpoints", &["ep"]),
+    builtin("EndpointSlice", "endpointslices", &[]),
+    builtin("Event", "events", &["ev"]),
+    builtin("Ingress", "ingresses", &["ing"]),
+    builtin("IngressClass", "ingressclasses", &[]),
+    builtin("NetworkPolicy", "networkpolicies", &["netpol"]),
+    builtin(
+        "HorizontalPodAutoscaler",
+        "horizontalpodautoscalers",
+        &["hpa"],
+    ),
+    builtin("PodDisruptionBudget", "poddisruptionbudgets", &["pdb"]),
+    builtin("PriorityClass", "priorityclasses", &["pc"]),
+    builtin("LimitRange", "limitranges", &["limits"]),
+    builtin("ResourceQuota", "resourcequotas", &["quota"]),
+    builtin("Role", "roles", &[]),
+    builtin("RoleBinding", "rolebindings", &[]),
+    builtin("ClusterRole", "clusterroles", &[]),
+    builtin("ClusterRoleBinding", "clusterrolebindings", &[]),
+    builtin(
+        "CustomResourceDefinition",
+        "customresourcedefinitions",
+        &["crd", "crds"],
+    ),
+];
+
+/// Canonical kind of a built-in kind's name, plural or short name
+pub fn builtin_kind(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    BUILTIN_KINDS
+        .iter()
+        .find(|b| {
+            b.kind.to_lowercase() == name || b.plural == name || b.short_names.contains(&&*name)
+        })
+        .map(|b| b.kind)
+}
+
+/// Canonical form of a kind name for comparisons: the kind of a built-in
+/// name, otherwise the name in lowercase
+pub fn canonical_kind(name: &str) -> String {
+    builtin_kind(name)
+        .map(str::to_string)
+        .unwrap_or_else(|| name.to_lowercase())
+}
+
+/// Whether `names` (kinds, plurals or short names, e.g. from
+/// `--include-types`) include objects of `kind`
+pub fn kind_in(kind: &str, names: &[String]) -> bool {
+    let kind = canonical_kind(kind).to_lowercase();
+    names
+        .iter()
+        .any(|name| canonical_kind(name).to_lowercase() == kind)
+}
+
+/// Whether `wanted` (in lowercase) names `resource`: its kind, plural,
+/// either qualified by the group, or a built-in short name of the kind
+fn names_resource(resource: &ApiResource, wanted: &str) -> bool {
+    let kind = resource.kind.to_lowercase();
+    let qualified = |name: &str| format!("{}.{}", name, resource.group);
+    let builtin_alias =
+        is_builtin_group(&resource.group) && builtin_kind(wanted) == Some(resource.kind.as_str());
+    kind == wanted
+        || resource.plural == wanted
+        || (!resource.group.is_empty()
+            && (qualified(&resource.plural) == wanted || qualified(&kind) == wanted))
+        || builtin_alias
+}
+
+/// Whether `group` is an API group of built-in kinds, so a CRD of the same
+/// kind is not mistaken for one
+fn is_builtin_group(group: &str) -> bool {
+    group.is_empty()
+        || group.ends_with(".k8s.io")
+        || matches!(group, "apps" | "batch" | "autoscaling" | "policy")
+}
+
+/// The served resource `name` refers to: a kind, plural, `plural.group`,
+/// built-in short name, or a short name declared in discovery
+pub fn find_resource(discovered: &ResourceDiscovery, name: &str) -> Option<(ApiResource, Scope)> {
+    let wanted = name.to_lowercase();
+    let by_name = discovered
+        .resources
+        .iter()
+        .find(|(resource, _)| names_resource(resource, &wanted));
+    let by_short_name = || {
+        let (group, plural) = discovered.short_names.get(&wanted)?;
+        discovered
+            .resources
+            .iter()
+            .find(|(resource, _)| &resource.group == group && &resource.plural == plural)
+    };
+    by_name.or_else(by_short_name).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kube::api::GroupVersionKind;
+
+    #[test]
+    fn test_builtin_kind() {
+        assert_eq!(builtin_kind("svc"), Some("Service"));
+        assert_eq!(builtin_kind("Deployments"), Some("Deployment"));
+        assert_eq!(builtin_kind("CM"), Some("ConfigMap"));
+        assert_eq!(builtin_kind("certificate"), None);
+        assert!(kind_in("StatefulSet", &["sts".to_string()]));
+        assert!(kind_in("Certificate", &["certificate".to_string()]));
+        assert!(!kind_in("Secret", &["cm".to_string()]));
+    }
+
+    #[test]
+    fn test_find_resource() {
+        let resource = |group: &str, version: &str, kind: &str, plural: &str| {
+            ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk(group, version, kind), plural)
+        };
+        let discovered = ResourceDiscovery {
+            resources: vec![
+                (
+                    resource("", "v1", "ConfigMap", "configmaps"),
+                    Scope::Namespaced,
+                ),
+                (
+                    resource("apps", "v1", "StatefulSet", "statefulsets"),
+                    Scope::Namespaced,
+                ),
+                (
+                    resource("cert-manager.io", "v1", "Certificate", "certificates"),
+                    Scope::Namespaced,
+                ),
+            ],
+            failures: Vec::new(),
+            short_names: [(
+                "cert".to_string(),
+                ("cert-manager.io".to_string(), "certificates".to_string()),
+            )]
+            .into(),
+        };
+
+        let kind = |name: &str| find_resource(&discovered, name).map(|(r, _)| r.kind);
+        assert_eq!(kind("cm").as_deref(), Some("ConfigMap"));
+        assert_eq!(kind("sts").as_deref(), Some("StatefulSet"));
+        assert_eq!(kind("cert").as_deref(), Some("Certificate"));
+        assert_eq!(
+            kind("certificates.cert-manager.io").as_deref(),
+            Some("Certificate")
+        );
+        assert_eq!(kind("widget"), None);
+    }
+}
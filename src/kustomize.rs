@@ -0,0 +1,288 @@
+//! Objects deployed with kustomize and drift from their overlay's labels
+//!
+//! A build leaves no single marker, so several are checked, in order:
+//! the `kustomize.toolkit.fluxcd.io/name` and `/namespace` labels Flux puts
+//! on everything a Kustomization applies, the `config.kubernetes.io/origin`
+//! annotation written when `buildMetadata: [originAnnotations]` is set
+//! (naming the overlay file the object came from), and an
+//! `app.kubernetes.io/managed-by` label starting with `kustomize`.
+//!
+//! `commonLabels` and `labels` in an overlay put the same labels on every
+//! object it renders. The labels most kustomize-managed objects of a
+//! namespace share are taken as the expected set, and objects missing any
+//! of them were edited by hand, created outside the overlay, or left over
+//! from an older build.
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use crate::extract;
+use kube::api::{DynamicObject, ListParams};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const MANAGED_BY_LABEL: &str = "app.kubernetes.io/managed-by";
+const FLUX_NAME_LABEL: &str = "kustomize.toolkit.fluxcd.io/name";
+const FLUX_NAMESPACE_LABEL: &str = "kustomize.toolkit.fluxcd.io/namespace";
+const ORIGIN_ANNOTATION: &str = "config.kubernetes.io/origin";
+
+/// Kustomize-managed objects a namespace needs before its labels are
+/// compared
+const MIN_MANAGED: usize = 2;
+
+/// The kustomization an object was rendered by: the Flux Kustomization as
+/// `namespace/name`, the overlay directory from the origin annotation, or
+/// just `kustomize` when only the managed-by label says so. `None` for
+/// objects kustomize did not produce.
+pub fn kustomization(
+    labels: &BTreeMap<String, String>,
+    annotations: Option<&BTreeMap<String, String>>,
+) -> Option<String> {
+    if let Some(name) = labels.get(FLUX_NAME_LABEL) {
+        return Some(match labels.get(FLUX_NAMESPACE_LABEL) {
+            Some(namespace) => format!("{}/{}", namespace, name),
+            None => name.clone(),
+        });
+    }
+    if let Some(origin) = annotations.and_then(|a| a.get(ORIGIN_ANNOTATION)) {
+        if let Some(directory) = origin_directory(origin) {
+            return Some(directory);
+        }
+    }
+    labels
+        .get(MANAGED_BY_LABEL)
+        .filter(|value| value.starts_with("kustomize"))
+        .map(|_| "kustomize".to_string())
+}
+
+/// Directory of the file named in an origin annotation such as
+/// `path: overlays/prod/deployment.yaml`, prefixed with the repo when the
+/// file came from a remote base
+fn origin_directory(origin: &str) -> Option<String> {
+    let origin: serde_yaml::Value = serde_yaml::from_str(origin).ok()?;
+    let path = origin.get("path")?.as_str()?;
+    let directory = match path.rsplit_once('/') {
+        Some((directory, _)) => directory,
+        None => ".",
+    };
+    Some(match origin.get("repo").and_then(|r| r.as_str()) {
+        Some(repo) => format!("{}//{}", repo, directory),
+        None => directory.to_string(),
+    })
+}
+
+/// Whether another tool than kustomize claims the object, e.g. Helm
+fn managed_by_other(labels: &BTreeMap<String, String>) -> bool {
+    labels
+        .get(MANAGED_BY_LABEL)
+        .is_some_and(|value| !value.starts_with("kustomize"))
+}
+
+/// An object as compared against its namespace's expected labels
+#[derive(Debug, Clone)]
+pub struct LiveObject {
+    pub kind: String,
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// An object lacking labels the kustomize-managed objects of its namespace
+/// carry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KustomizeDrift {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    /// Kustomization the object itself claims, if any
+    pub kustomization: Option<String>,
+    /// Expected labels it lacks or carries with another value, as
+    /// `key=value`
+    pub missing: Vec<String>,
+}
+
+/// Labels with the same value on more than half of `managed`
+pub fn expected_labels(managed: &[&LiveObject]) -> BTreeMap<String, String> {
+    let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for object in managed {
+        for (key, value) in &object.labels {
+            *counts.entry((key, value)).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| count * 2 > managed.len())
+        .map(|((key, value), _)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Objects of `namespace` missing any of the labels expected there. Objects
+/// another tool manages are left out, and so are namespaces with fewer than
+/// two kustomize-managed objects.
+pub fn find_drift(namespace: &str, objects: &[LiveObject]) -> Vec<KustomizeDrift> {
+    let managed: Vec<&LiveObject> = objects
+        .iter()
+        .filter(|o| kustomization(&o.labels, Some(&o.annotations)).is_some())
+        .collect();
+    if managed.len() < MIN_MANAGED {
+        return Vec::new();
+    }
+    let expected = expected_labels(&managed);
+
+    objects
+        .iter()
+        .filter(|o| !managed_by_other(&o.labels))
+        .filter_map(|o| {
+            let missing: Vec<String> = expected
+                .iter()
+                .filter(|(key, value)| o.labels.get(*key) != Some(value))
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            (!missing.is_empty()).then(|| KustomizeDrift {
+                namespace: namespace.to_string(),
+                kind: o.kind.clone(),
+                name: o.name.clone(),
+                kustomization: kustomization(&o.labels, Some(&o.annotations)),
+                missing,
+            })
+        })
+        .collect()
+}
+
+/// Objects in `namespace` (or every namespace in scope) that lack the
+/// kustomize labels expected in their namespace
+pub async fn find_kustomize_drift(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<KustomizeDrift>> {
+    let mut by_namespace: BTreeMap<String, Vec<LiveObject>> = BTreeMap::new();
+    for resource in extract::exported_kinds(true) {
+        let api: Api<DynamicObject> = match namespace {
+            Some(ns) => Api::namespaced_with(discovery.client().clone(), ns, &resource),
+            None => Api::all_with(discovery.client().clone(), &resource),
+        };
+        let list = match api.list(&ListParams::default()).await {
+            Ok(list) => list,
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for object in list.items {
+            let value = serde_json::to_value(&object)?;
+            if extract::is_generated(&resource.kind, &value) {
+                continue;
+            }
+            let metadata = object.metadata;
+            by_namespace
+                .entry(metadata.namespace.unwrap_or_default())
+                .or_default()
+                .push(LiveObject {
+                    kind: resource.kind.clone(),
+                    name: metadata.name.unwrap_or_default(),
+                    labels: metadata.labels.unwrap_or_default(),
+                    annotations: metadata.annotations.unwrap_or_default(),
+                });
+        }
+    }
+
+    let mut drift: Vec<KustomizeDrift> = by_namespace
+        .iter()
+        .flat_map(|(ns, objects)| find_drift(ns, objects))
+        .collect();
+    if namespace.is_none() {
+        discovery
+            .namespace_scope()
+            .retain(&mut drift, |d| Some(d.namespace.as_str()));
+    }
+    Ok(drift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(kind: &str, name: &str, labels: &[(&str, &str)]) -> LiveObject {
+        LiveObject {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_kustomization() {
+        let flux = object(
+            "Deployment",
+            "web",
+            &[
+                (FLUX_NAME_LABEL, "apps"),
+                (FLUX_NAMESPACE_LABEL, "flux-system"),
+            ],
+        );
+        assert_eq!(
+            kustomization(&flux.labels, None).as_deref(),
+            Some("flux-system/apps")
+        );
+
+        let mut annotations = BTreeMap::new();
+        annotations.insert(
+            ORIGIN_ANNOTATION.to_string(),
+            "path: overlays/prod/deployment.yaml\n".to_string(),
+        );
+        assert_eq!(
+            kustomization(&BTreeMap::new(), Some(&annotations)).as_deref(),
+            Some("overlays/prod")
+        );
+
+        let labeled = object("Service", "web", &[(MANAGED_BY_LABEL, "kustomize-v5.3.0")]);
+        assert_eq!(
+            kustomization(&labeled.labels, None).as_deref(),
+            Some("kustomize")
+        );
+        let helm = object("Service", "web", &[(MANAGED_BY_LABEL, "Helm")]);
+        assert_eq!(kustomization(&helm.labels, None), None);
+    }
+
+    #[test]
+    fn test_find_drift() {
+        let managed = [(MANAGED_BY_LABEL, "kustomize"), ("env", "prod")];
+        let objects = vec![
+            object("Deployment", "web", &managed),
+            object("Service", "web", &managed),
+            object("ConfigMap", "web-config", &managed),
+            // Edited by hand: the overlay's env label was changed
+            object(
+                "Deployment",
+                "worker",
+                &[(MANAGED_BY_LABEL, "kustomize"), ("env", "staging")],
+            ),
+            // Created outside the overlay
+            object("ConfigMap", "scratch", &[]),
+            object("Secret", "db", &[(MANAGED_BY_LABEL, "Helm")]),
+        ];
+        let drift = find_drift("shop", &objects);
+        let found: Vec<(&str, Vec<String>)> = drift
+            .iter()
+            .map(|d| (d.name.as_str(), d.missing.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("worker", vec!["env=prod".to_string()]),
+                (
+                    "scratch",
+                    vec![
+                        format!("{}=kustomize", MANAGED_BY_LABEL),
+                        "env=prod".to_string()
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(drift[0].kustomization.as_deref(), Some("kustomize"));
+
+        assert!(find_drift("shop", &objects[4..]).is_empty());
+    }
+}
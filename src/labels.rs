@@ -0,0 +1,130 @@
+//! Inventory of the values a label key takes across resources
+//!
+//! Selectors and grouping rules silently miss resources whose labels were
+//! spelled differently ("Prod", "prod", "production "). Listing every value
+//! of a key with the resources carrying it shows such drift before a
+//! selector is written against it.
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of example resources kept per value
+const MAX_EXAMPLES: usize = 3;
+
+/// Resources carrying one value of the label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelValueUsage {
+    pub value: String,
+    pub count: usize,
+    /// Resource count per kind
+    pub kinds: BTreeMap<String, usize>,
+    pub examples: Vec<String>,
+    /// Other values that differ only in case or surrounding whitespace
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub similar: Vec<String>,
+}
+
+/// Distinct values of a label key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelValues {
+    pub key: String,
+    /// Resources carrying the key
+    pub resources: usize,
+    pub values: Vec<LabelValueUsage>,
+}
+
+/// Distinct values of `key` on `objects` (kind and metadata pairs), most
+/// used first
+pub fn label_values(key: &str, objects: &[(String, ObjectMeta)]) -> LabelValues {
+    let mut usage: BTreeMap<&str, LabelValueUsage> = BTreeMap::new();
+    let mut resources = 0;
+
+    for (kind, metadata) in objects {
+        let Some(value) = metadata.labels.as_ref().and_then(|l| l.get(key)) else {
+            continue;
+        };
+        resources += 1;
+
+        let entry = usage.entry(value).or_insert_with(|| LabelValueUsage {
+            value: value.clone(),
+            count: 0,
+            kinds: BTreeMap::new(),
+            examples: Vec::new(),
+            similar: Vec::new(),
+        });
+        entry.count += 1;
+        *entry.kinds.entry(kind.clone()).or_default() += 1;
+        if entry.examples.len() < MAX_EXAMPLES {
+            let name = metadata.name.as_deref().unwrap_or_default();
+            entry.examples.push(match &metadata.namespace {
+                Some(ns) => format!("{}/{}/{}", kind, ns, name),
+                None => format!("{}/{}", kind, name),
+            });
+        }
+    }
+
+    let normalized = |value: &str| value.trim().to_lowercase();
+    let mut values: Vec<LabelValueUsage> = usage.into_values().collect();
+    let all: Vec<String> = values.iter().map(|v| v.value.clone()).collect();
+    for usage in &mut values {
+        usage.similar = all
+            .iter()
+            .filter(|other| **other != usage.value && normalized(other) == normalized(&usage.value))
+            .cloned()
+            .collect();
+    }
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+    LabelValues {
+        key: key.to_string(),
+        resources,
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(kind: &str, name: &str, labels: &[(&str, &str)]) -> (String, ObjectMeta) {
+        let metadata = ObjectMeta {
+            name: Some(name.to_string()),
+            namespace: Some("shop".to_string()),
+            labels: Some(
+                labels
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        (kind.to_string(), metadata)
+    }
+
+    #[test]
+    fn test_label_values() {
+        let objects = [
+            object("Deployment", "web", &[("env", "prod")]),
+            object("Pod", "web-1", &[("env", "prod")]),
+            object("Pod", "web-2", &[("env", "prod")]),
+            object("Service", "web", &[("env", "Prod")]),
+            object("ConfigMap", "web", &[("env", "staging")]),
+            object("Secret", "web", &[("team", "shop")]),
+        ];
+
+        let values = label_values("env", &objects);
+        assert_eq!(values.resources, 5);
+        let summary: Vec<(&str, usize)> = values
+            .values
+            .iter()
+            .map(|v| (v.value.as_str(), v.count))
+            .collect();
+        assert_eq!(summary, [("prod", 3), ("Prod", 1), ("staging", 1)]);
+
+        assert_eq!(values.values[0].kinds["Pod"], 2);
+        assert_eq!(values.values[0].examples[0], "Deployment/shop/web");
+        assert_eq!(values.values[0].similar, ["Prod"]);
+        assert!(values.values[2].similar.is_empty());
+    }
+}
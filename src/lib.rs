@@ -0,0 +1,153 @@
+//! K8s Discovery Experience (kdx) - Kubernetes cluster discovery as a library
+//!
+//! Everything the `kdx` command line tool does is available to other Rust
+//! programs: [`DiscoveryEngine`] lists and relates resources through a
+//! caching client, the info structs in [`discovery`] are the summaries it
+//! returns, [`ResourceFilter`] and [`ResourceGrouper`] narrow and organize
+//! them, and [`ServiceGraph`] builds dependency graphs.
+//!
+//! ```no_run
+//! use kdx::{DiscoveryEngine, FilterCriteria, ResourceFilter};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> kdx::Result<()> {
+//! let discovery = DiscoveryEngine::builder()
+//!     .context("prod")
+//!     .cache_ttl(Duration::from_secs(60))
+//!     .build()
+//!     .await?;
+//!
+//! let pods = discovery.list_pods(Some("shop"), Some("app=checkout")).await?;
+//! let criteria = FilterCriteria {
+//!     status_filter: Some("Running".to_string()),
+//!     ..Default::default()
+//! };
+//! for pod in ResourceFilter::filter_pods(pods, &criteria) {
+//!     println!("{} on {}", pod.name, pod.node_name.unwrap_or_default());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Filtering and grouping work on plain values, so they can be used with
+//! summaries from anywhere:
+//!
+//! ```
+//! use kdx::{FilterCriteria, ResourceFilter, ServiceInfo};
+//! use std::collections::BTreeMap;
+//!
+//! let service = |name: &str, app: &str| ServiceInfo {
+//!     name: name.to_string(),
+//!     namespace: "shop".to_string(),
+//!     ports: Vec::new(),
+//!     cluster_ip: None,
+//!     service_type: "ClusterIP".to_string(),
+//!     selector: Some(BTreeMap::from([("app".to_string(), app.to_string())])),
+//!     created_at: None,
+//!     annotations: BTreeMap::new(),
+//! };
+//! let services = vec![service("web", "web"), service("db", "postgres")];
+//!
+//! let criteria = FilterCriteria {
+//!     label_selector: Some("app in (web,api)".to_string()),
+//!     ..Default::default()
+//! };
+//! let frontends = ResourceFilter::filter_services(services, &criteria);
+//! assert_eq!(frontends[0].name, "web");
+//! assert_eq!(frontends.len(), 1);
+//! ```
+
+pub mod age;
+pub mod annotations;
+pub mod cache;
+pub mod checkpoint;
+pub mod cli;
+pub mod clusters;
+pub mod compare;
+pub mod compress;
+pub mod config;
+pub mod containers;
+pub mod controllers;
+pub mod crd_versions;
+pub mod dependencies;
+pub mod dependents;
+pub mod describe;
+pub mod diff;
+pub mod discovery;
+pub mod disk_cache;
+pub mod endpoints;
+pub mod env;
+pub mod envelope;
+pub mod error;
+pub mod events;
+pub mod externals;
+pub mod extract;
+pub mod filtering;
+pub mod graph;
+pub mod helm;
+pub mod history;
+pub mod hpa;
+pub mod ingress;
+pub mod jsonpath;
+pub mod kinds;
+pub mod kustomize;
+pub mod labels;
+pub mod lint;
+pub mod mesh;
+pub mod move_check;
+pub mod namespaces;
+pub mod netpol;
+pub mod network;
+pub mod node_images;
+pub mod oomkills;
+pub mod output;
+pub mod owners;
+pub mod pod_detail;
+pub mod pod_index;
+pub mod portforward;
+pub mod probe;
+pub mod progress;
+pub mod quantity;
+pub mod rbac;
+pub mod read_only;
+pub mod redact;
+pub mod references;
+pub mod report;
+pub mod resource;
+pub mod rollout;
+pub mod scaling;
+pub mod schema;
+pub mod scope;
+pub mod scorecard;
+pub mod secrets;
+pub mod session;
+pub mod simulate;
+pub mod snapshot;
+pub mod sort;
+pub mod spot;
+pub mod stability;
+pub mod statefulset;
+pub mod stats;
+pub mod storage;
+pub mod storage_rollup;
+pub mod stuck;
+pub mod template;
+pub mod tenant;
+pub mod terminating;
+pub mod timeline;
+pub mod tree;
+pub mod visibility;
+pub mod wait;
+pub mod warm;
+pub mod watch;
+pub mod zones;
+
+pub use cache::ResourceCache;
+pub use discovery::{
+    ConfigMapInfo, DaemonSetInfo, DeploymentInfo, DiscoveryEngine, DiscoveryEngineBuilder,
+    NodeInfo, PersistentVolumeClaimInfo, PersistentVolumeInfo, PodInfo, SecretInfo, ServiceInfo,
+    StatefulSetInfo,
+};
+pub use error::{ExplorerError, Result};
+pub use filtering::{FilterCriteria, GroupBy, ResourceFilter, ResourceGrouper};
+pub use graph::ServiceGraph;
@@ -0,0 +1,668 @@
+//! Policy checks over resource fields with built-in and user-defined rules
+//!
+//! Rules address fields with a dotted path into the object as returned by the
+//! API server, e.g. `spec.template.spec.containers[*].image` or
+//! `metadata.labels["app.kubernetes.io/name"]`. The `podSpec` prefix resolves
+//! to the pod spec of any workload kind, so one rule covers pods, deployments,
+//! jobs and cron jobs alike. A rule describes what a compliant object looks
+//! like; every resolved field that does not satisfy it is reported.
+
+use crate::error::{ExplorerError, Result};
+use crate::resource::ResourceKind;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Comparison applied to each field a rule's path resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operator {
+    /// The field must be set
+    Exists,
+    /// The field must not be set
+    Absent,
+    Equals,
+    NotEquals,
+    /// The field must match the regular expression in `value`
+    Matches,
+    NotMatches,
+    GreaterThan,
+    LessThan,
+}
+
+/// A single check over one field path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    /// Kinds the rule applies to (default: all workload kinds and pods)
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    pub path: String,
+    pub operator: Operator,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    pub message: String,
+}
+
+fn default_severity() -> Severity {
+    Severity::Warning
+}
+
+/// Contents of a rules file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    /// Built-in rules switched on (`true`) or off (`false`) by id
+    #[serde(default)]
+    pub builtin: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl RulesFile {
+    /// Load a rules file, parsed as TOML for `.toml` files and YAML otherwise
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+        let parsed = if is_toml {
+            toml::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+        };
+        parsed.map_err(|e| {
+            ExplorerError::InvalidArgument(format!("invalid rules file {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Built-in rules that are on unless a rules file disables them
+const DEFAULT_ENABLED: &[&str] = &[
+    "image-tag",
+    "image-latest",
+    "memory-limit",
+    "readiness-probe",
+    "privileged",
+];
+
+/// Rules shipped with kdx
+pub fn builtin_rules() -> Vec<Rule> {
+    let rule =
+        |id: &str, kinds: &[&str], path: &str, operator, value, severity, message: &str| Rule {
+            id: id.to_string(),
+            kinds: kinds.iter().map(|k| k.to_string()).collect(),
+            path: path.to_string(),
+            operator,
+            value,
+            severity,
+            message: message.to_string(),
+        };
+    let long_running = ["Deployment", "StatefulSet", "DaemonSet", "Pod"];
+
+    vec![
+        rule(
+            "image-tag",
+            &[],
+            "podSpec.containers[*].image",
+            Operator::Matches,
+            Some(Value::from(r"@sha256:|:[^/:]+$")),
+            Severity::Warning,
+            "Image has no tag or digest",
+        ),
+        rule(
+            "image-latest",
+            &[],
+            "podSpec.containers[*].image",
+            Operator::NotMatches,
+            Some(Value::from(":latest$")),
+            Severity::Warning,
+            "Image uses the mutable latest tag",
+        ),
+        rule(
+            "memory-limit",
+            &[],
+            "podSpec.containers[*].resources.limits.memory",
+            Operator::Exists,
+            None,
+            Severity::Warning,
+            "Container has no memory limit",
+        ),
+        rule(
+            "readiness-probe",
+            &long_running,
+            "podSpec.containers[*].readinessProbe",
+            Operator::Exists,
+            None,
+            Severity::Info,
+            "Container has no readiness probe",
+        ),
+        rule(
+            "privileged",
+            &[],
+            "podSpec.containers[*].securityContext.privileged",
+            Operator::NotEquals,
+            Some(Value::Bool(true)),
+            Severity::Error,
+            "Container runs privileged",
+        ),
+        rule(
+            "single-replica",
+            &["Deployment", "StatefulSet"],
+            "spec.replicas",
+            Operator::GreaterThan,
+            Some(Value::from(1)),
+            Severity::Info,
+            "Workload runs a single replica",
+        ),
+    ]
+}
+
+/// Built-in rules enabled by default or by the rules file, followed by the
+/// file's own rules
+pub fn effective_rules(file: &RulesFile) -> Result<Vec<Rule>> {
+    let builtins = builtin_rules();
+    if let Some(unknown) = file
+        .builtin
+        .keys()
+        .find(|id| !builtins.iter().any(|r| &r.id == *id))
+    {
+        return Err(ExplorerError::InvalidArgument(format!(
+            "unknown built-in rule '{}'",
+            unknown
+        )));
+    }
+
+    let mut rules: Vec<Rule> = builtins
+        .into_iter()
+        .filter(|r| {
+            file.builtin
+                .get(&r.id)
+                .copied()
+                .unwrap_or_else(|| DEFAULT_ENABLED.contains(&r.id.as_str()))
+        })
+        .collect();
+    rules.extend(file.rules.iter().cloned());
+
+    for rule in &rules {
+        validate_rule(rule)?;
+    }
+    Ok(rules)
+}
+
+fn validate_rule(rule: &Rule) -> Result<()> {
+    let invalid =
+        |msg: String| ExplorerError::InvalidArgument(format!("rule '{}': {}", rule.id, msg));
+
+    for kind in &rule.kinds {
+        kind.parse::<ResourceKind>()
+            .map_err(|e| invalid(e.to_string()))?;
+    }
+    parse_path(&rule.path).map_err(invalid)?;
+
+    match rule.operator {
+        Operator::Exists | Operator::Absent => {}
+        Operator::Matches | Operator::NotMatches => {
+            let pattern = rule.value.as_ref().and_then(Value::as_str).ok_or_else(|| {
+                invalid("a regular expression string value is required".to_string())
+            })?;
+            Regex::new(pattern).map_err(|e| invalid(e.to_string()))?;
+        }
+        Operator::GreaterThan | Operator::LessThan => {
+            rule.value
+                .as_ref()
+                .and_then(as_number)
+                .ok_or_else(|| invalid("a numeric value is required".to_string()))?;
+        }
+        Operator::Equals | Operator::NotEquals => {
+            if rule.value.is_none() {
+                return Err(invalid("a value is required".to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Kinds a rule applies to
+pub fn rule_kinds(rule: &Rule) -> Vec<ResourceKind> {
+    if rule.kinds.is_empty() {
+        return ResourceKind::ALL
+            .into_iter()
+            .filter(|k| *k != ResourceKind::Service)
+            .collect();
+    }
+    rule.kinds.iter().filter_map(|k| k.parse().ok()).collect()
+}
+
+/// A field that violates a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub severity: Severity,
+    pub rule: String,
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    /// Concrete path of the offending field, e.g. `spec.template.spec.containers[1].image`
+    pub path: String,
+    pub message: String,
+}
+
+/// Check every object against the rules that apply to its kind, most severe
+/// findings first. Objects managed by a controller are skipped because their
+/// owner's template is checked instead.
+pub fn lint_objects(objects: &[(ResourceKind, Value)], rules: &[Rule]) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    for (kind, object) in objects {
+        if has_controller(object) {
+            continue;
+        }
+        for rule in rules.iter().filter(|r| rule_kinds(r).contains(kind)) {
+            findings.extend(check(rule, *kind, object));
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.namespace.cmp(&b.namespace))
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    findings
+}
+
+fn has_controller(object: &Value) -> bool {
+    object["metadata"]["ownerReferences"]
+        .as_array()
+        .is_some_and(|owners| owners.iter().any(|o| o["controller"] == Value::Bool(true)))
+}
+
+fn check(rule: &Rule, kind: ResourceKind, object: &Value) -> Vec<LintFinding> {
+    let Ok(segments) = parse_path(&rule.path) else {
+        return Vec::new();
+    };
+    let segments = expand_pod_spec(segments, kind);
+    let regex = rule
+        .value
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(|p| Regex::new(p).ok());
+
+    resolve(object, &segments)
+        .into_iter()
+        .filter(|(_, field)| !satisfies(rule, *field, regex.as_ref()))
+        .map(|(path, _)| LintFinding {
+            severity: rule.severity,
+            rule: rule.id.clone(),
+            kind: kind.as_str().to_string(),
+            namespace: object["metadata"]["namespace"].as_str().map(String::from),
+            name: object["metadata"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            path,
+            message: rule.message.clone(),
+        })
+        .collect()
+}
+
+/// Whether a resolved field (`None` when unset) complies with the rule
+fn satisfies(rule: &Rule, field: Option<&Value>, regex: Option<&Regex>) -> bool {
+    let field = field.filter(|v| !v.is_null());
+    let expected = rule.value.as_ref();
+
+    match (rule.operator, field) {
+        (Operator::Exists, field) => field.is_some(),
+        (Operator::Absent, field) => field.is_none(),
+        // Comparisons only apply to fields that are set
+        (_, None) => true,
+        (Operator::Equals, Some(v)) => expected.is_some_and(|e| loosely_equal(v, e)),
+        (Operator::NotEquals, Some(v)) => !expected.is_some_and(|e| loosely_equal(v, e)),
+        (Operator::Matches, Some(v)) => regex.is_some_and(|re| re.is_match(&scalar(v))),
+        (Operator::NotMatches, Some(v)) => !regex.is_some_and(|re| re.is_match(&scalar(v))),
+        (Operator::GreaterThan, Some(v)) => {
+            matches!((as_number(v), expected.and_then(as_number)), (Some(a), Some(b)) if a > b)
+        }
+        (Operator::LessThan, Some(v)) => {
+            matches!((as_number(v), expected.and_then(as_number)), (Some(a), Some(b)) if a < b)
+        }
+    }
+}
+
+/// Compare as numbers when both sides are numeric, otherwise by text, so that
+/// `"1"` in a rules file matches the number 1 in the object
+fn loosely_equal(a: &Value, b: &Value) -> bool {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x == y,
+        _ => scalar(a) == scalar(b),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// One step of a field path
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// Every element of an array
+    Each,
+}
+
+fn parse_path(path: &str) -> std::result::Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut key = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !key.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut key)));
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut key)));
+                }
+                let segment = match chars.peek() {
+                    Some(&quote) if quote == '"' || quote == '\'' => {
+                        chars.next();
+                        let quoted: String = chars.by_ref().take_while(|c| *c != quote).collect();
+                        if chars.next() != Some(']') {
+                            return Err(format!("unclosed '[' in path '{}'", path));
+                        }
+                        Segment::Key(quoted)
+                    }
+                    _ => {
+                        let mut inner = String::new();
+                        loop {
+                            match chars.next() {
+                                Some(']') => break,
+                                Some(c) => inner.push(c),
+                                None => return Err(format!("unclosed '[' in path '{}'", path)),
+                            }
+                        }
+                        if inner == "*" {
+                            Segment::Each
+                        } else if let Ok(index) = inner.parse() {
+                            Segment::Index(index)
+                        } else {
+                            return Err(format!(
+                                "invalid selector '[{}]' in path '{}'",
+                                inner, path
+                            ));
+                        }
+                    }
+                };
+                segments.push(segment);
+            }
+            c => key.push(c),
+        }
+    }
+    if !key.is_empty() {
+        segments.push(Segment::Key(key));
+    }
+
+    if segments.is_empty() {
+        return Err("empty path".to_string());
+    }
+    Ok(segments)
+}
+
+/// Replace a leading `podSpec` with the location of the pod spec in `kind`
+fn expand_pod_spec(segments: Vec<Segment>, kind: ResourceKind) -> Vec<Segment> {
+    if segments.first() != Some(&Segment::Key("podSpec".to_string())) {
+        return segments;
+    }
+
+    let prefix: &[&str] = match kind {
+        ResourceKind::Pod => &["spec"],
+        ResourceKind::CronJob => &["spec", "jobTemplate", "spec", "template", "spec"],
+        _ => &["spec", "template", "spec"],
+    };
+    prefix
+        .iter()
+        .map(|k| Segment::Key(k.to_string()))
+        .chain(segments.into_iter().skip(1))
+        .collect()
+}
+
+/// Every field the path leads to, with its concrete path. A missing field
+/// yields `None` so that `exists` checks can report it; `[*]` over a missing
+/// or empty array yields nothing.
+fn resolve<'a>(object: &'a Value, segments: &[Segment]) -> Vec<(String, Option<&'a Value>)> {
+    fn walk<'a>(
+        value: Option<&'a Value>,
+        segments: &[Segment],
+        path: String,
+        out: &mut Vec<(String, Option<&'a Value>)>,
+    ) {
+        let Some((segment, rest)) = segments.split_first() else {
+            out.push((path, value));
+            return;
+        };
+
+        let child = |next: Option<&'a Value>| next.filter(|v| !v.is_null());
+        match segment {
+            Segment::Key(key) => {
+                let next = if key.contains(['.', '/']) {
+                    format!("{}[\"{}\"]", path, key)
+                } else if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                walk(child(value.and_then(|v| v.get(key))), rest, next, out);
+            }
+            Segment::Index(index) => {
+                let next = format!("{}[{}]", path, index);
+                walk(child(value.and_then(|v| v.get(index))), rest, next, out);
+            }
+            Segment::Each => {
+                let items = value.and_then(Value::as_array).into_iter().flatten();
+                for (i, item) in items.enumerate() {
+                    walk(Some(item), rest, format!("{}[{}]", path, i), out);
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(Some(object), segments, String::new(), &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn deployment() -> Value {
+        json!({
+            "metadata": {
+                "name": "web",
+                "namespace": "shop",
+                "labels": {"app.kubernetes.io/name": "web"}
+            },
+            "spec": {
+                "replicas": 1,
+                "template": {"spec": {"containers": [
+                    {
+                        "name": "app",
+                        "image": "registry:5000/shop/web:1.4.2",
+                        "resources": {"limits": {"memory": "256Mi"}},
+                        "readinessProbe": {"httpGet": {"path": "/ready"}}
+                    },
+                    {
+                        "name": "sidecar",
+                        "image": "envoy:latest",
+                        "securityContext": {"privileged": true}
+                    }
+                ]}}
+            }
+        })
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("metadata.labels[\"app.kubernetes.io/name\"]").unwrap(),
+            vec![
+                Segment::Key("metadata".to_string()),
+                Segment::Key("labels".to_string()),
+                Segment::Key("app.kubernetes.io/name".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_path("spec.containers[*].ports[0]").unwrap(),
+            vec![
+                Segment::Key("spec".to_string()),
+                Segment::Key("containers".to_string()),
+                Segment::Each,
+                Segment::Key("ports".to_string()),
+                Segment::Index(0),
+            ]
+        );
+        assert!(parse_path("spec.containers[*").is_err());
+        assert!(parse_path("spec[x]").is_err());
+    }
+
+    #[test]
+    fn test_builtin_rules() {
+        let rules = effective_rules(&RulesFile::default()).unwrap();
+        let objects = vec![(ResourceKind::Deployment, deployment())];
+        let findings = lint_objects(&objects, &rules);
+
+        let found: Vec<(&str, &str)> = findings
+            .iter()
+            .map(|f| (f.rule.as_str(), f.path.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "privileged",
+                    "spec.template.spec.containers[1].securityContext.privileged"
+                ),
+                ("image-latest", "spec.template.spec.containers[1].image"),
+                (
+                    "memory-limit",
+                    "spec.template.spec.containers[1].resources.limits.memory"
+                ),
+                (
+                    "readiness-probe",
+                    "spec.template.spec.containers[1].readinessProbe"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rules_file() {
+        let yaml = r#"
+builtin:
+  image-latest: false
+  single-replica: true
+rules:
+  - id: team-label
+    kinds: [deployment]
+    path: metadata.labels["team"]
+    operator: exists
+    severity: error
+    message: Workloads must carry a team label
+"#;
+        let file: RulesFile = serde_yaml::from_str(yaml).unwrap();
+        let rules = effective_rules(&file).unwrap();
+        assert!(!rules.iter().any(|r| r.id == "image-latest"));
+
+        let findings = lint_objects(&[(ResourceKind::Deployment, deployment())], &rules);
+        let team = findings.iter().find(|f| f.rule == "team-label").unwrap();
+        assert_eq!(team.severity, Severity::Error);
+        assert_eq!(team.path, "metadata.labels.team");
+        assert!(findings.iter().any(|f| f.rule == "single-replica"));
+
+        let toml = r#"
+[builtin]
+privileged = false
+
+[[rules]]
+id = "registry"
+path = "podSpec.containers[*].image"
+operator = "matches"
+value = "^registry:5000/"
+message = "Images must come from the internal registry"
+"#;
+        let file: RulesFile = toml::from_str(toml).unwrap();
+        let rules = effective_rules(&file).unwrap();
+        let findings = lint_objects(&[(ResourceKind::Deployment, deployment())], &rules);
+        assert!(!findings.iter().any(|f| f.rule == "privileged"));
+        let registry: Vec<_> = findings.iter().filter(|f| f.rule == "registry").collect();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let mut file = RulesFile::default();
+        file.builtin.insert("no-such-rule".to_string(), false);
+        assert!(effective_rules(&file).is_err());
+
+        let file: RulesFile = serde_yaml::from_str(
+            "rules:\n  - id: bad\n    path: spec.replicas\n    operator: greater_than\n    value: many\n    message: x\n",
+        )
+        .unwrap();
+        assert!(effective_rules(&file).is_err());
+    }
+
+    #[test]
+    fn test_controlled_objects_skipped() {
+        let mut pod = json!({
+            "metadata": {"name": "web-abc", "ownerReferences": [{"kind": "ReplicaSet", "controller": true}]},
+            "spec": {"containers": [{"name": "app", "image": "web"}]}
+        });
+        let rules = effective_rules(&RulesFile::default()).unwrap();
+        assert!(lint_objects(&[(ResourceKind::Pod, pod.clone())], &rules).is_empty());
+
+        pod["metadata"]["ownerReferences"] = json!([]);
+        let findings = lint_objects(&[(ResourceKind::Pod, pod)], &rules);
+        assert!(findings.iter().any(|f| f.rule == "image-tag"));
+    }
+}
@@ -2,72 +2,471 @@
 //!
 //! A command-line tool for exploring and discovering resources in Kubernetes clusters.
 //! Provides easy-to-use commands for listing services, pods, and understanding
-//! cluster topology and relationships.
-
-mod cache;
-mod cli;
-mod discovery;
-mod error;
-mod filtering;
-mod graph;
-mod output;
-mod progress;
-
-use clap::Parser;
-use cli::{Cli, Commands};
-use discovery::ServiceHealth;
-use filtering::{FilterCriteria, GroupBy, ResourceFilter, ResourceGrouper};
+//! cluster topology and relationships. The discovery itself lives in the `kdx`
+//! library; this binary parses arguments and prints results.
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, ValueEnum};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{ConfigMap, Node, Pod, Secret, Service};
+use kdx::cli::{
+    AnalysisCommands, Cli, Commands, NamespaceTarget, ReportCommands, ResourceCommands,
+    StateCommands, ToolCommands, ViewCommands,
+};
+use kdx::discovery::AllResources;
+use kdx::error::ExplorerError;
+use kdx::filtering::{FilterCriteria, GroupBy, ResourceFilter, ResourceGrouper};
+use kdx::{
+    age, annotations, checkpoint, cli, clusters, compare, compress, config, containers,
+    crd_versions, describe, diff, discovery, disk_cache, endpoints, env, envelope, events,
+    externals, extract, graph, history, hpa, ingress, jsonpath, kustomize, labels, lint, mesh,
+    move_check, namespaces, netpol, network, node_images, oomkills, output, owners, portforward,
+    probe, progress, quantity, rbac, redact, report, resource, rollout, scaling, schema, scope,
+    scorecard, secrets, simulate, snapshot, sort, spot, stability, stats, storage, storage_rollup,
+    stuck, template, tenant, terminating, timeline, tree, visibility, wait, warm, watch, zones,
+};
 use std::process;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Namespaces listed at once by scans across namespaces
+const DEFAULT_CONCURRENCY: usize = 20;
 
 #[tokio::main]
 async fn main() {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    if let Err(e) = run(cli).await {
+    if let Err(e) = run(cli, &matches).await {
         eprintln!("Error: {}", e);
-        process::exit(1);
+        let code = match e.downcast_ref::<ExplorerError>() {
+            Some(ExplorerError::Timeout(_)) => 2,
+            Some(ExplorerError::ConditionFailed(_)) => 3,
+            _ => 1,
+        };
+        process::exit(code);
+    }
+}
+
+/// Create a client for the given kubeconfig context, or the inferred default,
+/// with its API traffic counted in `stats`
+async fn create_client(
+    context: Option<&str>,
+    stats: &Arc<stats::RunStats>,
+) -> anyhow::Result<kube::Client> {
+    let config = discovery::load_config(context).await?;
+    Ok(stats.client(config)?)
+}
+
+/// Discovery engine settings of a run, for the cluster of `context`
+fn engine_builder(
+    cli: &Cli,
+    config: &config::Config,
+    excluded: &[String],
+    stats: &Arc<stats::RunStats>,
+    context: Option<&str>,
+) -> kdx::error::Result<discovery::DiscoveryEngineBuilder> {
+    let mut builder = discovery::DiscoveryEngine::builder()
+        .stats(stats.clone())
+        .warnings(cli.output.warnings());
+    if let Some(context) = context {
+        builder = builder.context(context);
+    }
+    if let Some(disk_cache) = disk_cache::DiskCache::open(context) {
+        builder = builder.disk_cache(disk_cache);
+    }
+    // Flags are applied after the config file so they win
+    for setting in config
+        .cache_ttls()?
+        .into_iter()
+        .chain(cli.cache_ttl.clone())
+    {
+        builder = match setting.kind {
+            Some(kind) => builder.resource_cache_ttl(kind, setting.ttl),
+            None => builder.cache_ttl(setting.ttl),
+        };
     }
+    if !cli.system {
+        builder = builder.namespace_scope(scope::NamespaceScope::excluding(excluded)?);
+    }
+    if let Some(fields) = &cli.field_selector {
+        builder = builder.pod_field_selector(fields.clone());
+    }
+    Ok(builder)
+}
+
+/// Subcommand names of a run, such as `cache warm`
+fn command_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut matches = matches;
+    while let Some((name, sub)) = matches.subcommand() {
+        names.push(name);
+        matches = sub;
+    }
+    names.join(" ")
 }
 
-async fn run(cli: Cli) -> anyhow::Result<()> {
-    // Load Kubernetes configuration
-    let config = if let Some(context) = &cli.context {
-        kube::Config::from_kubeconfig(&kube::config::KubeConfigOptions {
-            context: Some(context.clone()),
-            cluster: None,
-            user: None,
-        })
-        .await?
+/// Run the command parsed into `cli` from `matches`
+async fn run(mut cli: Cli, matches: &ArgMatches) -> anyhow::Result<()> {
+    if let Commands::State(StateCommands::Config { action }) = &cli.command {
+        return config_command(action, &cli);
+    }
+
+    let stats = Arc::new(
+        stats::RunStats::for_command(command_path(matches)).read_only(cli.read_only_verify),
+    );
+    let config = config::Config::load(cli.config.as_deref())?;
+
+    // Settings of the config file; flags win over them
+    let rules = config.namespace_rules(matches.subcommand_name().unwrap_or_default());
+    if cli.namespace.is_none() {
+        cli.namespace = rules.namespace;
+    }
+    if matches.value_source("output") != Some(ValueSource::CommandLine) {
+        if let Some(format) = config.output_format()? {
+            cli.output.format = format;
+        }
+    }
+    match cli.color.or(config.color) {
+        Some(cli::ColorMode::Always) => colored::control::set_override(true),
+        Some(cli::ColorMode::Never) => colored::control::set_override(false),
+        Some(cli::ColorMode::Auto) | None => {}
+    }
+    if let Some(selector) = cli.command.selector_mut() {
+        if let Some(preset) = selector.as_deref() {
+            *selector = Some(config.expand_selector(preset)?);
+        }
+    }
+    let concurrency = cli
+        .concurrency
+        .or(config.concurrency)
+        .unwrap_or(DEFAULT_CONCURRENCY);
+
+    if !cli.columns.is_empty() {
+        if !matches!(cli.output.format, cli::OutputFormat::Table) || cli.template_preset.is_some() {
+            return Err(ExplorerError::InvalidArgument(
+                "--columns prints a table and cannot be combined with --output or \
+                 --template-preset"
+                    .to_string(),
+            )
+            .into());
+        }
+        let columns = cli
+            .columns
+            .iter()
+            .map(|spec| jsonpath::Column::parse(spec))
+            .collect::<kdx::error::Result<Vec<_>>>()?;
+        cli.output.format = cli::OutputFormat::Columns(columns);
+    }
+    let sort_key = cli
+        .sort_by
+        .as_deref()
+        .map(sort::SortKey::parse)
+        .transpose()?;
+
+    if let Some(preset) = &cli.template_preset {
+        if !matches!(cli.output.format, cli::OutputFormat::Table) {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "--template-preset cannot be combined with --output {}",
+                cli.output
+            ))
+            .into());
+        }
+        cli.output.format = cli::OutputFormat::Template(template::Template::parse(
+            config.template_preset(preset)?,
+        )?);
+    }
+
+    if let Some(format) = cli.compress {
+        // Recorded snapshots are compressed regardless of the output format
+        let records_history = matches!(
+            cli.command,
+            Commands::State(StateCommands::History { .. })
+                | Commands::State(StateCommands::Snapshot { .. })
+        );
+        if matches!(cli.output.format, cli::OutputFormat::Table) && !records_history {
+            return Err(ExplorerError::InvalidArgument(
+                "--compress is not supported with table output".to_string(),
+            )
+            .into());
+        }
+        cli.output.compress = Some(format);
+    }
+
+    if cli.json_compact {
+        if !matches!(cli.output.format, cli::OutputFormat::Json) {
+            return Err(ExplorerError::InvalidArgument(
+                "--json-compact requires --output json".to_string(),
+            )
+            .into());
+        }
+        cli.output.json_compact = true;
+    }
+
+    if cli.envelope {
+        if !matches!(
+            cli.output.format,
+            cli::OutputFormat::Json | cli::OutputFormat::Yaml
+        ) {
+            return Err(ExplorerError::InvalidArgument(
+                "--envelope requires --output json or yaml".to_string(),
+            )
+            .into());
+        }
+        if cli.stream || cli.watch {
+            return Err(ExplorerError::InvalidArgument(
+                "--envelope cannot be combined with --stream or --watch".to_string(),
+            )
+            .into());
+        }
+        let query = envelope::Query {
+            command: command_path(matches),
+            context: cli.context().map(str::to_string),
+            namespace: cli.namespace.clone(),
+            selector: cli.command.selector_mut().and_then(|s| s.clone()),
+            limit: cli.limit,
+            as_of: cli.as_of,
+        };
+        cli.output.envelope = Some(envelope::Settings::new(query, Default::default()));
+    }
+
+    // A file loaded with `kdx snapshot load` answers instead of the cluster
+    let loaded = match (&cli.command, snapshot::ActiveSnapshot::open()) {
+        (Commands::State(StateCommands::Snapshot { .. }), _) | (_, Err(_)) => None,
+        (_, Ok(active)) => active.get()?,
+    };
+    let from_snapshot = cli.as_of.is_some() || loaded.is_some();
+
+    let contexts = if cli.all_contexts {
+        clusters::all_contexts()?
     } else {
-        kube::Config::infer().await?
+        cli.contexts.clone()
     };
+    if cli.fans_out() {
+        if loaded.is_some() {
+            return Err(ExplorerError::InvalidArgument(
+                "several contexts cannot be queried while a snapshot is loaded; \
+                 run `kdx snapshot unload` first"
+                    .to_string(),
+            )
+            .into());
+        }
+        return fan_out_command(
+            &cli,
+            &contexts,
+            &config,
+            &rules.exclude,
+            &stats,
+            concurrency,
+            sort_key.as_ref(),
+        )
+        .await;
+    }
+
+    // Manifest totals need no cluster
+    if let Commands::Tools(ToolCommands::Simulate {
+        from_manifests,
+        against_cluster: false,
+        namespace,
+    }) = &cli.command
+    {
+        let ns = namespace
+            .as_deref()
+            .or(cli.namespace.as_deref())
+            .unwrap_or("default");
+        let workloads = simulate::load_manifests(from_manifests, ns)?;
+        output::print_simulation(&simulate::offline(workloads), &cli.output)?;
+        return Ok(());
+    }
+
+    // Two snapshot files are compared without a cluster
+    if let Commands::Tools(ToolCommands::Diff {
+        from,
+        to,
+        namespace,
+    }) = &cli.command
+    {
+        let (from, to) = (diff::DiffSide::parse(from), diff::DiffSide::parse(to));
+        if from.is_snapshot() && to.is_snapshot() {
+            let diff =
+                diff::diff_sides(None, &from, &to, namespace.as_deref(), cli.page_size).await?;
+            output::print_diff(&diff, &cli.output)?;
+            return Ok(());
+        }
+    }
+
+    // Loading and unloading a snapshot file needs no cluster
+    if let Commands::State(StateCommands::Snapshot { action }) = &cli.command {
+        match action {
+            cli::SnapshotAction::Load { file } => {
+                let snapshot = snapshot::ActiveSnapshot::open()?.set(file)?;
+                println!(
+                    "Loaded snapshot of {} taken at {}; run `kdx snapshot unload` to use the cluster again",
+                    snapshot.context.as_deref().unwrap_or("the current context"),
+                    snapshot.taken_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+                return Ok(());
+            }
+            cli::SnapshotAction::Unload => {
+                match snapshot::ActiveSnapshot::open()?.clear()? {
+                    Some(path) => println!("Unloaded {}", path.display()),
+                    None => println!("No snapshot is loaded"),
+                }
+                return Ok(());
+            }
+            cli::SnapshotAction::Save { .. } => {}
+        }
+    }
 
     // Create Kubernetes client
-    let client = kube::Client::try_from(config)?;
+    let started = Instant::now();
+    let mut builder = engine_builder(&cli, &config, &rules.exclude, &stats, cli.context())?;
+    if let Some(path) = &loaded {
+        if !supports_as_of(&cli.command) || cli.as_of.is_some() || cli.watch {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "{} is loaded, which answers all, services, pods, deployments, statefulsets, \
+                 daemonsets, configmaps, secrets, describe, topology and graph without \
+                 --as-of or --watch; run `kdx snapshot unload` to use the cluster",
+                path.display()
+            ))
+            .into());
+        }
+        let snapshot = snapshot::read(path)?;
+        let message = format!(
+            "Answering from snapshot {} taken at {}",
+            path.display(),
+            snapshot.taken_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        eprintln!("{}", message);
+        cli.output.warnings().warn(message);
+        builder = builder.snapshot(snapshot);
+    }
+    let discovery = builder.build().await?;
+    stats.record_phase("connect", started);
+
+    if cli.watch {
+        return watch_command(&cli, &discovery).await;
+    }
 
-    // Create discovery engine
-    let discovery = discovery::DiscoveryEngine::new(client);
+    // Answer from recorded history instead of the live cluster
+    let discovery = match cli.as_of {
+        Some(as_of) => {
+            if !supports_as_of(&cli.command) {
+                return Err(ExplorerError::InvalidArgument(
+                    "--as-of is supported by all, services, pods, deployments, statefulsets, \
+                     daemonsets, configmaps, secrets, describe, topology and graph"
+                        .to_string(),
+                )
+                .into());
+            }
+            let store = history::HistoryStore::open(cli.context())?;
+            let snapshot = store.load_nearest(as_of)?;
+            let message = format!(
+                "Answering from snapshot taken at {}",
+                snapshot.taken_at.format("%Y-%m-%d %H:%M:%S UTC")
+            );
+            eprintln!("{}", message);
+            discovery.warnings().warn(message);
+            discovery.with_snapshot(snapshot)
+        }
+        None => discovery,
+    };
 
     // Execute command
+    let context = cli.context().map(str::to_string);
+    let started = Instant::now();
     match cli.command {
-        Commands::Services {
+        Commands::Resources(ResourceCommands::All {
             namespace,
             all_namespaces,
             selector,
+            include_types,
+            exclude_types,
             group_by,
-        } => {
-            let mut services = if all_namespaces {
-                // Use concurrent discovery for all namespaces
-                let progress = if cli.show_progress {
-                    Some(crate::progress::ProgressTracker::new(true, None))
-                } else {
-                    None
-                };
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let criteria = FilterCriteria {
+                label_selector: selector,
+                include_types,
+                exclude_types,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+
+            let progress = progress::ProgressTracker::new_spinner(
+                cli.show_progress,
+                "Discovering resources...",
+            );
+            let all = discovery
+                .list_all(ns, cli.limit, cli.page_size, |kind| {
+                    ResourceFilter::includes_kind(kind, &criteria)
+                })
+                .await?;
+            progress.finish_and_clear();
+
+            let mut all = AllResources {
+                services: ResourceFilter::filter_services(all.services, &criteria),
+                deployments: ResourceFilter::filter_deployments(all.deployments, &criteria),
+                statefulsets: ResourceFilter::filter_statefulsets(all.statefulsets, &criteria),
+                daemonsets: ResourceFilter::filter_daemonsets(all.daemonsets, &criteria),
+                pods: ResourceFilter::filter_pods(all.pods, &criteria),
+                configmaps: ResourceFilter::filter_configmaps(all.configmaps, &criteria),
+                secrets: ResourceFilter::filter_secrets(all.secrets, &criteria),
+            };
 
-                let namespaces = discovery.get_all_namespaces().await?;
+            if let Some(group_by_str) = group_by {
+                let grouped = ResourceGrouper::group_all(all, &parse_group_by(&group_by_str));
+                output::print_grouped_resources(&grouped, &cli.output)?;
+            } else {
+                sort::sort_items(&mut all.services, sort_key.as_ref())?;
+                sort::sort_items(&mut all.deployments, sort_key.as_ref())?;
+                sort::sort_items(&mut all.statefulsets, sort_key.as_ref())?;
+                sort::sort_items(&mut all.daemonsets, sort_key.as_ref())?;
+                sort::sort_items(&mut all.pods, sort_key.as_ref())?;
+                sort::sort_items(&mut all.configmaps, sort_key.as_ref())?;
+                sort::sort_items(&mut all.secrets, sort_key.as_ref())?;
+                output::print_all(&all, &cli.output)?;
+            }
+        }
+        Commands::Resources(ResourceCommands::Services {
+            namespace,
+            all_namespaces,
+            selector,
+            group_by,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+            let mut services = if !matches!(target, NamespaceTarget::One(_)) {
+                // Use concurrent discovery for all or several namespaces
+                // Created even without --show-progress to collect namespace failures
+                let progress = progress::ProgressTracker::new(cli.show_progress, None);
+
+                // Only full scans keep a checkpoint for --resume
+                let (namespaces, checkpoint) = match target {
+                    NamespaceTarget::Many(namespaces) => (namespaces, None),
+                    _ => (
+                        discovery.namespaces_in_scope().await?,
+                        Some(checkpoint::ScanCheckpoint::open(
+                            &checkpoint::checkpoint_key(
+                                context.as_deref(),
+                                "services",
+                                selector.as_deref(),
+                            ),
+                            cli.resume,
+                        )),
+                    ),
+                };
                 let result = discovery
                     .list_services_concurrent(
                         namespaces,
@@ -75,22 +474,22 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                         cli.limit,
                         cli.page_size,
                         true, // Use cache
-                        20,   // Optimal concurrency for most clusters
-                        progress.as_ref(),
+                        concurrency,
+                        Some(&progress),
+                        checkpoint.as_ref(),
                     )
                     .await?;
 
-                if let Some(progress) = progress {
-                    progress.finish_and_clear();
-                }
+                progress.finish_and_clear();
+                progress.report_failures();
 
                 result
             } else {
                 // Single namespace discovery
-                let ns = namespace.as_deref().or(cli.namespace.as_deref());
+                let ns = target.namespace();
 
                 let progress = if cli.show_progress {
-                    Some(crate::progress::ProgressTracker::new_spinner(
+                    Some(progress::ProgressTracker::new_spinner(
                         true,
                         "Discovering services...",
                     ))
@@ -118,6 +517,10 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             // Apply filtering
             let criteria = FilterCriteria {
                 label_selector: selector,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
                 ..Default::default()
             };
             services = ResourceFilter::filter_services(services, &criteria);
@@ -125,15 +528,32 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             // Use streaming output for large datasets if requested
             if cli.stream
                 && matches!(
-                    cli.output,
-                    crate::cli::OutputFormat::Json | crate::cli::OutputFormat::Yaml
+                    cli.output.format,
+                    cli::OutputFormat::Json | cli::OutputFormat::Yaml
                 )
             {
                 if group_by.is_some() {
                     eprintln!("Warning: Grouping is not supported with streaming output. Falling back to regular output.");
+                    sort::sort_items(&mut services, sort_key.as_ref())?;
                     output::print_services(&services, &cli.output)?;
                 } else {
-                    output::stream_services(services.into_iter(), std::io::stdout(), &cli.output)?;
+                    match cli.output.compress {
+                        Some(format) => {
+                            let mut compressor =
+                                compress::Compressor::new(format, std::io::stdout())?;
+                            output::stream_services(
+                                services.into_iter(),
+                                &mut compressor,
+                                &cli.output,
+                            )?;
+                            compressor.finish()?;
+                        }
+                        None => output::stream_services(
+                            services.into_iter(),
+                            std::io::stdout(),
+                            &cli.output,
+                        )?,
+                    }
                 }
             } else {
                 // Apply grouping if specified
@@ -149,26 +569,52 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                     );
                     output::print_grouped_resources(&grouped, &cli.output)?;
                 } else {
+                    sort::sort_items(&mut services, sort_key.as_ref())?;
                     output::print_services(&services, &cli.output)?;
                 }
             }
         }
-        Commands::Pods {
+        Commands::Resources(ResourceCommands::Pods {
             namespace,
             selector,
             all_namespaces,
             status,
+            qos,
+            show_qos,
+            show_mesh,
             group_by,
-        } => {
-            let mut pods = if all_namespaces {
-                // Use concurrent discovery for all namespaces
-                let progress = if cli.show_progress {
-                    Some(crate::progress::ProgressTracker::new(true, None))
-                } else {
-                    None
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+            let mut pods = if !matches!(target, NamespaceTarget::One(_)) {
+                // Use concurrent discovery for all or several namespaces
+                // Created even without --show-progress to collect namespace failures
+                let progress = progress::ProgressTracker::new(cli.show_progress, None);
+
+                // Only full scans keep a checkpoint for --resume
+                let (namespaces, checkpoint) = match target {
+                    NamespaceTarget::Many(namespaces) => (namespaces, None),
+                    _ => (
+                        discovery.namespaces_in_scope().await?,
+                        Some(checkpoint::ScanCheckpoint::open(
+                            &checkpoint::checkpoint_key(
+                                context.as_deref(),
+                                "pods",
+                                // Field-selected scans resume apart from full ones
+                                match &cli.field_selector {
+                                    Some(fields) => Some(format!(
+                                        "{};{}",
+                                        selector.as_deref().unwrap_or_default(),
+                                        fields
+                                    )),
+                                    None => selector.clone(),
+                                }
+                                .as_deref(),
+                            ),
+                            cli.resume,
+                        )),
+                    ),
                 };
-
-                let namespaces = discovery.get_all_namespaces().await?;
                 let result = discovery
                     .list_pods_concurrent(
                         namespaces,
@@ -176,22 +622,22 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                         cli.limit,
                         cli.page_size,
                         true, // Use cache
-                        20,   // Optimal concurrency for most clusters
-                        progress.as_ref(),
+                        concurrency,
+                        Some(&progress),
+                        checkpoint.as_ref(),
                     )
                     .await?;
 
-                if let Some(progress) = progress {
-                    progress.finish_and_clear();
-                }
+                progress.finish_and_clear();
+                progress.report_failures();
 
                 result
             } else {
                 // Single namespace discovery
-                let ns = namespace.as_deref().or(cli.namespace.as_deref());
+                let ns = target.namespace();
 
                 let progress = if cli.show_progress {
-                    Some(crate::progress::ProgressTracker::new_spinner(
+                    Some(progress::ProgressTracker::new_spinner(
                         true,
                         "Discovering pods...",
                     ))
@@ -220,6 +666,11 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             let criteria = FilterCriteria {
                 label_selector: selector,
                 status_filter: status,
+                qos_class: qos,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
                 ..Default::default()
             };
             pods = ResourceFilter::filter_pods(pods, &criteria);
@@ -237,24 +688,92 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                 );
                 output::print_grouped_resources(&grouped, &cli.output)?;
             } else {
-                output::print_pods(&pods, &cli.output)?;
+                sort::sort_items(&mut pods, sort_key.as_ref())?;
+                output::print_pods(&pods, &cli.output, show_qos, show_mesh)?;
+            }
+        }
+        Commands::Resources(ResourceCommands::BarePods {
+            namespace,
+            all_namespaces,
+            selector,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let pods = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let pods = discovery
+                        .list_pods_concurrent(
+                            namespaces,
+                            selector.as_deref(),
+                            cli.limit,
+                            cli.page_size,
+                            true, // Use cache
+                            concurrency,
+                            Some(&progress),
+                            None,
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    pods
+                }
+                target => {
+                    discovery
+                        .list_pods_with_options(
+                            target.namespace(),
+                            selector.as_deref(),
+                            cli.limit,
+                            cli.page_size,
+                            true, // Use cache
+                        )
+                        .await?
+                }
+            };
+
+            let criteria = FilterCriteria {
+                bare_only: true,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut pods = ResourceFilter::filter_pods(pods, &criteria);
+
+            // Other formats print the empty result, so scripts can parse it
+            if pods.is_empty() && matches!(cli.output.format, cli::OutputFormat::Table) {
+                println!("No bare pods found");
+            } else {
+                // Oldest first unless --sort-by is given: long-lived bare pods
+                // are the likeliest to be forgotten
+                pods.sort_by_key(|pod| pod.created_at);
+                sort::sort_items(&mut pods, sort_key.as_ref())?;
+                let grouped = ResourceGrouper::group_resources(
+                    vec![],
+                    pods,
+                    vec![],
+                    vec![],
+                    vec![],
+                    &GroupBy::Namespace,
+                );
+                output::print_grouped_resources(&grouped, &cli.output)?;
             }
         }
-        Commands::Deployments {
+        Commands::Resources(ResourceCommands::Deployments {
             namespace,
             all_namespaces,
             selector,
             status,
             group_by,
-        } => {
-            let ns = if all_namespaces {
-                None
-            } else {
-                namespace.as_deref().or(cli.namespace.as_deref())
-            };
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
 
-            let progress = if cli.show_progress {
-                Some(crate::progress::ProgressTracker::new_spinner(
+            // Several namespaces get a progress bar of their own below
+            let progress = if cli.show_progress && !matches!(target, NamespaceTarget::Many(_)) {
+                Some(progress::ProgressTracker::new_spinner(
                     true,
                     "Discovering deployments...",
                 ))
@@ -262,14 +781,44 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                 None
             };
 
-            let mut deployments = discovery
-                .list_deployments_with_options(
-                    ns,
-                    cli.limit,
-                    cli.page_size,
-                    true, // Use cache
-                )
-                .await?;
+            let mut deployments = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let (limit, page_size) = (cli.limit, cli.page_size);
+                    let mut deployments = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "deployments",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move {
+                                engine
+                                    .list_deployments_with_options(
+                                        Some(&ns),
+                                        limit,
+                                        page_size,
+                                        true,
+                                    )
+                                    .await
+                            },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    deployments.truncate(limit.unwrap_or(usize::MAX));
+                    deployments
+                }
+                target => {
+                    discovery
+                        .list_deployments_with_options(
+                            target.namespace(),
+                            cli.limit,
+                            cli.page_size,
+                            true, // Use cache
+                        )
+                        .await?
+                }
+            };
 
             if let Some(progress) = progress {
                 progress.finish_and_clear();
@@ -279,6 +828,10 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             let criteria = FilterCriteria {
                 label_selector: selector,
                 status_filter: status,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
                 ..Default::default()
             };
             deployments = ResourceFilter::filter_deployments(deployments, &criteria);
@@ -296,50 +849,269 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                 );
                 output::print_grouped_resources(&grouped, &cli.output)?;
             } else {
+                sort::sort_items(&mut deployments, sort_key.as_ref())?;
                 output::print_deployments(&deployments, &cli.output)?;
             }
         }
-        Commands::Statefulsets {
+        Commands::Resources(ResourceCommands::Statefulsets {
             namespace,
             all_namespaces,
-        } => {
-            let ns = if all_namespaces {
-                None
-            } else {
-                namespace.as_deref().or(cli.namespace.as_deref())
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let statefulsets = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let statefulsets = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "statefulsets",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move { engine.list_statefulsets(Some(&ns)).await },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    statefulsets
+                }
+                target => discovery.list_statefulsets(target.namespace()).await?,
             };
-
-            let statefulsets = discovery.list_statefulsets(ns).await?;
+            let criteria = FilterCriteria {
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut statefulsets = ResourceFilter::filter_statefulsets(statefulsets, &criteria);
+            sort::sort_items(&mut statefulsets, sort_key.as_ref())?;
             output::print_statefulsets(&statefulsets, &cli.output)?;
         }
-        Commands::Daemonsets {
+        Commands::Resources(ResourceCommands::Daemonsets {
             namespace,
             all_namespaces,
-        } => {
-            let ns = if all_namespaces {
-                None
-            } else {
-                namespace.as_deref().or(cli.namespace.as_deref())
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let daemonsets = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let daemonsets = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "daemonsets",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move { engine.list_daemonsets(Some(&ns)).await },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    daemonsets
+                }
+                target => discovery.list_daemonsets(target.namespace()).await?,
             };
-
-            let daemonsets = discovery.list_daemonsets(ns).await?;
+            let criteria = FilterCriteria {
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut daemonsets = ResourceFilter::filter_daemonsets(daemonsets, &criteria);
+            sort::sort_items(&mut daemonsets, sort_key.as_ref())?;
             output::print_daemonsets(&daemonsets, &cli.output)?;
         }
-        Commands::Configmaps {
+        Commands::Resources(ResourceCommands::Replicasets {
+            namespace,
+            all_namespaces,
+            history,
+        }) => {
+            if let Some(deployment) = history {
+                if namespace.len() > 1 {
+                    return Err(ExplorerError::InvalidArgument(
+                        "--history takes a single namespace".to_string(),
+                    )
+                    .into());
+                }
+                let ns = namespace
+                    .first()
+                    .map(String::as_str)
+                    .or(cli.namespace.as_deref())
+                    .unwrap_or("default");
+
+                let replicasets = discovery.list_replicasets(Some(ns)).await?;
+                let history = rollout::rollout_history(&deployment, &replicasets);
+                if history.is_empty() {
+                    return Err(ExplorerError::ResourceNotFound {
+                        kind: "Deployment".to_string(),
+                        name: deployment,
+                        namespace: ns.to_string(),
+                    }
+                    .into());
+                }
+                output::print_rollout_history(&history, &cli.output)?;
+                return Ok(());
+            }
+
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let replicasets = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let replicasets = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "replicasets",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move { engine.list_replicasets(Some(&ns)).await },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    replicasets
+                }
+                target => discovery.list_replicasets(target.namespace()).await?,
+            };
+            let criteria = FilterCriteria {
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut replicasets = ResourceFilter::filter_replicasets(replicasets, &criteria);
+            sort::sort_items(&mut replicasets, sort_key.as_ref())?;
+            output::print_replicasets(&replicasets, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Containers {
+            namespace,
+            all_namespaces,
+            image,
+            role,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let found = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let found = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "containers",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move {
+                                containers::list_containers(engine.client(), Some(&ns)).await
+                            },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    found
+                }
+                target => {
+                    containers::list_containers(discovery.client(), target.namespace()).await?
+                }
+            };
+            let mut found = containers::filter_containers(found, image.as_deref(), role.as_deref());
+            ResourceFilter::retain_names(&mut found, cli.name_filter.as_ref(), |c| &c.pod);
+            sort::sort_items(&mut found, sort_key.as_ref())?;
+            output::print_containers(&found, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Hpas {
+            namespace,
+            all_namespaces,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let mut hpas = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let hpas = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "hpas",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move {
+                                hpa::find_autoscalers(engine.client(), Some(&ns)).await
+                            },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    hpas
+                }
+                target => hpa::find_autoscalers(discovery.client(), target.namespace()).await?,
+            };
+            ResourceFilter::retain_names(&mut hpas, cli.name_filter.as_ref(), |h| &h.name);
+            sort::sort_items(&mut hpas, sort_key.as_ref())?;
+            output::print_hpas(&hpas, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Events {
+            namespace,
+            all_namespaces,
+            since,
+            event_type,
+        }) => {
+            let since = since
+                .map(|since| {
+                    chrono::Duration::from_std(since)
+                        .map(|since| chrono::Utc::now() - since)
+                        .map_err(|_| {
+                            ExplorerError::InvalidArgument("--since is too long".to_string())
+                        })
+                })
+                .transpose()?;
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let events = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let events = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "events",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move {
+                                events::list_events(engine.client(), Some(&ns), None).await
+                            },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    events
+                }
+                target => events::list_events(discovery.client(), target.namespace(), None).await?,
+            };
+            let mut events = events::filter_events(events, since, event_type.as_deref());
+            ResourceFilter::retain_names(&mut events, cli.name_filter.as_ref(), |e| &e.object_name);
+            sort::sort_items(&mut events, sort_key.as_ref())?;
+            output::print_events(&events, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Configmaps {
             namespace,
             all_namespaces,
             selector,
             group_by,
             unused,
-        } => {
-            let ns = if all_namespaces {
-                None
-            } else {
-                namespace.as_deref().or(cli.namespace.as_deref())
-            };
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
 
-            let progress = if cli.show_progress {
-                Some(crate::progress::ProgressTracker::new_spinner(
+            // Several namespaces get a progress bar of their own below
+            let progress = if cli.show_progress && !matches!(target, NamespaceTarget::Many(_)) {
+                Some(progress::ProgressTracker::new_spinner(
                     true,
                     "Discovering configmaps...",
                 ))
@@ -347,14 +1119,39 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                 None
             };
 
-            let mut configmaps = discovery
-                .list_configmaps_with_options(
-                    ns,
-                    cli.limit,
-                    cli.page_size,
-                    true, // Use cache
-                )
-                .await?;
+            let mut configmaps = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let (limit, page_size) = (cli.limit, cli.page_size);
+                    let mut configmaps = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "configmaps",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move {
+                                engine
+                                    .list_configmaps_with_options(Some(&ns), limit, page_size, true)
+                                    .await
+                            },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    configmaps.truncate(limit.unwrap_or(usize::MAX));
+                    configmaps
+                }
+                target => {
+                    discovery
+                        .list_configmaps_with_options(
+                            target.namespace(),
+                            cli.limit,
+                            cli.page_size,
+                            true, // Use cache
+                        )
+                        .await?
+                }
+            };
 
             if let Some(progress) = progress {
                 progress.finish_and_clear();
@@ -363,6 +1160,10 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             // Apply filtering
             let criteria = FilterCriteria {
                 label_selector: selector,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
                 ..Default::default()
             };
             configmaps = ResourceFilter::filter_configmaps(configmaps, &criteria);
@@ -378,28 +1179,49 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                 let grouped = ResourceGrouper::group_configmaps(configmaps, &group_by);
                 output::print_grouped_configmaps(&grouped, &cli.output)?;
             } else {
+                sort::sort_items(&mut configmaps, sort_key.as_ref())?;
                 output::print_configmaps(&configmaps, &cli.output)?;
             }
         }
-        Commands::Secrets {
+        Commands::Resources(ResourceCommands::Secrets {
             namespace,
             all_namespaces,
             selector,
             group_by,
             unused,
             secret_type,
-        } => {
-            let ns = if all_namespaces {
-                None
-            } else {
-                namespace.as_deref().or(cli.namespace.as_deref())
+            summary,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+            let accounts_target = summary.then(|| target.clone());
+
+            let mut secrets = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let progress = progress::ProgressTracker::new(cli.show_progress, None);
+                    let secrets = discovery
+                        .list_in_namespaces(
+                            namespaces,
+                            "secrets",
+                            concurrency,
+                            Some(&progress),
+                            |engine, ns| async move { engine.list_secrets(Some(&ns)).await },
+                        )
+                        .await?;
+                    progress.finish_and_clear();
+                    progress.report_failures();
+                    secrets
+                }
+                target => discovery.list_secrets(target.namespace()).await?,
             };
 
-            let mut secrets = discovery.list_secrets(ns).await?;
-
             // Apply filtering
             let criteria = FilterCriteria {
                 label_selector: selector,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
                 ..Default::default()
             };
             secrets = ResourceFilter::filter_secrets(secrets, &criteria);
@@ -414,114 +1236,1308 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                 secrets.retain(|s| s.used_by.is_empty());
             }
 
-            // Apply grouping if specified
-            if let Some(group_by_str) = group_by {
+            if let Some(target) = accounts_target {
+                // Registry credentials listed by a ServiceAccount count as used
+                let accounts = match target {
+                    NamespaceTarget::Many(namespaces) => {
+                        discovery
+                            .list_in_namespaces(
+                                namespaces,
+                                "serviceaccounts",
+                                concurrency,
+                                None,
+                                |engine, ns| async move {
+                                    engine.list_service_accounts(Some(&ns)).await
+                                },
+                            )
+                            .await?
+                    }
+                    target => discovery.list_service_accounts(target.namespace()).await?,
+                };
+                let summary = secrets::summarize(&secrets, &accounts);
+                output::print_secret_summary(&summary, &cli.output)?;
+            } else if let Some(group_by_str) = group_by {
                 let group_by = parse_group_by(&group_by_str);
                 let grouped = ResourceGrouper::group_secrets(secrets, &group_by);
                 output::print_grouped_secrets(&grouped, &cli.output)?;
             } else {
+                sort::sort_items(&mut secrets, sort_key.as_ref())?;
                 output::print_secrets(&secrets, &cli.output)?;
             }
         }
-        Commands::Crds {
-            selector,
-            group_by,
-            with_instances,
-            show_versions,
-        } => {
-            let mut crds = discovery.list_crds().await?;
-
-            // Apply filtering
-            let criteria = FilterCriteria {
-                label_selector: selector,
-                ..Default::default()
+        Commands::Resources(ResourceCommands::Serviceaccounts {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
             };
-            crds = ResourceFilter::filter_crds(crds, &criteria);
-
-            // Filter for CRDs with instances if requested
-            if with_instances {
-                crds.retain(|crd| crd.instance_count > 0);
-            }
 
-            // Apply grouping if specified
-            if let Some(group_by_str) = group_by {
-                let group_by = parse_group_by(&group_by_str);
-                let grouped = ResourceGrouper::group_crds(crds, &group_by);
-                output::print_grouped_crds(&grouped, &cli.output, show_versions)?;
-            } else {
-                output::print_crds(&crds, &cli.output, show_versions)?;
-            }
+            let (mut accounts, bindings) = tokio::try_join!(
+                discovery.list_service_accounts(ns),
+                rbac::list_bindings(&discovery, ns)
+            )?;
+            rbac::attach_roles(&mut accounts, &bindings);
+            ResourceFilter::retain_names(&mut accounts, cli.name_filter.as_ref(), |a| &a.name);
+            sort::sort_items(&mut accounts, sort_key.as_ref())?;
+            output::print_service_accounts(&accounts, &cli.output)?;
         }
-        Commands::CustomResources {
-            crd_name,
+        Commands::Resources(ResourceCommands::Roles {
             namespace,
             all_namespaces,
-            selector,
-            group_by,
-        } => {
+        }) => {
             let ns = if all_namespaces {
                 None
             } else {
                 namespace.as_deref().or(cli.namespace.as_deref())
             };
 
-            let mut custom_resources = discovery.list_custom_resources(&crd_name, ns).await?;
-
-            // Apply filtering
-            let criteria = FilterCriteria {
-                label_selector: selector,
-                ..Default::default()
-            };
-            custom_resources = ResourceFilter::filter_custom_resources(custom_resources, &criteria);
+            let (mut roles, bindings) =
+                tokio::try_join!(discovery.list_roles(ns), discovery.list_role_bindings(ns))?;
+            rbac::attach_subjects(&mut roles, &bindings);
+            ResourceFilter::retain_names(&mut roles, cli.name_filter.as_ref(), |r| &r.name);
+            sort::sort_items(&mut roles, sort_key.as_ref())?;
+            output::print_roles(&roles, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Clusterroles) => {
+            // RoleBindings in any namespace may grant a ClusterRole
+            let (mut roles, bindings) = tokio::try_join!(
+                discovery.list_cluster_roles(),
+                rbac::list_bindings(&discovery, None)
+            )?;
+            rbac::attach_subjects(&mut roles, &bindings);
+            ResourceFilter::retain_names(&mut roles, cli.name_filter.as_ref(), |r| &r.name);
+            sort::sort_items(&mut roles, sort_key.as_ref())?;
+            output::print_roles(&roles, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Rolebindings {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let mut bindings = discovery.list_role_bindings(ns).await?;
+            ResourceFilter::retain_names(&mut bindings, cli.name_filter.as_ref(), |b| &b.name);
+            sort::sort_items(&mut bindings, sort_key.as_ref())?;
+            output::print_role_bindings(&bindings, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Clusterrolebindings) => {
+            let mut bindings = discovery.list_cluster_role_bindings().await?;
+            ResourceFilter::retain_names(&mut bindings, cli.name_filter.as_ref(), |b| &b.name);
+            sort::sort_items(&mut bindings, sort_key.as_ref())?;
+            output::print_role_bindings(&bindings, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::WhoCan {
+            verb,
+            resource,
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let grants = rbac::who_can(&discovery, &verb, &resource, ns).await?;
+            output::print_who_can(&grants, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Nodes {
+            role,
+            condition,
+            selector,
+        }) => {
+            let nodes = discovery.list_nodes().await?;
+            let criteria = FilterCriteria {
+                label_selector: selector,
+                node_role: role,
+                node_condition: condition,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut nodes = ResourceFilter::filter_nodes(nodes, &criteria);
+            sort::sort_items(&mut nodes, sort_key.as_ref())?;
+            output::print_nodes(&nodes, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Pvs {
+            status,
+            storage_class,
+        }) => {
+            let mut volumes = discovery.list_persistent_volumes().await?;
+            let mut claims = discovery.list_persistent_volume_claims(None).await?;
+            let mounts = discovery.list_claim_mounts(None).await?;
+            storage::attach_mounts(&mut volumes, &mut claims, &mounts);
+
+            let criteria = FilterCriteria {
+                status_filter: status,
+                storage_class,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut volumes = ResourceFilter::filter_persistent_volumes(volumes, &criteria);
+            sort::sort_items(&mut volumes, sort_key.as_ref())?;
+            output::print_persistent_volumes(&volumes, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Pvcs {
+            namespace,
+            all_namespaces,
+            selector,
+            status,
+            storage_class,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+
+            let (mut claims, mounts) = match target {
+                NamespaceTarget::Many(namespaces) => {
+                    let mut claims = Vec::new();
+                    let mut mounts = storage::ClaimMounts::new();
+                    for ns in &namespaces {
+                        claims.extend(discovery.list_persistent_volume_claims(Some(ns)).await?);
+                        mounts.extend(discovery.list_claim_mounts(Some(ns)).await?);
+                    }
+                    (claims, mounts)
+                }
+                target => (
+                    discovery
+                        .list_persistent_volume_claims(target.namespace())
+                        .await?,
+                    discovery.list_claim_mounts(target.namespace()).await?,
+                ),
+            };
+            storage::attach_mounts(&mut [], &mut claims, &mounts);
+
+            let criteria = FilterCriteria {
+                label_selector: selector,
+                status_filter: status,
+                storage_class,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut claims = ResourceFilter::filter_persistent_volume_claims(claims, &criteria);
+            sort::sort_items(&mut claims, sort_key.as_ref())?;
+            output::print_persistent_volume_claims(&claims, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Crds {
+            selector,
+            group_by,
+            with_instances,
+            show_versions,
+        }) => {
+            let mut crds = discovery.list_crds().await?;
+
+            // Apply filtering
+            let criteria = FilterCriteria {
+                label_selector: selector,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            crds = ResourceFilter::filter_crds(crds, &criteria);
+
+            // Filter for CRDs with instances if requested
+            if with_instances {
+                crds.retain(|crd| crd.instance_count > 0);
+            }
 
             // Apply grouping if specified
             if let Some(group_by_str) = group_by {
                 let group_by = parse_group_by(&group_by_str);
-                let grouped = ResourceGrouper::group_custom_resources(custom_resources, &group_by);
-                output::print_grouped_custom_resources(&grouped, &cli.output)?;
+                let grouped = ResourceGrouper::group_crds(crds, &group_by);
+                output::print_grouped_crds(&grouped, &cli.output, show_versions)?;
+            } else {
+                sort::sort_items(&mut crds, sort_key.as_ref())?;
+                output::print_crds(&crds, &cli.output, show_versions)?;
+            }
+        }
+        Commands::Resources(ResourceCommands::CustomResources {
+            crd_name,
+            namespace,
+            all_namespaces,
+            selector,
+            group_by,
+            max_per_crd,
+        }) => {
+            let target =
+                NamespaceTarget::resolve(&namespace, all_namespaces, cli.namespace.as_deref());
+            let criteria = FilterCriteria {
+                label_selector: selector,
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let progress = progress::ProgressTracker::new(cli.show_progress, None);
+
+            if let Some(crd_name) = crd_name {
+                let mut custom_resources = match target {
+                    NamespaceTarget::Many(namespaces) => {
+                        discovery
+                            .list_in_namespaces(
+                                namespaces,
+                                "custom resources",
+                                concurrency,
+                                Some(&progress),
+                                |engine, ns| {
+                                    let crd_name = crd_name.clone();
+                                    async move {
+                                        engine.list_custom_resources(&crd_name, Some(&ns)).await
+                                    }
+                                },
+                            )
+                            .await?
+                    }
+                    target => {
+                        discovery
+                            .list_custom_resources(&crd_name, target.namespace())
+                            .await?
+                    }
+                };
+                progress.finish_and_clear();
+                progress.report_failures();
+
+                // Cluster-scoped instances come back for every namespace
+                let mut seen = std::collections::HashSet::new();
+                custom_resources.retain(|cr| seen.insert((cr.namespace.clone(), cr.name.clone())));
+
+                // Apply filtering
+                custom_resources =
+                    ResourceFilter::filter_custom_resources(custom_resources, &criteria);
+
+                // Apply grouping if specified
+                if let Some(group_by_str) = group_by {
+                    let group_by = parse_group_by(&group_by_str);
+                    let grouped =
+                        ResourceGrouper::group_custom_resources(custom_resources, &group_by);
+                    output::print_grouped_custom_resources(&grouped, &cli.output)?;
+                } else {
+                    sort::sort_items(&mut custom_resources, sort_key.as_ref())?;
+                    output::print_custom_resources(&custom_resources, &cli.output)?;
+                }
+            } else {
+                // Every CRD at once, with the instances shown per CRD capped
+                let mut all = match target {
+                    NamespaceTarget::Many(namespaces) => {
+                        let mut merged: Vec<discovery::CrdInstances> = Vec::new();
+                        for ns in &namespaces {
+                            for crd in discovery
+                                .list_all_custom_resources(Some(ns), concurrency)
+                                .await?
+                            {
+                                // Cluster-scoped instances come back for every namespace
+                                match merged.iter_mut().find(|m| m.crd_name == crd.crd_name) {
+                                    Some(existing) if crd.scope == "Namespaced" => {
+                                        existing.items.extend(crd.items)
+                                    }
+                                    Some(_) => {}
+                                    None => merged.push(crd),
+                                }
+                            }
+                        }
+                        merged
+                    }
+                    target => {
+                        discovery
+                            .list_all_custom_resources(target.namespace(), concurrency)
+                            .await?
+                    }
+                };
+                for crd in &mut all {
+                    let items = std::mem::take(&mut crd.items);
+                    crd.items = ResourceFilter::filter_custom_resources(items, &criteria);
+                    crd.instance_count = crd.items.len();
+                }
+
+                if let Some(group_by_str) = group_by {
+                    let group_by = parse_group_by(&group_by_str);
+                    let items = all.into_iter().flat_map(|crd| crd.items).collect();
+                    let grouped = ResourceGrouper::group_custom_resources(items, &group_by);
+                    output::print_grouped_custom_resources(&grouped, &cli.output)?;
+                } else {
+                    for crd in &mut all {
+                        crd.items.truncate(max_per_crd);
+                    }
+                    sort::sort_items(&mut all, sort_key.as_ref())?;
+                    output::print_crd_instances(&all, &cli.output)?;
+                }
+            }
+        }
+        Commands::Analysis(AnalysisCommands::Annotations {
+            namespace,
+            all_namespaces,
+            kind,
+            key,
+            value_regex,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let value_regex = value_regex
+                .map(|pattern| regex::Regex::new(&pattern))
+                .transpose()
+                .map_err(|e| ExplorerError::InvalidArgument(e.to_string()))?;
+
+            let objects = discovery.list_annotated_objects(ns, &kind).await?;
+            if key.is_some() || value_regex.is_some() {
+                let matches =
+                    annotations::find_annotated(&objects, key.as_deref(), value_regex.as_ref());
+                output::print_annotation_matches(&matches, &cli.output)?;
+            } else {
+                let inventory = annotations::annotation_inventory(&objects);
+                output::print_annotation_inventory(&inventory, &cli.output)?;
+            }
+        }
+        Commands::Analysis(AnalysisCommands::Labels { action }) => match action {
+            cli::LabelsAction::Values { key, namespace } => {
+                let ns = namespace.as_deref();
+                let discovered = discovery.discover_listable_resources().await?;
+                for failure in &discovered.failures {
+                    eprintln!(
+                        "Warning: skipping {}: {}",
+                        failure.group_version, failure.error
+                    );
+                }
+
+                // Only objects carrying the key are sent back
+                let params = kube::api::ListParams::default().labels(&key);
+                let objects: Vec<_> = discovery
+                    .list_object_metadata_with(ns, &discovered.resources, &params)
+                    .await?
+                    .into_iter()
+                    .filter(|(_, metadata)| ns.is_none() || metadata.namespace.as_deref() == ns)
+                    .collect();
+
+                let values = labels::label_values(&key, &objects);
+                output::print_label_values(&values, &cli.output)?;
+            }
+        },
+        Commands::Analysis(AnalysisCommands::Stuck {
+            namespace,
+            all_namespaces,
+            all_kinds,
+            stuck_for,
+        }) => {
+            let criteria = FilterCriteria {
+                older_than: cli.older_than,
+                newer_than: cli.newer_than,
+                ..Default::default()
+            };
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let resources = if all_kinds {
+                let discovered = discovery.discover_listable_resources().await?;
+                for failure in &discovered.failures {
+                    eprintln!(
+                        "Warning: skipping {}: {}",
+                        failure.group_version, failure.error
+                    );
+                }
+                discovered.resources
+            } else {
+                stuck::default_resources()
+            };
+
+            let now = chrono::Utc::now();
+            let mut objects: Vec<stuck::StuckObject> = discovery
+                .list_object_metadata(ns, &resources)
+                .await?
+                .iter()
+                .filter(|(_, metadata)| {
+                    // Cluster-scoped objects are listed cluster-wide; keep them
+                    // only when no namespace filter is in effect
+                    ns.is_none() || metadata.namespace.as_deref() == ns
+                })
+                .filter(|(_, metadata)| {
+                    let created_at = metadata.creation_timestamp.as_ref().map(|t| t.0);
+                    ResourceFilter::matches_age(created_at, &criteria)
+                })
+                .filter_map(|(kind, metadata)| stuck::stuck_object(kind, metadata, now, stuck_for))
+                .collect();
+            objects.sort_by_key(|o| o.deletion_timestamp);
+
+            output::print_stuck_objects(&objects, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Namespaces) => {
+            // Pod and service counts sort largest first, as before --sort-by
+            // applied to every list
+            let count_sort = cli
+                .sort_by
+                .as_deref()
+                .and_then(|field| cli::NamespaceSort::from_str(field, true).ok());
+            let progress = progress::ProgressTracker::new(cli.show_progress, None);
+            let mut summaries = namespaces::find_namespaces(
+                &discovery,
+                count_sort.unwrap_or(cli::NamespaceSort::Name),
+                &progress,
+            )
+            .await?;
+            progress.finish_and_clear();
+            progress.report_failures();
+            ResourceFilter::retain_names(&mut summaries, cli.name_filter.as_ref(), |n| &n.name);
+            if count_sort.is_none() {
+                sort::sort_items(&mut summaries, sort_key.as_ref())?;
+            }
+            output::print_namespaces(&summaries, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::NamespaceStuck { namespace }) => {
+            let diagnosis = terminating::diagnose_namespace(&discovery, &namespace).await?;
+            output::print_namespace_diagnosis(&diagnosis, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Mesh {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let pods = discovery
+                .list_pods_with_options(ns, None, cli.limit, cli.page_size, true)
+                .await?;
+            let coverage = mesh::mesh_coverage(&pods);
+            output::print_mesh_coverage(&coverage, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Zones {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let nodes = discovery.list_nodes().await?;
+            let pods = discovery
+                .list_pods_with_options(ns, None, cli.limit, cli.page_size, true)
+                .await?;
+            let services = discovery
+                .list_services_with_options(ns, None, cli.limit, cli.page_size, true)
+                .await?;
+
+            let report = zones::zone_report(&nodes, &pods, &services);
+            output::print_zone_report(&report, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Spot {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let nodes = discovery.list_nodes().await?;
+            let pods = discovery
+                .list_pods_with_options(ns, None, cli.limit, cli.page_size, true)
+                .await?;
+
+            let exposure = spot::spot_exposure(&nodes, &pods);
+            output::print_spot_exposure(&exposure, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::NodeImages { node }) => {
+            let mut report = node_images::fetch_node_images(&discovery).await?;
+            if let Some(node) = &node {
+                report.nodes.retain(|n| &n.node == node);
+                if report.nodes.is_empty() {
+                    return Err(ExplorerError::ResourceNotFound {
+                        kind: "Node".to_string(),
+                        name: node.clone(),
+                        namespace: String::new(),
+                    }
+                    .into());
+                }
+                report.unused.retain(|i| i.nodes.contains(node));
+            }
+            output::print_node_images(&report, node.is_some(), &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Externals {
+            namespace,
+            all_namespaces,
+            category,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let mut externals = externals::find_externals(&discovery, ns).await?;
+            if let Some(category) = &category {
+                externals.retain(|d| d.category.eq_ignore_ascii_case(category));
+            }
+            output::print_externals(&externals, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::IngressCheck {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let issues = ingress::find_ingress_issues(&discovery, ns).await?;
+            output::print_ingress_issues(&issues, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::MoveCheck {
+            target,
+            namespace,
+            to,
+        }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+            let check = move_check::check_workload_move(&discovery, ns, &target, &to).await?;
+            output::print_move_check(&check, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::NetworkCheck {
+            service_cidr,
+            node_port_range,
+        }) => {
+            let settings = network::NetworkSettings {
+                service_cidrs: service_cidr,
+                node_port_range,
+            };
+            let issues = network::find_network_issues(&discovery, &settings).await?;
+            output::print_network_issues(&issues, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Networkpolicies {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let mut policies = netpol::list_network_policies(&discovery, ns).await?;
+            ResourceFilter::retain_names(&mut policies, cli.name_filter.as_ref(), |p| &p.name);
+            sort::sort_items(&mut policies, sort_key.as_ref())?;
+            output::print_network_policies(&policies, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::CanReach {
+            from,
+            to,
+            namespace,
+            to_namespace,
+            port,
+            protocol,
+        }) => {
+            let from_ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+            let to_ns = to_namespace.as_deref().unwrap_or(from_ns);
+
+            // A bare name is a pod; kind/name picks the kind
+            let target = |target: &str| match target.parse::<resource::ObjectRef>() {
+                Ok(object) => Ok(object),
+                Err(_) if !target.contains('/') => Ok(resource::ObjectRef {
+                    kind: resource::ResourceKind::Pod,
+                    name: target.to_string(),
+                }),
+                Err(e) => Err(e),
+            };
+            let from = netpol::endpoint(&discovery, from_ns, &target(&from)?).await?;
+            let to = netpol::endpoint(&discovery, to_ns, &target(&to)?).await?;
+            let port = port
+                .map(|port| netpol::PortQuery::resolve(&port, &protocol, &to))
+                .transpose()?;
+
+            let reachability =
+                netpol::check_reachability(&discovery, &from, &to, port.as_ref()).await?;
+            output::print_reachability(&reachability, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::ScalingEvents {
+            namespace,
+            all_namespaces,
+            window,
+            from_history,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+            let window = chrono::Duration::from_std(window)
+                .map_err(|_| ExplorerError::InvalidArgument("--window is too long".to_string()))?;
+            let now = chrono::Utc::now();
+
+            let snapshots = if from_history {
+                history::HistoryStore::open(context.as_deref())?.load_between(now - window, now)?
+            } else {
+                Vec::new()
+            };
+            let changes =
+                scaling::find_scaling_events(&discovery, ns, now - window, &snapshots).await?;
+            output::print_scaling_events(&changes, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Oomkills {
+            namespace,
+            all_namespaces,
+            window,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+            let window = chrono::Duration::from_std(window)
+                .map_err(|_| ExplorerError::InvalidArgument("--window is too long".to_string()))?;
+
+            let summaries =
+                oomkills::find_oom_kills(&discovery, ns, chrono::Utc::now() - window).await?;
+            output::print_oom_kills(&summaries, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Stability {
+            namespace,
+            all_namespaces,
+            window,
+            from_history,
+            top,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+            let window = chrono::Duration::from_std(window)
+                .map_err(|_| ExplorerError::InvalidArgument("--window is too long".to_string()))?;
+            let now = chrono::Utc::now();
+
+            let workloads_created = stability::workload_creation_times(&discovery, ns).await?;
+            let mut ranked = if from_history {
+                let store = history::HistoryStore::open(context.as_deref())?;
+                let snapshots = store.load_between(now - window, now)?;
+                if snapshots.len() < 2 {
+                    return Err(ExplorerError::InvalidArgument(format!(
+                        "{} snapshots recorded in the window; at least two are needed",
+                        snapshots.len()
+                    ))
+                    .into());
+                }
+                let observations: Vec<_> = snapshots
+                    .iter()
+                    .map(|snapshot| {
+                        let pods = snapshot
+                            .pods
+                            .iter()
+                            .filter(|p| ns.is_none_or(|ns| ns == p.namespace))
+                            .filter_map(stability::PodObservation::from_info)
+                            .collect();
+                        (snapshot.taken_at, pods)
+                    })
+                    .collect();
+                stability::score_history(&observations, &workloads_created)
+            } else {
+                let pods = stability::observe_live_pods(&discovery, ns).await?;
+                stability::score_live(&pods, &workloads_created, now, window)
+            };
+            if let Some(top) = top {
+                ranked.truncate(top);
+            }
+            output::print_stability(&ranked, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::DanglingPvs) => {
+            let volumes = discovery.list_persistent_volumes().await?;
+            let claims = discovery.list_persistent_volume_claims(None).await?;
+
+            let report = storage::dangling_volumes(volumes, &claims);
+            output::print_dangling_volumes(&report, &cli.output)?;
+        }
+        Commands::Analysis(AnalysisCommands::Storage {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let rollup = storage_rollup::find_storage_rollup(&discovery, ns).await?;
+            output::print_storage_rollup(&rollup, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Wait {
+            target,
+            condition,
+            timeout,
+            interval,
+            namespace,
+        }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            wait::wait_for(
+                &discovery.session(),
+                &target,
+                ns,
+                &condition,
+                timeout,
+                interval,
+            )
+            .await?;
+            println!("{} condition met", target);
+        }
+        Commands::Tools(ToolCommands::PortForward {
+            service,
+            ports,
+            namespace,
+        }) => {
+            if cli.read_only_verify {
+                // Forwarding connects to pods, which read-only mode refuses
+                return Err(ExplorerError::InvalidArgument(
+                    "port-forward is not available with --read-only-verify".to_string(),
+                )
+                .into());
+            }
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            portforward::port_forward(discovery.client(), &service, ns, ports).await?;
+        }
+        Commands::Tools(ToolCommands::ProbeMatrix {
+            services,
+            namespace,
+            timeout,
+            http,
+        }) => {
+            if cli.read_only_verify {
+                // Forwarding connects to pods, which read-only mode refuses
+                return Err(ExplorerError::InvalidArgument(
+                    "probe-matrix is not available with --read-only-verify".to_string(),
+                )
+                .into());
+            }
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let results =
+                probe::probe_services(discovery.client(), &services, ns, timeout, http.as_deref())
+                    .await?;
+            output::print_probe_matrix(&results, &cli.output)?;
+
+            let failed = results
+                .iter()
+                .filter(|r| r.status != probe::ProbeStatus::Ok)
+                .count();
+            if failed > 0 {
+                return Err(ExplorerError::ConditionFailed(format!(
+                    "{} of {} probes failed",
+                    failed,
+                    results.len()
+                ))
+                .into());
+            }
+        }
+        Commands::Tools(ToolCommands::Endpoints { action }) => match action {
+            cli::EndpointsAction::Watch {
+                service,
+                namespace,
+                duration,
+            } => {
+                let ns = namespace
+                    .as_deref()
+                    .or(cli.namespace.as_deref())
+                    .unwrap_or("default");
+
+                let changes = endpoints::watch_endpoints(
+                    &discovery.session(),
+                    &service,
+                    ns,
+                    duration,
+                    |change| output::print_endpoint_change(change, &cli.output),
+                )
+                .await?;
+                if matches!(cli.output.format, cli::OutputFormat::Table) {
+                    output::print_endpoint_churn(&endpoints::churn(&changes));
+                }
+            }
+        },
+        Commands::Tools(ToolCommands::StorageVersions) => {
+            let mut migrations = Vec::new();
+            for crd in discovery.list_crd_definitions().await? {
+                if crd_versions::stale_versions(&crd).is_empty() {
+                    continue;
+                }
+                let instances = match crd_versions::storage_version(&crd) {
+                    Some(version) => discovery.list_crd_instance_metadata(&crd, version).await?,
+                    None => None,
+                };
+                migrations.extend(crd_versions::assess(&crd, instances.as_deref()));
+            }
+            migrations.sort_by(|a, b| {
+                b.blocking()
+                    .cmp(&a.blocking())
+                    .then_with(|| a.crd_name.cmp(&b.crd_name))
+            });
+            output::print_storage_migrations(&migrations, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Validate {
+            crd_name,
+            namespace,
+            all_namespaces,
+            version,
+        }) => {
+            let ns = if all_namespaces {
+                None
             } else {
-                output::print_custom_resources(&custom_resources, &cli.output)?;
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let crd = discovery.get_crd(&crd_name).await?;
+            let version = version
+                .or_else(|| {
+                    crd.spec
+                        .versions
+                        .iter()
+                        .find(|v| v.storage)
+                        .map(|v| v.name.clone())
+                })
+                .ok_or_else(|| anyhow::anyhow!("CRD {} has no storage version", crd_name))?;
+            let schema = schema::version_schema(&crd, &version).ok_or_else(|| {
+                anyhow::anyhow!("CRD {} version {} has no schema", crd_name, version)
+            })?;
+
+            let objects = discovery.list_dynamic_objects(&crd, &version, ns).await?;
+            let total = objects.len();
+            let mut results = Vec::new();
+            for object in objects {
+                let value = serde_json::to_value(&object)?;
+                let violations = schema::validate_object(&value, schema);
+                if violations.is_empty() {
+                    continue;
+                }
+                results.push(schema::InstanceValidation {
+                    name: object.metadata.name.clone().unwrap_or_default(),
+                    namespace: object.metadata.namespace.clone(),
+                    age: age::object_age(&object.metadata),
+                    violations,
+                });
+            }
+
+            output::print_instance_validations(&results, total, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Env { target, namespace }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let spec = discovery.get_pod_template(&target, ns).await?;
+            let sources = discovery.get_env_sources(&spec, ns).await;
+            let env = env::resolve_env(&spec, &sources);
+            output::print_container_env(&env, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Revisions {
+            target,
+            namespace,
+            diff,
+        }) => {
+            if target.kind != resource::ResourceKind::Deployment {
+                return Err(ExplorerError::InvalidArgument(format!(
+                    "revisions are tracked for deployments, not {}s",
+                    target.kind
+                ))
+                .into());
+            }
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            match diff.as_deref() {
+                Some(&[from, to]) => {
+                    let diff =
+                        rollout::revision_diff(&discovery, &target.name, ns, from, to).await?;
+                    output::print_revision_diff(&diff, &cli.output)?;
+                }
+                _ => {
+                    let replicasets = discovery.list_replicasets(Some(ns)).await?;
+                    let history = rollout::rollout_history(&target.name, &replicasets);
+                    if history.is_empty() {
+                        return Err(ExplorerError::ResourceNotFound {
+                            kind: "Deployment".to_string(),
+                            name: target.name,
+                            namespace: ns.to_string(),
+                        }
+                        .into());
+                    }
+                    output::print_rollout_history(&history, &cli.output)?;
+                }
+            }
+        }
+        Commands::Tools(ToolCommands::Owners { target, namespace }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let chain = owners::ownership_chain(discovery.client(), &target, ns).await?;
+            output::print_ownership_chain(&chain, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Tree {
+            kind,
+            name,
+            namespace,
+        }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let (kind, name) = match (name, kind.split_once('/')) {
+                (Some(name), None) => (kind.clone(), name),
+                (None, Some((kind, name))) if !name.is_empty() && !name.contains('/') => {
+                    (kind.to_string(), name.to_string())
+                }
+                _ => {
+                    return Err(ExplorerError::InvalidArgument(format!(
+                        "expected KIND NAME or KIND/NAME, got '{}'",
+                        kind
+                    ))
+                    .into())
+                }
+            };
+            let tree = tree::owner_tree(&discovery, &kind, &name, ns).await?;
+            output::print_owner_tree(&tree, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Compare {
+            context_a,
+            context_b,
+            namespace,
+        }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let engine_a =
+                discovery::DiscoveryEngine::new(create_client(Some(&context_a), &stats).await?)
+                    .with_stats(stats.clone())
+                    .with_warnings(cli.output.warnings());
+            let engine_b =
+                discovery::DiscoveryEngine::new(create_client(Some(&context_b), &stats).await?)
+                    .with_stats(stats.clone())
+                    .with_warnings(cli.output.warnings());
+            let (inventory_a, inventory_b) = tokio::try_join!(
+                engine_a.get_namespace_inventory(ns),
+                engine_b.get_namespace_inventory(ns)
+            )?;
+
+            let diff = compare::NamespaceDiff {
+                namespace: ns.to_string(),
+                differences: compare::diff_inventories(&inventory_a, &inventory_b),
+                context_a,
+                context_b,
+            };
+            output::print_namespace_diff(&diff, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Diff {
+            from,
+            to,
+            namespace,
+        }) => {
+            let diff = diff::diff_sides(
+                Some(&discovery),
+                &diff::DiffSide::parse(&from),
+                &diff::DiffSide::parse(&to),
+                namespace.as_deref(),
+                cli.page_size,
+            )
+            .await?;
+            output::print_diff(&diff, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Extract {
+            namespace,
+            output_dir,
+            include_secrets,
+        }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let summary =
+                extract::extract_namespace(&discovery, ns, &output_dir, include_secrets).await?;
+            output::print_extract_summary(&summary, &cli.output)?;
+        }
+        Commands::Tools(ToolCommands::Simulate {
+            from_manifests,
+            namespace,
+            ..
+        }) => {
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let workloads = simulate::load_manifests(&from_manifests, ns)?;
+            let simulation = simulate::simulate_against_cluster(&discovery, workloads).await?;
+            output::print_simulation(&simulation, &cli.output)?;
+            if !simulation.fits() {
+                return Err(ExplorerError::ConditionFailed(
+                    "the manifests do not fit the cluster".to_string(),
+                )
+                .into());
+            }
+        }
+        Commands::Tools(ToolCommands::KustomizeDrift {
+            namespace,
+            all_namespaces,
+        }) => {
+            let ns = if all_namespaces {
+                None
+            } else {
+                namespace.as_deref().or(cli.namespace.as_deref())
+            };
+
+            let drift = kustomize::find_kustomize_drift(&discovery, ns).await?;
+            output::print_kustomize_drift(&drift, &cli.output)?;
+        }
+        Commands::Reports(ReportCommands::Report {
+            namespace,
+            sections,
+            format,
+            redact,
+        }) => {
+            let ns = namespace.as_deref().or(cli.namespace.as_deref());
+
+            let mut report = report::build_report(&discovery, &sections, ns).await?;
+            if redact {
+                redact::Redactor::open()?.redact_report(&mut report);
+            }
+            output::print_report(&report, &format, &cli.output)?;
+        }
+        Commands::Reports(ReportCommands::TenantReport { label, format }) => {
+            kdx::filtering::LabelSelector::parse(&label).map_err(ExplorerError::InvalidArgument)?;
+
+            let report = tenant::fetch_tenant_report(&discovery, &label).await?;
+            for failure in &report.skipped {
+                eprintln!(
+                    "Warning: skipping {}: {}",
+                    failure.group_version, failure.error
+                );
+            }
+            output::print_tenant_report(&report, &format, &cli.output)?;
+        }
+        Commands::Reports(ReportCommands::Visibility { as_sa, namespace }) => {
+            let namespaces = if namespace.is_empty() {
+                discovery.namespaces_in_scope().await?
+            } else {
+                namespace
+            };
+
+            let mut config = discovery::load_config(context.as_deref()).await?;
+            visibility::impersonate(&mut config, &as_sa);
+            let client = stats.client(config)?;
+
+            let report = visibility::check_visibility(&client, &as_sa, namespaces).await?;
+            output::print_visibility(&report, &cli.output)?;
+        }
+        Commands::Reports(ReportCommands::Lint {
+            namespace,
+            all_namespaces,
+            rules,
+            list_rules,
+        }) => {
+            let rules_file = match rules {
+                Some(path) => lint::RulesFile::load(&path)?,
+                None => lint::RulesFile::default(),
+            };
+            let rules = lint::effective_rules(&rules_file)?;
+
+            if list_rules {
+                output::print_lint_rules(&rules, &cli.output)?;
+            } else {
+                let ns = if all_namespaces {
+                    None
+                } else {
+                    namespace.as_deref().or(cli.namespace.as_deref())
+                };
+
+                let mut kinds: Vec<resource::ResourceKind> = Vec::new();
+                for kind in rules.iter().flat_map(lint::rule_kinds) {
+                    if !kinds.contains(&kind) {
+                        kinds.push(kind);
+                    }
+                }
+
+                let objects = discovery.list_objects_json(ns, &kinds).await?;
+                let findings = lint::lint_objects(&objects, &rules);
+                output::print_lint_findings(&findings, &cli.output)?;
             }
         }
-        Commands::Describe { service, namespace } => {
+        Commands::Reports(ReportCommands::Scorecard { target, namespace }) => {
+            let card = match target.split_once('/') {
+                Some((kind, name)) => {
+                    let kind: resource::ResourceKind = kind.parse()?;
+                    if !matches!(
+                        kind,
+                        resource::ResourceKind::Deployment
+                            | resource::ResourceKind::StatefulSet
+                            | resource::ResourceKind::DaemonSet
+                    ) {
+                        return Err(ExplorerError::InvalidArgument(format!(
+                            "cannot score a {}: expected a deployment, statefulset or daemonset",
+                            kind
+                        ))
+                        .into());
+                    }
+                    let ns = namespace
+                        .as_deref()
+                        .or(cli.namespace.as_deref())
+                        .unwrap_or("default");
+                    scorecard::fetch_scorecard(&discovery, ns, Some((kind, name))).await?
+                }
+                None => scorecard::fetch_scorecard(&discovery, &target, None).await?,
+            };
+            output::print_scorecard(&card, target.contains('/'), &cli.output)?;
+        }
+        Commands::Views(ViewCommands::Describe {
+            target,
+            name,
+            namespace,
+            deep,
+        }) => {
             let ns = namespace
                 .as_deref()
                 .or(cli.namespace.as_deref())
                 .unwrap_or("default");
-            let service_info = discovery.describe_service(&service, ns).await?;
-            output::print_service_description(&service_info, &cli.output)?;
-
-            // Also show ingress information if available
-            let ingress_routes = discovery
-                .discover_ingress_for_service(&service, ns)
-                .await
-                .unwrap_or_default();
-            if !ingress_routes.is_empty() {
-                output::print_ingress_info(&ingress_routes, &cli.output)?;
-
-                // Also show configuration information if available
-                let (configmaps, secrets) = discovery
-                    .discover_service_configuration(&service, ns)
-                    .await
-                    .unwrap_or_default();
-                if !configmaps.is_empty() || !secrets.is_empty() {
-                    output::print_configuration_info(&configmaps, &secrets, &cli.output)?;
-
-                    // Also show health information
-                    let health = discovery
-                        .check_service_health(&service, ns)
+
+            // A bare name is a service; kind/name or kind and name pick the kind
+            let (kind, name) = match (name, target.split_once('/')) {
+                (Some(name), _) => (target.clone(), name),
+                (None, Some((kind, name))) if !name.is_empty() && !name.contains('/') => {
+                    (kind.to_string(), name.to_string())
+                }
+                (None, Some(_)) => {
+                    return Err(ExplorerError::InvalidArgument(format!(
+                        "invalid resource name in '{}'",
+                        target
+                    ))
+                    .into())
+                }
+                (None, None) => ("service".to_string(), target.clone()),
+            };
+            let kind = describe::resolve_kind(&discovery, &kind).await?;
+            if deep
+                && !matches!(
+                    kind,
+                    describe::DescribeKind::Builtin(resource::ResourceKind::Service)
+                )
+            {
+                return Err(ExplorerError::InvalidArgument(format!(
+                    "--deep only applies to services, not {}",
+                    name
+                ))
+                .into());
+            }
+            match kind {
+                describe::DescribeKind::Builtin(resource::ResourceKind::Service) if deep => {
+                    let description = discovery.describe_service_deep(&name, ns).await?;
+                    output::print_deep_service_description(&description, &cli.output)?;
+                }
+                describe::DescribeKind::Builtin(resource::ResourceKind::Service) => {
+                    let service = name;
+                    let service_info = discovery.describe_service(&service, ns).await?;
+                    output::print_service_description(&service_info, &cli.output)?;
+
+                    // Also show ingress information if available
+                    let ingress_routes = discovery
+                        .discover_ingress_for_service(&service, ns)
                         .await
-                        .unwrap_or_else(|_| ServiceHealth {
-                            service_name: service.clone(),
-                            namespace: ns.to_string(),
-                            overall_healthy: false,
-                            checked_at: "Error checking health".to_string(),
-                        });
-                    output::print_health_info(&health, &cli.output)?;
+                        .unwrap_or_default();
+                    if !ingress_routes.is_empty() {
+                        output::print_ingress_info(&ingress_routes, &cli.output)?;
+                    }
+                }
+                // Other kinds are described with what the snapshot keeps
+                kind if from_snapshot => {
+                    let description = describe::describe_recorded(&discovery, &kind, &name, ns)?;
+                    output::print_object_description(&description, &cli.output)?;
+                }
+                describe::DescribeKind::Builtin(resource::ResourceKind::Pod) => {
+                    let description = discovery.describe_pod(&name, ns).await?;
+                    output::print_pod_description(&description, &cli.output)?;
+                }
+                describe::DescribeKind::Builtin(resource::ResourceKind::StatefulSet) => {
+                    let statefulset = discovery.describe_statefulset(&name, ns).await?;
+                    output::print_statefulset_description(&statefulset, &cli.output)?;
+                }
+                describe::DescribeKind::Builtin(kind) => {
+                    let description = describe::describe_object(
+                        &discovery,
+                        &kind.api_resource(),
+                        &kube::discovery::Scope::Namespaced,
+                        &name,
+                        ns,
+                    )
+                    .await?;
+                    output::print_object_description(&description, &cli.output)?;
                 }
+                describe::DescribeKind::Other(resource, scope) => {
+                    let description =
+                        describe::describe_object(&discovery, &resource, &scope, &name, ns).await?;
+                    output::print_object_description(&description, &cli.output)?;
+                }
+            }
+        }
+        Commands::Reports(ReportCommands::Timeline { target, namespace }) => {
+            if target.kind != resource::ResourceKind::Pod {
+                return Err(ExplorerError::InvalidArgument(format!(
+                    "cannot show a timeline for {}: only pods are supported",
+                    target
+                ))
+                .into());
             }
+            let ns = namespace
+                .as_deref()
+                .or(cli.namespace.as_deref())
+                .unwrap_or("default");
+
+            let timeline = timeline::pod_timeline(discovery.client(), &target.name, ns).await?;
+            output::print_pod_timeline(&timeline, &cli.output)?;
         }
-        Commands::Topology { service, namespace } => {
+        Commands::Views(ViewCommands::Topology { service, namespace }) => {
             let ns = namespace
                 .as_deref()
                 .or(cli.namespace.as_deref())
@@ -529,29 +2545,198 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             let topology = discovery.analyze_service_topology(&service, ns).await?;
             output::print_service_topology(&topology, &cli.output)?;
         }
-        Commands::Graph {
+        Commands::Views(ViewCommands::Graph {
             namespace,
             format,
             include_pods,
+            include_custom_resources,
+            include_storage,
+            include_externals,
+            include_workloads,
+            include_config,
+            include_network_policies,
             highlight,
-        } => {
+            group_by,
+            helm_release,
+            max_nodes,
+            max_edges,
+        }) => {
+            if include_custom_resources && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--include-custom-resources cannot be answered from a snapshot: snapshots do not record custom resources".to_string(),
+                )
+                .into());
+            }
+            if include_storage && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--include-storage cannot be answered from a snapshot: snapshots do not record volumes".to_string(),
+                )
+                .into());
+            }
+            if include_externals && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--include-externals cannot be answered from a snapshot: snapshots do not record endpoints or pod environments".to_string(),
+                )
+                .into());
+            }
+            if (include_workloads || include_config) && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--include-workloads and --include-config cannot be answered from a snapshot: snapshots do not record pod templates".to_string(),
+                )
+                .into());
+            }
+            if include_network_policies && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--include-network-policies cannot be answered from a snapshot: snapshots do not record network policies".to_string(),
+                )
+                .into());
+            }
+            if group_by.is_some() && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--group-by cannot be answered from a snapshot: snapshots do not record pod environments".to_string(),
+                )
+                .into());
+            }
+            if helm_release.is_some() && from_snapshot {
+                return Err(ExplorerError::InvalidArgument(
+                    "--helm-release cannot be answered from a snapshot: snapshots do not record Helm releases".to_string(),
+                )
+                .into());
+            }
             let ns = namespace.as_deref();
-            let service_graph =
-                graph::generate_service_graph(&discovery, ns, include_pods, highlight.as_deref())
-                    .await?;
+            if let Some(release) = helm_release {
+                let ns = ns.or(cli.namespace.as_deref()).unwrap_or("default");
+                let release_graph = graph::generate_release_graph(&discovery, ns, &release).await?;
+
+                match format {
+                    cli::GraphFormat::Dot => {
+                        println!("{}", release_graph.to_dot());
+                    }
+                    cli::GraphFormat::Svg => {
+                        println!("{}", release_graph.to_svg()?);
+                    }
+                    cli::GraphFormat::Mermaid => {
+                        println!("{}", release_graph.to_mermaid());
+                    }
+                }
+            } else if let Some(group_by) = group_by {
+                let grouped_graph = graph::generate_grouped_graph(
+                    &discovery,
+                    ns,
+                    &parse_group_by(&group_by),
+                    highlight.as_deref(),
+                )
+                .await?;
+
+                match format {
+                    cli::GraphFormat::Dot => {
+                        println!("{}", grouped_graph.to_dot());
+                    }
+                    cli::GraphFormat::Svg => {
+                        println!("{}", grouped_graph.to_svg()?);
+                    }
+                    cli::GraphFormat::Mermaid => {
+                        println!("{}", grouped_graph.to_mermaid());
+                    }
+                }
+            } else {
+                let mut service_graph = graph::generate_service_graph(
+                    &discovery,
+                    ns,
+                    include_pods,
+                    include_custom_resources,
+                    include_storage,
+                    include_externals,
+                    include_workloads,
+                    include_config,
+                    include_network_policies,
+                    highlight.as_deref(),
+                )
+                .await?;
+                let (nodes, edges) = (service_graph.node_count(), service_graph.edge_count());
+                let collapsed = service_graph.limit(max_nodes, max_edges);
+                if !collapsed.is_empty() {
+                    let namespaces: Vec<String> = collapsed
+                        .iter()
+                        .map(|c| format!("{} ({} objects)", c.namespace, c.objects))
+                        .collect();
+                    eprintln!(
+                        "Warning: the graph has {} nodes and {} edges, over --max-nodes {} or --max-edges {}; collapsed namespaces: {}",
+                        nodes,
+                        edges,
+                        max_nodes,
+                        max_edges,
+                        namespaces.join(", ")
+                    );
+                }
+
+                match format {
+                    cli::GraphFormat::Dot => {
+                        println!("{}", service_graph.to_dot());
+                    }
+                    cli::GraphFormat::Svg => {
+                        println!("{}", service_graph.to_svg()?);
+                    }
+                    cli::GraphFormat::Mermaid => {
+                        println!("{}", service_graph.to_mermaid());
+                    }
+                }
+            }
+        }
+
+        Commands::State(StateCommands::History { action }) => {
+            let store = history::HistoryStore::open(context.as_deref())?;
 
-            match format {
-                cli::GraphFormat::Dot => {
-                    println!("{}", service_graph.to_dot());
+            match action {
+                cli::HistoryAction::Record => {
+                    let snapshot = history::take_snapshot(&discovery, context.as_deref()).await?;
+                    let path = store.save(&snapshot, cli.compress)?;
+                    println!(
+                        "Recorded {} services, {} pods and {} workloads to {}",
+                        snapshot.services.len(),
+                        snapshot.pods.len(),
+                        snapshot.deployments.len()
+                            + snapshot.statefulsets.len()
+                            + snapshot.daemonsets.len(),
+                        path.display()
+                    );
                 }
-                cli::GraphFormat::Svg => {
-                    println!("{}", service_graph.to_svg()?);
+                cli::HistoryAction::List => {
+                    output::print_snapshot_list(&store.list()?, &cli.output)?;
                 }
             }
         }
 
-        Commands::Cache { action } => {
-            use cli::CacheAction;
+        Commands::State(StateCommands::Snapshot {
+            action: cli::SnapshotAction::Save { file, redact },
+        }) => {
+            let mut snapshot = history::take_snapshot(&discovery, context.as_deref()).await?;
+            if redact {
+                redact::Redactor::open()?.redact_snapshot(&mut snapshot);
+            }
+            snapshot::save(
+                &file,
+                &snapshot,
+                cli.compress.unwrap_or(cli::CompressFormat::Gzip),
+            )?;
+            println!(
+                "Saved {} services, {} pods, {} workloads, {} configmaps and {} secrets to {}",
+                snapshot.services.len(),
+                snapshot.pods.len(),
+                snapshot.deployments.len()
+                    + snapshot.statefulsets.len()
+                    + snapshot.daemonsets.len(),
+                snapshot.configmaps.len(),
+                snapshot.secrets.len(),
+                file.display()
+            );
+        }
+        Commands::State(StateCommands::Snapshot { .. }) => {
+            unreachable!("loading and unloading need no cluster")
+        }
+
+        Commands::State(StateCommands::Cache { action }) => {
+            use cli::{CacheAction, WarmResource};
 
             match action {
                 CacheAction::Stats => {
@@ -569,8 +2754,34 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                         "  Custom Resources entries: {}",
                         stats.custom_resources_entries
                     );
+                    println!("  Pod index entries: {}", stats.pod_index_entries);
                     println!("  Total entries: {}", stats.total_entries());
                     println!("  Default TTL: {:?}", stats.default_ttl);
+                    for (kind, ttl) in &stats.ttls {
+                        if *ttl != stats.default_ttl {
+                            println!("  TTL for {}: {:?}", kind, ttl);
+                        }
+                    }
+                    println!("  Hits: {}", stats.hits);
+                    println!("  Misses: {}", stats.misses);
+                    println!("  Evictions: {}", stats.evictions);
+                    match disk_cache::usage() {
+                        Some(usage) => {
+                            println!("Persistent Cache: on ({})", usage.dir.display());
+                            println!("  Disk entries: {}", usage.entries);
+                            println!(
+                                "  Disk usage: {}",
+                                quantity::format_bytes(usage.bytes as f64)
+                            );
+                            println!(
+                                "  Hits: {} ({} from disk)",
+                                usage.counters.hits, usage.counters.disk_hits
+                            );
+                            println!("  Misses: {}", usage.counters.misses);
+                            println!("  Evictions: {}", usage.counters.evictions);
+                        }
+                        None => println!("Persistent Cache: off"),
+                    }
                 }
 
                 CacheAction::Clear => {
@@ -578,118 +2789,63 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                     println!("Cache cleared successfully");
                 }
 
+                CacheAction::Persist { state } => {
+                    let enabled = state == cli::PersistState::On;
+                    disk_cache::set_enabled(enabled)?;
+                    if enabled {
+                        println!("Cached listings are now kept on disk between runs");
+                    } else {
+                        println!("Cached listings are no longer kept on disk; persisted listings deleted");
+                    }
+                }
+
                 CacheAction::Warm {
                     namespaces,
                     resources,
+                    selector,
+                    parallelism,
                 } => {
-                    let progress = if cli.show_progress {
-                        Some(crate::progress::ProgressTracker::new_spinner(
-                            true,
-                            "Warming cache...",
-                        ))
-                    } else {
-                        None
-                    };
-
                     let target_namespaces = if namespaces.is_empty() {
-                        discovery.get_all_namespaces().await?
+                        discovery.namespaces_in_scope().await?
                     } else {
-                        namespaces.clone()
+                        namespaces
                     };
 
                     let target_resources = if resources.is_empty() {
-                        vec![
-                            "services".to_string(),
-                            "pods".to_string(),
-                            "deployments".to_string(),
-                            "configmaps".to_string(),
-                        ]
+                        WarmResource::ALL.to_vec()
                     } else {
-                        resources.clone()
+                        resources
                     };
 
-                    let mut warmed_count = 0;
-
-                    for resource_type in &target_resources {
-                        match resource_type.as_str() {
-                            "services" => {
-                                for namespace in &target_namespaces {
-                                    let _ = discovery
-                                        .list_services_with_options(
-                                            Some(namespace),
-                                            None,
-                                            None,
-                                            100,
-                                            true,
-                                        )
-                                        .await;
-                                    warmed_count += 1;
-                                }
-                            }
-                            "pods" => {
-                                for namespace in &target_namespaces {
-                                    let _ = discovery
-                                        .list_pods_with_options(
-                                            Some(namespace),
-                                            None,
-                                            None,
-                                            100,
-                                            true,
-                                        )
-                                        .await;
-                                    warmed_count += 1;
-                                }
-                            }
-                            "deployments" => {
-                                for namespace in &target_namespaces {
-                                    let _ = discovery
-                                        .list_deployments_with_options(
-                                            Some(namespace),
-                                            None,
-                                            100,
-                                            true,
-                                        )
-                                        .await;
-                                    warmed_count += 1;
-                                }
-                            }
-                            "configmaps" => {
-                                for namespace in &target_namespaces {
-                                    let _ = discovery
-                                        .list_configmaps_with_options(
-                                            Some(namespace),
-                                            None,
-                                            100,
-                                            true,
-                                        )
-                                        .await;
-                                    warmed_count += 1;
-                                }
-                            }
-                            _ => {
-                                eprintln!("Warning: Unknown resource type '{}'", resource_type);
-                            }
-                        }
-                    }
+                    let progress = progress::ProgressTracker::new(
+                        cli.show_progress,
+                        Some(target_namespaces.len() as u64),
+                    );
+                    progress.set_message("Warming cache...");
 
-                    if let Some(progress) = progress {
-                        progress.finish_and_clear();
-                    }
+                    let summary = warm::warm_cache(
+                        &discovery,
+                        target_namespaces,
+                        &target_resources,
+                        selector.as_deref(),
+                        cli.page_size,
+                        parallelism,
+                        Some(&progress),
+                    )
+                    .await;
+                    progress.finish_and_clear();
 
-                    println!(
-                        "Cache warmed successfully: {} namespace/resource combinations loaded",
-                        warmed_count
-                    );
+                    output::print_warm_summary(&summary, &cli.output)?;
                 }
             }
         }
 
-        Commands::Benchmark {
+        Commands::State(StateCommands::Benchmark {
             iterations,
             resources,
             test_memory,
             test_concurrent,
-        } => {
+        }) => {
             println!("🚀 Phase 2 Performance Benchmark");
             println!("================================");
 
@@ -721,6 +2877,7 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                                         true,
                                         20, // Optimal concurrency for benchmarks
                                         None,
+                                        None,
                                     )
                                     .await?;
                             } else if test_memory {
@@ -746,6 +2903,7 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
                                         true,
                                         20, // Optimal concurrency for benchmarks
                                         None,
+                                        None,
                                     )
                                     .await?;
                             } else if test_memory {
@@ -793,11 +2951,555 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             println!("\n✅ Benchmark completed!");
             println!("💡 Use --test-concurrent or --test-memory to test specific optimizations");
         }
+        // Answered before connecting to the cluster
+        Commands::State(StateCommands::Config { .. }) => unreachable!(),
+    }
+    stats.record_phase("command", started);
+    discovery.cache().persist_counters();
+
+    if cli.stats {
+        output::print_run_stats(&stats.summary(&discovery.cache_stats()), &cli.output)?;
+    }
+
+    Ok(())
+}
+
+/// Show or create the config file (`kdx config`); needs no cluster
+fn config_command(action: &cli::ConfigAction, cli: &Cli) -> anyhow::Result<()> {
+    let path = cli
+        .config
+        .clone()
+        .or_else(config::config_path)
+        .ok_or_else(|| {
+            ExplorerError::InvalidArgument("no home directory for the config file".to_string())
+        })?;
+    match action {
+        cli::ConfigAction::Show => {
+            let config = config::Config::load(cli.config.as_deref())?;
+            output::print_config(&config, &path, &cli.output)?;
+        }
+        cli::ConfigAction::Init { force } => {
+            config::init(&path, *force)?;
+            println!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// List one cluster's items of a namespace target with `list`, which is
+/// given the namespace to list or `None` for every namespace in scope
+async fn list_target<T, F, Fut>(
+    engine: discovery::DiscoveryEngine,
+    target: NamespaceTarget,
+    resource: &str,
+    concurrency: usize,
+    list: F,
+) -> kdx::error::Result<Vec<T>>
+where
+    T: Send + 'static,
+    F: Fn(discovery::DiscoveryEngine, Option<String>) -> Fut,
+    Fut: std::future::Future<Output = kdx::error::Result<Vec<T>>> + Send + 'static,
+{
+    let items = match target {
+        NamespaceTarget::Many(namespaces) => {
+            engine
+                .list_in_namespaces(namespaces, resource, concurrency, None, |engine, ns| {
+                    list(engine, Some(ns))
+                })
+                .await?
+        }
+        target => {
+            let namespace = target.namespace().map(str::to_string);
+            list(engine.clone(), namespace).await?
+        }
+    };
+    engine.cache().persist_counters();
+    Ok(items)
+}
+
+/// Run a list command against the clusters of several kubeconfig contexts
+/// (`--context` repeated, or `--all-contexts`) and print the merged result
+async fn fan_out_command(
+    cli: &Cli,
+    contexts: &[String],
+    config: &config::Config,
+    excluded: &[String],
+    stats: &Arc<stats::RunStats>,
+    concurrency: usize,
+    sort_key: Option<&sort::SortKey>,
+) -> anyhow::Result<()> {
+    if cli.watch || cli.stream || cli.as_of.is_some() {
+        return Err(ExplorerError::InvalidArgument(
+            "several contexts cannot be combined with --watch, --stream or --as-of".to_string(),
+        )
+        .into());
+    }
+    let started = Instant::now();
+    let warnings = &cli.output.warnings();
+    let connect = |context: String| async move {
+        engine_builder(cli, config, excluded, stats, Some(&context))?
+            .build()
+            .await
+    };
+    let resolve = |namespace: &[String], all_namespaces: bool| {
+        NamespaceTarget::resolve(namespace, all_namespaces, cli.namespace.as_deref())
+    };
+    let (limit, page_size) = (cli.limit, cli.page_size);
+
+    match &cli.command {
+        Commands::Resources(ResourceCommands::Services {
+            namespace,
+            all_namespaces,
+            selector,
+            group_by: None,
+        }) => {
+            let target = &resolve(namespace, *all_namespaces);
+            let criteria = &FilterCriteria {
+                label_selector: selector.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut services =
+                clusters::list_across(contexts, warnings, connect, |engine| async move {
+                    let services = list_target(engine, target.clone(), "services", concurrency, {
+                        |engine, ns| {
+                            let selector = selector.clone();
+                            async move {
+                                engine
+                                    .list_services_with_options(
+                                        ns.as_deref(),
+                                        selector.as_deref(),
+                                        limit,
+                                        page_size,
+                                        true,
+                                    )
+                                    .await
+                            }
+                        }
+                    })
+                    .await?;
+                    Ok(ResourceFilter::filter_services(services, criteria))
+                })
+                .await?;
+            sort::sort_items(&mut services, sort_key)?;
+            output::print_clustered_services(&services, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Pods {
+            namespace,
+            selector,
+            all_namespaces,
+            status,
+            qos,
+            show_qos,
+            show_mesh,
+            group_by: None,
+        }) => {
+            let target = &resolve(namespace, *all_namespaces);
+            let criteria = &FilterCriteria {
+                label_selector: selector.clone(),
+                status_filter: status.clone(),
+                qos_class: qos.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut pods =
+                clusters::list_across(contexts, warnings, connect, |engine| async move {
+                    let pods = list_target(engine, target.clone(), "pods", concurrency, {
+                        |engine, ns| {
+                            let selector = selector.clone();
+                            async move {
+                                engine
+                                    .list_pods_with_options(
+                                        ns.as_deref(),
+                                        selector.as_deref(),
+                                        limit,
+                                        page_size,
+                                        true,
+                                    )
+                                    .await
+                            }
+                        }
+                    })
+                    .await?;
+                    Ok(ResourceFilter::filter_pods(pods, criteria))
+                })
+                .await?;
+            sort::sort_items(&mut pods, sort_key)?;
+            output::print_clustered_pods(&pods, &cli.output, *show_qos, *show_mesh)?;
+        }
+        Commands::Resources(ResourceCommands::Deployments {
+            namespace,
+            all_namespaces,
+            selector,
+            status,
+            group_by: None,
+        }) => {
+            let target = &resolve(namespace, *all_namespaces);
+            let criteria = &FilterCriteria {
+                label_selector: selector.clone(),
+                status_filter: status.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut deployments =
+                clusters::list_across(contexts, warnings, connect, |engine| async move {
+                    let deployments =
+                        list_target(engine, target.clone(), "deployments", concurrency, {
+                            |engine, ns| async move {
+                                engine
+                                    .list_deployments_with_options(
+                                        ns.as_deref(),
+                                        limit,
+                                        page_size,
+                                        true,
+                                    )
+                                    .await
+                            }
+                        })
+                        .await?;
+                    Ok(ResourceFilter::filter_deployments(deployments, criteria))
+                })
+                .await?;
+            sort::sort_items(&mut deployments, sort_key)?;
+            output::print_clustered_deployments(&deployments, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Statefulsets {
+            namespace,
+            all_namespaces,
+        }) => {
+            let target = &resolve(namespace, *all_namespaces);
+            let criteria = &FilterCriteria {
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut statefulsets =
+                clusters::list_across(contexts, warnings, connect, |engine| async move {
+                    let statefulsets = list_target(
+                        engine,
+                        target.clone(),
+                        "statefulsets",
+                        concurrency,
+                        |engine, ns| async move {
+                            engine
+                                .list_statefulsets_with_options(ns.as_deref(), true)
+                                .await
+                        },
+                    )
+                    .await?;
+                    Ok(ResourceFilter::filter_statefulsets(statefulsets, criteria))
+                })
+                .await?;
+            sort::sort_items(&mut statefulsets, sort_key)?;
+            output::print_clustered_statefulsets(&statefulsets, &cli.output)?;
+        }
+        Commands::Resources(ResourceCommands::Daemonsets {
+            namespace,
+            all_namespaces,
+        }) => {
+            let target = &resolve(namespace, *all_namespaces);
+            let criteria = &FilterCriteria {
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            let mut daemonsets =
+                clusters::list_across(contexts, warnings, connect, |engine| async move {
+                    let daemonsets = list_target(
+                        engine,
+                        target.clone(),
+                        "daemonsets",
+                        concurrency,
+                        |engine, ns| async move {
+                            engine
+                                .list_daemonsets_with_options(ns.as_deref(), true)
+                                .await
+                        },
+                    )
+                    .await?;
+                    Ok(ResourceFilter::filter_daemonsets(daemonsets, criteria))
+                })
+                .await?;
+            sort::sort_items(&mut daemonsets, sort_key)?;
+            output::print_clustered_daemonsets(&daemonsets, &cli.output)?;
+        }
+        _ => {
+            return Err(ExplorerError::InvalidArgument(
+                "several contexts are supported by services, pods, deployments, statefulsets \
+                 and daemonsets without --group-by"
+                    .to_string(),
+            )
+            .into())
+        }
+    }
+    stats.record_phase("command", started);
+
+    if cli.stats {
+        let cache = kdx::cache::CacheStats::default();
+        output::print_run_stats(&stats.summary(&cache), &cli.output)?;
+    }
+    Ok(())
+}
+
+/// Run a list command in watch mode (`--watch`)
+async fn watch_command(cli: &Cli, discovery: &discovery::DiscoveryEngine) -> anyhow::Result<()> {
+    if cli.as_of.is_some() || cli.stream {
+        return Err(ExplorerError::InvalidArgument(
+            "--watch follows the live cluster and cannot be combined with --as-of or --stream"
+                .to_string(),
+        )
+        .into());
+    }
+    let resolve = |namespace: &[String], all_namespaces: bool| {
+        NamespaceTarget::resolve(namespace, all_namespaces, cli.namespace.as_deref())
+    };
+
+    match &cli.command {
+        Commands::Resources(ResourceCommands::Services {
+            namespace,
+            all_namespaces,
+            selector,
+            group_by: None,
+        }) => {
+            let criteria = FilterCriteria {
+                label_selector: selector.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            watch::watch_list::<Service, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                selector.as_deref(),
+                &cli.output,
+                |services| ResourceFilter::filter_services(services, &criteria),
+                output::print_services,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Pods {
+            namespace,
+            selector,
+            all_namespaces,
+            status,
+            qos,
+            show_qos,
+            show_mesh,
+            group_by: None,
+        }) => {
+            let criteria = FilterCriteria {
+                label_selector: selector.clone(),
+                status_filter: status.clone(),
+                qos_class: qos.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            watch::watch_list::<Pod, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                selector.as_deref(),
+                &cli.output,
+                |pods| ResourceFilter::filter_pods(pods, &criteria),
+                |pods, format| output::print_pods(pods, format, *show_qos, *show_mesh),
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Deployments {
+            namespace,
+            all_namespaces,
+            selector,
+            status,
+            group_by: None,
+        }) => {
+            let criteria = FilterCriteria {
+                label_selector: selector.clone(),
+                status_filter: status.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            watch::watch_list::<Deployment, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                selector.as_deref(),
+                &cli.output,
+                |deployments| ResourceFilter::filter_deployments(deployments, &criteria),
+                output::print_deployments,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Statefulsets {
+            namespace,
+            all_namespaces,
+        }) => {
+            watch::watch_list::<StatefulSet, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                None,
+                &cli.output,
+                |statefulsets| statefulsets,
+                output::print_statefulsets,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Daemonsets {
+            namespace,
+            all_namespaces,
+        }) => {
+            watch::watch_list::<DaemonSet, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                None,
+                &cli.output,
+                |daemonsets| daemonsets,
+                output::print_daemonsets,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Configmaps {
+            namespace,
+            all_namespaces,
+            selector,
+            group_by: None,
+            unused: false,
+        }) => {
+            let criteria = FilterCriteria {
+                label_selector: selector.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            watch::watch_list::<ConfigMap, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                selector.as_deref(),
+                &cli.output,
+                |configmaps| ResourceFilter::filter_configmaps(configmaps, &criteria),
+                output::print_configmaps,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Secrets {
+            namespace,
+            all_namespaces,
+            selector,
+            group_by: None,
+            unused: false,
+            secret_type,
+            summary: false,
+        }) => {
+            let criteria = FilterCriteria {
+                label_selector: selector.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            watch::watch_list::<Secret, _, _>(
+                discovery,
+                &resolve(namespace, *all_namespaces),
+                selector.as_deref(),
+                &cli.output,
+                |secrets| {
+                    let mut secrets = ResourceFilter::filter_secrets(secrets, &criteria);
+                    if let Some(stype) = secret_type {
+                        secrets.retain(|s| &s.secret_type == stype);
+                    }
+                    secrets
+                },
+                output::print_secrets,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Nodes {
+            role,
+            condition,
+            selector,
+        }) => {
+            let criteria = FilterCriteria {
+                label_selector: selector.clone(),
+                node_role: role.clone(),
+                node_condition: condition.clone(),
+                newer_than: cli.newer_than,
+                older_than: cli.older_than,
+                name_filter: cli.name_filter.clone(),
+                annotation_selector: cli.annotation_selector.clone(),
+                ..Default::default()
+            };
+            watch::watch_list::<Node, _, _>(
+                discovery,
+                &NamespaceTarget::One(None),
+                selector.as_deref(),
+                &cli.output,
+                |nodes| ResourceFilter::filter_nodes(nodes, &criteria),
+                output::print_nodes,
+            )
+            .await?;
+        }
+        Commands::Resources(ResourceCommands::Services { .. })
+        | Commands::Resources(ResourceCommands::Pods { .. })
+        | Commands::Resources(ResourceCommands::Deployments { .. })
+        | Commands::Resources(ResourceCommands::Configmaps { .. })
+        | Commands::Resources(ResourceCommands::Secrets { .. }) => {
+            return Err(ExplorerError::InvalidArgument(
+                "--watch cannot be combined with --group-by, --unused or --summary".to_string(),
+            )
+            .into());
+        }
+        _ => {
+            return Err(ExplorerError::InvalidArgument(
+                "--watch is supported by services, pods, deployments, statefulsets, \
+                 daemonsets, configmaps, secrets and nodes"
+                    .to_string(),
+            )
+            .into());
+        }
     }
 
     Ok(())
 }
 
+/// Commands whose data can be answered from a recorded snapshot
+fn supports_as_of(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Resources(ResourceCommands::All { .. })
+            | Commands::Resources(ResourceCommands::Services { .. })
+            | Commands::Resources(ResourceCommands::Pods { .. })
+            | Commands::Resources(ResourceCommands::Deployments { .. })
+            | Commands::Resources(ResourceCommands::Statefulsets { .. })
+            | Commands::Resources(ResourceCommands::Daemonsets { .. })
+            | Commands::Resources(ResourceCommands::Configmaps { .. })
+            | Commands::Resources(ResourceCommands::Secrets { .. })
+            | Commands::Views(ViewCommands::Describe { .. })
+            | Commands::Views(ViewCommands::Topology { .. })
+            | Commands::Views(ViewCommands::Graph { .. })
+    )
+}
+
 /// Parse group-by string into GroupBy enum
 fn parse_group_by(group_by_str: &str) -> GroupBy {
     match group_by_str.to_lowercase().as_str() {
@@ -805,6 +3507,7 @@ fn parse_group_by(group_by_str: &str) -> GroupBy {
         "tier" => GroupBy::Tier,
         "helm-release" | "helm" => GroupBy::HelmRelease,
         "namespace" | "ns" => GroupBy::Namespace,
+        "kustomization" | "kustomize" => GroupBy::Kustomization,
         "none" => GroupBy::None,
         custom => GroupBy::CustomLabel(custom.to_string()),
     }
@@ -0,0 +1,211 @@
+//! Service mesh sidecar detection and coverage reporting
+
+use crate::discovery::{pod_workload, PodInfo};
+use k8s_openapi::api::core::v1::PodSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Sidecar proxy container names for each supported mesh
+const MESH_PROXIES: &[(&str, &str)] = &[("istio-proxy", "istio"), ("linkerd-proxy", "linkerd")];
+
+/// Pod annotations or labels that explicitly request or opt out of injection
+const INJECTION_KEYS: &[&str] = &["sidecar.istio.io/inject", "linkerd.io/inject"];
+
+/// Return the mesh whose sidecar proxy runs in the pod, if any.
+///
+/// Native sidecars (restartable init containers) are checked as well.
+pub fn detect_sidecar(spec: &PodSpec) -> Option<String> {
+    let init_containers = spec.init_containers.iter().flatten();
+    spec.containers
+        .iter()
+        .chain(init_containers)
+        .find_map(|container| {
+            MESH_PROXIES
+                .iter()
+                .find(|(proxy, _)| container.name == *proxy)
+                .map(|(_, mesh)| mesh.to_string())
+        })
+}
+
+/// Return the injection setting from pod annotations or labels, if one is set.
+///
+/// `true` means injection was requested, `false` that the pod opted out.
+pub fn injection_setting(
+    annotations: &BTreeMap<String, String>,
+    labels: &BTreeMap<String, String>,
+) -> Option<bool> {
+    INJECTION_KEYS.iter().find_map(|key| {
+        annotations
+            .get(*key)
+            .or_else(|| labels.get(*key))
+            .map(|value| matches!(value.to_lowercase().as_str(), "true" | "enabled"))
+    })
+}
+
+/// Mesh status shown in the MESH column
+pub fn mesh_status(pod: &PodInfo) -> String {
+    match (&pod.mesh, pod.mesh_injection) {
+        (Some(mesh), _) => mesh.clone(),
+        (None, Some(true)) => "Missing".to_string(),
+        (None, Some(false)) => "Disabled".to_string(),
+        (None, None) => "None".to_string(),
+    }
+}
+
+/// Mesh membership of a single workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeshWorkload {
+    pub kind: String,
+    pub name: String,
+    pub mesh: Option<String>,
+    pub meshed_pods: u32,
+    pub total_pods: u32,
+}
+
+/// Per-namespace mesh coverage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceMeshCoverage {
+    pub namespace: String,
+    pub meshed: Vec<MeshWorkload>,
+    pub partial: Vec<MeshWorkload>,
+    pub unmeshed: Vec<MeshWorkload>,
+}
+
+impl NamespaceMeshCoverage {
+    /// Percentage of workloads with every pod inside the mesh
+    pub fn coverage(&self) -> f64 {
+        let total = self.meshed.len() + self.partial.len() + self.unmeshed.len();
+        if total == 0 {
+            return 0.0;
+        }
+        self.meshed.len() as f64 / total as f64 * 100.0
+    }
+}
+
+/// Build a per-namespace report of workloads inside and outside the mesh
+pub fn mesh_coverage(pods: &[PodInfo]) -> Vec<NamespaceMeshCoverage> {
+    let mut workloads: BTreeMap<(String, String, String), MeshWorkload> = BTreeMap::new();
+
+    for pod in pods {
+        let (kind, name) = pod_workload(pod);
+        let workload = workloads
+            .entry((pod.namespace.clone(), kind.clone(), name.clone()))
+            .or_insert_with(|| MeshWorkload {
+                kind,
+                name,
+                mesh: None,
+                meshed_pods: 0,
+                total_pods: 0,
+            });
+
+        workload.total_pods += 1;
+        if let Some(mesh) = &pod.mesh {
+            workload.meshed_pods += 1;
+            workload.mesh.get_or_insert_with(|| mesh.clone());
+        }
+    }
+
+    let mut report: BTreeMap<String, NamespaceMeshCoverage> = BTreeMap::new();
+    for ((namespace, _, _), workload) in workloads {
+        let coverage = report
+            .entry(namespace.clone())
+            .or_insert_with(|| NamespaceMeshCoverage {
+                namespace,
+                meshed: Vec::new(),
+                partial: Vec::new(),
+                unmeshed: Vec::new(),
+            });
+
+        if workload.meshed_pods == workload.total_pods {
+            coverage.meshed.push(workload);
+        } else if workload.meshed_pods > 0 {
+            coverage.partial.push(workload);
+        } else {
+            coverage.unmeshed.push(workload);
+        }
+    }
+
+    report.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::Container;
+
+    fn container(name: &str) -> Container {
+        Container {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn pod(namespace: &str, name: &str, owner: Option<&str>, mesh: Option<&str>) -> PodInfo {
+        PodInfo {
+            owner_kind: owner.map(|_| "StatefulSet".to_string()),
+            owner_name: owner.map(|o| o.to_string()),
+            mesh: mesh.map(|m| m.to_string()),
+            ..PodInfo::fixture(namespace, name)
+        }
+    }
+
+    #[test]
+    fn test_detect_sidecar() {
+        let spec = PodSpec {
+            containers: vec![container("app"), container("istio-proxy")],
+            ..Default::default()
+        };
+        assert_eq!(detect_sidecar(&spec), Some("istio".to_string()));
+
+        let native = PodSpec {
+            containers: vec![container("app")],
+            init_containers: Some(vec![container("linkerd-proxy")]),
+            ..Default::default()
+        };
+        assert_eq!(detect_sidecar(&native), Some("linkerd".to_string()));
+
+        let plain = PodSpec {
+            containers: vec![container("app")],
+            ..Default::default()
+        };
+        assert_eq!(detect_sidecar(&plain), None);
+    }
+
+    #[test]
+    fn test_injection_setting() {
+        let mut annotations = BTreeMap::new();
+        let labels = BTreeMap::new();
+        assert_eq!(injection_setting(&annotations, &labels), None);
+
+        annotations.insert("linkerd.io/inject".to_string(), "enabled".to_string());
+        assert_eq!(injection_setting(&annotations, &labels), Some(true));
+
+        let mut labels = BTreeMap::new();
+        labels.insert("sidecar.istio.io/inject".to_string(), "false".to_string());
+        assert_eq!(injection_setting(&BTreeMap::new(), &labels), Some(false));
+    }
+
+    #[test]
+    fn test_mesh_coverage() {
+        let pods = vec![
+            pod("shop", "db-0", Some("db"), Some("istio")),
+            pod("shop", "db-1", Some("db"), None),
+            pod("shop", "cache-0", Some("cache"), Some("istio")),
+            pod("shop", "debug", None, None),
+            pod("ops", "tool", None, None),
+        ];
+
+        let report = mesh_coverage(&pods);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].namespace, "ops");
+        assert_eq!(report[0].unmeshed.len(), 1);
+
+        let shop = &report[1];
+        assert_eq!(shop.meshed.len(), 1);
+        assert_eq!(shop.meshed[0].name, "cache");
+        assert_eq!(shop.partial.len(), 1);
+        assert_eq!(shop.partial[0].meshed_pods, 1);
+        assert_eq!(shop.unmeshed[0].kind, "Pod");
+        assert!((shop.coverage() - 100.0 / 3.0).abs() < 1e-9);
+    }
+}
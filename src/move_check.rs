@@ -0,0 +1,541 @@
+//! What would break if a workload moved to another namespace (`kdx move-check`)
+//!
+//! Most of what a workload relies on is bound to its namespace: the
+//! ConfigMaps, Secrets and service account its pods reference are looked up
+//! there, Services and Ingresses only route to pods of their own namespace,
+//! RBAC grants name the service account together with its namespace, and
+//! NetworkPolicies select pods and peers by namespace. This module lists
+//! each of those ties that the move would cut, and the policies of the
+//! target namespace that would start to restrict the workload.
+
+use crate::discovery::{DiscoveryEngine, RoleBindingInfo};
+use crate::error::{ExplorerError, Result};
+use crate::ingress::ingress_routes;
+use crate::netpol::{self, Endpoint};
+use crate::rbac::{self, names_service_account};
+use crate::references::{pod_config_references, ConfigKind};
+use crate::resource::{ObjectRef, ResourceKind};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, PodSpec, PodTemplateSpec, Secret, Service, ServiceAccount,
+};
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy, NetworkPolicyPeer};
+use kube::api::{ListParams, PartialObjectMeta};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// What a move would break, or change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MoveIssueKind {
+    /// A ConfigMap the pods reference does not exist in the target
+    MissingConfigMap,
+    /// A Secret the pods reference does not exist in the target
+    MissingSecret,
+    /// The pods' service account does not exist in the target
+    MissingServiceAccount,
+    /// A Service selecting the pods would lose them as endpoints
+    ServiceSelector,
+    /// An Ingress routing to such a Service would lose its backends
+    IngressBackend,
+    /// A binding granting the service account a role would no longer apply
+    RoleBinding,
+    /// A policy selecting the pods would no longer apply to them
+    PolicyNoLongerSelects,
+    /// A policy rule admitting the pods as a peer would no longer match them
+    PolicyNoLongerAdmits,
+    /// A policy of the target namespace would start to select the pods
+    PolicyWouldSelect,
+}
+
+impl std::fmt::Display for MoveIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveIssueKind::MissingConfigMap => write!(f, "missing configmap"),
+            MoveIssueKind::MissingSecret => write!(f, "missing secret"),
+            MoveIssueKind::MissingServiceAccount => write!(f, "missing service account"),
+            MoveIssueKind::ServiceSelector => write!(f, "service loses endpoints"),
+            MoveIssueKind::IngressBackend => write!(f, "ingress loses backend"),
+            MoveIssueKind::RoleBinding => write!(f, "role binding lost"),
+            MoveIssueKind::PolicyNoLongerSelects => write!(f, "policy no longer applies"),
+            MoveIssueKind::PolicyNoLongerAdmits => write!(f, "policy no longer admits"),
+            MoveIssueKind::PolicyWouldSelect => write!(f, "policy would apply"),
+        }
+    }
+}
+
+/// One tie the move would cut, with the object holding it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveIssue {
+    pub kind: MoveIssueKind,
+    /// Object involved, as `Kind namespace/name` (or `Kind name`)
+    pub object: String,
+    pub detail: String,
+}
+
+/// The result of `kdx move-check`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveCheck {
+    /// The workload as `kind/name`
+    pub workload: String,
+    pub from: String,
+    pub to: String,
+    pub issues: Vec<MoveIssue>,
+}
+
+/// The pods of a workload, through its pod template
+#[derive(Debug, Clone)]
+pub struct MovedWorkload {
+    pub name: String,
+    pub namespace: String,
+    pub labels: BTreeMap<String, String>,
+    pub spec: PodSpec,
+}
+
+impl MovedWorkload {
+    fn service_account(&self) -> &str {
+        self.spec
+            .service_account_name
+            .as_deref()
+            .unwrap_or("default")
+    }
+
+    fn endpoint(&self, namespace: &str, namespace_labels: &NamespaceLabels) -> Endpoint {
+        Endpoint {
+            namespace: namespace.to_string(),
+            name: self.name.clone(),
+            labels: self.labels.clone(),
+            namespace_labels: namespace_labels.get(namespace).cloned().unwrap_or_default(),
+            ip: None,
+            ports: Vec::new(),
+        }
+    }
+}
+
+type NamespaceLabels = BTreeMap<String, BTreeMap<String, String>>;
+
+/// The objects around a workload a move is checked against
+#[derive(Debug, Clone, Default)]
+pub struct MoveContext {
+    /// Names of the ConfigMaps, Secrets and service accounts of the target
+    pub target_configmaps: BTreeSet<String>,
+    pub target_secrets: BTreeSet<String>,
+    pub target_service_accounts: BTreeSet<String>,
+    /// Services and Ingresses of the workload's namespace
+    pub services: Vec<Service>,
+    pub ingresses: Vec<Ingress>,
+    /// Every RoleBinding and ClusterRoleBinding
+    pub bindings: Vec<RoleBindingInfo>,
+    /// Every NetworkPolicy
+    pub policies: Vec<NetworkPolicy>,
+    /// Labels of the source and target namespaces, for namespace selectors
+    pub namespace_labels: NamespaceLabels,
+}
+
+/// Everything moving `workload` to the namespace `to` would break
+pub fn check_move(workload: &MovedWorkload, to: &str, context: &MoveContext) -> Vec<MoveIssue> {
+    let from = workload.namespace.as_str();
+    let mut issues = Vec::new();
+
+    // Configuration is looked up in the pods' own namespace
+    let mut missing = BTreeSet::new();
+    for reference in pod_config_references(&workload.spec) {
+        let (kind, existing) = match reference.kind {
+            ConfigKind::ConfigMap => (MoveIssueKind::MissingConfigMap, &context.target_configmaps),
+            ConfigKind::Secret => (MoveIssueKind::MissingSecret, &context.target_secrets),
+        };
+        if !existing.contains(&reference.name) && missing.insert((kind, reference.name.clone())) {
+            let object_kind = match reference.kind {
+                ConfigKind::ConfigMap => "ConfigMap",
+                ConfigKind::Secret => "Secret",
+            };
+            issues.push(MoveIssue {
+                kind,
+                object: format!("{} {}/{}", object_kind, from, reference.name),
+                detail: format!("referenced by the pods, not found in {}", to),
+            });
+        }
+    }
+    let account = workload.service_account();
+    if account != "default" && !context.target_service_accounts.contains(account) {
+        issues.push(MoveIssue {
+            kind: MoveIssueKind::MissingServiceAccount,
+            object: format!("ServiceAccount {}/{}", from, account),
+            detail: format!("runs the pods, not found in {}", to),
+        });
+    }
+
+    // Services select pods of their own namespace only
+    let mut services = BTreeSet::new();
+    for service in &context.services {
+        let selector = service.spec.as_ref().and_then(|s| s.selector.as_ref());
+        let selects = selector.is_some_and(|selector| {
+            !selector.is_empty()
+                && selector
+                    .iter()
+                    .all(|(k, v)| workload.labels.get(k) == Some(v))
+        });
+        if selects {
+            let name = service.metadata.name.clone().unwrap_or_default();
+            issues.push(MoveIssue {
+                kind: MoveIssueKind::ServiceSelector,
+                object: format!("Service {}/{}", from, name),
+                detail: "selects the pods; it would have no endpoints from them".to_string(),
+            });
+            services.insert(name);
+        }
+    }
+    for ingress in &context.ingresses {
+        for route in ingress_routes(ingress) {
+            let service = route.backend.split(':').next().unwrap_or_default();
+            if services.contains(service) {
+                issues.push(MoveIssue {
+                    kind: MoveIssueKind::IngressBackend,
+                    object: format!("Ingress {}/{}", route.namespace, route.ingress),
+                    detail: format!("{}{} routes to {}", route.host, route.path, route.backend),
+                });
+            }
+        }
+    }
+
+    // Grants name the service account together with its namespace
+    for binding in &context.bindings {
+        let names = |namespace: &str| {
+            binding
+                .subjects
+                .iter()
+                .any(|s| names_service_account(s, namespace, account))
+        };
+        if names(from) && !names(to) {
+            let object = match &binding.namespace {
+                Some(ns) => format!("RoleBinding {}/{}", ns, binding.name),
+                None => format!("ClusterRoleBinding {}", binding.name),
+            };
+            issues.push(MoveIssue {
+                kind: MoveIssueKind::RoleBinding,
+                object,
+                detail: format!(
+                    "grants {}/{} to ServiceAccount {}/{}",
+                    binding.role_kind, binding.role_name, from, account
+                ),
+            });
+        }
+    }
+
+    let before = workload.endpoint(from, &context.namespace_labels);
+    let after = workload.endpoint(to, &context.namespace_labels);
+    for policy in &context.policies {
+        let namespace = policy.metadata.namespace.as_deref().unwrap_or_default();
+        let object = format!(
+            "NetworkPolicy {}/{}",
+            namespace,
+            policy.metadata.name.as_deref().unwrap_or_default()
+        );
+        let types = netpol::policy_types(policy)
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(" and ");
+
+        if netpol::policy_selects(policy, &before) {
+            issues.push(MoveIssue {
+                kind: MoveIssueKind::PolicyNoLongerSelects,
+                object: object.clone(),
+                detail: format!(
+                    "isolates {} of the pods; its rules would stop applying",
+                    types
+                ),
+            });
+        }
+        if netpol::policy_selects(policy, &after) {
+            issues.push(MoveIssue {
+                kind: MoveIssueKind::PolicyWouldSelect,
+                object: object.clone(),
+                detail: format!("would isolate {} of the pods", types),
+            });
+        }
+
+        let Some(spec) = &policy.spec else {
+            continue;
+        };
+        let admits = |peers: Option<&Vec<NetworkPolicyPeer>>| {
+            peers.into_iter().flatten().any(|peer| {
+                netpol::peer_matches(peer, namespace, &before)
+                    && !netpol::peer_matches(peer, namespace, &after)
+            })
+        };
+        let ingress = spec
+            .ingress
+            .iter()
+            .flatten()
+            .any(|r| admits(r.from.as_ref()));
+        let egress = spec.egress.iter().flatten().any(|r| admits(r.to.as_ref()));
+        for (lost, direction) in [(ingress, "traffic from"), (egress, "traffic to")] {
+            if lost {
+                issues.push(MoveIssue {
+                    kind: MoveIssueKind::PolicyNoLongerAdmits,
+                    object: object.clone(),
+                    detail: format!(
+                        "allows {} the pods only while they are in {}",
+                        direction, from
+                    ),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+fn names<K>(objects: Vec<PartialObjectMeta<K>>) -> BTreeSet<String> {
+    objects
+        .into_iter()
+        .filter_map(|o| o.metadata.name)
+        .collect()
+}
+
+/// Labels and spec of the pod template of a Deployment, StatefulSet or
+/// DaemonSet
+async fn fetch_workload(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    target: &ObjectRef,
+) -> Result<MovedWorkload> {
+    let client = discovery.client().clone();
+    let template: Option<PodTemplateSpec> = match target.kind {
+        ResourceKind::Deployment => Api::<Deployment>::namespaced(client, namespace)
+            .get(&target.name)
+            .await?
+            .spec
+            .map(|s| s.template),
+        ResourceKind::StatefulSet => Api::<StatefulSet>::namespaced(client, namespace)
+            .get(&target.name)
+            .await?
+            .spec
+            .map(|s| s.template),
+        ResourceKind::DaemonSet => Api::<DaemonSet>::namespaced(client, namespace)
+            .get(&target.name)
+            .await?
+            .spec
+            .map(|s| s.template),
+        kind => {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "move-check takes deployments, statefulsets and daemonsets, not {}",
+                kind
+            )))
+        }
+    };
+    let template = template.unwrap_or_default();
+
+    Ok(MovedWorkload {
+        name: target.to_string(),
+        namespace: namespace.to_string(),
+        labels: template.metadata.and_then(|m| m.labels).unwrap_or_default(),
+        spec: template.spec.unwrap_or_default(),
+    })
+}
+
+/// Check what moving `target` from `namespace` to `to` would break
+pub async fn check_workload_move(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    target: &ObjectRef,
+    to: &str,
+) -> Result<MoveCheck> {
+    if namespace == to {
+        return Err(ExplorerError::InvalidArgument(format!(
+            "{} is already in {}",
+            target, to
+        )));
+    }
+
+    let client = discovery.client().clone();
+    let params = ListParams::default();
+    let namespaces = [namespace, to];
+    let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), to);
+    let secrets: Api<Secret> = Api::namespaced(client.clone(), to);
+    let accounts: Api<ServiceAccount> = Api::namespaced(client.clone(), to);
+    let services: Api<Service> = Api::namespaced(client.clone(), namespace);
+    let ingresses: Api<Ingress> = Api::namespaced(client, namespace);
+    let (
+        workload,
+        target_configmaps,
+        target_secrets,
+        target_service_accounts,
+        services,
+        ingresses,
+        bindings,
+        policies,
+        namespace_labels,
+    ) = tokio::try_join!(
+        fetch_workload(discovery, namespace, target),
+        async { Ok(names(configmaps.list_metadata(&params).await?.items)) },
+        async { Ok(names(secrets.list_metadata(&params).await?.items)) },
+        async { Ok(names(accounts.list_metadata(&params).await?.items)) },
+        async { Ok(services.list(&params).await?) },
+        async { Ok(ingresses.list(&params).await?) },
+        rbac::list_bindings(discovery, None),
+        netpol::fetch_policies(discovery, None),
+        netpol::namespace_labels(discovery, &namespaces),
+    )?;
+
+    let context = MoveContext {
+        target_configmaps,
+        target_secrets,
+        target_service_accounts,
+        services: services.items,
+        ingresses: ingresses.items,
+        bindings,
+        policies,
+        namespace_labels,
+    };
+    let mut issues = check_move(&workload, to, &context);
+    issues.sort_by(|a, b| (a.kind, &a.object).cmp(&(b.kind, &b.object)));
+
+    Ok(MoveCheck {
+        workload: workload.name,
+        from: namespace.to_string(),
+        to: to.to_string(),
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::SubjectInfo;
+    use serde_json::json;
+
+    fn from_json<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> T {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn policy(namespace: &str, name: &str, spec: serde_json::Value) -> NetworkPolicy {
+        from_json(json!({"metadata": {"name": name, "namespace": namespace}, "spec": spec}))
+    }
+
+    #[test]
+    fn test_check_move() {
+        let workload = MovedWorkload {
+            name: "deployment/web".to_string(),
+            namespace: "shop".to_string(),
+            labels: BTreeMap::from([("app".to_string(), "web".to_string())]),
+            spec: from_json(json!({
+                "serviceAccountName": "web",
+                "containers": [{
+                    "name": "web",
+                    "envFrom": [{"configMapRef": {"name": "web-config"}}],
+                }],
+                "volumes": [{"name": "tls", "secret": {"secretName": "web-tls"}}],
+            })),
+        };
+        let binding = |namespace: Option<&str>, name: &str, subject: SubjectInfo| RoleBindingInfo {
+            name: name.to_string(),
+            namespace: namespace.map(str::to_string),
+            role_kind: "ClusterRole".to_string(),
+            role_name: "view".to_string(),
+            subjects: vec![subject],
+            age: "1d".to_string(),
+            labels: BTreeMap::new(),
+        };
+        let context = MoveContext {
+            target_configmaps: BTreeSet::from(["web-config".to_string()]),
+            services: vec![
+                from_json(
+                    json!({"metadata": {"name": "web"}, "spec": {"selector": {"app": "web"}}}),
+                ),
+                from_json(json!({"metadata": {"name": "db"}, "spec": {"selector": {"app": "db"}}})),
+            ],
+            ingresses: vec![from_json(json!({
+                "metadata": {"name": "shop", "namespace": "shop"},
+                "spec": {"rules": [{"host": "shop.example.com", "http": {"paths": [{
+                    "path": "/", "pathType": "Prefix",
+                    "backend": {"service": {"name": "web", "port": {"number": 80}}},
+                }]}}]},
+            }))],
+            bindings: vec![
+                binding(
+                    Some("shop"),
+                    "web-view",
+                    SubjectInfo {
+                        kind: "ServiceAccount".to_string(),
+                        name: "web".to_string(),
+                        namespace: Some("shop".to_string()),
+                    },
+                ),
+                // Holds for service accounts of every namespace
+                binding(
+                    None,
+                    "all-accounts",
+                    SubjectInfo {
+                        kind: "Group".to_string(),
+                        name: "system:serviceaccounts".to_string(),
+                        namespace: None,
+                    },
+                ),
+            ],
+            policies: vec![
+                policy(
+                    "shop",
+                    "web-ingress",
+                    json!({"podSelector": {"matchLabels": {"app": "web"}}}),
+                ),
+                policy(
+                    "data",
+                    "db-clients",
+                    json!({
+                        "podSelector": {},
+                        "ingress": [{"from": [{
+                            "namespaceSelector": {"matchLabels": {"kubernetes.io/metadata.name": "shop"}},
+                            "podSelector": {"matchLabels": {"app": "web"}},
+                        }]}],
+                    }),
+                ),
+                policy("payments", "default-deny", json!({"podSelector": {}})),
+            ],
+            namespace_labels: [("shop", "shop"), ("payments", "payments")]
+                .into_iter()
+                .map(|(ns, name)| {
+                    (
+                        ns.to_string(),
+                        BTreeMap::from([(
+                            "kubernetes.io/metadata.name".to_string(),
+                            name.to_string(),
+                        )]),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let issues = check_move(&workload, "payments", &context);
+        let found: Vec<(MoveIssueKind, &str)> =
+            issues.iter().map(|i| (i.kind, i.object.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (MoveIssueKind::MissingSecret, "Secret shop/web-tls"),
+                (
+                    MoveIssueKind::MissingServiceAccount,
+                    "ServiceAccount shop/web"
+                ),
+                (MoveIssueKind::ServiceSelector, "Service shop/web"),
+                (MoveIssueKind::IngressBackend, "Ingress shop/shop"),
+                (MoveIssueKind::RoleBinding, "RoleBinding shop/web-view"),
+                (
+                    MoveIssueKind::PolicyNoLongerSelects,
+                    "NetworkPolicy shop/web-ingress"
+                ),
+                (
+                    MoveIssueKind::PolicyNoLongerAdmits,
+                    "NetworkPolicy data/db-clients"
+                ),
+                (
+                    MoveIssueKind::PolicyWouldSelect,
+                    "NetworkPolicy payments/default-deny"
+                ),
+            ]
+        );
+    }
+}
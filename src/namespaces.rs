@@ -0,0 +1,245 @@
+//! Namespace inventory with per-namespace object counts
+//!
+//! Counts come from metadata-only lists, one namespace at a time, so the
+//! inventory stays cheap on large clusters. Pods are listed in full, since
+//! their QoS classes are counted too. A kind the user may not list in a
+//! namespace is shown as unknown rather than as zero.
+
+use crate::age::format_age;
+use crate::cli::NamespaceSort;
+use crate::discovery::{pod_qos_class, DiscoveryEngine};
+use crate::error::Result;
+use crate::progress::ProgressTracker;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Pod, Secret, Service};
+use kube::{Api, Client, Resource};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Object counts of one namespace; `None` where listing was forbidden
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectCounts {
+    pub services: Option<usize>,
+    pub pods: Option<usize>,
+    pub deployments: Option<usize>,
+    pub configmaps: Option<usize>,
+    pub secrets: Option<usize>,
+    /// Pods by QoS class, classified like the pod QOS column
+    pub guaranteed: Option<usize>,
+    pub burstable: Option<usize>,
+    pub best_effort: Option<usize>,
+}
+
+/// A namespace with its status and object counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceSummary {
+    pub name: String,
+    /// Active or Terminating
+    pub status: String,
+    pub age: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub labels: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub counts: ObjectCounts,
+}
+
+async fn count<K>(client: &Client, namespace: &str) -> Result<Option<usize>>
+where
+    K: Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + std::fmt::Debug,
+    <K as Resource>::DynamicType: Default,
+{
+    let api: Api<K> = Api::namespaced(client.clone(), namespace);
+    match api.list_metadata(&Default::default()).await {
+        Ok(list) => Ok(Some(list.items.len())),
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Pods of `namespace`, or `None` when listing them is forbidden
+async fn list_pods(client: &Client, namespace: &str) -> Result<Option<Vec<Pod>>> {
+    let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    match api.list(&Default::default()).await {
+        Ok(list) => Ok(Some(list.items)),
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Guaranteed, Burstable and BestEffort pods among `pods`
+fn count_qos(pods: &[Pod]) -> (usize, usize, usize) {
+    let mut counts = (0, 0, 0);
+    for pod in pods {
+        let class = pod
+            .spec
+            .as_ref()
+            .map(|spec| pod_qos_class(spec, pod.status.as_ref()));
+        match class.as_deref() {
+            Some("Guaranteed") => counts.0 += 1,
+            Some("Burstable") => counts.1 += 1,
+            _ => counts.2 += 1,
+        }
+    }
+    counts
+}
+
+/// Count services, pods by QoS class, deployments, configmaps and secrets
+/// in `namespace`
+pub async fn count_objects(client: &Client, namespace: &str) -> Result<ObjectCounts> {
+    let (services, pods, deployments, configmaps, secrets) = tokio::try_join!(
+        count::<Service>(client, namespace),
+        list_pods(client, namespace),
+        count::<Deployment>(client, namespace),
+        count::<ConfigMap>(client, namespace),
+        count::<Secret>(client, namespace),
+    )?;
+    let qos = pods.as_deref().map(count_qos);
+    Ok(ObjectCounts {
+        services,
+        pods: pods.as_ref().map(Vec::len),
+        deployments,
+        configmaps,
+        secrets,
+        guaranteed: qos.map(|q| q.0),
+        burstable: qos.map(|q| q.1),
+        best_effort: qos.map(|q| q.2),
+    })
+}
+
+/// Combine `namespaces` with their counts, in `sort` order
+pub fn summarize(
+    namespaces: &[Namespace],
+    mut counts: HashMap<String, ObjectCounts>,
+    sort: NamespaceSort,
+) -> Vec<NamespaceSummary> {
+    let mut summaries: Vec<NamespaceSummary> = namespaces
+        .iter()
+        .filter_map(|namespace| {
+            let name = namespace.metadata.name.clone()?;
+            let created_at = namespace.metadata.creation_timestamp.as_ref().map(|t| t.0);
+            Some(NamespaceSummary {
+                status: namespace
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.phase.clone())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                age: format_age(created_at),
+                created_at,
+                labels: namespace.metadata.labels.clone().unwrap_or_default(),
+                counts: counts.remove(&name).unwrap_or_default(),
+                name,
+            })
+        })
+        .collect();
+
+    // Counts sort largest first; unknown counts go last
+    let key = |summary: &NamespaceSummary| match sort {
+        NamespaceSort::Name => None,
+        NamespaceSort::Pods => summary.counts.pods,
+        NamespaceSort::Services => summary.counts.services,
+    };
+    summaries.sort_by(|a, b| key(b).cmp(&key(a)).then_with(|| a.name.cmp(&b.name)));
+    summaries
+}
+
+/// Summarize every namespace, counting objects in up to 20 namespaces at once
+pub async fn find_namespaces(
+    discovery: &DiscoveryEngine,
+    sort: NamespaceSort,
+    progress: &ProgressTracker,
+) -> Result<Vec<NamespaceSummary>> {
+    let api: Api<Namespace> = Api::all(discovery.client().clone());
+    let namespaces = api.list(&Default::default()).await?.items;
+    let names: Vec<String> = namespaces
+        .iter()
+        .filter_map(|n| n.metadata.name.clone())
+        .collect();
+
+    let counts = discovery
+        .list_in_namespaces(
+            names,
+            "namespace counts",
+            20,
+            Some(progress),
+            |engine, ns| async move {
+                let counts = count_objects(engine.client(), &ns).await?;
+                Ok(vec![(ns, counts)])
+            },
+        )
+        .await?;
+    Ok(summarize(&namespaces, counts.into_iter().collect(), sort))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn namespace(name: &str) -> Namespace {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": name, "labels": {"team": "shop"}},
+            "status": {"phase": "Active"},
+        }))
+        .unwrap()
+    }
+
+    fn object_counts(pods: Option<usize>, services: usize) -> ObjectCounts {
+        ObjectCounts {
+            services: Some(services),
+            pods,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize_sorts_by_count() {
+        let namespaces = [namespace("web"), namespace("db"), namespace("batch")];
+        let counts = || {
+            HashMap::from([
+                ("web".to_string(), object_counts(Some(12), 3)),
+                ("db".to_string(), object_counts(Some(3), 5)),
+                ("batch".to_string(), object_counts(None, 0)),
+            ])
+        };
+        let names = |sort| -> Vec<String> {
+            summarize(&namespaces, counts(), sort)
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        assert_eq!(names(NamespaceSort::Name), ["batch", "db", "web"]);
+        assert_eq!(names(NamespaceSort::Pods), ["web", "db", "batch"]);
+        assert_eq!(names(NamespaceSort::Services), ["db", "web", "batch"]);
+
+        let web = &summarize(&namespaces, counts(), NamespaceSort::Name)[2];
+        assert_eq!(web.status, "Active");
+        assert_eq!(web.labels["team"], "shop");
+        assert_eq!(web.counts.secrets, None);
+    }
+
+    #[test]
+    fn test_count_qos() {
+        let pod = |resources: serde_json::Value, qos: Option<&str>| -> Pod {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": "web"},
+                "spec": {"containers": [{"name": "app", "resources": resources}]},
+                "status": {"qosClass": qos},
+            }))
+            .unwrap()
+        };
+        let pods = [
+            pod(serde_json::json!({}), Some("Guaranteed")),
+            pod(
+                serde_json::json!({"limits": {"cpu": "1", "memory": "1Gi"}}),
+                None,
+            ),
+            pod(serde_json::json!({"requests": {"cpu": "100m"}}), None),
+            pod(serde_json::json!({}), None),
+        ];
+        assert_eq!(count_qos(&pods), (2, 1, 1));
+    }
+}
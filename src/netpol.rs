@@ -0,0 +1,727 @@
+//! NetworkPolicy listing and reachability between pods
+//!
+//! A pod selected by no NetworkPolicy of a direction accepts (or sends) any
+//! traffic in that direction. Once a policy selects it, only what one of
+//! the selecting policies allows gets through, and traffic has to be
+//! allowed both as egress from the source and as ingress to the
+//! destination. This module describes policies and evaluates those rules
+//! for two pods or workloads the way the API documents them; whether the
+//! network plugin enforces policies at all is not visible from the API.
+
+use crate::dependents::selector_matches;
+use crate::discovery::{DiscoveryEngine, PodInfo};
+use crate::error::{ExplorerError, Result};
+use crate::network::Cidr;
+use crate::resource::{ObjectRef, ResourceKind};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{Namespace, Pod, PodSpec, PodTemplateSpec};
+use k8s_openapi::api::networking::v1::{NetworkPolicy, NetworkPolicyPeer, NetworkPolicyPort};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+/// Label the API server sets on every namespace to its name
+const NAMESPACE_NAME_LABEL: &str = "kubernetes.io/metadata.name";
+
+/// Direction of traffic a policy rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Ingress,
+    Egress,
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Ingress => write!(f, "ingress"),
+            Direction::Egress => write!(f, "egress"),
+        }
+    }
+}
+
+/// A NetworkPolicy with its rules described for display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkPolicyInfo {
+    pub namespace: String,
+    pub name: String,
+    /// Selector of the pods the policy applies to; empty for every pod of
+    /// the namespace
+    pub pod_selector: String,
+    /// Directions the policy isolates the selected pods in
+    pub policy_types: Vec<Direction>,
+    /// Traffic allowed in, one entry per rule; none allowed when empty and
+    /// ingress is isolated
+    pub ingress: Vec<String>,
+    /// Traffic allowed out, one entry per rule
+    pub egress: Vec<String>,
+    /// Pods of the namespace the policy currently selects
+    pub affected_pods: usize,
+}
+
+/// A container port a pod or workload declares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedPort {
+    pub name: Option<String>,
+    pub port: u16,
+    pub protocol: String,
+}
+
+/// A pod, or the pods of a workload, as policies see it
+#[derive(Debug, Clone, Default)]
+pub struct Endpoint {
+    pub namespace: String,
+    /// `kind/name` as given on the command line
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub namespace_labels: BTreeMap<String, String>,
+    /// Pod IP, matched against ipBlock peers; unknown for workloads
+    pub ip: Option<IpAddr>,
+    pub ports: Vec<NamedPort>,
+}
+
+impl Endpoint {
+    /// Endpoint of a pod listed by the discovery engine, without ports
+    pub fn from_pod_info(pod: &PodInfo, namespace_labels: BTreeMap<String, String>) -> Self {
+        Self {
+            namespace: pod.namespace.clone(),
+            name: format!("pod/{}", pod.name),
+            labels: pod.labels.clone(),
+            namespace_labels,
+            ip: pod.pod_ip.as_deref().and_then(|ip| ip.parse().ok()),
+            ports: Vec::new(),
+        }
+    }
+}
+
+/// Port and protocol of the traffic checked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortQuery {
+    pub port: u16,
+    pub protocol: String,
+}
+
+impl std::fmt::Display for PortQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.protocol, self.port)
+    }
+}
+
+impl PortQuery {
+    /// Port given by number or by the name of a container port of
+    /// `destination`
+    pub fn resolve(port: &str, protocol: &str, destination: &Endpoint) -> Result<Self> {
+        let protocol = protocol.to_uppercase();
+        if let Ok(number) = port.parse() {
+            return Ok(Self {
+                port: number,
+                protocol,
+            });
+        }
+        destination
+            .ports
+            .iter()
+            .find(|p| p.name.as_deref() == Some(port) && p.protocol == protocol)
+            .map(|p| Self {
+                port: p.port,
+                protocol: protocol.clone(),
+            })
+            .ok_or_else(|| {
+                ExplorerError::InvalidArgument(format!(
+                    "{} declares no {} port named '{}'",
+                    destination.name, protocol, port
+                ))
+            })
+    }
+}
+
+/// Outcome of the policies of one side for one direction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verdict {
+    pub direction: Direction,
+    /// Whether any policy selects the pod for this direction
+    pub isolated: bool,
+    pub allowed: bool,
+    /// `namespace/name` of the policies selecting the pod
+    pub policies: Vec<String>,
+    /// Selecting policies with a rule allowing the traffic
+    pub allowed_by: Vec<String>,
+}
+
+/// Whether traffic from one endpoint reaches another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reachability {
+    pub from: String,
+    pub from_namespace: String,
+    pub to: String,
+    pub to_namespace: String,
+    pub port: Option<PortQuery>,
+    pub allowed: bool,
+    pub egress: Verdict,
+    pub ingress: Verdict,
+}
+
+/// Peers and ports of an ingress or egress rule
+struct Rule<'a> {
+    peers: Option<&'a [NetworkPolicyPeer]>,
+    ports: Option<&'a [NetworkPolicyPort]>,
+}
+
+fn rules(policy: &NetworkPolicy, direction: Direction) -> Vec<Rule<'_>> {
+    let Some(spec) = &policy.spec else {
+        return Vec::new();
+    };
+    match direction {
+        Direction::Ingress => spec
+            .ingress
+            .iter()
+            .flatten()
+            .map(|r| Rule {
+                peers: r.from.as_deref(),
+                ports: r.ports.as_deref(),
+            })
+            .collect(),
+        Direction::Egress => spec
+            .egress
+            .iter()
+            .flatten()
+            .map(|r| Rule {
+                peers: r.to.as_deref(),
+                ports: r.ports.as_deref(),
+            })
+            .collect(),
+    }
+}
+
+/// Directions a policy isolates; without `policyTypes` that is ingress, and
+/// egress too when the policy has egress rules
+pub fn policy_types(policy: &NetworkPolicy) -> Vec<Direction> {
+    let Some(spec) = &policy.spec else {
+        return vec![Direction::Ingress];
+    };
+    match &spec.policy_types {
+        Some(types) => [Direction::Ingress, Direction::Egress]
+            .into_iter()
+            .filter(|d| types.iter().any(|t| t.eq_ignore_ascii_case(&d.to_string())))
+            .collect(),
+        None if spec.egress.is_some() => vec![Direction::Ingress, Direction::Egress],
+        None => vec![Direction::Ingress],
+    }
+}
+
+/// Whether `labels` satisfy `selector`; an empty selector matches everything
+fn selects(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    serde_json::to_value(selector).is_ok_and(|selector| selector_matches(&selector, labels))
+}
+
+/// Whether `policy` selects the pods of `endpoint`
+pub fn policy_selects(policy: &NetworkPolicy, endpoint: &Endpoint) -> bool {
+    policy.metadata.namespace.as_deref() == Some(endpoint.namespace.as_str())
+        && policy
+            .spec
+            .as_ref()
+            .is_some_and(|s| selects(&s.pod_selector, &endpoint.labels))
+}
+
+/// Selector in `kubectl` syntax, e.g. `app=web,tier in (a,b)`
+pub fn selector_text(selector: &LabelSelector) -> String {
+    let mut terms: Vec<String> = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    for expression in selector.match_expressions.iter().flatten() {
+        let values = expression.values.as_deref().unwrap_or_default().join(",");
+        terms.push(match expression.operator.as_str() {
+            "In" => format!("{} in ({})", expression.key, values),
+            "NotIn" => format!("{} notin ({})", expression.key, values),
+            "Exists" => expression.key.clone(),
+            "DoesNotExist" => format!("!{}", expression.key),
+            operator => format!("{} {} ({})", expression.key, operator, values),
+        });
+    }
+    terms.join(",")
+}
+
+fn peer_text(peer: &NetworkPolicyPeer) -> String {
+    if let Some(block) = &peer.ip_block {
+        return match block.except.as_deref() {
+            Some(except) if !except.is_empty() => {
+                format!("{} except {}", block.cidr, except.join(","))
+            }
+            _ => block.cidr.clone(),
+        };
+    }
+    let pods = peer.pod_selector.as_ref().map(|s| match selector_text(s) {
+        text if text.is_empty() => "all pods".to_string(),
+        text => format!("pods {}", text),
+    });
+    let namespaces = peer
+        .namespace_selector
+        .as_ref()
+        .map(|s| match selector_text(s) {
+            text if text.is_empty() => "all namespaces".to_string(),
+            text => format!("namespaces {}", text),
+        });
+    match (pods, namespaces) {
+        (Some(pods), Some(namespaces)) => format!("{} in {}", pods, namespaces),
+        (Some(pods), None) => pods,
+        (None, Some(namespaces)) => namespaces,
+        (None, None) => "nothing".to_string(),
+    }
+}
+
+fn port_text(port: &NetworkPolicyPort) -> String {
+    let protocol = port.protocol.as_deref().unwrap_or("TCP");
+    match (&port.port, port.end_port) {
+        (Some(IntOrString::Int(start)), Some(end)) => format!("{}/{}-{}", protocol, start, end),
+        (Some(IntOrString::Int(number)), None) => format!("{}/{}", protocol, number),
+        (Some(IntOrString::String(name)), _) => format!("{}/{}", protocol, name),
+        (None, _) => format!("{}/*", protocol),
+    }
+}
+
+fn rule_text(rule: &Rule) -> String {
+    let peers = match rule.peers {
+        Some(peers) if !peers.is_empty() => {
+            peers.iter().map(peer_text).collect::<Vec<_>>().join("; ")
+        }
+        _ => "anywhere".to_string(),
+    };
+    match rule.ports {
+        Some(ports) if !ports.is_empty() => format!(
+            "{} on {}",
+            peers,
+            ports.iter().map(port_text).collect::<Vec<_>>().join(", ")
+        ),
+        _ => peers,
+    }
+}
+
+/// Describe `policy`, counting the `pods` it selects
+pub fn summarize(policy: &NetworkPolicy, pods: &[PodInfo]) -> NetworkPolicyInfo {
+    let namespace = policy.metadata.namespace.clone().unwrap_or_default();
+    let selector = policy
+        .spec
+        .as_ref()
+        .map(|s| s.pod_selector.clone())
+        .unwrap_or_default();
+    let describe = |direction| rules(policy, direction).iter().map(rule_text).collect();
+    NetworkPolicyInfo {
+        name: policy.metadata.name.clone().unwrap_or_default(),
+        pod_selector: selector_text(&selector),
+        policy_types: policy_types(policy),
+        ingress: describe(Direction::Ingress),
+        egress: describe(Direction::Egress),
+        affected_pods: pods
+            .iter()
+            .filter(|p| p.namespace == namespace && selects(&selector, &p.labels))
+            .count(),
+        namespace,
+    }
+}
+
+/// Whether `ip` lies in `cidr`; malformed blocks contain nothing
+fn cidr_contains(cidr: &str, ip: IpAddr) -> bool {
+    Cidr::parse(cidr).is_ok_and(|cidr| cidr.contains(ip))
+}
+
+/// Whether `peer` of a policy in `namespace` matches `endpoint`
+pub fn peer_matches(peer: &NetworkPolicyPeer, namespace: &str, endpoint: &Endpoint) -> bool {
+    if let Some(block) = &peer.ip_block {
+        return endpoint.ip.is_some_and(|ip| {
+            cidr_contains(&block.cidr, ip)
+                && !block
+                    .except
+                    .iter()
+                    .flatten()
+                    .any(|except| cidr_contains(except, ip))
+        });
+    }
+    let namespace_matches = match &peer.namespace_selector {
+        Some(selector) => selects(selector, &endpoint.namespace_labels),
+        None => endpoint.namespace == namespace,
+    };
+    namespace_matches
+        && peer
+            .pod_selector
+            .as_ref()
+            .is_none_or(|selector| selects(selector, &endpoint.labels))
+}
+
+/// Whether a rule's `ports` admit `port` on `destination`; named ports are
+/// looked up among the destination's container ports. Without a port every
+/// rule admits some traffic.
+fn port_allowed(
+    ports: Option<&[NetworkPolicyPort]>,
+    port: Option<&PortQuery>,
+    destination: &Endpoint,
+) -> bool {
+    let (Some(ports), Some(port)) = (ports, port) else {
+        return true;
+    };
+    ports.is_empty()
+        || ports.iter().any(|allowed| {
+            let protocol = allowed.protocol.as_deref().unwrap_or("TCP");
+            protocol.eq_ignore_ascii_case(&port.protocol)
+                && match &allowed.port {
+                    None => true,
+                    Some(IntOrString::Int(start)) => {
+                        let end = allowed.end_port.unwrap_or(*start);
+                        (*start..=end).contains(&i32::from(port.port))
+                    }
+                    Some(IntOrString::String(name)) => destination.ports.iter().any(|p| {
+                        p.name.as_deref() == Some(name.as_str())
+                            && p.port == port.port
+                            && p.protocol.eq_ignore_ascii_case(&port.protocol)
+                    }),
+                }
+        })
+}
+
+/// Evaluate the policies selecting `subject` for traffic in `direction`
+/// to or from `peer`
+pub fn evaluate(
+    policies: &[NetworkPolicy],
+    direction: Direction,
+    subject: &Endpoint,
+    peer: &Endpoint,
+    port: Option<&PortQuery>,
+) -> Verdict {
+    let destination = match direction {
+        Direction::Ingress => subject,
+        Direction::Egress => peer,
+    };
+    let mut verdict = Verdict {
+        direction,
+        isolated: false,
+        allowed: true,
+        policies: Vec::new(),
+        allowed_by: Vec::new(),
+    };
+    for policy in policies {
+        let namespace = policy.metadata.namespace.as_deref().unwrap_or_default();
+        if !policy_types(policy).contains(&direction) || !policy_selects(policy, subject) {
+            continue;
+        }
+        let name = format!(
+            "{}/{}",
+            namespace,
+            policy.metadata.name.as_deref().unwrap_or_default()
+        );
+        let allows = rules(policy, direction).iter().any(|rule| {
+            let peer_allowed = match rule.peers {
+                Some(peers) if !peers.is_empty() => {
+                    peers.iter().any(|p| peer_matches(p, namespace, peer))
+                }
+                _ => true,
+            };
+            peer_allowed && port_allowed(rule.ports, port, destination)
+        });
+        if allows {
+            verdict.allowed_by.push(name.clone());
+        }
+        verdict.policies.push(name);
+    }
+    verdict.isolated = !verdict.policies.is_empty();
+    verdict.allowed = !verdict.isolated || !verdict.allowed_by.is_empty();
+    verdict
+}
+
+/// Whether `policies` let traffic from `from` reach `to`
+pub fn can_reach(
+    policies: &[NetworkPolicy],
+    from: &Endpoint,
+    to: &Endpoint,
+    port: Option<&PortQuery>,
+) -> Reachability {
+    let egress = evaluate(policies, Direction::Egress, from, to, port);
+    let ingress = evaluate(policies, Direction::Ingress, to, from, port);
+    Reachability {
+        from: from.name.clone(),
+        from_namespace: from.namespace.clone(),
+        to: to.name.clone(),
+        to_namespace: to.namespace.clone(),
+        port: port.cloned(),
+        allowed: egress.allowed && ingress.allowed,
+        egress,
+        ingress,
+    }
+}
+
+/// NetworkPolicies in `namespace`, or in every namespace in scope
+pub(crate) async fn fetch_policies(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<NetworkPolicy>> {
+    let api: Api<NetworkPolicy> = match namespace {
+        Some(ns) => Api::namespaced(discovery.client().clone(), ns),
+        None => Api::all(discovery.client().clone()),
+    };
+    let mut policies = api.list(&ListParams::default()).await?.items;
+    if namespace.is_none() {
+        discovery
+            .namespace_scope()
+            .retain(&mut policies, |p| p.metadata.namespace.as_deref());
+    }
+    Ok(policies)
+}
+
+/// Describe the NetworkPolicies in `namespace` (all namespaces when `None`)
+pub async fn list_network_policies(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<NetworkPolicyInfo>> {
+    let policies = fetch_policies(discovery, namespace).await?;
+    let pods = discovery.list_pods(namespace, None).await?;
+    Ok(policies.iter().map(|p| summarize(p, &pods)).collect())
+}
+
+/// Labels of every namespace, by name. Namespaces that may not be read
+/// get only the name label the API server sets.
+pub async fn namespace_labels(
+    discovery: &DiscoveryEngine,
+    names: &[&str],
+) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+    let api: Api<Namespace> = Api::all(discovery.client().clone());
+    let mut labels = match api.list_metadata(&ListParams::default()).await {
+        Ok(list) => list
+            .items
+            .into_iter()
+            .filter_map(|ns| Some((ns.metadata.name?, ns.metadata.labels.unwrap_or_default())))
+            .collect(),
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => BTreeMap::new(),
+        Err(e) => return Err(e.into()),
+    };
+    for name in names {
+        labels.entry(name.to_string()).or_insert_with(|| {
+            BTreeMap::from([(NAMESPACE_NAME_LABEL.to_string(), name.to_string())])
+        });
+    }
+    Ok(labels)
+}
+
+fn container_ports(spec: Option<&PodSpec>) -> Vec<NamedPort> {
+    spec.into_iter()
+        .flat_map(|s| &s.containers)
+        .flat_map(|c| c.ports.iter().flatten())
+        .filter_map(|p| {
+            Some(NamedPort {
+                name: p.name.clone(),
+                port: u16::try_from(p.container_port).ok()?,
+                protocol: p.protocol.clone().unwrap_or_else(|| "TCP".to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Endpoint of a pod, or of the pods of a Deployment, StatefulSet or
+/// DaemonSet through their pod template
+pub async fn endpoint(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    target: &ObjectRef,
+) -> Result<Endpoint> {
+    let client = discovery.client().clone();
+    let template = |template: Option<&PodTemplateSpec>| {
+        (
+            template
+                .and_then(|t| t.metadata.as_ref())
+                .and_then(|m| m.labels.clone())
+                .unwrap_or_default(),
+            container_ports(template.and_then(|t| t.spec.as_ref())),
+        )
+    };
+    let (labels, ports, ip) = match target.kind {
+        ResourceKind::Pod => {
+            let pod = Api::<Pod>::namespaced(client, namespace)
+                .get(&target.name)
+                .await?;
+            let ip = pod
+                .status
+                .as_ref()
+                .and_then(|s| s.pod_ip.as_deref())
+                .and_then(|ip| ip.parse().ok());
+            (
+                pod.metadata.labels.unwrap_or_default(),
+                container_ports(pod.spec.as_ref()),
+                ip,
+            )
+        }
+        ResourceKind::Deployment => {
+            let deployment = Api::<Deployment>::namespaced(client, namespace)
+                .get(&target.name)
+                .await?;
+            let (labels, ports) = template(deployment.spec.as_ref().map(|s| &s.template));
+            (labels, ports, None)
+        }
+        ResourceKind::StatefulSet => {
+            let statefulset = Api::<StatefulSet>::namespaced(client, namespace)
+                .get(&target.name)
+                .await?;
+            let (labels, ports) = template(statefulset.spec.as_ref().map(|s| &s.template));
+            (labels, ports, None)
+        }
+        ResourceKind::DaemonSet => {
+            let daemonset = Api::<DaemonSet>::namespaced(client, namespace)
+                .get(&target.name)
+                .await?;
+            let (labels, ports) = template(daemonset.spec.as_ref().map(|s| &s.template));
+            (labels, ports, None)
+        }
+        kind => {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "can-reach takes pods, deployments, statefulsets and daemonsets, not {}",
+                kind
+            )))
+        }
+    };
+    let namespace_labels = namespace_labels(discovery, &[namespace])
+        .await?
+        .remove(namespace)
+        .unwrap_or_default();
+    Ok(Endpoint {
+        namespace: namespace.to_string(),
+        name: target.to_string(),
+        labels,
+        namespace_labels,
+        ip,
+        ports,
+    })
+}
+
+/// Whether the NetworkPolicies of both namespaces let traffic from `from`
+/// reach `to`
+pub async fn check_reachability(
+    discovery: &DiscoveryEngine,
+    from: &Endpoint,
+    to: &Endpoint,
+    port: Option<&PortQuery>,
+) -> Result<Reachability> {
+    let mut policies = fetch_policies(discovery, Some(&from.namespace)).await?;
+    if to.namespace != from.namespace {
+        policies.extend(fetch_policies(discovery, Some(&to.namespace)).await?);
+    }
+    Ok(can_reach(&policies, from, to, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(namespace: &str, name: &str, spec: serde_json::Value) -> NetworkPolicy {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": name, "namespace": namespace},
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+
+    fn endpoint(namespace: &str, name: &str, app: &str, ip: &str) -> Endpoint {
+        Endpoint {
+            namespace: namespace.to_string(),
+            name: format!("pod/{}", name),
+            labels: BTreeMap::from([("app".to_string(), app.to_string())]),
+            namespace_labels: BTreeMap::from([(
+                NAMESPACE_NAME_LABEL.to_string(),
+                namespace.to_string(),
+            )]),
+            ip: ip.parse().ok(),
+            ports: vec![NamedPort {
+                name: Some("postgres".to_string()),
+                port: 5432,
+                protocol: "TCP".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert!(cidr_contains("10.0.0.0/8", ip("10.1.2.3")));
+        assert!(!cidr_contains("10.0.0.0/16", ip("10.1.2.3")));
+        assert!(cidr_contains("0.0.0.0/0", ip("192.168.1.1")));
+        assert!(cidr_contains("10.1.2.3/32", ip("10.1.2.3")));
+        assert!(cidr_contains("fd00::/8", ip("fd12::1")));
+        assert!(!cidr_contains("10.0.0.0/8", ip("fd12::1")));
+        assert!(!cidr_contains("10.0.0.0", ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_can_reach() {
+        let web = endpoint("shop", "web-0", "web", "10.0.1.5");
+        let db = endpoint("data", "db-0", "db", "10.0.2.7");
+        let postgres = PortQuery::resolve("postgres", "tcp", &db).unwrap();
+        assert_eq!(postgres.port, 5432);
+
+        // Nothing selects either pod: everything is allowed
+        let open = can_reach(&[], &web, &db, Some(&postgres));
+        assert!(open.allowed && !open.ingress.isolated);
+
+        let policies = vec![
+            policy(
+                "data",
+                "default-deny",
+                serde_json::json!({"podSelector": {}}),
+            ),
+            policy(
+                "data",
+                "allow-shop",
+                serde_json::json!({
+                    "podSelector": {"matchLabels": {"app": "db"}},
+                    "ingress": [{
+                        "from": [{
+                            "namespaceSelector": {"matchLabels": {NAMESPACE_NAME_LABEL: "shop"}},
+                            "podSelector": {"matchLabels": {"app": "web"}},
+                        }],
+                        "ports": [{"port": "postgres"}],
+                    }],
+                }),
+            ),
+            policy(
+                "shop",
+                "egress-internal",
+                serde_json::json!({
+                    "podSelector": {},
+                    "policyTypes": ["Egress"],
+                    "egress": [{"to": [{"ipBlock": {"cidr": "10.0.0.0/16", "except": ["10.0.3.0/24"]}}]}],
+                }),
+            ),
+        ];
+
+        let reach = can_reach(&policies, &web, &db, Some(&postgres));
+        assert!(reach.allowed);
+        assert_eq!(
+            reach.ingress.policies,
+            ["data/default-deny", "data/allow-shop"]
+        );
+        assert_eq!(reach.ingress.allowed_by, ["data/allow-shop"]);
+        assert_eq!(reach.egress.allowed_by, ["shop/egress-internal"]);
+
+        let http = PortQuery::resolve("80", "TCP", &db).unwrap();
+        let reach = can_reach(&policies, &web, &db, Some(&http));
+        assert!(!reach.allowed && reach.egress.allowed && !reach.ingress.allowed);
+
+        let other = endpoint("data", "db-1", "db", "10.0.3.9");
+        assert!(!can_reach(&policies, &web, &other, None).egress.allowed);
+        let admin = endpoint("ops", "admin-0", "web", "10.0.4.1");
+        assert!(!can_reach(&policies, &admin, &db, Some(&postgres)).allowed);
+
+        let info = summarize(&policies[1], &[]);
+        assert_eq!(info.pod_selector, "app=db");
+        assert_eq!(info.policy_types, [Direction::Ingress]);
+        assert_eq!(
+            info.ingress,
+            ["pods app=web in namespaces kubernetes.io/metadata.name=shop on TCP/postgres"]
+        );
+        assert_eq!(
+            summarize(&policies[2], &[]).egress,
+            ["10.0.0.0/16 except 10.0.3.0/24"]
+        );
+    }
+}
@@ -0,0 +1,509 @@
+//! Cluster network sanity checks (`kdx network-check`)
+//!
+//! Address problems rarely surface as errors: two pods sharing an IP after a
+//! CNI hiccup, an EndpointSlice still pointing at an address its pod no
+//! longer has, or a Service IP inside the pod network all show up as
+//! intermittent misrouted traffic. This module cross-checks pod IPs,
+//! EndpointSlices, Service IPs and NodePorts against each other and against
+//! the node pod CIDRs and the service CIDRs given on the command line.
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use k8s_openapi::api::core::v1::{Node, Pod, Service};
+use k8s_openapi::api::discovery::v1::EndpointSlice;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+/// An IPv4 or IPv6 network in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    bits: u32,
+}
+
+impl Cidr {
+    pub fn parse(value: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("invalid CIDR '{}'", value);
+        let (network, bits) = value.trim().split_once('/').ok_or_else(invalid)?;
+        let network: IpAddr = network.parse().map_err(|_| invalid())?;
+        let bits: u32 = bits.parse().map_err(|_| invalid())?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        if bits > max {
+            return Err(invalid());
+        }
+        Ok(Self { network, bits })
+    }
+
+    /// Whether `ip` lies in this network
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let (network, ip) = match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => (
+                (u32::from(network) as u128) << 96,
+                (u32::from(ip) as u128) << 96,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => (u128::from(network), u128::from(ip)),
+            _ => return false,
+        };
+        self.bits == 0 || (network ^ ip) >> (128 - self.bits) == 0
+    }
+
+    /// Whether the two networks share any address
+    pub fn overlaps(&self, other: &Cidr) -> bool {
+        self.contains(other.network) || other.contains(self.network)
+    }
+}
+
+impl std::fmt::Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.bits)
+    }
+}
+
+/// The range NodePorts are allocated from, inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodePortRange {
+    pub start: i32,
+    pub end: i32,
+}
+
+impl Default for NodePortRange {
+    /// The kube-apiserver default, `--service-node-port-range=30000-32767`
+    fn default() -> Self {
+        Self {
+            start: 30000,
+            end: 32767,
+        }
+    }
+}
+
+impl NodePortRange {
+    pub fn parse(value: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("invalid port range '{}' (expected START-END)", value);
+        let (start, end) = value.trim().split_once('-').ok_or_else(invalid)?;
+        let start: u16 = start.parse().map_err(|_| invalid())?;
+        let end: u16 = end.parse().map_err(|_| invalid())?;
+        if start > end {
+            return Err(invalid());
+        }
+        Ok(Self {
+            start: start.into(),
+            end: end.into(),
+        })
+    }
+}
+
+/// Kind of network problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkIssueKind {
+    /// Several live pods report the same IP
+    DuplicatePodIp,
+    /// An EndpointSlice address whose target pod is gone or has another IP
+    StaleEndpoint,
+    /// Several Services have the same cluster IP
+    DuplicateClusterIp,
+    /// A cluster IP outside every configured service CIDR
+    ClusterIpOutsideServiceCidr,
+    /// A cluster IP inside a node's pod CIDR
+    ClusterIpInPodCidr,
+    /// A configured service CIDR overlapping a node's pod CIDR
+    ServiceCidrOverlap,
+    /// A NodePort outside the expected range
+    NodePortOutOfRange,
+}
+
+impl std::fmt::Display for NetworkIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkIssueKind::DuplicatePodIp => write!(f, "duplicate pod IP"),
+            NetworkIssueKind::StaleEndpoint => write!(f, "stale endpoint"),
+            NetworkIssueKind::DuplicateClusterIp => write!(f, "duplicate cluster IP"),
+            NetworkIssueKind::ClusterIpOutsideServiceCidr => {
+                write!(f, "cluster IP outside service CIDR")
+            }
+            NetworkIssueKind::ClusterIpInPodCidr => write!(f, "cluster IP in pod CIDR"),
+            NetworkIssueKind::ServiceCidrOverlap => write!(f, "service CIDR overlap"),
+            NetworkIssueKind::NodePortOutOfRange => write!(f, "NodePort out of range"),
+        }
+    }
+}
+
+/// A problem with the address involved and the objects claiming it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkIssue {
+    pub kind: NetworkIssueKind,
+    /// IP, CIDR or port the issue is about
+    pub address: String,
+    /// Objects involved, as `kind namespace/name` (or `kind name`)
+    pub objects: Vec<String>,
+    pub detail: Option<String>,
+}
+
+/// What the cluster is expected to look like; CIDR checks are skipped when
+/// no service CIDR is given
+#[derive(Debug, Clone, Default)]
+pub struct NetworkSettings {
+    pub service_cidrs: Vec<Cidr>,
+    pub node_port_range: NodePortRange,
+}
+
+fn object(kind: &str, namespace: Option<&String>, name: Option<&String>) -> String {
+    let name = name.map(String::as_str).unwrap_or_default();
+    match namespace {
+        Some(ns) => format!("{} {}/{}", kind, ns, name),
+        None => format!("{} {}", kind, name),
+    }
+}
+
+/// IPs of a pod that owns its addresses: host-network pods share the node's
+/// IP and finished pods may have handed theirs on
+fn live_pod_ips(pod: &Pod) -> Vec<String> {
+    let host_network = pod
+        .spec
+        .as_ref()
+        .and_then(|s| s.host_network)
+        .unwrap_or(false);
+    let Some(status) = &pod.status else {
+        return Vec::new();
+    };
+    let finished = matches!(status.phase.as_deref(), Some("Succeeded" | "Failed"));
+    if host_network || finished {
+        return Vec::new();
+    }
+
+    let mut ips: Vec<String> = status
+        .pod_ips
+        .iter()
+        .flatten()
+        .filter_map(|ip| ip.ip.clone())
+        .collect();
+    if ips.is_empty() {
+        ips.extend(status.pod_ip.clone());
+    }
+    ips
+}
+
+fn duplicate_pod_ips(pods: &[Pod]) -> Vec<NetworkIssue> {
+    let mut by_ip: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for pod in pods {
+        for ip in live_pod_ips(pod) {
+            by_ip.entry(ip).or_default().push(object(
+                "Pod",
+                pod.metadata.namespace.as_ref(),
+                pod.metadata.name.as_ref(),
+            ));
+        }
+    }
+    by_ip
+        .into_iter()
+        .filter(|(_, objects)| objects.len() > 1)
+        .map(|(address, objects)| NetworkIssue {
+            kind: NetworkIssueKind::DuplicatePodIp,
+            address,
+            objects,
+            detail: None,
+        })
+        .collect()
+}
+
+fn stale_endpoints(pods: &[Pod], slices: &[EndpointSlice]) -> Vec<NetworkIssue> {
+    let pod_ips: HashMap<(&str, &str), Vec<String>> = pods
+        .iter()
+        .filter_map(|p| {
+            Some((
+                (
+                    p.metadata.namespace.as_deref()?,
+                    p.metadata.name.as_deref()?,
+                ),
+                live_pod_ips(p),
+            ))
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    for slice in slices {
+        let namespace = slice.metadata.namespace.as_deref().unwrap_or_default();
+        for endpoint in &slice.endpoints {
+            let Some(target) = &endpoint.target_ref else {
+                continue;
+            };
+            if target.kind.as_deref() != Some("Pod") {
+                continue;
+            }
+            let pod_namespace = target.namespace.as_deref().unwrap_or(namespace);
+            let pod_name = target.name.as_deref().unwrap_or_default();
+            let current = pod_ips.get(&(pod_namespace, pod_name));
+
+            for address in &endpoint.addresses {
+                let detail = match current {
+                    None => "pod no longer exists".to_string(),
+                    Some(ips) if ips.contains(address) => continue,
+                    Some(ips) if ips.is_empty() => "pod has no live IP".to_string(),
+                    Some(ips) => format!("pod now has {}", ips.join(", ")),
+                };
+                issues.push(NetworkIssue {
+                    kind: NetworkIssueKind::StaleEndpoint,
+                    address: address.clone(),
+                    objects: vec![
+                        object(
+                            "EndpointSlice",
+                            slice.metadata.namespace.as_ref(),
+                            slice.metadata.name.as_ref(),
+                        ),
+                        format!("Pod {}/{}", pod_namespace, pod_name),
+                    ],
+                    detail: Some(detail),
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn service_issues(
+    services: &[Service],
+    pod_cidrs: &[(String, Cidr)],
+    settings: &NetworkSettings,
+) -> Vec<NetworkIssue> {
+    let mut issues = Vec::new();
+    let mut by_ip: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for service in services {
+        let name = object(
+            "Service",
+            service.metadata.namespace.as_ref(),
+            service.metadata.name.as_ref(),
+        );
+        let Some(spec) = &service.spec else {
+            continue;
+        };
+
+        let cluster_ips = spec
+            .cluster_ips
+            .clone()
+            .or_else(|| spec.cluster_ip.clone().map(|ip| vec![ip]))
+            .unwrap_or_default();
+        for ip in cluster_ips {
+            // Headless services have no address to collide
+            let Ok(address) = ip.parse::<IpAddr>() else {
+                continue;
+            };
+            by_ip.entry(ip.clone()).or_default().push(name.clone());
+
+            let family = settings
+                .service_cidrs
+                .iter()
+                .filter(|c| c.network.is_ipv4() == address.is_ipv4());
+            if family.clone().next().is_some() && !family.clone().any(|c| c.contains(address)) {
+                issues.push(NetworkIssue {
+                    kind: NetworkIssueKind::ClusterIpOutsideServiceCidr,
+                    address: ip.clone(),
+                    objects: vec![name.clone()],
+                    detail: Some(format!(
+                        "service CIDR {}",
+                        family.map(Cidr::to_string).collect::<Vec<_>>().join(", ")
+                    )),
+                });
+            }
+            for (node, cidr) in pod_cidrs.iter().filter(|(_, c)| c.contains(address)) {
+                issues.push(NetworkIssue {
+                    kind: NetworkIssueKind::ClusterIpInPodCidr,
+                    address: ip.clone(),
+                    objects: vec![name.clone(), format!("Node {}", node)],
+                    detail: Some(format!("pod CIDR {}", cidr)),
+                });
+            }
+        }
+
+        let range = settings.node_port_range;
+        for port in spec.ports.iter().flatten() {
+            let Some(node_port) = port.node_port else {
+                continue;
+            };
+            if node_port < range.start || node_port > range.end {
+                issues.push(NetworkIssue {
+                    kind: NetworkIssueKind::NodePortOutOfRange,
+                    address: node_port.to_string(),
+                    objects: vec![name.clone()],
+                    detail: Some(format!("expected {}-{}", range.start, range.end)),
+                });
+            }
+        }
+    }
+
+    issues.extend(
+        by_ip
+            .into_iter()
+            .filter(|(_, objects)| objects.len() > 1)
+            .map(|(address, objects)| NetworkIssue {
+                kind: NetworkIssueKind::DuplicateClusterIp,
+                address,
+                objects,
+                detail: None,
+            }),
+    );
+    issues
+}
+
+/// Check pods, EndpointSlices, Services and node pod CIDRs against each
+/// other and against `settings`
+pub fn check_network(
+    pods: &[Pod],
+    slices: &[EndpointSlice],
+    services: &[Service],
+    nodes: &[Node],
+    settings: &NetworkSettings,
+) -> Vec<NetworkIssue> {
+    let pod_cidrs: Vec<(String, Cidr)> = nodes
+        .iter()
+        .flat_map(|node| {
+            let name = node.metadata.name.clone().unwrap_or_default();
+            let spec = node.spec.as_ref();
+            spec.and_then(|s| s.pod_cidrs.clone())
+                .or_else(|| spec.and_then(|s| s.pod_cidr.clone()).map(|c| vec![c]))
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(move |cidr| Some((name.clone(), Cidr::parse(&cidr).ok()?)))
+        })
+        .collect();
+
+    let mut issues = duplicate_pod_ips(pods);
+    issues.extend(stale_endpoints(pods, slices));
+    issues.extend(service_issues(services, &pod_cidrs, settings));
+    for service_cidr in &settings.service_cidrs {
+        for (node, cidr) in pod_cidrs.iter().filter(|(_, c)| c.overlaps(service_cidr)) {
+            issues.push(NetworkIssue {
+                kind: NetworkIssueKind::ServiceCidrOverlap,
+                address: service_cidr.to_string(),
+                objects: vec![format!("Node {}", node)],
+                detail: Some(format!("pod CIDR {}", cidr)),
+            });
+        }
+    }
+    issues.sort_by(|a, b| (a.kind, &a.address).cmp(&(b.kind, &b.address)));
+    issues
+}
+
+/// List pods, EndpointSlices, Services and nodes across the cluster and
+/// check them. Addresses are cluster-wide, so there is no namespace scope.
+pub async fn find_network_issues(
+    discovery: &DiscoveryEngine,
+    settings: &NetworkSettings,
+) -> Result<Vec<NetworkIssue>> {
+    let client = discovery.client().clone();
+    let params = ListParams::default();
+
+    let (pods, slices, services, nodes): (Api<Pod>, Api<EndpointSlice>, Api<Service>, Api<Node>) = (
+        Api::all(client.clone()),
+        Api::all(client.clone()),
+        Api::all(client.clone()),
+        Api::all(client),
+    );
+    let (pods, slices, services, nodes) = tokio::try_join!(
+        pods.list(&params),
+        slices.list(&params),
+        services.list(&params),
+        nodes.list(&params),
+    )?;
+
+    Ok(check_network(
+        &pods.items,
+        &slices.items,
+        &services.items,
+        &nodes.items,
+        settings,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_json<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> T {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn pod(name: &str, ip: &str) -> Pod {
+        from_json(serde_json::json!({
+            "metadata": {"name": name, "namespace": "shop"},
+            "spec": {"containers": []},
+            "status": {"phase": "Running", "podIP": ip},
+        }))
+    }
+
+    fn service(name: &str, ip: &str, node_port: Option<i32>) -> Service {
+        from_json(serde_json::json!({
+            "metadata": {"name": name, "namespace": "shop"},
+            "spec": {"clusterIP": ip, "ports": [{"port": 80, "nodePort": node_port}]},
+        }))
+    }
+
+    #[test]
+    fn test_cidr() {
+        let cidr = Cidr::parse("10.96.0.0/12").unwrap();
+        assert!(cidr.contains("10.100.1.1".parse().unwrap()));
+        assert!(!cidr.contains("10.112.0.1".parse().unwrap()));
+        assert!(!cidr.contains("fd00::1".parse().unwrap()));
+        assert!(cidr.overlaps(&Cidr::parse("10.0.0.0/8").unwrap()));
+        assert!(!cidr.overlaps(&Cidr::parse("10.244.0.0/16").unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("10.0.0.0").is_err());
+        assert_eq!(
+            NodePortRange::parse("30000-32767").unwrap(),
+            NodePortRange::default()
+        );
+        assert!(NodePortRange::parse("32767-30000").is_err());
+    }
+
+    #[test]
+    fn test_check_network() {
+        let pods = [
+            pod("web-1", "10.244.1.5"),
+            pod("web-2", "10.244.1.5"),
+            pod("api-1", "10.244.1.7"),
+        ];
+        let slices: Vec<EndpointSlice> = vec![from_json(serde_json::json!({
+            "metadata": {"name": "api-x1", "namespace": "shop"},
+            "addressType": "IPv4",
+            "endpoints": [
+                {"addresses": ["10.244.1.7"], "targetRef": {"kind": "Pod", "name": "api-1"}},
+                {"addresses": ["10.244.1.9"], "targetRef": {"kind": "Pod", "name": "api-0"}},
+            ],
+        }))];
+        let services = [
+            service("web", "10.96.0.10", Some(30080)),
+            service("api", "10.96.0.10", Some(8080)),
+            service("db", "10.244.0.3", None),
+            service("headless", "None", None),
+        ];
+        let nodes: Vec<Node> = vec![from_json(serde_json::json!({
+            "metadata": {"name": "node-a"},
+            "spec": {"podCIDR": "10.244.0.0/24"},
+        }))];
+        let settings = NetworkSettings {
+            service_cidrs: vec![Cidr::parse("10.96.0.0/12").unwrap()],
+            ..Default::default()
+        };
+
+        let issues = check_network(&pods, &slices, &services, &nodes, &settings);
+        let kinds: Vec<(NetworkIssueKind, &str)> = issues
+            .iter()
+            .map(|i| (i.kind, i.address.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (NetworkIssueKind::DuplicatePodIp, "10.244.1.5"),
+                (NetworkIssueKind::StaleEndpoint, "10.244.1.9"),
+                (NetworkIssueKind::DuplicateClusterIp, "10.96.0.10"),
+                (NetworkIssueKind::ClusterIpOutsideServiceCidr, "10.244.0.3"),
+                (NetworkIssueKind::ClusterIpInPodCidr, "10.244.0.3"),
+                (NetworkIssueKind::NodePortOutOfRange, "8080"),
+            ]
+        );
+        assert_eq!(issues[1].detail.as_deref(), Some("pod no longer exists"));
+    }
+}
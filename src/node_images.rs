@@ -0,0 +1,290 @@
+//! Images cached on nodes (`kdx node-images`)
+//!
+//! The kubelet reports the images in its container runtime's store in the
+//! node's `status.images`, each under its tag and digest references with its
+//! size. An image is in use on a node when a pod there that has not finished
+//! references it from a container spec or status; references are compared
+//! fully qualified, so `nginx` matches `docker.io/library/nginx:latest`.
+//! Images that no such pod anywhere references are what image garbage
+//! collection, or a manual prune, could free. The pod sandbox (`pause`)
+//! image is never counted as unused since the runtime keeps it pinned.
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+
+/// One image in a node's store
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedImage {
+    /// First tag reference, or a digest reference for untagged images
+    pub name: String,
+    pub size_bytes: u64,
+    /// Whether a pod on this node references the image
+    pub in_use: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeImages {
+    pub node: String,
+    /// Largest first
+    pub images: Vec<CachedImage>,
+    pub total_bytes: u64,
+    /// Bytes of images no pod on the node references
+    pub unused_bytes: u64,
+}
+
+/// An image that no pod in the cluster references
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnusedImage {
+    pub name: String,
+    pub size_bytes: u64,
+    pub nodes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeImageReport {
+    /// Largest footprint first
+    pub nodes: Vec<NodeImages>,
+    /// Most space across nodes first
+    pub unused: Vec<UnusedImage>,
+}
+
+/// Fully qualify an image reference: default registry and `library/`
+/// namespace, `latest` without a tag or digest, runtime prefixes dropped
+pub fn normalize_image(reference: &str) -> String {
+    let reference = reference
+        .strip_prefix("docker-pullable://")
+        .or_else(|| reference.strip_prefix("docker://"))
+        .unwrap_or(reference);
+    if reference.starts_with("sha256:") {
+        return reference.to_string();
+    }
+
+    let (name, digest) = match reference.split_once('@') {
+        Some((name, digest)) => (name, Some(digest)),
+        None => (reference, None),
+    };
+    let mut qualified = match name.split_once('/') {
+        Some((domain, _))
+            if domain.contains('.') || domain.contains(':') || domain == "localhost" =>
+        {
+            name.to_string()
+        }
+        Some(_) => format!("docker.io/{}", name),
+        None => format!("docker.io/library/{}", name),
+    };
+    let last = qualified.rsplit('/').next().unwrap_or_default();
+    match digest {
+        Some(digest) => {
+            qualified.push('@');
+            qualified.push_str(digest);
+        }
+        None if !last.contains(':') => qualified.push_str(":latest"),
+        None => {}
+    }
+    qualified
+}
+
+/// Whether the image is the pod sandbox image (`registry.k8s.io/pause`)
+fn is_sandbox(name: &str) -> bool {
+    let repository = name.split('@').next().unwrap_or_default();
+    let repository = repository
+        .rsplit_once(':')
+        .filter(|(_, tag)| !tag.contains('/'))
+        .map_or(repository, |(repository, _)| repository);
+    repository.ends_with("/pause")
+}
+
+/// Build the report from nodes and the pods of every namespace
+pub fn node_image_report(nodes: &[Node], pods: &[Pod]) -> NodeImageReport {
+    let mut used_on: BTreeMap<&str, HashSet<String>> = BTreeMap::new();
+    for pod in pods {
+        let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+        if matches!(phase, Some("Succeeded") | Some("Failed")) {
+            continue;
+        }
+        let Some(spec) = &pod.spec else { continue };
+        let Some(node) = spec.node_name.as_deref() else {
+            continue;
+        };
+        let used = used_on.entry(node).or_default();
+        let specs = spec
+            .containers
+            .iter()
+            .chain(spec.init_containers.iter().flatten())
+            .filter_map(|c| c.image.as_deref());
+        used.extend(specs.map(normalize_image));
+        if let Some(status) = &pod.status {
+            let statuses = status
+                .container_statuses
+                .iter()
+                .flatten()
+                .chain(status.init_container_statuses.iter().flatten());
+            for s in statuses {
+                used.extend(
+                    [&s.image, &s.image_id]
+                        .into_iter()
+                        .filter(|r| !r.is_empty())
+                        .map(|r| normalize_image(r)),
+                );
+            }
+        }
+    }
+    let used_anywhere: HashSet<&String> = used_on.values().flatten().collect();
+
+    let mut report = NodeImageReport {
+        nodes: Vec::new(),
+        unused: Vec::new(),
+    };
+    let mut unused: BTreeMap<String, UnusedImage> = BTreeMap::new();
+    for node in nodes {
+        let name = node.metadata.name.clone().unwrap_or_default();
+        let used = used_on.get(name.as_str());
+        let mut images = Vec::new();
+        for image in node
+            .status
+            .as_ref()
+            .and_then(|s| s.images.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            let names: Vec<String> = image
+                .names
+                .iter()
+                .flatten()
+                .map(|n| normalize_image(n))
+                .collect();
+            let Some(display) = image
+                .names
+                .iter()
+                .flatten()
+                .find(|n| !n.contains('@'))
+                .or_else(|| image.names.as_ref().and_then(|n| n.first()))
+            else {
+                continue;
+            };
+            let size_bytes = image.size_bytes.unwrap_or_default().max(0) as u64;
+            let sandbox = names.iter().any(|n| is_sandbox(n));
+            if !sandbox && !names.iter().any(|n| used_anywhere.contains(n)) {
+                unused
+                    .entry(display.clone())
+                    .or_insert_with(|| UnusedImage {
+                        name: display.clone(),
+                        size_bytes,
+                        nodes: Vec::new(),
+                    })
+                    .nodes
+                    .push(name.clone());
+            }
+            images.push(CachedImage {
+                name: display.clone(),
+                size_bytes,
+                in_use: sandbox || used.is_some_and(|used| names.iter().any(|n| used.contains(n))),
+            });
+        }
+        images.sort_by_key(|i| Reverse(i.size_bytes));
+        report.nodes.push(NodeImages {
+            node: name,
+            total_bytes: images.iter().map(|i| i.size_bytes).sum(),
+            unused_bytes: images
+                .iter()
+                .filter(|i| !i.in_use)
+                .map(|i| i.size_bytes)
+                .sum(),
+            images,
+        });
+    }
+    report.nodes.sort_by_key(|n| Reverse(n.total_bytes));
+    report.unused = unused.into_values().collect();
+    report
+        .unused
+        .sort_by_key(|i| Reverse(i.size_bytes * i.nodes.len() as u64));
+    report
+}
+
+/// Fetch nodes and pods and build the report
+pub async fn fetch_node_images(discovery: &DiscoveryEngine) -> Result<NodeImageReport> {
+    let nodes: Api<Node> = Api::all(discovery.client().clone());
+    let pods: Api<Pod> = Api::all(discovery.client().clone());
+    let params = ListParams::default();
+    let (nodes, pods) = tokio::try_join!(nodes.list(&params), pods.list(&params))?;
+    Ok(node_image_report(&nodes.items, &pods.items))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_image() {
+        assert_eq!(normalize_image("nginx"), "docker.io/library/nginx:latest");
+        assert_eq!(
+            normalize_image("bitnami/redis:7.2"),
+            "docker.io/bitnami/redis:7.2"
+        );
+        assert_eq!(
+            normalize_image("localhost:5000/app"),
+            "localhost:5000/app:latest"
+        );
+        assert_eq!(
+            normalize_image("docker-pullable://nginx@sha256:abc"),
+            "docker.io/library/nginx@sha256:abc"
+        );
+        assert_eq!(
+            normalize_image("ghcr.io/org/tool:v1"),
+            "ghcr.io/org/tool:v1"
+        );
+    }
+
+    #[test]
+    fn test_node_image_report() {
+        let node = |name: &str| -> Node {
+            serde_json::from_value(json!({
+                "metadata": {"name": name},
+                "status": {"images": [
+                    {"names": ["docker.io/library/nginx@sha256:aa", "docker.io/library/nginx:1.25"], "sizeBytes": 70},
+                    {"names": ["quay.io/old/batch:v1"], "sizeBytes": 500},
+                    {"names": ["registry.k8s.io/pause:3.9"], "sizeBytes": 1},
+                ]}
+            }))
+            .unwrap()
+        };
+        let pod = |node: &str, image: &str, phase: &str| -> Pod {
+            serde_json::from_value(json!({
+                "spec": {"nodeName": node, "containers": [{"name": "c", "image": image}]},
+                "status": {"phase": phase}
+            }))
+            .unwrap()
+        };
+        let nodes = vec![node("a"), node("b")];
+        let pods = vec![
+            pod("a", "nginx:1.25", "Running"),
+            pod("b", "quay.io/old/batch:v1", "Succeeded"),
+        ];
+
+        let report = node_image_report(&nodes, &pods);
+        let a = report.nodes.iter().find(|n| n.node == "a").unwrap();
+        assert_eq!(a.total_bytes, 571);
+        assert_eq!(a.unused_bytes, 500);
+        assert_eq!(a.images[0].name, "quay.io/old/batch:v1");
+        assert_eq!(a.images[1].name, "docker.io/library/nginx:1.25");
+        assert!(a.images[1].in_use);
+        let b = report.nodes.iter().find(|n| n.node == "b").unwrap();
+        assert_eq!(b.unused_bytes, 570);
+
+        let unused: Vec<(&str, usize)> = report
+            .unused
+            .iter()
+            .map(|i| (i.name.as_str(), i.nodes.len()))
+            .collect();
+        // nginx runs on "a", so its copy on "b" is reclaimable there but
+        // not unused cluster-wide
+        assert_eq!(unused, vec![("quay.io/old/batch:v1", 2)]);
+    }
+}
@@ -0,0 +1,423 @@
+//! OOMKill and eviction history for `kdx oomkills`
+//!
+//! The kubelet records why a container last terminated in its
+//! `lastState.terminated`, and marks pods it evicted under node pressure as
+//! Failed with reason `Evicted`. Neither survives long: a container only
+//! remembers its latest termination, and evicted pods are garbage collected.
+//! This report collects what is still visible within a window, groups it by
+//! workload and container, puts each container's memory limit next to its
+//! current usage from the metrics API when that is installed, and flags kills
+//! on nodes that reported memory pressure or a system OOM around that time.
+
+use crate::discovery::{pod_workload, DiscoveryEngine, LazyConvert, PodInfo};
+use crate::error::Result;
+use crate::events::{list_events, EventInfo};
+use crate::quantity::parse_quantity;
+use crate::resource::ResourceKind;
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Node events reporting memory trouble
+const NODE_MEMORY_REASONS: &[&str] = &[
+    "NodeHasInsufficientMemory",
+    "EvictionThresholdMet",
+    "SystemOOM",
+];
+
+/// How far a node event may lie from a kill to be related to it
+const CORRELATION_MINUTES: i64 = 10;
+
+/// One container killed for exceeding memory, or one evicted pod
+#[derive(Debug, Clone, PartialEq)]
+pub struct Termination {
+    pub namespace: String,
+    pub pod: String,
+    pub workload_kind: String,
+    pub workload_name: String,
+    /// `None` for an eviction, which ends the whole pod
+    pub container: Option<String>,
+    pub node: Option<String>,
+    pub at: Option<DateTime<Utc>>,
+    pub memory_limit: Option<f64>,
+    pub memory_request: Option<f64>,
+}
+
+/// OOMKills and evictions of one container (or pod, for evictions) of a
+/// workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OomKillSummary {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    /// Container killed; absent for evictions
+    pub container: Option<String>,
+    pub oom_kills: usize,
+    pub evictions: usize,
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Bytes, from the pod spec
+    pub memory_limit: Option<f64>,
+    pub memory_request: Option<f64>,
+    /// Highest current usage among the workload's containers, in bytes, when
+    /// the metrics API is available
+    pub memory_usage: Option<f64>,
+    pub nodes: Vec<String>,
+    /// Nodes that reported memory pressure or a system OOM around a kill
+    pub memory_pressure_nodes: Vec<String>,
+}
+
+/// Memory `limits` or `requests` of a container, in bytes
+fn memory(resources: Option<&BTreeMap<String, Quantity>>) -> Option<f64> {
+    resources?.get("memory").and_then(|q| parse_quantity(&q.0))
+}
+
+/// Terminations of `pod` at or after `since`: containers whose current or
+/// last termination was an OOMKill, and the pod itself if it was evicted
+pub fn terminations(pod: &Pod, since: DateTime<Utc>) -> Vec<Termination> {
+    let Some(info): Option<PodInfo> = pod.lazy_convert() else {
+        return Vec::new();
+    };
+    let (workload_kind, workload_name) = pod_workload(&info);
+    let status = pod.status.as_ref();
+    let spec = pod.spec.as_ref();
+    let termination = |container: Option<String>, at: Option<DateTime<Utc>>| {
+        let resources = container.as_ref().and_then(|name| {
+            spec?
+                .containers
+                .iter()
+                .find(|c| &c.name == name)?
+                .resources
+                .as_ref()
+        });
+        Termination {
+            namespace: info.namespace.clone(),
+            pod: info.name.clone(),
+            workload_kind: workload_kind.clone(),
+            workload_name: workload_name.clone(),
+            container,
+            node: info.node_name.clone(),
+            at,
+            memory_limit: memory(resources.and_then(|r| r.limits.as_ref())),
+            memory_request: memory(resources.and_then(|r| r.requests.as_ref())),
+        }
+    };
+
+    let mut found = Vec::new();
+    for container in status
+        .and_then(|s| s.container_statuses.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        let killed = [container.state.as_ref(), container.last_state.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(|state| state.terminated.as_ref())
+            .filter(|t| t.reason.as_deref() == Some("OOMKilled"))
+            .map(|t| t.finished_at.as_ref().map(|time| time.0))
+            .find(|at| at.is_some_and(|at| at >= since));
+        if let Some(at) = killed {
+            found.push(termination(Some(container.name.clone()), at));
+        }
+    }
+
+    if status.and_then(|s| s.reason.as_deref()) == Some("Evicted") {
+        // Evicted pods keep the time their conditions last changed
+        let at = status
+            .and_then(|s| s.conditions.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|c| c.last_transition_time.as_ref().map(|t| t.0))
+            .max()
+            .or(info.created_at);
+        if at.is_none_or(|at| at >= since) {
+            found.push(termination(None, at));
+        }
+    }
+    found
+}
+
+/// Whether a node memory event on `node` lies close to `at`
+fn pressure_near(events: &[EventInfo], node: &str, at: DateTime<Utc>) -> bool {
+    let slack = Duration::minutes(CORRELATION_MINUTES);
+    events.iter().any(|e| {
+        e.object_kind == "Node"
+            && e.object_name == node
+            && NODE_MEMORY_REASONS.contains(&e.reason.as_str())
+            && e.first_seen.is_none_or(|first| first - slack <= at)
+            && e.last_seen.is_none_or(|last| at <= last + slack)
+    })
+}
+
+/// Memory usage keyed by namespace, workload kind, workload name and
+/// container
+pub type WorkloadUsage = HashMap<(String, String, String, String), f64>;
+
+/// Group `terminations` by workload and container, with the current memory
+/// `usage` of each workload container and node events to correlate kills
+/// with. Most kills first.
+pub fn summarize(
+    terminations: &[Termination],
+    usage: Option<&WorkloadUsage>,
+    node_events: &[EventInfo],
+) -> Vec<OomKillSummary> {
+    let mut summaries: BTreeMap<(String, String, String, Option<String>), OomKillSummary> =
+        BTreeMap::new();
+    let mut nodes: HashMap<_, (BTreeSet<String>, BTreeSet<String>)> = HashMap::new();
+    for t in terminations {
+        let key = (
+            t.namespace.clone(),
+            t.workload_kind.clone(),
+            t.workload_name.clone(),
+            t.container.clone(),
+        );
+        let summary = summaries
+            .entry(key.clone())
+            .or_insert_with(|| OomKillSummary {
+                namespace: t.namespace.clone(),
+                kind: t.workload_kind.clone(),
+                name: t.workload_name.clone(),
+                container: t.container.clone(),
+                oom_kills: 0,
+                evictions: 0,
+                last_seen: None,
+                memory_limit: None,
+                memory_request: None,
+                memory_usage: None,
+                nodes: Vec::new(),
+                memory_pressure_nodes: Vec::new(),
+            });
+        if t.container.is_some() {
+            summary.oom_kills += 1;
+        } else {
+            summary.evictions += 1;
+        }
+        summary.last_seen = summary.last_seen.max(t.at);
+        summary.memory_limit = summary.memory_limit.or(t.memory_limit);
+        summary.memory_request = summary.memory_request.or(t.memory_request);
+
+        let (seen, pressured) = nodes.entry(key).or_default();
+        if let Some(node) = &t.node {
+            seen.insert(node.clone());
+            if t.at.is_some_and(|at| pressure_near(node_events, node, at)) {
+                pressured.insert(node.clone());
+            }
+        }
+    }
+
+    let mut summaries: Vec<OomKillSummary> = summaries
+        .into_iter()
+        .map(|(key, mut summary)| {
+            if let Some((seen, pressured)) = nodes.remove(&key) {
+                summary.nodes = seen.into_iter().collect();
+                summary.memory_pressure_nodes = pressured.into_iter().collect();
+            }
+            if let (Some(usage), Some(container)) = (usage, &summary.container) {
+                let key = (
+                    summary.namespace.clone(),
+                    summary.kind.clone(),
+                    summary.name.clone(),
+                    container.clone(),
+                );
+                summary.memory_usage = usage.get(&key).copied();
+            }
+            summary
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        (b.oom_kills + b.evictions)
+            .cmp(&(a.oom_kills + a.evictions))
+            .then(b.last_seen.cmp(&a.last_seen))
+    });
+    summaries
+}
+
+/// Highest current memory usage of each workload container from the
+/// metrics API, with `workloads` mapping namespace and pod name to the
+/// workload kind and name; `None` when metrics-server is not installed or
+/// may not be read
+async fn memory_usage(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+    workloads: &HashMap<(String, String), (String, String)>,
+) -> Option<WorkloadUsage> {
+    let resource = ApiResource {
+        group: "metrics.k8s.io".to_string(),
+        version: "v1beta1".to_string(),
+        api_version: "metrics.k8s.io/v1beta1".to_string(),
+        kind: "PodMetrics".to_string(),
+        plural: "pods".to_string(),
+    };
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(discovery.client().clone(), ns, &resource),
+        None => Api::all_with(discovery.client().clone(), &resource),
+    };
+    let metrics = api.list(&ListParams::default()).await.ok()?;
+    let mut usage = WorkloadUsage::new();
+    for pod in metrics.items {
+        let (Some(namespace), Some(name)) = (pod.metadata.namespace, pod.metadata.name) else {
+            continue;
+        };
+        let Some((kind, workload)) = workloads.get(&(namespace.clone(), name)) else {
+            continue;
+        };
+        for container in pod.data["containers"].as_array().into_iter().flatten() {
+            let bytes = container["usage"]["memory"]
+                .as_str()
+                .and_then(parse_quantity);
+            if let (Some(container), Some(bytes)) = (container["name"].as_str(), bytes) {
+                let key = (
+                    namespace.clone(),
+                    kind.clone(),
+                    workload.clone(),
+                    container.to_string(),
+                );
+                let highest = usage.entry(key).or_insert(bytes);
+                *highest = highest.max(bytes);
+            }
+        }
+    }
+    Some(usage)
+}
+
+/// OOMKills and evictions in `namespace` (or all namespaces in scope) since
+/// `since`
+pub async fn find_oom_kills(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+    since: DateTime<Utc>,
+) -> Result<Vec<OomKillSummary>> {
+    let mut found = Vec::new();
+    let mut workloads = HashMap::new();
+    for (_, object) in discovery
+        .list_objects_json(namespace, &[ResourceKind::Pod])
+        .await?
+    {
+        let pod: Pod = serde_json::from_value(object)?;
+        found.extend(terminations(&pod, since));
+        if let Some(info) = LazyConvert::<PodInfo>::lazy_convert(&pod) {
+            workloads.insert(
+                (info.namespace.clone(), info.name.clone()),
+                pod_workload(&info),
+            );
+        }
+    }
+    if namespace.is_none() {
+        discovery
+            .namespace_scope()
+            .retain(&mut found, |t| Some(t.namespace.as_str()));
+    }
+    if found.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let usage = memory_usage(discovery, namespace, &workloads).await;
+    // Node events live in the default namespace; reading them is optional
+    let node_events = list_events(discovery.client(), None, Some("involvedObject.kind=Node"))
+        .await
+        .unwrap_or_default();
+    Ok(summarize(&found, usage.as_ref(), &node_events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(name: &str, status: serde_json::Value) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": name, "namespace": "shop",
+                "labels": {"pod-template-hash": "abc12"},
+                "ownerReferences": [{
+                    "apiVersion": "apps/v1", "kind": "ReplicaSet", "name": "web-abc12",
+                    "uid": "1", "controller": true,
+                }],
+                "creationTimestamp": "2024-05-01T08:00:00Z",
+            },
+            "spec": {
+                "nodeName": "node-1",
+                "containers": [{
+                    "name": "app",
+                    "resources": {"limits": {"memory": "256Mi"}, "requests": {"memory": "128Mi"}},
+                }],
+            },
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    fn oom_killed(finished_at: &str) -> serde_json::Value {
+        serde_json::json!({
+            "phase": "Running",
+            "containerStatuses": [{
+                "name": "app", "image": "web", "imageID": "", "ready": true, "restartCount": 3,
+                "state": {"running": {}},
+                "lastState": {"terminated": {
+                    "exitCode": 137, "reason": "OOMKilled", "finishedAt": finished_at,
+                }},
+            }],
+        })
+    }
+
+    #[test]
+    fn test_summarize_oom_kills() {
+        let since = "2024-05-01T09:00:00Z".parse().unwrap();
+        let pods = [
+            pod("web-abc12-x", oom_killed("2024-05-01T10:00:00Z")),
+            pod("web-abc12-y", oom_killed("2024-05-01T11:00:00Z")),
+            // Killed before the window
+            pod("web-abc12-z", oom_killed("2024-05-01T08:30:00Z")),
+            pod(
+                "web-abc12-e",
+                serde_json::json!({
+                    "phase": "Failed", "reason": "Evicted",
+                    "message": "The node was low on resource: memory.",
+                    "conditions": [{
+                        "type": "Ready", "status": "False",
+                        "lastTransitionTime": "2024-05-01T10:30:00Z",
+                    }],
+                }),
+            ),
+        ];
+        let found: Vec<Termination> = pods.iter().flat_map(|p| terminations(p, since)).collect();
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].workload_name, "web");
+        assert_eq!(found[0].memory_limit, Some(256.0 * 1024.0 * 1024.0));
+        assert_eq!(found[2].container, None);
+
+        let pressure: EventInfo = serde_json::from_value(serde_json::json!({
+            "namespace": "default", "event_type": "Warning", "reason": "SystemOOM",
+            "message": "System OOM encountered", "count": 1,
+            "object_kind": "Node", "object_name": "node-1",
+            "first_seen": "2024-05-01T10:55:00Z", "last_seen": "2024-05-01T10:58:00Z",
+            "age": "1h",
+        }))
+        .unwrap();
+        let usage = WorkloadUsage::from([(
+            (
+                "shop".to_string(),
+                "Deployment".to_string(),
+                "web".to_string(),
+                "app".to_string(),
+            ),
+            200.0 * 1024.0 * 1024.0,
+        )]);
+        let summaries = summarize(&found, Some(&usage), &[pressure]);
+        assert_eq!(summaries.len(), 2);
+        let kills = &summaries[0];
+        assert_eq!(kills.container.as_deref(), Some("app"));
+        assert_eq!(kills.oom_kills, 2);
+        assert_eq!(
+            kills.last_seen,
+            Some("2024-05-01T11:00:00Z".parse().unwrap())
+        );
+        assert_eq!(kills.memory_usage, Some(200.0 * 1024.0 * 1024.0));
+        assert_eq!(kills.nodes, ["node-1"]);
+        assert_eq!(kills.memory_pressure_nodes, ["node-1"]);
+        assert_eq!(summaries[1].evictions, 1);
+        assert_eq!(summaries[1].memory_limit, None);
+    }
+}
@@ -1,152 +1,509 @@
 //! Output formatting for different data types
 
-use crate::cli::OutputFormat;
+use crate::age::{format_age, format_duration};
+use crate::annotations::{AnnotationKeyUsage, AnnotationMatch};
+use crate::cli::{Output, OutputFormat, ReportFormat};
+use crate::clusters::Clustered;
+use crate::compare::NamespaceDiff;
+use crate::compress::Compressor;
+use crate::config::Config;
+use crate::containers::{ContainerInfo, ContainerRole};
+use crate::crd_versions::StorageMigration;
+use crate::describe::ObjectDescription;
+use crate::diff::{Change, ResourceDiff};
 use crate::discovery::{
-    CRDInfo, ConfigMapInfo, CustomResourceInfo, DaemonSetInfo, DeploymentInfo, IngressInfo,
-    PodInfo, SecretInfo, ServiceDescription, ServiceHealth, ServiceInfo, ServiceTopology,
-    StatefulSetInfo,
+    AllResources, CRDInfo, ConfigMapInfo, CrdInstances, CustomResourceInfo, DaemonSetInfo,
+    DeepServiceDescription, DeploymentInfo, IngressInfo, NodeInfo, PersistentVolumeClaimInfo,
+    PersistentVolumeInfo, PodDescription, PodInfo, ReplicaSetInfo, ResourceReference,
+    RoleBindingInfo, RoleInfo, SecretInfo, ServiceAccountInfo, ServiceDescription, ServiceHealth,
+    ServiceInfo, ServiceTopology, StatefulSetInfo, SubjectInfo,
 };
+use crate::endpoints::{ChangeKind, EndpointChange, EndpointChurn};
+use crate::env::{ContainerEnv, EnvVar};
 use crate::error::{ExplorerError, Result};
-use crate::filtering::GroupedResources;
+use crate::events::EventInfo;
+use crate::externals::ExternalDependency;
+use crate::extract::ExtractSummary;
+use crate::filtering::{GroupedResources, ResourceGroup};
+use crate::hpa::HpaInfo;
+use crate::ingress::IngressIssue;
+use crate::jsonpath::{Column, JsonPath};
+use crate::kustomize::KustomizeDrift;
+use crate::labels::LabelValues;
+use crate::lint::{LintFinding, Rule, Severity};
+use crate::mesh::{mesh_status, NamespaceMeshCoverage};
+use crate::move_check::MoveCheck;
+use crate::namespaces::NamespaceSummary;
+use crate::netpol::{Direction, NetworkPolicyInfo, Reachability};
+use crate::network::NetworkIssue;
+use crate::node_images::{CachedImage, NodeImageReport};
+use crate::oomkills::OomKillSummary;
+use crate::owners::OwnershipChain;
+use crate::probe::{ProbeResult, ProbeStatus};
+use crate::quantity::{format_bytes, format_cpu, parse_quantity};
+use crate::rbac::Grant;
+use crate::report::Report;
+use crate::rollout::{RevisionDiff, RolloutRevision};
+use crate::scaling::{ScalingEvent, ScalingSource};
+use crate::schema::InstanceValidation;
+use crate::scorecard::{Check, CheckScore, Scorecard};
+use crate::secrets::{AnomalyKind, SecretSummary};
+use crate::simulate::Simulation;
+use crate::spot::SpotExposure;
+use crate::stability::WorkloadStability;
+use crate::stats::StatsSummary;
+use crate::storage::DanglingVolumeReport;
+use crate::storage_rollup::NamespaceStorage;
+use crate::stuck::StuckObject;
+use crate::template::{self, Template};
+use crate::tenant::TenantReport;
+use crate::terminating::NamespaceDiagnosis;
+use crate::timeline::{Phase, PodTimeline};
+use crate::tree::{OwnerTree, TreeNode};
+use crate::visibility::VisibilityReport;
+use crate::warm::WarmSummary;
+use crate::zones::ZoneReport;
+use chrono::{DateTime, Utc};
 use colored::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::Write;
+use std::sync::{Arc, OnceLock, RwLock};
+use tabled::builder::Builder;
+use tabled::settings::{location::ByColumnName, Concat, Disable};
 use tabled::{Table, Tabled};
 
 /// Print services in the specified format
-pub fn print_services(services: &[ServiceInfo], format: &OutputFormat) -> Result<()> {
-    if services.is_empty() {
+pub fn print_services(services: &[ServiceInfo], output: &Output) -> Result<()> {
+    if services.is_empty() && says_none(output) {
         println!("No services found");
         return Ok(());
     }
 
-    match format {
+    match &output.format {
         OutputFormat::Table => print_services_table(services),
-        OutputFormat::Json => print_json(&services)?,
-        OutputFormat::Yaml => print_yaml(&services)?,
+        OutputFormat::Csv => print_csv(&services_table(services), output)?,
+        _ => print_structured(&services, output)?,
     }
 
     Ok(())
 }
 
 /// Print pods in the specified format
-pub fn print_pods(pods: &[PodInfo], format: &OutputFormat) -> Result<()> {
-    if pods.is_empty() {
+pub fn print_pods(
+    pods: &[PodInfo],
+    output: &Output,
+    show_qos: bool,
+    show_mesh: bool,
+) -> Result<()> {
+    if pods.is_empty() && says_none(output) {
         println!("No pods found");
         return Ok(());
     }
 
-    match format {
-        OutputFormat::Table => print_pods_table(pods),
-        OutputFormat::Json => print_json(&pods)?,
-        OutputFormat::Yaml => print_yaml(&pods)?,
+    match &output.format {
+        OutputFormat::Table => print_pods_table(pods, show_qos, show_mesh),
+        OutputFormat::Csv => print_csv(&pods_table(pods, show_qos, show_mesh), output)?,
+        _ => print_structured(&pods, output)?,
     }
 
     Ok(())
 }
 
 /// Print deployments in the specified format
-pub fn print_deployments(deployments: &[DeploymentInfo], format: &OutputFormat) -> Result<()> {
-    if deployments.is_empty() {
+pub fn print_deployments(deployments: &[DeploymentInfo], output: &Output) -> Result<()> {
+    if deployments.is_empty() && says_none(output) {
         println!("No deployments found");
         return Ok(());
     }
 
-    match format {
+    match &output.format {
         OutputFormat::Table => print_deployments_table(deployments),
-        OutputFormat::Json => print_json(&deployments)?,
-        OutputFormat::Yaml => print_yaml(&deployments)?,
+        OutputFormat::Csv => print_csv(&deployments_table(deployments), output)?,
+        _ => print_structured(&deployments, output)?,
     }
 
     Ok(())
 }
 
 /// Print statefulsets in the specified format
-pub fn print_statefulsets(statefulsets: &[StatefulSetInfo], format: &OutputFormat) -> Result<()> {
-    if statefulsets.is_empty() {
+pub fn print_statefulsets(statefulsets: &[StatefulSetInfo], output: &Output) -> Result<()> {
+    if statefulsets.is_empty() && says_none(output) {
         println!("No statefulsets found");
         return Ok(());
     }
 
-    match format {
+    match &output.format {
         OutputFormat::Table => print_statefulsets_table(statefulsets),
-        OutputFormat::Json => print_json(&statefulsets)?,
-        OutputFormat::Yaml => print_yaml(&statefulsets)?,
+        OutputFormat::Csv => print_csv(&statefulsets_table(statefulsets), output)?,
+        _ => print_structured(&statefulsets, output)?,
     }
 
     Ok(())
 }
 
 /// Print daemonsets in the specified format
-pub fn print_daemonsets(daemonsets: &[DaemonSetInfo], format: &OutputFormat) -> Result<()> {
-    if daemonsets.is_empty() {
+pub fn print_daemonsets(daemonsets: &[DaemonSetInfo], output: &Output) -> Result<()> {
+    if daemonsets.is_empty() && says_none(output) {
         println!("No daemonsets found");
         return Ok(());
     }
 
-    match format {
+    match &output.format {
         OutputFormat::Table => print_daemonsets_table(daemonsets),
-        OutputFormat::Json => print_json(&daemonsets)?,
-        OutputFormat::Yaml => print_yaml(&daemonsets)?,
+        OutputFormat::Csv => print_csv(&daemonsets_table(daemonsets), output)?,
+        _ => print_structured(&daemonsets, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print items merged from several clusters: a CLUSTER column in front of
+/// the usual table, or a `cluster` field on every item
+fn print_clustered<T: serde::Serialize + Clone>(
+    items: &[Clustered<T>],
+    output: &Output,
+    kind: &str,
+    table: impl Fn(&[T]) -> Table,
+) -> Result<()> {
+    if items.is_empty() && says_none(output) {
+        println!("No {} found", kind);
+        return Ok(());
+    }
+
+    let merged = || {
+        #[derive(Tabled)]
+        struct ClusterRow<'a> {
+            #[tabled(rename = "CLUSTER")]
+            cluster: &'a str,
+        }
+        let clusters = items.iter().map(|i| ClusterRow {
+            cluster: &i.cluster,
+        });
+        let rows: Vec<T> = items.iter().map(|i| i.item.clone()).collect();
+        let mut merged = Table::new(clusters);
+        merged.with(Concat::horizontal(table(&rows)));
+        merged
+    };
+    match &output.format {
+        OutputFormat::Table => println!("{}", merged()),
+        OutputFormat::Csv => print_csv(&merged(), output)?,
+        _ => print_structured(&items, output)?,
+    }
+
+    Ok(())
+}
+
+pub fn print_clustered_services(
+    services: &[Clustered<ServiceInfo>],
+    output: &Output,
+) -> Result<()> {
+    print_clustered(services, output, "services", services_table)
+}
+
+pub fn print_clustered_pods(
+    pods: &[Clustered<PodInfo>],
+    output: &Output,
+    show_qos: bool,
+    show_mesh: bool,
+) -> Result<()> {
+    print_clustered(pods, output, "pods", |pods| {
+        pods_table(pods, show_qos, show_mesh)
+    })
+}
+
+pub fn print_clustered_deployments(
+    deployments: &[Clustered<DeploymentInfo>],
+    output: &Output,
+) -> Result<()> {
+    print_clustered(deployments, output, "deployments", deployments_table)
+}
+
+pub fn print_clustered_statefulsets(
+    statefulsets: &[Clustered<StatefulSetInfo>],
+    output: &Output,
+) -> Result<()> {
+    print_clustered(statefulsets, output, "statefulsets", statefulsets_table)
+}
+
+pub fn print_clustered_daemonsets(
+    daemonsets: &[Clustered<DaemonSetInfo>],
+    output: &Output,
+) -> Result<()> {
+    print_clustered(daemonsets, output, "daemonsets", daemonsets_table)
+}
+
+/// Print replicasets in the specified format
+pub fn print_replicasets(replicasets: &[ReplicaSetInfo], output: &Output) -> Result<()> {
+    if replicasets.is_empty() && says_none(output) {
+        println!("No replicasets found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_replicasets_table(replicasets),
+        OutputFormat::Csv => print_csv(&replicasets_table(replicasets), output)?,
+        _ => print_structured(&replicasets, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print the rollout history of a deployment in the specified format
+pub fn print_rollout_history(history: &[RolloutRevision], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => println!("{}", rollout_history_table(history)),
+        OutputFormat::Csv => print_csv(&rollout_history_table(history), output)?,
+        _ => print_structured(&history, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print the pod template changes between two revisions in the specified
+/// format
+pub fn print_revision_diff(diff: &RevisionDiff, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_revision_diff_table(diff),
+        OutputFormat::Csv => print_csv(&revision_diff_table(diff), output)?,
+        _ => print_structured(diff, output)?,
     }
 
     Ok(())
 }
 
+fn print_revision_diff_table(diff: &RevisionDiff) {
+    println!(
+        "deployment/{}: revision {} ({}) -> revision {} ({})",
+        diff.deployment.cyan(),
+        diff.from_revision,
+        diff.from_replicaset,
+        diff.to_revision,
+        diff.to_replicaset
+    );
+    if diff.changes.is_empty() {
+        println!("\nThe pod templates are identical");
+        return;
+    }
+    println!("\n{}", revision_diff_table(diff));
+}
+
+fn revision_diff_table(diff: &RevisionDiff) -> Table {
+    #[derive(Tabled)]
+    struct ChangeRow {
+        #[tabled(rename = "FIELD")]
+        path: String,
+        #[tabled(rename = "FROM")]
+        from: String,
+        #[tabled(rename = "TO")]
+        to: String,
+    }
+
+    let rows: Vec<ChangeRow> = diff
+        .changes
+        .iter()
+        .map(|c| ChangeRow {
+            path: c.path.clone(),
+            from: c
+                .from
+                .as_ref()
+                .map_or_else(|| "<none>".to_string(), |v| v.red().to_string()),
+            to: c
+                .to
+                .as_ref()
+                .map_or_else(|| "<none>".to_string(), |v| v.green().to_string()),
+        })
+        .collect();
+    Table::new(rows)
+}
+
 /// Print configmaps in the specified format
-pub fn print_configmaps(configmaps: &[ConfigMapInfo], format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_configmaps(configmaps: &[ConfigMapInfo], output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_configmaps_table(configmaps),
-        OutputFormat::Json => print_json(&configmaps)?,
-        OutputFormat::Yaml => print_yaml(&configmaps)?,
+        OutputFormat::Csv => print_csv(&configmaps_table(configmaps), output)?,
+        _ => print_structured(&configmaps, output)?,
     }
 
     Ok(())
 }
 
 /// Print secrets in the specified format
-pub fn print_secrets(secrets: &[SecretInfo], format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_secrets(secrets: &[SecretInfo], output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_secrets_table(secrets),
-        OutputFormat::Json => print_json(&secrets)?,
-        OutputFormat::Yaml => print_yaml(&secrets)?,
+        OutputFormat::Csv => print_csv(&secrets_table(secrets), output)?,
+        _ => print_structured(&secrets, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print secret counts by type and the anomalies found
+pub fn print_secret_summary(summary: &SecretSummary, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_secret_summary_table(summary),
+        OutputFormat::Csv => print_csv(&secret_summary_table(summary), output)?,
+        _ => print_structured(summary, output)?,
     }
 
     Ok(())
 }
 
+fn print_secret_summary_table(summary: &SecretSummary) {
+    if summary.total == 0 {
+        println!("No secrets found");
+        return;
+    }
+
+    println!("{}", secret_types_table(summary));
+    println!("\nTotal: {} secrets", summary.total);
+
+    if summary.anomalies.is_empty() {
+        println!("{}", "No anomalies found".green());
+        return;
+    }
+    println!();
+    println!("{}", secret_anomalies_table(summary));
+}
+
+/// Counts by type and the anomalies, a SECTION column telling them apart
+fn secret_summary_table(summary: &SecretSummary) -> Table {
+    sections_table(
+        "SECTION",
+        vec![
+            ("Types".to_string(), secret_types_table(summary)),
+            ("Anomalies".to_string(), secret_anomalies_table(summary)),
+        ],
+    )
+}
+
+fn secret_types_table(summary: &SecretSummary) -> Table {
+    #[derive(Tabled)]
+    struct TypeRow {
+        #[tabled(rename = "TYPE")]
+        secret_type: String,
+        #[tabled(rename = "COUNT")]
+        count: usize,
+        #[tabled(rename = "UNUSED")]
+        unused: usize,
+    }
+
+    let rows: Vec<TypeRow> = summary
+        .types
+        .iter()
+        .map(|t| TypeRow {
+            secret_type: t.secret_type.clone(),
+            count: t.count,
+            unused: t.unused,
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn secret_anomalies_table(summary: &SecretSummary) -> Table {
+    #[derive(Tabled)]
+    struct AnomalyRow {
+        #[tabled(rename = "ANOMALY")]
+        kind: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "DETAIL")]
+        detail: String,
+    }
+
+    let rows: Vec<AnomalyRow> = summary
+        .anomalies
+        .iter()
+        .map(|a| AnomalyRow {
+            kind: match a.kind {
+                AnomalyKind::TokenCount => a.kind.to_string().red().to_string(),
+                _ => a.kind.to_string().yellow().to_string(),
+            },
+            namespace: a.namespace.clone(),
+            name: a.name.clone(),
+            detail: a.detail.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
 /// Print grouped configmaps in the specified format
-pub fn print_grouped_configmaps(grouped: &GroupedResources, format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_grouped_configmaps(grouped: &GroupedResources, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_grouped_configmaps_table(grouped),
-        OutputFormat::Json => print_json(&grouped)?,
-        OutputFormat::Yaml => print_yaml(&grouped)?,
+        OutputFormat::Csv => print_csv(
+            &grouped_table(grouped, |group| configmaps_table(&group.configmaps)),
+            output,
+        )?,
+        _ => print_structured(&grouped, output)?,
     }
 
     Ok(())
 }
 
 /// Print grouped secrets in the specified format
-pub fn print_grouped_secrets(grouped: &GroupedResources, format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_grouped_secrets(grouped: &GroupedResources, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_grouped_secrets_table(grouped),
-        OutputFormat::Json => print_json(&grouped)?,
-        OutputFormat::Yaml => print_yaml(&grouped)?,
+        OutputFormat::Csv => print_csv(
+            &grouped_table(grouped, |group| secrets_table(&group.secrets)),
+            output,
+        )?,
+        _ => print_structured(&grouped, output)?,
     }
 
     Ok(())
 }
 
 /// Print CRDs in the specified format
-pub fn print_crds(crds: &[CRDInfo], format: &OutputFormat, show_versions: bool) -> Result<()> {
-    if crds.is_empty() {
+pub fn print_crds(crds: &[CRDInfo], output: &Output, show_versions: bool) -> Result<()> {
+    if crds.is_empty() && says_none(output) {
         println!("No CRDs found");
         return Ok(());
     }
 
-    match format {
+    match &output.format {
         OutputFormat::Table => print_crds_table(crds, show_versions),
-        OutputFormat::Json => print_json(&crds)?,
-        OutputFormat::Yaml => print_yaml(&crds)?,
+        OutputFormat::Csv => print_csv(&crds_table(crds), output)?,
+        _ => print_structured(&crds, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print nodes in the specified format
+pub fn print_nodes(nodes: &[NodeInfo], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_nodes_table(nodes),
+        OutputFormat::Csv => print_csv(&nodes_table(nodes), output)?,
+        _ => print_structured(&nodes, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print persistent volumes in the specified format
+pub fn print_persistent_volumes(volumes: &[PersistentVolumeInfo], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_persistent_volumes_table(volumes),
+        OutputFormat::Csv => print_csv(&persistent_volumes_table(volumes), output)?,
+        _ => print_structured(&volumes, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print persistent volume claims in the specified format
+pub fn print_persistent_volume_claims(
+    claims: &[PersistentVolumeClaimInfo],
+    output: &Output,
+) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_persistent_volume_claims_table(claims),
+        OutputFormat::Csv => print_csv(&persistent_volume_claims_table(claims), output)?,
+        _ => print_structured(&claims, output)?,
     }
 
     Ok(())
@@ -155,17 +512,17 @@ pub fn print_crds(crds: &[CRDInfo], format: &OutputFormat, show_versions: bool)
 /// Print custom resources in the specified format
 pub fn print_custom_resources(
     custom_resources: &[CustomResourceInfo],
-    format: &OutputFormat,
+    output: &Output,
 ) -> Result<()> {
-    if custom_resources.is_empty() {
+    if custom_resources.is_empty() && says_none(output) {
         println!("No custom resources found");
         return Ok(());
     }
 
-    match format {
+    match &output.format {
         OutputFormat::Table => print_custom_resources_table(custom_resources),
-        OutputFormat::Json => print_json(&custom_resources)?,
-        OutputFormat::Yaml => print_yaml(&custom_resources)?,
+        OutputFormat::Csv => print_csv(&custom_resources_table(custom_resources), output)?,
+        _ => print_structured(&custom_resources, output)?,
     }
 
     Ok(())
@@ -174,69 +531,391 @@ pub fn print_custom_resources(
 /// Print grouped CRDs in the specified format
 pub fn print_grouped_crds(
     grouped: &GroupedResources,
-    format: &OutputFormat,
+    output: &Output,
     show_versions: bool,
 ) -> Result<()> {
-    match format {
+    match &output.format {
         OutputFormat::Table => print_grouped_crds_table(grouped, show_versions),
-        OutputFormat::Json => print_json(&grouped)?,
-        OutputFormat::Yaml => print_yaml(&grouped)?,
+        OutputFormat::Csv => print_csv(
+            &grouped_table(grouped, |group| crds_table(&group.crds)),
+            output,
+        )?,
+        _ => print_structured(&grouped, output)?,
     }
 
     Ok(())
 }
 
 /// Print grouped custom resources in the specified format
-pub fn print_grouped_custom_resources(
-    grouped: &GroupedResources,
-    format: &OutputFormat,
-) -> Result<()> {
-    match format {
+pub fn print_grouped_custom_resources(grouped: &GroupedResources, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_grouped_custom_resources_table(grouped),
-        OutputFormat::Json => print_json(&grouped)?,
-        OutputFormat::Yaml => print_yaml(&grouped)?,
+        OutputFormat::Csv => print_csv(
+            &grouped_table(grouped, |group| {
+                custom_resources_table(&group.custom_resources)
+            }),
+            output,
+        )?,
+        _ => print_structured(&grouped, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print the listing of `kdx all`: a table per kind that has objects, or
+/// one combined document
+pub fn print_all(all: &AllResources, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_all_table(all),
+        OutputFormat::Csv => print_csv(&sections_table("KIND", all_sections(all)), output)?,
+        _ => print_structured(all, output)?,
     }
 
     Ok(())
 }
 
+fn print_all_table(all: &AllResources) {
+    if all.total() == 0 {
+        println!("No resources found");
+        return;
+    }
+
+    // Like `kubectl get all`, kinds without objects are left out
+    let sections = all_sections(all)
+        .into_iter()
+        .filter(|(_, table)| table.count_rows() > 1);
+    for (i, (heading, table)) in sections.enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", format!("{}:", heading).bold());
+        println!("{}", table);
+    }
+}
+
+/// A table of each kind of `kdx all`, every kind included so the columns
+/// do not depend on which kinds have objects
+fn all_sections(all: &AllResources) -> Vec<(String, Table)> {
+    vec![
+        ("Services".to_string(), services_table(&all.services)),
+        (
+            "Deployments".to_string(),
+            deployments_table(&all.deployments),
+        ),
+        (
+            "StatefulSets".to_string(),
+            statefulsets_table(&all.statefulsets),
+        ),
+        ("DaemonSets".to_string(), daemonsets_table(&all.daemonsets)),
+        ("Pods".to_string(), pods_table(&all.pods, false, false)),
+        ("ConfigMaps".to_string(), configmaps_table(&all.configmaps)),
+        ("Secrets".to_string(), secrets_table(&all.secrets)),
+    ]
+}
+
+/// One table of every group, the group's name in a GROUP column
+fn grouped_table(grouped: &GroupedResources, table: impl Fn(&ResourceGroup) -> Table) -> Table {
+    items_table(
+        "GROUP",
+        table(&ResourceGroup::new(String::new(), String::new())),
+        grouped
+            .groups
+            .iter()
+            .map(|(name, group)| (name.clone(), table(group))),
+    )
+}
+
+/// The objects of a group by kind, in the order the table view shows them
+fn group_table(group: &ResourceGroup) -> Table {
+    sections_table(
+        "KIND",
+        vec![
+            ("Services".to_string(), services_table(&group.services)),
+            (
+                "Deployments".to_string(),
+                deployments_table(&group.deployments),
+            ),
+            ("Pods".to_string(), pods_table(&group.pods, false, false)),
+            (
+                "StatefulSets".to_string(),
+                statefulsets_table(&group.statefulsets),
+            ),
+            (
+                "DaemonSets".to_string(),
+                daemonsets_table(&group.daemonsets),
+            ),
+            (
+                "ConfigMaps".to_string(),
+                configmaps_table(&group.configmaps),
+            ),
+            ("Secrets".to_string(), secrets_table(&group.secrets)),
+        ],
+    )
+}
+
 /// Print grouped resources in the specified format
-pub fn print_grouped_resources(grouped: &GroupedResources, format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_grouped_resources(grouped: &GroupedResources, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_grouped_resources_table(grouped),
-        OutputFormat::Json => print_json(&grouped)?,
-        OutputFormat::Yaml => print_yaml(&grouped)?,
+        OutputFormat::Csv => print_csv(&grouped_table(grouped, group_table), output)?,
+        _ => print_structured(&grouped, output)?,
     }
 
     Ok(())
 }
 
 /// Print service description in the specified format
-pub fn print_service_description(
-    description: &ServiceDescription,
-    format: &OutputFormat,
-) -> Result<()> {
-    match format {
+pub fn print_service_description(description: &ServiceDescription, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_service_description_table(description),
-        OutputFormat::Json => print_json(&description)?,
-        OutputFormat::Yaml => print_yaml(&description)?,
+        OutputFormat::Csv => print_csv(
+            &sections_table("SECTION", service_description_sections(description)),
+            output,
+        )?,
+        _ => print_structured(&description, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print a service with all its dependent objects in the specified format
+pub fn print_deep_service_description(
+    description: &DeepServiceDescription,
+    output: &Output,
+) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_deep_service_description_table(description),
+        OutputFormat::Csv => print_csv(&deep_service_description_table(description), output)?,
+        _ => print_structured(&description, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_deep_service_description_table(deep: &DeepServiceDescription) {
+    print_service_description_table(&deep.description);
+
+    // Every section is printed, so an empty one reads as "none" rather than
+    // as "not checked"
+    let none = |heading: &str| println!("\n{}\n  None", heading);
+
+    if deep.ingresses.is_empty() {
+        none("Ingress Routes:");
+    } else {
+        print_ingress_table(&deep.ingresses);
+    }
+
+    if deep.configmaps.is_empty() && deep.secrets.is_empty() {
+        none("Configuration:");
+    } else {
+        print_configuration_table(&deep.configmaps, &deep.secrets);
+    }
+
+    if deep.endpoints.is_empty() {
+        none("Endpoints:");
+    } else {
+        let ready = deep.endpoints.iter().filter(|e| e.ready).count();
+        println!(
+            "\nEndpoints: {} ready, {} not ready",
+            ready,
+            deep.endpoints.len() - ready
+        );
+        for endpoint in &deep.endpoints {
+            let state = if endpoint.terminating {
+                "terminating".yellow()
+            } else if endpoint.ready {
+                "ready".green()
+            } else {
+                "not ready".red()
+            };
+            let target = endpoint
+                .target
+                .as_ref()
+                .map_or_else(String::new, |t| format!(" -> {}", t));
+            let node = endpoint
+                .node
+                .as_ref()
+                .map_or_else(String::new, |n| format!(" on {}", n));
+            println!("  {}{}{} ({})", endpoint.address, target, node, state);
+        }
+    }
+
+    if deep.monitors.is_empty() {
+        none("Monitors:");
+    } else {
+        println!("\nMonitors:");
+        for monitor in &deep.monitors {
+            println!(
+                "  servicemonitor/{} (namespace: {}, ports: {})",
+                monitor.name.cyan(),
+                monitor.namespace,
+                monitor.ports.join(", ")
+            );
+        }
+    }
+
+    if deep.webhooks.is_empty() {
+        none("Webhooks:");
+    } else {
+        println!("\nWebhooks:");
+        for webhook in &deep.webhooks {
+            let policy = if webhook.failure_policy == "Fail" {
+                webhook.failure_policy.yellow()
+            } else {
+                webhook.failure_policy.normal()
+            };
+            println!(
+                "  {} {} (configuration: {}, path: {}, failure policy: {})",
+                webhook.kind,
+                webhook.webhook.cyan(),
+                webhook.configuration,
+                webhook.path.as_deref().unwrap_or("/"),
+                policy
+            );
+        }
+    }
+
+    print_health_table(&deep.health);
+
+    if !deep.unavailable.is_empty() {
+        println!("\n{}", "Not available:".yellow());
+        for (section, error) in &deep.unavailable {
+            println!("  {}: {}", section, error);
+        }
+    }
+}
+
+fn deep_service_description_table(deep: &DeepServiceDescription) -> Table {
+    #[derive(Tabled)]
+    struct EndpointRow {
+        #[tabled(rename = "ADDRESS")]
+        address: String,
+        #[tabled(rename = "TARGET")]
+        target: String,
+        #[tabled(rename = "NODE")]
+        node: String,
+        #[tabled(rename = "STATE")]
+        state: &'static str,
+    }
+
+    #[derive(Tabled)]
+    struct MonitorRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "PORTS")]
+        ports: String,
+    }
+
+    #[derive(Tabled)]
+    struct WebhookRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "CONFIGURATION")]
+        configuration: String,
+        #[tabled(rename = "PATH")]
+        path: String,
+        #[tabled(rename = "FAILURE-POLICY")]
+        failure_policy: String,
+    }
+
+    let none = || "-".to_string();
+    let endpoints = deep.endpoints.iter().map(|e| EndpointRow {
+        address: e.address.clone(),
+        target: e.target.clone().unwrap_or_else(none),
+        node: e.node.clone().unwrap_or_else(none),
+        state: if e.terminating {
+            "terminating"
+        } else if e.ready {
+            "ready"
+        } else {
+            "not ready"
+        },
+    });
+    let monitors = deep.monitors.iter().map(|m| MonitorRow {
+        name: m.name.clone(),
+        namespace: m.namespace.clone(),
+        ports: m.ports.join(","),
+    });
+    let webhooks = deep.webhooks.iter().map(|w| WebhookRow {
+        kind: w.kind.to_string(),
+        name: w.webhook.clone(),
+        configuration: w.configuration.clone(),
+        path: w.path.clone().unwrap_or_else(|| "/".to_string()),
+        failure_policy: w.failure_policy.clone(),
+    });
+
+    let mut sections = service_description_sections(&deep.description);
+    sections.extend([
+        ("Ingress Routes".to_string(), ingress_table(&deep.ingresses)),
+        (
+            "Configuration".to_string(),
+            configuration_table(&deep.configmaps, &deep.secrets),
+        ),
+        ("Endpoints".to_string(), Table::new(endpoints)),
+        ("Monitors".to_string(), Table::new(monitors)),
+        ("Webhooks".to_string(), Table::new(webhooks)),
+        ("Health".to_string(), health_table(&deep.health)),
+        (
+            "Not available".to_string(),
+            fields_table(deep.unavailable.clone().into_iter().collect()),
+        ),
+    ]);
+    sections_table("SECTION", sections)
+}
+
+/// Print pod description in the specified format
+pub fn print_pod_description(description: &PodDescription, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_pod_description_table(description),
+        OutputFormat::Csv => print_csv(&pod_description_table(description), output)?,
+        _ => print_structured(&description, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print statefulset description in the specified format
+pub fn print_statefulset_description(statefulset: &StatefulSetInfo, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_statefulset_description_table(statefulset),
+        OutputFormat::Csv => print_csv(&statefulset_description_table(statefulset), output)?,
+        _ => print_structured(&statefulset, output)?,
+    }
+
+    Ok(())
+}
+
+/// Print a pod timeline in the specified format
+pub fn print_pod_timeline(timeline: &PodTimeline, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_pod_timeline_table(timeline),
+        OutputFormat::Csv => print_csv(&pod_timeline_table(timeline), output)?,
+        _ => print_structured(&timeline, output)?,
     }
 
     Ok(())
 }
 
 /// Print service topology in the specified format
-pub fn print_service_topology(topology: &ServiceTopology, format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_service_topology(topology: &ServiceTopology, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_service_topology_table(topology),
-        OutputFormat::Json => print_json(&topology)?,
-        OutputFormat::Yaml => print_yaml(&topology)?,
+        OutputFormat::Csv => print_csv(&service_topology_table(topology), output)?,
+        _ => print_structured(&topology, output)?,
     }
 
     Ok(())
 }
 
 fn print_services_table(services: &[ServiceInfo]) {
+    println!("{}", services_table(services));
+}
+
+fn services_table(services: &[ServiceInfo]) -> Table {
     #[derive(Tabled)]
     struct ServiceRow {
         #[tabled(rename = "NAME")]
@@ -277,11 +956,14 @@ fn print_services_table(services: &[ServiceInfo]) {
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
+}
+
+fn print_pods_table(pods: &[PodInfo], show_qos: bool, show_mesh: bool) {
+    println!("{}", pods_table(pods, show_qos, show_mesh));
 }
 
-fn print_pods_table(pods: &[PodInfo]) {
+fn pods_table(pods: &[PodInfo], show_qos: bool, show_mesh: bool) -> Table {
     #[derive(Tabled)]
     struct PodRow {
         #[tabled(rename = "NAME")]
@@ -300,6 +982,12 @@ fn print_pods_table(pods: &[PodInfo]) {
         ip: String,
         #[tabled(rename = "NODE")]
         node: String,
+        #[tabled(rename = "QOS")]
+        qos: String,
+        #[tabled(rename = "PRIORITY")]
+        priority: String,
+        #[tabled(rename = "MESH")]
+        mesh: String,
     }
     let rows: Vec<PodRow> = pods
         .iter()
@@ -315,15 +1003,35 @@ fn print_pods_table(pods: &[PodInfo]) {
                 age: pod.age.clone(),
                 ip: pod.pod_ip.clone().unwrap_or_else(|| "None".to_string()),
                 node: pod.node_name.clone().unwrap_or_else(|| "None".to_string()),
+                qos: pod.qos_class.clone(),
+                priority: match (&pod.priority_class, pod.priority) {
+                    (Some(class), Some(value)) => format!("{} ({})", class, value),
+                    (Some(class), None) => class.clone(),
+                    (None, Some(value)) => value.to_string(),
+                    (None, None) => "None".to_string(),
+                },
+                mesh: mesh_status(pod),
             }
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    let mut table = Table::new(rows);
+    if !show_qos {
+        table
+            .with(Disable::column(ByColumnName::new("QOS")))
+            .with(Disable::column(ByColumnName::new("PRIORITY")));
+    }
+    if !show_mesh {
+        table.with(Disable::column(ByColumnName::new("MESH")));
+    }
+    table
 }
 
 fn print_deployments_table(deployments: &[DeploymentInfo]) {
+    println!("{}", deployments_table(deployments));
+}
+
+fn deployments_table(deployments: &[DeploymentInfo]) -> Table {
     #[derive(Tabled)]
     struct DeploymentRow {
         #[tabled(rename = "NAME")]
@@ -355,11 +1063,14 @@ fn print_deployments_table(deployments: &[DeploymentInfo]) {
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
 }
 
 fn print_statefulsets_table(statefulsets: &[StatefulSetInfo]) {
+    println!("{}", statefulsets_table(statefulsets));
+}
+
+fn statefulsets_table(statefulsets: &[StatefulSetInfo]) -> Table {
     #[derive(Tabled)]
     struct StatefulSetRow {
         #[tabled(rename = "NAME")]
@@ -385,11 +1096,14 @@ fn print_statefulsets_table(statefulsets: &[StatefulSetInfo]) {
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
 }
 
 fn print_daemonsets_table(daemonsets: &[DaemonSetInfo]) {
+    println!("{}", daemonsets_table(daemonsets));
+}
+
+fn daemonsets_table(daemonsets: &[DaemonSetInfo]) -> Table {
     #[derive(Tabled)]
     struct DaemonSetRow {
         #[tabled(rename = "NAME")]
@@ -421,27 +1135,115 @@ fn print_daemonsets_table(daemonsets: &[DaemonSetInfo]) {
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
 }
 
-fn print_grouped_resources_table(grouped: &GroupedResources) {
-    for (group_name, group) in &grouped.groups {
-        println!("\n=== Group: {} ({}) ===", group_name, group.group_type);
-
-        if !group.services.is_empty() {
-            println!("\nServices:");
-            print_services_table(&group.services);
-        }
+fn print_replicasets_table(replicasets: &[ReplicaSetInfo]) {
+    println!("{}", replicasets_table(replicasets));
+}
 
-        if !group.deployments.is_empty() {
-            println!("\nDeployments:");
+fn replicasets_table(replicasets: &[ReplicaSetInfo]) -> Table {
+    #[derive(Tabled)]
+    struct ReplicaSetRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "DESIRED")]
+        desired: String,
+        #[tabled(rename = "CURRENT")]
+        current: String,
+        #[tabled(rename = "READY")]
+        ready: String,
+        #[tabled(rename = "DEPLOYMENT")]
+        deployment: String,
+        #[tabled(rename = "REVISION")]
+        revision: String,
+        #[tabled(rename = "POD-TEMPLATE-HASH")]
+        hash: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let none = || "-".to_string();
+    let rows: Vec<ReplicaSetRow> = replicasets
+        .iter()
+        .map(|r| ReplicaSetRow {
+            name: r.name.clone(),
+            namespace: r.namespace.clone(),
+            desired: r.desired.to_string(),
+            current: r.current.to_string(),
+            ready: r.ready.to_string(),
+            deployment: r.deployment.clone().unwrap_or_else(none),
+            revision: r.revision.map_or_else(none, |r| r.to_string()),
+            hash: r.pod_template_hash.clone().unwrap_or_else(none),
+            age: r.age.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+fn rollout_history_table(history: &[RolloutRevision]) -> Table {
+    #[derive(Tabled)]
+    struct RevisionRow {
+        #[tabled(rename = "REVISION")]
+        revision: String,
+        #[tabled(rename = "REPLICASET")]
+        replicaset: String,
+        #[tabled(rename = "IMAGES")]
+        images: String,
+        #[tabled(rename = "READY")]
+        ready: String,
+        #[tabled(rename = "CHANGE-CAUSE")]
+        change_cause: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<RevisionRow> = history
+        .iter()
+        .map(|r| {
+            let mut revision = r.revision.to_string();
+            if !r.previous_revisions.is_empty() {
+                let previous: Vec<String> =
+                    r.previous_revisions.iter().map(|p| p.to_string()).collect();
+                revision = format!("{} (was {})", revision, previous.join(", "));
+            }
+            if r.current {
+                revision = format!("{} (current)", revision).green().to_string();
+            }
+            RevisionRow {
+                revision,
+                replicaset: r.replicaset.clone(),
+                images: r.images.join(", "),
+                ready: format!("{}/{}", r.ready, r.desired),
+                change_cause: r.change_cause.clone().unwrap_or_else(|| "-".to_string()),
+                age: r.age.clone(),
+            }
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+fn print_grouped_resources_table(grouped: &GroupedResources) {
+    for (group_name, group) in &grouped.groups {
+        println!("\n=== Group: {} ({}) ===", group_name, group.group_type);
+
+        if !group.services.is_empty() {
+            println!("\nServices:");
+            print_services_table(&group.services);
+        }
+
+        if !group.deployments.is_empty() {
+            println!("\nDeployments:");
             print_deployments_table(&group.deployments);
         }
 
         if !group.pods.is_empty() {
             println!("\nPods:");
-            print_pods_table(&group.pods);
+            print_pods_table(&group.pods, false, false);
         }
 
         if !group.statefulsets.is_empty() {
@@ -454,6 +1256,16 @@ fn print_grouped_resources_table(grouped: &GroupedResources) {
             print_daemonsets_table(&group.daemonsets);
         }
 
+        if !group.configmaps.is_empty() {
+            println!("\nConfigMaps:");
+            print_configmaps_table(&group.configmaps);
+        }
+
+        if !group.secrets.is_empty() {
+            println!("\nSecrets:");
+            print_secrets_table(&group.secrets);
+        }
+
         println!("\nTotal resources in group: {}", group.total_resources());
     }
 }
@@ -480,71 +1292,5035 @@ fn print_service_description_table(description: &ServiceDescription) {
         }
     }
 
-    if let Some(selector) = &service.selector {
-        println!("\nSelector:");
-        for (key, value) in selector {
-            println!("  {} = {}", key, value);
+    if let Some(selector) = &service.selector {
+        println!("\nSelector:");
+        for (key, value) in selector {
+            println!("  {} = {}", key, value);
+        }
+    }
+
+    if !description.related_pods.is_empty() {
+        println!("\nRelated Pods:");
+        print_pods_table(&description.related_pods, false, false);
+    }
+
+    print_autoscalers(&description.autoscalers);
+    print_pod_warnings(&description.warnings);
+}
+
+/// The service's own fields, then its pods, autoscalers and warnings
+fn service_description_sections(description: &ServiceDescription) -> Vec<(String, Table)> {
+    vec![
+        (
+            "Service".to_string(),
+            fields_table(service_fields(&description.service)),
+        ),
+        (
+            "Related Pods".to_string(),
+            pods_table(&description.related_pods, false, false),
+        ),
+        (
+            "Autoscalers".to_string(),
+            hpas_table(&description.autoscalers),
+        ),
+        ("Warnings".to_string(), events_table(&description.warnings)),
+    ]
+}
+
+fn service_fields(service: &ServiceInfo) -> Vec<(String, String)> {
+    let ports: Vec<String> = service
+        .ports
+        .iter()
+        .map(|port| {
+            format!(
+                "{} {}:{} -> {}",
+                port.name.as_deref().unwrap_or("unnamed"),
+                port.port,
+                port.protocol,
+                port.target_port
+            )
+        })
+        .collect();
+    let selector: Vec<String> = service
+        .selector
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    vec![
+        ("Name".to_string(), service.name.clone()),
+        ("Namespace".to_string(), service.namespace.clone()),
+        ("Type".to_string(), service.service_type.clone()),
+        (
+            "Cluster IP".to_string(),
+            service
+                .cluster_ip
+                .clone()
+                .unwrap_or_else(|| "None".to_string()),
+        ),
+        ("Ports".to_string(), ports.join(", ")),
+        ("Selector".to_string(), selector.join(", ")),
+    ]
+}
+
+/// The owners of an object after the object itself, e.g.
+/// "ReplicaSet/web-5d4f -> Deployment/web"
+fn owner_chain(owners: &Option<OwnershipChain>) -> String {
+    match owners {
+        // The first link is the object itself
+        Some(owners) if owners.chain.len() > 1 => owners
+            .chain
+            .iter()
+            .skip(1)
+            .map(|link| format!("{}/{}", link.kind, link.name))
+            .collect::<Vec<_>>()
+            .join(" -> "),
+        _ => "None".to_string(),
+    }
+}
+
+/// Autoscalers of a service's workloads, one line each
+fn print_autoscalers(autoscalers: &[HpaInfo]) {
+    if autoscalers.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "Autoscalers:".bold());
+    for hpa in autoscalers {
+        let replicas = format!(
+            "{}/{}-{} replicas",
+            hpa.current_replicas
+                .map_or_else(|| "?".to_string(), |r| r.to_string()),
+            hpa.min_replicas,
+            hpa.max_replicas
+        );
+        let replicas = if hpa.at_max() {
+            replicas.yellow().to_string()
+        } else {
+            replicas
+        };
+        let metrics: Vec<String> = hpa.metrics.iter().map(|m| m.to_string()).collect();
+        println!(
+            "  hpa/{} -> {}  {}  {}",
+            hpa.name,
+            hpa.target().to_lowercase(),
+            replicas,
+            metrics.join(", ")
+        );
+    }
+}
+
+/// Warning events of a service's pods, one line each
+fn print_pod_warnings(warnings: &[EventInfo]) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "Warnings:".bold());
+    for event in warnings {
+        let count = if event.count > 1 {
+            format!(" (x{})", event.count)
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} ago  {} {}{}: {}",
+            event.age,
+            event.reason.yellow(),
+            event.object(),
+            count,
+            event.message
+        );
+    }
+}
+
+/// Events about one object, one line each, warnings highlighted
+fn print_object_events(events: &[EventInfo]) {
+    if events.is_empty() {
+        println!("\nEvents: None");
+        return;
+    }
+
+    println!("\n{}", "Events:".bold());
+    for event in events {
+        let count = if event.count > 1 {
+            format!(" (x{})", event.count)
+        } else {
+            String::new()
+        };
+        let reason = if event.is_warning() {
+            event.reason.yellow()
+        } else {
+            event.reason.normal()
+        };
+        println!(
+            "  {} ago  {}{}: {}",
+            event.age, reason, count, event.message
+        );
+    }
+}
+
+/// Print any object's description in the specified format
+pub fn print_object_description(description: &ObjectDescription, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_object_description_table(description),
+        OutputFormat::Csv => print_csv(&object_description_table(description), output)?,
+        _ => print_structured(&description, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_object_description_table(description: &ObjectDescription) {
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "None".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+    let pairs = |map: &std::collections::BTreeMap<String, String>| {
+        map.iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+    };
+
+    println!(
+        "{}",
+        format!("{}: {}", description.kind, description.name).bold()
+    );
+    if let Some(namespace) = &description.namespace {
+        println!("Namespace: {}", namespace);
+    }
+    println!("API Version: {}", description.api_version);
+    println!("Age: {}", description.age);
+    println!("Labels: {}", list(&pairs(&description.labels)));
+    if !description.annotations.is_empty() {
+        println!(
+            "Annotations: {}",
+            pairs(&description.annotations).join(", ")
+        );
+    }
+    for field in &description.fields {
+        println!("{}: {}", field.name, field.value);
+    }
+
+    if !description.conditions.is_empty() {
+        println!("\n{}", "Conditions:".bold());
+        for condition in &description.conditions {
+            let line = if condition.contains("=True") {
+                condition.normal()
+            } else {
+                condition.yellow()
+            };
+            println!("  {}", line);
+        }
+    }
+
+    println!();
+    println!("Owners: {}", owner_chain(&description.owners));
+    if let Some(manager) = description
+        .owners
+        .as_ref()
+        .and_then(|o| o.managed_by.as_ref())
+    {
+        println!("Managed by: {} {}", manager.tool, manager.name);
+    }
+    println!("Manages: {}", list(&description.manages));
+    println!("Used By: {}", list(&description.used_by));
+
+    print_object_events(&description.events);
+}
+
+/// The description's lines as fields, then its events
+fn object_description_table(description: &ObjectDescription) -> Table {
+    let pairs = |map: &BTreeMap<String, String>| {
+        map.iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut fields = vec![
+        ("Kind".to_string(), description.kind.clone()),
+        ("Name".to_string(), description.name.clone()),
+        (
+            "Namespace".to_string(),
+            description.namespace.clone().unwrap_or_default(),
+        ),
+        ("API Version".to_string(), description.api_version.clone()),
+        ("Age".to_string(), description.age.clone()),
+        ("Labels".to_string(), pairs(&description.labels)),
+        ("Annotations".to_string(), pairs(&description.annotations)),
+    ];
+    fields.extend(
+        description
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), field.value.clone())),
+    );
+    fields.extend([
+        ("Conditions".to_string(), description.conditions.join(", ")),
+        ("Owners".to_string(), owner_chain(&description.owners)),
+        (
+            "Managed by".to_string(),
+            description
+                .owners
+                .as_ref()
+                .and_then(|o| o.managed_by.as_ref())
+                .map_or_else(String::new, |m| format!("{} {}", m.tool, m.name)),
+        ),
+        ("Manages".to_string(), description.manages.join(", ")),
+        ("Used By".to_string(), description.used_by.join(", ")),
+    ]);
+
+    sections_table(
+        "SECTION",
+        vec![
+            (description.kind.clone(), fields_table(fields)),
+            ("Events".to_string(), events_table(&description.events)),
+        ],
+    )
+}
+
+fn print_pod_description_table(description: &PodDescription) {
+    let pod = &description.pod;
+    let none = || "None".to_string();
+
+    println!("{}", format!("Pod: {}", pod.name).bold());
+    println!("Namespace: {}", pod.namespace);
+    let phase = match pod.phase.as_str() {
+        "Running" | "Succeeded" => pod.phase.green(),
+        "Pending" => pod.phase.yellow(),
+        "Failed" => pod.phase.red(),
+        _ => pod.phase.normal(),
+    };
+    println!("Status: {}", phase);
+    println!("Age: {}", pod.age);
+    println!("IP: {}", pod.pod_ip.clone().unwrap_or_else(none));
+    println!("QoS Class: {}", pod.qos_class);
+    println!(
+        "Service Account: {}",
+        description.service_account.clone().unwrap_or_else(none)
+    );
+
+    println!("\n{}", "Containers:".bold());
+    for container in &description.containers {
+        let init = if container.init { " (init)" } else { "" };
+        println!("  {}{}", container.name.cyan(), init);
+        println!("    Image:     {}", container.image);
+        let state = if container.state == "Running" {
+            container.state.green()
+        } else if container.state.starts_with("Waiting") {
+            container.state.yellow()
+        } else if container.state.starts_with("Terminated (Completed") {
+            container.state.normal()
+        } else {
+            container.state.red()
+        };
+        println!("    State:     {}", state);
+        println!("    Ready:     {}", container.ready);
+        println!("    Restarts:  {}", container.restart_count);
+        if let Some(last) = &container.last_termination {
+            println!("    Last exit: {}", last);
+        }
+        let quantities = |map: &std::collections::BTreeMap<String, String>| {
+            if map.is_empty() {
+                none()
+            } else {
+                map.iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        };
+        println!("    Requests:  {}", quantities(&container.requests));
+        println!("    Limits:    {}", quantities(&container.limits));
+        if !container.mounts.is_empty() {
+            println!("    Mounts:");
+            for mount in &container.mounts {
+                println!("      {}", mount);
+            }
+        }
+        if !container.probes.is_empty() {
+            println!("    Probes:");
+            for probe in &container.probes {
+                println!("      {}", probe);
+            }
+        }
+    }
+
+    println!(
+        "\nServices: {}",
+        if description.services.is_empty() {
+            none()
+        } else {
+            description.services.join(", ")
+        }
+    );
+
+    println!("Owners: {}", owner_chain(&description.owners));
+
+    match &description.node {
+        Some(node) => {
+            println!("\n{}", "Node:".bold());
+            println!("  Name: {}", node.name);
+            println!("  Zone: {}", node.zone.clone().unwrap_or_else(none));
+            println!("  Spot: {}", node.spot);
+            if !node.taints.is_empty() {
+                println!("  Taints: {}", node.taints.join(", "));
+            }
+        }
+        None => println!(
+            "\nNode: {}",
+            pod.node_name
+                .clone()
+                .unwrap_or_else(|| "Not scheduled".to_string())
+        ),
+    }
+
+    print_object_events(&description.events);
+}
+
+/// The pod's fields, a row per container, then the pod's events
+fn pod_description_table(description: &PodDescription) -> Table {
+    #[derive(Tabled)]
+    struct ContainerRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "INIT")]
+        init: bool,
+        #[tabled(rename = "IMAGE")]
+        image: String,
+        #[tabled(rename = "STATE")]
+        state: String,
+        #[tabled(rename = "READY")]
+        ready: bool,
+        #[tabled(rename = "RESTARTS")]
+        restarts: i32,
+        #[tabled(rename = "LAST-EXIT")]
+        last_exit: String,
+        #[tabled(rename = "REQUESTS")]
+        requests: String,
+        #[tabled(rename = "LIMITS")]
+        limits: String,
+        #[tabled(rename = "MOUNTS")]
+        mounts: String,
+        #[tabled(rename = "PROBES")]
+        probes: String,
+    }
+
+    let pod = &description.pod;
+    let quantities = |map: &BTreeMap<String, String>| {
+        map.iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let containers = description.containers.iter().map(|c| ContainerRow {
+        name: c.name.clone(),
+        init: c.init,
+        image: c.image.clone(),
+        state: c.state.clone(),
+        ready: c.ready,
+        restarts: c.restart_count,
+        last_exit: c.last_termination.clone().unwrap_or_default(),
+        requests: quantities(&c.requests),
+        limits: quantities(&c.limits),
+        mounts: c.mounts.join("\n"),
+        probes: c.probes.join("\n"),
+    });
+
+    let mut fields = vec![
+        ("Name".to_string(), pod.name.clone()),
+        ("Namespace".to_string(), pod.namespace.clone()),
+        ("Status".to_string(), pod.phase.clone()),
+        ("Age".to_string(), pod.age.clone()),
+        ("IP".to_string(), pod.pod_ip.clone().unwrap_or_default()),
+        ("QoS Class".to_string(), pod.qos_class.clone()),
+        (
+            "Service Account".to_string(),
+            description.service_account.clone().unwrap_or_default(),
+        ),
+        ("Services".to_string(), description.services.join(", ")),
+        ("Owners".to_string(), owner_chain(&description.owners)),
+        (
+            "Node".to_string(),
+            pod.node_name.clone().unwrap_or_default(),
+        ),
+    ];
+    if let Some(node) = &description.node {
+        fields.extend([
+            ("Zone".to_string(), node.zone.clone().unwrap_or_default()),
+            ("Spot".to_string(), node.spot.to_string()),
+            ("Taints".to_string(), node.taints.join(", ")),
+        ]);
+    }
+
+    sections_table(
+        "SECTION",
+        vec![
+            ("Pod".to_string(), fields_table(fields)),
+            ("Containers".to_string(), Table::new(containers)),
+            ("Events".to_string(), events_table(&description.events)),
+        ],
+    )
+}
+
+fn print_statefulset_description_table(statefulset: &StatefulSetInfo) {
+    let none = || "None".to_string();
+
+    println!("{}", format!("StatefulSet: {}", statefulset.name).bold());
+    println!("Namespace: {}", statefulset.namespace);
+    println!(
+        "Replicas: {}/{} ready",
+        statefulset.ready_replicas, statefulset.replicas
+    );
+    println!(
+        "Update Revision: {}",
+        statefulset.update_revision.clone().unwrap_or_else(none)
+    );
+    println!(
+        "Volume Claim Templates: {}",
+        if statefulset.volume_claim_templates.is_empty() {
+            none()
+        } else {
+            statefulset.volume_claim_templates.join(", ")
+        }
+    );
+
+    println!("\n{}", "Ordinals:".bold());
+    println!("{}", ordinals_table(statefulset));
+
+    let gaps = statefulset.gaps();
+    if gaps.is_empty() {
+        println!("\n{} {}", "Gaps:".bold(), "None".green());
+    } else {
+        println!("\n{}", "Gaps:".bold());
+        for gap in gaps {
+            println!("  {}", gap.red());
+        }
+    }
+}
+
+/// The statefulset's fields, then a row per ordinal
+fn statefulset_description_table(statefulset: &StatefulSetInfo) -> Table {
+    let fields = vec![
+        ("Name".to_string(), statefulset.name.clone()),
+        ("Namespace".to_string(), statefulset.namespace.clone()),
+        (
+            "Replicas".to_string(),
+            format!(
+                "{}/{} ready",
+                statefulset.ready_replicas, statefulset.replicas
+            ),
+        ),
+        (
+            "Update Revision".to_string(),
+            statefulset.update_revision.clone().unwrap_or_default(),
+        ),
+        (
+            "Volume Claim Templates".to_string(),
+            statefulset.volume_claim_templates.join(", "),
+        ),
+        ("Gaps".to_string(), statefulset.gaps().join(", ")),
+    ];
+
+    sections_table(
+        "SECTION",
+        vec![
+            ("StatefulSet".to_string(), fields_table(fields)),
+            ("Ordinals".to_string(), ordinals_table(statefulset)),
+        ],
+    )
+}
+
+fn ordinals_table(statefulset: &StatefulSetInfo) -> Table {
+    let none = || "None".to_string();
+
+    #[derive(Tabled)]
+    struct OrdinalRow {
+        #[tabled(rename = "ORDINAL")]
+        ordinal: i32,
+        #[tabled(rename = "POD")]
+        pod: String,
+        #[tabled(rename = "STATUS")]
+        status: String,
+        #[tabled(rename = "READY")]
+        ready: bool,
+        #[tabled(rename = "REVISION")]
+        revision: String,
+        #[tabled(rename = "CLAIMS")]
+        claims: String,
+    }
+
+    let rows: Vec<OrdinalRow> = statefulset
+        .ordinals
+        .iter()
+        .map(|o| OrdinalRow {
+            ordinal: o.ordinal,
+            pod: o.pod.clone(),
+            status: match o.phase.as_deref() {
+                Some("Running") => "Running".to_string(),
+                Some(phase) => phase.yellow().to_string(),
+                None => "Missing".red().to_string(),
+            },
+            ready: o.ready,
+            revision: o.revision.clone().unwrap_or_else(none),
+            claims: if o.claims.is_empty() {
+                none()
+            } else {
+                o.claims
+                    .iter()
+                    .map(|c| match (&c.phase, &c.volume) {
+                        (Some(phase), Some(volume)) => {
+                            format!("{} ({}, {})", c.name, phase, volume)
+                        }
+                        (Some(phase), None) => format!("{} ({})", c.name, phase),
+                        (None, _) => format!("{} (Missing)", c.name),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn print_pod_timeline_table(timeline: &PodTimeline) {
+    println!(
+        "{}",
+        format!("Timeline for pod {}/{}", timeline.namespace, timeline.pod).bold()
+    );
+    if timeline.entries.is_empty() {
+        println!("No events found");
+        return;
+    }
+    println!("{}", timeline_entries_table(timeline));
+
+    if !timeline.durations.is_empty() {
+        println!("\n{}", "Durations:".bold());
+        for duration in &timeline.durations {
+            println!(
+                "  {:<16} {}",
+                format!("{}:", duration.phase),
+                format_duration(duration.seconds)
+            );
+        }
+    }
+}
+
+/// The timeline's entries, then the time spent in each phase
+fn pod_timeline_table(timeline: &PodTimeline) -> Table {
+    #[derive(Tabled)]
+    struct DurationRow {
+        #[tabled(rename = "PHASE")]
+        phase: String,
+        #[tabled(rename = "DURATION")]
+        duration: String,
+    }
+
+    let durations = timeline.durations.iter().map(|d| DurationRow {
+        phase: d.phase.to_string(),
+        duration: format_duration(d.seconds),
+    });
+    sections_table(
+        "SECTION",
+        vec![
+            ("Entries".to_string(), timeline_entries_table(timeline)),
+            ("Durations".to_string(), Table::new(durations)),
+        ],
+    )
+}
+
+fn timeline_entries_table(timeline: &PodTimeline) -> Table {
+    #[derive(Tabled)]
+    struct EntryRow {
+        #[tabled(rename = "TIME")]
+        time: String,
+        #[tabled(rename = "DELTA")]
+        delta: String,
+        #[tabled(rename = "PHASE")]
+        phase: String,
+        #[tabled(rename = "REASON")]
+        reason: String,
+        #[tabled(rename = "CONTAINER")]
+        container: String,
+        #[tabled(rename = "MESSAGE")]
+        message: String,
+    }
+
+    let rows: Vec<EntryRow> = timeline
+        .entries
+        .iter()
+        .map(|entry| EntryRow {
+            time: entry.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            delta: entry
+                .since_previous
+                .map_or_else(String::new, |s| format!("+{}", format_duration(s))),
+            phase: match entry.phase {
+                Phase::Restart => entry.phase.to_string().red().to_string(),
+                Phase::Warning => entry.phase.to_string().yellow().to_string(),
+                _ => entry.phase.to_string(),
+            },
+            reason: entry.reason.clone(),
+            container: entry
+                .container
+                .clone()
+                .unwrap_or_else(|| "None".to_string()),
+            message: entry.message.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn print_service_topology_table(topology: &ServiceTopology) {
+    let service = &topology.service;
+
+    println!("{}", format!("Service Topology: {}", service.name).bold());
+    println!("├── Namespace: {}", service.namespace);
+    println!("├── Type: {}", service.service_type);
+
+    if let Some(cluster_ip) = &service.cluster_ip {
+        println!("├── Cluster IP: {}", cluster_ip);
+    }
+
+    if !topology.backend_pods.is_empty() {
+        println!("└── Backend Pods:");
+        for (i, pod) in topology.backend_pods.iter().enumerate() {
+            let last = i == topology.backend_pods.len() - 1;
+            let (prefix, indent) = if last {
+                ("    └──", "        ")
+            } else {
+                ("    ├──", "    │   ")
+            };
+            let status_color = match pod.phase.as_str() {
+                "Running" => pod.phase.green(),
+                "Pending" => pod.phase.yellow(),
+                "Failed" => pod.phase.red(),
+                _ => pod.phase.normal(),
+            };
+            println!("{} {} ({})", prefix, pod.name, status_color);
+
+            // Storage chain: pod -> claim -> volume
+            let storage: Vec<_> = topology
+                .storage
+                .iter()
+                .filter(|s| s.pod == pod.name)
+                .collect();
+            for (j, chain) in storage.iter().enumerate() {
+                let branch = if j == storage.len() - 1 {
+                    "└──"
+                } else {
+                    "├──"
+                };
+                let volume = chain
+                    .volume
+                    .as_ref()
+                    .map_or_else(String::new, |v| format!(" -> pv/{}", v));
+                let details: Vec<&str> = [
+                    chain.storage_class.as_deref(),
+                    chain.capacity.as_deref(),
+                    Some(chain.phase.as_str()),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                println!(
+                    "{}{} pvc/{}{} ({})",
+                    indent,
+                    branch,
+                    chain.claim,
+                    volume,
+                    details.join(", ")
+                );
+            }
+        }
+    }
+
+    print_autoscalers(&topology.autoscalers);
+    print_pod_warnings(&topology.warnings);
+
+    // TODO: Add ingress routes and dependencies when implemented
+}
+
+/// The service's fields, its backend pods and their storage chains, then
+/// autoscalers and warnings
+fn service_topology_table(topology: &ServiceTopology) -> Table {
+    #[derive(Tabled)]
+    struct StorageRow {
+        #[tabled(rename = "POD")]
+        pod: String,
+        #[tabled(rename = "CLAIM")]
+        claim: String,
+        #[tabled(rename = "VOLUME")]
+        volume: String,
+        #[tabled(rename = "STORAGECLASS")]
+        storage_class: String,
+        #[tabled(rename = "CAPACITY")]
+        capacity: String,
+        #[tabled(rename = "PHASE")]
+        phase: String,
+    }
+
+    let none = || "-".to_string();
+    let storage = topology.storage.iter().map(|chain| StorageRow {
+        pod: chain.pod.clone(),
+        claim: chain.claim.clone(),
+        volume: chain.volume.clone().unwrap_or_else(none),
+        storage_class: chain.storage_class.clone().unwrap_or_else(none),
+        capacity: chain.capacity.clone().unwrap_or_else(none),
+        phase: chain.phase.clone(),
+    });
+    sections_table(
+        "SECTION",
+        vec![
+            (
+                "Service".to_string(),
+                fields_table(service_fields(&topology.service)),
+            ),
+            (
+                "Backend Pods".to_string(),
+                pods_table(&topology.backend_pods, false, false),
+            ),
+            ("Storage".to_string(), Table::new(storage)),
+            ("Autoscalers".to_string(), hpas_table(&topology.autoscalers)),
+            ("Warnings".to_string(), events_table(&topology.warnings)),
+        ],
+    )
+}
+
+/// Print events in the specified format
+pub fn print_events(events: &[EventInfo], output: &Output) -> Result<()> {
+    if events.is_empty() && says_none(output) {
+        println!("No events found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_events_table(events),
+        OutputFormat::Csv => print_csv(&events_table(events), output)?,
+        _ => print_structured(&events, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_events_table(events: &[EventInfo]) {
+    println!("{}", events_table(events));
+}
+
+fn events_table(events: &[EventInfo]) -> Table {
+    #[derive(Tabled)]
+    struct EventRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "LAST SEEN")]
+        last_seen: String,
+        #[tabled(rename = "TYPE")]
+        event_type: String,
+        #[tabled(rename = "REASON")]
+        reason: String,
+        #[tabled(rename = "OBJECT")]
+        object: String,
+        #[tabled(rename = "COUNT")]
+        count: i32,
+        #[tabled(rename = "MESSAGE")]
+        message: String,
+    }
+
+    let rows: Vec<EventRow> = events
+        .iter()
+        .map(|event| EventRow {
+            namespace: event.namespace.clone(),
+            last_seen: event.age.clone(),
+            event_type: if event.is_warning() {
+                event.event_type.yellow().to_string()
+            } else {
+                event.event_type.clone()
+            },
+            reason: event.reason.clone(),
+            object: event.object(),
+            count: event.count,
+            message: event.message.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print containers with their pods, roles and resources in the specified format
+pub fn print_containers(containers: &[ContainerInfo], output: &Output) -> Result<()> {
+    if containers.is_empty() && says_none(output) {
+        println!("No containers found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_containers_table(containers),
+        OutputFormat::Csv => print_csv(&containers_table(containers), output)?,
+        _ => print_structured(&containers, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_containers_table(containers: &[ContainerInfo]) {
+    println!("{}", containers_table(containers));
+}
+
+fn containers_table(containers: &[ContainerInfo]) -> Table {
+    #[derive(Tabled)]
+    struct ContainerRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "POD")]
+        pod: String,
+        #[tabled(rename = "CONTAINER")]
+        name: String,
+        #[tabled(rename = "ROLE")]
+        role: String,
+        #[tabled(rename = "IMAGE")]
+        image: String,
+        #[tabled(rename = "STATE")]
+        state: String,
+        #[tabled(rename = "RESTARTS")]
+        restarts: i32,
+        #[tabled(rename = "REQUESTS")]
+        requests: String,
+        #[tabled(rename = "LIMITS")]
+        limits: String,
+    }
+
+    let resources = |map: &BTreeMap<String, String>| {
+        if map.is_empty() {
+            "None".to_string()
+        } else {
+            map.iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+    };
+    let rows: Vec<ContainerRow> = containers
+        .iter()
+        .map(|c| ContainerRow {
+            namespace: c.namespace.clone(),
+            pod: c.pod.clone(),
+            name: c.name.clone(),
+            role: match c.role {
+                ContainerRole::Main => c.role.to_string(),
+                _ => c.role.to_string().dimmed().to_string(),
+            },
+            image: c.image.clone(),
+            state: if c.ready || c.role == ContainerRole::Init {
+                c.state.clone()
+            } else {
+                c.state.yellow().to_string()
+            },
+            restarts: c.restart_count,
+            requests: resources(&c.requests),
+            limits: resources(&c.limits),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print autoscalers with their targets and metrics in the specified format
+pub fn print_hpas(hpas: &[HpaInfo], output: &Output) -> Result<()> {
+    if hpas.is_empty() && says_none(output) {
+        println!("No horizontal pod autoscalers found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_hpas_table(hpas),
+        OutputFormat::Csv => print_csv(&hpas_table(hpas), output)?,
+        _ => print_structured(&hpas, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_hpas_table(hpas: &[HpaInfo]) {
+    println!("{}", hpas_table(hpas));
+}
+
+fn hpas_table(hpas: &[HpaInfo]) -> Table {
+    #[derive(Tabled)]
+    struct HpaRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "TARGET")]
+        target: String,
+        #[tabled(rename = "METRICS")]
+        metrics: String,
+        #[tabled(rename = "MIN")]
+        min: i32,
+        #[tabled(rename = "MAX")]
+        max: i32,
+        #[tabled(rename = "REPLICAS")]
+        replicas: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<HpaRow> = hpas
+        .iter()
+        .map(|hpa| {
+            let replicas = hpa
+                .current_replicas
+                .map_or_else(|| "-".to_string(), |r| r.to_string());
+            HpaRow {
+                namespace: hpa.namespace.clone(),
+                name: hpa.name.clone(),
+                target: match hpa.target_found {
+                    Some(false) => format!("{} (missing)", hpa.target()).red().to_string(),
+                    _ => hpa.target(),
+                },
+                metrics: hpa
+                    .metrics
+                    .iter()
+                    .map(|m| m.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                min: hpa.min_replicas,
+                max: hpa.max_replicas,
+                replicas: if hpa.at_max() {
+                    replicas.yellow().to_string()
+                } else {
+                    replicas
+                },
+                age: hpa.age.clone(),
+            }
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print per-namespace service mesh coverage in the specified format
+pub fn print_mesh_coverage(coverage: &[NamespaceMeshCoverage], output: &Output) -> Result<()> {
+    if coverage.is_empty() && says_none(output) {
+        println!("No workloads found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_mesh_coverage_table(coverage),
+        OutputFormat::Csv => print_csv(&mesh_coverage_table(coverage), output)?,
+        _ => print_structured(&coverage, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_mesh_coverage_table(coverage: &[NamespaceMeshCoverage]) {
+    println!("{}", namespace_mesh_coverage_table(coverage));
+
+    for ns in coverage {
+        if ns.partial.is_empty() && ns.unmeshed.is_empty() {
+            continue;
+        }
+
+        println!("\n{}", format!("Outside mesh in {}:", ns.namespace).bold());
+        for workload in &ns.partial {
+            println!(
+                "  {}/{} ({}/{} pods meshed)",
+                workload.kind, workload.name, workload.meshed_pods, workload.total_pods
+            );
+        }
+        for workload in &ns.unmeshed {
+            println!("  {}/{}", workload.kind, workload.name);
+        }
+    }
+}
+
+/// Coverage by namespace, then the workloads outside the mesh
+fn mesh_coverage_table(coverage: &[NamespaceMeshCoverage]) -> Table {
+    #[derive(Tabled)]
+    struct OutsideRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "WORKLOAD")]
+        workload: String,
+        #[tabled(rename = "PODS-MESHED")]
+        meshed: String,
+    }
+
+    let outside = coverage.iter().flat_map(|ns| {
+        let partial = ns.partial.iter().map(|w| OutsideRow {
+            namespace: ns.namespace.clone(),
+            workload: format!("{}/{}", w.kind, w.name),
+            meshed: format!("{}/{}", w.meshed_pods, w.total_pods),
+        });
+        let unmeshed = ns.unmeshed.iter().map(|w| OutsideRow {
+            namespace: ns.namespace.clone(),
+            workload: format!("{}/{}", w.kind, w.name),
+            meshed: "0".to_string(),
+        });
+        partial.chain(unmeshed)
+    });
+    sections_table(
+        "SECTION",
+        vec![
+            (
+                "Namespaces".to_string(),
+                namespace_mesh_coverage_table(coverage),
+            ),
+            ("Outside mesh".to_string(), Table::new(outside)),
+        ],
+    )
+}
+
+fn namespace_mesh_coverage_table(coverage: &[NamespaceMeshCoverage]) -> Table {
+    #[derive(Tabled)]
+    struct MeshCoverageRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "MESHED")]
+        meshed: usize,
+        #[tabled(rename = "PARTIAL")]
+        partial: usize,
+        #[tabled(rename = "UNMESHED")]
+        unmeshed: usize,
+        #[tabled(rename = "COVERAGE")]
+        coverage: String,
+    }
+
+    let rows: Vec<MeshCoverageRow> = coverage
+        .iter()
+        .map(|ns| MeshCoverageRow {
+            namespace: ns.namespace.clone(),
+            meshed: ns.meshed.len(),
+            partial: ns.partial.len(),
+            unmeshed: ns.unmeshed.len(),
+            coverage: format!("{:.0}%", ns.coverage()),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print zone distribution report in the specified format
+pub fn print_zone_report(report: &ZoneReport, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_zone_report_table(report),
+        OutputFormat::Csv => print_csv(&zone_report_table(report), output)?,
+        _ => print_structured(report, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_zone_report_table(report: &ZoneReport) {
+    if report.zones.is_empty() {
+        println!("No nodes found");
+        return;
+    }
+    println!("{}", zones_table(report));
+
+    if !report.workloads.is_empty() {
+        println!("\nWorkload distribution:");
+        println!("{}", workload_spread_table(report));
+    }
+
+    if !report.single_zone_services.is_empty() {
+        println!("\n{}", "Services with all replicas in one zone:".yellow());
+        for service in &report.single_zone_services {
+            println!(
+                "  {}/{} ({} replicas in {})",
+                service.namespace, service.name, service.replicas, service.zone
+            );
+        }
+    }
+}
+
+/// Zones, the spread of each workload, then the services with all
+/// replicas in one zone
+fn zone_report_table(report: &ZoneReport) -> Table {
+    #[derive(Tabled)]
+    struct SingleZoneRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "SERVICE")]
+        service: String,
+        #[tabled(rename = "REPLICAS")]
+        replicas: String,
+        #[tabled(rename = "ZONE")]
+        zone: String,
+    }
+
+    let single_zone = report.single_zone_services.iter().map(|s| SingleZoneRow {
+        namespace: s.namespace.clone(),
+        service: s.name.clone(),
+        replicas: s.replicas.to_string(),
+        zone: s.zone.clone(),
+    });
+    sections_table(
+        "SECTION",
+        vec![
+            ("Zones".to_string(), zones_table(report)),
+            ("Workloads".to_string(), workload_spread_table(report)),
+            ("Single-zone services".to_string(), Table::new(single_zone)),
+        ],
+    )
+}
+
+fn zones_table(report: &ZoneReport) -> Table {
+    #[derive(Tabled)]
+    struct ZoneRow {
+        #[tabled(rename = "ZONE")]
+        zone: String,
+        #[tabled(rename = "REGION")]
+        region: String,
+        #[tabled(rename = "NODES")]
+        nodes: usize,
+        #[tabled(rename = "PODS")]
+        pods: usize,
+        #[tabled(rename = "WORKLOADS")]
+        workloads: usize,
+    }
+
+    let rows: Vec<ZoneRow> = report
+        .zones
+        .iter()
+        .map(|z| ZoneRow {
+            zone: z.zone.clone(),
+            region: z.region.clone().unwrap_or_else(|| "None".to_string()),
+            nodes: z.nodes,
+            pods: z.pods,
+            workloads: z.workloads,
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn workload_spread_table(report: &ZoneReport) -> Table {
+    #[derive(Tabled)]
+    struct WorkloadSpreadRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "WORKLOAD")]
+        workload: String,
+        #[tabled(rename = "REPLICAS")]
+        replicas: u32,
+        #[tabled(rename = "ZONES")]
+        zones: String,
+    }
+
+    let rows: Vec<WorkloadSpreadRow> = report
+        .workloads
+        .iter()
+        .map(|w| WorkloadSpreadRow {
+            namespace: w.namespace.clone(),
+            workload: format!("{}/{}", w.kind, w.name),
+            replicas: w.replicas_by_zone.values().sum(),
+            zones: w
+                .replicas_by_zone
+                .iter()
+                .map(|(zone, count)| format!("{}={}", zone, count))
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print workloads running only on spot capacity in the specified format
+pub fn print_spot_exposure(exposure: &[SpotExposure], output: &Output) -> Result<()> {
+    if exposure.is_empty() && says_none(output) {
+        println!("No workloads run exclusively on spot capacity");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", spot_exposure_table(exposure)),
+        OutputFormat::Csv => print_csv(&spot_exposure_table(exposure), output)?,
+        _ => print_structured(&exposure, output)?,
+    }
+
+    Ok(())
+}
+
+fn spot_exposure_table(exposure: &[SpotExposure]) -> Table {
+    #[derive(Tabled)]
+    struct SpotExposureRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "OWNER")]
+        owner: String,
+        #[tabled(rename = "REPLICAS")]
+        replicas: u32,
+        #[tabled(rename = "SPOT NODES")]
+        nodes: String,
+    }
+
+    let rows: Vec<SpotExposureRow> = exposure
+        .iter()
+        .map(|e| SpotExposureRow {
+            namespace: e.namespace.clone(),
+            owner: format!("{}/{}", e.kind, e.name),
+            replicas: e.replicas,
+            nodes: e.nodes.join(", "),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print node image footprints; `per_image` lists the images of the
+/// (single) node in the report instead of the per-node totals
+pub fn print_node_images(report: &NodeImageReport, per_image: bool, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_node_images_table(report, per_image),
+        OutputFormat::Csv => print_csv(&node_images_table(report, per_image), output)?,
+        _ => print_structured(report, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_node_images_table(report: &NodeImageReport, per_image: bool) {
+    if per_image {
+        for node in &report.nodes {
+            if node.images.is_empty() {
+                println!("No images reported on node {}", node.node);
+                continue;
+            }
+            println!("{}", images_table(&node.images));
+            println!(
+                "\n{}: {} images, {} ({} not used by pods on the node)",
+                node.node,
+                node.images.len(),
+                format_bytes(node.total_bytes as f64),
+                format_bytes(node.unused_bytes as f64)
+            );
+        }
+        return;
+    }
+
+    if report.nodes.is_empty() {
+        println!("No nodes found");
+        return;
+    }
+    println!("{}", node_footprints_table(report));
+
+    if report.unused.is_empty() {
+        println!("\nEvery cached image is used by a running workload");
+        return;
+    }
+
+    let reclaimable: u64 = report
+        .unused
+        .iter()
+        .map(|i| i.size_bytes * i.nodes.len() as u64)
+        .sum();
+    println!("\nImages used by no running workload:");
+    println!("{}", unused_images_table(report));
+    println!("\nReclaimable: {}", format_bytes(reclaimable as f64));
+}
+
+/// The images of each node with `per_image`, the node in a NODE column;
+/// otherwise the footprint of each node and the images no workload uses
+fn node_images_table(report: &NodeImageReport, per_image: bool) -> Table {
+    if per_image {
+        return items_table(
+            "NODE",
+            images_table(&[]),
+            report
+                .nodes
+                .iter()
+                .map(|node| (node.node.clone(), images_table(&node.images))),
+        );
+    }
+    sections_table(
+        "SECTION",
+        vec![
+            ("Nodes".to_string(), node_footprints_table(report)),
+            ("Unused".to_string(), unused_images_table(report)),
+        ],
+    )
+}
+
+fn images_table(images: &[CachedImage]) -> Table {
+    #[derive(Tabled)]
+    struct ImageRow {
+        #[tabled(rename = "IMAGE")]
+        name: String,
+        #[tabled(rename = "SIZE")]
+        size: String,
+        #[tabled(rename = "IN USE")]
+        in_use: String,
+    }
+
+    let rows: Vec<ImageRow> = images
+        .iter()
+        .map(|i| ImageRow {
+            name: i.name.clone(),
+            size: format_bytes(i.size_bytes as f64),
+            in_use: if i.in_use {
+                "yes".green().to_string()
+            } else {
+                "no".yellow().to_string()
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn node_footprints_table(report: &NodeImageReport) -> Table {
+    #[derive(Tabled)]
+    struct NodeRow {
+        #[tabled(rename = "NODE")]
+        node: String,
+        #[tabled(rename = "IMAGES")]
+        images: usize,
+        #[tabled(rename = "FOOTPRINT")]
+        total: String,
+        #[tabled(rename = "UNUSED ON NODE")]
+        unused: String,
+    }
+
+    let rows: Vec<NodeRow> = report
+        .nodes
+        .iter()
+        .map(|n| NodeRow {
+            node: n.node.clone(),
+            images: n.images.len(),
+            total: format_bytes(n.total_bytes as f64),
+            unused: format_bytes(n.unused_bytes as f64),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn unused_images_table(report: &NodeImageReport) -> Table {
+    #[derive(Tabled)]
+    struct UnusedRow {
+        #[tabled(rename = "IMAGE")]
+        name: String,
+        #[tabled(rename = "SIZE")]
+        size: String,
+        #[tabled(rename = "NODES")]
+        nodes: usize,
+    }
+
+    let rows: Vec<UnusedRow> = report
+        .unused
+        .iter()
+        .map(|i| UnusedRow {
+            name: i.name.clone(),
+            size: format_bytes(i.size_bytes as f64),
+            nodes: i.nodes.len(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print out-of-cluster dependencies in the specified format
+pub fn print_externals(externals: &[ExternalDependency], output: &Output) -> Result<()> {
+    if externals.is_empty() && says_none(output) {
+        println!("No external dependencies found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", externals_table(externals)),
+        OutputFormat::Csv => print_csv(&externals_table(externals), output)?,
+        _ => print_structured(&externals, output)?,
+    }
+
+    Ok(())
+}
+
+fn externals_table(externals: &[ExternalDependency]) -> Table {
+    #[derive(Tabled)]
+    struct ExternalRow {
+        #[tabled(rename = "HOST")]
+        host: String,
+        #[tabled(rename = "CATEGORY")]
+        category: String,
+        #[tabled(rename = "PORTS")]
+        ports: String,
+        #[tabled(rename = "SOURCE")]
+        source: String,
+        #[tabled(rename = "REFERENCED BY")]
+        referenced_by: String,
+    }
+
+    let rows: Vec<ExternalRow> = externals
+        .iter()
+        .map(|d| {
+            let mut sources: Vec<String> =
+                d.references.iter().map(|r| r.source.to_string()).collect();
+            sources.dedup();
+            let mut objects: Vec<String> = d
+                .references
+                .iter()
+                .map(|r| format!("{}/{}", r.namespace, r.object()))
+                .collect();
+            objects.sort();
+            objects.dedup();
+            ExternalRow {
+                host: d.host.clone(),
+                category: d.category.clone(),
+                ports: if d.ports.is_empty() {
+                    "-".to_string()
+                } else {
+                    d.ports
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                },
+                source: sources.join(", "),
+                referenced_by: objects.join(", "),
+            }
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print ingress collisions and missing TLS secrets in the specified format
+pub fn print_ingress_issues(issues: &[IngressIssue], output: &Output) -> Result<()> {
+    if issues.is_empty() && says_none(output) {
+        println!("No ingress conflicts found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", ingress_issues_table(issues)),
+        OutputFormat::Csv => print_csv(&ingress_issues_table(issues), output)?,
+        _ => print_structured(&issues, output)?,
+    }
+
+    Ok(())
+}
+
+fn ingress_issues_table(issues: &[IngressIssue]) -> Table {
+    #[derive(Tabled)]
+    struct IngressIssueRow {
+        #[tabled(rename = "ISSUE")]
+        issue: String,
+        #[tabled(rename = "HOST")]
+        host: String,
+        #[tabled(rename = "PATH")]
+        path: String,
+        #[tabled(rename = "INGRESS")]
+        ingress: String,
+        #[tabled(rename = "CLASS")]
+        class: String,
+        #[tabled(rename = "BACKEND")]
+        backend: String,
+    }
+
+    // One row per route involved, with the issue shown on its first row
+    let mut rows = Vec::new();
+    for issue in issues {
+        let mut first = true;
+        for route in &issue.routes {
+            let (issue_name, host, path) = if first {
+                (
+                    issue.kind.to_string(),
+                    issue.host.clone(),
+                    issue.path.clone().unwrap_or_else(|| "-".to_string()),
+                )
+            } else {
+                Default::default()
+            };
+            first = false;
+            rows.push(IngressIssueRow {
+                issue: issue_name,
+                host,
+                path,
+                ingress: format!("{}/{}", route.namespace, route.ingress),
+                class: route.class.clone().unwrap_or_else(|| "-".to_string()),
+                backend: match &issue.secret {
+                    Some(secret) => format!("secret {} not found", secret),
+                    None => route.backend.clone(),
+                },
+            });
+        }
+    }
+
+    Table::new(rows)
+}
+
+pub fn print_network_issues(issues: &[NetworkIssue], output: &Output) -> Result<()> {
+    if issues.is_empty() && says_none(output) {
+        println!("No network issues found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", network_issues_table(issues)),
+        OutputFormat::Csv => print_csv(&network_issues_table(issues), output)?,
+        _ => print_structured(&issues, output)?,
+    }
+
+    Ok(())
+}
+
+fn network_issues_table(issues: &[NetworkIssue]) -> Table {
+    #[derive(Tabled)]
+    struct NetworkIssueRow {
+        #[tabled(rename = "ISSUE")]
+        issue: String,
+        #[tabled(rename = "ADDRESS")]
+        address: String,
+        #[tabled(rename = "OBJECTS")]
+        objects: String,
+        #[tabled(rename = "DETAIL")]
+        detail: String,
+    }
+
+    let rows: Vec<NetworkIssueRow> = issues
+        .iter()
+        .map(|issue| NetworkIssueRow {
+            issue: issue.kind.to_string(),
+            address: issue.address.clone(),
+            objects: issue.objects.join("\n"),
+            detail: issue.detail.clone().unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print what moving a workload to another namespace would break
+pub fn print_move_check(check: &MoveCheck, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_move_check_table(check),
+        OutputFormat::Csv => print_csv(&move_check_table(check), output)?,
+        _ => print_structured(check, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_move_check_table(check: &MoveCheck) {
+    if check.issues.is_empty() {
+        println!(
+            "Nothing found that would break moving {} from {} to {}",
+            check.workload, check.from, check.to
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Moving {} from {} to {}:",
+            check.workload, check.from, check.to
+        )
+        .bold()
+    );
+    println!("{}", move_check_table(check));
+}
+
+fn move_check_table(check: &MoveCheck) -> Table {
+    #[derive(Tabled)]
+    struct MoveIssueRow {
+        #[tabled(rename = "ISSUE")]
+        issue: String,
+        #[tabled(rename = "OBJECT")]
+        object: String,
+        #[tabled(rename = "DETAIL")]
+        detail: String,
+    }
+
+    let rows: Vec<MoveIssueRow> = check
+        .issues
+        .iter()
+        .map(|issue| MoveIssueRow {
+            issue: issue.kind.to_string(),
+            object: issue.object.clone(),
+            detail: issue.detail.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print NetworkPolicies in the specified format
+pub fn print_network_policies(policies: &[NetworkPolicyInfo], output: &Output) -> Result<()> {
+    if policies.is_empty() && says_none(output) {
+        println!("No network policies found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", network_policies_table(policies)),
+        OutputFormat::Csv => print_csv(&network_policies_table(policies), output)?,
+        _ => print_structured(&policies, output)?,
+    }
+
+    Ok(())
+}
+
+fn network_policies_table(policies: &[NetworkPolicyInfo]) -> Table {
+    #[derive(Tabled)]
+    struct NetworkPolicyRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "POD-SELECTOR")]
+        pod_selector: String,
+        #[tabled(rename = "PODS")]
+        pods: usize,
+        #[tabled(rename = "INGRESS")]
+        ingress: String,
+        #[tabled(rename = "EGRESS")]
+        egress: String,
+    }
+
+    // Isolated directions without rules deny everything; others are open
+    let rules = |policy: &NetworkPolicyInfo, direction, rules: &[String]| {
+        if !policy.policy_types.contains(&direction) {
+            "-".to_string()
+        } else if rules.is_empty() {
+            "deny all".to_string()
+        } else {
+            rules.join("\n")
+        }
+    };
+    let rows: Vec<NetworkPolicyRow> = policies
+        .iter()
+        .map(|policy| NetworkPolicyRow {
+            namespace: policy.namespace.clone(),
+            name: policy.name.clone(),
+            pod_selector: if policy.pod_selector.is_empty() {
+                "<all pods>".to_string()
+            } else {
+                policy.pod_selector.clone()
+            },
+            pods: policy.affected_pods,
+            ingress: rules(policy, Direction::Ingress, &policy.ingress),
+            egress: rules(policy, Direction::Egress, &policy.egress),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print whether NetworkPolicies let traffic through in the specified format
+pub fn print_reachability(reachability: &Reachability, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_reachability_text(reachability),
+        OutputFormat::Csv => print_csv(&reachability_table(reachability), output)?,
+        _ => print_structured(reachability, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_reachability_text(reachability: &Reachability) {
+    let port = reachability
+        .port
+        .as_ref()
+        .map(|port| format!(" on {}", port))
+        .unwrap_or_default();
+    let verdict = if reachability.allowed {
+        "ALLOWED".green()
+    } else {
+        "BLOCKED".red()
+    };
+    println!(
+        "{} ({}) -> {} ({}){}: {}",
+        reachability.from,
+        reachability.from_namespace,
+        reachability.to,
+        reachability.to_namespace,
+        port,
+        verdict
+    );
+
+    for (verdict, side) in [
+        (&reachability.egress, &reachability.from),
+        (&reachability.ingress, &reachability.to),
+    ] {
+        let outcome = if !verdict.isolated {
+            "not isolated, allowed".to_string()
+        } else if verdict.allowed {
+            format!("allowed by {}", verdict.allowed_by.join(", "))
+        } else {
+            format!("no rule of {} allows it", verdict.policies.join(", "))
+        };
+        println!("  {} of {}: {}", verdict.direction, side, outcome);
+    }
+    if reachability.port.is_none() && reachability.allowed {
+        println!("  (some port is allowed; pass --port to check a specific one)");
+    }
+}
+
+/// The egress verdict of the source and the ingress verdict of the
+/// destination, one row each
+fn reachability_table(reachability: &Reachability) -> Table {
+    #[derive(Tabled)]
+    struct VerdictRow {
+        #[tabled(rename = "DIRECTION")]
+        direction: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "PORT")]
+        port: String,
+        #[tabled(rename = "ISOLATED")]
+        isolated: bool,
+        #[tabled(rename = "ALLOWED")]
+        allowed: bool,
+        #[tabled(rename = "POLICIES")]
+        policies: String,
+        #[tabled(rename = "ALLOWED-BY")]
+        allowed_by: String,
+    }
+
+    let port = reachability
+        .port
+        .as_ref()
+        .map_or_else(|| "any".to_string(), |port| port.to_string());
+    let rows = [
+        (
+            &reachability.egress,
+            &reachability.from_namespace,
+            &reachability.from,
+        ),
+        (
+            &reachability.ingress,
+            &reachability.to_namespace,
+            &reachability.to,
+        ),
+    ]
+    .map(|(verdict, namespace, name)| VerdictRow {
+        direction: verdict.direction.to_string(),
+        namespace: namespace.clone(),
+        name: name.clone(),
+        port: port.clone(),
+        isolated: verdict.isolated,
+        allowed: verdict.allowed,
+        policies: verdict.policies.join(", "),
+        allowed_by: verdict.allowed_by.join(", "),
+    });
+    Table::new(rows)
+}
+
+/// Print what an impersonated service account can see in the specified format
+pub fn print_visibility(report: &VisibilityReport, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_visibility_table(report),
+        OutputFormat::Csv => print_csv(&visibility_table(report), output)?,
+        _ => print_structured(report, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_visibility_table(report: &VisibilityReport) {
+    println!(
+        "{} {}",
+        "Visibility of".bold(),
+        report.service_account.bold()
+    );
+
+    let cluster: Vec<String> = report
+        .cluster_scoped
+        .iter()
+        .map(|access| {
+            if access.visible {
+                access.resource.green().to_string()
+            } else {
+                access.resource.dimmed().to_string()
+            }
+        })
+        .collect();
+    println!("Cluster-scoped: {}", cluster.join(", "));
+
+    let cluster_wide: Vec<&str> = report
+        .namespaced
+        .iter()
+        .filter(|r| r.cluster_wide)
+        .map(|r| r.resource.as_str())
+        .collect();
+    if !cluster_wide.is_empty() {
+        println!(
+            "Listable across all namespaces: {}",
+            cluster_wide.join(", ").yellow()
+        );
+    }
+    println!();
+
+    if report.by_namespace().is_empty() {
+        println!("No resources visible in any namespace");
+    } else {
+        println!("{}", namespace_visibility_table(report));
+    }
+
+    let hidden = report.hidden_namespaces();
+    if !hidden.is_empty() {
+        println!(
+            "\nNothing visible in {} of {} namespaces",
+            hidden.len(),
+            report.namespaces.len()
+        );
+    }
+}
+
+/// Cluster-scoped resources and whether they are visible, then the
+/// resources visible in each namespace
+fn visibility_table(report: &VisibilityReport) -> Table {
+    #[derive(Tabled)]
+    struct ClusterRow {
+        #[tabled(rename = "RESOURCE")]
+        resource: String,
+        #[tabled(rename = "VISIBLE")]
+        visible: bool,
+        #[tabled(rename = "ALL NAMESPACES")]
+        cluster_wide: String,
+    }
+
+    let cluster_scoped = report.cluster_scoped.iter().map(|access| ClusterRow {
+        resource: access.resource.clone(),
+        visible: access.visible,
+        cluster_wide: "-".to_string(),
+    });
+    let namespaced = report.namespaced.iter().map(|r| ClusterRow {
+        resource: r.resource.clone(),
+        visible: r.cluster_wide || !r.namespaces.is_empty(),
+        cluster_wide: r.cluster_wide.to_string(),
+    });
+    sections_table(
+        "SECTION",
+        vec![
+            (
+                "Cluster".to_string(),
+                Table::new(cluster_scoped.chain(namespaced)),
+            ),
+            ("Namespaces".to_string(), namespace_visibility_table(report)),
+        ],
+    )
+}
+
+fn namespace_visibility_table(report: &VisibilityReport) -> Table {
+    #[derive(Tabled)]
+    struct VisibilityRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "VISIBLE RESOURCES")]
+        resources: String,
+    }
+
+    let rows: Vec<VisibilityRow> = report
+        .by_namespace()
+        .into_iter()
+        .map(|(namespace, resources)| VisibilityRow {
+            namespace: namespace.to_string(),
+            resources: resources.join(", "),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print dangling persistent volumes in the specified format
+pub fn print_dangling_volumes(report: &DanglingVolumeReport, output: &Output) -> Result<()> {
+    if report.volumes.is_empty() && says_none(output) {
+        println!("No dangling persistent volumes found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_dangling_volumes_table(report),
+        OutputFormat::Csv => print_csv(
+            &sections_table(
+                "SECTION",
+                vec![
+                    ("Volumes".to_string(), dangling_volumes_table(report)),
+                    (
+                        "Orphaned capacity".to_string(),
+                        orphaned_capacity_table(report),
+                    ),
+                ],
+            ),
+            output,
+        )?,
+        _ => print_structured(report, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_dangling_volumes_table(report: &DanglingVolumeReport) {
+    println!("{}", dangling_volumes_table(report));
+    println!("\nOrphaned capacity by storage class:");
+    println!("{}", orphaned_capacity_table(report));
+}
+
+fn dangling_volumes_table(report: &DanglingVolumeReport) -> Table {
+    #[derive(Tabled)]
+    struct DanglingVolumeRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "REASON")]
+        reason: String,
+        #[tabled(rename = "CAPACITY")]
+        capacity: String,
+        #[tabled(rename = "STORAGECLASS")]
+        storage_class: String,
+        #[tabled(rename = "RECLAIM POLICY")]
+        reclaim_policy: String,
+        #[tabled(rename = "CLAIM")]
+        claim: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<DanglingVolumeRow> = report
+        .volumes
+        .iter()
+        .map(|d| {
+            let pv = &d.volume;
+            DanglingVolumeRow {
+                name: pv.name.clone(),
+                reason: d.reason.to_string(),
+                capacity: pv.capacity.clone().unwrap_or_else(|| "None".to_string()),
+                storage_class: pv
+                    .storage_class
+                    .clone()
+                    .unwrap_or_else(|| "None".to_string()),
+                reclaim_policy: pv.reclaim_policy.clone(),
+                claim: match (&pv.claim_namespace, &pv.claim_name) {
+                    (Some(ns), Some(name)) => format!("{}/{}", ns, name),
+                    _ => "None".to_string(),
+                },
+                age: pv.age.clone(),
+            }
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn orphaned_capacity_table(report: &DanglingVolumeReport) -> Table {
+    #[derive(Tabled)]
+    struct OrphanedCapacityRow {
+        #[tabled(rename = "STORAGECLASS")]
+        storage_class: String,
+        #[tabled(rename = "VOLUMES")]
+        volumes: usize,
+        #[tabled(rename = "CAPACITY")]
+        capacity: String,
+    }
+
+    let capacity_rows: Vec<OrphanedCapacityRow> = report
+        .orphaned_capacity
+        .iter()
+        .map(|c| OrphanedCapacityRow {
+            storage_class: c.storage_class.clone(),
+            volumes: c.volumes,
+            capacity: format_bytes(c.bytes),
+        })
+        .collect();
+    Table::new(capacity_rows)
+}
+
+/// Print requested storage per namespace, storage class and workload in the
+/// specified format
+pub fn print_storage_rollup(rollup: &[NamespaceStorage], output: &Output) -> Result<()> {
+    if rollup.is_empty() && says_none(output) {
+        println!("No persistent volume claims found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_storage_rollup_table(rollup),
+        OutputFormat::Csv => print_csv(&storage_rollup_table(rollup), output)?,
+        _ => print_structured(&rollup, output)?,
+    }
+
+    Ok(())
+}
+
+/// Quota limit with the share of it requested, colored as it fills up
+fn format_quota(quota: Option<f64>, used: Option<f64>) -> String {
+    let (Some(quota), Some(used)) = (quota, used) else {
+        return "None".to_string();
+    };
+    let cell = format!("{} ({:.0}%)", format_bytes(quota), used);
+    if used >= 100.0 {
+        cell.red().to_string()
+    } else if used >= 80.0 {
+        cell.yellow().to_string()
+    } else {
+        cell
+    }
+}
+
+fn print_storage_rollup_table(rollup: &[NamespaceStorage]) {
+    println!("{}", namespace_storage_table(rollup));
+    println!("\nBy storage class:");
+    println!("{}", class_storage_table(rollup));
+    println!("\nBy workload:");
+    println!("{}", workload_storage_table(rollup));
+}
+
+/// Storage by namespace, by storage class and by workload
+fn storage_rollup_table(rollup: &[NamespaceStorage]) -> Table {
+    sections_table(
+        "BY",
+        vec![
+            ("Namespace".to_string(), namespace_storage_table(rollup)),
+            ("StorageClass".to_string(), class_storage_table(rollup)),
+            ("Workload".to_string(), workload_storage_table(rollup)),
+        ],
+    )
+}
+
+fn namespace_storage_table(rollup: &[NamespaceStorage]) -> Table {
+    #[derive(Tabled)]
+    struct NamespaceRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "CLAIMS")]
+        claims: usize,
+        #[tabled(rename = "REQUESTED")]
+        requested: String,
+        #[tabled(rename = "QUOTA")]
+        quota: String,
+    }
+
+    let rows: Vec<NamespaceRow> = rollup
+        .iter()
+        .map(|ns| NamespaceRow {
+            namespace: ns.namespace.clone(),
+            claims: ns.claims,
+            requested: format_bytes(ns.requested_bytes),
+            quota: format_quota(ns.quota_bytes, ns.quota_used()),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn class_storage_table(rollup: &[NamespaceStorage]) -> Table {
+    #[derive(Tabled)]
+    struct ClassRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "STORAGECLASS")]
+        name: String,
+        #[tabled(rename = "CLAIMS")]
+        claims: usize,
+        #[tabled(rename = "REQUESTED")]
+        requested: String,
+        #[tabled(rename = "QUOTA")]
+        quota: String,
+    }
+
+    let class_rows: Vec<ClassRow> = rollup
+        .iter()
+        .flat_map(|ns| {
+            ns.classes.iter().map(|class| ClassRow {
+                namespace: ns.namespace.clone(),
+                name: class.name.clone(),
+                claims: class.claims,
+                requested: format_bytes(class.requested_bytes),
+                quota: format_quota(class.quota_bytes, class.quota_used()),
+            })
+        })
+        .collect();
+    Table::new(class_rows)
+}
+
+fn workload_storage_table(rollup: &[NamespaceStorage]) -> Table {
+    #[derive(Tabled)]
+    struct WorkloadRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "WORKLOAD")]
+        workload: String,
+        #[tabled(rename = "CLAIMS")]
+        claims: usize,
+        #[tabled(rename = "REQUESTED")]
+        requested: String,
+    }
+
+    let workload_rows: Vec<WorkloadRow> = rollup
+        .iter()
+        .flat_map(|ns| {
+            ns.workloads.iter().map(|workload| WorkloadRow {
+                namespace: ns.namespace.clone(),
+                workload: workload.name.clone(),
+                claims: workload.claims,
+                requested: format_bytes(workload.requested_bytes),
+            })
+        })
+        .collect();
+    Table::new(workload_rows)
+}
+
+/// Print custom resources failing schema validation in the specified format
+pub fn print_instance_validations(
+    results: &[InstanceValidation],
+    total: usize,
+    output: &Output,
+) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_instance_validations_table(results, total),
+        OutputFormat::Csv => print_csv(&instance_validations_table(results), output)?,
+        _ => print_structured(&results, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_instance_validations_table(results: &[InstanceValidation], total: usize) {
+    if results.is_empty() {
+        println!("All {} instances are valid", total);
+        return;
+    }
+
+    println!("{}", instance_validations_table(results));
+    println!(
+        "\n{} of {} instances would fail re-creation",
+        results.len(),
+        total
+    );
+}
+
+fn instance_validations_table(results: &[InstanceValidation]) -> Table {
+    #[derive(Tabled)]
+    struct ViolationRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+        #[tabled(rename = "PATH")]
+        path: String,
+        #[tabled(rename = "VIOLATION")]
+        message: String,
+    }
+
+    let rows: Vec<ViolationRow> = results
+        .iter()
+        .flat_map(|result| {
+            result.violations.iter().map(|v| ViolationRow {
+                name: result.name.clone(),
+                namespace: result
+                    .namespace
+                    .clone()
+                    .unwrap_or_else(|| "None".to_string()),
+                age: result.age.clone(),
+                path: v.path.clone(),
+                message: v.message.clone(),
+            })
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print the effective environment of each container in the specified format
+pub fn print_container_env(containers: &[ContainerEnv], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_container_env_table(containers),
+        OutputFormat::Csv => print_csv(
+            &items_table(
+                "CONTAINER",
+                env_table(&[]),
+                containers
+                    .iter()
+                    .map(|c| (c.container.clone(), env_table(&c.variables))),
+            ),
+            output,
+        )?,
+        _ => print_structured(&containers, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_container_env_table(containers: &[ContainerEnv]) {
+    for container in containers {
+        let kind = if container.init {
+            "Init container"
+        } else {
+            "Container"
+        };
+        println!("{}", format!("{}: {}", kind, container.container).bold());
+
+        if container.variables.is_empty() {
+            println!("  No environment variables\n");
+            continue;
+        }
+
+        println!("{}\n", env_table(&container.variables));
+    }
+}
+
+fn env_table(variables: &[EnvVar]) -> Table {
+    #[derive(Tabled)]
+    struct EnvRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "VALUE")]
+        value: String,
+        #[tabled(rename = "SOURCE")]
+        source: String,
+    }
+
+    let rows: Vec<EnvRow> = variables
+        .iter()
+        .map(|v| EnvRow {
+            name: v.name.clone(),
+            value: v.value.clone().unwrap_or_else(|| "None".to_string()),
+            source: v.source.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_ownership_chain(chain: &OwnershipChain, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_ownership_chain_table(chain),
+        OutputFormat::Csv => print_csv(&ownership_chain_table(chain), output)?,
+        _ => print_structured(chain, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_ownership_chain_table(chain: &OwnershipChain) {
+    println!("{}", "Ownership Chain".bold());
+
+    // The chain is stored bottom-up; print it from the top-level owner down
+    for (depth, link) in chain.chain.iter().rev().enumerate() {
+        let indent = if depth == 0 {
+            String::new()
+        } else {
+            format!("{}└─ ", "   ".repeat(depth - 1))
+        };
+        println!(
+            "{}{}/{} ({})",
+            indent,
+            link.kind.cyan(),
+            link.name,
+            link.api_version
+        );
+    }
+
+    match &chain.managed_by {
+        Some(manager) => {
+            let namespace = manager
+                .namespace
+                .as_ref()
+                .map(|ns| format!(" in namespace {}", ns))
+                .unwrap_or_default();
+            println!(
+                "\nManaged by: {} {}{}",
+                manager.tool.green(),
+                manager.name,
+                namespace
+            );
+        }
+        None => println!("\nManaged by: None"),
+    }
+}
+
+/// The chain from the top-level owner down, then the managing tool
+fn ownership_chain_table(chain: &OwnershipChain) -> Table {
+    #[derive(Tabled)]
+    struct LinkRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "API-VERSION")]
+        api_version: String,
+    }
+
+    #[derive(Tabled)]
+    struct ManagerRow {
+        #[tabled(rename = "TOOL")]
+        tool: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+    }
+
+    let links = chain.chain.iter().rev().map(|link| LinkRow {
+        kind: link.kind.clone(),
+        name: link.name.clone(),
+        namespace: link.namespace.clone().unwrap_or_default(),
+        api_version: link.api_version.clone(),
+    });
+    let manager = chain.managed_by.iter().map(|manager| ManagerRow {
+        tool: manager.tool.clone(),
+        name: manager.name.clone(),
+        namespace: manager.namespace.clone().unwrap_or_default(),
+    });
+    sections_table(
+        "SECTION",
+        vec![
+            ("Owners".to_string(), Table::new(links)),
+            ("Managed by".to_string(), Table::new(manager)),
+        ],
+    )
+}
+
+/// Print an ownership tree in the specified format
+pub fn print_owner_tree(tree: &OwnerTree, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_owner_tree_table(tree),
+        OutputFormat::Csv => print_csv(&owner_tree_table(tree), output)?,
+        _ => print_structured(tree, output)?,
+    }
+
+    Ok(())
+}
+
+#[derive(Tabled)]
+struct TreeRow {
+    #[tabled(rename = "NAMESPACE")]
+    namespace: String,
+    #[tabled(rename = "NAME")]
+    name: String,
+    #[tabled(rename = "AGE")]
+    age: String,
+}
+
+/// Rows of `node`'s dependents, drawn below a line starting with `prefix`
+fn tree_rows(node: &TreeNode, prefix: &str, rows: &mut Vec<TreeRow>) {
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        rows.push(TreeRow {
+            namespace: child.namespace.clone().unwrap_or_else(|| "-".to_string()),
+            name: format!(
+                "{}{}{}/{}",
+                prefix,
+                if last { "└─" } else { "├─" },
+                child.kind,
+                child.name
+            ),
+            age: child.age.clone(),
+        });
+        let nested = format!("{}{}", prefix, if last { "  " } else { "│ " });
+        tree_rows(child, &nested, rows);
+    }
+}
+
+fn print_owner_tree_table(tree: &OwnerTree) {
+    println!("{}", owner_tree_table(tree));
+    println!("\n{} dependent objects", tree.dependent_count());
+}
+
+fn owner_tree_table(tree: &OwnerTree) -> Table {
+    let mut rows = Vec::new();
+    // Owners form a single line of descent down to the object
+    for (depth, owner) in tree.owners.iter().enumerate() {
+        let branch = if depth == 0 { "" } else { "└─" };
+        rows.push(TreeRow {
+            namespace: owner.namespace.clone().unwrap_or_else(|| "-".to_string()),
+            name: format!(
+                "{}{}{}/{}",
+                "  ".repeat(depth.saturating_sub(1)),
+                branch,
+                owner.kind,
+                owner.name
+            ),
+            age: "-".to_string(),
+        });
+    }
+    let depth = tree.owners.len();
+    let root = &tree.root;
+    rows.push(TreeRow {
+        namespace: root.namespace.clone().unwrap_or_else(|| "-".to_string()),
+        name: format!(
+            "{}{}{}",
+            "  ".repeat(depth.saturating_sub(1)),
+            if depth == 0 { "" } else { "└─" },
+            format!("{}/{}", root.kind, root.name).bold()
+        ),
+        age: root.age.clone(),
+    });
+    tree_rows(root, &"  ".repeat(depth), &mut rows);
+
+    Table::new(rows)
+}
+
+pub fn print_namespace_diff(diff: &NamespaceDiff, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_namespace_diff_table(diff),
+        OutputFormat::Csv => print_csv(&namespace_diff_table(diff), output)?,
+        _ => print_structured(diff, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_namespace_diff_table(diff: &NamespaceDiff) {
+    println!(
+        "Namespace {}: A = {}, B = {}",
+        diff.namespace.bold(),
+        diff.context_a.cyan(),
+        diff.context_b.cyan()
+    );
+
+    if diff.differences.is_empty() {
+        println!("{}", "No differences found".green());
+        return;
+    }
+    println!("{}", namespace_diff_table(diff));
+}
+
+fn namespace_diff_table(diff: &NamespaceDiff) -> Table {
+    #[derive(Tabled)]
+    struct DiffRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "FIELD")]
+        field: String,
+        #[tabled(rename = "A")]
+        a: String,
+        #[tabled(rename = "B")]
+        b: String,
+    }
+
+    let rows: Vec<DiffRow> = diff
+        .differences
+        .iter()
+        .map(|d| DiffRow {
+            kind: d.kind.clone(),
+            name: d.name.clone(),
+            field: d.field.clone(),
+            a: d.a.clone().unwrap_or_else(|| "None".to_string()),
+            b: d.b.clone().unwrap_or_else(|| "None".to_string()),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_diff(diff: &ResourceDiff, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_diff_table(diff),
+        OutputFormat::Csv => print_csv(&diff_table(diff), output)?,
+        _ => print_structured(diff, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_diff_table(diff: &ResourceDiff) {
+    println!("From {} to {}", diff.from.cyan(), diff.to.cyan());
+
+    if diff.changes.is_empty() {
+        println!("{}", "No differences found".green());
+        return;
+    }
+
+    println!("{}", diff_table(diff));
+    println!(
+        "{} added, {} removed, {} changed",
+        diff.count(Change::Added),
+        diff.count(Change::Removed),
+        diff.count(Change::Changed)
+    );
+}
+
+fn diff_table(diff: &ResourceDiff) -> Table {
+    #[derive(Tabled)]
+    struct ChangeRow {
+        #[tabled(rename = "CHANGE")]
+        change: String,
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "FIELD")]
+        field: String,
+        #[tabled(rename = "FROM")]
+        from: String,
+        #[tabled(rename = "TO")]
+        to: String,
+    }
+
+    let rows: Vec<ChangeRow> = diff
+        .changes
+        .iter()
+        .map(|c| ChangeRow {
+            change: match c.change {
+                Change::Added => c.change.to_string().green().to_string(),
+                Change::Removed => c.change.to_string().red().to_string(),
+                Change::Changed => c.change.to_string().yellow().to_string(),
+            },
+            kind: c.kind.clone(),
+            name: c.name.clone(),
+            field: c.field.clone().unwrap_or_else(|| "-".to_string()),
+            from: c.from.clone().unwrap_or_else(|| "-".to_string()),
+            to: c.to.clone().unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_report(report: &Report, format: &ReportFormat, output: &Output) -> Result<()> {
+    match format {
+        ReportFormat::Markdown => print!("{}", crate::report::to_markdown(report)),
+        ReportFormat::Html => print!("{}", crate::report::to_html(report)),
+        ReportFormat::Json => print_json(report, output)?,
+    }
+
+    Ok(())
+}
+
+pub fn print_tenant_report(
+    report: &TenantReport,
+    format: &ReportFormat,
+    output: &Output,
+) -> Result<()> {
+    match format {
+        ReportFormat::Markdown => print!("{}", crate::tenant::to_markdown(report)),
+        ReportFormat::Html => print!("{}", crate::tenant::to_html(report)),
+        ReportFormat::Json => print_json(report, output)?,
+    }
+
+    Ok(())
+}
+
+pub fn print_annotation_inventory(inventory: &[AnnotationKeyUsage], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_annotation_inventory_table(inventory),
+        OutputFormat::Csv => print_csv(&annotation_inventory_table(inventory), output)?,
+        _ => print_structured(inventory, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_annotation_inventory_table(inventory: &[AnnotationKeyUsage]) {
+    if inventory.is_empty() {
+        println!("No annotations found");
+        return;
+    }
+
+    println!("{}", annotation_inventory_table(inventory));
+}
+
+fn annotation_inventory_table(inventory: &[AnnotationKeyUsage]) -> Table {
+    #[derive(Tabled)]
+    struct AnnotationKeyRow {
+        #[tabled(rename = "KEY")]
+        key: String,
+        #[tabled(rename = "COUNT")]
+        count: usize,
+        #[tabled(rename = "TOTAL SIZE")]
+        total: String,
+        #[tabled(rename = "MAX SIZE")]
+        max: String,
+        #[tabled(rename = "EXAMPLES")]
+        examples: String,
+    }
+
+    let rows: Vec<AnnotationKeyRow> = inventory
+        .iter()
+        .map(|usage| AnnotationKeyRow {
+            key: usage.key.clone(),
+            count: usage.count,
+            total: format_bytes(usage.total_bytes as f64),
+            max: format_bytes(usage.max_bytes as f64),
+            examples: usage.examples.join(", "),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_annotation_matches(matches: &[AnnotationMatch], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_annotation_matches_table(matches),
+        OutputFormat::Csv => print_csv(&annotation_matches_table(matches), output)?,
+        _ => print_structured(matches, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_annotation_matches_table(matches: &[AnnotationMatch]) {
+    if matches.is_empty() {
+        println!("No matching resources found");
+        return;
+    }
+
+    println!("{}", annotation_matches_table(matches));
+}
+
+fn annotation_matches_table(matches: &[AnnotationMatch]) -> Table {
+    #[derive(Tabled)]
+    struct AnnotationMatchRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "KEY")]
+        key: String,
+        #[tabled(rename = "SIZE")]
+        size: String,
+        #[tabled(rename = "VALUE")]
+        value: String,
+    }
+
+    let rows: Vec<AnnotationMatchRow> = matches
+        .iter()
+        .map(|m| AnnotationMatchRow {
+            kind: m.kind.clone(),
+            namespace: m.namespace.clone().unwrap_or_else(|| "None".to_string()),
+            name: m.name.clone(),
+            key: m.key.clone(),
+            size: format_bytes(m.bytes as f64),
+            value: m.value.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print the values of a label key in the specified format
+pub fn print_label_values(values: &LabelValues, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_label_values_table(values),
+        OutputFormat::Csv => print_csv(&label_values_table(values), output)?,
+        _ => print_structured(values, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_label_values_table(values: &LabelValues) {
+    if values.values.is_empty() {
+        println!("No resources carry label {}", values.key);
+        return;
+    }
+
+    println!(
+        "{} {}: {} values on {} resources",
+        "Label".bold(),
+        values.key.bold(),
+        values.values.len(),
+        values.resources
+    );
+
+    println!("{}", label_values_table(values));
+
+    let similar: Vec<String> = values
+        .values
+        .iter()
+        .filter(|usage| !usage.similar.is_empty())
+        .map(|usage| format!("{:?}", usage.value))
+        .collect();
+    if !similar.is_empty() {
+        println!(
+            "\n{} values differ only in case or whitespace: {}",
+            "Warning:".yellow(),
+            similar.join(", ")
+        );
+    }
+}
+
+fn label_values_table(values: &LabelValues) -> Table {
+    #[derive(Tabled)]
+    struct LabelValueRow {
+        #[tabled(rename = "VALUE")]
+        value: String,
+        #[tabled(rename = "COUNT")]
+        count: usize,
+        #[tabled(rename = "KINDS")]
+        kinds: String,
+        #[tabled(rename = "EXAMPLES")]
+        examples: String,
+    }
+
+    let rows: Vec<LabelValueRow> = values
+        .values
+        .iter()
+        .map(|usage| LabelValueRow {
+            // Quote values so stray whitespace is visible
+            value: if usage.similar.is_empty() {
+                format!("{:?}", usage.value)
+            } else {
+                format!("{:?}", usage.value).yellow().to_string()
+            },
+            count: usage.count,
+            kinds: usage
+                .kinds
+                .iter()
+                .map(|(kind, count)| format!("{} {}", kind, count))
+                .collect::<Vec<_>>()
+                .join(", "),
+            examples: usage.examples.join(", "),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_stuck_objects(objects: &[StuckObject], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_stuck_objects_table(objects),
+        OutputFormat::Csv => print_csv(&stuck_objects_table(objects), output)?,
+        _ => print_structured(objects, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_stuck_objects_table(objects: &[StuckObject]) {
+    if objects.is_empty() {
+        println!("{}", "No stuck objects found".green());
+        return;
+    }
+
+    println!("{}", stuck_objects_table(objects));
+}
+
+fn stuck_objects_table(objects: &[StuckObject]) -> Table {
+    #[derive(Tabled)]
+    struct StuckRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "TERMINATING")]
+        terminating_for: String,
+        #[tabled(rename = "FINALIZERS")]
+        finalizers: String,
+        #[tabled(rename = "OWNERS")]
+        owners: String,
+    }
+
+    let rows: Vec<StuckRow> = objects
+        .iter()
+        .map(|o| StuckRow {
+            kind: o.kind.clone(),
+            namespace: o.namespace.clone().unwrap_or_else(|| "None".to_string()),
+            name: o.name.clone(),
+            terminating_for: o.terminating_for.clone(),
+            finalizers: o.finalizers.join(", "),
+            owners: if o.owners.is_empty() {
+                "None".to_string()
+            } else {
+                o.owners.join(", ")
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print namespaces with their object counts in the specified format
+pub fn print_namespaces(namespaces: &[NamespaceSummary], output: &Output) -> Result<()> {
+    if namespaces.is_empty() && says_none(output) {
+        println!("No namespaces found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_namespaces_table(namespaces),
+        OutputFormat::Csv => print_csv(&namespaces_table(namespaces), output)?,
+        _ => print_structured(&namespaces, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_namespaces_table(namespaces: &[NamespaceSummary]) {
+    println!("{}", namespaces_table(namespaces));
+}
+
+fn namespaces_table(namespaces: &[NamespaceSummary]) -> Table {
+    #[derive(Tabled)]
+    struct NamespaceRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "STATUS")]
+        status: String,
+        #[tabled(rename = "SERVICES")]
+        services: String,
+        #[tabled(rename = "PODS")]
+        pods: String,
+        #[tabled(rename = "QOS (G/B/BE)")]
+        qos: String,
+        #[tabled(rename = "DEPLOYMENTS")]
+        deployments: String,
+        #[tabled(rename = "CONFIGMAPS")]
+        configmaps: String,
+        #[tabled(rename = "SECRETS")]
+        secrets: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+        #[tabled(rename = "LABELS")]
+        labels: String,
+    }
+
+    // Forbidden kinds have no count
+    let count = |count: Option<usize>| count.map_or_else(|| "-".to_string(), |c| c.to_string());
+    let rows: Vec<NamespaceRow> = namespaces
+        .iter()
+        .map(|ns| NamespaceRow {
+            name: ns.name.clone(),
+            status: if ns.status == "Active" {
+                ns.status.clone()
+            } else {
+                ns.status.yellow().to_string()
+            },
+            services: count(ns.counts.services),
+            pods: count(ns.counts.pods),
+            qos: match (
+                ns.counts.guaranteed,
+                ns.counts.burstable,
+                ns.counts.best_effort,
+            ) {
+                (Some(guaranteed), Some(burstable), Some(best_effort)) => {
+                    format!("{}/{}/{}", guaranteed, burstable, best_effort)
+                }
+                _ => "-".to_string(),
+            },
+            deployments: count(ns.counts.deployments),
+            configmaps: count(ns.counts.configmaps),
+            secrets: count(ns.counts.secrets),
+            age: ns.age.clone(),
+            // Every namespace carries its own name as a label
+            labels: ns
+                .labels
+                .iter()
+                .filter(|(key, _)| *key != "kubernetes.io/metadata.name")
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(","),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+pub fn print_namespace_diagnosis(diagnosis: &NamespaceDiagnosis, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_namespace_diagnosis_table(diagnosis),
+        OutputFormat::Csv => print_csv(&namespace_diagnosis_table(diagnosis), output)?,
+        _ => print_structured(diagnosis, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_namespace_diagnosis_table(diagnosis: &NamespaceDiagnosis) {
+    let phase = if diagnosis.phase == "Terminating" {
+        diagnosis.phase.red()
+    } else {
+        diagnosis.phase.green()
+    };
+    println!("{} {}", "Namespace:".bold(), diagnosis.name.cyan());
+    println!("{} {}", "Phase:".bold(), phase);
+    if let Some(duration) = &diagnosis.terminating_for {
+        println!("{} {}", "Terminating for:".bold(), duration);
+    }
+    if !diagnosis.finalizers.is_empty() {
+        println!(
+            "{} {}",
+            "Finalizers:".bold(),
+            diagnosis.finalizers.join(", ")
+        );
+    }
+
+    if !diagnosis.conditions.is_empty() {
+        println!("\n{}", "Conditions:".bold());
+        for condition in &diagnosis.conditions {
+            println!(
+                "  {} ({}): {}",
+                condition.condition_type.yellow(),
+                condition.reason.as_deref().unwrap_or("None"),
+                condition.message.as_deref().unwrap_or("")
+            );
+        }
+    }
+
+    if !diagnosis.unavailable_api_services.is_empty() {
+        println!("\n{}", "Unavailable API services:".bold());
+        for service in &diagnosis.unavailable_api_services {
+            println!(
+                "  {} -> {} ({}): {}",
+                service.name.red(),
+                service.service.as_deref().unwrap_or("local"),
+                service.reason.as_deref().unwrap_or("None"),
+                service.message.as_deref().unwrap_or("")
+            );
+        }
+    }
+
+    if !diagnosis.discovery_failures.is_empty() {
+        println!("\n{}", "Discovery failures:".bold());
+        for failure in &diagnosis.discovery_failures {
+            println!("  {}: {}", failure.group_version.red(), failure.error);
+        }
+    }
+
+    println!();
+    if diagnosis.remaining.is_empty() {
+        println!("{}", "No remaining resources found".green());
+        return;
+    }
+
+    println!("{}", "Remaining resources:".bold());
+    println!("{}", remaining_resources_table(diagnosis));
+}
+
+/// The namespace's fields, what blocks its deletion, then the resources
+/// left in it
+fn namespace_diagnosis_table(diagnosis: &NamespaceDiagnosis) -> Table {
+    #[derive(Tabled)]
+    struct ConditionRow {
+        #[tabled(rename = "TYPE")]
+        condition_type: String,
+        #[tabled(rename = "REASON")]
+        reason: String,
+        #[tabled(rename = "MESSAGE")]
+        message: String,
+    }
+
+    #[derive(Tabled)]
+    struct ApiServiceRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "SERVICE")]
+        service: String,
+        #[tabled(rename = "REASON")]
+        reason: String,
+        #[tabled(rename = "MESSAGE")]
+        message: String,
+    }
+
+    #[derive(Tabled)]
+    struct DiscoveryFailureRow {
+        #[tabled(rename = "GROUP VERSION")]
+        group_version: String,
+        #[tabled(rename = "MESSAGE")]
+        error: String,
+    }
+
+    let fields = vec![
+        ("Namespace".to_string(), diagnosis.name.clone()),
+        ("Phase".to_string(), diagnosis.phase.clone()),
+        (
+            "Terminating for".to_string(),
+            diagnosis.terminating_for.clone().unwrap_or_default(),
+        ),
+        ("Finalizers".to_string(), diagnosis.finalizers.join(", ")),
+    ];
+    let conditions = diagnosis.conditions.iter().map(|c| ConditionRow {
+        condition_type: c.condition_type.clone(),
+        reason: c.reason.clone().unwrap_or_default(),
+        message: c.message.clone().unwrap_or_default(),
+    });
+    let api_services = diagnosis
+        .unavailable_api_services
+        .iter()
+        .map(|service| ApiServiceRow {
+            name: service.name.clone(),
+            service: service
+                .service
+                .clone()
+                .unwrap_or_else(|| "local".to_string()),
+            reason: service.reason.clone().unwrap_or_default(),
+            message: service.message.clone().unwrap_or_default(),
+        });
+    let failures = diagnosis
+        .discovery_failures
+        .iter()
+        .map(|failure| DiscoveryFailureRow {
+            group_version: failure.group_version.clone(),
+            error: failure.error.clone(),
+        });
+    sections_table(
+        "SECTION",
+        vec![
+            ("Namespace".to_string(), fields_table(fields)),
+            ("Conditions".to_string(), Table::new(conditions)),
+            (
+                "Unavailable API services".to_string(),
+                Table::new(api_services),
+            ),
+            ("Discovery failures".to_string(), Table::new(failures)),
+            (
+                "Remaining".to_string(),
+                remaining_resources_table(diagnosis),
+            ),
+        ],
+    )
+}
+
+fn remaining_resources_table(diagnosis: &NamespaceDiagnosis) -> Table {
+    #[derive(Tabled)]
+    struct RemainingRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "API VERSION")]
+        api_version: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "TERMINATING")]
+        terminating: String,
+        #[tabled(rename = "FINALIZERS")]
+        finalizers: String,
+    }
+
+    let rows: Vec<RemainingRow> = diagnosis
+        .remaining
+        .iter()
+        .map(|o| RemainingRow {
+            kind: o.kind.clone(),
+            api_version: o.api_version.clone(),
+            name: o.name.clone(),
+            terminating: if o.terminating { "Yes" } else { "No" }.to_string(),
+            finalizers: if o.finalizers.is_empty() {
+                "None".to_string()
+            } else {
+                o.finalizers.join(", ")
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_lint_findings(findings: &[LintFinding], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_lint_findings_table(findings),
+        OutputFormat::Csv => print_csv(&lint_findings_table(findings), output)?,
+        _ => print_structured(findings, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_lint_findings_table(findings: &[LintFinding]) {
+    if findings.is_empty() {
+        println!("{}", "No lint findings".green());
+        return;
+    }
+
+    println!("{}", lint_findings_table(findings));
+}
+
+fn lint_findings_table(findings: &[LintFinding]) -> Table {
+    #[derive(Tabled)]
+    struct LintFindingRow {
+        #[tabled(rename = "SEVERITY")]
+        severity: String,
+        #[tabled(rename = "RULE")]
+        rule: String,
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "PATH")]
+        path: String,
+        #[tabled(rename = "MESSAGE")]
+        message: String,
+    }
+
+    let rows: Vec<LintFindingRow> = findings
+        .iter()
+        .map(|f| LintFindingRow {
+            severity: match f.severity {
+                Severity::Error => f.severity.to_string().red().to_string(),
+                Severity::Warning => f.severity.to_string().yellow().to_string(),
+                Severity::Info => f.severity.to_string(),
+            },
+            rule: f.rule.clone(),
+            kind: f.kind.clone(),
+            namespace: f.namespace.clone().unwrap_or_else(|| "None".to_string()),
+            name: f.name.clone(),
+            path: f.path.clone(),
+            message: f.message.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_lint_rules(rules: &[Rule], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_lint_rules_table(rules),
+        OutputFormat::Csv => print_csv(&lint_rules_table(rules), output)?,
+        _ => print_structured(rules, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_lint_rules_table(rules: &[Rule]) {
+    if rules.is_empty() {
+        println!("No lint rules enabled");
+        return;
+    }
+
+    println!("{}", lint_rules_table(rules));
+}
+
+fn lint_rules_table(rules: &[Rule]) -> Table {
+    #[derive(Tabled)]
+    struct LintRuleRow {
+        #[tabled(rename = "RULE")]
+        id: String,
+        #[tabled(rename = "SEVERITY")]
+        severity: String,
+        #[tabled(rename = "KINDS")]
+        kinds: String,
+        #[tabled(rename = "CHECK")]
+        check: String,
+        #[tabled(rename = "MESSAGE")]
+        message: String,
+    }
+
+    let rows: Vec<LintRuleRow> = rules
+        .iter()
+        .map(|r| LintRuleRow {
+            id: r.id.clone(),
+            severity: r.severity.to_string(),
+            kinds: if r.kinds.is_empty() {
+                "workloads".to_string()
+            } else {
+                r.kinds.join(", ")
+            },
+            check: match &r.value {
+                Some(value) => format!("{} {:?} {}", r.path, r.operator, value),
+                None => format!("{} {:?}", r.path, r.operator),
+            },
+            message: r.message.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_scorecard(card: &Scorecard, breakdown: bool, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_scorecard_table(card, breakdown),
+        OutputFormat::Csv => print_csv(&scorecard_table(card, breakdown), output)?,
+        _ => print_structured(card, output)?,
+    }
+
+    Ok(())
+}
+
+fn colored_score(score: u32) -> String {
+    let text = score.to_string();
+    match score {
+        80.. => text.green().to_string(),
+        50..=79 => text.yellow().to_string(),
+        _ => text.red().to_string(),
+    }
+}
+
+fn print_scorecard_table(card: &Scorecard, breakdown: bool) {
+    if breakdown {
+        for workload in &card.workloads {
+            println!("{}", checks_table(&workload.checks));
+            println!(
+                "\n{}/{}: {}/100",
+                workload.kind,
+                workload.name,
+                colored_score(workload.score)
+            );
+        }
+        return;
+    }
+
+    if card.workloads.is_empty() {
+        println!("No workloads found in namespace {}", card.namespace);
+        return;
+    }
+
+    println!("{}", scorecard_table(card, false));
+    println!(
+        "\nNamespace {}: {}/100 across {} workloads",
+        card.namespace,
+        colored_score(card.score),
+        card.workloads.len()
+    );
+}
+
+/// A row per workload, or with `breakdown` a row per check of each
+/// workload, the workload in a WORKLOAD column
+fn scorecard_table(card: &Scorecard, breakdown: bool) -> Table {
+    if breakdown {
+        return items_table(
+            "WORKLOAD",
+            checks_table(&[]),
+            card.workloads
+                .iter()
+                .map(|w| (format!("{}/{}", w.kind, w.name), checks_table(&w.checks))),
+        );
+    }
+
+    #[derive(Tabled)]
+    struct WorkloadRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "SCORE")]
+        score: String,
+        #[tabled(rename = "LINT")]
+        lint: String,
+        #[tabled(rename = "PDB")]
+        pdb: String,
+        #[tabled(rename = "PROBES")]
+        probes: String,
+        #[tabled(rename = "SPREAD")]
+        spread: String,
+        #[tabled(rename = "RESOURCES")]
+        resources: String,
+        #[tabled(rename = "MONITORING")]
+        monitoring: String,
+    }
+
+    let rows: Vec<WorkloadRow> = card
+        .workloads
+        .iter()
+        .map(|w| {
+            let cell = |check: Check| match w.check(check) {
+                Some(c) => format!("{:.0}%", c.score * 100.0),
+                None => "-".to_string(),
+            };
+            WorkloadRow {
+                kind: w.kind.clone(),
+                name: w.name.clone(),
+                score: colored_score(w.score),
+                lint: cell(Check::Lint),
+                pdb: cell(Check::Pdb),
+                probes: cell(Check::Probes),
+                spread: cell(Check::Spread),
+                resources: cell(Check::Resources),
+                monitoring: cell(Check::Monitoring),
+            }
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn checks_table(checks: &[CheckScore]) -> Table {
+    #[derive(Tabled)]
+    struct CheckRow {
+        #[tabled(rename = "CHECK")]
+        check: String,
+        #[tabled(rename = "WEIGHT")]
+        weight: u32,
+        #[tabled(rename = "SCORE")]
+        score: String,
+        #[tabled(rename = "DETAIL")]
+        detail: String,
+    }
+
+    let rows: Vec<CheckRow> = checks
+        .iter()
+        .map(|c| CheckRow {
+            check: c.check.to_string(),
+            weight: c.weight,
+            score: colored_score((c.score * 100.0).round() as u32),
+            detail: c.detail.clone(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print the config read from `path` in the file's own format
+pub fn print_config(config: &Config, path: &std::path::Path, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => {
+            let state = if path.exists() { "" } else { " (not found)" };
+            println!("{}", format!("# {}{}", path.display(), state).dimmed());
+            print!("{}", config.render(path)?);
+        }
+        OutputFormat::Csv => print_csv(&config_table(config)?, output)?,
+        _ => print_structured(config, output)?,
+    }
+
+    Ok(())
+}
+
+/// The settings as dotted keys, e.g. `cache.ttl.pods`, with their values
+fn config_table(config: &Config) -> Result<Table> {
+    fn flatten(key: String, value: &serde_json::Value, fields: &mut Vec<(String, String)>) {
+        match value {
+            serde_json::Value::Null => {}
+            serde_json::Value::Object(map) => {
+                for (name, value) in map {
+                    let key = if key.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{}.{}", key, name)
+                    };
+                    flatten(key, value, fields);
+                }
+            }
+            serde_json::Value::String(text) => fields.push((key, text.clone())),
+            value => fields.push((key, value.to_string())),
+        }
+    }
+
+    let mut fields = Vec::new();
+    flatten(String::new(), &serde_json::to_value(config)?, &mut fields);
+    Ok(fields_table(fields))
+}
+
+/// Print the run summary to stderr so it never mixes with command output
+pub fn print_run_stats(summary: &StatsSummary, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Json => eprintln!("{}", serialize_json(summary, output.json_compact)?),
+        OutputFormat::Yaml => eprint!("{}", serde_yaml::to_string(summary)?),
+        _ => {
+            eprintln!("\n{}", "Run statistics".bold());
+            if let Some(command) = &summary.command {
+                eprintln!("  Command:            {}", command);
+            }
+            eprintln!(
+                "  API requests:       {} ({:.1}/s)",
+                summary.api_requests, summary.requests_per_second
+            );
+            for resource in &summary.resources {
+                eprintln!("    {:<30} {}", resource.resource, resource.requests);
+            }
+            eprintln!(
+                "  Bytes received:     {}",
+                format_bytes(summary.bytes_received as f64)
+            );
+            eprintln!(
+                "  Cache hits/misses:  {}/{}",
+                summary.cache_hits, summary.cache_misses
+            );
+            eprintln!("  Namespaces scanned: {}", summary.namespaces_scanned);
+            for phase in &summary.phases {
+                eprintln!("  {:<19} {}ms", format!("{}:", phase.phase), phase.millis);
+            }
+            eprintln!("  {:<19} {}ms", "total:", summary.total_millis);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_snapshot_list(times: &[DateTime<Utc>], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_snapshot_list_table(times),
+        OutputFormat::Csv => print_csv(&snapshot_list_table(times), output)?,
+        _ => print_structured(times, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_snapshot_list_table(times: &[DateTime<Utc>]) {
+    if times.is_empty() {
+        println!("No snapshots recorded");
+        return;
+    }
+
+    println!("{}", snapshot_list_table(times));
+}
+
+fn snapshot_list_table(times: &[DateTime<Utc>]) -> Table {
+    #[derive(Tabled)]
+    struct SnapshotRow {
+        #[tabled(rename = "TAKEN AT")]
+        taken_at: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<SnapshotRow> = times
+        .iter()
+        .map(|t| SnapshotRow {
+            taken_at: t.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            age: format_age(Some(*t)),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print instances of every CRD: a count per CRD, then the instances of each
+/// CRD that has any
+pub fn print_crd_instances(crds: &[CrdInstances], output: &Output) -> Result<()> {
+    if crds.is_empty() && says_none(output) {
+        println!("No CRDs found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_crd_instances_table(crds),
+        OutputFormat::Csv => print_csv(&crd_instances_table(crds), output)?,
+        _ => print_structured(&crds, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_crd_instances_table(crds: &[CrdInstances]) {
+    println!("{}", crd_counts_table(crds));
+
+    for crd in crds.iter().filter(|crd| crd.instance_count > 0) {
+        println!(
+            "\n=== {} ({} instances) ===",
+            crd.crd_name.cyan(),
+            crd.instance_count
+        );
+        print_custom_resources_table(&crd.items);
+        let hidden = crd.instance_count - crd.items.len();
+        if hidden > 0 {
+            println!(
+                "... {} more (kdx custom-resources {} to list all)",
+                hidden, crd.crd_name
+            );
+        }
+    }
+
+    let total: usize = crds.iter().map(|crd| crd.instance_count).sum();
+    println!("\nTotal: {} instances across {} CRDs", total, crds.len());
+}
+
+/// The count of each CRD, then the instances listed for each
+fn crd_instances_table(crds: &[CrdInstances]) -> Table {
+    let instances = items_table(
+        "CRD",
+        custom_resources_table(&[]),
+        crds.iter()
+            .map(|crd| (crd.crd_name.clone(), custom_resources_table(&crd.items))),
+    );
+    sections_table(
+        "SECTION",
+        vec![
+            ("Counts".to_string(), crd_counts_table(crds)),
+            ("Instances".to_string(), instances),
+        ],
+    )
+}
+
+fn crd_counts_table(crds: &[CrdInstances]) -> Table {
+    #[derive(Tabled)]
+    struct CrdCountRow {
+        #[tabled(rename = "CRD")]
+        crd: String,
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "SCOPE")]
+        scope: String,
+        #[tabled(rename = "INSTANCES")]
+        instances: usize,
+    }
+
+    let rows: Vec<CrdCountRow> = crds
+        .iter()
+        .map(|crd| CrdCountRow {
+            crd: crd.crd_name.clone(),
+            kind: crd.kind.clone(),
+            scope: crd.scope.clone(),
+            instances: crd.instance_count,
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print CRDs with objects that may still be stored at old versions
+pub fn print_storage_migrations(migrations: &[StorageMigration], output: &Output) -> Result<()> {
+    if migrations.is_empty() && says_none(output) {
+        println!("{}", "No CRDs need storage migration".green());
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_storage_migrations_table(migrations),
+        OutputFormat::Csv => print_csv(&storage_migrations_table(migrations), output)?,
+        _ => print_structured(&migrations, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_storage_migrations_table(migrations: &[StorageMigration]) {
+    println!("{}", storage_migrations_table(migrations));
+
+    let blocking = migrations.iter().filter(|m| m.blocking()).count();
+    if blocking > 0 {
+        println!(
+            "\n{} {} CRDs still record versions that are no longer served",
+            "Warning:".red().bold(),
+            blocking
+        );
+    }
+    println!(
+        "Rewrite the listed objects (e.g. with kube-storage-version-migrator or a no-op update), \
+         then remove the old versions from status.storedVersions before dropping them from the CRD."
+    );
+}
+
+fn storage_migrations_table(migrations: &[StorageMigration]) -> Table {
+    #[derive(Tabled)]
+    struct MigrationRow {
+        #[tabled(rename = "CRD")]
+        crd: String,
+        #[tabled(rename = "STORAGE")]
+        storage: String,
+        #[tabled(rename = "OLD STORED VERSIONS")]
+        stale: String,
+        #[tabled(rename = "INSTANCES")]
+        instances: String,
+        #[tabled(rename = "POSSIBLY AT OLD VERSION")]
+        possibly_stale: String,
+    }
+
+    let unknown = || "Unknown".to_string();
+    let rows: Vec<MigrationRow> = migrations
+        .iter()
+        .map(|m| MigrationRow {
+            crd: m.crd_name.clone(),
+            storage: m.storage_version.clone(),
+            stale: m
+                .stale_versions
+                .iter()
+                .map(|v| {
+                    let state = match (v.served, v.deprecated) {
+                        (false, _) => "not served".red(),
+                        (true, true) => "deprecated".yellow(),
+                        (true, false) => "served".normal(),
+                    };
+                    format!("{} ({})", v.version, state)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            instances: m.instances.map_or_else(unknown, |n| n.to_string()),
+            possibly_stale: m.possibly_stale.map_or_else(unknown, |n| n.to_string()),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_warm_summary(summary: &WarmSummary, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_warm_summary_table(summary),
+        OutputFormat::Csv => print_csv(&warm_summary_table(summary), output)?,
+        _ => print_structured(summary, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_warm_summary_table(summary: &WarmSummary) {
+    if summary.namespaces.is_empty() {
+        println!("No namespaces to warm");
+        return;
+    }
+
+    println!("{}", warm_summary_table(summary));
+
+    let failures = summary.failures();
+    if failures > 0 {
+        println!(
+            "\n{} {} resource loads failed:",
+            "Warning:".red().bold(),
+            failures
+        );
+        for warmup in &summary.namespaces {
+            let namespace = warmup.namespace.as_deref().unwrap_or("(cluster)");
+            for failure in &warmup.failures {
+                println!("  {}/{}: {}", namespace, failure.resource, failure.error);
+            }
+        }
+    }
+    println!(
+        "Cache warmed: {} items in {} namespaces in {}ms",
+        summary.items(),
+        summary
+            .namespaces
+            .iter()
+            .filter(|n| n.namespace.is_some())
+            .count(),
+        summary.total_millis
+    );
+    if let Some(selector) = &summary.selector {
+        println!("Services and pods cached for selector '{}'", selector);
+    }
+}
+
+fn warm_summary_table(summary: &WarmSummary) -> Table {
+    #[derive(Tabled)]
+    struct WarmRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "RESOURCES")]
+        resources: usize,
+        #[tabled(rename = "ITEMS")]
+        items: usize,
+        #[tabled(rename = "DURATION")]
+        duration: String,
+        #[tabled(rename = "FAILED")]
+        failed: String,
+    }
+
+    let rows: Vec<WarmRow> = summary
+        .namespaces
+        .iter()
+        .map(|n| WarmRow {
+            namespace: n
+                .namespace
+                .clone()
+                .unwrap_or_else(|| "(cluster)".to_string()),
+            resources: n.resources,
+            items: n.items,
+            duration: format!("{}ms", n.millis),
+            failed: if n.failures.is_empty() {
+                "None".to_string()
+            } else {
+                n.failures
+                    .iter()
+                    .map(|f| f.resource.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .red()
+                    .to_string()
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+pub fn print_stability(workloads: &[WorkloadStability], output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Table => print_stability_table(workloads),
+        OutputFormat::Csv => print_csv(&stability_table(workloads), output)?,
+        _ => print_structured(&workloads, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_stability_table(workloads: &[WorkloadStability]) {
+    if workloads.is_empty() {
+        println!("No workloads found");
+        return;
+    }
+
+    println!("{}", stability_table(workloads));
+}
+
+fn stability_table(workloads: &[WorkloadStability]) -> Table {
+    #[derive(Tabled)]
+    struct StabilityRow {
+        #[tabled(rename = "SCORE")]
+        score: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "WORKLOAD")]
+        workload: String,
+        #[tabled(rename = "PODS")]
+        pods: usize,
+        #[tabled(rename = "RESTARTS")]
+        restarts: u32,
+        #[tabled(rename = "FLAPS")]
+        flaps: u32,
+        #[tabled(rename = "POD AGE")]
+        pod_age: String,
+        #[tabled(rename = "WORKLOAD AGE")]
+        workload_age: String,
+        #[tabled(rename = "REASONS")]
+        reasons: String,
+    }
+
+    let rows: Vec<StabilityRow> = workloads
+        .iter()
+        .map(|w| StabilityRow {
+            score: match w.score {
+                s if s >= 50.0 => format!("{:.1}", s).red().to_string(),
+                s if s >= 20.0 => format!("{:.1}", s).yellow().to_string(),
+                s => format!("{:.1}", s),
+            },
+            namespace: w.namespace.clone(),
+            workload: format!("{}/{}", w.kind.to_lowercase(), w.name),
+            pods: w.pods,
+            restarts: w.restarts,
+            flaps: w.readiness_flaps,
+            pod_age: w
+                .median_pod_age
+                .clone()
+                .unwrap_or_else(|| "None".to_string()),
+            workload_age: w.workload_age.clone().unwrap_or_else(|| "None".to_string()),
+            reasons: if w.reasons.is_empty() {
+                "None".to_string()
+            } else {
+                w.reasons.join("; ")
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print replica changes in the specified format
+pub fn print_scaling_events(changes: &[ScalingEvent], output: &Output) -> Result<()> {
+    if changes.is_empty() && says_none(output) {
+        println!("No scaling events found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", scaling_events_table(changes)),
+        OutputFormat::Csv => print_csv(&scaling_events_table(changes), output)?,
+        _ => print_structured(&changes, output)?,
+    }
+
+    Ok(())
+}
+
+fn scaling_events_table(changes: &[ScalingEvent]) -> Table {
+    #[derive(Tabled)]
+    struct ScalingRow {
+        #[tabled(rename = "TIME")]
+        time: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "WORKLOAD")]
+        workload: String,
+        #[tabled(rename = "REPLICAS")]
+        replicas: String,
+        #[tabled(rename = "SOURCE")]
+        source: String,
+        #[tabled(rename = "REASON")]
+        reason: String,
+    }
+
+    let rows: Vec<ScalingRow> = changes
+        .iter()
+        .map(|c| ScalingRow {
+            time: c
+                .time
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            namespace: c.namespace.clone(),
+            workload: c.workload(),
+            replicas: match c.from {
+                Some(from) if from < c.to => format!("{} -> {}", from, c.to).green().to_string(),
+                Some(from) => format!("{} -> {}", from, c.to).yellow().to_string(),
+                None => format!("-> {}", c.to),
+            },
+            source: match c.source {
+                ScalingSource::Manual => c.source.to_string().cyan().to_string(),
+                ScalingSource::Autoscaler => c.source.to_string(),
+            },
+            reason: if c.count > 1 {
+                format!("{} (x{})", c.reason, c.count)
+            } else {
+                c.reason.clone()
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print objects drifting from their namespace's kustomize labels in the
+/// specified format
+pub fn print_kustomize_drift(drift: &[KustomizeDrift], output: &Output) -> Result<()> {
+    if drift.is_empty() && says_none(output) {
+        println!("No objects missing kustomize labels found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", kustomize_drift_table(drift)),
+        OutputFormat::Csv => print_csv(&kustomize_drift_table(drift), output)?,
+        _ => print_structured(&drift, output)?,
+    }
+
+    Ok(())
+}
+
+fn kustomize_drift_table(drift: &[KustomizeDrift]) -> Table {
+    #[derive(Tabled)]
+    struct DriftRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "KUSTOMIZATION")]
+        kustomization: String,
+        #[tabled(rename = "MISSING LABELS")]
+        missing: String,
+    }
+
+    let rows: Vec<DriftRow> = drift
+        .iter()
+        .map(|d| DriftRow {
+            namespace: d.namespace.clone(),
+            kind: d.kind.clone(),
+            name: d.name.clone(),
+            kustomization: d
+                .kustomization
+                .clone()
+                .unwrap_or_else(|| "none".yellow().to_string()),
+            missing: d.missing.join("\n"),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print the results of `kdx probe-matrix` in the specified format
+pub fn print_probe_matrix(results: &[ProbeResult], output: &Output) -> Result<()> {
+    if results.is_empty() && says_none(output) {
+        println!("No TCP ports to probe");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_probe_matrix_table(results),
+        OutputFormat::Csv => print_csv(&probe_matrix_table(results), output)?,
+        _ => print_structured(&results, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_probe_matrix_table(results: &[ProbeResult]) {
+    println!("{}", probe_matrix_table(results));
+
+    let ok = results
+        .iter()
+        .filter(|r| r.status == ProbeStatus::Ok)
+        .count();
+    println!("\n{} of {} probes succeeded", ok, results.len());
+}
+
+fn probe_matrix_table(results: &[ProbeResult]) -> Table {
+    #[derive(Tabled)]
+    struct ProbeRow {
+        #[tabled(rename = "SERVICE")]
+        service: String,
+        #[tabled(rename = "PORT")]
+        port: String,
+        #[tabled(rename = "POD")]
+        pod: String,
+        #[tabled(rename = "STATUS")]
+        status: String,
+        #[tabled(rename = "LATENCY")]
+        latency: String,
+        #[tabled(rename = "HTTP")]
+        http: String,
+        #[tabled(rename = "ERROR")]
+        error: String,
+    }
+
+    let rows: Vec<ProbeRow> = results
+        .iter()
+        .map(|r| ProbeRow {
+            service: r.service.clone(),
+            port: r.port.map_or_else(|| "-".to_string(), |p| p.to_string()),
+            pod: r.pod.clone().unwrap_or_else(|| "-".to_string()),
+            status: match r.status {
+                ProbeStatus::Ok => r.status.to_string().green().to_string(),
+                ProbeStatus::Timeout => r.status.to_string().yellow().to_string(),
+                ProbeStatus::Failed | ProbeStatus::NoPods => r.status.to_string().red().to_string(),
+            },
+            latency: r
+                .latency_ms
+                .map_or_else(|| "-".to_string(), |ms| format!("{}ms", ms)),
+            http: r
+                .http_status
+                .map_or_else(|| "-".to_string(), |s| s.to_string()),
+            error: r.error.clone().unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print the result of `kdx simulate` in the specified format
+pub fn print_simulation(simulation: &Simulation, output: &Output) -> Result<()> {
+    if simulation.workloads.is_empty() && says_none(output) {
+        println!("No workloads found in the manifests");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_simulation_table(simulation),
+        OutputFormat::Csv => print_csv(&simulation_table(simulation), output)?,
+        _ => print_structured(simulation, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_simulation_table(simulation: &Simulation) {
+    println!("{}", simulated_workloads_table(simulation));
+    println!(
+        "\nNew requests: {} CPU, {} memory",
+        format_cpu(simulation.total.cpu),
+        format_bytes(simulation.total.memory)
+    );
+    if !simulation.against_cluster {
+        return;
+    }
+
+    if simulation.nodes.iter().any(|n| n.new_pods > 0) {
+        println!("\nNode Headroom:\n{}", node_headroom_table(simulation));
+    }
+    if !simulation.quotas.is_empty() {
+        println!("\nResource Quotas:\n{}", quota_fit_table(simulation));
+    }
+
+    let unplaced = simulation.unplaced();
+    if simulation.fits() {
+        println!("\n{}", "All new pods fit".green());
+    } else if unplaced > 0 {
+        println!(
+            "\n{}",
+            format!("{} new pods do not fit on any node", unplaced).red()
+        );
+    } else {
+        println!("\n{}", "The new pods exceed a resource quota".red());
+    }
+}
+
+/// The simulated workloads, the headroom of the nodes the new pods land
+/// on and the quotas they count against
+fn simulation_table(simulation: &Simulation) -> Table {
+    sections_table(
+        "SECTION",
+        vec![
+            (
+                "Workloads".to_string(),
+                simulated_workloads_table(simulation),
+            ),
+            ("Node Headroom".to_string(), node_headroom_table(simulation)),
+            ("Resource Quotas".to_string(), quota_fit_table(simulation)),
+        ],
+    )
+}
+
+fn simulated_workloads_table(simulation: &Simulation) -> Table {
+    #[derive(Tabled)]
+    struct WorkloadRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "PODS")]
+        pods: String,
+        #[tabled(rename = "CPU/POD")]
+        cpu: String,
+        #[tabled(rename = "MEMORY/POD")]
+        memory: String,
+        #[tabled(rename = "PLACED")]
+        placed: String,
+    }
+
+    let rows: Vec<WorkloadRow> = simulation
+        .workloads
+        .iter()
+        .map(|fit| {
+            let w = &fit.workload;
+            WorkloadRow {
+                namespace: w.namespace.clone(),
+                kind: w.kind.clone(),
+                name: w.name.clone(),
+                pods: if w.per_node && !simulation.against_cluster {
+                    "per node".to_string()
+                } else {
+                    w.replicas.to_string()
+                },
+                cpu: format_cpu(w.requests.cpu),
+                memory: format_bytes(w.requests.memory),
+                placed: match (fit.placed, &fit.reason) {
+                    (None, _) => "-".to_string(),
+                    (Some(placed), None) => {
+                        format!("{}/{}", placed, w.replicas).green().to_string()
+                    }
+                    (Some(placed), Some(reason)) => {
+                        format!("{}/{} ({})", placed, w.replicas, reason)
+                            .red()
+                            .to_string()
+                    }
+                },
+            }
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Free capacity before -> after, for the nodes the new pods land on
+fn node_headroom_table(simulation: &Simulation) -> Table {
+    #[derive(Tabled)]
+    struct NodeRow {
+        #[tabled(rename = "NODE")]
+        name: String,
+        #[tabled(rename = "NEW PODS")]
+        new_pods: u32,
+        #[tabled(rename = "CPU FREE")]
+        cpu: String,
+        #[tabled(rename = "MEMORY FREE")]
+        memory: String,
+    }
+
+    let rows: Vec<NodeRow> = simulation
+        .nodes
+        .iter()
+        .filter(|n| n.new_pods > 0)
+        .map(|n| NodeRow {
+            name: n.name.clone(),
+            new_pods: n.new_pods,
+            cpu: format!(
+                "{} -> {}",
+                format_cpu(n.free_before.cpu),
+                format_cpu(n.free_after.cpu)
+            ),
+            memory: format!(
+                "{} -> {}",
+                format_bytes(n.free_before.memory),
+                format_bytes(n.free_after.memory)
+            ),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+fn quota_fit_table(simulation: &Simulation) -> Table {
+    #[derive(Tabled)]
+    struct QuotaRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "QUOTA")]
+        quota: String,
+        #[tabled(rename = "RESOURCE")]
+        resource: String,
+        #[tabled(rename = "USED")]
+        used: String,
+        #[tabled(rename = "ADDING")]
+        adding: String,
+        #[tabled(rename = "HARD")]
+        hard: String,
+        #[tabled(rename = "FITS")]
+        fits: String,
+    }
+
+    let amount = |resource: &str, value: f64| {
+        if resource.ends_with("cpu") {
+            format_cpu(value)
+        } else if resource.ends_with("memory") {
+            format_bytes(value)
+        } else {
+            value.to_string()
+        }
+    };
+    let rows: Vec<QuotaRow> = simulation
+        .quotas
+        .iter()
+        .map(|q| QuotaRow {
+            namespace: q.namespace.clone(),
+            quota: q.quota.clone(),
+            resource: q.resource.clone(),
+            used: amount(&q.resource, q.used),
+            adding: amount(&q.resource, q.adding),
+            hard: amount(&q.resource, q.hard),
+            fits: if q.fits() {
+                "yes".green().to_string()
+            } else {
+                "no".red().to_string()
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print OOMKills and evictions in the specified format
+pub fn print_oom_kills(summaries: &[OomKillSummary], output: &Output) -> Result<()> {
+    if summaries.is_empty() && says_none(output) {
+        println!("No OOMKills or evictions found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", oom_kills_table(summaries)),
+        OutputFormat::Csv => print_csv(&oom_kills_table(summaries), output)?,
+        _ => print_structured(&summaries, output)?,
+    }
+
+    Ok(())
+}
+
+fn oom_kills_table(summaries: &[OomKillSummary]) -> Table {
+    #[derive(Tabled)]
+    struct OomKillRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "WORKLOAD")]
+        workload: String,
+        #[tabled(rename = "CONTAINER")]
+        container: String,
+        #[tabled(rename = "OOMKILLS")]
+        oom_kills: usize,
+        #[tabled(rename = "EVICTIONS")]
+        evictions: usize,
+        #[tabled(rename = "LAST")]
+        last: String,
+        #[tabled(rename = "REQUEST")]
+        request: String,
+        #[tabled(rename = "LIMIT")]
+        limit: String,
+        #[tabled(rename = "USAGE")]
+        usage: String,
+        #[tabled(rename = "NODES")]
+        nodes: String,
+    }
+
+    let bytes = |b: Option<f64>| b.map(format_bytes).unwrap_or_else(|| "-".to_string());
+    let rows: Vec<OomKillRow> = summaries
+        .iter()
+        .map(|s| OomKillRow {
+            namespace: s.namespace.clone(),
+            workload: format!("{}/{}", s.kind.to_lowercase(), s.name),
+            container: s.container.clone().unwrap_or_else(|| "-".to_string()),
+            oom_kills: s.oom_kills,
+            evictions: s.evictions,
+            last: format_age(s.last_seen),
+            request: bytes(s.memory_request),
+            limit: bytes(s.memory_limit),
+            // Usage close to the limit means the next kill is near
+            usage: match (s.memory_usage, s.memory_limit) {
+                (Some(usage), Some(limit)) if limit > 0.0 => {
+                    let text = format!("{} ({:.0}%)", format_bytes(usage), usage / limit * 100.0);
+                    if usage / limit >= 0.9 {
+                        text.red().to_string()
+                    } else {
+                        text
+                    }
+                }
+                (usage, _) => bytes(usage),
+            },
+            nodes: s
+                .nodes
+                .iter()
+                .map(|node| {
+                    if s.memory_pressure_nodes.contains(node) {
+                        format!("{} (memory pressure)", node).yellow().to_string()
+                    } else {
+                        node.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Up to three items joined, with a count of the rest
+fn summarize_list<T: std::fmt::Display>(items: &[T]) -> String {
+    if items.is_empty() {
+        return "-".to_string();
+    }
+    let shown: Vec<String> = items.iter().take(3).map(T::to_string).collect();
+    match items.len() {
+        n if n > 3 => format!("{} +{} more", shown.join(", "), n - 3),
+        _ => shown.join(", "),
+    }
+}
+
+/// Print service accounts in the specified format
+pub fn print_service_accounts(accounts: &[ServiceAccountInfo], output: &Output) -> Result<()> {
+    if accounts.is_empty() && says_none(output) {
+        println!("No service accounts found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_service_accounts_table(accounts),
+        OutputFormat::Csv => print_csv(&service_accounts_table(accounts), output)?,
+        _ => print_structured(&accounts, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_service_accounts_table(accounts: &[ServiceAccountInfo]) {
+    println!("{}", service_accounts_table(accounts));
+}
+
+fn service_accounts_table(accounts: &[ServiceAccountInfo]) -> Table {
+    #[derive(Tabled)]
+    struct ServiceAccountRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "AUTOMOUNT")]
+        automount: String,
+        #[tabled(rename = "PULL SECRETS")]
+        pull_secrets: String,
+        #[tabled(rename = "ROLES")]
+        roles: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<ServiceAccountRow> = accounts
+        .iter()
+        .map(|a| ServiceAccountRow {
+            name: a.name.clone(),
+            namespace: a.namespace.clone(),
+            automount: match a.automount_token {
+                Some(true) => "true".to_string(),
+                Some(false) => "false".to_string(),
+                None => "-".to_string(),
+            },
+            pull_secrets: summarize_list(&a.image_pull_secrets),
+            roles: a.roles.join(", "),
+            age: a.age.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print Roles or ClusterRoles in the specified format
+pub fn print_roles(roles: &[RoleInfo], output: &Output) -> Result<()> {
+    if roles.is_empty() && says_none(output) {
+        println!("No roles found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_roles_table(roles),
+        OutputFormat::Csv => print_csv(&roles_table(roles), output)?,
+        _ => print_structured(&roles, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_roles_table(roles: &[RoleInfo]) {
+    println!("{}", roles_table(roles));
+}
+
+fn roles_table(roles: &[RoleInfo]) -> Table {
+    #[derive(Tabled)]
+    struct RoleRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "RULES")]
+        rules: String,
+        #[tabled(rename = "SUBJECTS")]
+        subjects: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<RoleRow> = roles
+        .iter()
+        .map(|r| RoleRow {
+            name: r.name.clone(),
+            namespace: r.namespace.clone().unwrap_or_else(|| "-".to_string()),
+            rules: if r.aggregated {
+                format!("{} (aggregated)", r.rules.len())
+            } else {
+                r.rules.len().to_string()
+            },
+            subjects: summarize_list(&r.subjects),
+            age: r.age.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print RoleBindings or ClusterRoleBindings in the specified format
+pub fn print_role_bindings(bindings: &[RoleBindingInfo], output: &Output) -> Result<()> {
+    if bindings.is_empty() && says_none(output) {
+        println!("No role bindings found");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_role_bindings_table(bindings),
+        OutputFormat::Csv => print_csv(&role_bindings_table(bindings), output)?,
+        _ => print_structured(&bindings, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_role_bindings_table(bindings: &[RoleBindingInfo]) {
+    println!("{}", role_bindings_table(bindings));
+}
+
+fn role_bindings_table(bindings: &[RoleBindingInfo]) -> Table {
+    #[derive(Tabled)]
+    struct RoleBindingRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "ROLE")]
+        role: String,
+        #[tabled(rename = "SUBJECTS")]
+        subjects: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<RoleBindingRow> = bindings
+        .iter()
+        .map(|b| RoleBindingRow {
+            name: b.name.clone(),
+            namespace: b.namespace.clone().unwrap_or_else(|| "-".to_string()),
+            role: format!("{}/{}", b.role_kind, b.role_name),
+            subjects: summarize_list(&b.subjects),
+            age: b.age.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Print the subjects allowed by `kdx who-can` in the specified format
+pub fn print_who_can(grants: &[Grant], output: &Output) -> Result<()> {
+    if grants.is_empty() && says_none(output) {
+        println!("No subject is allowed by any binding");
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => println!("{}", who_can_table(grants)),
+        OutputFormat::Csv => print_csv(&who_can_table(grants), output)?,
+        _ => print_structured(&grants, output)?,
+    }
+
+    Ok(())
+}
+
+fn who_can_table(grants: &[Grant]) -> Table {
+    #[derive(Tabled)]
+    struct GrantRow {
+        #[tabled(rename = "SUBJECT")]
+        subject: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "VIA")]
+        via: String,
+        #[tabled(rename = "RESOURCE NAMES")]
+        resource_names: String,
+    }
+
+    let subject = |s: &SubjectInfo| {
+        // Everyone authenticated, or every service account, deserves a look
+        let text = s.to_string();
+        match s.name.as_str() {
+            "system:authenticated" | "system:unauthenticated" | "system:serviceaccounts" => {
+                text.red().to_string()
+            }
+            _ => text,
+        }
+    };
+    let rows: Vec<GrantRow> = grants
+        .iter()
+        .map(|g| GrantRow {
+            subject: subject(&g.subject),
+            namespace: g.namespace.clone().unwrap_or_else(|| "*".to_string()),
+            via: format!("{} -> {}", g.binding, g.role),
+            resource_names: if g.resource_names.is_empty() {
+                "*".to_string()
+            } else {
+                g.resource_names.join(", ")
+            },
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print the manifests written by `kdx extract` in the specified format
+pub fn print_extract_summary(summary: &ExtractSummary, output: &Output) -> Result<()> {
+    if summary.manifests.is_empty() && says_none(output) {
+        println!("Nothing to extract in namespace {}", summary.namespace);
+        return Ok(());
+    }
+
+    match &output.format {
+        OutputFormat::Table => print_extract_summary_table(summary),
+        OutputFormat::Csv => print_csv(&extract_summary_table(summary), output)?,
+        _ => print_structured(summary, output)?,
+    }
+
+    Ok(())
+}
+
+fn print_extract_summary_table(summary: &ExtractSummary) {
+    println!("{}", extract_summary_table(summary));
+    println!(
+        "\n{} manifests written to {}, {} generated objects skipped",
+        summary.manifests.len(),
+        summary.output_dir.display(),
+        summary.skipped
+    );
+}
+
+fn extract_summary_table(summary: &ExtractSummary) -> Table {
+    #[derive(Tabled)]
+    struct ManifestRow {
+        #[tabled(rename = "KIND")]
+        kind: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "FILE")]
+        file: String,
+    }
+
+    let rows: Vec<ManifestRow> = summary
+        .manifests
+        .iter()
+        .map(|m| ManifestRow {
+            kind: m.kind.clone(),
+            name: m.name.clone(),
+            file: m.path.display().to_string(),
+        })
+        .collect();
+    Table::new(rows)
+}
+
+/// Print one endpoint change as soon as it is seen: a timestamped line for
+/// table output, or one JSON/YAML document per change
+pub fn print_endpoint_change(change: &EndpointChange, output: &Output) -> Result<()> {
+    match &output.format {
+        OutputFormat::Json => {
+            // One change per line so the stream can be piped to jq
+            let json = serialize_json(change, true)?;
+            write_document(&json, output)?;
+        }
+        OutputFormat::Yaml => {
+            write_document(&format!("---\n{}", serde_yaml::to_string(change)?), output)?
+        }
+        _ => {
+            let target = match (&change.target, &change.node) {
+                (Some(target), Some(node)) => format!(" ({} on {})", target, node),
+                (Some(target), None) => format!(" ({})", target),
+                (None, Some(node)) => format!(" (on {})", node),
+                (None, None) => String::new(),
+            };
+            let kind = match change.change {
+                ChangeKind::Added | ChangeKind::BecameReady => change.change.to_string().green(),
+                ChangeKind::Removed | ChangeKind::BecameNotReady => change.change.to_string().red(),
+            };
+            let state = match (change.change, change.ready, change.terminating) {
+                (ChangeKind::Added, true, _) => " ready",
+                (ChangeKind::Added, false, _) => " not ready",
+                (_, _, true) => " terminating",
+                _ => "",
+            };
+            println!(
+                "{} {} {}{}{}",
+                change.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+                change.address,
+                kind,
+                state,
+                target
+            );
+        }
+    }
+    std::io::stdout().flush()?;
+    Ok(())
+}
+
+/// Per-endpoint change counts printed when a watch ends
+pub fn print_endpoint_churn(churn: &[EndpointChurn]) {
+    #[derive(Tabled)]
+    struct ChurnRow {
+        #[tabled(rename = "ADDRESS")]
+        address: String,
+        #[tabled(rename = "TARGET")]
+        target: String,
+        #[tabled(rename = "ADDED")]
+        added: usize,
+        #[tabled(rename = "REMOVED")]
+        removed: usize,
+        #[tabled(rename = "READY FLIPS")]
+        ready_flips: usize,
+    }
+
+    if churn.is_empty() {
+        println!("\nNo endpoint changes seen");
+        return;
+    }
+
+    let rows: Vec<ChurnRow> = churn
+        .iter()
+        .map(|c| ChurnRow {
+            address: c.address.clone(),
+            target: c.target.clone().unwrap_or_else(|| "None".to_string()),
+            added: c.added,
+            removed: c.removed,
+            ready_flips: c.ready_flips,
+        })
+        .collect();
+    println!("\n{}", Table::new(rows));
+}
+
+/// Write a serialized document to stdout, compressed with `--compress`.
+/// Each document becomes a separate gzip member or zstd frame, which
+/// standard tools decompress as one stream.
+fn write_document(document: &str, output: &Output) -> Result<()> {
+    match output.compress {
+        Some(format) => {
+            let mut compressor = Compressor::new(format, std::io::stdout().lock())?;
+            writeln!(compressor, "{}", document)?;
+            compressor.finish()?.flush()?;
+        }
+        None => println!("{}", document),
+    }
+    Ok(())
+}
+
+/// A table's header and rows as RFC 4180 CSV, so `-o csv` has exactly the
+/// columns of the table view, hidden columns included. Colors are stripped.
+fn table_csv(table: &Table) -> String {
+    table
+        .get_records()
+        .iter()
+        .map(|row| csv_line(row.iter().map(|cell| strip_ansi(cell.as_ref()))))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+fn print_csv(table: &Table, output: &Output) -> Result<()> {
+    write_document(&table_csv(table), output)
+}
+
+/// Whether an empty result is reported with a message such as "No pods
+/// found". CSV output prints the header row instead, so a script always
+/// gets the columns.
+fn says_none(output: &Output) -> bool {
+    !matches!(output.format, OutputFormat::Csv)
+}
+
+/// The tables of a view made of several sections as one table: the
+/// section in a leading `column`, then the columns of every section in
+/// order of appearance, blank where a section has no such column
+fn sections_table(column: &str, sections: Vec<(String, Table)>) -> Table {
+    let sections: Vec<(String, Vec<Vec<String>>)> = sections
+        .into_iter()
+        .map(|(section, table)| {
+            let records = table
+                .get_records()
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.as_ref().to_string()).collect())
+                .collect();
+            (section, records)
+        })
+        .collect();
+
+    let mut headers: Vec<&str> = Vec::new();
+    for (_, records) in &sections {
+        for header in records.first().into_iter().flatten() {
+            if !headers.contains(&header.as_str()) {
+                headers.push(header);
+            }
+        }
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(std::iter::once(column).chain(headers.iter().copied()));
+    for (section, records) in &sections {
+        let Some((section_headers, rows)) = records.split_first() else {
+            continue;
+        };
+        for row in rows {
+            let mut record = vec![String::new(); headers.len()];
+            for (header, cell) in section_headers.iter().zip(row) {
+                if let Some(i) = headers.iter().position(|h| h == header) {
+                    record[i] = cell.clone();
+                }
+            }
+            builder.push_record(std::iter::once(section.clone()).chain(record));
+        }
+    }
+    builder.build()
+}
+
+/// A table per item as one, the item in a leading `column`. `empty`, the
+/// table of no rows, gives the columns when there are no items.
+fn items_table(column: &str, empty: Table, items: impl Iterator<Item = (String, Table)>) -> Table {
+    sections_table(
+        column,
+        std::iter::once((String::new(), empty))
+            .chain(items)
+            .collect(),
+    )
+}
+
+/// A FIELD/VALUE table of the lines of a description
+fn fields_table(fields: Vec<(String, String)>) -> Table {
+    #[derive(Tabled)]
+    struct FieldRow {
+        #[tabled(rename = "FIELD")]
+        field: String,
+        #[tabled(rename = "VALUE")]
+        value: String,
+    }
+
+    Table::new(
+        fields
+            .into_iter()
+            .map(|(field, value)| FieldRow { field, value }),
+    )
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    fields
+        .map(|field| csv_field(&field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A field quoted when it holds a separator, quote or line break, with
+/// quotes doubled
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// `text` without ANSI color sequences
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a letter, e.g. `\x1b[1;32m`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+/// Serialize to JSON, on one line when `compact` (`--json-compact`). Fields
+/// keep their declaration order and maps are `BTreeMap`s, so the same data
+/// always serializes to the same text.
+fn serialize_json<T: serde::Serialize + ?Sized>(
+    data: &T,
+    compact: bool,
+) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(data)
+    } else {
+        serde_json::to_string_pretty(data)
+    }
+}
+
+fn print_json<T: serde::Serialize + ?Sized>(data: &T, output: &Output) -> Result<()> {
+    write_document(&Document::new(&data).json(output.json_compact)?, output)
+}
+
+/// Serializations renderers can ask for. Implemented for every `Serialize`
+/// type so a document can be handed to renderers as a trait object.
+trait Serialized {
+    fn json(&self, compact: bool) -> serde_json::Result<String>;
+    fn yaml(&self) -> serde_yaml::Result<String>;
+    fn value(&self) -> serde_json::Result<serde_json::Value>;
+}
+
+impl<T: serde::Serialize> Serialized for T {
+    fn json(&self, compact: bool) -> serde_json::Result<String> {
+        serialize_json(self, compact)
+    }
+
+    fn yaml(&self) -> serde_yaml::Result<String> {
+        serde_yaml::to_string(self)
+    }
+
+    fn value(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+}
+
+/// Data handed to a [`Renderer`], serialized in whichever form it needs
+pub struct Document<'a> {
+    data: &'a dyn Serialized,
+}
+
+impl<'a> Document<'a> {
+    pub fn new<T: serde::Serialize>(data: &'a T) -> Self {
+        Self { data }
+    }
+
+    /// Pretty-printed JSON with fields in declaration order
+    pub fn to_json(&self) -> Result<String> {
+        self.json(false)
+    }
+
+    fn json(&self, compact: bool) -> Result<String> {
+        self.data
+            .json(compact)
+            .map_err(|e| ExplorerError::OutputFormat(format!("JSON serialization failed: {}", e)))
+    }
+
+    pub fn to_yaml(&self) -> Result<String> {
+        self.data
+            .yaml()
+            .map_err(|e| ExplorerError::OutputFormat(format!("YAML serialization failed: {}", e)))
+    }
+
+    /// The data as a JSON value. Object keys are sorted, so renderers that
+    /// care about field order should start from [`Document::to_json`].
+    pub fn to_value(&self) -> Result<serde_json::Value> {
+        Ok(self.data.value()?)
+    }
+}
+
+/// Renders data in one output format. Every non-table format is printed by
+/// the renderer registered for it, so a new format is one registration
+/// rather than a change to each `print_*` function.
+pub trait Renderer: Send + Sync {
+    /// The text to print; empty output prints nothing
+    fn render(&self, document: &Document<'_>) -> Result<String>;
+}
+
+struct JsonRenderer {
+    /// `--json-compact`
+    compact: bool,
+}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, document: &Document<'_>) -> Result<String> {
+        document.json(self.compact)
+    }
+}
+
+struct YamlRenderer;
+
+impl Renderer for YamlRenderer {
+    fn render(&self, document: &Document<'_>) -> Result<String> {
+        document.to_yaml()
+    }
+}
+
+/// One line per resource: array elements, or objects carrying a `name`
+/// found by descending through wrapper objects such as groups
+enum LineRenderer {
+    Name,
+    NamespacedName,
+    /// The `--template-preset` template
+    Preset(Template),
+}
+
+impl Renderer for LineRenderer {
+    fn render(&self, document: &Document<'_>) -> Result<String> {
+        let template = match self {
+            LineRenderer::Name => Template::parse(template::NAME)?,
+            LineRenderer::NamespacedName => Template::parse(template::NAMESPACED_NAME)?,
+            LineRenderer::Preset(template) => template.clone(),
+        };
+        // Cluster-scoped resources have no namespace to prefix
+        let fallback = Template::parse(template::NAME)?;
+
+        let value = document.to_value()?;
+        let lines: Vec<String> = line_items(&value)
+            .into_iter()
+            .map(|item| {
+                if matches!(self, LineRenderer::NamespacedName) && !template.matches(item) {
+                    fallback.render(item)
+                } else {
+                    template.render(item)
+                }
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
+
+/// CSV for commands without a table of their own: one row per array
+/// element (or the single object), with the union of their fields as
+/// columns. Nested values are written as compact JSON.
+struct CsvRenderer;
+
+impl Renderer for CsvRenderer {
+    fn render(&self, document: &Document<'_>) -> Result<String> {
+        let value = document.to_value()?;
+        let items: Vec<&serde_json::Value> = match &value {
+            serde_json::Value::Array(items) => items.iter().collect(),
+            value => vec![value],
+        };
+        let mut columns: Vec<&str> = Vec::new();
+        for item in &items {
+            if let serde_json::Value::Object(fields) = item {
+                for key in fields.keys() {
+                    if !columns.contains(&key.as_str()) {
+                        columns.push(key);
+                    }
+                }
+            }
+        }
+        // Nothing to take the columns from
+        if items.is_empty() {
+            return Ok(String::new());
+        }
+        if columns.is_empty() {
+            return Err(ExplorerError::OutputFormat(
+                "csv output needs a list of objects".to_string(),
+            ));
+        }
+
+        let cell = |value: Option<&serde_json::Value>| match value {
+            None | Some(serde_json::Value::Null) => String::new(),
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(value) => value.to_string(),
+        };
+        let mut lines = vec![csv_line(columns.iter().map(|c| c.to_string()))];
+        lines.extend(
+            items
+                .iter()
+                .map(|item| csv_line(columns.iter().map(|c| cell(item.get(*c))))),
+        );
+        Ok(lines.join("\r\n"))
+    }
+}
+
+/// A table of the `--columns` fields, one row per resource
+struct ColumnsRenderer(Vec<Column>);
+
+impl Renderer for ColumnsRenderer {
+    fn render(&self, document: &Document<'_>) -> Result<String> {
+        let columns = &self.0;
+        let value = document.to_value()?;
+        let mut builder = Builder::default();
+        builder.push_record(columns.iter().map(|c| c.header.clone()));
+        for item in line_items(&value) {
+            builder.push_record(columns.iter().map(|c| c.cell(item)));
         }
+        Ok(builder.build().to_string())
     }
+}
 
-    if !description.related_pods.is_empty() {
-        println!("\nRelated Pods:");
-        print_pods_table(&description.related_pods);
+/// The JSONPath template against the document `-o json` would print
+struct JsonPathRenderer(JsonPath);
+
+impl Renderer for JsonPathRenderer {
+    fn render(&self, document: &Document<'_>) -> Result<String> {
+        // The document is printed with a newline of its own
+        let rendered = self.0.render(&document.to_value()?);
+        Ok(match rendered.strip_suffix('\n') {
+            Some(rendered) => rendered.to_string(),
+            None => rendered,
+        })
     }
 }
 
-fn print_service_topology_table(topology: &ServiceTopology) {
-    let service = &topology.service;
+type Renderers = RwLock<HashMap<String, Arc<dyn Renderer>>>;
 
-    println!("{}", format!("Service Topology: {}", service.name).bold());
-    println!("├── Namespace: {}", service.namespace);
-    println!("├── Type: {}", service.service_type);
+/// Renderers registered by output format name
+fn renderers() -> &'static Renderers {
+    static RENDERERS: OnceLock<Renderers> = OnceLock::new();
+    RENDERERS.get_or_init(Default::default)
+}
 
-    if let Some(cluster_ip) = &service.cluster_ip {
-        println!("├── Cluster IP: {}", cluster_ip);
-    }
+/// Print `format` with `renderer` from now on, adding a format
+/// (`OutputFormat::Custom`) or replacing a built-in one
+pub fn register_renderer(format: OutputFormat, renderer: impl Renderer + 'static) {
+    renderers()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(format.to_string(), Arc::new(renderer));
+}
 
-    if !topology.backend_pods.is_empty() {
-        println!("└── Backend Pods:");
-        for (i, pod) in topology.backend_pods.iter().enumerate() {
-            let prefix = if i == topology.backend_pods.len() - 1 {
-                "    └──"
-            } else {
-                "    ├──"
-            };
-            let status_color = match pod.phase.as_str() {
-                "Running" => pod.phase.green(),
-                "Pending" => pod.phase.yellow(),
-                "Failed" => pod.phase.red(),
-                _ => pod.phase.normal(),
-            };
-            println!("{} {} ({})", prefix, pod.name, status_color);
-        }
+/// The renderer registered for the output's format, or else the built-in
+/// one, which carries whatever the format was parsed with
+fn renderer(output: &Output) -> Result<Arc<dyn Renderer>> {
+    if let Some(renderer) = renderers()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&output.format.to_string())
+    {
+        return Ok(renderer.clone());
     }
-
-    // TODO: Add ingress routes and dependencies when implemented
+    Ok(match &output.format {
+        OutputFormat::Json => Arc::new(JsonRenderer {
+            compact: output.json_compact,
+        }),
+        OutputFormat::Yaml => Arc::new(YamlRenderer),
+        OutputFormat::Name => Arc::new(LineRenderer::Name),
+        OutputFormat::NsName => Arc::new(LineRenderer::NamespacedName),
+        OutputFormat::Csv => Arc::new(CsvRenderer),
+        OutputFormat::Template(template) => Arc::new(LineRenderer::Preset(template.clone())),
+        OutputFormat::Columns(columns) => Arc::new(ColumnsRenderer(columns.clone())),
+        OutputFormat::JsonPath(template) => Arc::new(JsonPathRenderer(template.clone())),
+        OutputFormat::Table | OutputFormat::Custom(_) => {
+            return Err(ExplorerError::OutputFormat(format!(
+                "no renderer for {} output",
+                output
+            )))
+        }
+    })
 }
 
-fn print_json<T: serde::Serialize + ?Sized>(data: &T) -> Result<()> {
-    let json = serde_json::to_string_pretty(data)
-        .map_err(|e| ExplorerError::OutputFormat(format!("JSON serialization failed: {}", e)))?;
-    println!("{}", json);
-    Ok(())
+/// Print data in one of the non-table formats with its registered renderer.
+/// JSON and YAML documents are wrapped in the envelope with `--envelope`.
+fn print_structured<T: serde::Serialize + ?Sized>(data: &T, output: &Output) -> Result<()> {
+    let renderer = renderer(output)?;
+    let text = match &output.envelope {
+        Some(envelope) if matches!(output.format, OutputFormat::Json | OutputFormat::Yaml) => {
+            renderer.render(&Document::new(&envelope.wrap(data)))?
+        }
+        _ => renderer.render(&Document::new(&data))?,
+    };
+    if text.is_empty() {
+        return Ok(());
+    }
+    write_document(&text, output)
 }
 
-fn print_yaml<T: serde::Serialize + ?Sized>(data: &T) -> Result<()> {
-    let yaml = serde_yaml::to_string(data)
-        .map_err(|e| ExplorerError::OutputFormat(format!("YAML serialization failed: {}", e)))?;
-    println!("{}", yaml);
-    Ok(())
+fn line_items(value: &serde_json::Value) -> Vec<&serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items
+            .iter()
+            .flat_map(|item| match item {
+                serde_json::Value::Object(_) if item.get("name").is_some() => vec![item],
+                _ => line_items(item),
+            })
+            .collect(),
+        serde_json::Value::Object(map) if map.contains_key("name") => vec![value],
+        serde_json::Value::Object(map) => map.values().flat_map(line_items).collect(),
+        _ => Vec::new(),
+    }
 }
 
 /// Print ingress information in the specified format
-pub fn print_ingress_info(ingress_routes: &[IngressInfo], format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_ingress_info(ingress_routes: &[IngressInfo], output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_ingress_table(ingress_routes),
-        OutputFormat::Json => print_json(&ingress_routes)?,
-        OutputFormat::Yaml => print_yaml(&ingress_routes)?,
+        OutputFormat::Csv => print_csv(&ingress_table(ingress_routes), output)?,
+        _ => print_structured(&ingress_routes, output)?,
     }
 
     Ok(())
@@ -586,27 +6362,63 @@ fn print_ingress_table(ingress_routes: &[IngressInfo]) {
     }
 }
 
+/// One row per ingress path; an ingress without paths gets a row of its own
+fn ingress_table(ingress_routes: &[IngressInfo]) -> Table {
+    #[derive(Tabled)]
+    struct IngressRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "HOSTS")]
+        hosts: String,
+        #[tabled(rename = "TLS")]
+        tls: bool,
+        #[tabled(rename = "PATH")]
+        path: String,
+        #[tabled(rename = "BACKEND")]
+        backend: String,
+    }
+
+    let mut rows = Vec::new();
+    for ingress in ingress_routes {
+        let row = |path: String, backend: String| IngressRow {
+            name: ingress.name.clone(),
+            namespace: ingress.namespace.clone(),
+            hosts: ingress.hosts.join(","),
+            tls: ingress.tls_enabled,
+            path,
+            backend,
+        };
+        if ingress.paths.is_empty() {
+            rows.push(row("-".to_string(), "-".to_string()));
+        }
+        for path in &ingress.paths {
+            rows.push(row(
+                path.path.clone(),
+                format!("{}:{}", path.service_name, path.service_port),
+            ));
+        }
+    }
+
+    Table::new(rows)
+}
+
 /// Print configuration information (ConfigMaps and Secrets) in the specified format
 pub fn print_configuration_info(
     configmaps: &[ConfigMapInfo],
     secrets: &[SecretInfo],
-    format: &OutputFormat,
+    output: &Output,
 ) -> Result<()> {
-    match format {
+    match &output.format {
         OutputFormat::Table => print_configuration_table(configmaps, secrets),
-        OutputFormat::Json => {
-            let config = serde_json::json!({
-                "configmaps": configmaps,
-                "secrets": secrets
-            });
-            print_json(&config)?;
-        }
-        OutputFormat::Yaml => {
+        OutputFormat::Csv => print_csv(&configuration_table(configmaps, secrets), output)?,
+        _ => {
             let config = serde_json::json!({
                 "configmaps": configmaps,
                 "secrets": secrets
             });
-            print_yaml(&config)?;
+            print_structured(&config, output)?;
         }
     }
 
@@ -656,12 +6468,53 @@ fn print_configuration_table(configmaps: &[ConfigMapInfo], secrets: &[SecretInfo
     }
 }
 
+/// ConfigMaps and Secrets in one table, a KIND column telling them apart
+fn configuration_table(configmaps: &[ConfigMapInfo], secrets: &[SecretInfo]) -> Table {
+    #[derive(Tabled)]
+    struct ConfigurationRow {
+        #[tabled(rename = "KIND")]
+        kind: &'static str,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "TYPE")]
+        secret_type: String,
+        #[tabled(rename = "MOUNTED-AT")]
+        mount_paths: String,
+    }
+
+    let mounted = |paths: &[String]| {
+        if paths.is_empty() {
+            "environment variable".to_string()
+        } else {
+            paths.join(", ")
+        }
+    };
+    let configmaps = configmaps.iter().map(|cm| ConfigurationRow {
+        kind: "ConfigMap",
+        name: cm.name.clone(),
+        namespace: cm.namespace.clone(),
+        secret_type: "-".to_string(),
+        mount_paths: mounted(&cm.mount_paths),
+    });
+    let secrets = secrets.iter().map(|secret| ConfigurationRow {
+        kind: "Secret",
+        name: secret.name.clone(),
+        namespace: secret.namespace.clone(),
+        secret_type: secret.secret_type.clone(),
+        mount_paths: mounted(&secret.mount_paths),
+    });
+
+    Table::new(configmaps.chain(secrets))
+}
+
 /// Print health information in the specified format
-pub fn print_health_info(health: &ServiceHealth, format: &OutputFormat) -> Result<()> {
-    match format {
+pub fn print_health_info(health: &ServiceHealth, output: &Output) -> Result<()> {
+    match &output.format {
         OutputFormat::Table => print_health_table(health),
-        OutputFormat::Json => print_json(&health)?,
-        OutputFormat::Yaml => print_yaml(&health)?,
+        OutputFormat::Csv => print_csv(&health_table(health), output)?,
+        _ => print_structured(&health, output)?,
     }
 
     Ok(())
@@ -684,12 +6537,38 @@ fn print_health_table(health: &ServiceHealth) {
     }
 }
 
+fn health_table(health: &ServiceHealth) -> Table {
+    let status = if health.overall_healthy {
+        "Healthy"
+    } else {
+        "Unhealthy"
+    };
+    fields_table(vec![
+        ("Status".to_string(), status.to_string()),
+        ("Checked at".to_string(), health.checked_at.to_string()),
+    ])
+}
+
+/// Number of objects in `used_by`; an object using a ConfigMap or Secret in
+/// several ways is listed once per way
+fn distinct_users(used_by: &[ResourceReference]) -> usize {
+    used_by
+        .iter()
+        .map(|r| (&r.kind, &r.namespace, &r.name))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
 fn print_configmaps_table(configmaps: &[ConfigMapInfo]) {
     if configmaps.is_empty() {
         println!("No configmaps found");
         return;
     }
 
+    println!("{}", configmaps_table(configmaps));
+}
+
+fn configmaps_table(configmaps: &[ConfigMapInfo]) -> Table {
     #[derive(Tabled)]
     struct ConfigMapRow {
         #[tabled(rename = "NAME")]
@@ -714,13 +6593,12 @@ fn print_configmaps_table(configmaps: &[ConfigMapInfo]) {
             used_by: if cm.used_by.is_empty() {
                 "None".to_string()
             } else {
-                format!("{} resources", cm.used_by.len())
+                format!("{} resources", distinct_users(&cm.used_by))
             },
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
 }
 
 fn print_secrets_table(secrets: &[SecretInfo]) {
@@ -729,6 +6607,10 @@ fn print_secrets_table(secrets: &[SecretInfo]) {
         return;
     }
 
+    println!("{}", secrets_table(secrets));
+}
+
+fn secrets_table(secrets: &[SecretInfo]) -> Table {
     #[derive(Tabled)]
     struct SecretRow {
         #[tabled(rename = "NAME")]
@@ -756,13 +6638,12 @@ fn print_secrets_table(secrets: &[SecretInfo]) {
             used_by: if s.used_by.is_empty() {
                 "None".to_string()
             } else {
-                format!("{} resources", s.used_by.len())
+                format!("{} resources", distinct_users(&s.used_by))
             },
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
 }
 
 fn print_grouped_configmaps_table(grouped: &GroupedResources) {
@@ -799,12 +6680,263 @@ fn print_grouped_secrets_table(grouped: &GroupedResources) {
     }
 }
 
+fn print_nodes_table(nodes: &[NodeInfo]) {
+    if nodes.is_empty() {
+        println!("No nodes found");
+        return;
+    }
+
+    println!("{}", nodes_table(nodes));
+}
+
+fn nodes_table(nodes: &[NodeInfo]) -> Table {
+    #[derive(Tabled)]
+    struct NodeRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "STATUS")]
+        status: String,
+        #[tabled(rename = "ROLES")]
+        roles: String,
+        #[tabled(rename = "VERSION")]
+        version: String,
+        #[tabled(rename = "OS/ARCH")]
+        platform: String,
+        #[tabled(rename = "CPU")]
+        cpu: String,
+        #[tabled(rename = "MEMORY")]
+        memory: String,
+        #[tabled(rename = "CONDITIONS")]
+        conditions: String,
+        #[tabled(rename = "TAINTS")]
+        taints: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let none_if_empty = |values: Vec<&str>| {
+        if values.is_empty() {
+            "None".to_string()
+        } else {
+            values.join(",")
+        }
+    };
+
+    let rows: Vec<NodeRow> = nodes
+        .iter()
+        .map(|node| {
+            let mut status = match node.ready_status() {
+                "Ready" => "Ready".to_string(),
+                other => other.red().to_string(),
+            };
+            if node.unschedulable {
+                status.push_str(",SchedulingDisabled");
+            }
+            let problems = node.problems();
+            NodeRow {
+                name: node.name.clone(),
+                status,
+                roles: none_if_empty(node.roles.iter().map(String::as_str).collect()),
+                version: node.kubelet_version.clone(),
+                platform: format!("{}/{}", node.os, node.arch),
+                cpu: node
+                    .allocatable_cpu
+                    .clone()
+                    .unwrap_or_else(|| "None".to_string()),
+                memory: node.allocatable_memory.as_deref().map_or_else(
+                    || "None".to_string(),
+                    |m| parse_quantity(m).map_or_else(|| m.to_string(), format_bytes),
+                ),
+                conditions: if problems.is_empty() {
+                    "None".to_string()
+                } else {
+                    problems.join(",").yellow().to_string()
+                },
+                taints: node.taints.len().to_string(),
+                age: node.age.clone(),
+            }
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+/// Short access mode names as shown by kubectl, e.g. "RWO,ROX"
+fn access_modes(modes: &[String]) -> String {
+    let short: Vec<&str> = modes
+        .iter()
+        .map(|mode| match mode.as_str() {
+            "ReadWriteOnce" => "RWO",
+            "ReadOnlyMany" => "ROX",
+            "ReadWriteMany" => "RWX",
+            "ReadWriteOncePod" => "RWOP",
+            other => other,
+        })
+        .collect();
+    if short.is_empty() {
+        "None".to_string()
+    } else {
+        short.join(",")
+    }
+}
+
+/// Pods mounting a claim, or "None"
+fn mounted_by(pods: &[String]) -> String {
+    if pods.is_empty() {
+        "None".to_string()
+    } else {
+        pods.join(",")
+    }
+}
+
+fn print_persistent_volumes_table(volumes: &[PersistentVolumeInfo]) {
+    if volumes.is_empty() {
+        println!("No persistent volumes found");
+        return;
+    }
+
+    println!("{}", persistent_volumes_table(volumes));
+}
+
+fn persistent_volumes_table(volumes: &[PersistentVolumeInfo]) -> Table {
+    #[derive(Tabled)]
+    struct VolumeRow {
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "CAPACITY")]
+        capacity: String,
+        #[tabled(rename = "ACCESS MODES")]
+        access_modes: String,
+        #[tabled(rename = "RECLAIM POLICY")]
+        reclaim_policy: String,
+        #[tabled(rename = "STATUS")]
+        status: String,
+        #[tabled(rename = "CLAIM")]
+        claim: String,
+        #[tabled(rename = "STORAGECLASS")]
+        storage_class: String,
+        #[tabled(rename = "MOUNTED BY")]
+        mounted_by: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<VolumeRow> = volumes
+        .iter()
+        .map(|pv| VolumeRow {
+            name: pv.name.clone(),
+            capacity: pv.capacity.clone().unwrap_or_else(|| "None".to_string()),
+            access_modes: access_modes(&pv.access_modes),
+            reclaim_policy: pv.reclaim_policy.clone(),
+            status: match pv.phase.as_str() {
+                "Bound" | "Available" => pv.phase.clone(),
+                "Released" => pv.phase.yellow().to_string(),
+                _ => pv.phase.red().to_string(),
+            },
+            claim: match (&pv.claim_namespace, &pv.claim_name) {
+                (Some(ns), Some(name)) => format!("{}/{}", ns, name),
+                _ => "None".to_string(),
+            },
+            storage_class: pv
+                .storage_class
+                .clone()
+                .unwrap_or_else(|| "None".to_string()),
+            mounted_by: mounted_by(&pv.mounted_by),
+            age: pv.age.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
+fn print_persistent_volume_claims_table(claims: &[PersistentVolumeClaimInfo]) {
+    if claims.is_empty() {
+        println!("No persistent volume claims found");
+        return;
+    }
+
+    println!("{}", persistent_volume_claims_table(claims));
+}
+
+fn persistent_volume_claims_table(claims: &[PersistentVolumeClaimInfo]) -> Table {
+    #[derive(Tabled)]
+    struct ClaimRow {
+        #[tabled(rename = "NAMESPACE")]
+        namespace: String,
+        #[tabled(rename = "NAME")]
+        name: String,
+        #[tabled(rename = "STATUS")]
+        status: String,
+        #[tabled(rename = "VOLUME")]
+        volume: String,
+        #[tabled(rename = "CAPACITY")]
+        capacity: String,
+        #[tabled(rename = "ACCESS MODES")]
+        access_modes: String,
+        #[tabled(rename = "STORAGECLASS")]
+        storage_class: String,
+        #[tabled(rename = "MOUNTED BY")]
+        mounted_by: String,
+        #[tabled(rename = "AGE")]
+        age: String,
+    }
+
+    let rows: Vec<ClaimRow> = claims
+        .iter()
+        .map(|pvc| ClaimRow {
+            namespace: pvc.namespace.clone(),
+            name: pvc.name.clone(),
+            status: match pvc.phase.as_str() {
+                "Bound" => pvc.phase.clone(),
+                "Pending" => pvc.phase.yellow().to_string(),
+                _ => pvc.phase.red().to_string(),
+            },
+            volume: pvc
+                .volume_name
+                .clone()
+                .unwrap_or_else(|| "None".to_string()),
+            capacity: pvc.capacity.clone().unwrap_or_else(|| "None".to_string()),
+            access_modes: access_modes(&pvc.access_modes),
+            storage_class: pvc
+                .storage_class
+                .clone()
+                .unwrap_or_else(|| "None".to_string()),
+            mounted_by: mounted_by(&pvc.mounted_by),
+            age: pvc.age.clone(),
+        })
+        .collect();
+
+    Table::new(rows)
+}
+
 fn print_crds_table(crds: &[CRDInfo], show_versions: bool) {
     if crds.is_empty() {
         println!("No CRDs found");
         return;
     }
 
+    println!("{}", crds_table(crds));
+
+    if show_versions {
+        for crd in crds {
+            if crd.versions.len() > 1 {
+                println!("\nVersions for {}:", crd.name.cyan());
+                for version in &crd.versions {
+                    let status = if version.storage {
+                        "storage".green()
+                    } else if version.served {
+                        "served".yellow()
+                    } else {
+                        "deprecated".red()
+                    };
+                    println!("  {} ({})", version.name, status);
+                }
+            }
+        }
+    }
+}
+
+fn crds_table(crds: &[CRDInfo]) -> Table {
     #[derive(Tabled)]
     struct CRDRow {
         #[tabled(rename = "NAME")]
@@ -817,6 +6949,8 @@ fn print_crds_table(crds: &[CRDInfo], show_versions: bool) {
         kind: String,
         #[tabled(rename = "SCOPE")]
         scope: String,
+        #[tabled(rename = "CONTROLLER")]
+        controller: String,
         #[tabled(rename = "INSTANCES")]
         instances: String,
         #[tabled(rename = "AGE")]
@@ -831,31 +6965,16 @@ fn print_crds_table(crds: &[CRDInfo], show_versions: bool) {
             version: crd.version.clone(),
             kind: crd.kind.clone(),
             scope: crd.scope.clone(),
+            controller: crd
+                .controller
+                .as_ref()
+                .map_or_else(|| "None".to_string(), |c| c.to_string()),
             instances: crd.instance_count.to_string(),
             age: crd.age.clone(),
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
-
-    if show_versions {
-        for crd in crds {
-            if crd.versions.len() > 1 {
-                println!("\nVersions for {}:", crd.name.cyan());
-                for version in &crd.versions {
-                    let status = if version.storage {
-                        "storage".green()
-                    } else if version.served {
-                        "served".yellow()
-                    } else {
-                        "deprecated".red()
-                    };
-                    println!("  {} ({})", version.name, status);
-                }
-            }
-        }
-    }
+    Table::new(rows)
 }
 
 fn print_custom_resources_table(custom_resources: &[CustomResourceInfo]) {
@@ -864,6 +6983,10 @@ fn print_custom_resources_table(custom_resources: &[CustomResourceInfo]) {
         return;
     }
 
+    println!("{}", custom_resources_table(custom_resources));
+}
+
+fn custom_resources_table(custom_resources: &[CustomResourceInfo]) -> Table {
     #[derive(Tabled)]
     struct CustomResourceRow {
         #[tabled(rename = "NAME")]
@@ -892,8 +7015,7 @@ fn print_custom_resources_table(custom_resources: &[CustomResourceInfo]) {
         })
         .collect();
 
-    let table = Table::new(rows);
-    println!("{}", table);
+    Table::new(rows)
 }
 
 fn print_grouped_crds_table(grouped: &GroupedResources, show_versions: bool) {
@@ -933,28 +7055,28 @@ fn print_grouped_custom_resources_table(grouped: &GroupedResources) {
 /// Streaming output for large datasets to reduce memory usage
 pub struct StreamingOutput<W: Write> {
     writer: W,
-    format: OutputFormat,
+    output: Output,
     first_item: bool,
 }
 
 impl<W: Write> StreamingOutput<W> {
-    pub fn new(writer: W, format: OutputFormat) -> Self {
+    pub fn new(writer: W, output: Output) -> Self {
         Self {
             writer,
-            format,
+            output,
             first_item: true,
         }
     }
 
     pub fn start_array(&mut self) -> Result<()> {
-        match self.format {
+        match self.output.format {
             OutputFormat::Json => {
                 writeln!(self.writer, "[")?;
             }
             OutputFormat::Yaml => {
                 // YAML arrays start with items
             }
-            OutputFormat::Table => {
+            _ => {
                 // Table format doesn't support streaming
             }
         }
@@ -962,12 +7084,12 @@ impl<W: Write> StreamingOutput<W> {
     }
 
     pub fn write_item<T: serde::Serialize>(&mut self, item: &T) -> Result<()> {
-        match self.format {
+        match self.output.format {
             OutputFormat::Json => {
                 if !self.first_item {
                     writeln!(self.writer, ",")?;
                 }
-                let json = serde_json::to_string_pretty(item)?;
+                let json = serialize_json(item, self.output.json_compact)?;
                 write!(self.writer, "{}", json)?;
                 self.first_item = false;
             }
@@ -976,7 +7098,7 @@ impl<W: Write> StreamingOutput<W> {
                 writeln!(self.writer, "---")?;
                 write!(self.writer, "{}", yaml)?;
             }
-            OutputFormat::Table => {
+            _ => {
                 // Table format requires all data at once
                 return Err(ExplorerError::OutputFormat(
                     "Table format doesn't support streaming".to_string(),
@@ -987,7 +7109,7 @@ impl<W: Write> StreamingOutput<W> {
     }
 
     pub fn end_array(&mut self) -> Result<()> {
-        match self.format {
+        match self.output.format {
             OutputFormat::Json => {
                 writeln!(self.writer)?;
                 writeln!(self.writer, "]")?;
@@ -995,7 +7117,7 @@ impl<W: Write> StreamingOutput<W> {
             OutputFormat::Yaml => {
                 // YAML doesn't need explicit array end
             }
-            OutputFormat::Table => {
+            _ => {
                 // Table format doesn't support streaming
             }
         }
@@ -1008,9 +7130,9 @@ impl<W: Write> StreamingOutput<W> {
 pub fn stream_services<W: Write>(
     services: impl Iterator<Item = ServiceInfo>,
     writer: W,
-    format: &OutputFormat,
+    output: &Output,
 ) -> Result<()> {
-    let mut streaming = StreamingOutput::new(writer, format.clone());
+    let mut streaming = StreamingOutput::new(writer, output.clone());
     streaming.start_array()?;
 
     for service in services {
@@ -1043,6 +7165,9 @@ mod tests {
             age: "5d".to_string(),
             labels,
             selector,
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         }
     }
 
@@ -1062,6 +7187,12 @@ mod tests {
             age: "10d".to_string(),
             labels,
             selector,
+            images: BTreeMap::new(),
+            volume_claim_templates: Vec::new(),
+            update_revision: None,
+            ordinals: Vec::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         }
     }
 
@@ -1082,90 +7213,93 @@ mod tests {
             age: "30d".to_string(),
             labels,
             selector,
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
         }
     }
 
     #[test]
     fn test_print_deployments_json() {
         let deployments = vec![create_test_deployment()];
-        let result = print_deployments(&deployments, &OutputFormat::Json);
+        let result = print_deployments(&deployments, &OutputFormat::Json.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_deployments_yaml() {
         let deployments = vec![create_test_deployment()];
-        let result = print_deployments(&deployments, &OutputFormat::Yaml);
+        let result = print_deployments(&deployments, &OutputFormat::Yaml.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_deployments_table() {
         let deployments = vec![create_test_deployment()];
-        let result = print_deployments(&deployments, &OutputFormat::Table);
+        let result = print_deployments(&deployments, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_empty_deployments() {
         let deployments = vec![];
-        let result = print_deployments(&deployments, &OutputFormat::Table);
+        let result = print_deployments(&deployments, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_statefulsets_json() {
         let statefulsets = vec![create_test_statefulset()];
-        let result = print_statefulsets(&statefulsets, &OutputFormat::Json);
+        let result = print_statefulsets(&statefulsets, &OutputFormat::Json.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_statefulsets_yaml() {
         let statefulsets = vec![create_test_statefulset()];
-        let result = print_statefulsets(&statefulsets, &OutputFormat::Yaml);
+        let result = print_statefulsets(&statefulsets, &OutputFormat::Yaml.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_statefulsets_table() {
         let statefulsets = vec![create_test_statefulset()];
-        let result = print_statefulsets(&statefulsets, &OutputFormat::Table);
+        let result = print_statefulsets(&statefulsets, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_empty_statefulsets() {
         let statefulsets = vec![];
-        let result = print_statefulsets(&statefulsets, &OutputFormat::Table);
+        let result = print_statefulsets(&statefulsets, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_daemonsets_json() {
         let daemonsets = vec![create_test_daemonset()];
-        let result = print_daemonsets(&daemonsets, &OutputFormat::Json);
+        let result = print_daemonsets(&daemonsets, &OutputFormat::Json.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_daemonsets_yaml() {
         let daemonsets = vec![create_test_daemonset()];
-        let result = print_daemonsets(&daemonsets, &OutputFormat::Yaml);
+        let result = print_daemonsets(&daemonsets, &OutputFormat::Yaml.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_daemonsets_table() {
         let daemonsets = vec![create_test_daemonset()];
-        let result = print_daemonsets(&daemonsets, &OutputFormat::Table);
+        let result = print_daemonsets(&daemonsets, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_print_empty_daemonsets() {
         let daemonsets = vec![];
-        let result = print_daemonsets(&daemonsets, &OutputFormat::Table);
+        let result = print_daemonsets(&daemonsets, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
@@ -1180,9 +7314,9 @@ mod tests {
         }];
 
         // Test all output formats with multiple deployments
-        assert!(print_deployments(&deployments, &OutputFormat::Table).is_ok());
-        assert!(print_deployments(&deployments, &OutputFormat::Json).is_ok());
-        assert!(print_deployments(&deployments, &OutputFormat::Yaml).is_ok());
+        assert!(print_deployments(&deployments, &OutputFormat::Table.into()).is_ok());
+        assert!(print_deployments(&deployments, &OutputFormat::Json.into()).is_ok());
+        assert!(print_deployments(&deployments, &OutputFormat::Yaml.into()).is_ok());
     }
 
     #[test]
@@ -1193,7 +7327,7 @@ mod tests {
         deployment.available_replicas = 0;
 
         let deployments = vec![deployment];
-        let result = print_deployments(&deployments, &OutputFormat::Table);
+        let result = print_deployments(&deployments, &OutputFormat::Table.into());
         assert!(result.is_ok());
     }
 
@@ -1205,14 +7339,14 @@ mod tests {
         statefulset.current_replicas = 98;
 
         let statefulsets = vec![statefulset];
-        let result = print_statefulsets(&statefulsets, &OutputFormat::Json);
+        let result = print_statefulsets(&statefulsets, &OutputFormat::Json.into());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_streaming_output_json() {
         let mut buffer = Vec::new();
-        let mut streaming = StreamingOutput::new(&mut buffer, OutputFormat::Json);
+        let mut streaming = StreamingOutput::new(&mut buffer, OutputFormat::Json.into());
 
         streaming.start_array().unwrap();
 
@@ -1231,7 +7365,7 @@ mod tests {
     #[test]
     fn test_streaming_output_yaml() {
         let mut buffer = Vec::new();
-        let mut streaming = StreamingOutput::new(&mut buffer, OutputFormat::Yaml);
+        let mut streaming = StreamingOutput::new(&mut buffer, OutputFormat::Yaml.into());
 
         streaming.start_array().unwrap();
 
@@ -1249,7 +7383,7 @@ mod tests {
     #[test]
     fn test_streaming_output_table_error() {
         let mut buffer = Vec::new();
-        let mut streaming = StreamingOutput::new(&mut buffer, OutputFormat::Table);
+        let mut streaming = StreamingOutput::new(&mut buffer, OutputFormat::Table.into());
 
         let service = create_test_service();
         let result = streaming.write_item(&service);
@@ -1265,7 +7399,11 @@ mod tests {
         let services = vec![create_test_service(), create_test_service()];
         let mut buffer = Vec::new();
 
-        let result = stream_services(services.into_iter(), &mut buffer, &OutputFormat::Json);
+        let result = stream_services(
+            services.into_iter(),
+            &mut buffer,
+            &OutputFormat::Json.into(),
+        );
         assert!(result.is_ok());
 
         let output = String::from_utf8(buffer).unwrap();
@@ -1273,6 +7411,25 @@ mod tests {
         assert!(output.ends_with("]\n"));
     }
 
+    #[test]
+    fn test_line_items_descend_into_groups() {
+        let grouped = serde_json::json!({
+            "groups": [
+                {"key": "app=web", "configmaps": [{"name": "web-config", "namespace": "shop"}]},
+                {"key": "app=api", "configmaps": [{"name": "api-config", "namespace": "shop"}]},
+            ]
+        });
+        let names: Vec<&str> = line_items(&grouped)
+            .iter()
+            .filter_map(|item| item["name"].as_str())
+            .collect();
+        assert_eq!(names, ["web-config", "api-config"]);
+
+        assert!(
+            print_deployments(&[create_test_deployment()], &OutputFormat::NsName.into()).is_ok()
+        );
+    }
+
     fn create_test_service() -> ServiceInfo {
         ServiceInfo {
             name: "test-service".to_string(),
@@ -1281,6 +7438,128 @@ mod tests {
             cluster_ip: Some("10.0.0.1".to_string()),
             ports: vec![],
             selector: Some(std::collections::BTreeMap::new()),
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_json_deterministic_and_compact() {
+        let mut first = create_test_deployment();
+        let mut second = create_test_deployment();
+        for (key, value) in [("tier", "web"), ("env", "prod"), ("team", "shop")] {
+            first.labels.insert(key.to_string(), value.to_string());
         }
+        for (key, value) in [("team", "shop"), ("env", "prod"), ("tier", "web")] {
+            second.labels.insert(key.to_string(), value.to_string());
+        }
+
+        let pretty = serialize_json(&first, false).unwrap();
+        assert_eq!(pretty, serialize_json(&second, false).unwrap());
+        assert!(pretty.find("\"env\"").unwrap() < pretty.find("\"tier\"").unwrap());
+
+        let compact = serialize_json(&[first, second], true).unwrap();
+        assert!(!compact.contains('\n'));
+        let items: Vec<DeploymentInfo> = serde_json::from_str(&compact).unwrap();
+        assert_eq!(items[0].labels, items[1].labels);
+    }
+
+    #[test]
+    fn test_registered_renderer() {
+        struct Count;
+        impl Renderer for Count {
+            fn render(&self, document: &Document<'_>) -> Result<String> {
+                let value = document.to_value()?;
+                Ok(line_items(&value).len().to_string())
+            }
+        }
+
+        let format = Output::from(OutputFormat::Custom("count"));
+        assert!(renderer(&format).is_err());
+        register_renderer(format.format.clone(), Count);
+        let services = vec![create_test_service(), create_test_service()];
+        let document = Document::new(&services);
+        assert_eq!(renderer(&format).unwrap().render(&document).unwrap(), "2");
+        assert!(print_services(&services, &format).is_ok());
+
+        // Built-in formats share the trait; JSON keeps field order
+        let json = renderer(&OutputFormat::Json.into())
+            .unwrap()
+            .render(&document)
+            .unwrap();
+        assert!(json.find("\"ports\"").unwrap() < json.find("\"cluster_ip\"").unwrap());
+        let compact = Output {
+            json_compact: true,
+            ..OutputFormat::Json.into()
+        };
+        let line = renderer(&compact).unwrap().render(&document).unwrap();
+        assert!(!line.contains('\n') && line.len() < json.len());
+        let names = renderer(&OutputFormat::NsName.into())
+            .unwrap()
+            .render(&document)
+            .unwrap();
+        assert_eq!(names, "default/test-service\ndefault/test-service");
+
+        // Parsed templates travel with the format, so two can be used in one run
+        let jsonpath = |template: &str| {
+            renderer(&OutputFormat::JsonPath(JsonPath::parse(template).unwrap()).into())
+                .unwrap()
+                .render(&document)
+                .unwrap()
+        };
+        assert_eq!(jsonpath("{[0].name}"), "test-service");
+        assert_eq!(jsonpath("{[1].namespace}"), "default");
+        let columns = OutputFormat::Columns(vec![Column::parse("NAME:name").unwrap()]).into();
+        let table = renderer(&columns).unwrap().render(&document).unwrap();
+        assert!(table.contains("NAME") && table.contains("test-service"));
+    }
+
+    #[test]
+    fn test_table_csv() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(strip_ansi("\x1b[1;32mRunning\x1b[0m"), "Running");
+
+        let csv = table_csv(&deployments_table(&[create_test_deployment()]));
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("NAME,NAMESPACE,"));
+        assert!(lines[1].starts_with("test-deployment,default,"));
+
+        // An empty result is the header alone
+        let csv = table_csv(&deployments_table(&[]));
+        assert!(csv.starts_with("NAME,NAMESPACE,"));
+        assert!(!csv.contains("\r\n"));
+        let empty = CsvRenderer.render(&Document::new(&Vec::<DeploymentInfo>::new()));
+        assert_eq!(empty.unwrap(), "");
+    }
+
+    #[test]
+    fn test_sections_table() {
+        let all = AllResources {
+            deployments: vec![create_test_deployment()],
+            statefulsets: vec![create_test_statefulset()],
+            ..Default::default()
+        };
+        let csv = table_csv(&sections_table("KIND", all_sections(&all)));
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+
+        // Columns of every kind, shared ones once, whether or not the kind
+        // has objects
+        let header: Vec<&str> = lines[0].split(',').collect();
+        assert_eq!(header[..3], ["KIND", "NAME", "NAMESPACE"]);
+        assert!(header.contains(&"TYPE") && header.contains(&"STRATEGY"));
+        assert_eq!(header.iter().filter(|h| **h == "NAME").count(), 1);
+        assert!(lines[1].starts_with("Deployments,test-deployment,default,"));
+        assert!(lines[2].starts_with("StatefulSets,test-statefulset,default,"));
+        assert!(lines.iter().all(|l| l.split(',').count() == header.len()));
+
+        let empty = table_csv(&sections_table(
+            "KIND",
+            all_sections(&AllResources::default()),
+        ));
+        assert_eq!(empty, lines[0]);
     }
 }
@@ -0,0 +1,259 @@
+//! Upward ownership chain lookup and management tool detection
+
+use crate::error::{ExplorerError, Result};
+use crate::resource::ObjectRef;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use kube::api::{ApiResource, DynamicObject};
+use kube::core::GroupVersion;
+use kube::discovery::{pinned_kind, Scope};
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Safety limit on chain length in case of ownership cycles
+const MAX_DEPTH: usize = 16;
+
+/// One object in an ownership chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerLink {
+    pub api_version: String,
+    pub kind: String,
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
+/// Deployment tool managing an object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manager {
+    pub tool: String,
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
+/// Ownership chain from the starting object up to its top-level owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipChain {
+    pub chain: Vec<OwnerLink>,
+    pub managed_by: Option<Manager>,
+}
+
+/// Walk ownerReferences from `start` upwards, preferring the controller reference
+pub async fn ownership_chain(
+    client: &Client,
+    start: &ObjectRef,
+    namespace: &str,
+) -> Result<OwnershipChain> {
+    ownership_chain_of(client, &start.kind.api_resource(), &start.name, namespace).await
+}
+
+/// Follow owner references upwards from the namespaced object of any
+/// `resource` kind named `name`
+pub async fn ownership_chain_of(
+    client: &Client,
+    resource: &ApiResource,
+    name: &str,
+    namespace: &str,
+) -> Result<OwnershipChain> {
+    let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, resource);
+    let mut object = api
+        .get_opt(name)
+        .await?
+        .ok_or_else(|| ExplorerError::ResourceNotFound {
+            kind: resource.kind.clone(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+        })?;
+    let mut current = link(resource, &object);
+    let mut chain = Vec::new();
+
+    loop {
+        let owner = next_owner(&object);
+        chain.push(current);
+
+        let Some(owner) = owner else {
+            break;
+        };
+        if chain.len() >= MAX_DEPTH {
+            break;
+        }
+
+        let gvk = owner
+            .api_version
+            .parse::<GroupVersion>()
+            .map_err(|e| ExplorerError::InvalidArgument(e.to_string()))?
+            .with_kind(&owner.kind);
+        let (resource, capabilities) = pinned_kind(client, &gvk).await?;
+        let api: Api<DynamicObject> = match capabilities.scope {
+            Scope::Namespaced => Api::namespaced_with(client.clone(), namespace, &resource),
+            Scope::Cluster => Api::all_with(client.clone(), &resource),
+        };
+
+        match api.get_opt(&owner.name).await? {
+            Some(found) => {
+                object = found;
+                current = link(&resource, &object);
+            }
+            None => {
+                // The owner was deleted; report it and stop
+                chain.push(OwnerLink {
+                    api_version: owner.api_version.clone(),
+                    kind: format!("{} (not found)", owner.kind),
+                    name: owner.name.clone(),
+                    namespace: match capabilities.scope {
+                        Scope::Namespaced => Some(namespace.to_string()),
+                        Scope::Cluster => None,
+                    },
+                });
+                break;
+            }
+        }
+    }
+
+    let managed_by = detect_manager(
+        object.metadata.labels.as_ref().unwrap_or(&BTreeMap::new()),
+        object
+            .metadata
+            .annotations
+            .as_ref()
+            .unwrap_or(&BTreeMap::new()),
+    );
+
+    Ok(OwnershipChain { chain, managed_by })
+}
+
+fn link(resource: &ApiResource, object: &DynamicObject) -> OwnerLink {
+    OwnerLink {
+        api_version: resource.api_version.clone(),
+        kind: resource.kind.clone(),
+        name: object.metadata.name.clone().unwrap_or_default(),
+        namespace: object.metadata.namespace.clone(),
+    }
+}
+
+/// The controller owner reference, or the first owner if none is marked as controller
+fn next_owner(object: &DynamicObject) -> Option<OwnerReference> {
+    let owners = object.metadata.owner_references.as_ref()?;
+    owners
+        .iter()
+        .find(|o| o.controller == Some(true))
+        .or_else(|| owners.first())
+        .cloned()
+}
+
+/// Detect the Helm release, Argo CD application or Flux object managing a resource
+pub fn detect_manager(
+    labels: &BTreeMap<String, String>,
+    annotations: &BTreeMap<String, String>,
+) -> Option<Manager> {
+    if let Some(release) = annotations.get("meta.helm.sh/release-name") {
+        return Some(Manager {
+            tool: "Helm".to_string(),
+            name: release.clone(),
+            namespace: annotations.get("meta.helm.sh/release-namespace").cloned(),
+        });
+    }
+
+    // Annotation tracking ids look like "<app>:<group>/<kind>:<namespace>/<name>"
+    if let Some(tracking) = annotations.get("argocd.argoproj.io/tracking-id") {
+        let app = tracking.split(':').next().unwrap_or(tracking);
+        return Some(Manager {
+            tool: "Argo CD".to_string(),
+            name: app.to_string(),
+            namespace: None,
+        });
+    }
+    if let Some(app) = labels.get("argocd.argoproj.io/instance") {
+        return Some(Manager {
+            tool: "Argo CD".to_string(),
+            name: app.clone(),
+            namespace: None,
+        });
+    }
+
+    for (prefix, kind) in [
+        ("helm.toolkit.fluxcd.io", "HelmRelease"),
+        ("kustomize.toolkit.fluxcd.io", "Kustomization"),
+    ] {
+        if let Some(name) = labels.get(&format!("{}/name", prefix)) {
+            return Some(Manager {
+                tool: format!("Flux {}", kind),
+                name: name.clone(),
+                namespace: labels.get(&format!("{}/namespace", prefix)).cloned(),
+            });
+        }
+    }
+
+    if labels
+        .get("app.kubernetes.io/managed-by")
+        .map(String::as_str)
+        == Some("Helm")
+    {
+        if let Some(instance) = labels.get("app.kubernetes.io/instance") {
+            return Some(Manager {
+                tool: "Helm".to_string(),
+                name: instance.clone(),
+                namespace: None,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_helm_release() {
+        let annotations = map(&[
+            ("meta.helm.sh/release-name", "payments"),
+            ("meta.helm.sh/release-namespace", "prod"),
+        ]);
+        assert_eq!(
+            detect_manager(&BTreeMap::new(), &annotations),
+            Some(Manager {
+                tool: "Helm".to_string(),
+                name: "payments".to_string(),
+                namespace: Some("prod".to_string()),
+            })
+        );
+
+        let labels = map(&[
+            ("app.kubernetes.io/managed-by", "Helm"),
+            ("app.kubernetes.io/instance", "redis"),
+        ]);
+        assert_eq!(
+            detect_manager(&labels, &BTreeMap::new()).map(|m| m.name),
+            Some("redis".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_argocd_and_flux() {
+        let annotations = map(&[(
+            "argocd.argoproj.io/tracking-id",
+            "shop:apps/Deployment:prod/web",
+        )]);
+        let manager = detect_manager(&BTreeMap::new(), &annotations).unwrap();
+        assert_eq!(manager.tool, "Argo CD");
+        assert_eq!(manager.name, "shop");
+
+        let labels = map(&[
+            ("kustomize.toolkit.fluxcd.io/name", "apps"),
+            ("kustomize.toolkit.fluxcd.io/namespace", "flux-system"),
+        ]);
+        let manager = detect_manager(&labels, &BTreeMap::new()).unwrap();
+        assert_eq!(manager.tool, "Flux Kustomization");
+        assert_eq!(manager.namespace.as_deref(), Some("flux-system"));
+
+        assert_eq!(detect_manager(&BTreeMap::new(), &BTreeMap::new()), None);
+    }
+}
@@ -0,0 +1,284 @@
+//! Container-level detail for `kdx describe pod/<name>`
+
+use k8s_openapi::api::core::v1::{
+    Container, ContainerState, ContainerStatus, Pod, Probe, ResourceRequirements, Volume,
+};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One container of a pod with its spec and current status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerDetail {
+    pub name: String,
+    pub init: bool,
+    pub image: String,
+    /// e.g. "Running", "Waiting (CrashLoopBackOff)", "Terminated (Error, exit 1)"
+    pub state: String,
+    pub ready: bool,
+    pub restart_count: i32,
+    /// How the previous instance ended, e.g. "OOMKilled, exit 137 at 2024-05-01 12:00:00 UTC"
+    pub last_termination: Option<String>,
+    pub requests: BTreeMap<String, String>,
+    pub limits: BTreeMap<String, String>,
+    /// Mount path and its source, e.g. "/data <- pvc/data-db-0 (ro)"
+    pub mounts: Vec<String>,
+    /// Probe type and check, e.g. "liveness: http-get :8080/healthz every 10s"
+    pub probes: Vec<String>,
+}
+
+/// Details of the init and app containers of a pod, init containers first
+pub fn container_details(pod: &Pod) -> Vec<ContainerDetail> {
+    let Some(spec) = &pod.spec else {
+        return Vec::new();
+    };
+    let status = pod.status.as_ref();
+    let volumes = spec.volumes.as_deref().unwrap_or_default();
+
+    let init = spec.init_containers.iter().flatten().map(|c| {
+        let statuses = status.and_then(|s| s.init_container_statuses.as_deref());
+        detail(c, true, find_status(statuses, &c.name), volumes)
+    });
+    let app = spec.containers.iter().map(|c| {
+        let statuses = status.and_then(|s| s.container_statuses.as_deref());
+        detail(c, false, find_status(statuses, &c.name), volumes)
+    });
+    init.chain(app).collect()
+}
+
+fn find_status<'a>(
+    statuses: Option<&'a [ContainerStatus]>,
+    name: &str,
+) -> Option<&'a ContainerStatus> {
+    statuses?.iter().find(|s| s.name == name)
+}
+
+fn detail(
+    container: &Container,
+    init: bool,
+    status: Option<&ContainerStatus>,
+    volumes: &[Volume],
+) -> ContainerDetail {
+    let (requests, limits) = resources(container.resources.as_ref());
+    let probes = [
+        ("startup", &container.startup_probe),
+        ("readiness", &container.readiness_probe),
+        ("liveness", &container.liveness_probe),
+    ]
+    .into_iter()
+    .filter_map(|(kind, probe)| {
+        probe
+            .as_ref()
+            .map(|p| format!("{}: {}", kind, describe_probe(p)))
+    })
+    .collect();
+
+    ContainerDetail {
+        name: container.name.clone(),
+        init,
+        image: container.image.clone().unwrap_or_default(),
+        state: status
+            .and_then(|s| s.state.as_ref())
+            .map_or_else(|| "Unknown".to_string(), describe_state),
+        ready: status.is_some_and(|s| s.ready),
+        restart_count: status.map_or(0, |s| s.restart_count),
+        last_termination: status
+            .and_then(|s| s.last_state.as_ref())
+            .and_then(|s| s.terminated.as_ref())
+            .map(|t| {
+                let reason = t.reason.as_deref().unwrap_or("Terminated");
+                match &t.finished_at {
+                    Some(at) => format!(
+                        "{}, exit {} at {}",
+                        reason,
+                        t.exit_code,
+                        at.0.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    None => format!("{}, exit {}", reason, t.exit_code),
+                }
+            }),
+        requests,
+        limits,
+        mounts: container
+            .volume_mounts
+            .iter()
+            .flatten()
+            .map(|m| {
+                let source = volumes
+                    .iter()
+                    .find(|v| v.name == m.name)
+                    .map_or_else(|| m.name.clone(), volume_source);
+                let read_only = if m.read_only == Some(true) {
+                    " (ro)"
+                } else {
+                    ""
+                };
+                format!("{} <- {}{}", m.mount_path, source, read_only)
+            })
+            .collect(),
+        probes,
+    }
+}
+
+fn resources(
+    resources: Option<&ResourceRequirements>,
+) -> (BTreeMap<String, String>, BTreeMap<String, String>) {
+    let to_strings = |map: Option<&BTreeMap<String, Quantity>>| {
+        map.into_iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.0.clone()))
+            .collect()
+    };
+    (
+        to_strings(resources.and_then(|r| r.requests.as_ref())),
+        to_strings(resources.and_then(|r| r.limits.as_ref())),
+    )
+}
+
+fn describe_state(state: &ContainerState) -> String {
+    if state.running.is_some() {
+        "Running".to_string()
+    } else if let Some(waiting) = &state.waiting {
+        match &waiting.reason {
+            Some(reason) => format!("Waiting ({})", reason),
+            None => "Waiting".to_string(),
+        }
+    } else if let Some(terminated) = &state.terminated {
+        let reason = terminated.reason.as_deref().unwrap_or("Terminated");
+        format!("Terminated ({}, exit {})", reason, terminated.exit_code)
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+/// Short description of where a volume comes from, e.g. "configmap/app-config"
+fn volume_source(volume: &Volume) -> String {
+    if let Some(pvc) = &volume.persistent_volume_claim {
+        format!("pvc/{}", pvc.claim_name)
+    } else if let Some(cm) = &volume.config_map {
+        format!("configmap/{}", cm.name.as_deref().unwrap_or(&volume.name))
+    } else if let Some(secret) = &volume.secret {
+        format!(
+            "secret/{}",
+            secret.secret_name.as_deref().unwrap_or(&volume.name)
+        )
+    } else if volume.empty_dir.is_some() {
+        format!("emptyDir/{}", volume.name)
+    } else if let Some(host) = &volume.host_path {
+        format!("hostPath {}", host.path)
+    } else if volume.projected.is_some() {
+        format!("projected/{}", volume.name)
+    } else {
+        volume.name.clone()
+    }
+}
+
+/// Describe what a probe checks and how often, e.g. "tcp :5432 every 10s"
+pub fn describe_probe(probe: &Probe) -> String {
+    let port = |port: &IntOrString| match port {
+        IntOrString::Int(p) => p.to_string(),
+        IntOrString::String(s) => s.clone(),
+    };
+
+    let check = if let Some(http) = &probe.http_get {
+        format!(
+            "http-get :{}{}",
+            port(&http.port),
+            http.path.as_deref().unwrap_or("/")
+        )
+    } else if let Some(tcp) = &probe.tcp_socket {
+        format!("tcp :{}", port(&tcp.port))
+    } else if let Some(grpc) = &probe.grpc {
+        format!("grpc :{}", grpc.port)
+    } else if let Some(exec) = &probe.exec {
+        format!(
+            "exec {}",
+            exec.command
+                .iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    } else {
+        "unknown".to_string()
+    };
+
+    format!("{} every {}s", check, probe.period_seconds.unwrap_or(10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_details() {
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "db-0", "namespace": "data"},
+            "spec": {
+                "initContainers": [{"name": "init-perms", "image": "busybox"}],
+                "containers": [{
+                    "name": "postgres",
+                    "image": "postgres:16",
+                    "resources": {"requests": {"memory": "1Gi"}, "limits": {"memory": "2Gi"}},
+                    "volumeMounts": [
+                        {"name": "data", "mountPath": "/var/lib/postgresql"},
+                        {"name": "config", "mountPath": "/etc/postgres", "readOnly": true},
+                    ],
+                    "livenessProbe": {"tcpSocket": {"port": 5432}, "periodSeconds": 20},
+                    "readinessProbe": {"httpGet": {"port": "metrics", "path": "/ready"}},
+                }],
+                "volumes": [
+                    {"name": "data", "persistentVolumeClaim": {"claimName": "data-db-0"}},
+                    {"name": "config", "configMap": {"name": "pg-config"}},
+                ],
+            },
+            "status": {
+                "initContainerStatuses": [{
+                    "name": "init-perms", "image": "busybox", "imageID": "", "ready": true,
+                    "restartCount": 0,
+                    "state": {"terminated": {"exitCode": 0, "reason": "Completed"}},
+                }],
+                "containerStatuses": [{
+                    "name": "postgres", "image": "postgres:16", "imageID": "", "ready": false,
+                    "restartCount": 3,
+                    "state": {"waiting": {"reason": "CrashLoopBackOff"}},
+                    "lastState": {"terminated": {
+                        "exitCode": 137, "reason": "OOMKilled",
+                        "finishedAt": "2024-05-01T12:00:00Z",
+                    }},
+                }],
+            },
+        }))
+        .unwrap();
+
+        let details = container_details(&pod);
+        assert_eq!(details.len(), 2);
+        assert!(details[0].init);
+        assert_eq!(details[0].state, "Terminated (Completed, exit 0)");
+
+        let postgres = &details[1];
+        assert_eq!(postgres.state, "Waiting (CrashLoopBackOff)");
+        assert_eq!(postgres.restart_count, 3);
+        assert_eq!(
+            postgres.last_termination.as_deref(),
+            Some("OOMKilled, exit 137 at 2024-05-01 12:00:00 UTC")
+        );
+        assert_eq!(postgres.limits["memory"], "2Gi");
+        assert_eq!(
+            postgres.mounts,
+            vec![
+                "/var/lib/postgresql <- pvc/data-db-0",
+                "/etc/postgres <- configmap/pg-config (ro)"
+            ]
+        );
+        assert_eq!(
+            postgres.probes,
+            vec![
+                "readiness: http-get :metrics/ready every 10s",
+                "liveness: tcp :5432 every 20s"
+            ]
+        );
+    }
+}
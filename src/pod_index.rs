@@ -0,0 +1,273 @@
+//! Index of pods shared by association passes
+//!
+//! ConfigMap, Secret, Service and claim associations all come down to
+//! questions about the same pods: which pods reference or mount this object,
+//! which pods does this selector match. Listing pods once per run and indexing them answers
+//! every pass from memory instead of re-listing pods for each one.
+
+use crate::discovery::{
+    pod_workload, ConfigMapInfo, LazyConvert, PodInfo, ResourceReference, SecretInfo,
+};
+use crate::references::{pod_config_references, ConfigKind, PodConfigReference};
+use crate::storage;
+use k8s_openapi::api::core::v1::Pod;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A pod with what association passes need from its spec
+#[derive(Debug, Clone)]
+pub struct IndexedPod {
+    pub info: PodInfo,
+    /// Kind and name of the workload owning the pod
+    pub workload: (String, String),
+    pub references: Vec<PodConfigReference>,
+    /// Persistent volume claims the pod mounts
+    pub claims: Vec<String>,
+}
+
+/// Pods of one listing, indexed by the ConfigMaps and Secrets they reference
+#[derive(Debug, Default)]
+pub struct PodIndex {
+    pods: Vec<IndexedPod>,
+    /// Positions in `pods` of the pods referencing each (kind, namespace, name)
+    by_reference: HashMap<(ConfigKind, String, String), Vec<usize>>,
+}
+
+impl PodIndex {
+    /// Index full pod objects, including the references in their specs
+    pub fn from_pods(pods: &[Pod]) -> Self {
+        let mut index = Self::default();
+        for pod in pods {
+            let Some(info) = pod.lazy_convert() else {
+                continue;
+            };
+            let references = pod
+                .spec
+                .as_ref()
+                .map(pod_config_references)
+                .unwrap_or_default();
+            index.push(info, references, storage::pod_claims(pod));
+        }
+        index
+    }
+
+    /// Index pods known only by their summaries, e.g. from a snapshot; they
+    /// reference and mount nothing
+    pub fn from_infos(pods: Vec<PodInfo>) -> Self {
+        let mut index = Self::default();
+        for info in pods {
+            index.push(info, Vec::new(), Vec::new());
+        }
+        index
+    }
+
+    fn push(&mut self, info: PodInfo, references: Vec<PodConfigReference>, claims: Vec<String>) {
+        let position = self.pods.len();
+        for reference in &references {
+            let key = (
+                reference.kind,
+                info.namespace.clone(),
+                reference.name.clone(),
+            );
+            let positions = self.by_reference.entry(key).or_default();
+            if positions.last() != Some(&position) {
+                positions.push(position);
+            }
+        }
+        self.pods.push(IndexedPod {
+            workload: pod_workload(&info),
+            info,
+            references,
+            claims,
+        });
+    }
+
+    pub fn pods(&self) -> &[IndexedPod] {
+        &self.pods
+    }
+
+    pub fn len(&self) -> usize {
+        self.pods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pods.is_empty()
+    }
+
+    /// Pods in `namespace` whose labels match every pair of `selector`. An
+    /// empty selector matches nothing, as for Services.
+    pub fn selected_by<'a>(
+        &'a self,
+        namespace: &'a str,
+        selector: &'a BTreeMap<String, String>,
+    ) -> impl Iterator<Item = &'a PodInfo> + 'a {
+        self.pods.iter().map(|p| &p.info).filter(move |p| {
+            !selector.is_empty()
+                && p.namespace == namespace
+                && selector.iter().all(|(k, v)| p.labels.get(k) == Some(v))
+        })
+    }
+
+    /// Pods mounting each claim, for the pods in `namespace` (all pods
+    /// when `None`)
+    pub fn claim_mounts(&self, namespace: Option<&str>) -> storage::ClaimMounts {
+        let mut mounts = storage::ClaimMounts::new();
+        for pod in &self.pods {
+            if namespace.is_some_and(|ns| ns != pod.info.namespace) {
+                continue;
+            }
+            for claim in &pod.claims {
+                mounts
+                    .entry((pod.info.namespace.clone(), claim.clone()))
+                    .or_default()
+                    .push(pod.info.name.clone());
+            }
+        }
+        mounts
+    }
+
+    /// Fill `used_by` and `mount_paths` of the object at `key`. A pod is
+    /// listed once per way it uses the object.
+    fn attach(
+        &self,
+        key: (ConfigKind, String, String),
+        used_by: &mut Vec<ResourceReference>,
+        mount_paths: &mut Vec<String>,
+    ) {
+        let mut paths = BTreeSet::new();
+        let (kind, _, name) = &key;
+        for &position in self.by_reference.get(&key).into_iter().flatten() {
+            let pod = &self.pods[position];
+            let uses = pod
+                .references
+                .iter()
+                .filter(|r| r.kind == *kind && r.name == *name);
+            for reference in uses {
+                let known = used_by.iter().any(|r| {
+                    r.kind == "Pod"
+                        && r.namespace == pod.info.namespace
+                        && r.name == pod.info.name
+                        && r.reference_type == reference.reference_type
+                });
+                if !known {
+                    used_by.push(ResourceReference {
+                        kind: "Pod".to_string(),
+                        name: pod.info.name.clone(),
+                        namespace: pod.info.namespace.clone(),
+                        reference_type: reference.reference_type.clone(),
+                    });
+                }
+                paths.extend(reference.mount_path.clone());
+            }
+        }
+        *mount_paths = paths.into_iter().collect();
+    }
+
+    /// Record the pods using each ConfigMap
+    pub fn attach_configmaps(&self, configmaps: &mut [ConfigMapInfo]) {
+        for cm in configmaps {
+            let key = (ConfigKind::ConfigMap, cm.namespace.clone(), cm.name.clone());
+            self.attach(key, &mut cm.used_by, &mut cm.mount_paths);
+        }
+    }
+
+    /// Record the pods using each Secret
+    pub fn attach_secrets(&self, secrets: &mut [SecretInfo]) {
+        for secret in secrets {
+            let key = (
+                ConfigKind::Secret,
+                secret.namespace.clone(),
+                secret.name.clone(),
+            );
+            self.attach(key, &mut secret.used_by, &mut secret.mount_paths);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ReferenceType;
+
+    #[test]
+    fn test_pod_index() {
+        let pod = |name: &str, namespace: &str, app: &str| -> Pod {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {
+                    "name": name,
+                    "namespace": namespace,
+                    "labels": {"app": app},
+                    "ownerReferences": [{
+                        "apiVersion": "apps/v1", "kind": "ReplicaSet", "name": format!("{}-5d4f8", app),
+                        "uid": "1", "controller": true,
+                    }],
+                },
+                "spec": {
+                    "containers": [{
+                        "name": app,
+                        "envFrom": [{"configMapRef": {"name": "shared"}}],
+                        "volumeMounts": [{"name": "config", "mountPath": "/etc/app"}],
+                    }],
+                    "volumes": [{"name": "config", "configMap": {"name": "shared"}}],
+                },
+            }))
+            .unwrap()
+        };
+        let index = PodIndex::from_pods(&[
+            pod("web-1", "shop", "web"),
+            pod("api-1", "shop", "api"),
+            pod("web-1", "staging", "web"),
+        ]);
+        assert_eq!(index.len(), 3);
+
+        let selector: BTreeMap<String, String> = [("app".to_string(), "web".to_string())].into();
+        let selected: Vec<&str> = index
+            .selected_by("shop", &selector)
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(selected, ["web-1"]);
+        assert_eq!(index.selected_by("shop", &BTreeMap::new()).count(), 0);
+
+        let mut configmaps: Vec<ConfigMapInfo> = vec![serde_json::from_value(serde_json::json!({
+            "name": "shared", "namespace": "shop", "data_keys": [], "age": "1d",
+            "labels": {}, "used_by": [], "mount_paths": [],
+        }))
+        .unwrap()];
+        index.attach_configmaps(&mut configmaps);
+        let used_by: Vec<(&str, ReferenceType)> = configmaps[0]
+            .used_by
+            .iter()
+            .map(|r| (r.name.as_str(), r.reference_type.clone()))
+            .collect();
+        assert_eq!(
+            used_by,
+            [
+                ("web-1", ReferenceType::VolumeMount),
+                ("web-1", ReferenceType::EnvironmentFrom),
+                ("api-1", ReferenceType::VolumeMount),
+                ("api-1", ReferenceType::EnvironmentFrom),
+            ]
+        );
+        assert_eq!(configmaps[0].mount_paths, ["/etc/app"]);
+        assert_eq!(index.pods()[0].workload.0, "ReplicaSet");
+    }
+
+    #[test]
+    fn test_claim_mounts() {
+        let pod = |name: &str, namespace: &str| -> Pod {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": name, "namespace": namespace},
+                "spec": {
+                    "containers": [{"name": "db"}],
+                    "volumes": [{"name": "data", "persistentVolumeClaim": {"claimName": "data"}}],
+                },
+            }))
+            .unwrap()
+        };
+        let index = PodIndex::from_pods(&[pod("db-0", "shop"), pod("db-0", "staging")]);
+
+        let mounts = index.claim_mounts(Some("shop"));
+        assert_eq!(mounts.len(), 1);
+        assert_eq!(mounts[&("shop".to_string(), "data".to_string())], ["db-0"]);
+        assert_eq!(index.claim_mounts(None).len(), 2);
+    }
+}
@@ -0,0 +1,304 @@
+//! Port forwarding to a service through one of its backing pods
+//!
+//! The service's selector is resolved to its pods, ready pods first, and
+//! local connections are forwarded to the pod's target port through the API
+//! server. When a forward to the pod fails, for example because the pod was
+//! deleted, the next connection is sent to another backing pod.
+
+use crate::error::{ExplorerError, Result};
+use k8s_openapi::api::core::v1::{Pod, Service, ServicePort};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::api::ListParams;
+use kube::{Api, Client};
+use std::collections::HashSet;
+use std::str::FromStr;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Local port and service port to forward, e.g. "8080:80"; a single port is
+/// used on both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMapping {
+    pub local: u16,
+    pub remote: u16,
+}
+
+impl FromStr for PortMapping {
+    type Err = ExplorerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let port = |value: &str| {
+            value.parse::<u16>().map_err(|_| {
+                ExplorerError::InvalidArgument(format!(
+                    "invalid port '{}' in '{}' (expected [LOCAL:]REMOTE)",
+                    value, s
+                ))
+            })
+        };
+        match s.split_once(':') {
+            Some((local, remote)) => Ok(PortMapping {
+                local: port(local)?,
+                remote: port(remote)?,
+            }),
+            None => {
+                let port = port(s)?;
+                Ok(PortMapping {
+                    local: port,
+                    remote: port,
+                })
+            }
+        }
+    }
+}
+
+/// Pods that can take a forward, ready pods first, then by name. Pods that
+/// are not running or are being deleted are left out.
+pub fn candidate_pods(pods: &[Pod]) -> Vec<&Pod> {
+    let mut candidates: Vec<&Pod> = pods
+        .iter()
+        .filter(|pod| pod.metadata.deletion_timestamp.is_none())
+        .filter(|pod| {
+            pod.status
+                .as_ref()
+                .and_then(|s| s.phase.as_deref())
+                .is_some_and(|phase| phase == "Running")
+        })
+        .collect();
+    candidates.sort_by_key(|pod| (!is_ready(pod), pod.metadata.name.clone()));
+    candidates
+}
+
+fn is_ready(pod: &Pod) -> bool {
+    pod.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .is_some_and(|conditions| {
+            conditions
+                .iter()
+                .any(|c| c.type_ == "Ready" && c.status == "True")
+        })
+}
+
+/// Container port of `pod` that `port` of the service sends traffic to,
+/// resolving named target ports against the pod's containers
+pub fn target_port(port: &ServicePort, pod: &Pod) -> Option<u16> {
+    let number = match &port.target_port {
+        None => port.port,
+        Some(IntOrString::Int(number)) => *number,
+        Some(IntOrString::String(name)) => {
+            pod.spec
+                .as_ref()?
+                .containers
+                .iter()
+                .flat_map(|c| c.ports.iter().flatten())
+                .find(|p| p.name.as_deref() == Some(name))?
+                .container_port
+        }
+    };
+    u16::try_from(number).ok()
+}
+
+/// Forward `mapping.local` on 127.0.0.1 to service port `mapping.remote` (the
+/// first service port when not given) until Ctrl-C is pressed
+pub async fn port_forward(
+    client: &Client,
+    service: &str,
+    namespace: &str,
+    mapping: Option<PortMapping>,
+) -> Result<()> {
+    let svc = Api::<Service>::namespaced(client.clone(), namespace)
+        .get(service)
+        .await?;
+    let spec = svc.spec.unwrap_or_default();
+    let selector = spec.selector.unwrap_or_default();
+    if selector.is_empty() {
+        return Err(ExplorerError::InvalidArgument(format!(
+            "service {} has no selector, so there are no pods to forward to",
+            service
+        )));
+    }
+    let ports = spec.ports.unwrap_or_default();
+    let service_port = match mapping {
+        Some(mapping) => ports.iter().find(|p| p.port == i32::from(mapping.remote)),
+        None => ports.first(),
+    }
+    .cloned()
+    .ok_or_else(|| {
+        let available: Vec<String> = ports.iter().map(|p| p.port.to_string()).collect();
+        ExplorerError::InvalidArgument(format!(
+            "service {} has no port {} (ports: {})",
+            service,
+            mapping.map_or_else(String::new, |m| m.remote.to_string()),
+            available.join(", ")
+        ))
+    })?;
+    let local = mapping.map_or(service_port.port as u16, |m| m.local);
+
+    let selector = selector
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",");
+    let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+
+    let listener = TcpListener::bind(("127.0.0.1", local)).await?;
+    eprintln!(
+        "Forwarding from 127.0.0.1:{} to service {}/{} port {}",
+        local, namespace, service, service_port.port
+    );
+
+    // Pods whose forwards failed, reported by connection tasks
+    let (failed_tx, mut failed_rx) = mpsc::unbounded_channel::<String>();
+    let mut current: Option<(String, u16)> = None;
+
+    loop {
+        let (connection, _) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        while let Ok(pod) = failed_rx.try_recv() {
+            if current.as_ref().is_some_and(|(name, _)| *name == pod) {
+                current = None;
+            }
+        }
+
+        // Try the current pod, then every other candidate once
+        let mut tried = HashSet::new();
+        let forwarder = loop {
+            let (pod, port) = match current.clone() {
+                Some(target) => target,
+                None => {
+                    let list = match pods.list(&ListParams::default().labels(&selector)).await {
+                        Ok(list) => list,
+                        Err(e) => {
+                            eprintln!("Could not list pods of service {}: {}", service, e);
+                            break None;
+                        }
+                    };
+                    let next = candidate_pods(&list.items).into_iter().find_map(|pod| {
+                        let name = pod.metadata.name.clone()?;
+                        let port = target_port(&service_port, pod)?;
+                        (!tried.contains(&name)).then_some((name, port))
+                    });
+                    let Some(target) = next else {
+                        break None;
+                    };
+                    eprintln!("Forwarding through pod {} port {}", target.0, target.1);
+                    current = Some(target.clone());
+                    target
+                }
+            };
+
+            match pods.portforward(&pod, &[port]).await {
+                Ok(forwarder) => break Some((forwarder, pod, port)),
+                Err(e) => {
+                    eprintln!("Pod {} unavailable ({}); trying another pod", pod, e);
+                    tried.insert(pod);
+                    current = None;
+                }
+            }
+        };
+
+        match forwarder {
+            Some((forwarder, pod, port)) => {
+                let failed_tx = failed_tx.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = forward(connection, forwarder, port).await {
+                        eprintln!("Forward through pod {} failed: {}", pod, e);
+                        let _ = failed_tx.send(pod);
+                    }
+                });
+            }
+            None => eprintln!(
+                "No pod behind service {}/{} accepted the forward; connection dropped",
+                namespace, service
+            ),
+        }
+    }
+}
+
+/// Copy one local connection to and from a pod port
+async fn forward(
+    mut connection: TcpStream,
+    mut forwarder: kube::api::Portforwarder,
+    port: u16,
+) -> Result<()> {
+    let mut upstream = forwarder
+        .take_stream(port)
+        .ok_or_else(|| std::io::Error::other(format!("no stream for port {}", port)))?;
+    let error = forwarder.take_error(port);
+
+    tokio::io::copy_bidirectional(&mut connection, &mut upstream).await?;
+    drop(upstream);
+    if let Some(error) = error {
+        if let Some(message) = error.await {
+            return Err(std::io::Error::other(message).into());
+        }
+    }
+    forwarder
+        .join()
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(name: &str, phase: &str, ready: bool) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": name},
+            "spec": {"containers": [{
+                "name": "app",
+                "ports": [{"name": "http", "containerPort": 8080}],
+            }]},
+            "status": {
+                "phase": phase,
+                "conditions": [{"type": "Ready", "status": if ready { "True" } else { "False" }}],
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_port_mapping() {
+        assert_eq!(
+            "8080:80".parse::<PortMapping>().unwrap(),
+            PortMapping {
+                local: 8080,
+                remote: 80
+            }
+        );
+        assert_eq!("5432".parse::<PortMapping>().unwrap().local, 5432);
+        assert!("http".parse::<PortMapping>().is_err());
+        assert!("70000:80".parse::<PortMapping>().is_err());
+    }
+
+    #[test]
+    fn test_candidate_pods_and_target_port() {
+        let pods = [
+            pod("web-a", "Running", false),
+            pod("web-b", "Running", true),
+            pod("web-c", "Pending", false),
+        ];
+        let names: Vec<_> = candidate_pods(&pods)
+            .iter()
+            .map(|p| p.metadata.name.clone().unwrap())
+            .collect();
+        assert_eq!(names, ["web-b", "web-a"]);
+
+        let named: ServicePort = serde_json::from_value(serde_json::json!({
+            "port": 80, "targetPort": "http",
+        }))
+        .unwrap();
+        assert_eq!(target_port(&named, &pods[0]), Some(8080));
+        let unset: ServicePort = serde_json::from_value(serde_json::json!({"port": 80})).unwrap();
+        assert_eq!(target_port(&unset, &pods[0]), Some(80));
+        let missing: ServicePort = serde_json::from_value(serde_json::json!({
+            "port": 80, "targetPort": "grpc",
+        }))
+        .unwrap();
+        assert_eq!(target_port(&missing, &pods[0]), None);
+    }
+}
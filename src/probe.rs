@@ -0,0 +1,293 @@
+//! Synthetic health checks across services (`kdx probe-matrix`)
+//!
+//! Every TCP port of each service is probed through a port-forward to one
+//! of its backing pods, chosen the same way as `kdx port-forward`. A TCP
+//! probe succeeds when the forward opens and the kubelet reports no error
+//! connecting to the pod port within a short grace period; an HTTP probe
+//! also sends a `GET` and reads the response status line. Services are
+//! probed concurrently, the ports of one service one after the other.
+
+use crate::error::Result;
+use crate::portforward::{candidate_pods, target_port};
+use futures::{stream, StreamExt};
+use k8s_openapi::api::core::v1::{Pod, Service};
+use kube::api::ListParams;
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Services probed at the same time
+const PROBE_CONCURRENCY: usize = 8;
+
+/// How long a TCP probe waits for the kubelet to report a failed connection
+const CONNECT_GRACE: Duration = Duration::from_millis(500);
+
+/// Outcome of probing one service port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeStatus {
+    Ok,
+    Failed,
+    Timeout,
+    NoPods,
+}
+
+impl fmt::Display for ProbeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeStatus::Ok => write!(f, "ok"),
+            ProbeStatus::Failed => write!(f, "failed"),
+            ProbeStatus::Timeout => write!(f, "timeout"),
+            ProbeStatus::NoPods => write!(f, "no pods"),
+        }
+    }
+}
+
+/// One cell of the matrix: a service port and how its probe went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeResult {
+    pub service: String,
+    pub namespace: String,
+    /// Service port; absent when the service could not be probed at all
+    pub port: Option<i32>,
+    pub pod: Option<String>,
+    pub status: ProbeStatus,
+    pub latency_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl ProbeResult {
+    fn unprobed(service: &str, namespace: &str, status: ProbeStatus, error: String) -> Self {
+        ProbeResult {
+            service: service.to_string(),
+            namespace: namespace.to_string(),
+            port: None,
+            pod: None,
+            status,
+            latency_ms: None,
+            http_status: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Status code of an HTTP response status line such as `HTTP/1.1 200 OK`
+pub fn parse_status_line(line: &str) -> Option<u16> {
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+/// Whether an HTTP status counts as healthy; anything the server answers
+/// below 500 means it is up, even a 404 for a path it does not serve
+pub fn http_healthy(status: u16) -> bool {
+    status < 500
+}
+
+/// Probe every TCP port of `services` in `namespace`, each within `timeout`.
+/// With `http_path`, an HTTP request for that path is sent as well.
+/// Results follow the order the services were given in.
+pub async fn probe_services(
+    client: &Client,
+    services: &[String],
+    namespace: &str,
+    timeout: Duration,
+    http_path: Option<&str>,
+) -> Result<Vec<ProbeResult>> {
+    let mut probed: Vec<(usize, Result<Vec<ProbeResult>>)> =
+        stream::iter(services.iter().enumerate())
+            .map(|(i, service)| async move {
+                let results = probe_service(client, service, namespace, timeout, http_path).await;
+                (i, results)
+            })
+            .buffer_unordered(PROBE_CONCURRENCY)
+            .collect()
+            .await;
+    probed.sort_by_key(|(i, _)| *i);
+
+    let mut results = Vec::new();
+    for (_, service_results) in probed {
+        results.extend(service_results?);
+    }
+    Ok(results)
+}
+
+async fn probe_service(
+    client: &Client,
+    service: &str,
+    namespace: &str,
+    timeout: Duration,
+    http_path: Option<&str>,
+) -> Result<Vec<ProbeResult>> {
+    let svc = match Api::<Service>::namespaced(client.clone(), namespace)
+        .get_opt(service)
+        .await?
+    {
+        Some(svc) => svc,
+        None => {
+            return Ok(vec![ProbeResult::unprobed(
+                service,
+                namespace,
+                ProbeStatus::Failed,
+                "service not found".to_string(),
+            )])
+        }
+    };
+    let spec = svc.spec.unwrap_or_default();
+    let selector = spec.selector.unwrap_or_default();
+    if selector.is_empty() {
+        return Ok(vec![ProbeResult::unprobed(
+            service,
+            namespace,
+            ProbeStatus::NoPods,
+            "service has no selector".to_string(),
+        )]);
+    }
+    let selector = selector
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let list = pods.list(&ListParams::default().labels(&selector)).await?;
+    let Some(pod) = candidate_pods(&list.items).into_iter().next() else {
+        return Ok(vec![ProbeResult::unprobed(
+            service,
+            namespace,
+            ProbeStatus::NoPods,
+            "no running pods behind the service".to_string(),
+        )]);
+    };
+    let pod_name = pod.metadata.name.clone().unwrap_or_default();
+
+    let mut results = Vec::new();
+    for port in spec.ports.unwrap_or_default() {
+        if port.protocol.as_deref().is_some_and(|p| p != "TCP") {
+            continue;
+        }
+        let mut result = ProbeResult {
+            service: service.to_string(),
+            namespace: namespace.to_string(),
+            port: Some(port.port),
+            pod: Some(pod_name.clone()),
+            status: ProbeStatus::Failed,
+            latency_ms: None,
+            http_status: None,
+            error: None,
+        };
+        let Some(pod_port) = target_port(&port, pod) else {
+            result.error = Some("target port not found on the pod".to_string());
+            results.push(result);
+            continue;
+        };
+
+        match tokio::time::timeout(timeout, probe_port(&pods, &pod_name, pod_port, http_path)).await
+        {
+            Ok(Ok((latency, http_status))) => {
+                result.latency_ms = Some(latency.as_millis() as u64);
+                result.http_status = http_status;
+                result.status = match http_status {
+                    Some(status) if !http_healthy(status) => ProbeStatus::Failed,
+                    _ => ProbeStatus::Ok,
+                };
+            }
+            Ok(Err(message)) => result.error = Some(message),
+            Err(_) => {
+                result.status = ProbeStatus::Timeout;
+                result.error = Some(format!("no answer within {:?}", timeout));
+            }
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Open a forward to `port` of `pod` and check it, returning the time until
+/// the forward opened, or until the response status line with `http_path`,
+/// and the HTTP status
+async fn probe_port(
+    pods: &Api<Pod>,
+    pod: &str,
+    port: u16,
+    http_path: Option<&str>,
+) -> std::result::Result<(Duration, Option<u16>), String> {
+    let started = Instant::now();
+    let mut forwarder = pods
+        .portforward(pod, &[port])
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut upstream = forwarder
+        .take_stream(port)
+        .ok_or_else(|| format!("no stream for port {}", port))?;
+    let error = forwarder.take_error(port);
+    let opened = started.elapsed();
+
+    let outcome = match http_path {
+        None => match error {
+            Some(error) => match tokio::time::timeout(CONNECT_GRACE, error).await {
+                Ok(Some(message)) => Err(message),
+                _ => Ok((opened, None)),
+            },
+            None => Ok((opened, None)),
+        },
+        Some(path) => {
+            let request = format!(
+                "GET {} HTTP/1.1\r\nHost: localhost\r\nUser-Agent: kdx\r\nConnection: close\r\n\r\n",
+                path
+            );
+            let exchange = async {
+                upstream.write_all(request.as_bytes()).await?;
+                let mut line = String::new();
+                BufReader::new(&mut upstream).read_line(&mut line).await?;
+                Ok::<_, std::io::Error>(line)
+            };
+            match exchange.await {
+                Ok(line) if !line.trim().is_empty() => parse_status_line(&line)
+                    .map(|status| (started.elapsed(), Some(status)))
+                    .ok_or_else(|| format!("not an HTTP response: {}", line.trim())),
+                // A refused connection closes the stream; the reason comes
+                // over the error channel
+                outcome => {
+                    let reason = match error {
+                        Some(error) => tokio::time::timeout(CONNECT_GRACE, error)
+                            .await
+                            .ok()
+                            .flatten(),
+                        None => None,
+                    };
+                    Err(reason.unwrap_or_else(|| match outcome {
+                        Err(e) => e.to_string(),
+                        Ok(_) => "connection closed without a response".to_string(),
+                    }))
+                }
+            }
+        }
+    };
+    drop(upstream);
+    forwarder.abort();
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status_line() {
+        assert_eq!(parse_status_line("HTTP/1.1 200 OK\r\n"), Some(200));
+        assert_eq!(
+            parse_status_line("HTTP/1.0 503 Service Unavailable"),
+            Some(503)
+        );
+        assert_eq!(parse_status_line("SSH-2.0-OpenSSH_9.6"), None);
+        assert_eq!(parse_status_line(""), None);
+        assert!(http_healthy(404));
+        assert!(!http_healthy(502));
+    }
+}
@@ -1,10 +1,25 @@
 //! Progress tracking for long-running operations
 
+use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// A namespace whose resources could not be fetched
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceFailure {
+    pub namespace: String,
+    pub resource: String,
+    pub error: String,
+    /// The request was retried before giving up
+    pub retried: bool,
+}
 
-/// Progress tracker for resource discovery operations
+/// Progress tracker for resource discovery operations. Namespace failures
+/// are collected even when no bar is shown and reported once work is done.
 pub struct ProgressTracker {
     bar: Option<ProgressBar>,
+    failures: Mutex<Vec<NamespaceFailure>>,
 }
 
 impl ProgressTracker {
@@ -27,7 +42,10 @@ impl ProgressTracker {
             None
         };
 
-        Self { bar }
+        Self {
+            bar,
+            failures: Mutex::new(Vec::new()),
+        }
     }
 
     /// Create a spinner for indeterminate progress
@@ -45,7 +63,10 @@ impl ProgressTracker {
             None
         };
 
-        Self { bar }
+        Self {
+            bar,
+            failures: Mutex::new(Vec::new()),
+        }
     }
 
     /// Set the progress message
@@ -84,6 +105,48 @@ impl ProgressTracker {
             bar.finish_and_clear();
         }
     }
+
+    /// Record a namespace that failed; results from other namespaces are
+    /// still returned
+    pub fn record_failure(&self, failure: NamespaceFailure) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.push(failure);
+        }
+    }
+
+    /// Failures recorded so far, sorted by namespace
+    pub fn failures(&self) -> Vec<NamespaceFailure> {
+        let mut failures = self.failures.lock().map(|f| f.clone()).unwrap_or_default();
+        failures.sort_by(|a, b| {
+            a.namespace
+                .cmp(&b.namespace)
+                .then(a.resource.cmp(&b.resource))
+        });
+        failures
+    }
+
+    /// Print recorded failures to stderr, if any
+    pub fn report_failures(&self) {
+        let failures = self.failures();
+        if failures.is_empty() {
+            return;
+        }
+
+        eprintln!(
+            "{} {} namespaces failed; results are incomplete:",
+            "Warning:".yellow().bold(),
+            failures.len()
+        );
+        for failure in &failures {
+            eprintln!(
+                "  {} ({}){}: {}",
+                failure.namespace,
+                failure.resource,
+                if failure.retried { ", retried" } else { "" },
+                failure.error
+            );
+        }
+    }
 }
 
 impl Drop for ProgressTracker {
@@ -126,4 +189,22 @@ mod tests {
         tracker.set_position(50);
         tracker.finish();
     }
+
+    #[test]
+    fn test_failures_recorded_without_bar() {
+        let tracker = ProgressTracker::new(false, None);
+        assert!(tracker.failures().is_empty());
+
+        for namespace in ["team-b", "team-a"] {
+            tracker.record_failure(NamespaceFailure {
+                namespace: namespace.to_string(),
+                resource: "pods".to_string(),
+                error: "connection reset".to_string(),
+                retried: true,
+            });
+        }
+        let failures = tracker.failures();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].namespace, "team-a");
+    }
 }
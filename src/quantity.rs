@@ -0,0 +1,120 @@
+//! Parsing of Kubernetes resource quantities
+
+/// Parse a Kubernetes quantity string (e.g. "500m", "2", "128Mi", "1G") into
+/// its value in base units (cores for CPU, bytes for memory and storage)
+pub fn parse_quantity(quantity: &str) -> Option<f64> {
+    let quantity = quantity.trim();
+    if quantity.is_empty() {
+        return None;
+    }
+
+    let split_at = quantity
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(quantity.len());
+    let (number, suffix) = quantity.split_at(split_at);
+
+    // Scientific notation ("1e3") is valid in quantities; a bare "E" or "Ei"
+    // is the exa suffix
+    let exponent = suffix
+        .strip_prefix(['e', 'E'])
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+'));
+    if exponent {
+        return quantity.parse::<f64>().ok();
+    }
+
+    let value: f64 = number.parse().ok()?;
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024.0_f64.powi(2),
+        "Gi" => 1024.0_f64.powi(3),
+        "Ti" => 1024.0_f64.powi(4),
+        "Pi" => 1024.0_f64.powi(5),
+        "Ei" => 1024.0_f64.powi(6),
+        _ => return None,
+    };
+
+    Some(value * multiplier)
+}
+
+/// Format a byte count using binary units (e.g. "1.5Gi")
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: &[&str] = &["Ki", "Mi", "Gi", "Ti", "Pi"];
+
+    if bytes < 1024.0 {
+        return format!("{}", bytes.round());
+    }
+
+    let mut value = bytes;
+    let mut unit = "";
+    for next in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    let formatted = format!("{:.1}", value);
+    format!("{}{}", formatted.trim_end_matches(".0"), unit)
+}
+
+/// Format CPU cores, in millicores when fractional (e.g. "2", "250m")
+pub fn format_cpu(cores: f64) -> String {
+    let millis = (cores * 1000.0).round();
+    if millis % 1000.0 == 0.0 {
+        format!("{}", millis / 1000.0)
+    } else {
+        format!("{}m", millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_quantities() {
+        assert_eq!(parse_quantity("500m"), Some(0.5));
+        assert_eq!(parse_quantity("2"), Some(2.0));
+        assert_eq!(parse_quantity("1.5"), Some(1.5));
+        assert_eq!(parse_quantity("1000m"), parse_quantity("1"));
+    }
+
+    #[test]
+    fn test_parse_memory_quantities() {
+        assert_eq!(parse_quantity("128Mi"), Some(128.0 * 1024.0 * 1024.0));
+        assert_eq!(parse_quantity("1G"), Some(1e9));
+        assert_eq!(parse_quantity("1e3"), Some(1000.0));
+        assert_eq!(parse_quantity("1E"), Some(1e18));
+        assert_eq!(parse_quantity("1Ei"), Some(1024.0_f64.powi(6)));
+        assert_eq!(parse_quantity("2Ei"), Some(2.0 * 1024.0_f64.powi(6)));
+        assert_eq!(parse_quantity("5e-1"), Some(0.5));
+        assert_eq!(parse_quantity(""), None);
+        assert_eq!(parse_quantity("12Qi"), None);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512.0), "512");
+        assert_eq!(format_bytes(10.0 * 1024.0 * 1024.0 * 1024.0), "10Gi");
+        assert_eq!(format_bytes(1536.0 * 1024.0 * 1024.0), "1.5Gi");
+        assert_eq!(format_bytes(2048.0 * 1024.0_f64.powi(4)), "2Pi");
+    }
+
+    #[test]
+    fn test_format_cpu() {
+        assert_eq!(format_cpu(2.0), "2");
+        assert_eq!(format_cpu(0.25), "250m");
+        assert_eq!(format_cpu(1.5), "1500m");
+    }
+}
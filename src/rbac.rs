@@ -0,0 +1,414 @@
+//! Resolution of RBAC bindings: who holds which role, and who can do what
+//!
+//! Roles and bindings are read as listed; nothing is checked against the
+//! API server. A binding grants the rules of its role to its subjects,
+//! everywhere for a ClusterRoleBinding and in its own namespace for a
+//! RoleBinding. Service accounts are also matched through the groups every
+//! account token carries. Rules limited by `resourceNames` are reported
+//! with the names they are limited to.
+
+use crate::discovery::{
+    DiscoveryEngine, PolicyRuleInfo, RoleBindingInfo, RoleInfo, ServiceAccountInfo, SubjectInfo,
+};
+use crate::error::{ExplorerError, Result};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Resource asked about by `kdx who-can`: `pods`, `pods/log`,
+/// `deployments.apps` or `deployments.apps/scale`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceQuery {
+    pub resource: String,
+    pub subresource: Option<String>,
+    /// API group; when not given, rules for any group match
+    pub group: Option<String>,
+}
+
+impl FromStr for ResourceQuery {
+    type Err = ExplorerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (resource, subresource) = match s.split_once('/') {
+            Some((resource, subresource)) => (resource, Some(subresource)),
+            None => (s, None),
+        };
+        let (resource, group) = match resource.split_once('.') {
+            Some((resource, group)) => (resource, Some(group)),
+            None => (resource, None),
+        };
+        if resource.is_empty() || subresource.is_some_and(|sub| sub.is_empty() || sub.contains('/'))
+        {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "expected a resource such as pods, pods/log or deployments.apps, got '{}'",
+                s
+            )));
+        }
+        Ok(ResourceQuery {
+            resource: resource.to_lowercase(),
+            subresource: subresource.map(str::to_string),
+            group: group.map(str::to_string),
+        })
+    }
+}
+
+impl fmt::Display for ResourceQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.resource)?;
+        if let Some(group) = &self.group {
+            write!(f, ".{}", group)?;
+        }
+        if let Some(subresource) = &self.subresource {
+            write!(f, "/{}", subresource)?;
+        }
+        Ok(())
+    }
+}
+
+/// A subject allowed to perform the queried verb on the queried resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Grant {
+    pub subject: SubjectInfo,
+    /// Namespace the permission holds in; None for every namespace
+    pub namespace: Option<String>,
+    /// Binding granting it, e.g. `ClusterRoleBinding/admins`
+    pub binding: String,
+    /// Role granting it, e.g. `ClusterRole/admin`
+    pub role: String,
+    /// Objects the permission is limited to; empty when unrestricted
+    pub resource_names: Vec<String>,
+}
+
+/// Whether `rule` allows `verb` on the queried resource, following the
+/// API server's matching of wildcards and `*/subresource`
+pub fn rule_allows(rule: &PolicyRuleInfo, verb: &str, query: &ResourceQuery) -> bool {
+    let any = |values: &[String], wanted: &str| values.iter().any(|v| v == "*" || v == wanted);
+    let requested = match &query.subresource {
+        Some(sub) => format!("{}/{}", query.resource, sub),
+        None => query.resource.clone(),
+    };
+    any(&rule.verbs, verb)
+        && query
+            .group
+            .as_deref()
+            .is_none_or(|group| any(&rule.api_groups, group))
+        && rule.resources.iter().any(|r| {
+            r == "*"
+                || *r == requested
+                || query
+                    .subresource
+                    .as_ref()
+                    .is_some_and(|sub| r.strip_prefix("*/") == Some(sub.as_str()))
+        })
+}
+
+/// Role a binding refers to: a ClusterRole, or a Role of the binding's
+/// namespace
+fn bound_role<'a>(binding: &RoleBindingInfo, roles: &'a [RoleInfo]) -> Option<&'a RoleInfo> {
+    roles.iter().find(|role| {
+        role.name == binding.role_name
+            && role.kind() == binding.role_kind
+            && (role.namespace.is_none() || role.namespace == binding.namespace)
+    })
+}
+
+/// Every subject that can `verb` the queried resource in `namespace` (or
+/// anywhere if None)
+pub fn resolve_grants(
+    verb: &str,
+    query: &ResourceQuery,
+    namespace: Option<&str>,
+    roles: &[RoleInfo],
+    bindings: &[RoleBindingInfo],
+) -> Vec<Grant> {
+    let mut grants = Vec::new();
+    for binding in bindings {
+        if namespace.is_some_and(|ns| binding.namespace.as_deref().is_some_and(|b| b != ns)) {
+            continue;
+        }
+        let Some(role) = bound_role(binding, roles) else {
+            continue;
+        };
+        let matching: Vec<&PolicyRuleInfo> = role
+            .rules
+            .iter()
+            .filter(|rule| rule_allows(rule, verb, query))
+            .collect();
+        if matching.is_empty() {
+            continue;
+        }
+        // A rule without resourceNames lifts any limit of the others
+        let resource_names: Vec<String> = if matching.iter().any(|r| r.resource_names.is_empty()) {
+            Vec::new()
+        } else {
+            matching
+                .iter()
+                .flat_map(|r| r.resource_names.iter().cloned())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        for subject in &binding.subjects {
+            grants.push(Grant {
+                subject: subject.clone(),
+                namespace: binding.namespace.clone(),
+                binding: format!("{}/{}", binding.kind(), binding.name),
+                role: format!("{}/{}", role.kind(), role.name),
+                resource_names: resource_names.clone(),
+            });
+        }
+    }
+    grants.sort_by(|a, b| (&a.subject, &a.namespace).cmp(&(&b.subject, &b.namespace)));
+    grants
+}
+
+/// Whether `subject` names the service account `name` in `namespace`,
+/// directly or through the service account groups. `system:authenticated`
+/// is left out: the discovery roles bound to it hold for every account.
+pub(crate) fn names_service_account(subject: &SubjectInfo, namespace: &str, name: &str) -> bool {
+    match subject.kind.as_str() {
+        "ServiceAccount" => subject.name == name && subject.namespace.as_deref() == Some(namespace),
+        "Group" => {
+            subject.name == "system:serviceaccounts"
+                || subject.name == format!("system:serviceaccounts:{}", namespace)
+        }
+        _ => false,
+    }
+}
+
+/// Fill in the subjects bound to each role
+pub fn attach_subjects(roles: &mut [RoleInfo], bindings: &[RoleBindingInfo]) {
+    for role in roles.iter_mut() {
+        let subjects: BTreeSet<SubjectInfo> = bindings
+            .iter()
+            .filter(|b| {
+                b.role_name == role.name
+                    && b.role_kind == role.kind()
+                    && (role.namespace.is_none() || role.namespace == b.namespace)
+            })
+            .flat_map(|b| b.subjects.iter().cloned())
+            .collect();
+        role.subjects = subjects.into_iter().collect();
+    }
+}
+
+/// Fill in the roles bound to each service account, as `Kind/name`,
+/// marking those granted in every namespace
+pub fn attach_roles(accounts: &mut [ServiceAccountInfo], bindings: &[RoleBindingInfo]) {
+    for account in accounts.iter_mut() {
+        let roles: BTreeSet<String> = bindings
+            .iter()
+            .filter(|b| {
+                b.subjects
+                    .iter()
+                    .any(|s| names_service_account(s, &account.namespace, &account.name))
+            })
+            .map(|b| match &b.namespace {
+                None => format!("{}/{} (cluster-wide)", b.role_kind, b.role_name),
+                Some(ns) if *ns != account.namespace => {
+                    format!("{}/{} (in {})", b.role_kind, b.role_name, ns)
+                }
+                Some(_) => format!("{}/{}", b.role_kind, b.role_name),
+            })
+            .collect();
+        account.roles = roles.into_iter().collect();
+    }
+}
+
+/// RoleBindings of `namespace` (or all namespaces if None) together with
+/// every ClusterRoleBinding
+pub async fn list_bindings(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<RoleBindingInfo>> {
+    let (mut bindings, cluster_bindings) = tokio::try_join!(
+        discovery.list_role_bindings(namespace),
+        discovery.list_cluster_role_bindings()
+    )?;
+    bindings.extend(cluster_bindings);
+    Ok(bindings)
+}
+
+/// Every subject that can `verb` the queried resource in `namespace` (or
+/// anywhere if None)
+pub async fn who_can(
+    discovery: &DiscoveryEngine,
+    verb: &str,
+    query: &ResourceQuery,
+    namespace: Option<&str>,
+) -> Result<Vec<Grant>> {
+    let (mut roles, cluster_roles, bindings) = tokio::try_join!(
+        discovery.list_roles(namespace),
+        discovery.list_cluster_roles(),
+        list_bindings(discovery, namespace)
+    )?;
+    roles.extend(cluster_roles);
+    Ok(resolve_grants(verb, query, namespace, &roles, &bindings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn rule(verbs: &[&str], groups: &[&str], resources: &[&str], names: &[&str]) -> PolicyRuleInfo {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        PolicyRuleInfo {
+            verbs: strings(verbs),
+            api_groups: strings(groups),
+            resources: strings(resources),
+            resource_names: strings(names),
+            non_resource_urls: Vec::new(),
+        }
+    }
+
+    fn role(name: &str, namespace: Option<&str>, rules: Vec<PolicyRuleInfo>) -> RoleInfo {
+        RoleInfo {
+            name: name.to_string(),
+            namespace: namespace.map(str::to_string),
+            rules,
+            aggregated: false,
+            age: "1d".to_string(),
+            labels: BTreeMap::new(),
+            subjects: Vec::new(),
+        }
+    }
+
+    fn binding(
+        name: &str,
+        namespace: Option<&str>,
+        role: (&str, &str),
+        subjects: Vec<SubjectInfo>,
+    ) -> RoleBindingInfo {
+        RoleBindingInfo {
+            name: name.to_string(),
+            namespace: namespace.map(str::to_string),
+            role_kind: role.0.to_string(),
+            role_name: role.1.to_string(),
+            subjects,
+            age: "1d".to_string(),
+            labels: BTreeMap::new(),
+        }
+    }
+
+    fn subject(kind: &str, name: &str, namespace: Option<&str>) -> SubjectInfo {
+        SubjectInfo {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            namespace: namespace.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_rule_allows() {
+        let query: ResourceQuery = "pods/log".parse().unwrap();
+        assert!(rule_allows(
+            &rule(&["get"], &[""], &["pods/log"], &[]),
+            "get",
+            &query
+        ));
+        assert!(rule_allows(
+            &rule(&["*"], &["*"], &["*/log"], &[]),
+            "get",
+            &query
+        ));
+        assert!(!rule_allows(
+            &rule(&["get"], &[""], &["pods"], &[]),
+            "get",
+            &query
+        ));
+
+        let query: ResourceQuery = "deployments.apps".parse().unwrap();
+        assert_eq!(query.group.as_deref(), Some("apps"));
+        assert!(rule_allows(
+            &rule(&["patch"], &["apps"], &["deployments"], &[]),
+            "patch",
+            &query
+        ));
+        assert!(!rule_allows(
+            &rule(&["patch"], &["extensions"], &["deployments"], &[]),
+            "patch",
+            &query
+        ));
+        assert!(!rule_allows(
+            &rule(&["get"], &["apps"], &["deployments"], &[]),
+            "patch",
+            &query
+        ));
+        assert!("pods/".parse::<ResourceQuery>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_grants() {
+        let roles = vec![
+            role(
+                "admin",
+                None,
+                vec![rule(&["*"], &["", "apps"], &["secrets", "pods"], &[])],
+            ),
+            role(
+                "reader",
+                Some("shop"),
+                vec![rule(&["get"], &[""], &["secrets"], &["db-creds"])],
+            ),
+            role(
+                "reader",
+                Some("billing"),
+                vec![rule(&["get"], &[""], &["secrets"], &[])],
+            ),
+        ];
+        let ci = subject("ServiceAccount", "ci", Some("shop"));
+        let bindings = vec![
+            binding(
+                "admins",
+                None,
+                ("ClusterRole", "admin"),
+                vec![subject("Group", "platform", None)],
+            ),
+            binding(
+                "ci-reader",
+                Some("shop"),
+                ("Role", "reader"),
+                vec![ci.clone()],
+            ),
+            binding(
+                "billing",
+                Some("billing"),
+                ("Role", "reader"),
+                vec![subject("User", "alice", None)],
+            ),
+        ];
+        let query: ResourceQuery = "secrets".parse().unwrap();
+
+        let grants = resolve_grants("get", &query, Some("shop"), &roles, &bindings);
+        assert_eq!(grants.len(), 2);
+        assert_eq!(grants[0].subject.name, "platform");
+        assert_eq!(grants[0].namespace, None);
+        assert_eq!(grants[1].subject, ci);
+        assert_eq!(grants[1].role, "Role/reader");
+        assert_eq!(grants[1].resource_names, ["db-creds"]);
+        assert_eq!(
+            resolve_grants("get", &query, None, &roles, &bindings).len(),
+            3
+        );
+        assert!(resolve_grants("delete", &query, Some("shop"), &roles, &bindings).len() == 1);
+
+        let mut accounts = vec![ServiceAccountInfo {
+            name: "ci".to_string(),
+            namespace: "shop".to_string(),
+            automount_token: None,
+            image_pull_secrets: Vec::new(),
+            age: "1d".to_string(),
+            labels: BTreeMap::new(),
+            roles: Vec::new(),
+        }];
+        attach_roles(&mut accounts, &bindings);
+        assert_eq!(accounts[0].roles, ["Role/reader"]);
+
+        let mut roles = roles;
+        attach_subjects(&mut roles, &bindings);
+        assert_eq!(roles[1].subjects, [ci]);
+        assert_eq!(roles[2].subjects[0].name, "alice");
+    }
+}
@@ -0,0 +1,228 @@
+//! Read-only guarantee for `--read-only-verify`
+//!
+//! kdx only ever reads from the cluster, but by convention alone. In
+//! read-only mode every client built by a read-only
+//! [`RunStats`](crate::stats::RunStats) gets a layer that classifies each
+//! request by the Kubernetes verb it maps to, logs it to stderr, and fails it
+//! before it is sent unless the verb is get, list or watch. A mutating code
+//! path added later then fails loudly instead of changing the cluster.
+//! Streaming subresources (exec, attach, port-forward, proxy) are rejected
+//! too: they are requested with GET but reach into pods.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use thiserror::Error;
+use tower::layer::util::Identity;
+use tower::util::Either;
+use tower::{BoxError, Layer, Service};
+
+/// The guard layer when `enforced`, a pass-through otherwise
+pub fn layer(enforced: bool) -> Either<ReadOnlyLayer, Identity> {
+    tower::util::option_layer(enforced.then_some(ReadOnlyLayer))
+}
+
+/// Kubernetes verb of an API request, as RBAC names it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verb {
+    Get,
+    List,
+    Watch,
+    Create,
+    Update,
+    Patch,
+    Delete,
+    DeleteCollection,
+    /// exec, attach, port-forward and proxy subresources
+    Connect,
+}
+
+impl Verb {
+    pub fn is_read_only(self) -> bool {
+        matches!(self, Verb::Get | Verb::List | Verb::Watch)
+    }
+}
+
+impl std::fmt::Display for Verb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verb = match self {
+            Verb::Get => "get",
+            Verb::List => "list",
+            Verb::Watch => "watch",
+            Verb::Create => "create",
+            Verb::Update => "update",
+            Verb::Patch => "patch",
+            Verb::Delete => "delete",
+            Verb::DeleteCollection => "deletecollection",
+            Verb::Connect => "connect",
+        };
+        write!(f, "{}", verb)
+    }
+}
+
+/// Whether the path names a single object rather than a collection. Paths
+/// outside /api and /apis (discovery, /version, /openapi) count as objects.
+fn names_object(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    // Skip /api/<version> or /apis/<group>/<version>
+    let rest = match segments.first() {
+        Some(&"api") => segments.get(2..),
+        Some(&"apis") => segments.get(3..),
+        _ => return true,
+    };
+    match rest.unwrap_or_default() {
+        [] => true,
+        // Namespace collection, a namespace, or a resource collection in it
+        ["namespaces"] => false,
+        ["namespaces", _] => true,
+        ["namespaces", _, _] => false,
+        ["namespaces", _, _, _, ..] => true,
+        [_] => false,
+        _ => true,
+    }
+}
+
+/// The verb a request with `method` to `uri` is authorized as
+pub fn classify(method: &http::Method, uri: &http::Uri) -> Verb {
+    let path = uri.path();
+    let watch = uri
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .any(|pair| pair == "watch=true" || pair == "watch=1");
+    let connect = ["/exec", "/attach", "/portforward", "/proxy"]
+        .iter()
+        .any(|subresource| path.ends_with(subresource))
+        || path.contains("/proxy/");
+    let object = names_object(path);
+
+    match *method {
+        _ if connect => Verb::Connect,
+        http::Method::GET if watch || path.contains("/watch/") => Verb::Watch,
+        http::Method::GET | http::Method::HEAD if object => Verb::Get,
+        http::Method::GET | http::Method::HEAD => Verb::List,
+        http::Method::PUT => Verb::Update,
+        http::Method::PATCH => Verb::Patch,
+        http::Method::DELETE if object => Verb::Delete,
+        http::Method::DELETE => Verb::DeleteCollection,
+        _ => Verb::Create,
+    }
+}
+
+/// A request refused by read-only mode
+#[derive(Error, Debug)]
+#[error("read-only mode refused to send a {verb} request to {path}")]
+pub struct ReadOnlyViolation {
+    pub verb: Verb,
+    pub path: String,
+}
+
+/// Layer guarding a client in read-only mode
+#[derive(Debug, Clone, Copy)]
+pub struct ReadOnlyLayer;
+
+impl<S> Layer<S> for ReadOnlyLayer {
+    type Service = ReadOnlyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadOnlyService { inner }
+    }
+}
+
+/// Service logging the verb of every request and refusing the mutating ones
+#[derive(Debug, Clone)]
+pub struct ReadOnlyService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for ReadOnlyService<S>
+where
+    S: Service<http::Request<B>>,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let verb = classify(request.method(), request.uri());
+        let path = request.uri().path().to_string();
+        if !verb.is_read_only() {
+            eprintln!("[read-only] REFUSED {} {}", verb, path);
+            return Box::pin(async move { Err(ReadOnlyViolation { verb, path }.into()) });
+        }
+        eprintln!("[read-only] {} {}", verb, path);
+        let response = self.inner.call(request);
+        Box::pin(async move { response.await.map_err(Into::into) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let verb = |method: http::Method, uri: &str| classify(&method, &uri.parse().unwrap());
+
+        assert_eq!(
+            verb(http::Method::GET, "/api/v1/pods?limit=100"),
+            Verb::List
+        );
+        assert_eq!(
+            verb(http::Method::GET, "/api/v1/namespaces/shop/pods"),
+            Verb::List
+        );
+        assert_eq!(
+            verb(http::Method::GET, "/api/v1/namespaces/shop/pods/web-0/log"),
+            Verb::Get
+        );
+        assert_eq!(verb(http::Method::GET, "/api/v1/namespaces"), Verb::List);
+        assert_eq!(
+            verb(http::Method::GET, "/api/v1/namespaces/shop"),
+            Verb::Get
+        );
+        assert_eq!(verb(http::Method::GET, "/apis/apps/v1"), Verb::Get);
+        assert_eq!(verb(http::Method::GET, "/version"), Verb::Get);
+        assert_eq!(
+            verb(
+                http::Method::GET,
+                "/apis/apps/v1/namespaces/shop/deployments?watch=true&resourceVersion=10"
+            ),
+            Verb::Watch
+        );
+        assert_eq!(
+            verb(
+                http::Method::GET,
+                "/api/v1/namespaces/shop/pods/web-0/portforward?ports=8080"
+            ),
+            Verb::Connect
+        );
+
+        assert_eq!(
+            verb(
+                http::Method::POST,
+                "/apis/authorization.k8s.io/v1/selfsubjectaccessreviews"
+            ),
+            Verb::Create
+        );
+        assert_eq!(
+            verb(
+                http::Method::DELETE,
+                "/apis/apps/v1/namespaces/shop/deployments"
+            ),
+            Verb::DeleteCollection
+        );
+        assert_eq!(
+            verb(http::Method::PATCH, "/api/v1/nodes/node-1"),
+            Verb::Patch
+        );
+        assert!(!Verb::Connect.is_read_only());
+        assert!(Verb::Watch.is_read_only());
+    }
+}
@@ -0,0 +1,457 @@
+//! Pseudonymizing snapshots and reports before they are shared
+//!
+//! With `--redact`, names, namespaces, label and annotation values, image
+//! repositories, IP addresses, port names and paths are replaced by a salted
+//! hash. The same input always maps to the same pseudonym, so a Service
+//! still selects its pods, a ConfigMap is still used by its Deployment and
+//! two redacted snapshots can be compared with `kdx diff`. Kinds, counts,
+//! phases, image tags and data key names are kept.
+
+use crate::cache::cache_dir;
+use crate::discovery::{
+    ConfigMapInfo, DaemonSetInfo, DeploymentInfo, PodInfo, ResourceReference, SecretInfo,
+    ServiceInfo, StatefulSetInfo,
+};
+use crate::error::{ExplorerError, Result};
+use crate::history::ClusterSnapshot;
+use crate::report::{Report, HOST_PATH_FINDING};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Environment variable overriding the salt, to get the same pseudonyms on
+/// several machines
+pub const SALT_ENV: &str = "KDX_REDACT_SALT";
+
+/// Hex digits kept from each hash
+const PSEUDONYM_LEN: usize = 12;
+
+/// Random bytes in a generated salt
+const SALT_LEN: usize = 16;
+
+pub struct Redactor {
+    salt: Vec<u8>,
+}
+
+impl Redactor {
+    pub fn new(salt: impl Into<Vec<u8>>) -> Self {
+        Self { salt: salt.into() }
+    }
+
+    /// Use the salt from `KDX_REDACT_SALT`, or else the one generated on
+    /// first use and kept in the cache directory
+    pub fn open() -> Result<Self> {
+        if let Ok(salt) = std::env::var(SALT_ENV) {
+            return Ok(Self::new(salt));
+        }
+
+        let dir = cache_dir().ok_or_else(|| {
+            ExplorerError::InvalidArgument(format!(
+                "no cache directory; set {} or KDX_CACHE_DIR to redact",
+                SALT_ENV
+            ))
+        })?;
+        let path = dir.join("redact-salt");
+        match fs::read_to_string(&path) {
+            Ok(salt) => Ok(Self::new(salt.trim_end())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut bytes = [0u8; SALT_LEN];
+                getrandom::getrandom(&mut bytes).map_err(std::io::Error::from)?;
+                let salt: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                fs::create_dir_all(&dir)?;
+                fs::write(&path, &salt)?;
+                Ok(Self::new(salt))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stable pseudonym of `value` under this salt
+    pub fn pseudonym(&self, value: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.salt);
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.finalize()[..PSEUDONYM_LEN / 2]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn redact_snapshot(&self, snapshot: &mut ClusterSnapshot) {
+        self.option(&mut snapshot.context);
+        snapshot.namespaces.iter_mut().for_each(|ns| self.name(ns));
+        snapshot.service_labels = std::mem::take(&mut snapshot.service_labels)
+            .into_iter()
+            .map(|(key, mut labels)| {
+                let key = match key.split_once('/') {
+                    Some((ns, name)) => format!("{}/{}", self.pseudonym(ns), self.pseudonym(name)),
+                    None => self.pseudonym(&key),
+                };
+                self.labels(&mut labels);
+                (key, labels)
+            })
+            .collect();
+
+        snapshot.services.iter_mut().for_each(|s| self.service(s));
+        snapshot.pods.iter_mut().for_each(|p| self.pod(p));
+        snapshot
+            .deployments
+            .iter_mut()
+            .for_each(|d| self.deployment(d));
+        snapshot
+            .statefulsets
+            .iter_mut()
+            .for_each(|s| self.statefulset(s));
+        snapshot
+            .daemonsets
+            .iter_mut()
+            .for_each(|d| self.daemonset(d));
+        snapshot
+            .configmaps
+            .iter_mut()
+            .for_each(|c| self.configmap(c));
+        snapshot.secrets.iter_mut().for_each(|s| self.secret(s));
+    }
+
+    pub fn redact_report(&self, report: &mut Report) {
+        self.option(&mut report.namespace);
+        for unused in report.unused.iter_mut().flatten() {
+            self.name(&mut unused.namespace);
+            self.name(&mut unused.name);
+        }
+        for finding in report.audit.iter_mut().flatten() {
+            self.name(&mut finding.namespace);
+            self.name(&mut finding.pod);
+            self.option(&mut finding.container);
+            if let Some(path) = finding.finding.strip_prefix(HOST_PATH_FINDING) {
+                finding.finding = format!("{}{}", HOST_PATH_FINDING, self.pseudonym(path));
+            }
+        }
+        for cert in report.certs.iter_mut().flatten() {
+            self.name(&mut cert.namespace);
+            self.name(&mut cert.secret);
+        }
+        for deprecation in report.deprecations.iter_mut().flatten() {
+            self.name(&mut deprecation.crd);
+            self.option(&mut deprecation.warning);
+        }
+        for rollout in report.rollouts.iter_mut().flatten() {
+            self.name(&mut rollout.namespace);
+            self.name(&mut rollout.name);
+        }
+    }
+
+    fn name(&self, value: &mut String) {
+        *value = self.pseudonym(value);
+    }
+
+    fn option(&self, value: &mut Option<String>) {
+        if let Some(value) = value {
+            self.name(value);
+        }
+    }
+
+    /// Keys are kept apart from a domain prefix outside Kubernetes, since
+    /// they are mostly well known and say what a value means
+    fn key(&self, key: &str) -> String {
+        match key.split_once('/') {
+            Some((prefix, name))
+                if !(prefix.ends_with("kubernetes.io") || prefix.ends_with("k8s.io")) =>
+            {
+                format!("{}/{}", self.pseudonym(prefix), name)
+            }
+            _ => key.to_string(),
+        }
+    }
+
+    fn labels(&self, labels: &mut BTreeMap<String, String>) {
+        *labels = std::mem::take(labels)
+            .into_iter()
+            .map(|(k, v)| (self.key(&k), self.pseudonym(&v)))
+            .collect();
+    }
+
+    /// The repository is hashed; the tag or digest is kept
+    fn image(&self, image: &str) -> String {
+        let split = match image.find('@') {
+            Some(at) => at,
+            None => match image.rfind(':') {
+                Some(colon) if !image[colon..].contains('/') => colon,
+                _ => image.len(),
+            },
+        };
+        format!("{}{}", self.pseudonym(&image[..split]), &image[split..])
+    }
+
+    fn images(&self, images: &mut BTreeMap<String, String>) {
+        *images = std::mem::take(images)
+            .into_iter()
+            .map(|(container, image)| (self.pseudonym(&container), self.image(&image)))
+            .collect();
+    }
+
+    fn references(&self, references: &mut [ResourceReference]) {
+        for reference in references {
+            self.name(&mut reference.namespace);
+            self.name(&mut reference.name);
+        }
+    }
+
+    fn service(&self, svc: &mut ServiceInfo) {
+        self.name(&mut svc.name);
+        self.name(&mut svc.namespace);
+        self.option(&mut svc.cluster_ip);
+        for port in &mut svc.ports {
+            self.option(&mut port.name);
+            // A named target port refers to a container port name
+            if port.target_port.parse::<u16>().is_err() {
+                self.name(&mut port.target_port);
+            }
+        }
+        if let Some(selector) = &mut svc.selector {
+            self.labels(selector);
+        }
+        self.labels(&mut svc.annotations);
+    }
+
+    fn pod(&self, pod: &mut PodInfo) {
+        self.name(&mut pod.name);
+        self.name(&mut pod.namespace);
+        self.option(&mut pod.pod_ip);
+        self.option(&mut pod.node_name);
+        self.option(&mut pod.priority_class);
+        self.option(&mut pod.owner_name);
+        self.labels(&mut pod.labels);
+        self.labels(&mut pod.annotations);
+    }
+
+    fn deployment(&self, d: &mut DeploymentInfo) {
+        self.name(&mut d.name);
+        self.name(&mut d.namespace);
+        self.labels(&mut d.labels);
+        self.labels(&mut d.selector);
+        self.labels(&mut d.annotations);
+        self.images(&mut d.images);
+    }
+
+    fn statefulset(&self, s: &mut StatefulSetInfo) {
+        self.name(&mut s.name);
+        self.name(&mut s.namespace);
+        self.labels(&mut s.labels);
+        self.labels(&mut s.selector);
+        self.labels(&mut s.annotations);
+        self.images(&mut s.images);
+        s.volume_claim_templates
+            .iter_mut()
+            .for_each(|t| self.name(t));
+        self.option(&mut s.update_revision);
+        for ordinal in &mut s.ordinals {
+            self.name(&mut ordinal.pod);
+            self.option(&mut ordinal.revision);
+            for claim in &mut ordinal.claims {
+                self.name(&mut claim.name);
+                self.option(&mut claim.volume);
+            }
+        }
+    }
+
+    fn daemonset(&self, d: &mut DaemonSetInfo) {
+        self.name(&mut d.name);
+        self.name(&mut d.namespace);
+        self.labels(&mut d.labels);
+        self.labels(&mut d.selector);
+        self.labels(&mut d.annotations);
+        self.images(&mut d.images);
+    }
+
+    fn configmap(&self, cm: &mut ConfigMapInfo) {
+        self.name(&mut cm.name);
+        self.name(&mut cm.namespace);
+        self.labels(&mut cm.labels);
+        self.labels(&mut cm.annotations);
+        self.references(&mut cm.used_by);
+        cm.mount_paths.iter_mut().for_each(|p| self.name(p));
+    }
+
+    fn secret(&self, secret: &mut SecretInfo) {
+        self.name(&mut secret.name);
+        self.name(&mut secret.namespace);
+        self.labels(&mut secret.labels);
+        self.labels(&mut secret.annotations);
+        self.references(&mut secret.used_by);
+        secret.mount_paths.iter_mut().for_each(|p| self.name(p));
+        self.option(&mut secret.service_account);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{diff_inventories, inventory};
+    use crate::discovery::{AllResources, ServicePort};
+    use crate::report::{
+        AuditFinding, CertificateExpiry, DeprecatedVersion, Overview, StalledRollout, UnusedConfig,
+    };
+    use chrono::Utc;
+
+    fn snapshot() -> ClusterSnapshot {
+        let labels = BTreeMap::from([
+            ("app".to_string(), "checkout".to_string()),
+            (
+                "payments.acme.internal/tier".to_string(),
+                "gold".to_string(),
+            ),
+        ]);
+        ClusterSnapshot {
+            taken_at: Utc::now(),
+            context: Some("acme-prod".to_string()),
+            namespaces: vec!["payments".to_string()],
+            services: vec![ServiceInfo {
+                name: "checkout".to_string(),
+                namespace: "payments".to_string(),
+                ports: vec![ServicePort {
+                    name: Some("checkout-grpc".to_string()),
+                    port: 9090,
+                    target_port: "checkout-grpc".to_string(),
+                    protocol: "TCP".to_string(),
+                }],
+                cluster_ip: Some("10.96.12.7".to_string()),
+                service_type: "ClusterIP".to_string(),
+                selector: Some(BTreeMap::from([(
+                    "app".to_string(),
+                    "checkout".to_string(),
+                )])),
+                created_at: None,
+                annotations: BTreeMap::new(),
+            }],
+            service_labels: BTreeMap::new(),
+            pods: vec![PodInfo {
+                pod_ip: Some("10.4.7.21".to_string()),
+                node_name: Some("acme-node-3".to_string()),
+                priority_class: Some("payments-critical".to_string()),
+                labels: labels.clone(),
+                ..PodInfo::fixture("payments", "checkout-7d9f")
+            }],
+            deployments: vec![DeploymentInfo {
+                labels: labels.clone(),
+                selector: BTreeMap::from([("app".to_string(), "checkout".to_string())]),
+                images: BTreeMap::from([(
+                    "checkout".to_string(),
+                    "registry.acme.internal:5000/payments/checkout:1.4".to_string(),
+                )]),
+                ..DeploymentInfo::fixture("payments", "checkout", 3)
+            }],
+            statefulsets: Vec::new(),
+            daemonsets: Vec::new(),
+            configmaps: Vec::new(),
+            secrets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_redact_snapshot() {
+        let redactor = Redactor::new("salt");
+        let mut redacted = snapshot();
+        redactor.redact_snapshot(&mut redacted);
+
+        let json = serde_json::to_string(&redacted).unwrap();
+        for internal in ["acme", "payments", "checkout", "10.4.7.21", "10.96.12.7"] {
+            assert!(!json.contains(internal), "{} leaked: {}", internal, json);
+        }
+
+        // Relationships survive: the selector still matches the pod labels
+        let deployment = &redacted.deployments[0];
+        let selector = redacted.services[0].selector.as_ref().unwrap();
+        assert_eq!(selector.get("app"), deployment.labels.get("app"));
+        assert_eq!(redacted.services[0].name, deployment.name);
+        assert!(deployment.images.values().all(|i| i.ends_with(":1.4")));
+        let port = &redacted.services[0].ports[0];
+        assert_eq!(port.name.as_ref(), Some(&port.target_port));
+        assert_eq!(port.port, 9090);
+
+        // The same salt gives the same pseudonyms, so redacted snapshots
+        // can still be diffed; another salt does not
+        let mut again = snapshot();
+        redactor.redact_snapshot(&mut again);
+        let resources = |s: &ClusterSnapshot| AllResources {
+            services: s.services.clone(),
+            deployments: s.deployments.clone(),
+            ..Default::default()
+        };
+        assert!(diff_inventories(
+            &inventory(&resources(&redacted), false),
+            &inventory(&resources(&again), false)
+        )
+        .is_empty());
+        assert_ne!(
+            Redactor::new("other").pseudonym("payments"),
+            redactor.pseudonym("payments")
+        );
+    }
+
+    #[test]
+    fn test_redact_report() {
+        let mut report = Report {
+            generated_at: Utc::now(),
+            namespace: Some("payments".to_string()),
+            overview: Some(Overview {
+                pods: 4,
+                pod_phases: BTreeMap::from([("Running".to_string(), 4)]),
+                ..Default::default()
+            }),
+            unused: Some(vec![UnusedConfig {
+                kind: "ConfigMap".to_string(),
+                namespace: "payments".to_string(),
+                name: "acme-legacy-flags".to_string(),
+            }]),
+            audit: Some(vec![
+                AuditFinding {
+                    namespace: "payments".to_string(),
+                    pod: "checkout-7d9f".to_string(),
+                    container: None,
+                    finding: format!("{}/srv/acme/ledger", HOST_PATH_FINDING),
+                },
+                AuditFinding {
+                    namespace: "payments".to_string(),
+                    pod: "checkout-7d9f".to_string(),
+                    container: Some("checkout".to_string()),
+                    finding: "privileged container".to_string(),
+                },
+            ]),
+            certs: Some(vec![CertificateExpiry {
+                namespace: "payments".to_string(),
+                secret: "checkout-tls".to_string(),
+                not_after: None,
+                days_remaining: Some(12),
+                status: "expiring".to_string(),
+            }]),
+            deprecations: Some(vec![DeprecatedVersion {
+                crd: "ledgers.acme.internal".to_string(),
+                version: "v1beta1".to_string(),
+                storage: false,
+                warning: Some("acme.internal/v1beta1 Ledger is deprecated".to_string()),
+            }]),
+            rollouts: Some(vec![StalledRollout {
+                kind: "Deployment".to_string(),
+                namespace: "payments".to_string(),
+                name: "checkout".to_string(),
+                desired: 3,
+                ready: 1,
+            }]),
+        };
+        Redactor::new("salt").redact_report(&mut report);
+
+        let json = serde_json::to_string(&report).unwrap();
+        for internal in ["acme", "payments", "checkout", "ledger", "Ledger", "/srv"] {
+            assert!(!json.contains(internal), "{} leaked: {}", internal, json);
+        }
+
+        // What makes the report useful is kept
+        let audit = report.audit.as_ref().unwrap();
+        assert!(audit[0].finding.starts_with(HOST_PATH_FINDING));
+        assert_eq!(audit[1].finding, "privileged container");
+        assert_eq!(report.deprecations.as_ref().unwrap()[0].version, "v1beta1");
+        assert_eq!(report.certs.as_ref().unwrap()[0].days_remaining, Some(12));
+    }
+}
@@ -0,0 +1,263 @@
+//! ConfigMap and Secret references in pod specs
+//!
+//! A pod uses a ConfigMap or Secret through volumes (including projected
+//! volumes), single env variables, envFrom and, for Secrets, imagePullSecrets.
+//! Reading these from the pod spec gives the `used_by` and `mount_paths` of
+//! ConfigMaps and Secrets (see `pod_index`), which `--unused` relies on.
+
+use crate::discovery::ReferenceType;
+use k8s_openapi::api::core::v1::PodSpec;
+
+/// Kind of object a pod references
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigKind {
+    ConfigMap,
+    Secret,
+}
+
+/// A ConfigMap or Secret referenced by a pod spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PodConfigReference {
+    pub kind: ConfigKind,
+    pub name: String,
+    pub reference_type: ReferenceType,
+    /// Where the volume is mounted, for volume references mounted by a container
+    pub mount_path: Option<String>,
+}
+
+/// Every ConfigMap and Secret referenced by `spec`, once per reference type
+/// and mount path
+pub fn pod_config_references(spec: &PodSpec) -> Vec<PodConfigReference> {
+    let mut references = Vec::new();
+    let mut add = |kind, name: Option<&String>, reference_type, mount_path: Option<&String>| {
+        let reference = PodConfigReference {
+            kind,
+            name: name.cloned().unwrap_or_default(),
+            reference_type,
+            mount_path: mount_path.cloned(),
+        };
+        if !reference.name.is_empty() && !references.contains(&reference) {
+            references.push(reference);
+        }
+    };
+
+    let containers: Vec<_> = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&spec.containers)
+        .collect();
+
+    for volume in spec.volumes.iter().flatten() {
+        let mut sources: Vec<(ConfigKind, Option<&String>)> = Vec::new();
+        if let Some(cm) = &volume.config_map {
+            sources.push((ConfigKind::ConfigMap, cm.name.as_ref()));
+        }
+        if let Some(secret) = &volume.secret {
+            sources.push((ConfigKind::Secret, secret.secret_name.as_ref()));
+        }
+        for projection in volume
+            .projected
+            .iter()
+            .flat_map(|p| p.sources.iter().flatten())
+        {
+            if let Some(cm) = &projection.config_map {
+                sources.push((ConfigKind::ConfigMap, cm.name.as_ref()));
+            }
+            if let Some(secret) = &projection.secret {
+                sources.push((ConfigKind::Secret, secret.name.as_ref()));
+            }
+        }
+
+        let mount_paths: Vec<&String> = containers
+            .iter()
+            .flat_map(|c| c.volume_mounts.iter().flatten())
+            .filter(|m| m.name == volume.name)
+            .map(|m| &m.mount_path)
+            .collect();
+        for (kind, name) in sources {
+            if mount_paths.is_empty() {
+                add(kind, name, ReferenceType::VolumeMount, None);
+            }
+            for path in &mount_paths {
+                add(kind, name, ReferenceType::VolumeMount, Some(path));
+            }
+        }
+    }
+
+    for container in &containers {
+        for env_from in container.env_from.iter().flatten() {
+            if let Some(cm) = &env_from.config_map_ref {
+                add(
+                    ConfigKind::ConfigMap,
+                    cm.name.as_ref(),
+                    ReferenceType::EnvironmentFrom,
+                    None,
+                );
+            }
+            if let Some(secret) = &env_from.secret_ref {
+                add(
+                    ConfigKind::Secret,
+                    secret.name.as_ref(),
+                    ReferenceType::EnvironmentFrom,
+                    None,
+                );
+            }
+        }
+        for value_from in container
+            .env
+            .iter()
+            .flatten()
+            .filter_map(|v| v.value_from.as_ref())
+        {
+            if let Some(cm) = &value_from.config_map_key_ref {
+                add(
+                    ConfigKind::ConfigMap,
+                    cm.name.as_ref(),
+                    ReferenceType::Environment,
+                    None,
+                );
+            }
+            if let Some(secret) = &value_from.secret_key_ref {
+                add(
+                    ConfigKind::Secret,
+                    secret.name.as_ref(),
+                    ReferenceType::Environment,
+                    None,
+                );
+            }
+        }
+    }
+
+    for pull_secret in spec.image_pull_secrets.iter().flatten() {
+        add(
+            ConfigKind::Secret,
+            pull_secret.name.as_ref(),
+            ReferenceType::ImagePullSecret,
+            None,
+        );
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::SecretInfo;
+    use crate::pod_index::PodIndex;
+    use k8s_openapi::api::core::v1::Pod;
+
+    fn pod(spec: serde_json::Value) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "web-1", "namespace": "shop"},
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pod_config_references() {
+        let web = pod(serde_json::json!({
+            "containers": [{
+                "name": "web",
+                "envFrom": [{"configMapRef": {"name": "web-env"}}],
+                "env": [{"name": "DB_PASSWORD", "valueFrom": {"secretKeyRef": {"name": "db", "key": "password"}}}],
+                "volumeMounts": [
+                    {"name": "config", "mountPath": "/etc/web"},
+                    {"name": "bundle", "mountPath": "/var/run/bundle"},
+                ],
+            }],
+            "imagePullSecrets": [{"name": "registry"}],
+            "volumes": [
+                {"name": "config", "configMap": {"name": "web-config"}},
+                {"name": "bundle", "projected": {"sources": [
+                    {"configMap": {"name": "ca"}},
+                    {"secret": {"name": "db"}},
+                ]}},
+                {"name": "unused", "secret": {"secretName": "spare"}},
+            ],
+        }));
+
+        let references = pod_config_references(web.spec.as_ref().unwrap());
+        let summary: Vec<(ConfigKind, &str, ReferenceType, Option<&str>)> = references
+            .iter()
+            .map(|r| {
+                (
+                    r.kind,
+                    r.name.as_str(),
+                    r.reference_type.clone(),
+                    r.mount_path.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    ConfigKind::ConfigMap,
+                    "web-config",
+                    ReferenceType::VolumeMount,
+                    Some("/etc/web")
+                ),
+                (
+                    ConfigKind::ConfigMap,
+                    "ca",
+                    ReferenceType::VolumeMount,
+                    Some("/var/run/bundle")
+                ),
+                (
+                    ConfigKind::Secret,
+                    "db",
+                    ReferenceType::VolumeMount,
+                    Some("/var/run/bundle")
+                ),
+                (
+                    ConfigKind::Secret,
+                    "spare",
+                    ReferenceType::VolumeMount,
+                    None
+                ),
+                (
+                    ConfigKind::ConfigMap,
+                    "web-env",
+                    ReferenceType::EnvironmentFrom,
+                    None
+                ),
+                (ConfigKind::Secret, "db", ReferenceType::Environment, None),
+                (
+                    ConfigKind::Secret,
+                    "registry",
+                    ReferenceType::ImagePullSecret,
+                    None
+                ),
+            ]
+        );
+
+        let secret = |name: &str, namespace: &str| -> SecretInfo {
+            serde_json::from_value(serde_json::json!({
+                "name": name, "namespace": namespace, "secret_type": "Opaque", "data_keys": [],
+                "age": "1d", "labels": {}, "used_by": [], "mount_paths": [],
+            }))
+            .unwrap()
+        };
+        let mut secrets = [
+            secret("db", "shop"),
+            secret("db", "other"),
+            secret("unrelated", "shop"),
+        ];
+        PodIndex::from_pods(&[web]).attach_secrets(&mut secrets);
+        let types: Vec<ReferenceType> = secrets[0]
+            .used_by
+            .iter()
+            .map(|r| r.reference_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            [ReferenceType::VolumeMount, ReferenceType::Environment]
+        );
+        assert_eq!(secrets[0].mount_paths, ["/var/run/bundle"]);
+        assert!(secrets[1].used_by.is_empty());
+        assert!(secrets[2].used_by.is_empty());
+    }
+}
@@ -0,0 +1,735 @@
+//! Composite platform health report combining several analysis sections
+
+use crate::cli::ReportSection;
+use crate::discovery::{
+    ConfigMapInfo, DaemonSetInfo, DeploymentInfo, DiscoveryEngine, LazyConvert, PodInfo,
+    SecretInfo, StatefulSetInfo,
+};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{Pod, PodSpec, Secret};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Certificates expiring within this many days are flagged
+const CERT_WARNING_DAYS: i64 = 30;
+
+/// Start of the finding for a hostPath volume, which ends with the path
+pub const HOST_PATH_FINDING: &str = "mounts hostPath ";
+
+/// Resource counts across the report scope
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Overview {
+    pub namespaces: usize,
+    pub pods: usize,
+    pub pod_phases: BTreeMap<String, usize>,
+    pub not_ready_pods: usize,
+    pub restarts: u32,
+    pub deployments: usize,
+    pub statefulsets: usize,
+    pub daemonsets: usize,
+    pub configmaps: usize,
+    pub secrets: usize,
+}
+
+/// ConfigMap or Secret not referenced by any workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedConfig {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+/// Security-relevant setting found in a pod spec
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditFinding {
+    pub namespace: String,
+    pub pod: String,
+    pub container: Option<String>,
+    pub finding: String,
+}
+
+/// Expiry of the leaf certificate in a TLS secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateExpiry {
+    pub namespace: String,
+    pub secret: String,
+    pub not_after: Option<DateTime<Utc>>,
+    pub days_remaining: Option<i64>,
+    pub status: String,
+}
+
+/// CRD version marked as deprecated that is still served
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecatedVersion {
+    pub crd: String,
+    pub version: String,
+    pub storage: bool,
+    pub warning: Option<String>,
+}
+
+/// Workload whose ready replicas lag behind the desired count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StalledRollout {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub desired: i32,
+    pub ready: i32,
+}
+
+/// Combined report; sections that were not requested are left out
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Report {
+    pub generated_at: DateTime<Utc>,
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overview: Option<Overview>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused: Option<Vec<UnusedConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<Vec<AuditFinding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certs: Option<Vec<CertificateExpiry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecations: Option<Vec<DeprecatedVersion>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollouts: Option<Vec<StalledRollout>>,
+}
+
+/// Build the report, fetching each resource type at most once and sharing it
+/// between the sections that need it
+pub async fn build_report(
+    discovery: &DiscoveryEngine,
+    sections: &[ReportSection],
+    namespace: Option<&str>,
+) -> Result<Report> {
+    let wants = |section: ReportSection| sections.is_empty() || sections.contains(&section);
+    let client = discovery.client().clone();
+    let mut report = Report {
+        generated_at: Utc::now(),
+        namespace: namespace.map(String::from),
+        ..Default::default()
+    };
+
+    let pods: Vec<Pod> = if wants(ReportSection::Overview) || wants(ReportSection::Audit) {
+        let api: Api<Pod> = match namespace {
+            Some(ns) => Api::namespaced(client.clone(), ns),
+            None => Api::all(client.clone()),
+        };
+        api.list(&ListParams::default()).await?.items
+    } else {
+        Vec::new()
+    };
+
+    let workloads_needed = wants(ReportSection::Overview) || wants(ReportSection::Rollouts);
+    let (deployments, statefulsets, daemonsets) = if workloads_needed {
+        (
+            discovery
+                .list_deployments_with_options(namespace, None, 100, true)
+                .await?,
+            discovery.list_statefulsets(namespace).await?,
+            discovery.list_daemonsets(namespace).await?,
+        )
+    } else {
+        (Vec::new(), Vec::new(), Vec::new())
+    };
+
+    let config_needed = wants(ReportSection::Overview) || wants(ReportSection::Unused);
+    let (configmaps, secrets) = if config_needed {
+        // Both passes share one pod index
+        tokio::try_join!(
+            discovery.list_configmaps_with_options(namespace, None, 100, true),
+            discovery.list_secrets(namespace),
+        )?
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    if wants(ReportSection::Overview) {
+        let pod_infos: Vec<PodInfo> = pods.iter().filter_map(|p| p.lazy_convert()).collect();
+        let mut overview = overview(&pod_infos);
+        overview.deployments = deployments.len();
+        overview.statefulsets = statefulsets.len();
+        overview.daemonsets = daemonsets.len();
+        overview.configmaps = configmaps.len();
+        overview.secrets = secrets.len();
+        overview.namespaces = match namespace {
+            Some(_) => 1,
+            None => discovery.get_all_namespaces().await?.len(),
+        };
+        report.overview = Some(overview);
+    }
+
+    if wants(ReportSection::Unused) {
+        report.unused = Some(unused_config(&configmaps, &secrets));
+    }
+
+    if wants(ReportSection::Audit) {
+        let findings = pods
+            .iter()
+            .filter_map(|pod| {
+                let namespace = pod.metadata.namespace.as_deref()?;
+                let name = pod.metadata.name.as_deref()?;
+                Some(audit_pod(namespace, name, pod.spec.as_ref()?))
+            })
+            .flatten()
+            .collect();
+        report.audit = Some(findings);
+    }
+
+    if wants(ReportSection::Certs) {
+        let api: Api<Secret> = match namespace {
+            Some(ns) => Api::namespaced(client.clone(), ns),
+            None => Api::all(client.clone()),
+        };
+        let params = ListParams::default().fields("type=kubernetes.io/tls");
+        let now = Utc::now();
+        let mut certs: Vec<CertificateExpiry> = api
+            .list(&params)
+            .await?
+            .items
+            .iter()
+            .map(|secret| certificate_expiry(secret, now))
+            .collect();
+        certs.sort_by_key(|c| c.days_remaining.unwrap_or(i64::MIN));
+        report.certs = Some(certs);
+    }
+
+    if wants(ReportSection::Deprecations) {
+        let api: Api<CustomResourceDefinition> = Api::all(client.clone());
+        let crds = api.list(&ListParams::default()).await?.items;
+        report.deprecations = Some(deprecated_versions(&crds));
+    }
+
+    if wants(ReportSection::Rollouts) {
+        report.rollouts = Some(stalled_rollouts(&deployments, &statefulsets, &daemonsets));
+    }
+
+    Ok(report)
+}
+
+/// Pod counts by phase, readiness and restarts; workload and config counts are
+/// filled in by the caller
+pub fn overview(pods: &[PodInfo]) -> Overview {
+    let mut overview = Overview {
+        pods: pods.len(),
+        ..Default::default()
+    };
+
+    for pod in pods {
+        *overview.pod_phases.entry(pod.phase.clone()).or_default() += 1;
+        if pod.phase == "Running" && pod.ready_containers < pod.total_containers {
+            overview.not_ready_pods += 1;
+        }
+        overview.restarts += pod.restart_count;
+    }
+
+    overview
+}
+
+/// ConfigMaps and Secrets without any consumer. The per-namespace root CA
+/// bundle and service account tokens are consumed implicitly and left out.
+pub fn unused_config(configmaps: &[ConfigMapInfo], secrets: &[SecretInfo]) -> Vec<UnusedConfig> {
+    let configmaps = configmaps
+        .iter()
+        .filter(|cm| cm.used_by.is_empty() && cm.name != "kube-root-ca.crt")
+        .map(|cm| UnusedConfig {
+            kind: "ConfigMap".to_string(),
+            namespace: cm.namespace.clone(),
+            name: cm.name.clone(),
+        });
+    let secrets = secrets
+        .iter()
+        .filter(|s| s.used_by.is_empty() && s.secret_type != "kubernetes.io/service-account-token")
+        .map(|s| UnusedConfig {
+            kind: "Secret".to_string(),
+            namespace: s.namespace.clone(),
+            name: s.name.clone(),
+        });
+
+    configmaps.chain(secrets).collect()
+}
+
+/// Flag privileged containers, host namespaces, hostPath volumes, containers
+/// running as root and containers without a memory limit
+pub fn audit_pod(namespace: &str, pod: &str, spec: &PodSpec) -> Vec<AuditFinding> {
+    let mut findings = Vec::new();
+    let mut finding = |container: Option<&str>, text: &str| {
+        findings.push(AuditFinding {
+            namespace: namespace.to_string(),
+            pod: pod.to_string(),
+            container: container.map(String::from),
+            finding: text.to_string(),
+        })
+    };
+
+    for (enabled, text) in [
+        (spec.host_network, "uses host network"),
+        (spec.host_pid, "uses host PID namespace"),
+        (spec.host_ipc, "uses host IPC namespace"),
+    ] {
+        if enabled == Some(true) {
+            finding(None, text);
+        }
+    }
+    for volume in spec.volumes.iter().flatten() {
+        if let Some(host_path) = &volume.host_path {
+            finding(None, &format!("{}{}", HOST_PATH_FINDING, host_path.path));
+        }
+    }
+
+    let pod_user = spec.security_context.as_ref().and_then(|s| s.run_as_user);
+    for container in &spec.containers {
+        let security = container.security_context.as_ref();
+        if security.and_then(|s| s.privileged) == Some(true) {
+            finding(Some(&container.name), "privileged container");
+        }
+        if security.and_then(|s| s.run_as_user).or(pod_user) == Some(0) {
+            finding(Some(&container.name), "runs as root (UID 0)");
+        }
+        let memory_limit = container
+            .resources
+            .as_ref()
+            .and_then(|r| r.limits.as_ref())
+            .is_some_and(|l| l.contains_key("memory"));
+        if !memory_limit {
+            finding(Some(&container.name), "no memory limit");
+        }
+    }
+
+    findings
+}
+
+/// Read the leaf certificate of a TLS secret and classify its expiry
+pub fn certificate_expiry(secret: &Secret, now: DateTime<Utc>) -> CertificateExpiry {
+    let not_after = secret
+        .data
+        .as_ref()
+        .and_then(|data| data.get("tls.crt"))
+        .and_then(|pem| certificate_not_after(&pem.0));
+    let days_remaining = not_after.map(|t| (t - now).num_days());
+
+    let status = match days_remaining {
+        None => "unreadable",
+        Some(days) if days < 0 => "expired",
+        Some(days) if days <= CERT_WARNING_DAYS => "expiring",
+        Some(_) => "ok",
+    };
+
+    CertificateExpiry {
+        namespace: secret.metadata.namespace.clone().unwrap_or_default(),
+        secret: secret.metadata.name.clone().unwrap_or_default(),
+        not_after,
+        days_remaining,
+        status: status.to_string(),
+    }
+}
+
+fn certificate_not_after(pem: &[u8]) -> Option<DateTime<Utc>> {
+    let (_, pem) = x509_parser::pem::parse_x509_pem(pem).ok()?;
+    let certificate = pem.parse_x509().ok()?;
+    DateTime::from_timestamp(certificate.validity().not_after.timestamp(), 0)
+}
+
+/// Served CRD versions that the CRD author marked as deprecated
+pub fn deprecated_versions(crds: &[CustomResourceDefinition]) -> Vec<DeprecatedVersion> {
+    crds.iter()
+        .flat_map(|crd| {
+            crd.spec
+                .versions
+                .iter()
+                .filter(|v| v.served && v.deprecated == Some(true))
+                .map(|v| DeprecatedVersion {
+                    crd: crd.metadata.name.clone().unwrap_or_default(),
+                    version: v.name.clone(),
+                    storage: v.storage,
+                    warning: v.deprecation_warning.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Workloads with fewer ready replicas than desired
+pub fn stalled_rollouts(
+    deployments: &[DeploymentInfo],
+    statefulsets: &[StatefulSetInfo],
+    daemonsets: &[DaemonSetInfo],
+) -> Vec<StalledRollout> {
+    let rollout = |kind: &str, namespace: &str, name: &str, desired: i32, ready: i32| {
+        (ready < desired).then(|| StalledRollout {
+            kind: kind.to_string(),
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            desired,
+            ready,
+        })
+    };
+
+    deployments
+        .iter()
+        .filter_map(|d| {
+            rollout(
+                "Deployment",
+                &d.namespace,
+                &d.name,
+                d.replicas,
+                d.available_replicas,
+            )
+        })
+        .chain(statefulsets.iter().filter_map(|s| {
+            rollout(
+                "StatefulSet",
+                &s.namespace,
+                &s.name,
+                s.replicas,
+                s.ready_replicas,
+            )
+        }))
+        .chain(
+            daemonsets
+                .iter()
+                .filter_map(|d| rollout("DaemonSet", &d.namespace, &d.name, d.desired, d.ready)),
+        )
+        .collect()
+}
+
+/// A titled table in a rendered report
+pub(crate) struct ReportTable {
+    pub(crate) title: &'static str,
+    pub(crate) headers: Vec<&'static str>,
+    pub(crate) rows: Vec<Vec<String>>,
+}
+
+fn tables(report: &Report) -> Vec<ReportTable> {
+    let mut tables = Vec::new();
+
+    if let Some(overview) = &report.overview {
+        let mut rows = vec![
+            vec!["Namespaces".to_string(), overview.namespaces.to_string()],
+            vec!["Pods".to_string(), overview.pods.to_string()],
+        ];
+        for (phase, count) in &overview.pod_phases {
+            rows.push(vec![format!("Pods ({})", phase), count.to_string()]);
+        }
+        rows.extend([
+            vec![
+                "Running pods not ready".to_string(),
+                overview.not_ready_pods.to_string(),
+            ],
+            vec![
+                "Container restarts".to_string(),
+                overview.restarts.to_string(),
+            ],
+            vec!["Deployments".to_string(), overview.deployments.to_string()],
+            vec![
+                "StatefulSets".to_string(),
+                overview.statefulsets.to_string(),
+            ],
+            vec!["DaemonSets".to_string(), overview.daemonsets.to_string()],
+            vec!["ConfigMaps".to_string(), overview.configmaps.to_string()],
+            vec!["Secrets".to_string(), overview.secrets.to_string()],
+        ]);
+        tables.push(ReportTable {
+            title: "Overview",
+            headers: vec!["Resource", "Count"],
+            rows,
+        });
+    }
+
+    if let Some(unused) = &report.unused {
+        tables.push(ReportTable {
+            title: "Unused Configuration",
+            headers: vec!["Kind", "Namespace", "Name"],
+            rows: unused
+                .iter()
+                .map(|u| vec![u.kind.clone(), u.namespace.clone(), u.name.clone()])
+                .collect(),
+        });
+    }
+
+    if let Some(audit) = &report.audit {
+        tables.push(ReportTable {
+            title: "Security Audit",
+            headers: vec!["Namespace", "Pod", "Container", "Finding"],
+            rows: audit
+                .iter()
+                .map(|f| {
+                    vec![
+                        f.namespace.clone(),
+                        f.pod.clone(),
+                        f.container.clone().unwrap_or_else(|| "-".to_string()),
+                        f.finding.clone(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    if let Some(certs) = &report.certs {
+        tables.push(ReportTable {
+            title: "TLS Certificates",
+            headers: vec!["Namespace", "Secret", "Expires", "Days Left", "Status"],
+            rows: certs
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.namespace.clone(),
+                        c.secret.clone(),
+                        c.not_after
+                            .map(|t| t.format("%Y-%m-%d").to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        c.days_remaining
+                            .map(|d| d.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        c.status.clone(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    if let Some(deprecations) = &report.deprecations {
+        tables.push(ReportTable {
+            title: "Deprecated API Versions",
+            headers: vec!["CRD", "Version", "Storage", "Warning"],
+            rows: deprecations
+                .iter()
+                .map(|d| {
+                    vec![
+                        d.crd.clone(),
+                        d.version.clone(),
+                        d.storage.to_string(),
+                        d.warning.clone().unwrap_or_else(|| "-".to_string()),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    if let Some(rollouts) = &report.rollouts {
+        tables.push(ReportTable {
+            title: "Incomplete Rollouts",
+            headers: vec!["Kind", "Namespace", "Name", "Ready", "Desired"],
+            rows: rollouts
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.kind.clone(),
+                        r.namespace.clone(),
+                        r.name.clone(),
+                        r.ready.to_string(),
+                        r.desired.to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    tables
+}
+
+fn scope(report: &Report) -> String {
+    match &report.namespace {
+        Some(ns) => format!("namespace {}", ns),
+        None => "all namespaces".to_string(),
+    }
+}
+
+/// Render the report as GitHub-flavoured Markdown
+pub fn to_markdown(report: &Report) -> String {
+    render_markdown("Cluster Health Report", &summary(report), tables(report))
+}
+
+/// Render the report as a standalone HTML page
+pub fn to_html(report: &Report) -> String {
+    render_html("Cluster Health Report", &summary(report), tables(report))
+}
+
+fn summary(report: &Report) -> String {
+    format!(
+        "Generated {} for {}.",
+        report.generated_at.format("%Y-%m-%d %H:%M UTC"),
+        scope(report)
+    )
+}
+
+/// Markdown document with a heading, a summary line and one section per table
+pub(crate) fn render_markdown(title: &str, summary: &str, tables: Vec<ReportTable>) -> String {
+    let mut out = format!("# {}\n\n{}\n", title, summary);
+
+    for table in tables {
+        out.push_str(&format!("\n## {}\n\n", table.title));
+        if table.rows.is_empty() {
+            out.push_str("No findings.\n");
+            continue;
+        }
+        out.push_str(&format!("| {} |\n", table.headers.join(" | ")));
+        out.push_str(&format!("|{}\n", " --- |".repeat(table.headers.len())));
+        for row in table.rows {
+            let cells: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+
+    out
+}
+
+/// Standalone HTML page with the same layout as [`render_markdown`]
+pub(crate) fn render_html(title: &str, summary: &str, tables: Vec<ReportTable>) -> String {
+    let title = escape_html(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+         th,td{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>\n</head>\n<body>\n",
+        title
+    );
+    out.push_str(&format!(
+        "<h1>{}</h1>\n<p>{}</p>\n",
+        title,
+        escape_html(summary)
+    ));
+
+    for table in tables {
+        out.push_str(&format!("<h2>{}</h2>\n", table.title));
+        if table.rows.is_empty() {
+            out.push_str("<p>No findings.</p>\n");
+            continue;
+        }
+        out.push_str("<table>\n<tr>");
+        for header in &table.headers {
+            out.push_str(&format!("<th>{}</th>", header));
+        }
+        out.push_str("</tr>\n");
+        for row in &table.rows {
+            out.push_str("<tr>");
+            for cell in row {
+                out.push_str(&format!("<td>{}</td>", escape_html(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{
+        Container, HostPathVolumeSource, ResourceRequirements, SecurityContext, Volume,
+    };
+    use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+    #[test]
+    fn test_audit_pod() {
+        let spec = PodSpec {
+            host_network: Some(true),
+            volumes: Some(vec![Volume {
+                name: "docker".to_string(),
+                host_path: Some(HostPathVolumeSource {
+                    path: "/var/run/docker.sock".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]),
+            containers: vec![
+                Container {
+                    name: "agent".to_string(),
+                    security_context: Some(SecurityContext {
+                        privileged: Some(true),
+                        run_as_user: Some(0),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                Container {
+                    name: "web".to_string(),
+                    resources: Some(ResourceRequirements {
+                        limits: Some(
+                            [("memory".to_string(), Quantity("256Mi".to_string()))].into(),
+                        ),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let findings: Vec<String> = audit_pod("ops", "agent-x", &spec)
+            .into_iter()
+            .map(|f| f.finding)
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "uses host network",
+                "mounts hostPath /var/run/docker.sock",
+                "privileged container",
+                "runs as root (UID 0)",
+                "no memory limit",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stalled_rollouts() {
+        let deployment = |name: &str, replicas: i32, available: i32| DeploymentInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            replicas,
+            ready_replicas: available,
+            available_replicas: available,
+            strategy: "RollingUpdate".to_string(),
+            age: "1d".to_string(),
+            labels: BTreeMap::new(),
+            selector: BTreeMap::new(),
+            images: BTreeMap::new(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+        };
+
+        let stalled = stalled_rollouts(
+            &[deployment("web", 3, 3), deployment("api", 3, 1)],
+            &[],
+            &[],
+        );
+        assert_eq!(stalled.len(), 1);
+        assert_eq!(stalled[0].name, "api");
+    }
+
+    #[test]
+    fn test_markdown_rendering() {
+        let report = Report {
+            unused: Some(vec![UnusedConfig {
+                kind: "ConfigMap".to_string(),
+                namespace: "default".to_string(),
+                name: "old|config".to_string(),
+            }]),
+            rollouts: Some(Vec::new()),
+            ..Default::default()
+        };
+
+        let markdown = to_markdown(&report);
+        assert!(markdown.contains("## Unused Configuration"));
+        assert!(markdown.contains("| ConfigMap | default | old\\|config |"));
+        assert!(markdown.contains("## Incomplete Rollouts\n\nNo findings."));
+        assert!(!markdown.contains("## Overview"));
+
+        let html = to_html(&report);
+        assert!(html.contains("<td>old|config</td>"));
+    }
+}
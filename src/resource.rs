@@ -0,0 +1,156 @@
+//! Resource kinds and `kind/name` references accepted on the command line
+
+use crate::error::{ExplorerError, Result};
+use crate::kinds;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{Pod, Service};
+use kube::api::ApiResource;
+use std::fmt;
+use std::str::FromStr;
+
+/// Resource kinds that can be addressed as `kind/name`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Pod,
+    Service,
+    Deployment,
+    StatefulSet,
+    DaemonSet,
+    ReplicaSet,
+    Job,
+    CronJob,
+}
+
+impl ResourceKind {
+    /// Every addressable kind
+    pub const ALL: [ResourceKind; 8] = [
+        ResourceKind::Pod,
+        ResourceKind::Service,
+        ResourceKind::Deployment,
+        ResourceKind::StatefulSet,
+        ResourceKind::DaemonSet,
+        ResourceKind::ReplicaSet,
+        ResourceKind::Job,
+        ResourceKind::CronJob,
+    ];
+
+    /// Canonical Kubernetes kind name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResourceKind::Pod => "Pod",
+            ResourceKind::Service => "Service",
+            ResourceKind::Deployment => "Deployment",
+            ResourceKind::StatefulSet => "StatefulSet",
+            ResourceKind::DaemonSet => "DaemonSet",
+            ResourceKind::ReplicaSet => "ReplicaSet",
+            ResourceKind::Job => "Job",
+            ResourceKind::CronJob => "CronJob",
+        }
+    }
+
+    /// API resource used to fetch objects of this kind dynamically
+    pub fn api_resource(&self) -> ApiResource {
+        match self {
+            ResourceKind::Pod => ApiResource::erase::<Pod>(&()),
+            ResourceKind::Service => ApiResource::erase::<Service>(&()),
+            ResourceKind::Deployment => ApiResource::erase::<Deployment>(&()),
+            ResourceKind::StatefulSet => ApiResource::erase::<StatefulSet>(&()),
+            ResourceKind::DaemonSet => ApiResource::erase::<DaemonSet>(&()),
+            ResourceKind::ReplicaSet => ApiResource::erase::<ReplicaSet>(&()),
+            ResourceKind::Job => ApiResource::erase::<Job>(&()),
+            ResourceKind::CronJob => ApiResource::erase::<CronJob>(&()),
+        }
+    }
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str().to_lowercase())
+    }
+}
+
+impl FromStr for ResourceKind {
+    type Err = ExplorerError;
+
+    /// Accepts singular, plural and short names (e.g. "deploy", "sts", "svc")
+    fn from_str(s: &str) -> Result<Self> {
+        let kind = kinds::builtin_kind(s);
+        ResourceKind::ALL
+            .into_iter()
+            .find(|k| Some(k.as_str()) == kind)
+            .ok_or_else(|| {
+                ExplorerError::InvalidArgument(format!(
+                    "unsupported resource kind '{}'",
+                    s.to_lowercase()
+                ))
+            })
+    }
+}
+
+/// A `kind/name` reference to a single resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectRef {
+    pub kind: ResourceKind,
+    pub name: String,
+}
+
+impl fmt::Display for ObjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.kind, self.name)
+    }
+}
+
+impl FromStr for ObjectRef {
+    type Err = ExplorerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, name) = s.split_once('/').ok_or_else(|| {
+            ExplorerError::InvalidArgument(format!("expected kind/name, got '{}'", s))
+        })?;
+
+        if name.is_empty() || name.contains('/') {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "invalid resource name in '{}'",
+                s
+            )));
+        }
+
+        Ok(ObjectRef {
+            kind: kind.parse()?,
+            name: name.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kind_aliases() {
+        assert_eq!(
+            "deploy".parse::<ResourceKind>().unwrap(),
+            ResourceKind::Deployment
+        );
+        assert_eq!(
+            "SVC".parse::<ResourceKind>().unwrap(),
+            ResourceKind::Service
+        );
+        assert_eq!("pods".parse::<ResourceKind>().unwrap(), ResourceKind::Pod);
+        assert_eq!("cj".parse::<ResourceKind>().unwrap(), ResourceKind::CronJob);
+        assert!("widget".parse::<ResourceKind>().is_err());
+    }
+
+    #[test]
+    fn test_parse_object_ref() {
+        let target: ObjectRef = "deployment/web".parse().unwrap();
+        assert_eq!(target.kind, ResourceKind::Deployment);
+        assert_eq!(target.name, "web");
+        assert_eq!(target.to_string(), "deployment/web");
+
+        assert!("web".parse::<ObjectRef>().is_err());
+        assert!("pod/".parse::<ObjectRef>().is_err());
+        assert!("pod/a/b".parse::<ObjectRef>().is_err());
+    }
+}
@@ -0,0 +1,337 @@
+//! Deployment rollout history reconstructed from ReplicaSets
+//!
+//! Each rollout of a Deployment creates (or reuses, on rollback) a
+//! ReplicaSet and stamps it with the rollout's revision number. Ordering the
+//! Deployment's ReplicaSets by revision gives the same history as
+//! `kubectl rollout history`, with the images and replica counts of each
+//! revision alongside. History is limited by `revisionHistoryLimit`: older
+//! ReplicaSets are garbage collected.
+//!
+//! Two revisions are compared field by field on their ReplicaSets' pod
+//! templates, without the `pod-template-hash` label the Deployment adds.
+//! List entries with a `name`, such as containers, env vars and volumes, are
+//! matched by name rather than position, so reordering them is no change.
+
+use crate::discovery::{DiscoveryEngine, ReplicaSetInfo};
+use crate::error::{ExplorerError, Result};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// One revision of a Deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutRevision {
+    pub revision: i64,
+    pub replicaset: String,
+    pub pod_template_hash: Option<String>,
+    pub images: Vec<String>,
+    pub desired: i32,
+    pub ready: i32,
+    pub change_cause: Option<String>,
+    /// Earlier revisions served by the same ReplicaSet before a rollback to it
+    pub previous_revisions: Vec<i64>,
+    pub age: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// The revision the Deployment currently rolls out
+    pub current: bool,
+}
+
+/// Rollout history of `deployment` from its ReplicaSets, oldest revision
+/// first. ReplicaSets without a revision annotation are left out.
+pub fn rollout_history(deployment: &str, replicasets: &[ReplicaSetInfo]) -> Vec<RolloutRevision> {
+    let mut history: Vec<RolloutRevision> = replicasets
+        .iter()
+        .filter(|rs| rs.deployment.as_deref() == Some(deployment))
+        .filter_map(|rs| {
+            Some(RolloutRevision {
+                revision: rs.revision?,
+                replicaset: rs.name.clone(),
+                pod_template_hash: rs.pod_template_hash.clone(),
+                images: rs.images.clone(),
+                desired: rs.desired,
+                ready: rs.ready,
+                change_cause: rs.change_cause.clone(),
+                previous_revisions: rs.revision_history.clone(),
+                age: rs.age.clone(),
+                created_at: rs.created_at,
+                current: false,
+            })
+        })
+        .collect();
+
+    history.sort_by_key(|r| r.revision);
+    if let Some(latest) = history.last_mut() {
+        latest.current = true;
+    }
+    history
+}
+
+/// A pod template field that differs between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateChange {
+    /// Field path, e.g. `spec.containers[app].image`
+    pub path: String,
+    /// Value in the older revision; absent when the field was added
+    pub from: Option<String>,
+    /// Value in the newer revision; absent when the field was removed
+    pub to: Option<String>,
+}
+
+/// Pod template differences between two revisions of a Deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub deployment: String,
+    pub namespace: String,
+    pub from_revision: i64,
+    pub from_replicaset: String,
+    pub to_revision: i64,
+    pub to_replicaset: String,
+    pub changes: Vec<TemplateChange>,
+}
+
+/// Leaf fields of `value` by path, skipping nulls and empty containers
+fn flatten(path: &str, value: &Value, fields: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Null => {}
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten(&path, value, fields);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                let key = match item.get("name").and_then(Value::as_str) {
+                    Some(name) => name.to_string(),
+                    None => i.to_string(),
+                };
+                flatten(&format!("{}[{}]", path, key), item, fields);
+            }
+        }
+        Value::String(text) => {
+            fields.insert(path.to_string(), text.clone());
+        }
+        value => {
+            fields.insert(path.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Fields of a ReplicaSet's pod template as a Deployment revision
+pub fn template_fields(replicaset: &ReplicaSet) -> BTreeMap<String, String> {
+    let mut template = replicaset
+        .spec
+        .as_ref()
+        .and_then(|s| serde_json::to_value(&s.template).ok())
+        .unwrap_or_default();
+    if let Some(labels) = template
+        .pointer_mut("/metadata/labels")
+        .and_then(Value::as_object_mut)
+    {
+        labels.remove("pod-template-hash");
+    }
+    let mut fields = BTreeMap::new();
+    flatten("", &template, &mut fields);
+    fields
+}
+
+/// Fields that differ between two flattened templates, in path order
+pub fn template_changes(
+    from: &BTreeMap<String, String>,
+    to: &BTreeMap<String, String>,
+) -> Vec<TemplateChange> {
+    let mut paths: Vec<&String> = from.keys().chain(to.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .filter(|path| from.get(*path) != to.get(*path))
+        .map(|path| TemplateChange {
+            path: path.clone(),
+            from: from.get(path).cloned(),
+            to: to.get(path).cloned(),
+        })
+        .collect()
+}
+
+/// Compare the pod templates of revisions `from` and `to` of `deployment`.
+/// A revision a rollback reused is found on the ReplicaSet that now
+/// carries it in its revision history.
+pub async fn revision_diff(
+    discovery: &DiscoveryEngine,
+    deployment: &str,
+    namespace: &str,
+    from: i64,
+    to: i64,
+) -> Result<RevisionDiff> {
+    let api: Api<ReplicaSet> = Api::namespaced(discovery.client().clone(), namespace);
+    let mut revisions = Vec::new();
+    for replicaset in api.list(&ListParams::default()).await?.items {
+        let fields = template_fields(&replicaset);
+        if let Some(info) = discovery.convert_replicaset_to_info(replicaset).await {
+            if info.deployment.as_deref() == Some(deployment) {
+                revisions.push((info, fields));
+            }
+        }
+    }
+    if revisions.is_empty() {
+        return Err(ExplorerError::ResourceNotFound {
+            kind: "Deployment".to_string(),
+            name: deployment.to_string(),
+            namespace: namespace.to_string(),
+        });
+    }
+
+    let find = |revision: i64| {
+        revisions
+            .iter()
+            .find(|(rs, _)| rs.revision == Some(revision))
+            .or_else(|| {
+                revisions
+                    .iter()
+                    .find(|(rs, _)| rs.revision_history.contains(&revision))
+            })
+            .ok_or_else(|| {
+                let mut known: Vec<i64> = revisions
+                    .iter()
+                    .flat_map(|(rs, _)| rs.revision.into_iter().chain(rs.revision_history.clone()))
+                    .collect();
+                known.sort();
+                ExplorerError::InvalidArgument(format!(
+                    "deployment/{} has no revision {} (known: {})",
+                    deployment,
+                    revision,
+                    known
+                        .iter()
+                        .map(i64::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })
+    };
+    let (from_rs, from_fields) = find(from)?;
+    let (to_rs, to_fields) = find(to)?;
+
+    Ok(RevisionDiff {
+        deployment: deployment.to_string(),
+        namespace: namespace.to_string(),
+        from_revision: from,
+        from_replicaset: from_rs.name.clone(),
+        to_revision: to,
+        to_replicaset: to_rs.name.clone(),
+        changes: template_changes(from_fields, to_fields),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replicaset(name: &str, revision: Option<i64>, history: &[i64]) -> ReplicaSetInfo {
+        ReplicaSetInfo {
+            name: name.to_string(),
+            namespace: "shop".to_string(),
+            desired: 0,
+            current: 0,
+            ready: 0,
+            deployment: Some("web".to_string()),
+            revision,
+            revision_history: history.to_vec(),
+            pod_template_hash: name.strip_prefix("web-").map(str::to_string),
+            change_cause: None,
+            images: vec![format!("web:{}", name)],
+            age: "1d".to_string(),
+            created_at: None,
+            annotations: BTreeMap::new(),
+            labels: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_rollout_history() {
+        let mut other = replicaset("api-5c9d7", Some(9), &[]);
+        other.deployment = Some("api".to_string());
+        let replicasets = [
+            // Rolled back to: revision 1 became revision 4
+            replicaset("web-6b8f4", Some(4), &[1]),
+            replicaset("web-7d9f2", Some(2), &[]),
+            replicaset("web-8a1c3", Some(3), &[]),
+            replicaset("web-unannotated", None, &[]),
+            other,
+        ];
+
+        let history = rollout_history("web", &replicasets);
+        let summary: Vec<(i64, &str, bool)> = history
+            .iter()
+            .map(|r| (r.revision, r.replicaset.as_str(), r.current))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (2, "web-7d9f2", false),
+                (3, "web-8a1c3", false),
+                (4, "web-6b8f4", true),
+            ]
+        );
+        assert_eq!(history[2].previous_revisions, [1]);
+        assert_eq!(history[2].pod_template_hash.as_deref(), Some("6b8f4"));
+        assert!(rollout_history("missing", &replicasets).is_empty());
+    }
+
+    #[test]
+    fn test_template_changes() {
+        let replicaset = |hash: &str, image: &str, env: serde_json::Value| -> ReplicaSet {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": format!("web-{}", hash)},
+                "spec": {
+                    "selector": {},
+                    "template": {
+                        "metadata": {"labels": {"app": "web", "pod-template-hash": hash}},
+                        "spec": {"containers": [
+                            {"name": "proxy", "image": "envoy:1.29"},
+                            {"name": "app", "image": image, "env": env},
+                        ]},
+                    },
+                },
+            }))
+            .unwrap()
+        };
+        let old = template_fields(&replicaset(
+            "7d9f2",
+            "web:1.0",
+            serde_json::json!([{"name": "MODE", "value": "a"}, {"name": "DEBUG", "value": "1"}]),
+        ));
+        let new = template_fields(&replicaset(
+            "8a1c3",
+            "web:1.1",
+            serde_json::json!([{"name": "MODE", "value": "a"}]),
+        ));
+        assert_eq!(old["spec.containers[proxy].image"], "envoy:1.29");
+
+        let changes = template_changes(&old, &new);
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.from.as_deref(), c.to.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("spec.containers[app].env[DEBUG].name", Some("DEBUG"), None),
+                ("spec.containers[app].env[DEBUG].value", Some("1"), None),
+                (
+                    "spec.containers[app].image",
+                    Some("web:1.0"),
+                    Some("web:1.1")
+                ),
+            ]
+        );
+    }
+}
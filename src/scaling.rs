@@ -0,0 +1,401 @@
+//! Replica changes of workloads (`kdx scaling-events`)
+//!
+//! Autoscalers record each rescale as a `SuccessfulRescale` event carrying
+//! the new size and the metric that caused it. The deployment controller
+//! records a `ScalingReplicaSet` event whenever it resizes a replica set,
+//! which happens both when the Deployment's replica count changes and
+//! during rollouts; events for two replica sets of one Deployment close
+//! together are a rollout and are left out. A change to a workload no
+//! autoscaler targets was made by hand or by a tool applying manifests.
+//!
+//! Events expire after an hour by default. Snapshots recorded with
+//! `kdx history record` keep replica counts for longer, and consecutive
+//! snapshots with different counts are reported as well.
+
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use crate::events::{self, EventInfo};
+use crate::history::ClusterSnapshot;
+use crate::hpa::{self, HpaInfo};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Replica set events of one Deployment this close together are a rollout
+const ROLLOUT_WINDOW_SECS: i64 = 300;
+
+/// What changed the replica count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScalingSource {
+    Autoscaler,
+    Manual,
+}
+
+impl std::fmt::Display for ScalingSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScalingSource::Autoscaler => write!(f, "autoscaler"),
+            ScalingSource::Manual => write!(f, "manual"),
+        }
+    }
+}
+
+/// A change of a workload's replica count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalingEvent {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    pub time: Option<DateTime<Utc>>,
+    /// Replicas before the change, when known
+    pub from: Option<i32>,
+    pub to: i32,
+    pub source: ScalingSource,
+    /// Metric behind an autoscaler's decision, or what recorded the change
+    pub reason: String,
+    /// Times the change was repeated with the same message
+    pub count: i32,
+}
+
+impl ScalingEvent {
+    /// The workload as kind/name
+    pub fn workload(&self) -> String {
+        format!("{}/{}", self.kind.to_lowercase(), self.name)
+    }
+}
+
+/// New size and reason of a `SuccessfulRescale` message, e.g.
+/// `New size: 5; reason: cpu resource utilization (percentage of request)
+/// above target`
+fn parse_rescale(message: &str) -> Option<(i32, String)> {
+    let rest = message.strip_prefix("New size: ")?;
+    let (size, reason) = rest.split_once("; reason: ").unwrap_or((rest, ""));
+    Some((size.trim().parse().ok()?, reason.trim().to_string()))
+}
+
+/// A replica set resized by the deployment controller
+#[derive(Debug, PartialEq, Eq)]
+struct ReplicaSetResize {
+    replica_set: String,
+    from: Option<i32>,
+    to: i32,
+}
+
+/// The resize reported by a `ScalingReplicaSet` message, e.g. `Scaled down
+/// replica set web-5d4f from 3 to 2`; older controllers leave out the old
+/// size
+fn parse_scaled_replica_set(message: &str) -> Option<ReplicaSetResize> {
+    let rest = message
+        .strip_prefix("Scaled up replica set ")
+        .or_else(|| message.strip_prefix("Scaled down replica set "))?;
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let (replica_set, from, to) = match words.as_slice() {
+        [name, "to", to] => (name, None, to),
+        [name, "from", from, "to", to] => (name, Some(from.parse().ok()?), to),
+        _ => return None,
+    };
+    Some(ReplicaSetResize {
+        replica_set: replica_set.to_string(),
+        from,
+        to: to.parse().ok()?,
+    })
+}
+
+fn close(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>, seconds: i64) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).num_seconds().abs() <= seconds,
+        _ => false,
+    }
+}
+
+/// Replica changes found in `events`, attributed with `hpas`
+pub fn from_events(events: &[EventInfo], hpas: &[HpaInfo]) -> Vec<ScalingEvent> {
+    let autoscaler_of = |namespace: &str, kind: &str, name: &str| {
+        hpas.iter()
+            .find(|h| h.namespace == namespace && h.scales(kind, name))
+    };
+
+    let mut changes = Vec::new();
+    for event in events.iter().filter(|e| e.reason == "SuccessfulRescale") {
+        let Some((to, reason)) = parse_rescale(&event.message) else {
+            continue;
+        };
+        // An autoscaler that is gone leaves its own name as the workload
+        let (kind, name) = hpas
+            .iter()
+            .find(|h| h.namespace == event.namespace && h.name == event.object_name)
+            .map(|h| (h.target_kind.clone(), h.target_name.clone()))
+            .unwrap_or_else(|| (event.object_kind.clone(), event.object_name.clone()));
+        changes.push(ScalingEvent {
+            namespace: event.namespace.clone(),
+            kind,
+            name,
+            time: event.last_seen,
+            from: None,
+            to,
+            source: ScalingSource::Autoscaler,
+            reason,
+            count: event.count,
+        });
+    }
+
+    let resizes: Vec<(&EventInfo, ReplicaSetResize)> = events
+        .iter()
+        .filter(|e| e.reason == "ScalingReplicaSet" && e.object_kind == "Deployment")
+        .filter_map(|e| Some((e, parse_scaled_replica_set(&e.message)?)))
+        .collect();
+    for (event, resize) in &resizes {
+        let rollout = resizes.iter().any(|(other, other_resize)| {
+            other.namespace == event.namespace
+                && other.object_name == event.object_name
+                && other_resize.replica_set != resize.replica_set
+                && close(other.last_seen, event.last_seen, ROLLOUT_WINDOW_SECS)
+        });
+        if rollout {
+            continue;
+        }
+        let autoscaled = autoscaler_of(&event.namespace, "Deployment", &event.object_name);
+        // The autoscaler's own event already reports the change
+        let reported = autoscaled.is_some()
+            && changes.iter().any(|c| {
+                c.namespace == event.namespace
+                    && c.kind == "Deployment"
+                    && c.name == event.object_name
+                    && c.to == resize.to
+                    && close(c.time, event.last_seen, 60)
+            });
+        if reported {
+            continue;
+        }
+        changes.push(ScalingEvent {
+            namespace: event.namespace.clone(),
+            kind: "Deployment".to_string(),
+            name: event.object_name.clone(),
+            time: event.last_seen,
+            from: resize.from,
+            to: resize.to,
+            source: if autoscaled.is_some() {
+                ScalingSource::Autoscaler
+            } else {
+                ScalingSource::Manual
+            },
+            reason: format!("replica set {} resized", resize.replica_set),
+            count: event.count,
+        });
+    }
+
+    // Fill in the size before each autoscaler change from the one before it
+    changes.sort_by_key(|c| c.time);
+    let mut last: BTreeMap<(String, String, String), i32> = BTreeMap::new();
+    for change in &mut changes {
+        let key = (
+            change.namespace.clone(),
+            change.kind.clone(),
+            change.name.clone(),
+        );
+        if change.from.is_none() {
+            change.from = last.get(&key).copied();
+        }
+        last.insert(key, change.to);
+    }
+    changes
+}
+
+/// Replica counts of the Deployments and StatefulSets in `snapshot`
+fn replica_counts(
+    snapshot: &ClusterSnapshot,
+    namespace: Option<&str>,
+) -> BTreeMap<(String, String, String), i32> {
+    let deployments = snapshot
+        .deployments(namespace)
+        .into_iter()
+        .map(|d| ((d.namespace, "Deployment".to_string(), d.name), d.replicas));
+    let statefulsets = snapshot
+        .statefulsets(namespace)
+        .into_iter()
+        .map(|s| ((s.namespace, "StatefulSet".to_string(), s.name), s.replicas));
+    deployments.chain(statefulsets).collect()
+}
+
+/// Replica changes between consecutive `snapshots`, oldest first
+pub fn from_snapshots(
+    snapshots: &[ClusterSnapshot],
+    namespace: Option<&str>,
+    hpas: &[HpaInfo],
+) -> Vec<ScalingEvent> {
+    let mut changes = Vec::new();
+    for pair in snapshots.windows(2) {
+        let before = replica_counts(&pair[0], namespace);
+        for ((ns, kind, name), to) in replica_counts(&pair[1], namespace) {
+            let Some(&from) = before.get(&(ns.clone(), kind.clone(), name.clone())) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            let autoscaled = hpas
+                .iter()
+                .any(|h| h.namespace == ns && h.scales(&kind, &name));
+            changes.push(ScalingEvent {
+                reason: format!(
+                    "recorded between snapshots at {} and {}",
+                    pair[0].taken_at.format("%H:%M"),
+                    pair[1].taken_at.format("%H:%M")
+                ),
+                namespace: ns,
+                kind,
+                name,
+                time: Some(pair[1].taken_at),
+                from: Some(from),
+                to,
+                source: if autoscaled {
+                    ScalingSource::Autoscaler
+                } else {
+                    ScalingSource::Manual
+                },
+                count: 1,
+            });
+        }
+    }
+    changes
+}
+
+/// Replica changes in `namespace` (or all namespaces if None) since
+/// `since`, from events and from `snapshots`, oldest first
+pub async fn find_scaling_events(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+    since: DateTime<Utc>,
+    snapshots: &[ClusterSnapshot],
+) -> Result<Vec<ScalingEvent>> {
+    let client = discovery.client();
+    let (events, hpas) = tokio::try_join!(
+        events::list_events(client, namespace, None),
+        hpa::list_autoscalers(client, namespace)
+    )?;
+    let events = events::filter_events(events, Some(since), None);
+
+    let mut changes = from_events(&events, &hpas);
+    changes.extend(from_snapshots(snapshots, namespace, &hpas));
+    changes.retain(|c| c.time.is_none_or(|t| t >= since));
+    discovery
+        .namespace_scope()
+        .retain(&mut changes, |c| Some(c.namespace.as_str()));
+    changes.sort_by_key(|c| c.time);
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: &str, name: &str, reason: &str, message: &str, time: &str) -> EventInfo {
+        EventInfo {
+            namespace: "shop".to_string(),
+            event_type: "Normal".to_string(),
+            reason: reason.to_string(),
+            message: message.to_string(),
+            count: 1,
+            object_kind: kind.to_string(),
+            object_name: name.to_string(),
+            first_seen: time.parse().ok(),
+            last_seen: time.parse().ok(),
+            age: "1m".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_scaling_events_from_events() {
+        let hpa: HpaInfo = serde_json::from_value(serde_json::json!({
+            "name": "web", "namespace": "shop", "target_kind": "Deployment",
+            "target_name": "web", "target_found": true, "min_replicas": 2,
+            "max_replicas": 10, "current_replicas": 4, "desired_replicas": 4,
+            "metrics": [], "age": "3d", "created_at": null,
+        }))
+        .unwrap();
+        let events = vec![
+            event(
+                "HorizontalPodAutoscaler",
+                "web",
+                "SuccessfulRescale",
+                "New size: 3; reason: cpu resource utilization (percentage of request) above target",
+                "2024-05-01T10:00:00Z",
+            ),
+            event(
+                "Deployment",
+                "web",
+                "ScalingReplicaSet",
+                "Scaled up replica set web-5d4f from 2 to 3",
+                "2024-05-01T10:00:05Z",
+            ),
+            event(
+                "HorizontalPodAutoscaler",
+                "web",
+                "SuccessfulRescale",
+                "New size: 4; reason: All metrics below target",
+                "2024-05-01T11:00:00Z",
+            ),
+            // A rollout: two replica sets resized together
+            event(
+                "Deployment",
+                "api",
+                "ScalingReplicaSet",
+                "Scaled up replica set api-7c9 from 0 to 1",
+                "2024-05-01T10:10:00Z",
+            ),
+            event(
+                "Deployment",
+                "api",
+                "ScalingReplicaSet",
+                "Scaled down replica set api-6b1 from 2 to 1",
+                "2024-05-01T10:10:30Z",
+            ),
+            event(
+                "Deployment",
+                "worker",
+                "ScalingReplicaSet",
+                "Scaled down replica set worker-1a2 to 0",
+                "2024-05-01T10:30:00Z",
+            ),
+        ];
+
+        let changes = from_events(&events, &[hpa]);
+        let summary: Vec<(String, Option<i32>, i32, ScalingSource)> = changes
+            .iter()
+            .map(|c| (c.workload(), c.from, c.to, c.source))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "deployment/web".to_string(),
+                    None,
+                    3,
+                    ScalingSource::Autoscaler
+                ),
+                (
+                    "deployment/worker".to_string(),
+                    None,
+                    0,
+                    ScalingSource::Manual
+                ),
+                (
+                    "deployment/web".to_string(),
+                    Some(3),
+                    4,
+                    ScalingSource::Autoscaler
+                ),
+            ]
+        );
+        assert_eq!(changes[2].reason, "All metrics below target");
+        assert_eq!(
+            parse_scaled_replica_set("Scaled down replica set web-5d4f from 3 to 2"),
+            Some(ReplicaSetResize {
+                replica_set: "web-5d4f".to_string(),
+                from: Some(3),
+                to: 2,
+            })
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Local validation of custom resources against CRD structural schemas
+
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+    CustomResourceDefinition, JSONSchemaProps, JSONSchemaPropsOrArray, JSONSchemaPropsOrBool,
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Top-level fields validated by the API server itself rather than the CRD schema
+const ROOT_FIELDS: &[&str] = &["apiVersion", "kind", "metadata"];
+
+/// A single schema violation at a JSON path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub message: String,
+}
+
+/// Validation outcome for one custom resource instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceValidation {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub age: String,
+    pub violations: Vec<SchemaViolation>,
+}
+
+/// Return the OpenAPI v3 schema of a CRD version, if the CRD defines one
+pub fn version_schema<'a>(
+    crd: &'a CustomResourceDefinition,
+    version: &str,
+) -> Option<&'a JSONSchemaProps> {
+    crd.spec
+        .versions
+        .iter()
+        .find(|v| v.name == version)
+        .and_then(|v| v.schema.as_ref())
+        .and_then(|s| s.open_api_v3_schema.as_ref())
+}
+
+/// Validate a whole object against its CRD version schema
+pub fn validate_object(object: &Value, schema: &JSONSchemaProps) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_value(object, schema, "", true, &mut violations);
+    violations
+}
+
+fn violation(violations: &mut Vec<SchemaViolation>, path: &str, message: String) {
+    violations.push(SchemaViolation {
+        path: if path.is_empty() {
+            ".".to_string()
+        } else {
+            path.to_string()
+        },
+        message,
+    });
+}
+
+fn validate_value(
+    value: &Value,
+    schema: &JSONSchemaProps,
+    path: &str,
+    root: bool,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    // Nulls are dropped by the API server unless the field is nullable
+    if value.is_null() {
+        return;
+    }
+
+    if schema.x_kubernetes_int_or_string == Some(true) {
+        if !(value.is_string() || is_integer(value)) {
+            violation(violations, path, "must be an integer or string".to_string());
+        }
+        return;
+    }
+
+    if let Some(expected) = schema.type_.as_deref() {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => is_integer(value),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            _ => true,
+        };
+        if !matches {
+            violation(
+                violations,
+                path,
+                format!("expected {}, found {}", expected, json_type(value)),
+            );
+            return;
+        }
+    }
+
+    if let Some(allowed) = &schema.enum_ {
+        if !allowed.iter().any(|a| a.0 == *value) {
+            let options: Vec<String> = allowed.iter().map(|a| a.0.to_string()).collect();
+            violation(
+                violations,
+                path,
+                format!("value {} not in enum [{}]", value, options.join(", ")),
+            );
+        }
+    }
+
+    match value {
+        Value::String(s) => validate_string(s, schema, path, violations),
+        Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                validate_number(n, schema, path, violations);
+            }
+        }
+        Value::Array(items) => validate_array(items, schema, path, violations),
+        Value::Object(map) => validate_map(map, schema, path, root, violations),
+        _ => {}
+    }
+}
+
+fn validate_string(
+    s: &str,
+    schema: &JSONSchemaProps,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    let length = s.chars().count() as i64;
+    if let Some(min) = schema.min_length.filter(|min| length < *min) {
+        violation(violations, path, format!("shorter than minLength {}", min));
+    }
+    if let Some(max) = schema.max_length.filter(|max| length > *max) {
+        violation(violations, path, format!("longer than maxLength {}", max));
+    }
+    if let Some(pattern) = &schema.pattern {
+        // Patterns the regex engine cannot compile are skipped rather than reported
+        if let Ok(re) = Regex::new(pattern) {
+            if !re.is_match(s) {
+                violation(
+                    violations,
+                    path,
+                    format!("does not match pattern '{}'", pattern),
+                );
+            }
+        }
+    }
+}
+
+fn validate_number(
+    n: f64,
+    schema: &JSONSchemaProps,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    if let Some(min) = schema.minimum {
+        let exclusive = schema.exclusive_minimum == Some(true);
+        if n < min || (exclusive && n == min) {
+            violation(violations, path, format!("{} is below minimum {}", n, min));
+        }
+    }
+    if let Some(max) = schema.maximum {
+        let exclusive = schema.exclusive_maximum == Some(true);
+        if n > max || (exclusive && n == max) {
+            violation(violations, path, format!("{} is above maximum {}", n, max));
+        }
+    }
+}
+
+fn validate_array(
+    items: &[Value],
+    schema: &JSONSchemaProps,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    let count = items.len() as i64;
+    if let Some(min) = schema.min_items.filter(|min| count < *min) {
+        violation(violations, path, format!("fewer than minItems {}", min));
+    }
+    if let Some(max) = schema.max_items.filter(|max| count > *max) {
+        violation(violations, path, format!("more than maxItems {}", max));
+    }
+
+    if let Some(JSONSchemaPropsOrArray::Schema(item_schema)) = &schema.items {
+        for (i, item) in items.iter().enumerate() {
+            let item_path = format!("{}[{}]", path, i);
+            validate_value(item, item_schema, &item_path, false, violations);
+        }
+    }
+}
+
+fn validate_map(
+    map: &serde_json::Map<String, Value>,
+    schema: &JSONSchemaProps,
+    path: &str,
+    root: bool,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    for field in schema.required.iter().flatten() {
+        if root && ROOT_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        if map.get(field).is_none_or(Value::is_null) {
+            violation(
+                violations,
+                &format!("{}.{}", path, field),
+                "missing required field".to_string(),
+            );
+        }
+    }
+
+    // Embedded resources and preserved fields accept arbitrary content
+    let open = schema.x_kubernetes_preserve_unknown_fields == Some(true)
+        || schema.x_kubernetes_embedded_resource == Some(true);
+
+    for (key, child) in map {
+        if root && ROOT_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let child_path = format!("{}.{}", path, key);
+
+        if let Some(child_schema) = schema.properties.as_ref().and_then(|p| p.get(key)) {
+            validate_value(child, child_schema, &child_path, false, violations);
+            continue;
+        }
+
+        match &schema.additional_properties {
+            Some(JSONSchemaPropsOrBool::Schema(value_schema)) => {
+                validate_value(child, value_schema, &child_path, false, violations);
+            }
+            Some(JSONSchemaPropsOrBool::Bool(true)) => {}
+            _ if open => {}
+            _ => violation(
+                violations,
+                &child_path,
+                "unknown field (pruned or rejected by strict validation)".to_string(),
+            ),
+        }
+    }
+}
+
+fn is_integer(value: &Value) -> bool {
+    value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> JSONSchemaProps {
+        serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "spec": {
+                    "type": "object",
+                    "required": ["engine"],
+                    "properties": {
+                        "engine": {"type": "string", "enum": ["postgres", "mysql"]},
+                        "replicas": {"type": "integer", "minimum": 1, "maximum": 5},
+                        "name": {"type": "string", "pattern": "^[a-z-]+$", "maxLength": 10},
+                        "port": {"x-kubernetes-int-or-string": true},
+                        "tags": {"type": "array", "maxItems": 2, "items": {"type": "string"}},
+                        "labels": {"type": "object", "additionalProperties": {"type": "string"}},
+                        "extra": {"type": "object", "x-kubernetes-preserve-unknown-fields": true}
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_object() {
+        let object = json!({
+            "apiVersion": "db.example.com/v1",
+            "kind": "Database",
+            "metadata": {"name": "orders"},
+            "spec": {
+                "engine": "postgres",
+                "replicas": 3,
+                "name": "orders-db",
+                "port": "http",
+                "tags": ["a"],
+                "labels": {"team": "payments"},
+                "extra": {"anything": {"goes": true}}
+            }
+        });
+
+        assert!(validate_object(&object, &schema()).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_object() {
+        let object = json!({
+            "metadata": {"name": "legacy"},
+            "spec": {
+                "engine": "oracle",
+                "replicas": 9,
+                "name": "Legacy_DB_Name",
+                "tags": ["a", "b", 3],
+                "labels": {"team": 7},
+                "legacyField": true
+            }
+        });
+
+        let violations = validate_object(&object, &schema());
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+
+        assert!(paths.contains(&".spec.engine"));
+        assert!(paths.contains(&".spec.replicas"));
+        assert!(paths.contains(&".spec.name"));
+        assert!(paths.contains(&".spec.tags"));
+        assert!(paths.contains(&".spec.tags[2]"));
+        assert!(paths.contains(&".spec.labels.team"));
+        assert!(paths.contains(&".spec.legacyField"));
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        let violations = validate_object(&json!({"spec": {"replicas": 1}}), &schema());
+        assert_eq!(
+            violations,
+            vec![SchemaViolation {
+                path: ".spec.engine".to_string(),
+                message: "missing required field".to_string(),
+            }]
+        );
+    }
+}
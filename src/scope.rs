@@ -0,0 +1,74 @@
+//! Namespaces left out of listings that span namespaces
+//!
+//! System namespaces hold many of a cluster's pods, secrets and configmaps,
+//! and rarely what `kdx pods -A` is looking for. The `[namespaces]` table of
+//! the config file names namespaces, or patterns such as `kube-*`, to leave
+//! out whenever a command lists every namespace. Namespaces asked for by
+//! name with `-n` are always listed, and `--system` lists everything.
+
+use crate::error::{ExplorerError, Result};
+use regex::Regex;
+
+/// Namespaces excluded from listings across namespaces
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceScope {
+    excluded: Vec<Regex>,
+}
+
+/// `*` matches any run of characters; everything else is literal
+fn pattern_regex(pattern: &str) -> Result<Regex> {
+    let escaped: Vec<String> = pattern.split('*').map(regex::escape).collect();
+    Regex::new(&format!("^{}$", escaped.join(".*"))).map_err(|e| {
+        ExplorerError::InvalidArgument(format!("invalid namespace pattern '{}': {}", pattern, e))
+    })
+}
+
+impl NamespaceScope {
+    /// Exclude namespaces matching any of `patterns`
+    pub fn excluding(patterns: &[String]) -> Result<Self> {
+        Ok(Self {
+            excluded: patterns
+                .iter()
+                .map(|p| pattern_regex(p))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Whether nothing is excluded
+    pub fn is_empty(&self) -> bool {
+        self.excluded.is_empty()
+    }
+
+    pub fn includes(&self, namespace: &str) -> bool {
+        !self.excluded.iter().any(|re| re.is_match(namespace))
+    }
+
+    /// Drop the items of excluded namespaces; items without a namespace
+    /// (cluster-scoped ones) are kept
+    pub fn retain<T>(&self, items: &mut Vec<T>, namespace: impl Fn(&T) -> Option<&str>) {
+        if !self.is_empty() {
+            items.retain(|item| namespace(item).is_none_or(|ns| self.includes(ns)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excluded_namespaces() {
+        let scope =
+            NamespaceScope::excluding(&["kube-*".to_string(), "cert-manager".to_string()]).unwrap();
+        assert!(!scope.includes("kube-system"));
+        assert!(!scope.includes("kube-node-lease"));
+        assert!(!scope.includes("cert-manager"));
+        assert!(scope.includes("cert-manager-test"));
+        assert!(scope.includes("shop"));
+
+        let mut items = vec![Some("kube-system"), Some("shop"), None];
+        scope.retain(&mut items, |ns| *ns);
+        assert_eq!(items, [Some("shop"), None]);
+        assert!(NamespaceScope::default().includes("kube-system"));
+    }
+}
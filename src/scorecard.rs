@@ -0,0 +1,505 @@
+//! Production-readiness score of workloads (`kdx scorecard`)
+//!
+//! Each Deployment, StatefulSet and DaemonSet is rated on six checks, each
+//! scoring between 0 and 1, and the weighted sum is scaled to 0-100:
+//!
+//! - lint: no warnings or errors from the default `kdx lint` rules; info
+//!   findings only count half
+//! - pdb: a PodDisruptionBudget selects the pod template
+//! - probes: every container has readiness and liveness probes
+//! - spread: more than one replica, running in more than one zone when the
+//!   cluster has several
+//! - resources: every container requests CPU and memory and limits memory
+//! - monitoring: a ServiceMonitor scrapes a service in front of the pods, a
+//!   PodMonitor selects them, or the template has `prometheus.io/scrape`
+//!
+//! Checks that do not apply to a kind (PDBs and spread for DaemonSets,
+//! which run one pod per node) are left out of its score rather than
+//! counted as passed. A namespace scores the average of its workloads.
+
+use crate::dependents::{list_monitors, monitor_selects, selector_matches};
+use crate::discovery::{pod_workload, DiscoveryEngine};
+use crate::error::{ExplorerError, Result};
+use crate::lint::{self, LintFinding, RulesFile, Severity};
+use crate::resource::ResourceKind;
+use k8s_openapi::api::policy::v1::PodDisruptionBudget;
+use kube::api::{DynamicObject, ListParams};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Check {
+    Lint,
+    Pdb,
+    Probes,
+    Spread,
+    Resources,
+    Monitoring,
+}
+
+impl Check {
+    /// Share of the score, out of 100 when every check applies
+    pub fn weight(&self) -> u32 {
+        match self {
+            Check::Lint => 15,
+            Check::Pdb => 15,
+            Check::Probes => 20,
+            Check::Spread => 15,
+            Check::Resources => 20,
+            Check::Monitoring => 15,
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Check::Lint => "lint",
+            Check::Pdb => "pdb",
+            Check::Probes => "probes",
+            Check::Spread => "spread",
+            Check::Resources => "resources",
+            Check::Monitoring => "monitoring",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckScore {
+    pub check: Check,
+    pub weight: u32,
+    /// Between 0 and 1
+    pub score: f64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkloadScore {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    /// Weighted score out of 100
+    pub score: u32,
+    pub checks: Vec<CheckScore>,
+}
+
+impl WorkloadScore {
+    pub fn check(&self, check: Check) -> Option<&CheckScore> {
+        self.checks.iter().find(|c| c.check == check)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scorecard {
+    pub namespace: String,
+    /// Average of the workload scores
+    pub score: u32,
+    /// Lowest score first
+    pub workloads: Vec<WorkloadScore>,
+}
+
+/// What the checks look at besides the workload itself, all from the
+/// workload's namespace except the monitors
+#[derive(Debug, Default)]
+pub struct Context {
+    pub findings: Vec<LintFinding>,
+    /// PodDisruptionBudgets as JSON
+    pub pdbs: Vec<Value>,
+    /// Services as JSON
+    pub services: Vec<Value>,
+    pub service_monitors: Vec<DynamicObject>,
+    pub pod_monitors: Vec<DynamicObject>,
+    /// Zones of the nodes running each workload's pods, by (kind, name)
+    pub pod_zones: BTreeMap<(String, String), BTreeSet<String>>,
+    /// Zones in the cluster
+    pub cluster_zones: usize,
+}
+
+fn labels(value: &Value) -> BTreeMap<String, String> {
+    value
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+        .collect()
+}
+
+/// Score one workload given as its JSON object
+pub fn score_workload(kind: &str, object: &Value, context: &Context) -> WorkloadScore {
+    let namespace = object["metadata"]["namespace"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let name = object["metadata"]["name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let template = &object["spec"]["template"];
+    let template_labels = labels(&template["metadata"]["labels"]);
+    let containers: Vec<&Value> = template["spec"]["containers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .collect();
+    let per_node = kind == "DaemonSet";
+
+    let mut checks = Vec::new();
+    let mut push = |check: Check, score: f64, detail: String| {
+        checks.push(CheckScore {
+            check,
+            weight: check.weight(),
+            score,
+            detail,
+        })
+    };
+
+    let findings: Vec<&LintFinding> = context
+        .findings
+        .iter()
+        .filter(|f| f.kind == kind && f.name == name)
+        .collect();
+    let (score, detail) = if findings.is_empty() {
+        (1.0, "no findings".to_string())
+    } else {
+        let rules: BTreeSet<&str> = findings.iter().map(|f| f.rule.as_str()).collect();
+        let rules = rules.into_iter().collect::<Vec<_>>().join(", ");
+        if findings.iter().all(|f| f.severity == Severity::Info) {
+            (0.5, format!("info: {}", rules))
+        } else {
+            (0.0, rules)
+        }
+    };
+    push(Check::Lint, score, detail);
+
+    if !per_node {
+        let pdb = context
+            .pdbs
+            .iter()
+            .find(|pdb| selector_matches(&pdb["spec"]["selector"], &template_labels));
+        match pdb {
+            Some(pdb) => push(
+                Check::Pdb,
+                1.0,
+                pdb["metadata"]["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            None => push(Check::Pdb, 0.0, "no PodDisruptionBudget".to_string()),
+        }
+    }
+
+    let count = |field: &str| containers.iter().filter(|c| c[field].is_object()).count();
+    let (readiness, liveness) = (count("readinessProbe"), count("livenessProbe"));
+    push(
+        Check::Probes,
+        ratio(readiness + liveness, 2 * containers.len()),
+        format!(
+            "readiness {}/{}, liveness {}/{}",
+            readiness,
+            containers.len(),
+            liveness,
+            containers.len()
+        ),
+    );
+
+    if !per_node {
+        let replicas = object["spec"]["replicas"].as_i64().unwrap_or(1);
+        let zones = context
+            .pod_zones
+            .get(&(kind.to_string(), name.clone()))
+            .map_or(0, BTreeSet::len);
+        let (score, detail) = match (replicas > 1, context.cluster_zones > 1) {
+            (false, _) => (0.0, format!("{} replica", replicas)),
+            (true, false) => (1.0, format!("{} replicas, single-zone cluster", replicas)),
+            (true, true) if zones > 1 => (1.0, format!("{} replicas in {} zones", replicas, zones)),
+            (true, true) => (0.5, format!("{} replicas in one zone", replicas)),
+        };
+        push(Check::Spread, score, detail);
+    }
+
+    let fields = ["requests.cpu", "requests.memory", "limits.memory"];
+    let set = containers
+        .iter()
+        .flat_map(|c| {
+            fields.iter().filter(move |field| {
+                let (section, resource) = field.split_once('.').unwrap_or_default();
+                !c["resources"][section][resource].is_null()
+            })
+        })
+        .count();
+    let missing: BTreeSet<&str> = fields
+        .iter()
+        .copied()
+        .filter(|field| {
+            let (section, resource) = field.split_once('.').unwrap_or_default();
+            containers
+                .iter()
+                .any(|c| c["resources"][section][resource].is_null())
+        })
+        .collect();
+    push(
+        Check::Resources,
+        ratio(set, fields.len() * containers.len()),
+        if missing.is_empty() {
+            "requests and memory limits set".to_string()
+        } else {
+            format!(
+                "missing {}",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            )
+        },
+    );
+
+    push_monitoring(&mut push, &namespace, template, &template_labels, context);
+
+    let weight: u32 = checks.iter().map(|c| c.weight).sum();
+    let earned: f64 = checks.iter().map(|c| c.score * c.weight as f64).sum();
+    WorkloadScore {
+        namespace,
+        kind: kind.to_string(),
+        name,
+        score: (100.0 * earned / weight as f64).round() as u32,
+        checks,
+    }
+}
+
+fn push_monitoring(
+    push: &mut impl FnMut(Check, f64, String),
+    namespace: &str,
+    template: &Value,
+    template_labels: &BTreeMap<String, String>,
+    context: &Context,
+) {
+    let name = |object: &DynamicObject| object.metadata.name.clone().unwrap_or_default();
+    if let Some(monitor) = context
+        .pod_monitors
+        .iter()
+        .find(|m| monitor_selects(m, namespace, template_labels))
+    {
+        return push(
+            Check::Monitoring,
+            1.0,
+            format!("PodMonitor {}", name(monitor)),
+        );
+    }
+    let fronting = context.services.iter().filter(|service| {
+        let selector = labels(&service["spec"]["selector"]);
+        !selector.is_empty()
+            && selector
+                .iter()
+                .all(|(k, v)| template_labels.get(k) == Some(v))
+    });
+    for service in fronting {
+        let service_labels = labels(&service["metadata"]["labels"]);
+        if let Some(monitor) = context
+            .service_monitors
+            .iter()
+            .find(|m| monitor_selects(m, namespace, &service_labels))
+        {
+            return push(
+                Check::Monitoring,
+                1.0,
+                format!("ServiceMonitor {}", name(monitor)),
+            );
+        }
+    }
+    if template["metadata"]["annotations"]["prometheus.io/scrape"].as_str() == Some("true") {
+        return push(
+            Check::Monitoring,
+            1.0,
+            "prometheus.io/scrape annotation".to_string(),
+        );
+    }
+    push(Check::Monitoring, 0.0, "not scraped".to_string())
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    part as f64 / whole as f64
+}
+
+/// Score every workload of `namespace`, given as (kind, object) pairs
+pub fn scorecard(namespace: &str, workloads: &[(String, Value)], context: &Context) -> Scorecard {
+    let mut scores: Vec<WorkloadScore> = workloads
+        .iter()
+        .map(|(kind, object)| score_workload(kind, object, context))
+        .collect();
+    scores.sort_by(|a, b| a.score.cmp(&b.score).then_with(|| a.name.cmp(&b.name)));
+    let total: u32 = scores.iter().map(|s| s.score).sum();
+    Scorecard {
+        namespace: namespace.to_string(),
+        score: if scores.is_empty() {
+            0
+        } else {
+            (total as f64 / scores.len() as f64).round() as u32
+        },
+        workloads: scores,
+    }
+}
+
+/// Kinds that are scored
+const KINDS: [ResourceKind; 3] = [
+    ResourceKind::Deployment,
+    ResourceKind::StatefulSet,
+    ResourceKind::DaemonSet,
+];
+
+/// Fetch the workloads of `namespace` and what their checks need, and score
+/// them; with `workload` (kind and name) only that one
+pub async fn fetch_scorecard(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+    workload: Option<(ResourceKind, &str)>,
+) -> Result<Scorecard> {
+    let client = discovery.client();
+    let kinds: Vec<ResourceKind> = match workload {
+        Some((kind, _)) => vec![kind],
+        None => KINDS.to_vec(),
+    };
+    let pdbs: Api<PodDisruptionBudget> = Api::namespaced(client.clone(), namespace);
+    let params = ListParams::default();
+    let (objects, services, pdbs, service_monitors, pod_monitors, pods, nodes) = tokio::try_join!(
+        discovery.list_objects_json(Some(namespace), &kinds),
+        discovery.list_objects_json(Some(namespace), &[ResourceKind::Service]),
+        async { Ok(pdbs.list(&params).await?) },
+        list_monitors(client, "ServiceMonitor"),
+        list_monitors(client, "PodMonitor"),
+        discovery.list_pods(Some(namespace), None),
+        discovery.list_nodes(),
+    )?;
+
+    let objects: Vec<(ResourceKind, Value)> = objects
+        .into_iter()
+        .filter(|(_, object)| workload.is_none_or(|(_, name)| object["metadata"]["name"] == name))
+        .collect();
+    if let Some((kind, name)) = workload {
+        if objects.is_empty() {
+            return Err(ExplorerError::ResourceNotFound {
+                kind: kind.as_str().to_string(),
+                name: name.to_string(),
+                namespace: namespace.to_string(),
+            });
+        }
+    }
+
+    let zones: BTreeMap<&str, &str> = nodes
+        .iter()
+        .filter_map(|n| Some((n.name.as_str(), n.zone.as_deref()?)))
+        .collect();
+    let mut pod_zones: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    for pod in pods.iter().filter(|p| p.phase == "Running") {
+        if let Some(zone) = pod.node_name.as_deref().and_then(|n| zones.get(n)) {
+            pod_zones
+                .entry(pod_workload(pod))
+                .or_default()
+                .insert(zone.to_string());
+        }
+    }
+
+    let context = Context {
+        findings: lint::lint_objects(&objects, &lint::effective_rules(&RulesFile::default())?),
+        pdbs: pdbs
+            .items
+            .iter()
+            .filter_map(|pdb| serde_json::to_value(pdb).ok())
+            .collect(),
+        services: services.into_iter().map(|(_, service)| service).collect(),
+        service_monitors,
+        pod_monitors,
+        pod_zones,
+        cluster_zones: zones.values().collect::<BTreeSet<_>>().len(),
+    };
+    let workloads: Vec<(String, Value)> = objects
+        .into_iter()
+        .map(|(kind, object)| (kind.as_str().to_string(), object))
+        .collect();
+    Ok(scorecard(namespace, &workloads, &context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn deployment(name: &str, replicas: i64, probes: bool, resources: bool) -> Value {
+        let mut container = json!({"name": "app", "image": "app:1"});
+        if probes {
+            container["readinessProbe"] = json!({"httpGet": {"path": "/ready"}});
+            container["livenessProbe"] = json!({"httpGet": {"path": "/live"}});
+        }
+        if resources {
+            container["resources"] = json!({
+                "requests": {"cpu": "100m", "memory": "128Mi"},
+                "limits": {"memory": "256Mi"}
+            });
+        }
+        json!({
+            "metadata": {"name": name, "namespace": "shop"},
+            "spec": {
+                "replicas": replicas,
+                "template": {
+                    "metadata": {"labels": {"app": name}},
+                    "spec": {"containers": [container]}
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_score_workload() {
+        let monitor: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "monitoring.coreos.com/v1",
+            "kind": "ServiceMonitor",
+            "metadata": {"name": "web", "namespace": "shop"},
+            "spec": {"selector": {"matchLabels": {"app": "web"}}}
+        }))
+        .unwrap();
+        let context = Context {
+            pdbs: vec![json!({
+                "metadata": {"name": "web-pdb"},
+                "spec": {"selector": {"matchLabels": {"app": "web"}}}
+            })],
+            services: vec![json!({
+                "metadata": {"name": "web", "labels": {"app": "web"}},
+                "spec": {"selector": {"app": "web"}}
+            })],
+            service_monitors: vec![monitor],
+            pod_zones: BTreeMap::from([(
+                ("Deployment".to_string(), "web".to_string()),
+                BTreeSet::from(["a".to_string(), "b".to_string()]),
+            )]),
+            cluster_zones: 3,
+            ..Default::default()
+        };
+
+        let web = score_workload("Deployment", &deployment("web", 3, true, true), &context);
+        assert_eq!(web.score, 100);
+        assert_eq!(web.check(Check::Pdb).unwrap().detail, "web-pdb");
+
+        let batch = score_workload(
+            "Deployment",
+            &deployment("batch", 1, false, false),
+            &context,
+        );
+        // Only lint passes
+        assert_eq!(batch.score, 15);
+        assert_eq!(
+            batch.check(Check::Resources).unwrap().detail,
+            "missing limits.memory, requests.cpu, requests.memory"
+        );
+
+        // Without PDB and spread, a DaemonSet scores out of the other 70
+        let agent = score_workload("DaemonSet", &deployment("agent", 1, true, false), &context);
+        assert_eq!(agent.checks.len(), 4);
+        assert_eq!(agent.score, 50);
+    }
+}
@@ -0,0 +1,255 @@
+//! Secret type statistics and anomalies (`kdx secrets --summary`)
+//!
+//! Secrets are counted per type, and three patterns worth a look are
+//! flagged: ServiceAccounts holding more token Secrets than they need,
+//! long-lived `kubernetes.io/service-account-token` Secrets, which the
+//! TokenRequest API has replaced, and registry credentials that no pod
+//! mounts and no ServiceAccount lists in its `imagePullSecrets`.
+
+use crate::discovery::{SecretInfo, ServiceAccountInfo};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Type of the Secrets holding legacy ServiceAccount tokens
+pub const SERVICE_ACCOUNT_TOKEN: &str = "kubernetes.io/service-account-token";
+
+/// Types of the Secrets holding registry credentials
+const DOCKER_CONFIG_TYPES: &[&str] = &["kubernetes.io/dockercfg", "kubernetes.io/dockerconfigjson"];
+
+/// Token Secrets one ServiceAccount may have before it is flagged
+pub const MAX_TOKENS_PER_ACCOUNT: usize = 2;
+
+/// Label the control plane sets on token Secrets it has seen in use
+const LAST_USED_LABEL: &str = "kubernetes.io/legacy-token-last-used";
+
+/// Label the control plane sets on token Secrets it has invalidated
+const INVALID_SINCE_LABEL: &str = "kubernetes.io/legacy-token-invalid-since";
+
+/// Secrets of one type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeCount {
+    pub secret_type: String,
+    pub count: usize,
+    /// Secrets of the type that no pod uses
+    pub unused: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnomalyKind {
+    /// A ServiceAccount with more than [`MAX_TOKENS_PER_ACCOUNT`] token Secrets
+    TokenCount,
+    /// A long-lived ServiceAccount token Secret
+    LegacyToken,
+    /// Registry credentials used by no pod or ServiceAccount
+    UnusedPullSecret,
+}
+
+impl fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnomalyKind::TokenCount => write!(f, "token-count"),
+            AnomalyKind::LegacyToken => write!(f, "legacy-token"),
+            AnomalyKind::UnusedPullSecret => write!(f, "unused-pull-secret"),
+        }
+    }
+}
+
+/// One finding; `name` is the ServiceAccount for token counts and the
+/// Secret otherwise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretAnomaly {
+    pub kind: AnomalyKind,
+    pub namespace: String,
+    pub name: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretSummary {
+    pub total: usize,
+    /// Most common type first
+    pub types: Vec<TypeCount>,
+    pub anomalies: Vec<SecretAnomaly>,
+}
+
+/// Count `secrets` by type and flag anomalies, using `accounts` to tell
+/// which registry credentials are referenced
+pub fn summarize(secrets: &[SecretInfo], accounts: &[ServiceAccountInfo]) -> SecretSummary {
+    let mut types: BTreeMap<&str, TypeCount> = BTreeMap::new();
+    for secret in secrets {
+        let entry = types
+            .entry(secret.secret_type.as_str())
+            .or_insert_with(|| TypeCount {
+                secret_type: secret.secret_type.clone(),
+                count: 0,
+                unused: 0,
+            });
+        entry.count += 1;
+        if secret.used_by.is_empty() {
+            entry.unused += 1;
+        }
+    }
+    let mut types: Vec<TypeCount> = types.into_values().collect();
+    types.sort_by_key(|t| Reverse(t.count));
+
+    let mut anomalies = Vec::new();
+
+    let mut tokens: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for secret in secrets
+        .iter()
+        .filter(|s| s.secret_type == SERVICE_ACCOUNT_TOKEN)
+    {
+        if let Some(account) = &secret.service_account {
+            *tokens.entry((&secret.namespace, account)).or_default() += 1;
+        }
+        anomalies.push(SecretAnomaly {
+            kind: AnomalyKind::LegacyToken,
+            namespace: secret.namespace.clone(),
+            name: secret.name.clone(),
+            detail: legacy_token_detail(secret),
+        });
+    }
+    for ((namespace, account), count) in tokens {
+        if count > MAX_TOKENS_PER_ACCOUNT {
+            anomalies.push(SecretAnomaly {
+                kind: AnomalyKind::TokenCount,
+                namespace: namespace.to_string(),
+                name: account.to_string(),
+                detail: format!("{} token secrets", count),
+            });
+        }
+    }
+
+    let pulled: BTreeSet<(&str, &str)> = accounts
+        .iter()
+        .flat_map(|a| {
+            a.image_pull_secrets
+                .iter()
+                .map(|s| (a.namespace.as_str(), s.as_str()))
+        })
+        .collect();
+    for secret in secrets {
+        if DOCKER_CONFIG_TYPES.contains(&secret.secret_type.as_str())
+            && secret.used_by.is_empty()
+            && !pulled.contains(&(secret.namespace.as_str(), secret.name.as_str()))
+        {
+            anomalies.push(SecretAnomaly {
+                kind: AnomalyKind::UnusedPullSecret,
+                namespace: secret.namespace.clone(),
+                name: secret.name.clone(),
+                detail: "not used by any pod or service account".to_string(),
+            });
+        }
+    }
+
+    anomalies.sort_by(|a, b| (a.kind, &a.namespace, &a.name).cmp(&(b.kind, &b.namespace, &b.name)));
+    SecretSummary {
+        total: secrets.len(),
+        types,
+        anomalies,
+    }
+}
+
+fn legacy_token_detail(secret: &SecretInfo) -> String {
+    let account = secret
+        .service_account
+        .as_deref()
+        .unwrap_or("unknown account");
+    if let Some(since) = secret.labels.get(INVALID_SINCE_LABEL) {
+        format!("token of {}, invalid since {}", account, since)
+    } else if let Some(used) = secret.labels.get(LAST_USED_LABEL) {
+        format!("token of {}, last used {}", account, used)
+    } else {
+        format!("token of {}, age {}", account, secret.age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{ReferenceType, ResourceReference};
+
+    fn secret(name: &str, secret_type: &str, account: Option<&str>) -> SecretInfo {
+        SecretInfo {
+            name: name.to_string(),
+            namespace: "shop".to_string(),
+            secret_type: secret_type.to_string(),
+            data_keys: vec![],
+            age: "90d".to_string(),
+            labels: BTreeMap::new(),
+            used_by: vec![],
+            mount_paths: vec![],
+            service_account: account.map(str::to_string),
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut used = secret("web-pull", "kubernetes.io/dockerconfigjson", None);
+        used.used_by.push(ResourceReference {
+            kind: "Pod".to_string(),
+            name: "web-0".to_string(),
+            namespace: "shop".to_string(),
+            reference_type: ReferenceType::ImagePullSecret,
+        });
+        let mut last_used = secret("ci-token-1", SERVICE_ACCOUNT_TOKEN, Some("ci"));
+        last_used
+            .labels
+            .insert(LAST_USED_LABEL.to_string(), "2026-09-01".to_string());
+        let secrets = vec![
+            secret("db", "Opaque", None),
+            secret("cache", "Opaque", None),
+            used,
+            secret("sa-pull", "kubernetes.io/dockercfg", None),
+            secret("stale-pull", "kubernetes.io/dockerconfigjson", None),
+            last_used,
+            secret("ci-token-2", SERVICE_ACCOUNT_TOKEN, Some("ci")),
+            secret("ci-token-3", SERVICE_ACCOUNT_TOKEN, Some("ci")),
+        ];
+        let accounts = vec![ServiceAccountInfo {
+            name: "builder".to_string(),
+            namespace: "shop".to_string(),
+            automount_token: None,
+            image_pull_secrets: vec!["sa-pull".to_string()],
+            age: "90d".to_string(),
+            labels: BTreeMap::new(),
+            roles: vec![],
+        }];
+
+        let summary = summarize(&secrets, &accounts);
+        assert_eq!(summary.total, 8);
+        assert_eq!(summary.types[0].secret_type, SERVICE_ACCOUNT_TOKEN);
+        assert_eq!(summary.types[0].count, 3);
+        let opaque = summary
+            .types
+            .iter()
+            .find(|t| t.secret_type == "Opaque")
+            .unwrap();
+        assert_eq!((opaque.count, opaque.unused), (2, 2));
+
+        let found: Vec<(AnomalyKind, &str)> = summary
+            .anomalies
+            .iter()
+            .map(|a| (a.kind, a.name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (AnomalyKind::TokenCount, "ci"),
+                (AnomalyKind::LegacyToken, "ci-token-1"),
+                (AnomalyKind::LegacyToken, "ci-token-2"),
+                (AnomalyKind::LegacyToken, "ci-token-3"),
+                (AnomalyKind::UnusedPullSecret, "stale-pull"),
+            ]
+        );
+        assert_eq!(
+            summary.anomalies[1].detail,
+            "token of ci, last used 2026-09-01"
+        );
+    }
+}
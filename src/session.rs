@@ -0,0 +1,92 @@
+//! Client credentials that outlive their first token
+//!
+//! The client refreshes tokens from exec credential plugins and token files
+//! on its own when they carry an expiry. Plugins that report no expiry,
+//! tokens revoked before they expire and rotated client certificates still
+//! show up as 401 Unauthorized, typically hours into `--watch`. Long-running
+//! commands therefore hold a session: on a 401 they ask it for a client
+//! built again from the kubeconfig, which re-runs the credential plugin, and
+//! carry on where they were.
+
+use crate::discovery::load_config;
+use crate::error::{ExplorerError, Result};
+use crate::stats::RunStats;
+use kube::Client;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// A client and how to build it again with fresh credentials
+pub struct ClientSession {
+    /// Kubeconfig context and statistics the client was built with; `None`
+    /// for a client given by the caller, which cannot be rebuilt
+    source: Option<(Option<String>, Arc<RunStats>)>,
+    /// The current client and how many times it was rebuilt
+    current: Mutex<(u64, Client)>,
+}
+
+impl ClientSession {
+    /// A session around a client that cannot be rebuilt
+    pub fn fixed(client: Client) -> Self {
+        Self {
+            source: None,
+            current: Mutex::new((0, client)),
+        }
+    }
+
+    /// A session around a client built from the kubeconfig `context` (or
+    /// the current context) with `stats`
+    pub fn from_kubeconfig(client: Client, context: Option<String>, stats: Arc<RunStats>) -> Self {
+        Self {
+            source: Some((context, stats)),
+            current: Mutex::new((0, client)),
+        }
+    }
+
+    /// The current client with its generation, to pass to
+    /// [`ClientSession::refresh`] when it is refused
+    pub async fn client(&self) -> (u64, Client) {
+        self.current.lock().await.clone()
+    }
+
+    /// A client with fresh credentials, after the client of `generation`
+    /// got a 401. Callers refused at the same time share one rebuild.
+    pub async fn refresh(&self, generation: u64) -> Result<(u64, Client)> {
+        let mut current = self.current.lock().await;
+        if current.0 == generation {
+            let (context, stats) = self.source.as_ref().ok_or_else(|| {
+                ExplorerError::Config(
+                    "credentials were rejected and the client cannot be rebuilt".to_string(),
+                )
+            })?;
+            eprintln!("Credentials rejected (401 Unauthorized); reloading kubeconfig");
+            let client = stats.client(load_config(context.as_deref()).await?)?;
+            *current = (generation + 1, client);
+        }
+        Ok(current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> Client {
+        let config = kube::Config::new("http://127.0.0.1:1".parse().unwrap());
+        Client::try_from(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_refresh() {
+        let fixed = ClientSession::fixed(client());
+        let (generation, _) = fixed.client().await;
+        assert_eq!(generation, 0);
+        assert!(fixed.refresh(generation).await.is_err());
+
+        // A caller refused with an older client gets the current one
+        // without another rebuild
+        let session = ClientSession::from_kubeconfig(client(), None, Arc::new(RunStats::default()));
+        *session.current.lock().await = (2, client());
+        let (generation, _) = session.refresh(1).await.unwrap();
+        assert_eq!(generation, 2);
+    }
+}
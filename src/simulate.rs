@@ -0,0 +1,707 @@
+//! Dry-run capacity check of manifests (`kdx simulate`)
+//!
+//! Workloads are read from YAML or JSON manifests on disk and each one
+//! contributes its replicas times the requests of its pod template:
+//! containers summed, init containers at their largest since they run one
+//! at a time, plus pod overhead. A container without a request gets its
+//! limit, as the API server defaults it.
+//!
+//! Against a cluster, the new pods are placed largest first onto the first
+//! node with room, where a node's room is its allocatable CPU and memory
+//! minus the requests of the pods already on it. Only Ready, uncordoned
+//! nodes whose labels match the pod's nodeSelector and whose NoSchedule and
+//! NoExecute taints it tolerates are considered; DaemonSets get one pod on
+//! each such node. The totals per namespace are then compared with what its
+//! ResourceQuotas have left. Affinity, topology spread and ports are not
+//! modelled, and objects that already exist are counted as new, so a fit
+//! is necessary for a rollout rather than a guarantee.
+
+use crate::discovery::{DiscoveryEngine, NodeInfo};
+use crate::error::{ExplorerError, Result};
+use crate::quantity::parse_quantity;
+use k8s_openapi::api::core::v1::{Pod, PodSpec, ResourceQuota, Toleration};
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Quota keys compared, with the resource they limit
+const QUOTA_KEYS: &[(&str, QuotaResource)] = &[
+    ("requests.cpu", QuotaResource::CpuRequests),
+    ("cpu", QuotaResource::CpuRequests),
+    ("requests.memory", QuotaResource::MemoryRequests),
+    ("memory", QuotaResource::MemoryRequests),
+    ("limits.cpu", QuotaResource::CpuLimits),
+    ("limits.memory", QuotaResource::MemoryLimits),
+    ("pods", QuotaResource::Pods),
+];
+
+/// Slack when comparing requests with free room
+const TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy)]
+enum QuotaResource {
+    CpuRequests,
+    MemoryRequests,
+    CpuLimits,
+    MemoryLimits,
+    Pods,
+}
+
+/// CPU in cores and memory in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Resources {
+    pub cpu: f64,
+    pub memory: f64,
+}
+
+impl Resources {
+    fn add(&mut self, other: Resources) {
+        self.cpu += other.cpu;
+        self.memory += other.memory;
+    }
+
+    fn max(self, other: Resources) -> Resources {
+        Resources {
+            cpu: self.cpu.max(other.cpu),
+            memory: self.memory.max(other.memory),
+        }
+    }
+
+    fn fits_in(&self, room: &Resources) -> bool {
+        // Parsed quantities are sums of decimals; don't let rounding refuse
+        // an exact fit
+        self.cpu <= room.cpu + TOLERANCE && self.memory <= room.memory + TOLERANCE
+    }
+
+    fn scaled(self, factor: u32) -> Resources {
+        Resources {
+            cpu: self.cpu * factor as f64,
+            memory: self.memory * factor as f64,
+        }
+    }
+}
+
+/// A workload read from a manifest file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestWorkload {
+    pub file: String,
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    /// Pods the workload runs; for DaemonSets, one per eligible node
+    pub replicas: u32,
+    pub per_node: bool,
+    /// Requests of one pod
+    pub requests: Resources,
+    /// Limits of one pod, counting only containers that set them
+    pub limits: Resources,
+    #[serde(skip)]
+    pub spec: PodSpec,
+}
+
+/// How a workload's pods fared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadFit {
+    #[serde(flatten)]
+    pub workload: ManifestWorkload,
+    /// Pods placed onto a node; absent without a cluster to place them on
+    pub placed: Option<u32>,
+    pub reason: Option<String>,
+}
+
+impl WorkloadFit {
+    pub fn fits(&self) -> bool {
+        self.placed
+            .is_none_or(|placed| placed == self.workload.replicas)
+    }
+}
+
+/// Room on one node before and after the new pods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeHeadroom {
+    pub name: String,
+    pub allocatable: Resources,
+    pub free_before: Resources,
+    pub free_after: Resources,
+    pub new_pods: u32,
+}
+
+/// One hard limit of a ResourceQuota against what the manifests add
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaCheck {
+    pub namespace: String,
+    pub quota: String,
+    pub resource: String,
+    pub hard: f64,
+    pub used: f64,
+    pub adding: f64,
+}
+
+impl QuotaCheck {
+    pub fn fits(&self) -> bool {
+        self.used + self.adding <= self.hard + TOLERANCE
+    }
+}
+
+/// Outcome of `kdx simulate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Simulation {
+    pub against_cluster: bool,
+    pub workloads: Vec<WorkloadFit>,
+    /// Requests of all new pods
+    pub total: Resources,
+    pub nodes: Vec<NodeHeadroom>,
+    pub quotas: Vec<QuotaCheck>,
+}
+
+impl Simulation {
+    pub fn fits(&self) -> bool {
+        self.workloads.iter().all(WorkloadFit::fits) && self.quotas.iter().all(QuotaCheck::fits)
+    }
+
+    /// New pods left without a node
+    pub fn unplaced(&self) -> u32 {
+        self.workloads
+            .iter()
+            .filter_map(|w| w.placed.map(|placed| w.workload.replicas - placed))
+            .sum()
+    }
+}
+
+/// Manifest files under `path`, or `path` itself when it is a file, in
+/// name order
+fn manifest_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?.path();
+        if entry.is_dir() {
+            files.extend(manifest_files(&entry)?);
+        } else if entry
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| matches!(e, "yaml" | "yml" | "json"))
+        {
+            files.push(entry);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Objects of a manifest file: every YAML document (JSON is YAML too), with
+/// `List` objects expanded into their items
+pub fn parse_manifests(text: &str) -> std::result::Result<Vec<Value>, serde_yaml::Error> {
+    let mut objects = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(text) {
+        let value = Value::deserialize(document)?;
+        match value {
+            Value::Null => {}
+            Value::Object(ref object) if object.get("kind") == Some(&"List".into()) => {
+                if let Some(Value::Array(items)) = object.get("items") {
+                    objects.extend(items.iter().cloned());
+                }
+            }
+            value => objects.push(value),
+        }
+    }
+    Ok(objects)
+}
+
+/// Workloads in the manifests at `path` (a file or a directory searched
+/// recursively for .yaml, .yml and .json files). Objects without a
+/// namespace go to `namespace`; objects that run no pods are skipped.
+pub fn load_manifests(path: &Path, namespace: &str) -> Result<Vec<ManifestWorkload>> {
+    let mut workloads = Vec::new();
+    for file in manifest_files(path)? {
+        let name = file.display().to_string();
+        let text = fs::read_to_string(&file)?;
+        let objects = parse_manifests(&text)
+            .map_err(|e| ExplorerError::InvalidArgument(format!("{}: {}", name, e)))?;
+        for object in objects {
+            if let Some(workload) = manifest_workload(&name, &object, namespace)? {
+                workloads.push(workload);
+            }
+        }
+    }
+    Ok(workloads)
+}
+
+/// The workload `object` describes, if it runs pods
+pub fn manifest_workload(
+    file: &str,
+    object: &Value,
+    namespace: &str,
+) -> Result<Option<ManifestWorkload>> {
+    let kind = object["kind"].as_str().unwrap_or_default();
+    let spec = &object["spec"];
+    let replicas = |value: &Value| value.as_u64().unwrap_or(1) as u32;
+    let (template, replicas, per_node) = match kind {
+        "Pod" => (spec, 1, false),
+        "Deployment" | "ReplicaSet" | "StatefulSet" | "ReplicationController" => (
+            &spec["template"]["spec"],
+            replicas(&spec["replicas"]),
+            false,
+        ),
+        "DaemonSet" => (&spec["template"]["spec"], 0, true),
+        "Job" => (
+            &spec["template"]["spec"],
+            replicas(&spec["parallelism"]),
+            false,
+        ),
+        "CronJob" => (
+            &spec["jobTemplate"]["spec"]["template"]["spec"],
+            replicas(&spec["jobTemplate"]["spec"]["parallelism"]),
+            false,
+        ),
+        _ => return Ok(None),
+    };
+    let name = object["metadata"]["name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let spec: PodSpec = serde_json::from_value(template.clone()).map_err(|e| {
+        ExplorerError::InvalidArgument(format!("{}: {} {}: {}", file, kind, name, e))
+    })?;
+
+    Ok(Some(ManifestWorkload {
+        file: file.to_string(),
+        namespace: object["metadata"]["namespace"]
+            .as_str()
+            .unwrap_or(namespace)
+            .to_string(),
+        kind: kind.to_string(),
+        name,
+        replicas,
+        per_node,
+        requests: pod_resources(&spec, true),
+        limits: pod_resources(&spec, false),
+        spec,
+    }))
+}
+
+/// Requests (or limits) of a pod as the scheduler counts them
+pub fn pod_resources(spec: &PodSpec, requests: bool) -> Resources {
+    let container = |resources: Option<&k8s_openapi::api::core::v1::ResourceRequirements>| {
+        let resources = resources.cloned().unwrap_or_default();
+        let limits = resources.limits.unwrap_or_default();
+        let requested = if requests {
+            resources.requests.unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        };
+        let get = |name: &str| {
+            requested
+                .get(name)
+                .or(limits.get(name))
+                .and_then(|q| parse_quantity(&q.0))
+                .unwrap_or_default()
+        };
+        Resources {
+            cpu: get("cpu"),
+            memory: get("memory"),
+        }
+    };
+
+    let mut total = Resources::default();
+    for c in &spec.containers {
+        total.add(container(c.resources.as_ref()));
+    }
+    for c in spec.init_containers.iter().flatten() {
+        total = total.max(container(c.resources.as_ref()));
+    }
+    if let Some(overhead) = &spec.overhead {
+        let get = |name: &str| {
+            overhead
+                .get(name)
+                .and_then(|q| parse_quantity(&q.0))
+                .unwrap_or_default()
+        };
+        total.add(Resources {
+            cpu: get("cpu"),
+            memory: get("memory"),
+        });
+    }
+    total
+}
+
+/// The totals of the manifests alone, without a cluster to check them
+pub fn offline(workloads: Vec<ManifestWorkload>) -> Simulation {
+    let mut total = Resources::default();
+    for workload in &workloads {
+        total.add(workload.requests.scaled(workload.replicas));
+    }
+    Simulation {
+        against_cluster: false,
+        workloads: workloads
+            .into_iter()
+            .map(|workload| WorkloadFit {
+                workload,
+                placed: None,
+                reason: None,
+            })
+            .collect(),
+        total,
+        nodes: Vec::new(),
+        quotas: Vec::new(),
+    }
+}
+
+/// A node's room for new pods
+#[derive(Debug, Clone)]
+pub struct NodeRoom {
+    pub node: NodeInfo,
+    pub allocatable: Resources,
+    pub free: Resources,
+}
+
+/// Whether a pod with `spec` may be scheduled onto `node`
+fn eligible(spec: &PodSpec, node: &NodeInfo) -> bool {
+    if node.unschedulable || node.ready_status() != "Ready" {
+        return false;
+    }
+    let selected = spec
+        .node_selector
+        .iter()
+        .flatten()
+        .all(|(key, value)| node.labels.get(key) == Some(value));
+    let tolerations = spec.tolerations.as_deref().unwrap_or_default();
+    selected
+        && node.taints.iter().all(|taint| {
+            let (key_value, effect) = taint.rsplit_once(':').unwrap_or((taint, ""));
+            let (key, value) = key_value.split_once('=').unwrap_or((key_value, ""));
+            effect == "PreferNoSchedule"
+                || tolerations.iter().any(|t| tolerates(t, key, value, effect))
+        })
+}
+
+fn tolerates(toleration: &Toleration, key: &str, value: &str, effect: &str) -> bool {
+    let effect_matches = toleration
+        .effect
+        .as_deref()
+        .is_none_or(|e| e.is_empty() || e == effect);
+    let key_matches = match toleration.key.as_deref() {
+        None | Some("") => toleration.operator.as_deref() == Some("Exists"),
+        Some(k) => k == key,
+    };
+    let value_matches = toleration.operator.as_deref() == Some("Exists")
+        || toleration.value.as_deref().unwrap_or_default() == value;
+    effect_matches && key_matches && value_matches
+}
+
+/// Place the pods of `workloads` onto `nodes` and compare the namespace
+/// totals with `quotas`
+pub fn simulate(
+    workloads: Vec<ManifestWorkload>,
+    mut nodes: Vec<NodeRoom>,
+    quotas: &[ResourceQuota],
+) -> Simulation {
+    let before: Vec<Resources> = nodes.iter().map(|n| n.free).collect();
+    let mut new_pods = vec![0u32; nodes.len()];
+    let mut placed = vec![0u32; workloads.len()];
+    let mut replicas: Vec<u32> = workloads.iter().map(|w| w.replicas).collect();
+
+    // DaemonSets first: their pods can go to one node only
+    for (i, workload) in workloads.iter().enumerate().filter(|(_, w)| w.per_node) {
+        for (n, node) in nodes.iter_mut().enumerate() {
+            if !eligible(&workload.spec, &node.node) {
+                continue;
+            }
+            replicas[i] += 1;
+            if workload.requests.fits_in(&node.free) {
+                node.free.cpu -= workload.requests.cpu;
+                node.free.memory -= workload.requests.memory;
+                placed[i] += 1;
+                new_pods[n] += 1;
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..workloads.len())
+        .filter(|i| !workloads[*i].per_node)
+        .collect();
+    order.sort_by(|a, b| {
+        let (a, b) = (&workloads[*a].requests, &workloads[*b].requests);
+        b.cpu.total_cmp(&a.cpu).then(b.memory.total_cmp(&a.memory))
+    });
+    for i in order {
+        let workload = &workloads[i];
+        for _ in 0..workload.replicas {
+            let Some(n) = nodes.iter().position(|node| {
+                eligible(&workload.spec, &node.node) && workload.requests.fits_in(&node.free)
+            }) else {
+                break;
+            };
+            nodes[n].free.cpu -= workload.requests.cpu;
+            nodes[n].free.memory -= workload.requests.memory;
+            placed[i] += 1;
+            new_pods[n] += 1;
+        }
+    }
+
+    let mut total = Resources::default();
+    let mut adding: BTreeMap<&str, (Resources, Resources, u32)> = BTreeMap::new();
+    let mut fits = Vec::new();
+    for ((mut workload, placed), replicas) in workloads.into_iter().zip(placed).zip(replicas) {
+        workload.replicas = replicas;
+        total.add(workload.requests.scaled(replicas));
+        let reason = (placed < replicas).then(|| {
+            if !nodes.iter().any(|n| eligible(&workload.spec, &n.node)) {
+                "no node matches its nodeSelector and tolerations".to_string()
+            } else {
+                "not enough free CPU or memory on eligible nodes".to_string()
+            }
+        });
+        fits.push(WorkloadFit {
+            workload,
+            placed: Some(placed),
+            reason,
+        });
+    }
+    for fit in &fits {
+        let workload = &fit.workload;
+        let entry = adding.entry(&workload.namespace).or_default();
+        entry.0.add(workload.requests.scaled(workload.replicas));
+        entry.1.add(workload.limits.scaled(workload.replicas));
+        entry.2 += workload.replicas;
+    }
+
+    let mut checks = Vec::new();
+    for quota in quotas {
+        let namespace = quota.metadata.namespace.as_deref().unwrap_or_default();
+        let Some((requests, limits, pods)) = adding.get(namespace) else {
+            continue;
+        };
+        let hard = quota.spec.as_ref().and_then(|s| s.hard.as_ref());
+        let used = quota.status.as_ref().and_then(|s| s.used.as_ref());
+        for (key, resource) in QUOTA_KEYS {
+            let Some(hard) = hard
+                .and_then(|h| h.get(*key))
+                .and_then(|q| parse_quantity(&q.0))
+            else {
+                continue;
+            };
+            checks.push(QuotaCheck {
+                namespace: namespace.to_string(),
+                quota: quota.metadata.name.clone().unwrap_or_default(),
+                resource: key.to_string(),
+                hard,
+                used: used
+                    .and_then(|u| u.get(*key))
+                    .and_then(|q| parse_quantity(&q.0))
+                    .unwrap_or_default(),
+                adding: match resource {
+                    QuotaResource::CpuRequests => requests.cpu,
+                    QuotaResource::MemoryRequests => requests.memory,
+                    QuotaResource::CpuLimits => limits.cpu,
+                    QuotaResource::MemoryLimits => limits.memory,
+                    QuotaResource::Pods => *pods as f64,
+                },
+            });
+        }
+    }
+
+    Simulation {
+        against_cluster: true,
+        workloads: fits,
+        total,
+        nodes: nodes
+            .into_iter()
+            .zip(before)
+            .zip(new_pods)
+            .map(|((room, free_before), new_pods)| NodeHeadroom {
+                name: room.node.name,
+                allocatable: room.allocatable,
+                free_before,
+                free_after: room.free,
+                new_pods,
+            })
+            .collect(),
+        quotas: checks,
+    }
+}
+
+/// Check `workloads` against the nodes and quotas of the cluster
+pub async fn simulate_against_cluster(
+    discovery: &DiscoveryEngine,
+    workloads: Vec<ManifestWorkload>,
+) -> Result<Simulation> {
+    let pods: Api<Pod> = Api::all(discovery.client().clone());
+    let active = ListParams::default().fields("status.phase!=Succeeded,status.phase!=Failed");
+    let (nodes, pods) = tokio::try_join!(discovery.list_nodes(), async {
+        Ok(pods.list(&active).await?.items)
+    })?;
+
+    let mut used: BTreeMap<String, Resources> = BTreeMap::new();
+    for pod in &pods {
+        let Some(spec) = &pod.spec else { continue };
+        if let Some(node) = &spec.node_name {
+            used.entry(node.clone())
+                .or_default()
+                .add(pod_resources(spec, true));
+        }
+    }
+    let rooms = nodes
+        .into_iter()
+        .map(|node| {
+            let allocatable = Resources {
+                cpu: node
+                    .allocatable_cpu
+                    .as_deref()
+                    .and_then(parse_quantity)
+                    .unwrap_or_default(),
+                memory: node
+                    .allocatable_memory
+                    .as_deref()
+                    .and_then(parse_quantity)
+                    .unwrap_or_default(),
+            };
+            let used = used.get(&node.name).copied().unwrap_or_default();
+            NodeRoom {
+                free: Resources {
+                    cpu: allocatable.cpu - used.cpu,
+                    memory: allocatable.memory - used.memory,
+                },
+                allocatable,
+                node,
+            }
+        })
+        .collect();
+
+    let mut namespaces: Vec<&str> = workloads.iter().map(|w| w.namespace.as_str()).collect();
+    namespaces.sort();
+    namespaces.dedup();
+    let mut quotas = Vec::new();
+    for namespace in namespaces {
+        let api: Api<ResourceQuota> = Api::namespaced(discovery.client().clone(), namespace);
+        match api.list(&ListParams::default()).await {
+            Ok(list) => quotas.extend(list.items),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(simulate(workloads, rooms, &quotas))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFESTS: &str = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  replicas: 3
+  template:
+    spec:
+      initContainers:
+        - name: migrate
+          resources: {requests: {cpu: "2", memory: 64Mi}}
+      containers:
+        - name: app
+          resources: {requests: {cpu: 500m, memory: 256Mi}}
+        - name: proxy
+          resources: {limits: {cpu: 100m, memory: 64Mi}}
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+---
+apiVersion: apps/v1
+kind: DaemonSet
+metadata:
+  name: agent
+  namespace: monitoring
+spec:
+  template:
+    spec:
+      nodeSelector: {pool: general}
+      containers:
+        - name: agent
+          resources: {requests: {cpu: 100m, memory: 128Mi}}
+"#;
+
+    fn node(name: &str, cpu: f64, memory: f64, pool: &str, taints: &[&str]) -> NodeRoom {
+        let node: NodeInfo = serde_json::from_value(serde_json::json!({
+            "name": name, "roles": [], "kubelet_version": "v1.29.0", "os": "linux",
+            "arch": "amd64", "zone": null, "region": null, "spot": false,
+            "unschedulable": false, "taints": taints, "allocatable_cpu": null,
+            "allocatable_memory": null, "conditions": {"Ready": "True"}, "age": "1d",
+            "labels": {"pool": pool},
+        }))
+        .unwrap();
+        let room = Resources { cpu, memory };
+        NodeRoom {
+            node,
+            allocatable: room,
+            free: room,
+        }
+    }
+
+    #[test]
+    fn test_load_workloads() {
+        let objects = parse_manifests(MANIFESTS).unwrap();
+        assert_eq!(objects.len(), 3);
+        let workloads: Vec<ManifestWorkload> = objects
+            .iter()
+            .filter_map(|o| manifest_workload("deploy.yaml", o, "shop").unwrap())
+            .collect();
+        assert_eq!(workloads.len(), 2);
+
+        let web = &workloads[0];
+        assert_eq!((web.namespace.as_str(), web.replicas), ("shop", 3));
+        // The init container outweighs the app containers for CPU only
+        assert_eq!(web.requests.cpu, 2.0);
+        assert_eq!(web.requests.memory, 320.0 * 1024.0 * 1024.0);
+        assert_eq!(web.limits.cpu, 0.1);
+        assert_eq!(workloads[1].namespace, "monitoring");
+        assert!(workloads[1].per_node);
+    }
+
+    #[test]
+    fn test_simulate_placement_and_quota() {
+        let workloads: Vec<ManifestWorkload> = parse_manifests(MANIFESTS)
+            .unwrap()
+            .iter()
+            .filter_map(|o| manifest_workload("deploy.yaml", o, "shop").unwrap())
+            .collect();
+        let gi = 1024.0 * 1024.0 * 1024.0;
+        let nodes = vec![
+            node("a", 4.1, 8.0 * gi, "general", &[]),
+            node("b", 2.0, 8.0 * gi, "batch", &[]),
+            node("c", 8.0, 8.0 * gi, "general", &["dedicated=gpu:NoSchedule"]),
+        ];
+        let quota: ResourceQuota = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "compute", "namespace": "shop"},
+            "spec": {"hard": {"requests.cpu": "10", "pods": "5"}},
+            "status": {"used": {"requests.cpu": "5", "pods": "1"}},
+        }))
+        .unwrap();
+
+        let simulation = simulate(workloads, nodes, &[quota]);
+        let web = &simulation.workloads[0];
+        // After the agent, a holds two web pods and b one
+        assert_eq!(web.placed, Some(3));
+        let agent = &simulation.workloads[1];
+        assert_eq!((agent.workload.replicas, agent.placed), (1, Some(1)));
+        assert_eq!(simulation.nodes[0].new_pods, 3);
+        assert_eq!(simulation.nodes[2].new_pods, 0);
+
+        let cpu = &simulation.quotas[0];
+        assert_eq!((cpu.resource.as_str(), cpu.adding), ("requests.cpu", 6.0));
+        assert!(!cpu.fits());
+        assert!(simulation.quotas[1].fits());
+        assert!(!simulation.fits());
+        assert_eq!(simulation.unplaced(), 0);
+    }
+}
@@ -0,0 +1,130 @@
+//! Cluster snapshots saved to a file for offline use
+//!
+//! `kdx snapshot save` writes what `kdx history record` records to a single
+//! compressed file that can be copied off the cluster's network. `kdx
+//! snapshot load` makes such a file the active snapshot: until `kdx snapshot
+//! unload`, list, describe, topology and graph commands are answered from it
+//! without reading a kubeconfig or contacting a cluster.
+
+use crate::cache::cache_dir;
+use crate::cli::CompressFormat;
+use crate::compress;
+use crate::error::{ExplorerError, Result};
+use crate::history::ClusterSnapshot;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Write `snapshot` to `path`, compressed with `format`
+pub fn save(path: &Path, snapshot: &ClusterSnapshot, format: CompressFormat) -> Result<()> {
+    let data = compress::compress(format, &serde_json::to_vec(snapshot)?)?;
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// Read a snapshot file, compressed or not
+pub fn read(path: &Path) -> Result<ClusterSnapshot> {
+    let data = compress::decompress(fs::read(path)?)?;
+    serde_json::from_slice(&data).map_err(|e| {
+        ExplorerError::InvalidArgument(format!("{} is not a kdx snapshot: {}", path.display(), e))
+    })
+}
+
+/// The snapshot file commands are answered from, recorded in the cache
+/// directory so it stays loaded across runs
+pub struct ActiveSnapshot {
+    marker: PathBuf,
+}
+
+impl ActiveSnapshot {
+    pub fn open() -> Result<Self> {
+        let dir = cache_dir().ok_or_else(|| {
+            ExplorerError::InvalidArgument(
+                "no cache directory; set KDX_CACHE_DIR to load snapshots".to_string(),
+            )
+        })?;
+        Ok(Self {
+            marker: dir.join("active-snapshot"),
+        })
+    }
+
+    /// Path of the loaded snapshot file, if any
+    pub fn get(&self) -> Result<Option<PathBuf>> {
+        match fs::read_to_string(&self.marker) {
+            Ok(path) => Ok(Some(PathBuf::from(path.trim_end()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Load the snapshot at `path`, checking that it can be read first
+    pub fn set(&self, path: &Path) -> Result<ClusterSnapshot> {
+        let path = fs::canonicalize(path)?;
+        let snapshot = read(&path)?;
+        if let Some(dir) = self.marker.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.marker, path.to_string_lossy().as_bytes())?;
+        Ok(snapshot)
+    }
+
+    /// Unload the snapshot, returning the path that was loaded
+    pub fn clear(&self) -> Result<Option<PathBuf>> {
+        let loaded = self.get()?;
+        if loaded.is_some() {
+            fs::remove_file(&self.marker)?;
+        }
+        Ok(loaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("kdx-snapshot-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("prod.kdx.gz");
+
+        let snapshot = ClusterSnapshot {
+            taken_at: Utc::now(),
+            context: Some("prod".to_string()),
+            namespaces: vec!["default".to_string()],
+            services: Vec::new(),
+            service_labels: BTreeMap::new(),
+            pods: Vec::new(),
+            deployments: Vec::new(),
+            statefulsets: Vec::new(),
+            daemonsets: Vec::new(),
+            configmaps: Vec::new(),
+            secrets: Vec::new(),
+        };
+        save(&file, &snapshot, CompressFormat::Gzip).unwrap();
+        assert!(fs::read(&file).unwrap().starts_with(&[0x1f, 0x8b]));
+
+        let active = ActiveSnapshot {
+            marker: dir.join("state").join("active-snapshot"),
+        };
+        assert_eq!(active.get().unwrap(), None);
+        let loaded = active.set(&file).unwrap();
+        assert_eq!(loaded.context.as_deref(), Some("prod"));
+        assert_eq!(
+            active.get().unwrap(),
+            Some(fs::canonicalize(&file).unwrap())
+        );
+        assert!(read(&active.get().unwrap().unwrap()).is_ok());
+
+        assert!(active.clear().unwrap().is_some());
+        assert_eq!(active.get().unwrap(), None);
+
+        // Anything else is refused before it is loaded
+        fs::write(&file, b"not a snapshot").unwrap();
+        assert!(active.set(&file).is_err());
+        assert_eq!(active.get().unwrap(), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+//! Ordering of list output (`--sort-by FIELD[:desc]`)
+//!
+//! Items are compared by a value of their JSON form, reached with the same
+//! paths `--columns` takes, so every list command sorts the same way without
+//! code of its own. A few names cover fields that are called differently
+//! from kind to kind: `status`, `ready`, `replicas`, `restarts`, `type` and
+//! `node`. `age` compares creation times, youngest first. Numbers compare by
+//! value, and so do quantities such as `10Gi` in fields that hold them (cpu,
+//! memory, requests, limits, capacity); other strings compare as text, so a
+//! name like `10m-worker` is not read as a quantity. Lists and maps compare
+//! by their length, and items without the field come last in either
+//! direction. The sort is stable, so items with equal values keep the order
+//! they were listed in.
+
+use crate::age::parse_duration;
+use crate::error::{ExplorerError, Result};
+use crate::jsonpath::Path;
+use crate::quantity::parse_quantity;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Fields named the same across kinds, with the paths tried in order
+const ALIASES: &[(&str, &[&str])] = &[
+    ("status", &["phase", "status", "conditions.Ready"]),
+    ("ready", &["ready_replicas", "ready", "ready_containers"]),
+    ("replicas", &["replicas", "desired"]),
+    ("restarts", &["restart_count", "restarts"]),
+    ("type", &["service_type", "secret_type", "type"]),
+    ("node", &["node_name", "node"]),
+];
+
+/// Field names whose strings are quantities; `allocatable_cpu` and
+/// `requests.memory` count too
+const QUANTITY_FIELDS: &[&str] = &[
+    "cpu",
+    "memory",
+    "requests",
+    "requested",
+    "limits",
+    "capacity",
+    "allocatable",
+    "storage",
+    "ephemeral-storage",
+];
+
+/// Whether strings of `field` are parsed as quantities
+fn holds_quantities(field: &str) -> bool {
+    field
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .any(|segment| {
+            QUANTITY_FIELDS.iter().any(|name| {
+                segment == *name
+                    || segment
+                        .strip_suffix(name)
+                        .is_some_and(|prefix| prefix.ends_with('_'))
+            })
+        })
+}
+
+/// A parsed `--sort-by`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub field: String,
+    pub descending: bool,
+    paths: Vec<Path>,
+    /// Strings are quantities to compare by value
+    quantities: bool,
+}
+
+impl SortKey {
+    /// `field`, `field:asc` or `field:desc`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (field, descending) = match spec.rsplit_once(':') {
+            Some((field, "desc")) => (field, true),
+            Some((field, "asc")) => (field, false),
+            _ => (spec, false),
+        };
+        if field.is_empty() {
+            return Err(ExplorerError::InvalidArgument(
+                "--sort-by needs a field, e.g. name or age:desc".to_string(),
+            ));
+        }
+        let sources: &[&str] = match ALIASES.iter().find(|(alias, _)| *alias == field) {
+            Some((_, paths)) => paths,
+            None if field == "age" => &[],
+            None => &[field],
+        };
+        Ok(Self {
+            field: field.to_string(),
+            descending,
+            quantities: holds_quantities(field),
+            paths: sources
+                .iter()
+                .map(|source| Path::parse(source))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// The value `item` is ordered by, if it has one
+    fn value(&self, item: &Value, now: DateTime<Utc>) -> Option<SortValue> {
+        if self.field == "age" {
+            let created = item
+                .get("created_at")
+                .and_then(Value::as_str)
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+            return match created {
+                Some(created) => Some(SortValue::Number(
+                    (now - created.with_timezone(&Utc)).num_seconds() as f64,
+                )),
+                None => item
+                    .get("age")
+                    .and_then(Value::as_str)
+                    .and_then(parse_duration)
+                    .map(|seconds| SortValue::Number(seconds as f64)),
+            };
+        }
+        self.paths.iter().find_map(|path| {
+            path.evaluate(item, item)
+                .into_iter()
+                .find_map(|value| SortValue::from_json(value, self.quantities))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SortValue {
+    Number(f64),
+    Text(String),
+}
+
+impl SortValue {
+    fn from_json(value: &Value, quantities: bool) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::Bool(b) => Some(SortValue::Number(*b as u8 as f64)),
+            Value::Number(n) => n.as_f64().map(SortValue::Number),
+            Value::String(s) => Some(match parse_quantity(s).filter(|_| quantities) {
+                Some(quantity) => SortValue::Number(quantity),
+                None => SortValue::Text(s.clone()),
+            }),
+            Value::Array(a) => Some(SortValue::Number(a.len() as f64)),
+            Value::Object(o) => Some(SortValue::Number(o.len() as f64)),
+        }
+    }
+
+    fn compare(&self, other: &SortValue) -> Ordering {
+        match (self, other) {
+            (SortValue::Number(a), SortValue::Number(b)) => a.total_cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortValue::Number(_), SortValue::Text(_)) => Ordering::Less,
+            (SortValue::Text(_), SortValue::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// Sort `items` by `key`, leaving them as listed without one. It is an
+/// error for no item to have the field, which usually means a typo.
+pub fn sort_items<T: Serialize>(items: &mut Vec<T>, key: Option<&SortKey>) -> Result<()> {
+    let Some(key) = key else {
+        return Ok(());
+    };
+    let now = Utc::now();
+    let mut keyed: Vec<(Option<SortValue>, T)> = std::mem::take(items)
+        .into_iter()
+        .map(|item| {
+            let value = serde_json::to_value(&item)
+                .ok()
+                .and_then(|json| key.value(&json, now));
+            (value, item)
+        })
+        .collect();
+    if !keyed.is_empty() && keyed.iter().all(|(value, _)| value.is_none()) {
+        *items = keyed.into_iter().map(|(_, item)| item).collect();
+        return Err(ExplorerError::InvalidArgument(format!(
+            "--sort-by: no listed resource has a '{}' field",
+            key.field
+        )));
+    }
+
+    keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) if key.descending => b.compare(a),
+        (Some(a), Some(b)) => a.compare(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    *items = keyed.into_iter().map(|(_, item)| item).collect();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names(items: &[Value]) -> Vec<&str> {
+        items.iter().map(|i| i["name"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_sort_items() {
+        let mut pods = vec![
+            json!({"name": "b", "phase": "Running", "restart_count": 3, "age": "2h"}),
+            json!({"name": "a", "phase": "Pending", "restart_count": 0, "age": "5m"}),
+            json!({"name": "c", "phase": "Running", "restart_count": 12, "age": "3d"}),
+        ];
+        sort_items(&mut pods, Some(&SortKey::parse("name").unwrap())).unwrap();
+        assert_eq!(names(&pods), ["a", "b", "c"]);
+        sort_items(&mut pods, Some(&SortKey::parse("restarts:desc").unwrap())).unwrap();
+        assert_eq!(names(&pods), ["c", "b", "a"]);
+        sort_items(&mut pods, Some(&SortKey::parse("age").unwrap())).unwrap();
+        assert_eq!(names(&pods), ["a", "b", "c"]);
+        // Stable: the two Running pods keep their age order
+        sort_items(&mut pods, Some(&SortKey::parse("status").unwrap())).unwrap();
+        assert_eq!(names(&pods), ["a", "b", "c"]);
+
+        let mut claims = vec![
+            json!({"name": "big", "capacity": "1Ti"}),
+            json!({"name": "pending", "capacity": null}),
+            json!({"name": "small", "capacity": "500Mi"}),
+        ];
+        let key = SortKey::parse("capacity:desc").unwrap();
+        sort_items(&mut claims, Some(&key)).unwrap();
+        assert_eq!(names(&claims), ["big", "small", "pending"]);
+
+        // Names that look like quantities still sort as text
+        let mut services = vec![
+            json!({"name": "api", "allocatable_cpu": "2"}),
+            json!({"name": "1e3-api", "allocatable_cpu": "500m"}),
+            json!({"name": "10m-worker", "allocatable_cpu": "1500m"}),
+        ];
+        sort_items(&mut services, Some(&SortKey::parse("name").unwrap())).unwrap();
+        assert_eq!(names(&services), ["10m-worker", "1e3-api", "api"]);
+        let key = SortKey::parse("allocatable_cpu").unwrap();
+        sort_items(&mut services, Some(&key)).unwrap();
+        assert_eq!(names(&services), ["1e3-api", "10m-worker", "api"]);
+        assert!(!holds_quantities("storage_class"));
+        assert!(holds_quantities("{.requests.memory}"));
+
+        let err = sort_items(&mut claims, Some(&SortKey::parse("colour").unwrap()));
+        assert!(err.is_err());
+        assert_eq!(claims.len(), 3);
+    }
+}
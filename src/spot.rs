@@ -0,0 +1,165 @@
+//! Spot and preemptible capacity detection and exposure reporting
+
+use crate::discovery::{pod_workload, NodeInfo, PodInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Node labels (and the value marking spot capacity) used by common providers
+const SPOT_LABELS: &[(&str, &str)] = &[
+    ("eks.amazonaws.com/capacityType", "spot"),
+    ("karpenter.sh/capacity-type", "spot"),
+    ("cloud.google.com/gke-spot", "true"),
+    ("cloud.google.com/gke-preemptible", "true"),
+    ("kubernetes.azure.com/scalesetpriority", "spot"),
+    ("node.kubernetes.io/lifecycle", "spot"),
+];
+
+/// Taint keys applied to spot or preemptible nodes
+const SPOT_TAINTS: &[&str] = &[
+    "cloud.google.com/gke-spot",
+    "cloud.google.com/gke-preemptible",
+    "kubernetes.azure.com/scalesetpriority",
+];
+
+/// Return true if the node labels or taints mark it as spot/preemptible capacity
+pub fn is_spot_node(labels: &BTreeMap<String, String>, taints: &[String]) -> bool {
+    let labelled = SPOT_LABELS.iter().any(|(key, value)| {
+        labels
+            .get(*key)
+            .is_some_and(|v| v.eq_ignore_ascii_case(value))
+    });
+
+    let tainted = taints.iter().any(|taint| {
+        SPOT_TAINTS.iter().any(|key| {
+            taint
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with('=') || rest.starts_with(':'))
+        })
+    });
+
+    labelled || tainted
+}
+
+/// A workload whose replicas all run on spot capacity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotExposure {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    pub replicas: u32,
+    pub nodes: Vec<String>,
+}
+
+/// Find workloads with no replica on on-demand nodes, ordered by namespace and owner.
+///
+/// Pods that are not scheduled yet are ignored.
+pub fn spot_exposure(nodes: &[NodeInfo], pods: &[PodInfo]) -> Vec<SpotExposure> {
+    let spot_nodes: HashSet<&str> = nodes
+        .iter()
+        .filter(|node| node.spot)
+        .map(|node| node.name.as_str())
+        .collect();
+
+    let mut workloads: BTreeMap<(String, String, String), Placement> = BTreeMap::new();
+
+    for pod in pods {
+        let Some(node) = pod.node_name.as_deref() else {
+            continue;
+        };
+
+        let (kind, name) = pod_workload(pod);
+        let placement = workloads
+            .entry((pod.namespace.clone(), kind, name))
+            .or_default();
+
+        placement.replicas += 1;
+        if spot_nodes.contains(node) {
+            placement.spot_nodes.insert(node.to_string());
+        } else {
+            placement.on_demand = true;
+        }
+    }
+
+    workloads
+        .into_iter()
+        .filter(|(_, placement)| !placement.on_demand)
+        .map(|((namespace, kind, name), placement)| SpotExposure {
+            namespace,
+            kind,
+            name,
+            replicas: placement.replicas,
+            nodes: placement.spot_nodes.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// Where a workload's scheduled replicas run
+#[derive(Default)]
+struct Placement {
+    replicas: u32,
+    spot_nodes: BTreeSet<String>,
+    on_demand: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, spot: bool) -> NodeInfo {
+        NodeInfo {
+            name: name.to_string(),
+            zone: None,
+            region: None,
+            spot,
+            taints: vec![],
+            age: "10d".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn pod(name: &str, owner: &str, node: &str) -> PodInfo {
+        PodInfo {
+            node_name: Some(node.to_string()),
+            owner_kind: Some("StatefulSet".to_string()),
+            owner_name: Some(owner.to_string()),
+            ..PodInfo::fixture("default", name)
+        }
+    }
+
+    #[test]
+    fn test_is_spot_node() {
+        let mut labels = BTreeMap::new();
+        assert!(!is_spot_node(&labels, &[]));
+
+        labels.insert("karpenter.sh/capacity-type".to_string(), "spot".to_string());
+        assert!(is_spot_node(&labels, &[]));
+
+        let taints = vec!["cloud.google.com/gke-spot=true:NoSchedule".to_string()];
+        assert!(is_spot_node(&BTreeMap::new(), &taints));
+
+        let unrelated = vec!["cloud.google.com/gke-spotless:NoSchedule".to_string()];
+        assert!(!is_spot_node(&BTreeMap::new(), &unrelated));
+    }
+
+    #[test]
+    fn test_spot_exposure() {
+        let nodes = vec![
+            node("spot-1", true),
+            node("spot-2", true),
+            node("od-1", false),
+        ];
+        let pods = vec![
+            pod("queue-0", "queue", "spot-1"),
+            pod("queue-1", "queue", "spot-2"),
+            pod("api-0", "api", "spot-1"),
+            pod("api-1", "api", "od-1"),
+            pod("db-0", "db", "od-1"),
+        ];
+
+        let exposed = spot_exposure(&nodes, &pods);
+        assert_eq!(exposed.len(), 1);
+        assert_eq!(exposed[0].name, "queue");
+        assert_eq!(exposed[0].replicas, 2);
+        assert_eq!(exposed[0].nodes, vec!["spot-1", "spot-2"]);
+    }
+}
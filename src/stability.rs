@@ -0,0 +1,440 @@
+//! Workload stability scoring for `kdx stability`
+//!
+//! Each workload gets a score from 0 (stable) to 100 built from three signals:
+//! container restarts per pod per day, readiness flaps, and how young its pods
+//! are compared with the workload itself. Pods replaced long after the
+//! workload was created point at evictions, crashes or constant rollouts.
+//!
+//! Live scoring reads the current pod state: restarts are spread over each
+//! pod's lifetime and a flap is a Ready condition that changed within the
+//! window after start-up. History scoring compares consecutive snapshots in
+//! the window, counting restarts that happened between them and every time a
+//! pod lost readiness.
+
+use crate::age::format_duration;
+use crate::discovery::{pod_workload, DiscoveryEngine, LazyConvert, PodInfo};
+use crate::error::Result;
+use crate::resource::ResourceKind;
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::core::v1::Pod;
+use kube::discovery::Scope;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Score contribution at or above `RESTARTS_PER_POD_DAY_MAX`
+const RESTART_WEIGHT: f64 = 50.0;
+const RESTARTS_PER_POD_DAY_MAX: f64 = 5.0;
+/// Score contribution at or above `FLAPS_PER_POD_MAX`
+const FLAP_WEIGHT: f64 = 30.0;
+const FLAPS_PER_POD_MAX: f64 = 2.0;
+/// Score contribution when every pod is brand new
+const TURNOVER_WEIGHT: f64 = 20.0;
+/// Workloads younger than this are not scored on pod age
+const MIN_WORKLOAD_AGE_SECONDS: i64 = 86400;
+/// Readiness changes this soon after a pod was created are start-up, not flaps
+const STARTUP_GRACE_SECONDS: i64 = 300;
+/// Shortest lifetime restarts are spread over, so a restart in a pod a few
+/// seconds old does not dominate the ranking
+const MIN_POD_SECONDS: i64 = 3600;
+
+/// Workload identity: namespace, kind and name
+pub type WorkloadKey = (String, String, String);
+
+/// State of one pod at one point in time
+#[derive(Debug, Clone)]
+pub struct PodObservation {
+    pub namespace: String,
+    pub name: String,
+    pub workload_kind: String,
+    pub workload_name: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    pub ready: bool,
+    /// Last change of the Ready condition, when known
+    pub ready_changed_at: Option<DateTime<Utc>>,
+}
+
+impl PodObservation {
+    /// Observe a live pod; completed pods are skipped
+    pub fn from_pod(pod: &Pod) -> Option<Self> {
+        let info: PodInfo = pod.lazy_convert()?;
+        let ready_changed_at = pod
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+            .and_then(|c| c.last_transition_time.as_ref())
+            .map(|t| t.0);
+
+        let mut observation = Self::from_info(&info)?;
+        observation.ready_changed_at = ready_changed_at;
+        Some(observation)
+    }
+
+    /// Observe a pod recorded in a snapshot; completed pods are skipped
+    pub fn from_info(info: &PodInfo) -> Option<Self> {
+        if info.phase == "Succeeded" {
+            return None;
+        }
+        let (workload_kind, workload_name) = pod_workload(info);
+        Some(Self {
+            namespace: info.namespace.clone(),
+            name: info.name.clone(),
+            workload_kind,
+            workload_name,
+            created_at: info.created_at,
+            restarts: info.restart_count,
+            ready: info.total_containers > 0 && info.ready_containers == info.total_containers,
+            ready_changed_at: None,
+        })
+    }
+
+    fn workload(&self) -> WorkloadKey {
+        (
+            self.namespace.clone(),
+            self.workload_kind.clone(),
+            self.workload_name.clone(),
+        )
+    }
+}
+
+/// Stability of one workload over the observed window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadStability {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    pub pods: usize,
+    pub restarts: u32,
+    pub restarts_per_pod_day: f64,
+    pub readiness_flaps: u32,
+    /// Median age of the current pods
+    pub median_pod_age: Option<String>,
+    pub workload_age: Option<String>,
+    /// 0 (stable) to 100
+    pub score: f64,
+    /// What contributed to the score, e.g. "12 restarts (4.0/pod/day)"
+    pub reasons: Vec<String>,
+}
+
+/// Signals collected for one workload
+#[derive(Default)]
+struct Signals {
+    pods: BTreeSet<String>,
+    restarts: u32,
+    pod_seconds: i64,
+    flaps: u32,
+    /// Ages in seconds of the pods at the end of the window
+    current_ages: Vec<i64>,
+}
+
+/// Observe the pods currently in `namespace` (or all namespaces)
+pub async fn observe_live_pods(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<PodObservation>> {
+    let mut observations = Vec::new();
+    for (_, object) in discovery
+        .list_objects_json(namespace, &[ResourceKind::Pod])
+        .await?
+    {
+        let pod: Pod = serde_json::from_value(object)?;
+        observations.extend(PodObservation::from_pod(&pod));
+    }
+    Ok(observations)
+}
+
+/// Creation times of the controllers pods resolve to, from the live cluster
+pub async fn workload_creation_times(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<HashMap<WorkloadKey, DateTime<Utc>>> {
+    let kinds: Vec<_> = [
+        ResourceKind::Deployment,
+        ResourceKind::ReplicaSet,
+        ResourceKind::StatefulSet,
+        ResourceKind::DaemonSet,
+        ResourceKind::Job,
+    ]
+    .iter()
+    .map(|kind| (kind.api_resource(), Scope::Namespaced))
+    .collect();
+
+    Ok(discovery
+        .list_object_metadata(namespace, &kinds)
+        .await?
+        .into_iter()
+        .filter_map(|(kind, meta)| {
+            let key = (meta.namespace?, kind, meta.name?);
+            Some((key, meta.creation_timestamp?.0))
+        })
+        .collect())
+}
+
+/// Score workloads from their current pods. `workloads_created` holds the
+/// creation times of the workloads, keyed by namespace, kind and name.
+pub fn score_live(
+    pods: &[PodObservation],
+    workloads_created: &HashMap<WorkloadKey, DateTime<Utc>>,
+    now: DateTime<Utc>,
+    window: Duration,
+) -> Vec<WorkloadStability> {
+    let mut signals: BTreeMap<WorkloadKey, Signals> = BTreeMap::new();
+    for pod in pods {
+        let entry = signals.entry(pod.workload()).or_default();
+        entry.pods.insert(pod.name.clone());
+        entry.restarts += pod.restarts;
+
+        let Some(created_at) = pod.created_at else {
+            continue;
+        };
+        let age = (now - created_at).num_seconds().max(0);
+        entry.pod_seconds += age.max(MIN_POD_SECONDS);
+        entry.current_ages.push(age);
+
+        if let Some(changed_at) = pod.ready_changed_at {
+            let after_startup = (changed_at - created_at).num_seconds() > STARTUP_GRACE_SECONDS;
+            if changed_at >= now - window && after_startup {
+                entry.flaps += 1;
+            }
+        }
+    }
+
+    rank(signals, workloads_created, now)
+}
+
+/// Score workloads from snapshots taken within the window, oldest first
+pub fn score_history(
+    snapshots: &[(DateTime<Utc>, Vec<PodObservation>)],
+    workloads_created: &HashMap<WorkloadKey, DateTime<Utc>>,
+) -> Vec<WorkloadStability> {
+    let Some((first_taken, _)) = snapshots.first() else {
+        return Vec::new();
+    };
+    let (now, last_pods) = snapshots.last().map(|(t, p)| (*t, p)).unwrap();
+
+    // Sightings of each pod, in snapshot order
+    let mut sightings = BTreeMap::<(&str, &str), Vec<_>>::new();
+    for (taken_at, pods) in snapshots {
+        for pod in pods {
+            sightings
+                .entry((&pod.namespace, &pod.name))
+                .or_default()
+                .push((*taken_at, pod));
+        }
+    }
+
+    let mut signals: BTreeMap<WorkloadKey, Signals> = BTreeMap::new();
+    for seen in sightings.values() {
+        let (first_seen, first) = seen[0];
+        let (last_seen, _) = seen[seen.len() - 1];
+        let entry = signals.entry(first.workload()).or_default();
+        entry.pods.insert(first.name.clone());
+        entry.pod_seconds += (last_seen - first_seen).num_seconds().max(MIN_POD_SECONDS);
+
+        // Restarts of a pod created within the window all happened in it
+        if first.created_at.is_some_and(|t| t > *first_taken) {
+            entry.restarts += first.restarts;
+        }
+        for pair in seen.windows(2) {
+            let (previous, current) = (pair[0].1, pair[1].1);
+            if current.created_at != previous.created_at {
+                // Recreated under the same name, e.g. a StatefulSet pod
+                entry.restarts += current.restarts;
+            } else {
+                entry.restarts += current.restarts.saturating_sub(previous.restarts);
+            }
+            if previous.ready && !current.ready {
+                entry.flaps += 1;
+            }
+        }
+    }
+    for pod in last_pods {
+        if let Some(created_at) = pod.created_at {
+            let entry = signals.entry(pod.workload()).or_default();
+            entry
+                .current_ages
+                .push((now - created_at).num_seconds().max(0));
+        }
+    }
+
+    rank(signals, workloads_created, now)
+}
+
+/// Score every workload and sort the least stable first
+fn rank(
+    signals: BTreeMap<WorkloadKey, Signals>,
+    workloads_created: &HashMap<WorkloadKey, DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Vec<WorkloadStability> {
+    let mut ranked: Vec<WorkloadStability> = signals
+        .into_iter()
+        .map(|(key, signals)| {
+            let workload_age = workloads_created
+                .get(&key)
+                .map(|created| (now - *created).num_seconds().max(0));
+            score(key, signals, workload_age)
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.namespace.cmp(&b.namespace))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranked
+}
+
+fn score(key: WorkloadKey, mut signals: Signals, workload_age: Option<i64>) -> WorkloadStability {
+    let (namespace, kind, name) = key;
+    let pods = signals.pods.len().max(1) as f64;
+    let pod_days = signals.pod_seconds.max(MIN_POD_SECONDS) as f64 / 86400.0;
+    let restarts_per_pod_day = f64::from(signals.restarts) / pod_days;
+
+    signals.current_ages.sort_unstable();
+    let median_age = signals
+        .current_ages
+        .get(signals.current_ages.len() / 2)
+        .copied();
+
+    let mut reasons = Vec::new();
+    let mut total = 0.0;
+    if signals.restarts > 0 {
+        total += RESTART_WEIGHT * (restarts_per_pod_day / RESTARTS_PER_POD_DAY_MAX).min(1.0);
+        reasons.push(format!(
+            "{} restarts ({:.1}/pod/day)",
+            signals.restarts, restarts_per_pod_day
+        ));
+    }
+    if signals.flaps > 0 {
+        total += FLAP_WEIGHT * (f64::from(signals.flaps) / pods / FLAPS_PER_POD_MAX).min(1.0);
+        reasons.push(format!("{} readiness flaps", signals.flaps));
+    }
+    if let (Some(median), Some(age)) = (median_age, workload_age) {
+        if age >= MIN_WORKLOAD_AGE_SECONDS {
+            let ratio = (median as f64 / age as f64).min(1.0);
+            total += TURNOVER_WEIGHT * (1.0 - ratio);
+            if ratio < 0.5 {
+                reasons.push(format!(
+                    "pods {} old vs workload {}",
+                    format_duration(median),
+                    format_duration(age)
+                ));
+            }
+        }
+    }
+
+    WorkloadStability {
+        namespace,
+        kind,
+        name,
+        pods: signals.pods.len(),
+        restarts: signals.restarts,
+        restarts_per_pod_day: (restarts_per_pod_day * 10.0).round() / 10.0,
+        readiness_flaps: signals.flaps,
+        median_pod_age: median_age.map(format_duration),
+        workload_age: workload_age.map(format_duration),
+        score: (total * 10.0).round() / 10.0,
+        reasons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(name: &str, workload: &str, created: &str, restarts: u32) -> PodObservation {
+        PodObservation {
+            namespace: "shop".to_string(),
+            name: name.to_string(),
+            workload_kind: "Deployment".to_string(),
+            workload_name: workload.to_string(),
+            created_at: Some(created.parse().unwrap()),
+            restarts,
+            ready: true,
+            ready_changed_at: None,
+        }
+    }
+
+    fn time(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn key(name: &str) -> WorkloadKey {
+        (
+            "shop".to_string(),
+            "Deployment".to_string(),
+            name.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_score_live() {
+        let now = time("2024-05-10T00:00:00Z");
+        let mut flapping = observation("api-1", "api", "2024-05-08T00:00:00Z", 20);
+        flapping.ready_changed_at = Some(time("2024-05-09T12:00:00Z"));
+        // Became ready right after start-up: not a flap
+        let mut starting = observation("api-2", "api", "2024-05-09T23:00:00Z", 0);
+        starting.ready_changed_at = Some(time("2024-05-09T23:01:00Z"));
+        let pods = [
+            flapping,
+            starting,
+            observation("web-1", "web", "2024-04-01T00:00:00Z", 0),
+        ];
+        let created = HashMap::from([
+            (key("api"), time("2024-01-01T00:00:00Z")),
+            (key("web"), time("2024-04-01T00:00:00Z")),
+        ]);
+
+        let ranked = score_live(&pods, &created, now, Duration::hours(24));
+        assert_eq!(ranked.len(), 2);
+
+        let api = &ranked[0];
+        assert_eq!(api.name, "api");
+        assert_eq!(api.restarts, 20);
+        // 20 restarts over 2 days + 1 hour of pod time
+        assert_eq!(api.restarts_per_pod_day, 9.8);
+        assert_eq!(api.readiness_flaps, 1);
+        assert_eq!(api.workload_age.as_deref(), Some("130d"));
+        assert_eq!(api.reasons.len(), 3);
+        // Restarts saturate at 50, one flap in two pods adds 7.5, pods 2d old
+        // in a 130d old workload add 19.7
+        assert_eq!(api.score, 77.2);
+
+        let web = &ranked[1];
+        assert_eq!(web.score, 0.0);
+        assert!(web.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_score_history() {
+        let t1 = time("2024-05-09T00:00:00Z");
+        let t2 = time("2024-05-09T12:00:00Z");
+        let t3 = time("2024-05-10T00:00:00Z");
+        let mut not_ready = observation("api-1", "api", "2024-05-01T00:00:00Z", 5);
+        not_ready.ready = false;
+        let snapshots = vec![
+            (
+                t1,
+                vec![observation("api-1", "api", "2024-05-01T00:00:00Z", 2)],
+            ),
+            (t2, vec![not_ready]),
+            (
+                t3,
+                vec![
+                    observation("api-1", "api", "2024-05-01T00:00:00Z", 6),
+                    // Created within the window with one restart
+                    observation("api-2", "api", "2024-05-09T18:00:00Z", 1),
+                ],
+            ),
+        ];
+
+        let ranked = score_history(&snapshots, &HashMap::new());
+        let api = &ranked[0];
+        // 4 restarts between snapshots of api-1 plus one in the new pod
+        assert_eq!(api.restarts, 5);
+        assert_eq!(api.readiness_flaps, 1);
+        assert_eq!(api.pods, 2);
+        assert_eq!(api.workload_age, None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Per-ordinal health of a StatefulSet
+//!
+//! A StatefulSet owns pods `<name>-<ordinal>` and, for every volume claim
+//! template, claims `<template>-<name>-<ordinal>`. Comparing what exists with
+//! the ordinals the spec asks for shows the gaps that block a rollout: a
+//! missing or unready pod, or a claim that was never created or never bound.
+
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod};
+use serde::{Deserialize, Serialize};
+
+/// Label holding the controller revision a pod was created from
+const REVISION_LABEL: &str = "controller-revision-hash";
+
+/// A claim made for one ordinal from a volume claim template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdinalClaim {
+    pub name: String,
+    /// Claim phase, or `None` when the claim does not exist
+    pub phase: Option<String>,
+    pub volume: Option<String>,
+}
+
+/// The pod and claims of one StatefulSet ordinal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatefulSetOrdinal {
+    pub ordinal: i32,
+    pub pod: String,
+    /// Pod phase, or `None` when the pod does not exist
+    pub phase: Option<String>,
+    pub ready: bool,
+    /// Controller revision the pod runs
+    pub revision: Option<String>,
+    pub claims: Vec<OrdinalClaim>,
+}
+
+impl StatefulSetOrdinal {
+    /// Problems with this ordinal, e.g. "pod web-2 missing"
+    pub fn gaps(&self, update_revision: Option<&str>) -> Vec<String> {
+        let mut gaps = Vec::new();
+        match &self.phase {
+            None => gaps.push(format!("pod {} missing", self.pod)),
+            Some(phase) if !self.ready => {
+                gaps.push(format!("pod {} not ready ({})", self.pod, phase))
+            }
+            Some(_) => {}
+        }
+        if let (Some(revision), Some(update)) = (&self.revision, update_revision) {
+            if revision != update {
+                gaps.push(format!("pod {} on old revision {}", self.pod, revision));
+            }
+        }
+        for claim in &self.claims {
+            match claim.phase.as_deref() {
+                None => gaps.push(format!("claim {} missing", claim.name)),
+                Some("Bound") => {}
+                Some(phase) => gaps.push(format!("claim {} {}", claim.name, phase)),
+            }
+        }
+        gaps
+    }
+}
+
+/// Names of the volume claim templates of a StatefulSet
+pub fn claim_templates(statefulset: &StatefulSet) -> Vec<String> {
+    statefulset
+        .spec
+        .iter()
+        .flat_map(|s| s.volume_claim_templates.iter().flatten())
+        .filter_map(|t| t.metadata.name.clone())
+        .collect()
+}
+
+/// Status of every ordinal the StatefulSet should have, followed by pods
+/// left over above the desired replica count
+pub fn ordinal_status(
+    statefulset: &StatefulSet,
+    pods: &[Pod],
+    claims: &[PersistentVolumeClaim],
+) -> Vec<StatefulSetOrdinal> {
+    let name = statefulset.metadata.name.as_deref().unwrap_or_default();
+    let spec = statefulset.spec.as_ref();
+    let replicas = spec.and_then(|s| s.replicas).unwrap_or(1);
+    let start = spec
+        .and_then(|s| s.ordinals.as_ref())
+        .and_then(|o| o.start)
+        .unwrap_or(0);
+    let templates = claim_templates(statefulset);
+
+    let pod_ordinal = |pod: &Pod| -> Option<i32> {
+        pod.metadata
+            .name
+            .as_deref()?
+            .strip_prefix(name)?
+            .strip_prefix('-')?
+            .parse()
+            .ok()
+    };
+    let extra = pods
+        .iter()
+        .filter_map(pod_ordinal)
+        .filter(|o| *o < start || *o >= start + replicas);
+    let mut ordinals: Vec<i32> = (start..start + replicas).chain(extra).collect();
+    ordinals.sort_unstable();
+    ordinals.dedup();
+
+    ordinals
+        .into_iter()
+        .map(|ordinal| {
+            let pod_name = format!("{}-{}", name, ordinal);
+            let pod = pods
+                .iter()
+                .find(|p| p.metadata.name.as_deref() == Some(pod_name.as_str()));
+            let status = pod.and_then(|p| p.status.as_ref());
+            let ready = status
+                .and_then(|s| s.conditions.as_ref())
+                .is_some_and(|c| c.iter().any(|c| c.type_ == "Ready" && c.status == "True"));
+
+            let claims = templates
+                .iter()
+                .map(|template| {
+                    let claim_name = format!("{}-{}", template, pod_name);
+                    let claim = claims
+                        .iter()
+                        .find(|c| c.metadata.name.as_deref() == Some(claim_name.as_str()));
+                    OrdinalClaim {
+                        name: claim_name,
+                        phase: claim.map(|c| {
+                            c.status
+                                .as_ref()
+                                .and_then(|s| s.phase.clone())
+                                .unwrap_or_else(|| "Unknown".to_string())
+                        }),
+                        volume: claim
+                            .and_then(|c| c.spec.as_ref())
+                            .and_then(|s| s.volume_name.clone()),
+                    }
+                })
+                .collect();
+
+            StatefulSetOrdinal {
+                ordinal,
+                pod: pod_name,
+                phase: pod.map(|_| {
+                    status
+                        .and_then(|s| s.phase.clone())
+                        .unwrap_or_else(|| "Unknown".to_string())
+                }),
+                ready,
+                revision: pod
+                    .and_then(|p| p.metadata.labels.as_ref())
+                    .and_then(|l| l.get(REVISION_LABEL).cloned()),
+                claims,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordinal_status_and_gaps() {
+        let statefulset: StatefulSet = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "db", "namespace": "data"},
+            "spec": {
+                "replicas": 3,
+                "serviceName": "db",
+                "selector": {"matchLabels": {"app": "db"}},
+                "template": {"metadata": {"labels": {"app": "db"}}},
+                "volumeClaimTemplates": [{"metadata": {"name": "data"}}],
+            },
+        }))
+        .unwrap();
+        let pod = |name: &str, ready: bool, revision: &str| -> Pod {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": name, "labels": {REVISION_LABEL: revision}},
+                "status": {
+                    "phase": if ready { "Running" } else { "Pending" },
+                    "conditions": [{"type": "Ready", "status": if ready { "True" } else { "False" }}],
+                },
+            }))
+            .unwrap()
+        };
+        let claim = |name: &str, phase: &str| -> PersistentVolumeClaim {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": name},
+                "spec": {"volumeName": format!("pv-{}", name)},
+                "status": {"phase": phase},
+            }))
+            .unwrap()
+        };
+        let pods = [
+            pod("db-0", true, "db-2"),
+            pod("db-1", false, "db-1"),
+            pod("db-4", true, "db-2"),
+            pod("dbx-0", true, "db-2"),
+        ];
+        let claims = [claim("data-db-0", "Bound"), claim("data-db-1", "Pending")];
+
+        let ordinals = ordinal_status(&statefulset, &pods, &claims);
+        let numbers: Vec<i32> = ordinals.iter().map(|o| o.ordinal).collect();
+        // db-4 is left over above the three replicas; dbx-0 is not ours
+        assert_eq!(numbers, [0, 1, 2, 4]);
+        assert_eq!(
+            ordinals[0].claims[0].volume.as_deref(),
+            Some("pv-data-db-0")
+        );
+
+        let update = Some("db-2");
+        assert!(ordinals[0].gaps(update).is_empty());
+        assert_eq!(
+            ordinals[1].gaps(update),
+            [
+                "pod db-1 not ready (Pending)",
+                "pod db-1 on old revision db-1",
+                "claim data-db-1 Pending"
+            ]
+        );
+        assert_eq!(
+            ordinals[2].gaps(update),
+            ["pod db-2 missing", "claim data-db-2 missing"]
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Execution statistics collected for `--stats`
+//!
+//! API traffic is counted by a layer on the Kubernetes client, so every
+//! request made through it is included regardless of which command issued it.
+//! Requests are also counted by the resource they address, so the load a
+//! command puts on the API server can be read off its summary.
+
+use crate::cache::CacheStats;
+use crate::read_only;
+use bytes::Bytes;
+use http_body::Body;
+use kube::client::DynBody;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::BoxError;
+
+/// Counters and phase timings for a single kdx run
+#[derive(Debug)]
+pub struct RunStats {
+    started: Instant,
+    /// Subcommand the run executes
+    command: Option<String>,
+    api_requests: AtomicU64,
+    /// Requests by the resource they address, as `resource.group`
+    resource_requests: Mutex<BTreeMap<String, u64>>,
+    bytes_received: AtomicU64,
+    namespaces_scanned: AtomicU64,
+    phases: Mutex<Vec<(String, Duration)>>,
+    /// Clients refuse mutating requests (`--read-only-verify`)
+    read_only: bool,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            command: None,
+            api_requests: AtomicU64::new(0),
+            resource_requests: Mutex::default(),
+            bytes_received: AtomicU64::new(0),
+            namespaces_scanned: AtomicU64::new(0),
+            phases: Mutex::new(Vec::new()),
+            read_only: false,
+        }
+    }
+}
+
+/// The resource a request to `path` addresses, named as kubectl does:
+/// `pods`, `deployments.apps`, `pods/log`. Discovery requests are
+/// `discovery`, and paths outside /api and /apis are named by their first
+/// segment (`version`, `openapi`).
+pub fn api_resource(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let (group, rest) = match segments.as_slice() {
+        ["api", _, rest @ ..] => ("", rest),
+        ["apis", group, _, rest @ ..] => (*group, rest),
+        ["api" | "apis", ..] => return "discovery".to_string(),
+        [first, ..] => return first.to_string(),
+        [] => return "discovery".to_string(),
+    };
+    // Legacy watch paths put `watch` before the resource
+    let rest = rest.strip_prefix(&["watch"]).unwrap_or(rest);
+    let (resource, subresource) = match rest {
+        [] => return "discovery".to_string(),
+        ["namespaces", _, resource, _, subresource, ..] => (*resource, Some(*subresource)),
+        ["namespaces", _, resource, ..] => (*resource, None),
+        [resource, _, subresource, ..] => (*resource, Some(*subresource)),
+        [resource, ..] => (*resource, None),
+    };
+    let mut name = resource.to_string();
+    if !group.is_empty() {
+        name = format!("{}.{}", name, group);
+    }
+    if let Some(subresource) = subresource {
+        name = format!("{}/{}", name, subresource);
+    }
+    name
+}
+
+impl RunStats {
+    /// Statistics of a run of the subcommand `command`
+    pub fn for_command(command: impl Into<String>) -> Self {
+        Self {
+            command: Some(command.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Guard the clients built from now on against mutating requests
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Build a client whose API traffic is added to these statistics,
+    /// guarded against mutating requests in read-only mode
+    pub fn client(self: &Arc<Self>, config: kube::Config) -> kube::Result<kube::Client> {
+        let requests = self.clone();
+        let responses = self.clone();
+        let layer = tower::ServiceBuilder::new()
+            .map_request(move |request: http::Request<_>| {
+                requests.count_request(request.uri().path());
+                request
+            })
+            .map_response(move |response: http::Response<Box<DynBody>>| {
+                let stats = responses.clone();
+                response.map(|inner| Box::new(CountingBody { inner, stats }) as Box<DynBody>)
+            });
+
+        Ok(kube::client::ClientBuilder::try_from(config)?
+            .with_layer(&layer)
+            .with_layer(&read_only::layer(self.read_only))
+            .build())
+    }
+
+    fn count_request(&self, path: &str) {
+        self.api_requests.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut resources) = self.resource_requests.lock() {
+            *resources.entry(api_resource(path)).or_default() += 1;
+        }
+    }
+
+    /// Count namespaces whose resources were fetched
+    pub fn add_namespaces_scanned(&self, count: usize) {
+        self.namespaces_scanned
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Record the wall time of a phase that began at `started`
+    pub fn record_phase(&self, phase: &str, started: Instant) {
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.push((phase.to_string(), started.elapsed()));
+        }
+    }
+
+    /// Snapshot of the counters, combined with the cache counters
+    pub fn summary(&self, cache: &CacheStats) -> StatsSummary {
+        let phases = self
+            .phases
+            .lock()
+            .map(|phases| {
+                phases
+                    .iter()
+                    .map(|(phase, elapsed)| PhaseTiming {
+                        phase: phase.clone(),
+                        millis: elapsed.as_millis() as u64,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let elapsed = self.started.elapsed();
+        let api_requests = self.api_requests.load(Ordering::Relaxed);
+        let mut resources: Vec<ResourceRequests> = self
+            .resource_requests
+            .lock()
+            .map(|resources| {
+                resources
+                    .iter()
+                    .map(|(resource, requests)| ResourceRequests {
+                        resource: resource.clone(),
+                        requests: *requests,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Busiest first; ties stay in name order
+        resources.sort_by_key(|r| std::cmp::Reverse(r.requests));
+
+        StatsSummary {
+            command: self.command.clone(),
+            api_requests,
+            requests_per_second: api_requests as f64 / elapsed.as_secs_f64().max(0.001),
+            resources,
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            cache_hits: cache.hits,
+            cache_misses: cache.misses,
+            namespaces_scanned: self.namespaces_scanned.load(Ordering::Relaxed),
+            total_millis: elapsed.as_millis() as u64,
+            phases,
+        }
+    }
+}
+
+/// Wall time of one phase of the run
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub millis: u64,
+}
+
+/// API requests made for one resource
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceRequests {
+    pub resource: String,
+    pub requests: u64,
+}
+
+/// Run summary printed by `--stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    pub api_requests: u64,
+    /// API requests per second over the whole run
+    pub requests_per_second: f64,
+    /// Requests by resource, busiest first
+    pub resources: Vec<ResourceRequests>,
+    /// Response body bytes received from the API server
+    pub bytes_received: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub namespaces_scanned: u64,
+    pub total_millis: u64,
+    pub phases: Vec<PhaseTiming>,
+}
+
+/// Response body that adds every chunk read to the byte counter
+struct CountingBody {
+    inner: Box<DynBody>,
+    stats: Arc<RunStats>,
+}
+
+impl Body for CountingBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let poll = Pin::new(&mut self.inner).poll_data(cx);
+        if let Poll::Ready(Some(Ok(data))) = &poll {
+            self.stats
+                .bytes_received
+                .fetch_add(data.len() as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.inner).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_counting_body() {
+        let stats = Arc::new(RunStats::default());
+        let inner: Box<DynBody> = Box::new(
+            http_body::Full::new(Bytes::from_static(b"{\"items\":[]}"))
+                .map_err(|e| Box::new(e) as BoxError),
+        );
+        let body = CountingBody {
+            inner,
+            stats: stats.clone(),
+        };
+
+        let bytes = body.collect().await.unwrap().to_bytes();
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(stats.bytes_received.load(Ordering::Relaxed), 12);
+    }
+
+    #[test]
+    fn test_api_resource() {
+        assert_eq!(api_resource("/api/v1/namespaces/shop/pods"), "pods");
+        assert_eq!(
+            api_resource("/api/v1/namespaces/shop/pods/web-0/log"),
+            "pods/log"
+        );
+        assert_eq!(api_resource("/api/v1/namespaces/shop"), "namespaces");
+        assert_eq!(api_resource("/api/v1/nodes"), "nodes");
+        assert_eq!(
+            api_resource("/apis/apps/v1/namespaces/shop/deployments/web"),
+            "deployments.apps"
+        );
+        assert_eq!(
+            api_resource("/apis/apiextensions.k8s.io/v1/customresourcedefinitions"),
+            "customresourcedefinitions.apiextensions.k8s.io"
+        );
+        assert_eq!(api_resource("/api/v1/watch/namespaces/shop/pods"), "pods");
+        assert_eq!(api_resource("/apis/apps/v1"), "discovery");
+        assert_eq!(api_resource("/apis"), "discovery");
+        assert_eq!(api_resource("/version"), "version");
+    }
+
+    #[test]
+    fn test_summary() {
+        let stats = RunStats::for_command("pods");
+        stats.count_request("/api/v1/pods");
+        stats.count_request("/api/v1/namespaces/shop/pods");
+        stats.count_request("/apis/apps/v1/deployments");
+        stats.add_namespaces_scanned(3);
+        stats.record_phase("connect", Instant::now());
+
+        let cache = CacheStats {
+            hits: 2,
+            misses: 5,
+            ..Default::default()
+        };
+        let summary = stats.summary(&cache);
+        assert_eq!(summary.namespaces_scanned, 3);
+        assert_eq!(summary.cache_hits, 2);
+        assert_eq!(summary.cache_misses, 5);
+        assert_eq!(summary.phases[0].phase, "connect");
+        assert_eq!(summary.command.as_deref(), Some("pods"));
+        assert_eq!(summary.api_requests, 3);
+        let resources: Vec<(&str, u64)> = summary
+            .resources
+            .iter()
+            .map(|r| (r.resource.as_str(), r.requests))
+            .collect();
+        assert_eq!(resources, [("pods", 2), ("deployments.apps", 1)]);
+    }
+}
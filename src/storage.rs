@@ -0,0 +1,366 @@
+//! Persistent storage analysis
+
+use crate::discovery::{PersistentVolumeClaimInfo, PersistentVolumeInfo};
+use crate::quantity::parse_quantity;
+use k8s_openapi::api::core::v1::Pod;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Pods mounting each claim, keyed by claim namespace and name
+pub type ClaimMounts = BTreeMap<(String, String), Vec<String>>;
+
+/// One claim mounted by a pod, with the volume bound to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodStorage {
+    pub pod: String,
+    pub claim: String,
+    /// Claim phase, or "Missing" when the claim does not exist
+    pub phase: String,
+    pub volume: Option<String>,
+    pub storage_class: Option<String>,
+    pub capacity: Option<String>,
+}
+
+/// Why a persistent volume is considered dangling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DanglingReason {
+    /// Volume is in the Failed phase
+    Failed,
+    /// Volume's claimRef points at a claim that no longer exists
+    ClaimDeleted,
+    /// Claim was released but the volume was kept
+    Released,
+    /// Volume uses the Retain policy and has never been claimed
+    Unclaimed,
+}
+
+impl std::fmt::Display for DanglingReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            DanglingReason::Failed => "Failed",
+            DanglingReason::ClaimDeleted => "ClaimDeleted",
+            DanglingReason::Released => "Released",
+            DanglingReason::Unclaimed => "Unclaimed",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// A persistent volume with no live consumer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DanglingVolume {
+    pub volume: PersistentVolumeInfo,
+    pub reason: DanglingReason,
+}
+
+/// Orphaned capacity for one storage class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedCapacity {
+    pub storage_class: String,
+    pub volumes: usize,
+    pub bytes: f64,
+}
+
+/// Dangling volumes plus orphaned capacity totals per storage class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DanglingVolumeReport {
+    pub volumes: Vec<DanglingVolume>,
+    pub orphaned_capacity: Vec<OrphanedCapacity>,
+}
+
+/// Classify a volume, returning `None` when it is in use or simply available
+/// for dynamic reuse
+pub fn dangling_reason(
+    volume: &PersistentVolumeInfo,
+    claims: &HashSet<(String, String)>,
+) -> Option<DanglingReason> {
+    if volume.phase == "Failed" {
+        return Some(DanglingReason::Failed);
+    }
+
+    if let (Some(namespace), Some(name)) = (&volume.claim_namespace, &volume.claim_name) {
+        if !claims.contains(&(namespace.clone(), name.clone())) {
+            return Some(DanglingReason::ClaimDeleted);
+        }
+    }
+
+    if volume.phase == "Released" {
+        return Some(DanglingReason::Released);
+    }
+
+    if volume.claim_name.is_none() && volume.reclaim_policy == "Retain" {
+        return Some(DanglingReason::Unclaimed);
+    }
+
+    None
+}
+
+/// Find dangling volumes and total their capacity by storage class
+pub fn dangling_volumes(
+    volumes: Vec<PersistentVolumeInfo>,
+    claims: &[PersistentVolumeClaimInfo],
+) -> DanglingVolumeReport {
+    let claim_keys: HashSet<(String, String)> = claims
+        .iter()
+        .map(|c| (c.namespace.clone(), c.name.clone()))
+        .collect();
+
+    let mut dangling = Vec::new();
+    let mut capacity: BTreeMap<String, OrphanedCapacity> = BTreeMap::new();
+
+    for volume in volumes {
+        let Some(reason) = dangling_reason(&volume, &claim_keys) else {
+            continue;
+        };
+
+        let storage_class = volume
+            .storage_class
+            .clone()
+            .unwrap_or_else(|| "None".to_string());
+        let total = capacity
+            .entry(storage_class.clone())
+            .or_insert_with(|| OrphanedCapacity {
+                storage_class,
+                volumes: 0,
+                bytes: 0.0,
+            });
+        total.volumes += 1;
+        total.bytes += volume
+            .capacity
+            .as_deref()
+            .and_then(parse_quantity)
+            .unwrap_or(0.0);
+
+        dangling.push(DanglingVolume { volume, reason });
+    }
+
+    DanglingVolumeReport {
+        volumes: dangling,
+        orphaned_capacity: capacity.into_values().collect(),
+    }
+}
+
+/// Claims mounted by each pod. Generic ephemeral volumes count as well:
+/// their claim is named `<pod>-<volume>`.
+pub fn claim_mounts(pods: &[Pod]) -> ClaimMounts {
+    let mut mounts = ClaimMounts::new();
+    for pod in pods {
+        let (Some(namespace), Some(name)) = (&pod.metadata.namespace, &pod.metadata.name) else {
+            continue;
+        };
+        for claim in pod_claims(pod) {
+            mounts
+                .entry((namespace.clone(), claim))
+                .or_default()
+                .push(name.clone());
+        }
+    }
+    mounts
+}
+
+/// Claims a pod mounts, including the claims of its generic ephemeral
+/// volumes (named `<pod>-<volume>`)
+pub fn pod_claims(pod: &Pod) -> Vec<String> {
+    let name = pod.metadata.name.as_deref().unwrap_or_default();
+    pod.spec
+        .iter()
+        .flat_map(|s| s.volumes.iter().flatten())
+        .filter_map(|volume| {
+            if let Some(claim) = &volume.persistent_volume_claim {
+                Some(claim.claim_name.clone())
+            } else if volume.ephemeral.is_some() {
+                Some(format!("{}-{}", name, volume.name))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Record the pods mounting each claim, and through its claim each volume
+pub fn attach_mounts(
+    volumes: &mut [PersistentVolumeInfo],
+    claims: &mut [PersistentVolumeClaimInfo],
+    mounts: &ClaimMounts,
+) {
+    for claim in claims {
+        let key = (claim.namespace.clone(), claim.name.clone());
+        claim.mounted_by = mounts.get(&key).cloned().unwrap_or_default();
+    }
+    for volume in volumes {
+        if let (Some(namespace), Some(name)) = (&volume.claim_namespace, &volume.claim_name) {
+            let key = (namespace.clone(), name.clone());
+            volume.mounted_by = mounts.get(&key).cloned().unwrap_or_default();
+        }
+    }
+}
+
+/// Storage chain of each pod in `pods`: the claims it mounts and the volumes
+/// bound to them
+pub fn pod_storage(
+    pods: &[String],
+    namespace: &str,
+    mounts: &ClaimMounts,
+    claims: &[PersistentVolumeClaimInfo],
+    volumes: &[PersistentVolumeInfo],
+) -> Vec<PodStorage> {
+    let mut storage = Vec::new();
+    for ((claim_namespace, claim_name), mounted_by) in mounts {
+        if claim_namespace != namespace {
+            continue;
+        }
+        let claim = claims
+            .iter()
+            .find(|c| c.namespace == *claim_namespace && c.name == *claim_name);
+        let volume = claim
+            .and_then(|c| c.volume_name.as_deref())
+            .and_then(|name| volumes.iter().find(|v| v.name == name));
+
+        for pod in mounted_by.iter().filter(|p| pods.contains(p)) {
+            storage.push(PodStorage {
+                pod: pod.clone(),
+                claim: claim_name.clone(),
+                phase: claim.map_or_else(|| "Missing".to_string(), |c| c.phase.clone()),
+                volume: claim.and_then(|c| c.volume_name.clone()),
+                storage_class: claim.and_then(|c| c.storage_class.clone()),
+                capacity: volume
+                    .and_then(|v| v.capacity.clone())
+                    .or_else(|| claim.and_then(|c| c.capacity.clone())),
+            });
+        }
+    }
+    storage.sort_by(|a, b| (&a.pod, &a.claim).cmp(&(&b.pod, &b.claim)));
+    storage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(name: &str, phase: &str, policy: &str, claim: Option<&str>) -> PersistentVolumeInfo {
+        PersistentVolumeInfo {
+            name: name.to_string(),
+            capacity: Some("10Gi".to_string()),
+            access_modes: vec!["ReadWriteOnce".to_string()],
+            reclaim_policy: policy.to_string(),
+            phase: phase.to_string(),
+            storage_class: Some("gp3".to_string()),
+            claim_namespace: claim.map(|_| "default".to_string()),
+            claim_name: claim.map(|c| c.to_string()),
+            age: "30d".to_string(),
+            mounted_by: vec![],
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    fn claim(name: &str) -> PersistentVolumeClaimInfo {
+        PersistentVolumeClaimInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            phase: "Bound".to_string(),
+            volume_name: None,
+            capacity: Some("10Gi".to_string()),
+            requested: Some("10Gi".to_string()),
+            access_modes: vec![],
+            storage_class: Some("gp3".to_string()),
+            age: "30d".to_string(),
+            labels: BTreeMap::new(),
+            mounted_by: vec![],
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_dangling_reason() {
+        let claims: HashSet<(String, String)> =
+            [("default".to_string(), "data".to_string())].into();
+
+        let bound = volume("pv-1", "Bound", "Delete", Some("data"));
+        assert_eq!(dangling_reason(&bound, &claims), None);
+
+        let deleted = volume("pv-2", "Bound", "Retain", Some("gone"));
+        assert_eq!(
+            dangling_reason(&deleted, &claims),
+            Some(DanglingReason::ClaimDeleted)
+        );
+
+        let released = volume("pv-3", "Released", "Retain", Some("data"));
+        assert_eq!(
+            dangling_reason(&released, &claims),
+            Some(DanglingReason::Released)
+        );
+
+        let failed = volume("pv-4", "Failed", "Delete", None);
+        assert_eq!(
+            dangling_reason(&failed, &claims),
+            Some(DanglingReason::Failed)
+        );
+
+        let unclaimed = volume("pv-5", "Available", "Retain", None);
+        assert_eq!(
+            dangling_reason(&unclaimed, &claims),
+            Some(DanglingReason::Unclaimed)
+        );
+
+        let available = volume("pv-6", "Available", "Delete", None);
+        assert_eq!(dangling_reason(&available, &claims), None);
+    }
+
+    #[test]
+    fn test_orphaned_capacity_per_storage_class() {
+        let volumes = vec![
+            volume("pv-1", "Bound", "Delete", Some("data")),
+            volume("pv-2", "Released", "Retain", Some("old")),
+            volume("pv-3", "Failed", "Delete", None),
+        ];
+
+        let report = dangling_volumes(volumes, &[claim("data")]);
+        assert_eq!(report.volumes.len(), 2);
+        assert_eq!(report.orphaned_capacity.len(), 1);
+        assert_eq!(report.orphaned_capacity[0].volumes, 2);
+        assert_eq!(
+            report.orphaned_capacity[0].bytes,
+            20.0 * 1024.0 * 1024.0 * 1024.0
+        );
+    }
+
+    #[test]
+    fn test_claim_mounts_and_pod_storage() {
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "db-0", "namespace": "default"},
+            "spec": {
+                "containers": [{"name": "postgres"}],
+                "volumes": [
+                    {"name": "data", "persistentVolumeClaim": {"claimName": "data"}},
+                    {"name": "scratch", "ephemeral": {"volumeClaimTemplate": {"spec": {}}}},
+                    {"name": "config", "configMap": {"name": "pg-config"}},
+                ],
+            },
+        }))
+        .unwrap();
+        let mounts = claim_mounts(&[pod]);
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(
+            mounts[&("default".to_string(), "db-0-scratch".to_string())],
+            ["db-0"]
+        );
+
+        let mut volumes = vec![volume("pv-1", "Bound", "Delete", Some("data"))];
+        let mut claims = vec![PersistentVolumeClaimInfo {
+            volume_name: Some("pv-1".to_string()),
+            ..claim("data")
+        }];
+        attach_mounts(&mut volumes, &mut claims, &mounts);
+        assert_eq!(claims[0].mounted_by, ["db-0"]);
+        assert_eq!(volumes[0].mounted_by, ["db-0"]);
+
+        let storage = pod_storage(&["db-0".to_string()], "default", &mounts, &claims, &volumes);
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage[0].claim, "data");
+        assert_eq!(storage[0].volume.as_deref(), Some("pv-1"));
+        assert_eq!(storage[1].claim, "db-0-scratch");
+        assert_eq!(storage[1].phase, "Missing");
+    }
+}
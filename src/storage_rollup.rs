@@ -0,0 +1,321 @@
+//! Requested storage rolled up by namespace, storage class and workload
+//!
+//! Each claim's requested size is attributed to its namespace, its storage
+//! class and the workload whose pods mount it, and namespace and class totals
+//! are compared with the `requests.storage` and
+//! `<class>.storageclass.storage.k8s.io/requests.storage` hard limits of the
+//! namespace's ResourceQuotas. A claim mounted by pods of several workloads is
+//! counted once, as shared; a claim no pod mounts is counted as unmounted.
+
+use crate::discovery::{DiscoveryEngine, PersistentVolumeClaimInfo};
+use crate::error::Result;
+use crate::pod_index::IndexedPod;
+use crate::quantity::parse_quantity;
+use k8s_openapi::api::core::v1::ResourceQuota;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Workload of claims mounted by pods of more than one workload
+pub const SHARED: &str = "(shared)";
+/// Workload of claims no pod mounts
+pub const UNMOUNTED: &str = "(unmounted)";
+/// Storage class of claims that leave it to the cluster default
+pub const DEFAULT_CLASS: &str = "(default)";
+
+const QUOTA_KEY: &str = "requests.storage";
+const CLASS_QUOTA_SUFFIX: &str = ".storageclass.storage.k8s.io/requests.storage";
+
+/// Claims and requested bytes of one storage class or workload
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageShare {
+    pub name: String,
+    pub claims: usize,
+    pub requested_bytes: f64,
+    /// Lowest quota hard limit for this storage class, if any
+    pub quota_bytes: Option<f64>,
+}
+
+/// Requested storage of one namespace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamespaceStorage {
+    pub namespace: String,
+    pub claims: usize,
+    pub requested_bytes: f64,
+    /// Lowest `requests.storage` hard limit of the namespace's quotas, if any
+    pub quota_bytes: Option<f64>,
+    /// Per storage class, largest first
+    pub classes: Vec<StorageShare>,
+    /// Per owning workload (Kind/name), largest first
+    pub workloads: Vec<StorageShare>,
+}
+
+impl NamespaceStorage {
+    /// Requested storage as a percentage of the quota
+    pub fn quota_used(&self) -> Option<f64> {
+        quota_used(self.requested_bytes, self.quota_bytes)
+    }
+}
+
+impl StorageShare {
+    /// Requested storage as a percentage of the quota
+    pub fn quota_used(&self) -> Option<f64> {
+        quota_used(self.requested_bytes, self.quota_bytes)
+    }
+
+    fn add(&mut self, bytes: f64) {
+        self.claims += 1;
+        self.requested_bytes += bytes;
+    }
+}
+
+fn quota_used(requested: f64, quota: Option<f64>) -> Option<f64> {
+    quota.filter(|q| *q > 0.0).map(|q| requested / q * 100.0)
+}
+
+/// Lowest hard limit per quota key, keyed by namespace
+fn quota_limits(quotas: &[ResourceQuota]) -> HashMap<String, BTreeMap<String, f64>> {
+    let mut limits: HashMap<String, BTreeMap<String, f64>> = HashMap::new();
+    for quota in quotas {
+        let Some(namespace) = &quota.metadata.namespace else {
+            continue;
+        };
+        let hard = quota.spec.as_ref().and_then(|s| s.hard.as_ref());
+        for (key, value) in hard.into_iter().flatten() {
+            if key != QUOTA_KEY && !key.ends_with(CLASS_QUOTA_SUFFIX) {
+                continue;
+            }
+            let Some(bytes) = parse_quantity(&value.0) else {
+                continue;
+            };
+            let limit = limits
+                .entry(namespace.clone())
+                .or_default()
+                .entry(key.clone())
+                .or_insert(bytes);
+            *limit = limit.min(bytes);
+        }
+    }
+    limits
+}
+
+/// Roll up the requested storage of `claims` per namespace, attributing each
+/// claim to the workload of the `pods` mounting it
+pub fn storage_rollup(
+    claims: &[PersistentVolumeClaimInfo],
+    pods: &[IndexedPod],
+    quotas: &[ResourceQuota],
+) -> Vec<NamespaceStorage> {
+    let mut mounted_by: HashMap<(&str, &str), BTreeSet<String>> = HashMap::new();
+    for pod in pods {
+        let (kind, name) = &pod.workload;
+        for claim in &pod.claims {
+            mounted_by
+                .entry((pod.info.namespace.as_str(), claim.as_str()))
+                .or_default()
+                .insert(format!("{}/{}", kind, name));
+        }
+    }
+    let limits = quota_limits(quotas);
+
+    let mut namespaces: BTreeMap<&str, NamespaceStorage> = BTreeMap::new();
+    let mut classes: BTreeMap<(&str, &str), StorageShare> = BTreeMap::new();
+    let mut workloads: BTreeMap<(&str, String), StorageShare> = BTreeMap::new();
+    for claim in claims {
+        let bytes = claim
+            .requested
+            .as_deref()
+            .or(claim.capacity.as_deref())
+            .and_then(parse_quantity)
+            .unwrap_or_default();
+        let namespace = claim.namespace.as_str();
+        let class = claim.storage_class.as_deref().unwrap_or(DEFAULT_CLASS);
+        let workload = match mounted_by.get(&(namespace, claim.name.as_str())) {
+            Some(workloads) if workloads.len() == 1 => workloads.iter().next().unwrap().clone(),
+            Some(_) => SHARED.to_string(),
+            None => UNMOUNTED.to_string(),
+        };
+
+        let total = namespaces
+            .entry(namespace)
+            .or_insert_with(|| NamespaceStorage {
+                namespace: namespace.to_string(),
+                ..Default::default()
+            });
+        total.claims += 1;
+        total.requested_bytes += bytes;
+        classes
+            .entry((namespace, class))
+            .or_insert_with(|| StorageShare {
+                name: class.to_string(),
+                ..Default::default()
+            })
+            .add(bytes);
+        workloads
+            .entry((namespace, workload.clone()))
+            .or_insert_with(|| StorageShare {
+                name: workload,
+                ..Default::default()
+            })
+            .add(bytes);
+    }
+
+    let by_size = |a: &StorageShare, b: &StorageShare| {
+        b.requested_bytes
+            .total_cmp(&a.requested_bytes)
+            .then_with(|| a.name.cmp(&b.name))
+    };
+    for ((namespace, _), mut share) in classes {
+        let limits = limits.get(namespace);
+        share.quota_bytes = limits
+            .and_then(|l| l.get(&format!("{}{}", share.name, CLASS_QUOTA_SUFFIX)))
+            .copied();
+        namespaces.get_mut(namespace).unwrap().classes.push(share);
+    }
+    for ((namespace, _), share) in workloads {
+        namespaces.get_mut(namespace).unwrap().workloads.push(share);
+    }
+
+    namespaces
+        .into_values()
+        .map(|mut storage| {
+            storage.quota_bytes = limits
+                .get(&storage.namespace)
+                .and_then(|l| l.get(QUOTA_KEY))
+                .copied();
+            storage.classes.sort_by(by_size);
+            storage.workloads.sort_by(by_size);
+            storage
+        })
+        .collect()
+}
+
+/// Roll up the claims in `namespace` (all namespaces when `None`). Quotas are
+/// only compared when ResourceQuotas may be listed.
+pub async fn find_storage_rollup(
+    discovery: &DiscoveryEngine,
+    namespace: Option<&str>,
+) -> Result<Vec<NamespaceStorage>> {
+    let quotas: Api<ResourceQuota> = match namespace {
+        Some(ns) => Api::namespaced(discovery.client().clone(), ns),
+        None => Api::all(discovery.client().clone()),
+    };
+    let quotas = async {
+        match quotas.list(&ListParams::default()).await {
+            Ok(list) => Ok(list.items),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    };
+
+    let (claims, pods, quotas) = tokio::try_join!(
+        discovery.list_persistent_volume_claims(namespace),
+        discovery.pod_index(namespace),
+        quotas,
+    )?;
+    Ok(storage_rollup(&claims, pods.pods(), &quotas))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod_index::PodIndex;
+    use k8s_openapi::api::core::v1::Pod;
+
+    fn claim(
+        namespace: &str,
+        name: &str,
+        class: Option<&str>,
+        size: &str,
+    ) -> PersistentVolumeClaimInfo {
+        serde_json::from_value(serde_json::json!({
+            "name": name, "namespace": namespace, "phase": "Bound", "volume_name": null,
+            "capacity": null, "requested": size, "access_modes": [], "storage_class": class,
+            "age": "1d", "labels": {},
+        }))
+        .unwrap()
+    }
+
+    fn pod(namespace: &str, name: &str, owner: &str, claims: &[&str]) -> Pod {
+        let volumes: Vec<serde_json::Value> = claims
+            .iter()
+            .map(|c| serde_json::json!({"name": c, "persistentVolumeClaim": {"claimName": c}}))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": name,
+                "namespace": namespace,
+                "ownerReferences": [{
+                    "apiVersion": "apps/v1", "kind": "StatefulSet", "name": owner,
+                    "uid": "1", "controller": true,
+                }],
+            },
+            "spec": {"containers": [{"name": "app"}], "volumes": volumes},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_storage_rollup() {
+        let claims = [
+            claim("data", "db-0", Some("gp3"), "100Gi"),
+            claim("data", "db-1", Some("gp3"), "100Gi"),
+            claim("data", "shared", Some("efs"), "20Gi"),
+            claim("data", "old", None, "5Gi"),
+            claim("web", "cache", Some("gp3"), "1Gi"),
+        ];
+        let index = PodIndex::from_pods(&[
+            pod("data", "db-0", "db", &["db-0"]),
+            pod("data", "db-1", "db", &["db-1", "shared"]),
+            pod("data", "queue-0", "queue", &["shared"]),
+        ]);
+        let quotas: Vec<ResourceQuota> = vec![
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": "storage", "namespace": "data"},
+                "spec": {"hard": {
+                    "requests.storage": "500Gi",
+                    "gp3.storageclass.storage.k8s.io/requests.storage": "200Gi",
+                }},
+            }))
+            .unwrap(),
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": "team", "namespace": "data"},
+                "spec": {"hard": {"requests.storage": "250Gi", "pods": "10"}},
+            }))
+            .unwrap(),
+        ];
+
+        let rollup = storage_rollup(&claims, index.pods(), &quotas);
+        assert_eq!(rollup.len(), 2);
+        let data = &rollup[0];
+        assert_eq!(data.claims, 4);
+        assert_eq!(data.requested_bytes, 225.0 * 1024f64.powi(3));
+        assert_eq!(data.quota_bytes, Some(250.0 * 1024f64.powi(3)));
+        assert_eq!(data.quota_used(), Some(90.0));
+
+        let classes: Vec<(&str, usize, Option<f64>)> = data
+            .classes
+            .iter()
+            .map(|c| (c.name.as_str(), c.claims, c.quota_used()))
+            .collect();
+        assert_eq!(
+            classes,
+            [
+                ("gp3", 2, Some(100.0)),
+                ("efs", 1, None),
+                (DEFAULT_CLASS, 1, None)
+            ]
+        );
+        let workloads: Vec<(&str, usize)> = data
+            .workloads
+            .iter()
+            .map(|w| (w.name.as_str(), w.claims))
+            .collect();
+        assert_eq!(
+            workloads,
+            [("StatefulSet/db", 2), (SHARED, 1), (UNMOUNTED, 1)]
+        );
+        assert_eq!(rollup[1].quota_bytes, None);
+    }
+}
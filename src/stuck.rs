@@ -0,0 +1,144 @@
+//! Detection of objects stuck in deletion behind finalizers
+
+use crate::age::format_age;
+use crate::resource::ResourceKind;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Namespace, PersistentVolume, PersistentVolumeClaim, Secret,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::ApiResource;
+use kube::discovery::Scope;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// An object whose deletion has been pending longer than the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StuckObject {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub deletion_timestamp: DateTime<Utc>,
+    /// How long the object has been terminating (e.g. "3d")
+    pub terminating_for: String,
+    pub finalizers: Vec<String>,
+    /// Owner references as `Kind/name`, controller first
+    pub owners: Vec<String>,
+}
+
+/// Resources checked when not scanning every kind: workloads, pods, services,
+/// configuration, storage and namespaces
+pub fn default_resources() -> Vec<(ApiResource, Scope)> {
+    let mut resources: Vec<(ApiResource, Scope)> = ResourceKind::ALL
+        .iter()
+        .map(|k| (k.api_resource(), Scope::Namespaced))
+        .collect();
+    resources.extend([
+        (ApiResource::erase::<ConfigMap>(&()), Scope::Namespaced),
+        (ApiResource::erase::<Secret>(&()), Scope::Namespaced),
+        (
+            ApiResource::erase::<PersistentVolumeClaim>(&()),
+            Scope::Namespaced,
+        ),
+        (ApiResource::erase::<PersistentVolume>(&()), Scope::Cluster),
+        (ApiResource::erase::<Namespace>(&()), Scope::Cluster),
+    ]);
+    resources
+}
+
+/// Return the object if it is terminating for longer than `threshold` while
+/// finalizers are still set
+pub fn stuck_object(
+    kind: &str,
+    metadata: &ObjectMeta,
+    now: DateTime<Utc>,
+    threshold: Duration,
+) -> Option<StuckObject> {
+    let deleted_at = metadata.deletion_timestamp.as_ref()?.0;
+    let finalizers = metadata.finalizers.clone().unwrap_or_default();
+    if finalizers.is_empty() {
+        return None;
+    }
+
+    let pending = (now - deleted_at).to_std().ok()?;
+    if pending < threshold {
+        return None;
+    }
+
+    let mut owners: Vec<_> = metadata.owner_references.iter().flatten().collect();
+    owners.sort_by_key(|o| o.controller != Some(true));
+
+    Some(StuckObject {
+        kind: kind.to_string(),
+        namespace: metadata.namespace.clone(),
+        name: metadata.name.clone().unwrap_or_default(),
+        deletion_timestamp: deleted_at,
+        terminating_for: format_age(Some(deleted_at)),
+        finalizers,
+        owners: owners
+            .into_iter()
+            .map(|o| format!("{}/{}", o.kind, o.name))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{OwnerReference, Time};
+
+    fn metadata(deleted_minutes_ago: Option<i64>, finalizers: &[&str]) -> ObjectMeta {
+        ObjectMeta {
+            name: Some("data-db-0".to_string()),
+            namespace: Some("db".to_string()),
+            deletion_timestamp: deleted_minutes_ago
+                .map(|m| Time(Utc::now() - chrono::Duration::minutes(m))),
+            finalizers: Some(finalizers.iter().map(|f| f.to_string()).collect()),
+            owner_references: Some(vec![
+                OwnerReference {
+                    kind: "Backup".to_string(),
+                    name: "nightly".to_string(),
+                    ..Default::default()
+                },
+                OwnerReference {
+                    kind: "StatefulSet".to_string(),
+                    name: "db".to_string(),
+                    controller: Some(true),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stuck_object() {
+        let threshold = Duration::from_secs(600);
+        let now = Utc::now();
+
+        let stuck = stuck_object(
+            "PersistentVolumeClaim",
+            &metadata(Some(120), &["kubernetes.io/pvc-protection"]),
+            now,
+            threshold,
+        )
+        .unwrap();
+        assert_eq!(stuck.finalizers, vec!["kubernetes.io/pvc-protection"]);
+        assert_eq!(stuck.owners, vec!["StatefulSet/db", "Backup/nightly"]);
+        assert_eq!(stuck.terminating_for, "2h");
+    }
+
+    #[test]
+    fn test_not_stuck() {
+        let threshold = Duration::from_secs(600);
+        let now = Utc::now();
+        let finalizers = ["example.com/cleanup"];
+
+        // Not being deleted
+        assert!(stuck_object("Pod", &metadata(None, &finalizers), now, threshold).is_none());
+        // Deletion still within the threshold
+        assert!(stuck_object("Pod", &metadata(Some(2), &finalizers), now, threshold).is_none());
+        // No finalizers left
+        assert!(stuck_object("Pod", &metadata(Some(120), &[]), now, threshold).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+//! Line templates rendered against serialized resources
+//!
+//! A template is literal text with `{path}` placeholders, where `path` is a
+//! dotted field path into the JSON form of a resource (`{name}`,
+//! `{labels.app}`, `{ports.0.port}`). `{{` and `}}` produce literal braces.
+//! Missing fields render as `<none>`.
+
+use crate::error::{ExplorerError, Result};
+use serde_json::Value;
+
+/// Template behind `-o name`
+pub const NAME: &str = "{name}";
+/// Template behind `-o ns/name`
+pub const NAMESPACED_NAME: &str = "{namespace}/{name}";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            ExplorerError::InvalidArgument(format!("template {:?}: {}", source, reason))
+        };
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut path = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => path.push(c),
+                            None => return Err(invalid("unclosed '{'")),
+                        }
+                    }
+                    let path = path.trim();
+                    if path.is_empty() {
+                        return Err(invalid("empty placeholder"));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(path.split('.').map(str::to_string).collect()));
+                }
+                '}' => return Err(invalid("unmatched '}'")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Render the template for one resource
+    pub fn render(&self, item: &Value) -> String {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => rendered.push_str(text),
+                Part::Field(path) => match lookup(item, path) {
+                    Some(Value::String(s)) => rendered.push_str(s),
+                    Some(Value::Null) | None => rendered.push_str("<none>"),
+                    Some(value) => rendered.push_str(&value.to_string()),
+                },
+            }
+        }
+        rendered
+    }
+
+    /// Whether every field the template references is present in `item`
+    pub fn matches(&self, item: &Value) -> bool {
+        self.parts.iter().all(|part| match part {
+            Part::Literal(_) => true,
+            Part::Field(path) => !matches!(lookup(item, path), None | Some(Value::Null)),
+        })
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match value {
+        Value::Object(map) => map.get(segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_fields() {
+        let item = json!({
+            "name": "web",
+            "namespace": "shop",
+            "labels": {"app": "web"},
+            "ports": [{"port": 80}],
+        });
+
+        let template =
+            Template::parse("{namespace}/{name} app={labels.app} port={ports.0.port}").unwrap();
+        assert_eq!(template.render(&item), "shop/web app=web port=80");
+
+        let missing = Template::parse("{name} {spec.replicas}").unwrap();
+        assert_eq!(missing.render(&item), "web <none>");
+        assert!(!missing.matches(&item));
+
+        let braces = Template::parse("{{{name}}}").unwrap();
+        assert_eq!(braces.render(&item), "{web}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{name").is_err());
+        assert!(Template::parse("{}").is_err());
+        assert!(Template::parse("name}").is_err());
+    }
+}
@@ -0,0 +1,432 @@
+//! Everything one tenant owns, gathered by label (`kdx tenant-report`)
+//!
+//! A tenant is whatever carries its label, e.g. `team=payments`, in any
+//! namespace and of any listable kind. Besides the labeled objects, the
+//! bundle lists the ConfigMaps and Secrets their pod specs reference (which
+//! need not carry the label themselves) and the Ingress hosts that expose
+//! them, so the owning team can review the whole footprint in one file.
+
+use crate::discovery::{DiscoveryEngine, DiscoveryFailure};
+use crate::error::Result;
+use crate::ingress::ingress_routes;
+use crate::references::{pod_config_references, ConfigKind};
+use crate::report::{render_html, render_markdown, ReportTable};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{Pod, PodSpec};
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::ListParams;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An object carrying the tenant label
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TenantResource {
+    pub kind: String,
+    /// None for cluster-scoped objects
+    pub namespace: Option<String>,
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A ConfigMap or Secret used by the tenant's pods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantConfigReference {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// Tenant workloads referencing it, as `Kind/name`
+    pub used_by: Vec<String>,
+    /// Whether the object itself carries the tenant label
+    pub labeled: bool,
+}
+
+/// An Ingress that is labeled for the tenant or routes to one of its Services
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantIngress {
+    pub namespace: String,
+    pub name: String,
+    pub hosts: Vec<String>,
+    /// Hosts covered by a `tls` entry
+    pub tls_hosts: Vec<String>,
+    /// Backend services, as `service:port`
+    pub backends: Vec<String>,
+    /// Whether the Ingress itself carries the tenant label
+    pub labeled: bool,
+}
+
+/// The bundle handed to a team
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantReport {
+    pub selector: String,
+    pub generated_at: DateTime<Utc>,
+    pub namespaces: Vec<String>,
+    pub resources: Vec<TenantResource>,
+    pub config_references: Vec<TenantConfigReference>,
+    pub ingresses: Vec<TenantIngress>,
+    /// API groups that could not be listed, so the bundle may be incomplete
+    pub skipped: Vec<DiscoveryFailure>,
+}
+
+/// A pod template of a labeled workload, as kind, namespace, name and spec
+pub type PodTemplate = (String, String, String, PodSpec);
+
+/// ConfigMaps and Secrets referenced by `templates`, marking those that are
+/// themselves among the tenant's `resources`
+pub fn config_references(
+    templates: &[PodTemplate],
+    resources: &[TenantResource],
+) -> Vec<TenantConfigReference> {
+    let labeled: BTreeSet<(&str, &str, &str)> = resources
+        .iter()
+        .filter_map(|r| Some((r.kind.as_str(), r.namespace.as_deref()?, r.name.as_str())))
+        .collect();
+
+    let mut used_by: BTreeMap<(&str, &str, String), BTreeSet<String>> = BTreeMap::new();
+    for (kind, namespace, name, spec) in templates {
+        for reference in pod_config_references(spec) {
+            let config_kind = match reference.kind {
+                ConfigKind::ConfigMap => "ConfigMap",
+                ConfigKind::Secret => "Secret",
+            };
+            used_by
+                .entry((config_kind, namespace.as_str(), reference.name))
+                .or_default()
+                .insert(format!("{}/{}", kind, name));
+        }
+    }
+
+    used_by
+        .into_iter()
+        .map(|((kind, namespace, name), users)| TenantConfigReference {
+            labeled: labeled.contains(&(kind, namespace, name.as_str())),
+            kind: kind.to_string(),
+            namespace: namespace.to_string(),
+            name,
+            used_by: users.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// Ingresses among `ingresses` that are labeled for the tenant or have a
+/// backend Service in `resources`
+pub fn tenant_ingresses(ingresses: &[Ingress], resources: &[TenantResource]) -> Vec<TenantIngress> {
+    let labeled = |kind: &str, namespace: &str, name: &str| {
+        resources
+            .iter()
+            .any(|r| r.kind == kind && r.namespace.as_deref() == Some(namespace) && r.name == name)
+    };
+
+    let mut tenant = Vec::new();
+    for ingress in ingresses {
+        let namespace = ingress.metadata.namespace.clone().unwrap_or_default();
+        let name = ingress.metadata.name.clone().unwrap_or_default();
+        let routes = ingress_routes(ingress);
+
+        let is_labeled = labeled("Ingress", &namespace, &name);
+        let routes_to_tenant = routes.iter().any(|route| {
+            let service = route.backend.split(':').next().unwrap_or_default();
+            labeled("Service", &namespace, service)
+        });
+        if !is_labeled && !routes_to_tenant {
+            continue;
+        }
+
+        let hosts: BTreeSet<String> = routes.iter().map(|r| r.host.clone()).collect();
+        let backends: BTreeSet<String> = routes.into_iter().map(|r| r.backend).collect();
+        let tls_hosts: BTreeSet<String> = ingress
+            .spec
+            .iter()
+            .flat_map(|spec| spec.tls.iter().flatten())
+            .flat_map(|tls| tls.hosts.iter().flatten())
+            .map(|host| host.to_lowercase())
+            .collect();
+
+        tenant.push(TenantIngress {
+            namespace,
+            name,
+            hosts: hosts.into_iter().collect(),
+            tls_hosts: tls_hosts.into_iter().collect(),
+            backends: backends.into_iter().collect(),
+            labeled: is_labeled,
+        });
+    }
+    tenant
+}
+
+/// Gather everything matching `selector` across all namespaces
+pub async fn fetch_tenant_report(
+    discovery: &DiscoveryEngine,
+    selector: &str,
+) -> Result<TenantReport> {
+    let params = ListParams::default().labels(selector);
+    let discovered = discovery.discover_listable_resources().await?;
+
+    let mut resources: Vec<TenantResource> = discovery
+        .list_object_metadata_with(None, &discovered.resources, &params)
+        .await?
+        .into_iter()
+        .map(|(kind, metadata)| TenantResource {
+            kind,
+            namespace: metadata.namespace,
+            name: metadata.name.unwrap_or_default(),
+            created_at: metadata.creation_timestamp.map(|t| t.0),
+        })
+        .collect();
+    resources.sort();
+    resources.dedup();
+
+    let client = discovery.client().clone();
+    let mut templates: Vec<PodTemplate> = Vec::new();
+    let mut add = |kind: &str, metadata: kube::api::ObjectMeta, spec: Option<PodSpec>| {
+        if let Some(spec) = spec {
+            templates.push((
+                kind.to_string(),
+                metadata.namespace.unwrap_or_default(),
+                metadata.name.unwrap_or_default(),
+                spec,
+            ));
+        }
+    };
+
+    for d in Api::<Deployment>::all(client.clone()).list(&params).await? {
+        add(
+            "Deployment",
+            d.metadata,
+            d.spec.and_then(|s| s.template.spec),
+        );
+    }
+    for s in Api::<StatefulSet>::all(client.clone())
+        .list(&params)
+        .await?
+    {
+        add(
+            "StatefulSet",
+            s.metadata,
+            s.spec.and_then(|s| s.template.spec),
+        );
+    }
+    for d in Api::<DaemonSet>::all(client.clone()).list(&params).await? {
+        add(
+            "DaemonSet",
+            d.metadata,
+            d.spec.and_then(|s| s.template.spec),
+        );
+    }
+    for c in Api::<CronJob>::all(client.clone()).list(&params).await? {
+        let spec = c
+            .spec
+            .and_then(|s| s.job_template.spec)
+            .and_then(|s| s.template.spec);
+        add("CronJob", c.metadata, spec);
+    }
+    // Pods and Jobs created by a controller are covered by their owner
+    for j in Api::<Job>::all(client.clone()).list(&params).await? {
+        if !is_controlled(&j.metadata) {
+            add("Job", j.metadata, j.spec.and_then(|s| s.template.spec));
+        }
+    }
+    for p in Api::<Pod>::all(client.clone()).list(&params).await? {
+        if !is_controlled(&p.metadata) {
+            add("Pod", p.metadata, p.spec);
+        }
+    }
+
+    let ingresses = Api::<Ingress>::all(client)
+        .list(&ListParams::default())
+        .await?
+        .items;
+
+    let namespaces: BTreeSet<String> = resources
+        .iter()
+        .filter_map(|r| r.namespace.clone())
+        .collect();
+
+    Ok(TenantReport {
+        selector: selector.to_string(),
+        generated_at: Utc::now(),
+        namespaces: namespaces.into_iter().collect(),
+        config_references: config_references(&templates, &resources),
+        ingresses: tenant_ingresses(&ingresses, &resources),
+        resources,
+        skipped: discovered.failures,
+    })
+}
+
+fn is_controlled(metadata: &kube::api::ObjectMeta) -> bool {
+    metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .any(|owner| owner.controller == Some(true))
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn tables(report: &TenantReport) -> Vec<ReportTable> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for resource in &report.resources {
+        *counts.entry(resource.kind.as_str()).or_default() += 1;
+    }
+
+    vec![
+        ReportTable {
+            title: "Summary",
+            headers: vec!["Kind", "Count"],
+            rows: counts
+                .into_iter()
+                .map(|(kind, count)| vec![kind.to_string(), count.to_string()])
+                .collect(),
+        },
+        ReportTable {
+            title: "Resources",
+            headers: vec!["Kind", "Namespace", "Name", "Age"],
+            rows: report
+                .resources
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.kind.clone(),
+                        r.namespace.clone().unwrap_or_else(|| "-".to_string()),
+                        r.name.clone(),
+                        crate::age::format_age(r.created_at),
+                    ]
+                })
+                .collect(),
+        },
+        ReportTable {
+            title: "Config and Secret References",
+            headers: vec!["Kind", "Namespace", "Name", "Used By", "Labeled"],
+            rows: report
+                .config_references
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.kind.clone(),
+                        c.namespace.clone(),
+                        c.name.clone(),
+                        c.used_by.join(", "),
+                        yes_no(c.labeled),
+                    ]
+                })
+                .collect(),
+        },
+        ReportTable {
+            title: "Ingress Hosts",
+            headers: vec![
+                "Namespace",
+                "Ingress",
+                "Hosts",
+                "TLS",
+                "Backends",
+                "Labeled",
+            ],
+            rows: report
+                .ingresses
+                .iter()
+                .map(|i| {
+                    vec![
+                        i.namespace.clone(),
+                        i.name.clone(),
+                        i.hosts.join(", "),
+                        i.tls_hosts.join(", "),
+                        i.backends.join(", "),
+                        yes_no(i.labeled),
+                    ]
+                })
+                .collect(),
+        },
+    ]
+}
+
+fn summary(report: &TenantReport) -> String {
+    format!(
+        "Generated {} for objects labeled {} in {} namespace(s).",
+        report.generated_at.format("%Y-%m-%d %H:%M UTC"),
+        report.selector,
+        report.namespaces.len()
+    )
+}
+
+/// Render the bundle as GitHub-flavoured Markdown
+pub fn to_markdown(report: &TenantReport) -> String {
+    render_markdown("Tenant Report", &summary(report), tables(report))
+}
+
+/// Render the bundle as a standalone HTML page
+pub fn to_html(report: &TenantReport) -> String {
+    render_html("Tenant Report", &summary(report), tables(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(kind: &str, namespace: &str, name: &str) -> TenantResource {
+        TenantResource {
+            kind: kind.to_string(),
+            namespace: Some(namespace.to_string()),
+            name: name.to_string(),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_config_references_and_ingresses() {
+        let spec: PodSpec = serde_json::from_value(serde_json::json!({
+            "containers": [{
+                "name": "api",
+                "envFrom": [{"configMapRef": {"name": "api-config"}}],
+                "env": [{"name": "PW", "valueFrom": {"secretKeyRef": {"name": "shared-db", "key": "pw"}}}],
+            }],
+        }))
+        .unwrap();
+        let resources = vec![
+            resource("ConfigMap", "pay", "api-config"),
+            resource("Deployment", "pay", "api"),
+            resource("Service", "pay", "api"),
+        ];
+        let templates = vec![(
+            "Deployment".to_string(),
+            "pay".to_string(),
+            "api".to_string(),
+            spec,
+        )];
+
+        let references = config_references(&templates, &resources);
+        assert_eq!(references.len(), 2);
+        assert_eq!(references[0].name, "api-config");
+        assert!(references[0].labeled);
+        assert_eq!(references[1].kind, "Secret");
+        assert!(!references[1].labeled);
+        assert_eq!(references[1].used_by, vec!["Deployment/api"]);
+
+        let ingress = |name: &str, service: &str| -> Ingress {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"name": name, "namespace": "pay"},
+                "spec": {
+                    "tls": [{"hosts": ["Pay.example.com"]}],
+                    "rules": [{"host": "pay.example.com", "http": {"paths": [{
+                        "path": "/",
+                        "pathType": "Prefix",
+                        "backend": {"service": {"name": service, "port": {"number": 80}}},
+                    }]}}],
+                },
+            }))
+            .unwrap()
+        };
+        let ingresses = tenant_ingresses(
+            &[ingress("pay", "api"), ingress("other", "web")],
+            &resources,
+        );
+        assert_eq!(ingresses.len(), 1);
+        assert_eq!(ingresses[0].hosts, vec!["pay.example.com"]);
+        assert_eq!(ingresses[0].tls_hosts, vec!["pay.example.com"]);
+        assert_eq!(ingresses[0].backends, vec!["api:80"]);
+        assert!(!ingresses[0].labeled);
+    }
+}
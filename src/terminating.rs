@@ -0,0 +1,249 @@
+//! Diagnosis of namespaces stuck in the Terminating phase
+
+use crate::age::format_age;
+use crate::discovery::{DiscoveryEngine, DiscoveryFailure};
+use crate::error::{ExplorerError, Result};
+use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::kube_aggregator::pkg::apis::apiregistration::v1::APIService;
+use kube::api::DynamicObject;
+use kube::discovery::Scope;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+
+/// A namespace status condition reported by the namespace controller
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceCondition {
+    pub condition_type: String,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+}
+
+/// An aggregated API service whose backend is not available
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnavailableApiService {
+    pub name: String,
+    pub service: Option<String>,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+}
+
+/// An object still present in the namespace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemainingObject {
+    pub kind: String,
+    pub api_version: String,
+    pub name: String,
+    pub finalizers: Vec<String>,
+    /// Whether deletion of the object itself has been requested
+    pub terminating: bool,
+}
+
+/// Everything known about why a namespace is not going away
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceDiagnosis {
+    pub name: String,
+    pub phase: String,
+    pub terminating_for: Option<String>,
+    /// Finalizers in the namespace spec (normally just "kubernetes")
+    pub finalizers: Vec<String>,
+    pub conditions: Vec<NamespaceCondition>,
+    pub unavailable_api_services: Vec<UnavailableApiService>,
+    /// API groups that could not be discovered, or resources that could not be listed
+    pub discovery_failures: Vec<DiscoveryFailure>,
+    pub remaining: Vec<RemainingObject>,
+}
+
+/// Inspect a namespace, the API services its deletion depends on, and every
+/// object still left in it
+pub async fn diagnose_namespace(
+    discovery: &DiscoveryEngine,
+    namespace: &str,
+) -> Result<NamespaceDiagnosis> {
+    let client = discovery.client().clone();
+
+    let namespaces: Api<Namespace> = Api::all(client.clone());
+    let ns =
+        namespaces
+            .get_opt(namespace)
+            .await?
+            .ok_or_else(|| ExplorerError::ResourceNotFound {
+                kind: "Namespace".to_string(),
+                name: namespace.to_string(),
+                namespace: "cluster".to_string(),
+            })?;
+
+    // The aggregation layer may not be installed or readable; treat that as no services
+    let api_services: Api<APIService> = Api::all(client.clone());
+    let api_services = api_services
+        .list(&Default::default())
+        .await
+        .map(|list| list.items)
+        .unwrap_or_default();
+
+    let discovered = discovery.discover_listable_resources().await?;
+    let mut discovery_failures = discovered.failures;
+    let mut remaining = Vec::new();
+
+    for (resource, scope) in &discovered.resources {
+        if !matches!(scope, Scope::Namespaced) || is_derived(&resource.kind) {
+            continue;
+        }
+
+        let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, resource);
+        match api.list_metadata(&Default::default()).await {
+            Ok(list) => remaining.extend(list.items.iter().map(|item| {
+                remaining_object(&resource.kind, &resource.api_version, &item.metadata)
+            })),
+            Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => {}
+            Err(e) => discovery_failures.push(DiscoveryFailure {
+                group_version: format!("{} ({})", resource.api_version, resource.plural),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    let status = ns.status.unwrap_or_default();
+    Ok(NamespaceDiagnosis {
+        name: namespace.to_string(),
+        phase: status.phase.unwrap_or_else(|| "Unknown".to_string()),
+        terminating_for: ns
+            .metadata
+            .deletion_timestamp
+            .map(|t| format_age(Some(t.0))),
+        finalizers: ns.spec.and_then(|s| s.finalizers).unwrap_or_default(),
+        conditions: status
+            .conditions
+            .iter()
+            .flatten()
+            .filter(|c| c.status == "True")
+            .map(|c| NamespaceCondition {
+                condition_type: c.type_.clone(),
+                reason: c.reason.clone(),
+                message: c.message.clone(),
+            })
+            .collect(),
+        unavailable_api_services: unavailable_api_services(&api_services),
+        discovery_failures,
+        remaining,
+    })
+}
+
+/// Kinds that mirror other objects and disappear with them, so they add only noise
+fn is_derived(kind: &str) -> bool {
+    matches!(kind, "Event" | "EndpointSlice" | "Endpoints" | "PodMetrics")
+}
+
+fn remaining_object(kind: &str, api_version: &str, metadata: &ObjectMeta) -> RemainingObject {
+    RemainingObject {
+        kind: kind.to_string(),
+        api_version: api_version.to_string(),
+        name: metadata.name.clone().unwrap_or_default(),
+        finalizers: metadata.finalizers.clone().unwrap_or_default(),
+        terminating: metadata.deletion_timestamp.is_some(),
+    }
+}
+
+/// API services whose Available condition is not True
+pub fn unavailable_api_services(services: &[APIService]) -> Vec<UnavailableApiService> {
+    services
+        .iter()
+        .filter_map(|service| {
+            let available = service
+                .status
+                .as_ref()
+                .and_then(|s| s.conditions.as_ref())
+                .and_then(|c| c.iter().find(|c| c.type_ == "Available"));
+            if available.is_some_and(|c| c.status == "True") {
+                return None;
+            }
+
+            let backend = service
+                .spec
+                .as_ref()
+                .and_then(|s| s.service.as_ref())
+                .map(|s| {
+                    format!(
+                        "{}/{}",
+                        s.namespace.clone().unwrap_or_default(),
+                        s.name.clone().unwrap_or_default()
+                    )
+                });
+
+            Some(UnavailableApiService {
+                name: service.metadata.name.clone().unwrap_or_default(),
+                service: backend,
+                reason: available.and_then(|c| c.reason.clone()),
+                message: available.and_then(|c| c.message.clone()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::kube_aggregator::pkg::apis::apiregistration::v1::{
+        APIServiceCondition, APIServiceSpec, APIServiceStatus, ServiceReference,
+    };
+
+    fn api_service(name: &str, available: &str) -> APIService {
+        APIService {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            spec: Some(APIServiceSpec {
+                service: Some(ServiceReference {
+                    name: Some("metrics-server".to_string()),
+                    namespace: Some("kube-system".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            status: Some(APIServiceStatus {
+                conditions: Some(vec![APIServiceCondition {
+                    type_: "Available".to_string(),
+                    status: available.to_string(),
+                    reason: Some("FailedDiscoveryCheck".to_string()),
+                    ..Default::default()
+                }]),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_unavailable_api_services() {
+        let services = vec![
+            api_service("v1.apps", "True"),
+            api_service("v1beta1.metrics.k8s.io", "False"),
+        ];
+
+        let unavailable = unavailable_api_services(&services);
+        assert_eq!(unavailable.len(), 1);
+        assert_eq!(unavailable[0].name, "v1beta1.metrics.k8s.io");
+        assert_eq!(
+            unavailable[0].service.as_deref(),
+            Some("kube-system/metrics-server")
+        );
+        assert_eq!(
+            unavailable[0].reason.as_deref(),
+            Some("FailedDiscoveryCheck")
+        );
+    }
+
+    #[test]
+    fn test_remaining_object() {
+        let metadata = ObjectMeta {
+            name: Some("orders".to_string()),
+            finalizers: Some(vec!["example.com/backup".to_string()]),
+            ..Default::default()
+        };
+
+        let object = remaining_object("Database", "db.example.com/v1", &metadata);
+        assert_eq!(object.finalizers, vec!["example.com/backup"]);
+        assert!(!object.terminating);
+        assert!(is_derived("Event"));
+        assert!(!is_derived("Database"));
+    }
+}
@@ -0,0 +1,406 @@
+//! Chronological startup and restart timeline of a pod
+//!
+//! Events recorded for the pod (scheduling, image pulls, container starts,
+//! probe failures, back-offs) are merged with what the pod status still
+//! remembers: its creation, condition transitions and the last termination
+//! of each container. Events expire after an hour by default, so for older
+//! pods the timeline falls back to the status alone.
+
+use crate::age::format_duration;
+use crate::error::{ExplorerError, Result};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::api::ListParams;
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Lifecycle phase an entry belongs to, in the order a pod goes through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Phase {
+    Created,
+    Scheduling,
+    Pulling,
+    Starting,
+    Ready,
+    Restart,
+    Warning,
+    Other,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Phase::Created => "created",
+            Phase::Scheduling => "scheduling",
+            Phase::Pulling => "pulling",
+            Phase::Starting => "starting",
+            Phase::Ready => "ready",
+            Phase::Restart => "restart",
+            Phase::Warning => "warning",
+            Phase::Other => "other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One point in the life of a pod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub time: DateTime<Utc>,
+    pub phase: Phase,
+    pub reason: String,
+    pub container: Option<String>,
+    pub message: String,
+    /// Seconds since the previous entry
+    pub since_previous: Option<i64>,
+}
+
+/// Time spent between two milestones, e.g. created to scheduled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseDuration {
+    pub phase: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodTimeline {
+    pub pod: String,
+    pub namespace: String,
+    pub entries: Vec<TimelineEntry>,
+    pub durations: Vec<PhaseDuration>,
+}
+
+/// Fetch a pod and its events and build its timeline
+pub async fn pod_timeline(client: &Client, name: &str, namespace: &str) -> Result<PodTimeline> {
+    let pod = Api::<Pod>::namespaced(client.clone(), namespace)
+        .get_opt(name)
+        .await?
+        .ok_or_else(|| ExplorerError::ResourceNotFound {
+            kind: "Pod".to_string(),
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+        })?;
+
+    let selector = format!("involvedObject.kind=Pod,involvedObject.name={}", name);
+    let events = Api::<Event>::namespaced(client.clone(), namespace)
+        .list(&ListParams::default().fields(&selector))
+        .await?
+        .items;
+
+    Ok(build_timeline(&pod, &events))
+}
+
+/// Merge the status of `pod` with its events into a sorted timeline
+pub fn build_timeline(pod: &Pod, events: &[Event]) -> PodTimeline {
+    let mut entries = Vec::new();
+    let entry =
+        |time, phase, reason: &str, container: Option<String>, message: String| TimelineEntry {
+            time,
+            phase,
+            reason: reason.to_string(),
+            container,
+            message,
+            since_previous: None,
+        };
+
+    if let Some(created) = &pod.metadata.creation_timestamp {
+        entries.push(entry(
+            created.0,
+            Phase::Created,
+            "Created",
+            None,
+            "Pod object created".to_string(),
+        ));
+    }
+
+    // Events left behind by an earlier pod with the same name are skipped
+    let uid = pod.metadata.uid.as_deref();
+    let events: Vec<&Event> = events
+        .iter()
+        .filter(|e| uid.is_none() || e.involved_object.uid.as_deref() == uid)
+        .collect();
+    for event in &events {
+        let Some(time) = event_time(event) else {
+            continue;
+        };
+        let reason = event.reason.as_deref().unwrap_or("Unknown");
+        let mut message = event.message.clone().unwrap_or_default();
+        let count = event.count.unwrap_or(1);
+        if count > 1 {
+            let last = event.last_timestamp.as_ref().map_or(time, |t| t.0);
+            message = format!(
+                "{} (x{} over {})",
+                message,
+                count,
+                format_duration((last - time).num_seconds())
+            );
+        }
+        entries.push(entry(
+            time,
+            event_phase(event),
+            reason,
+            event_container(event),
+            message,
+        ));
+    }
+
+    let status = pod.status.as_ref();
+    let has_event = |reason: &str| events.iter().any(|e| e.reason.as_deref() == Some(reason));
+    for condition in status
+        .and_then(|s| s.conditions.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        let Some(time) = condition.last_transition_time.as_ref().map(|t| t.0) else {
+            continue;
+        };
+        let phase = match condition.type_.as_str() {
+            "PodScheduled" if has_event("Scheduled") => continue,
+            "PodScheduled" => Phase::Scheduling,
+            "Initialized" => Phase::Starting,
+            "ContainersReady" | "Ready" => Phase::Ready,
+            _ => Phase::Other,
+        };
+        let message = match &condition.reason {
+            Some(reason) => format!("{}={} ({})", condition.type_, condition.status, reason),
+            None => format!("{}={}", condition.type_, condition.status),
+        };
+        entries.push(entry(time, phase, &condition.type_, None, message));
+    }
+
+    // The previous instance of each restarted container
+    let statuses = status
+        .into_iter()
+        .flat_map(|s| {
+            s.init_container_statuses
+                .iter()
+                .chain(&s.container_statuses)
+        })
+        .flatten();
+    for container in statuses {
+        let Some(terminated) = container
+            .last_state
+            .as_ref()
+            .and_then(|s| s.terminated.as_ref())
+        else {
+            continue;
+        };
+        let Some(finished) = terminated.finished_at.as_ref().map(|t| t.0) else {
+            continue;
+        };
+        let reason = terminated.reason.as_deref().unwrap_or("Terminated");
+        entries.push(entry(
+            finished,
+            Phase::Restart,
+            "Terminated",
+            Some(container.name.clone()),
+            format!(
+                "{}, exit {} (restart count {})",
+                reason, terminated.exit_code, container.restart_count
+            ),
+        ));
+    }
+
+    entries.sort_by_key(|e| (e.time, e.phase));
+    let mut previous = None;
+    for entry in &mut entries {
+        entry.since_previous = previous.map(|p: DateTime<Utc>| (entry.time - p).num_seconds());
+        previous = Some(entry.time);
+    }
+
+    PodTimeline {
+        pod: pod.metadata.name.clone().unwrap_or_default(),
+        namespace: pod.metadata.namespace.clone().unwrap_or_default(),
+        durations: durations(pod, &entries),
+        entries,
+    }
+}
+
+/// Time between the startup milestones that are known
+fn durations(pod: &Pod, entries: &[TimelineEntry]) -> Vec<PhaseDuration> {
+    let first = |reason: &str| entries.iter().find(|e| e.reason == reason).map(|e| e.time);
+    let last = |reason: &str| {
+        entries
+            .iter()
+            .rev()
+            .find(|e| e.reason == reason)
+            .map(|e| e.time)
+    };
+    let condition_true = |kind: &str| {
+        pod.status
+            .as_ref()?
+            .conditions
+            .as_ref()?
+            .iter()
+            .find(|c| c.type_ == kind && c.status == "True")?
+            .last_transition_time
+            .as_ref()
+            .map(|t| t.0)
+    };
+
+    let created = pod.metadata.creation_timestamp.as_ref().map(|t| t.0);
+    let scheduled = first("Scheduled").or_else(|| condition_true("PodScheduled"));
+    let started = first("Started");
+    let ready = condition_true("Ready");
+
+    [
+        ("Scheduling", created, scheduled),
+        ("Image pull", first("Pulling"), last("Pulled")),
+        ("Container start", scheduled, started),
+        ("Readiness", started, ready),
+        ("Total to ready", created, ready),
+    ]
+    .into_iter()
+    .filter_map(|(phase, from, to)| {
+        let seconds = (to? - from?).num_seconds();
+        (seconds >= 0).then(|| PhaseDuration {
+            phase: phase.to_string(),
+            seconds,
+        })
+    })
+    .collect()
+}
+
+/// When an event first happened
+fn event_time(event: &Event) -> Option<DateTime<Utc>> {
+    event
+        .first_timestamp
+        .as_ref()
+        .map(|t| t.0)
+        .or_else(|| event.event_time.as_ref().map(|t| t.0))
+        .or_else(|| event.metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+fn event_phase(event: &Event) -> Phase {
+    let message = event.message.as_deref().unwrap_or_default();
+    match event.reason.as_deref().unwrap_or_default() {
+        "Scheduled" | "FailedScheduling" | "Preempted" => Phase::Scheduling,
+        "Pulling" | "Pulled" | "ErrImagePull" | "ImagePullBackOff" => Phase::Pulling,
+        "BackOff" | "Failed" if message.contains("image") => Phase::Pulling,
+        "Created" | "Started" => Phase::Starting,
+        "Killing" | "BackOff" => Phase::Restart,
+        _ if event.type_.as_deref() == Some("Warning") => Phase::Warning,
+        _ => Phase::Other,
+    }
+}
+
+/// Container an event is about, from a field path like `spec.containers{app}`
+fn event_container(event: &Event) -> Option<String> {
+    let path = event.involved_object.field_path.as_deref()?;
+    let (_, rest) = path.split_once('{')?;
+    rest.strip_suffix('}').map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(reason: &str, kind: &str, container: Option<&str>, at: &str, count: i32) -> Event {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": format!("web-1.{}", reason.to_lowercase())},
+            "involvedObject": {
+                "kind": "Pod", "name": "web-1", "uid": "abc",
+                "fieldPath": container.map(|c| format!("spec.containers{{{}}}", c)),
+            },
+            "reason": reason,
+            "message": format!("{} message", reason),
+            "type": kind,
+            "count": count,
+            "firstTimestamp": at,
+            "lastTimestamp": "2024-05-01T12:02:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_timeline() {
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": "web-1", "namespace": "shop", "uid": "abc",
+                "creationTimestamp": "2024-05-01T12:00:00Z",
+            },
+            "status": {
+                "conditions": [
+                    {"type": "PodScheduled", "status": "True",
+                     "lastTransitionTime": "2024-05-01T12:00:02Z"},
+                    {"type": "Ready", "status": "True",
+                     "lastTransitionTime": "2024-05-01T12:01:30Z"},
+                ],
+                "containerStatuses": [{
+                    "name": "app", "image": "web", "imageID": "", "ready": true,
+                    "restartCount": 1,
+                    "lastState": {"terminated": {
+                        "exitCode": 1, "reason": "Error", "finishedAt": "2024-05-01T12:01:00Z",
+                    }},
+                }],
+            },
+        }))
+        .unwrap();
+        let mut stale = event("Started", "Normal", Some("app"), "2024-04-30T08:00:00Z", 1);
+        stale.involved_object.uid = Some("old".to_string());
+        let events = [
+            event("Scheduled", "Normal", None, "2024-05-01T12:00:02Z", 1),
+            event("Pulling", "Normal", Some("app"), "2024-05-01T12:00:03Z", 1),
+            event("Pulled", "Normal", Some("app"), "2024-05-01T12:00:43Z", 1),
+            event("Started", "Normal", Some("app"), "2024-05-01T12:00:45Z", 1),
+            event(
+                "Unhealthy",
+                "Warning",
+                Some("app"),
+                "2024-05-01T12:00:50Z",
+                3,
+            ),
+            stale,
+        ];
+
+        let timeline = build_timeline(&pod, &events);
+        let summary: Vec<(Phase, &str)> = timeline
+            .entries
+            .iter()
+            .map(|e| (e.phase, e.reason.as_str()))
+            .collect();
+        // The PodScheduled condition duplicates the Scheduled event
+        assert_eq!(
+            summary,
+            vec![
+                (Phase::Created, "Created"),
+                (Phase::Scheduling, "Scheduled"),
+                (Phase::Pulling, "Pulling"),
+                (Phase::Pulling, "Pulled"),
+                (Phase::Starting, "Started"),
+                (Phase::Warning, "Unhealthy"),
+                (Phase::Restart, "Terminated"),
+                (Phase::Ready, "Ready"),
+            ]
+        );
+        assert_eq!(timeline.entries[0].since_previous, None);
+        assert_eq!(timeline.entries[3].since_previous, Some(40));
+        assert_eq!(timeline.entries[3].container.as_deref(), Some("app"));
+        assert_eq!(
+            timeline.entries[5].message,
+            "Unhealthy message (x3 over 1m)"
+        );
+        assert_eq!(
+            timeline.entries[6].message,
+            "Error, exit 1 (restart count 1)"
+        );
+
+        let durations: Vec<(&str, i64)> = timeline
+            .durations
+            .iter()
+            .map(|d| (d.phase.as_str(), d.seconds))
+            .collect();
+        assert_eq!(
+            durations,
+            vec![
+                ("Scheduling", 2),
+                ("Image pull", 40),
+                ("Container start", 43),
+                ("Readiness", 45),
+                ("Total to ready", 90),
+            ]
+        );
+    }
+}
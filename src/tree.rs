@@ -0,0 +1,179 @@
+//! Ownership tree of an object (`kdx tree`)
+//!
+//! Owners are followed upwards through ownerReferences as in `kdx owners`.
+//! Downwards, every namespaced object in the namespace is indexed by the
+//! owners it names, so dependents of any kind are found, including ones
+//! created by operators for custom resources.
+
+use crate::age::object_age;
+use crate::describe::{self, DescribeKind};
+use crate::discovery::{DiscoveryEngine, OwnedObject, OwnerIndex};
+use crate::error::{ExplorerError, Result};
+use crate::owners::{self, OwnerLink};
+use kube::api::DynamicObject;
+use kube::discovery::Scope;
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Safety limit on tree depth in case of ownership cycles
+const MAX_DEPTH: usize = 16;
+
+/// An object and the objects it owns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub kind: String,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub age: String,
+    pub children: Vec<TreeNode>,
+}
+
+/// The owners above an object, outermost first, and everything it owns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerTree {
+    pub owners: Vec<OwnerLink>,
+    pub root: TreeNode,
+}
+
+impl OwnerTree {
+    /// Number of objects below the root
+    pub fn dependent_count(&self) -> usize {
+        fn count(node: &TreeNode) -> usize {
+            node.children.iter().map(|c| 1 + count(c)).sum()
+        }
+        count(&self.root)
+    }
+}
+
+/// `object` with its dependents from `index`, each object visited once
+pub fn build_tree(index: &OwnerIndex, object: &OwnedObject) -> TreeNode {
+    fn build(
+        index: &OwnerIndex,
+        object: &OwnedObject,
+        seen: &mut HashSet<String>,
+        depth: usize,
+    ) -> TreeNode {
+        let mut children = Vec::new();
+        if depth < MAX_DEPTH && seen.insert(object.uid.clone()) {
+            for dependent in index.dependents(&object.uid) {
+                if !seen.contains(&dependent.uid) {
+                    children.push(build(index, dependent, seen, depth + 1));
+                }
+            }
+        }
+        TreeNode {
+            kind: object.kind.clone(),
+            name: object.name.clone(),
+            namespace: object.namespace.clone(),
+            age: object.age.clone(),
+            children,
+        }
+    }
+    build(index, object, &mut HashSet::new(), 0)
+}
+
+/// Ownership tree of the object of `kind` named `name` in `namespace`
+pub async fn owner_tree(
+    discovery: &DiscoveryEngine,
+    kind: &str,
+    name: &str,
+    namespace: &str,
+) -> Result<OwnerTree> {
+    let (resource, scope) = match describe::resolve_kind(discovery, kind).await? {
+        DescribeKind::Builtin(kind) => (kind.api_resource(), Scope::Namespaced),
+        DescribeKind::Other(resource, scope) => (resource, scope),
+    };
+    if matches!(scope, Scope::Cluster) {
+        return Err(ExplorerError::InvalidArgument(format!(
+            "{} is cluster-scoped; tree only walks namespaced objects",
+            resource.kind
+        )));
+    }
+
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(discovery.client().clone(), namespace, &resource);
+    let object =
+        api.get_metadata_opt(name)
+            .await?
+            .ok_or_else(|| ExplorerError::ResourceNotFound {
+                kind: resource.kind.clone(),
+                name: name.to_string(),
+                namespace: namespace.to_string(),
+            })?;
+    let start = OwnedObject {
+        kind: resource.kind.clone(),
+        name: name.to_string(),
+        namespace: Some(namespace.to_string()),
+        uid: object.metadata.uid.clone().unwrap_or_default(),
+        age: object_age(&object.metadata),
+    };
+
+    let chain = owners::ownership_chain_of(discovery.client(), &resource, name, namespace).await?;
+    let mut owners: Vec<OwnerLink> = chain.chain.into_iter().skip(1).collect();
+    owners.reverse();
+
+    let index = discovery.owner_index(namespace).await?;
+    Ok(OwnerTree {
+        owners,
+        root: build_tree(&index, &start),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+    fn object(kind: &str, name: &str, uid: &str, owner: Option<&str>) -> (String, ObjectMeta) {
+        let metadata = serde_json::from_value(serde_json::json!({
+            "name": name,
+            "namespace": "shop",
+            "uid": uid,
+            "ownerReferences": owner.map(|owner| vec![serde_json::json!({
+                "apiVersion": "v1", "kind": "Owner", "name": "owner", "uid": owner,
+            })]),
+        }))
+        .unwrap();
+        (kind.to_string(), metadata)
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let index = OwnerIndex::from_objects(vec![
+            object("ReplicaSet", "web-5d4f8", "rs-1", Some("deploy-1")),
+            object("Pod", "web-5d4f8-b", "pod-2", Some("rs-1")),
+            object("Pod", "web-5d4f8-a", "pod-1", Some("rs-1")),
+            object("ReplicaSet", "web-7c9d2", "rs-2", Some("deploy-1")),
+            object("Pod", "db-0", "pod-3", Some("sts-1")),
+            // An ownership cycle must not loop
+            object("ConfigMap", "loop", "deploy-1", Some("pod-1")),
+        ]);
+        let deployment = OwnedObject {
+            kind: "Deployment".to_string(),
+            name: "web".to_string(),
+            namespace: Some("shop".to_string()),
+            uid: "deploy-1".to_string(),
+            age: "1d".to_string(),
+        };
+        let tree = build_tree(&index, &deployment);
+        let names: Vec<(&str, Vec<&str>)> = tree
+            .children
+            .iter()
+            .map(|rs| {
+                (
+                    rs.name.as_str(),
+                    rs.children.iter().map(|p| p.name.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("web-5d4f8", vec!["web-5d4f8-a", "web-5d4f8-b"]),
+                ("web-7c9d2", vec![])
+            ]
+        );
+        assert!(tree.children[0].children[0].children.is_empty());
+    }
+}
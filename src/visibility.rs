@@ -0,0 +1,282 @@
+//! What a service account can see, checked by impersonating it
+//!
+//! Every probe is a real list call (limited to one object) made with the
+//! service account's identity, so the result reflects RBAC as the API server
+//! enforces it. A resource that can be listed across all namespaces at once
+//! is visible everywhere; otherwise each namespace is probed on its own.
+
+use crate::error::{ExplorerError, Result};
+use crate::resource::ResourceKind;
+use futures::{stream, StreamExt};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Event, Namespace, Node, PersistentVolume, PersistentVolumeClaim, Secret,
+};
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::{Api, Client};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Namespaces probed at the same time
+const PROBE_CONCURRENCY: usize = 16;
+
+/// A service account given as `namespace/name`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl ServiceAccount {
+    /// User name the API server authenticates the service account as
+    pub fn username(&self) -> String {
+        format!("system:serviceaccount:{}:{}", self.namespace, self.name)
+    }
+
+    /// Groups every token of the service account carries, which RBAC
+    /// bindings may name instead of the account itself
+    pub fn groups(&self) -> Vec<String> {
+        vec![
+            "system:serviceaccounts".to_string(),
+            format!("system:serviceaccounts:{}", self.namespace),
+            "system:authenticated".to_string(),
+        ]
+    }
+}
+
+impl fmt::Display for ServiceAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.namespace, self.name)
+    }
+}
+
+impl FromStr for ServiceAccount {
+    type Err = ExplorerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('/') {
+            Some((namespace, name))
+                if !namespace.is_empty() && !name.is_empty() && !name.contains('/') =>
+            {
+                Ok(ServiceAccount {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                })
+            }
+            _ => Err(ExplorerError::InvalidArgument(format!(
+                "expected a service account as namespace/name, got '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Make every request of clients built from `config` as `account`
+pub fn impersonate(config: &mut kube::Config, account: &ServiceAccount) {
+    config.auth_info.impersonate = Some(account.username());
+    config.auth_info.impersonate_groups = Some(account.groups());
+}
+
+/// Whether a cluster-scoped resource can be listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterAccess {
+    pub resource: String,
+    pub visible: bool,
+}
+
+/// Namespaces in which a namespaced resource can be listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceVisibility {
+    pub resource: String,
+    /// Listable across all namespaces in one call
+    pub cluster_wide: bool,
+    pub namespaces: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisibilityReport {
+    pub service_account: String,
+    /// Namespaces that were checked
+    pub namespaces: Vec<String>,
+    pub cluster_scoped: Vec<ClusterAccess>,
+    pub namespaced: Vec<ResourceVisibility>,
+}
+
+impl VisibilityReport {
+    /// Visible resources per namespace; namespaces where nothing is visible
+    /// are left out
+    pub fn by_namespace(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut by_namespace: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for resource in &self.namespaced {
+            for namespace in &resource.namespaces {
+                by_namespace
+                    .entry(namespace)
+                    .or_default()
+                    .push(&resource.resource);
+            }
+        }
+        by_namespace
+    }
+
+    /// Checked namespaces in which nothing is visible
+    pub fn hidden_namespaces(&self) -> Vec<&str> {
+        let visible = self.by_namespace();
+        self.namespaces
+            .iter()
+            .map(String::as_str)
+            .filter(|ns| !visible.contains_key(ns))
+            .collect()
+    }
+}
+
+fn namespaced_resources() -> Vec<ApiResource> {
+    let mut resources: Vec<ApiResource> =
+        ResourceKind::ALL.iter().map(|k| k.api_resource()).collect();
+    resources.extend([
+        ApiResource::erase::<ConfigMap>(&()),
+        ApiResource::erase::<Secret>(&()),
+        ApiResource::erase::<PersistentVolumeClaim>(&()),
+        ApiResource::erase::<Ingress>(&()),
+        ApiResource::erase::<Event>(&()),
+    ]);
+    resources
+}
+
+fn cluster_resources() -> Vec<ApiResource> {
+    vec![
+        ApiResource::erase::<Namespace>(&()),
+        ApiResource::erase::<Node>(&()),
+        ApiResource::erase::<PersistentVolume>(&()),
+        ApiResource::erase::<CustomResourceDefinition>(&()),
+    ]
+}
+
+/// Whether `resource` can be listed, in `namespace` or across the cluster
+async fn can_list(
+    client: &Client,
+    resource: &ApiResource,
+    namespace: Option<&str>,
+    account: &ServiceAccount,
+) -> Result<bool> {
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(client.clone(), ns, resource),
+        None => Api::all_with(client.clone(), resource),
+    };
+    match api.list_metadata(&ListParams::default().limit(1)).await {
+        Ok(_) => Ok(true),
+        // The operator may not impersonate; every probe would fail the same way
+        Err(kube::Error::Api(e)) if e.code == 403 && e.message.contains("impersonate") => {
+            Err(ExplorerError::InvalidArgument(format!(
+                "cannot impersonate {}: {}",
+                account, e.message
+            )))
+        }
+        Err(kube::Error::Api(e)) if (403..=405).contains(&e.code) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Check what `client`, impersonating `account`, can list in `namespaces`
+/// and at cluster scope
+pub async fn check_visibility(
+    client: &Client,
+    account: &ServiceAccount,
+    namespaces: Vec<String>,
+) -> Result<VisibilityReport> {
+    let mut cluster_scoped = Vec::new();
+    for resource in cluster_resources() {
+        cluster_scoped.push(ClusterAccess {
+            visible: can_list(client, &resource, None, account).await?,
+            resource: resource.plural,
+        });
+    }
+
+    let mut namespaced = Vec::new();
+    for resource in namespaced_resources() {
+        let cluster_wide = can_list(client, &resource, None, account).await?;
+        let mut visible = if cluster_wide {
+            namespaces.clone()
+        } else {
+            let probes: Vec<(String, Result<bool>)> = stream::iter(namespaces.iter().cloned())
+                .map(|ns| {
+                    let resource = &resource;
+                    async move {
+                        let visible = can_list(client, resource, Some(&ns), account).await;
+                        (ns, visible)
+                    }
+                })
+                .buffer_unordered(PROBE_CONCURRENCY)
+                .collect()
+                .await;
+            let mut visible = Vec::new();
+            for (ns, result) in probes {
+                if result? {
+                    visible.push(ns);
+                }
+            }
+            visible
+        };
+        visible.sort();
+        namespaced.push(ResourceVisibility {
+            resource: resource.plural,
+            cluster_wide,
+            namespaces: visible,
+        });
+    }
+
+    Ok(VisibilityReport {
+        service_account: account.to_string(),
+        namespaces,
+        cluster_scoped,
+        namespaced,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_account() {
+        let account: ServiceAccount = "tenant-a/deployer".parse().unwrap();
+        assert_eq!(
+            account.username(),
+            "system:serviceaccount:tenant-a:deployer"
+        );
+        assert!(account
+            .groups()
+            .contains(&"system:serviceaccounts:tenant-a".to_string()));
+        assert!("deployer".parse::<ServiceAccount>().is_err());
+        assert!("tenant-a/".parse::<ServiceAccount>().is_err());
+        assert!("a/b/c".parse::<ServiceAccount>().is_err());
+    }
+
+    #[test]
+    fn test_visibility_by_namespace() {
+        let resource = |name: &str, namespaces: &[&str]| ResourceVisibility {
+            resource: name.to_string(),
+            cluster_wide: false,
+            namespaces: namespaces.iter().map(|s| s.to_string()).collect(),
+        };
+        let report = VisibilityReport {
+            service_account: "tenant-a/deployer".to_string(),
+            namespaces: ["default", "tenant-a", "tenant-b"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            cluster_scoped: Vec::new(),
+            namespaced: vec![
+                resource("pods", &["tenant-a", "tenant-b"]),
+                resource("secrets", &["tenant-a"]),
+            ],
+        };
+
+        let by_namespace = report.by_namespace();
+        assert_eq!(by_namespace["tenant-a"], ["pods", "secrets"]);
+        assert_eq!(by_namespace["tenant-b"], ["pods"]);
+        assert_eq!(report.hidden_namespaces(), ["default"]);
+    }
+}
@@ -0,0 +1,442 @@
+//! Waiting for workloads, services and pods to reach a condition
+
+use crate::error::{ExplorerError, Result};
+use crate::resource::{ObjectRef, ResourceKind};
+use crate::session::ClientSession;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::core::v1::{Endpoints, Pod};
+use kube::{Api, Client};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Condition to wait for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitCondition {
+    /// All replicas updated and ready (workloads) or the Ready condition (pods)
+    Ready,
+    /// At least one ready endpoint address behind a service
+    Endpoints,
+    /// Pod phase (Running, Succeeded, ...)
+    Phase(String),
+}
+
+impl FromStr for WaitCondition {
+    type Err = ExplorerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.to_lowercase();
+        match lower.as_str() {
+            "ready" | "available" => Ok(WaitCondition::Ready),
+            "endpoints" => Ok(WaitCondition::Endpoints),
+            "pending" | "running" | "succeeded" | "failed" => {
+                Ok(WaitCondition::Phase(phase(&lower)))
+            }
+            _ => match lower.strip_prefix("phase=") {
+                Some(value) if !value.is_empty() => Ok(WaitCondition::Phase(phase(value))),
+                _ => Err(ExplorerError::InvalidArgument(format!(
+                    "unknown condition '{}' (expected ready, endpoints or phase=<Phase>)",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+/// Capitalise a phase name the way the API reports it
+fn phase(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Result of a single condition check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionState {
+    Met,
+    Pending(String),
+    Failed(String),
+}
+
+/// Poll `target` until `condition` holds, the condition becomes unreachable, or
+/// `timeout` elapses. Progress messages are written to stderr when they change.
+/// When the credentials are rejected, polling goes on with a client rebuilt
+/// by `session`.
+pub async fn wait_for(
+    session: &ClientSession,
+    target: &ObjectRef,
+    namespace: &str,
+    condition: &WaitCondition,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<()> {
+    validate(target.kind, condition)?;
+    if interval.is_zero() {
+        return Err(ExplorerError::InvalidArgument(
+            "the polling interval must be greater than zero".to_string(),
+        ));
+    }
+
+    let started = Instant::now();
+    let mut last_message = String::new();
+    let (mut generation, mut client) = session.client().await;
+    let mut refreshed = false;
+
+    loop {
+        let state = match check(&client, target, namespace, condition).await {
+            Err(e) if e.is_unauthorized() && !refreshed => {
+                (generation, client) = session.refresh(generation).await?;
+                refreshed = true;
+                continue;
+            }
+            state => state?,
+        };
+        refreshed = false;
+        match state {
+            ConditionState::Met => return Ok(()),
+            ConditionState::Failed(reason) => {
+                return Err(ExplorerError::ConditionFailed(format!(
+                    "{}: {}",
+                    target, reason
+                )))
+            }
+            ConditionState::Pending(message) => {
+                if message != last_message {
+                    eprintln!("Waiting for {}: {}", target, message);
+                    last_message = message;
+                }
+            }
+        }
+
+        if started.elapsed() >= timeout {
+            return Err(ExplorerError::Timeout(format!(
+                "{} did not reach condition after {:?} ({})",
+                target, timeout, last_message
+            )));
+        }
+
+        tokio::time::sleep(interval.min(timeout.saturating_sub(started.elapsed()))).await;
+    }
+}
+
+/// Reject conditions that do not apply to the resource kind
+fn validate(kind: ResourceKind, condition: &WaitCondition) -> Result<()> {
+    let supported = match condition {
+        WaitCondition::Ready => !matches!(kind, ResourceKind::Job | ResourceKind::CronJob),
+        WaitCondition::Endpoints => kind == ResourceKind::Service,
+        WaitCondition::Phase(_) => kind == ResourceKind::Pod,
+    };
+
+    if supported {
+        Ok(())
+    } else {
+        Err(ExplorerError::InvalidArgument(format!(
+            "condition {:?} is not supported for {}",
+            condition, kind
+        )))
+    }
+}
+
+async fn check(
+    client: &Client,
+    target: &ObjectRef,
+    namespace: &str,
+    condition: &WaitCondition,
+) -> Result<ConditionState> {
+    let name = target.name.as_str();
+    let not_found = || ConditionState::Pending("not found yet".to_string());
+
+    let state = match target.kind {
+        ResourceKind::Deployment => {
+            let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(name)
+                .await?
+                .map_or_else(not_found, |d| deployment_state(&d))
+        }
+        ResourceKind::StatefulSet => {
+            let api: Api<StatefulSet> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(name)
+                .await?
+                .map_or_else(not_found, |s| statefulset_state(&s))
+        }
+        ResourceKind::DaemonSet => {
+            let api: Api<DaemonSet> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(name)
+                .await?
+                .map_or_else(not_found, |d| daemonset_state(&d))
+        }
+        ResourceKind::ReplicaSet => {
+            let api: Api<ReplicaSet> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(name)
+                .await?
+                .map_or_else(not_found, |r| replicaset_state(&r))
+        }
+        ResourceKind::Job | ResourceKind::CronJob => {
+            return Err(ExplorerError::InvalidArgument(format!(
+                "waiting is not supported for {}",
+                target.kind
+            )))
+        }
+        ResourceKind::Service => {
+            let api: Api<Endpoints> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(name)
+                .await?
+                .map_or_else(not_found, |e| endpoints_state(&e))
+        }
+        ResourceKind::Pod => {
+            let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(name)
+                .await?
+                .map_or_else(not_found, |p| pod_state(&p, condition))
+        }
+    };
+
+    Ok(state)
+}
+
+/// Replica progress shared by the workload checks
+fn replica_state(desired: i32, updated: i32, ready: i32, observed: bool) -> ConditionState {
+    if !observed {
+        ConditionState::Pending("waiting for controller to observe latest spec".to_string())
+    } else if updated < desired || ready < desired {
+        ConditionState::Pending(format!(
+            "{}/{} updated, {}/{} ready",
+            updated, desired, ready, desired
+        ))
+    } else {
+        ConditionState::Met
+    }
+}
+
+fn deployment_state(deployment: &Deployment) -> ConditionState {
+    let desired = deployment
+        .spec
+        .as_ref()
+        .and_then(|s| s.replicas)
+        .unwrap_or(1);
+    let status = deployment.status.clone().unwrap_or_default();
+    let observed = status.observed_generation >= deployment.metadata.generation;
+    let updated = status.updated_replicas.unwrap_or(0);
+
+    // Pods of the old ReplicaSets still count in status.replicas until they
+    // have terminated
+    match replica_state(
+        desired,
+        updated,
+        status.available_replicas.unwrap_or(0),
+        observed,
+    ) {
+        ConditionState::Met if status.replicas.unwrap_or(0) > updated => {
+            ConditionState::Pending(format!(
+                "{} old replicas pending termination",
+                status.replicas.unwrap_or(0) - updated
+            ))
+        }
+        state => state,
+    }
+}
+
+fn statefulset_state(statefulset: &StatefulSet) -> ConditionState {
+    let desired = statefulset
+        .spec
+        .as_ref()
+        .and_then(|s| s.replicas)
+        .unwrap_or(1);
+    let status = statefulset.status.clone().unwrap_or_default();
+    let observed = status.observed_generation >= statefulset.metadata.generation;
+
+    replica_state(
+        desired,
+        status.updated_replicas.unwrap_or(0),
+        status.ready_replicas.unwrap_or(0),
+        observed,
+    )
+}
+
+fn replicaset_state(replicaset: &ReplicaSet) -> ConditionState {
+    let desired = replicaset
+        .spec
+        .as_ref()
+        .and_then(|s| s.replicas)
+        .unwrap_or(1);
+    let status = replicaset.status.clone().unwrap_or_default();
+    let observed = status.observed_generation >= replicaset.metadata.generation;
+
+    // ReplicaSets have no rolling update, so every replica counts as updated
+    replica_state(
+        desired,
+        status.replicas,
+        status.ready_replicas.unwrap_or(0),
+        observed,
+    )
+}
+
+fn daemonset_state(daemonset: &DaemonSet) -> ConditionState {
+    let status = daemonset.status.clone().unwrap_or_default();
+    let observed = status.observed_generation >= daemonset.metadata.generation;
+
+    replica_state(
+        status.desired_number_scheduled,
+        status.updated_number_scheduled.unwrap_or(0),
+        status.number_ready,
+        observed,
+    )
+}
+
+fn endpoints_state(endpoints: &Endpoints) -> ConditionState {
+    let ready = endpoints
+        .subsets
+        .iter()
+        .flatten()
+        .map(|subset| subset.addresses.as_ref().map_or(0, |a| a.len()))
+        .sum::<usize>();
+
+    if ready > 0 {
+        ConditionState::Met
+    } else {
+        ConditionState::Pending("no ready endpoints".to_string())
+    }
+}
+
+fn pod_state(pod: &Pod, condition: &WaitCondition) -> ConditionState {
+    let status = pod.status.clone().unwrap_or_default();
+    let current = status.phase.unwrap_or_else(|| "Unknown".to_string());
+    let terminal = current == "Succeeded" || current == "Failed";
+
+    match condition {
+        WaitCondition::Phase(wanted) if current == *wanted => ConditionState::Met,
+        WaitCondition::Phase(wanted) if terminal => {
+            ConditionState::Failed(format!("pod is {}, will never be {}", current, wanted))
+        }
+        WaitCondition::Phase(_) => ConditionState::Pending(format!("phase {}", current)),
+        _ => {
+            let ready = status
+                .conditions
+                .iter()
+                .flatten()
+                .any(|c| c.type_ == "Ready" && c.status == "True");
+            if ready {
+                ConditionState::Met
+            } else if terminal {
+                ConditionState::Failed(format!("pod is {} and will not become ready", current))
+            } else {
+                ConditionState::Pending(format!("phase {}, not ready", current))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::{DeploymentSpec, DeploymentStatus};
+    use k8s_openapi::api::core::v1::{EndpointAddress, EndpointSubset, PodStatus};
+
+    #[test]
+    fn test_parse_condition() {
+        assert_eq!(
+            "ready".parse::<WaitCondition>().unwrap(),
+            WaitCondition::Ready
+        );
+        assert_eq!(
+            "phase=succeeded".parse::<WaitCondition>().unwrap(),
+            WaitCondition::Phase("Succeeded".to_string())
+        );
+        assert_eq!(
+            "Running".parse::<WaitCondition>().unwrap(),
+            WaitCondition::Phase("Running".to_string())
+        );
+        assert!("healthy".parse::<WaitCondition>().is_err());
+    }
+
+    #[test]
+    fn test_validate_condition_for_kind() {
+        assert!(validate(ResourceKind::Service, &WaitCondition::Endpoints).is_ok());
+        assert!(validate(ResourceKind::Deployment, &WaitCondition::Endpoints).is_err());
+        assert!(validate(ResourceKind::Job, &WaitCondition::Ready).is_err());
+        assert!(validate(
+            ResourceKind::Deployment,
+            &WaitCondition::Phase("Running".to_string())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_deployment_state() {
+        let mut deployment = Deployment {
+            spec: Some(DeploymentSpec {
+                replicas: Some(3),
+                ..Default::default()
+            }),
+            status: Some(DeploymentStatus {
+                replicas: Some(4),
+                updated_replicas: Some(3),
+                available_replicas: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            deployment_state(&deployment),
+            ConditionState::Pending(_)
+        ));
+
+        // Every new pod is available but an old one is still terminating
+        deployment.status.as_mut().unwrap().available_replicas = Some(3);
+        assert_eq!(
+            deployment_state(&deployment),
+            ConditionState::Pending("1 old replicas pending termination".to_string())
+        );
+
+        deployment.status.as_mut().unwrap().replicas = Some(3);
+        assert_eq!(deployment_state(&deployment), ConditionState::Met);
+
+        deployment.metadata.generation = Some(2);
+        deployment.status.as_mut().unwrap().observed_generation = Some(1);
+        assert!(matches!(
+            deployment_state(&deployment),
+            ConditionState::Pending(_)
+        ));
+    }
+
+    #[test]
+    fn test_endpoints_state() {
+        let mut endpoints = Endpoints::default();
+        assert!(matches!(
+            endpoints_state(&endpoints),
+            ConditionState::Pending(_)
+        ));
+
+        endpoints.subsets = Some(vec![EndpointSubset {
+            addresses: Some(vec![EndpointAddress {
+                ip: "10.0.0.1".to_string(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }]);
+        assert_eq!(endpoints_state(&endpoints), ConditionState::Met);
+    }
+
+    #[test]
+    fn test_pod_state() {
+        let pod = |phase: &str| Pod {
+            status: Some(PodStatus {
+                phase: Some(phase.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let running = WaitCondition::Phase("Running".to_string());
+        assert_eq!(pod_state(&pod("Running"), &running), ConditionState::Met);
+        assert!(matches!(
+            pod_state(&pod("Pending"), &running),
+            ConditionState::Pending(_)
+        ));
+        assert!(matches!(
+            pod_state(&pod("Failed"), &WaitCondition::Ready),
+            ConditionState::Failed(_)
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! Cache warming for `kdx cache warm`
+//!
+//! Namespaces are warmed concurrently, each one loading the requested
+//! resource types in turn so its duration reflects the whole namespace.
+//! CRDs are cluster-scoped and warmed once. Failures are collected instead of
+//! aborting the run.
+
+use crate::cli::WarmResource;
+use crate::discovery::DiscoveryEngine;
+use crate::error::Result;
+use crate::progress::ProgressTracker;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+
+/// A resource type that could not be loaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmFailure {
+    pub resource: String,
+    pub error: String,
+}
+
+/// Result of warming one namespace, or the cluster-scoped resources when
+/// `namespace` is `None`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceWarmup {
+    pub namespace: Option<String>,
+    /// Resource types loaded successfully
+    pub resources: usize,
+    /// Objects now in the cache
+    pub items: usize,
+    pub millis: u64,
+    pub failures: Vec<WarmFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmSummary {
+    /// Label selector the services and pods were cached under
+    pub selector: Option<String>,
+    /// Cluster-scoped resources first, then namespaces by name
+    pub namespaces: Vec<NamespaceWarmup>,
+    pub total_millis: u64,
+}
+
+impl WarmSummary {
+    pub fn items(&self) -> usize {
+        self.namespaces.iter().map(|n| n.items).sum()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.namespaces.iter().map(|n| n.failures.len()).sum()
+    }
+}
+
+/// Load the given resource types into the cache, at most `parallelism`
+/// namespaces at a time
+pub async fn warm_cache(
+    engine: &DiscoveryEngine,
+    namespaces: Vec<String>,
+    resources: &[WarmResource],
+    selector: Option<&str>,
+    page_size: usize,
+    parallelism: usize,
+    progress: Option<&ProgressTracker>,
+) -> WarmSummary {
+    let started = Instant::now();
+    let (cluster, namespaced): (Vec<WarmResource>, Vec<WarmResource>) =
+        resources.iter().partition(|r| r.is_cluster_scoped());
+
+    let mut warmups = Vec::new();
+    if !cluster.is_empty() {
+        warmups
+            .push(warm_namespace(engine, None, &cluster, selector, page_size, parallelism).await);
+    }
+
+    let total = namespaces.len();
+    let mut join_set = JoinSet::new();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(parallelism.max(1)));
+    if !namespaced.is_empty() {
+        for namespace in namespaces {
+            let engine = engine.clone();
+            let resources = namespaced.clone();
+            let selector = selector.map(|s| s.to_string());
+            let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+            join_set.spawn(async move {
+                let _permit = permit;
+                warm_namespace(
+                    &engine,
+                    Some(namespace),
+                    &resources,
+                    selector.as_deref(),
+                    page_size,
+                    parallelism,
+                )
+                .await
+            });
+        }
+    }
+
+    let mut completed = 0;
+    while let Some(result) = join_set.join_next().await {
+        completed += 1;
+        if let Some(progress) = progress {
+            progress.set_position(completed);
+            progress.set_message(&format!("Warmed {}/{} namespaces", completed, total));
+        }
+
+        match result {
+            Ok(warmup) => warmups.push(warmup),
+            Err(e) => eprintln!("Warning: Task failed: {}", e),
+        }
+    }
+
+    // None sorts first, keeping the cluster-scoped row on top
+    warmups.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+    WarmSummary {
+        selector: selector.map(|s| s.to_string()),
+        namespaces: warmups,
+        total_millis: started.elapsed().as_millis() as u64,
+    }
+}
+
+async fn warm_namespace(
+    engine: &DiscoveryEngine,
+    namespace: Option<String>,
+    resources: &[WarmResource],
+    selector: Option<&str>,
+    page_size: usize,
+    parallelism: usize,
+) -> NamespaceWarmup {
+    let started = Instant::now();
+    let mut warmup = NamespaceWarmup {
+        namespace,
+        resources: 0,
+        items: 0,
+        millis: 0,
+        failures: Vec::new(),
+    };
+
+    for resource in resources {
+        let namespace = warmup.namespace.as_deref();
+        match warm_resource(
+            engine,
+            *resource,
+            namespace,
+            selector,
+            page_size,
+            parallelism,
+        )
+        .await
+        {
+            Ok(items) => {
+                warmup.resources += 1;
+                warmup.items += items;
+            }
+            Err(e) => warmup.failures.push(WarmFailure {
+                resource: resource.to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    warmup.millis = started.elapsed().as_millis() as u64;
+    warmup
+}
+
+/// Load one resource type into the cache, returning the number of objects
+async fn warm_resource(
+    engine: &DiscoveryEngine,
+    resource: WarmResource,
+    namespace: Option<&str>,
+    selector: Option<&str>,
+    page_size: usize,
+    parallelism: usize,
+) -> Result<usize> {
+    let count = match resource {
+        WarmResource::Services => engine
+            .list_services_with_options(namespace, selector, None, page_size, true)
+            .await?
+            .len(),
+        WarmResource::Pods => engine
+            .list_pods_with_options(namespace, selector, None, page_size, true)
+            .await?
+            .len(),
+        WarmResource::Deployments => engine
+            .list_deployments_with_options(namespace, None, page_size, true)
+            .await?
+            .len(),
+        WarmResource::Statefulsets => engine
+            .list_statefulsets_with_options(namespace, true)
+            .await?
+            .len(),
+        WarmResource::Daemonsets => engine
+            .list_daemonsets_with_options(namespace, true)
+            .await?
+            .len(),
+        WarmResource::Configmaps => engine
+            .list_configmaps_with_options(namespace, None, page_size, true)
+            .await?
+            .len(),
+        WarmResource::Secrets => engine
+            .list_secrets_with_options(namespace, true)
+            .await?
+            .len(),
+        WarmResource::Crds => engine.list_crds_with_options(true).await?.len(),
+        WarmResource::CustomResources => engine
+            .list_all_custom_resources_with_options(namespace, parallelism, true)
+            .await?
+            .iter()
+            .map(|crd| crd.instance_count)
+            .sum(),
+    };
+    Ok(count)
+}
@@ -0,0 +1,768 @@
+//! Watch mode for list commands (`--watch`)
+//!
+//! Objects are listed once per namespace and then kept current with the watch
+//! API. Table output is redrawn in place after every change; JSON output is a
+//! stream of newline-delimited ADDED/MODIFIED/DELETED events. A watch the API
+//! server closes is resumed from the last resource version, and when that
+//! version has expired the namespace is listed again and the difference is
+//! reported as ordinary events. When the credentials expire, the client is
+//! rebuilt from the kubeconfig and the watches resume. Watches across
+//! namespaces leave out the namespaces excluded by the namespace scope.
+//!
+//! Above the table, pods are counted by phase and deployments by readiness at
+//! every redraw, and the last counts are drawn as sparklines so a growing
+//! number of pending pods or unavailable deployments stands out.
+
+use crate::cache::ResourceCache;
+use crate::cli::{NamespaceTarget, Output, OutputFormat};
+use crate::discovery::{
+    convert_node_to_info, ConfigMapInfo, DaemonSetInfo, DeploymentInfo, DiscoveryEngine, NodeInfo,
+    PodInfo, SecretInfo, ServiceInfo, StatefulSetInfo,
+};
+use crate::error::{ExplorerError, Result};
+use crate::scope::NamespaceScope;
+use crate::session::ClientSession;
+use colored::Colorize;
+use futures::StreamExt;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{ConfigMap, Node, Pod, Secret, Service};
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{ListParams, WatchEvent, WatchParams};
+use kube::{Api, Client, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::io::IsTerminal;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeType {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl fmt::Display for ChangeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeType::Added => write!(f, "ADDED"),
+            ChangeType::Modified => write!(f, "MODIFIED"),
+            ChangeType::Deleted => write!(f, "DELETED"),
+        }
+    }
+}
+
+/// One line of `--watch -o json` output
+#[derive(Serialize)]
+struct WatchLine<'a, T> {
+    #[serde(rename = "type")]
+    change: ChangeType,
+    object: &'a T,
+}
+
+/// A kind whose list command can be watched
+pub trait Watched:
+    Resource<DynamicType = ()> + Clone + DeserializeOwned + fmt::Debug + Send + Sync + 'static
+{
+    /// Row the list command shows for an object
+    type Info: Clone;
+
+    /// Api over one namespace, or every namespace when `None`
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self>;
+
+    fn info(self, discovery: &DiscoveryEngine) -> impl Future<Output = Option<Self::Info>> + Send;
+
+    /// Keep the discovery cache in line with what the watch has seen, for
+    /// kinds the cache holds
+    fn remember(
+        _cache: &ResourceCache,
+        _namespace: Option<&str>,
+        _selector: Option<&str>,
+        _infos: Vec<Self::Info>,
+    ) {
+    }
+
+    /// Counts by state, followed as trends above the table, for kinds whose
+    /// state shows how an incident develops
+    fn tally(_infos: &[Self::Info]) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+}
+
+fn namespaced_api<K>(client: Client, namespace: Option<&str>) -> Api<K>
+where
+    K: Resource<DynamicType = (), Scope = NamespaceResourceScope>,
+{
+    match namespace {
+        Some(ns) => Api::namespaced(client, ns),
+        None => Api::all(client),
+    }
+}
+
+impl Watched for Service {
+    type Info = ServiceInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<ServiceInfo> {
+        discovery.convert_service_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<ServiceInfo>,
+    ) {
+        cache.set_services(namespace, selector, infos);
+    }
+}
+
+impl Watched for Pod {
+    type Info = PodInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<PodInfo> {
+        discovery.convert_pod_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<PodInfo>,
+    ) {
+        cache.set_pods(namespace, selector, infos);
+    }
+
+    fn tally(infos: &[PodInfo]) -> Vec<(String, usize)> {
+        ["Running", "Pending", "Succeeded", "Failed", "Unknown"]
+            .iter()
+            .map(|phase| {
+                let count = infos.iter().filter(|p| p.phase == *phase).count();
+                (phase.to_string(), count)
+            })
+            .collect()
+    }
+}
+
+// The workload and config caches are keyed by namespace only, so a watch
+// narrowed by a selector would leave a partial list behind
+
+impl Watched for Deployment {
+    type Info = DeploymentInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<DeploymentInfo> {
+        discovery.convert_deployment_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<DeploymentInfo>,
+    ) {
+        if selector.is_none() {
+            cache.set_deployments(namespace, infos);
+        }
+    }
+
+    fn tally(infos: &[DeploymentInfo]) -> Vec<(String, usize)> {
+        let (mut ready, mut partial, mut unavailable) = (0, 0, 0);
+        for deployment in infos {
+            if deployment.ready_replicas >= deployment.replicas {
+                ready += 1;
+            } else if deployment.ready_replicas > 0 {
+                partial += 1;
+            } else {
+                unavailable += 1;
+            }
+        }
+        vec![
+            ("Ready".to_string(), ready),
+            ("Partial".to_string(), partial),
+            ("Unavailable".to_string(), unavailable),
+        ]
+    }
+}
+
+impl Watched for StatefulSet {
+    type Info = StatefulSetInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<StatefulSetInfo> {
+        discovery.convert_statefulset_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<StatefulSetInfo>,
+    ) {
+        if selector.is_none() {
+            cache.set_statefulsets(namespace, infos);
+        }
+    }
+}
+
+impl Watched for DaemonSet {
+    type Info = DaemonSetInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<DaemonSetInfo> {
+        discovery.convert_daemonset_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<DaemonSetInfo>,
+    ) {
+        if selector.is_none() {
+            cache.set_daemonsets(namespace, infos);
+        }
+    }
+}
+
+impl Watched for ConfigMap {
+    type Info = ConfigMapInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<ConfigMapInfo> {
+        discovery.convert_configmap_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<ConfigMapInfo>,
+    ) {
+        if selector.is_none() {
+            cache.set_configmaps(namespace, infos);
+        }
+    }
+}
+
+impl Watched for Secret {
+    type Info = SecretInfo;
+
+    fn api(client: Client, namespace: Option<&str>) -> Api<Self> {
+        namespaced_api(client, namespace)
+    }
+
+    async fn info(self, discovery: &DiscoveryEngine) -> Option<SecretInfo> {
+        discovery.convert_secret_to_info(self).await
+    }
+
+    fn remember(
+        cache: &ResourceCache,
+        namespace: Option<&str>,
+        selector: Option<&str>,
+        infos: Vec<SecretInfo>,
+    ) {
+        if selector.is_none() {
+            cache.set_secrets(namespace, infos);
+        }
+    }
+}
+
+impl Watched for Node {
+    type Info = NodeInfo;
+
+    fn api(client: Client, _namespace: Option<&str>) -> Api<Self> {
+        Api::all(client)
+    }
+
+    async fn info(self, _discovery: &DiscoveryEngine) -> Option<NodeInfo> {
+        convert_node_to_info(self)
+    }
+}
+
+/// Latest version of every watched object, keyed by namespace and name
+pub struct WatchState<K> {
+    objects: BTreeMap<(String, String), K>,
+}
+
+impl<K> Default for WatchState<K> {
+    fn default() -> Self {
+        Self {
+            objects: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K: Resource + Clone> WatchState<K> {
+    fn key(object: &K) -> (String, String) {
+        (object.namespace().unwrap_or_default(), object.name_any())
+    }
+
+    /// Record the latest version of an object; `None` when it is unchanged
+    pub fn upsert(&mut self, object: K) -> Option<(ChangeType, K)> {
+        let change = match self.objects.get(&Self::key(&object)) {
+            None => ChangeType::Added,
+            Some(old) if old.resource_version() == object.resource_version() => return None,
+            Some(_) => ChangeType::Modified,
+        };
+        self.objects.insert(Self::key(&object), object.clone());
+        Some((change, object))
+    }
+
+    /// Forget a deleted object; `None` when it was never seen
+    pub fn remove(&mut self, object: K) -> Option<(ChangeType, K)> {
+        self.objects
+            .remove(&Self::key(&object))
+            .map(|_| (ChangeType::Deleted, object))
+    }
+
+    /// Replace the objects of `namespace` (every object when `None`) with a
+    /// fresh listing, reporting what changed since they were last seen
+    pub fn relist(&mut self, namespace: Option<&str>, items: Vec<K>) -> Vec<(ChangeType, K)> {
+        let listed: Vec<(String, String)> = items.iter().map(Self::key).collect();
+        let gone: Vec<K> = self
+            .objects
+            .iter()
+            .filter(|((ns, _), _)| namespace.is_none_or(|namespace| ns == namespace))
+            .filter(|(key, _)| !listed.contains(key))
+            .map(|(_, object)| object.clone())
+            .collect();
+
+        let mut changes: Vec<(ChangeType, K)> =
+            gone.into_iter().filter_map(|o| self.remove(o)).collect();
+        changes.extend(items.into_iter().filter_map(|o| self.upsert(o)));
+        changes
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = &K> {
+        self.objects.values()
+    }
+}
+
+/// Samples kept of each trend, one per redraw
+const TREND_SAMPLES: usize = 30;
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One character per value, scaled between the smallest and largest value
+pub fn sparkline(values: &[usize]) -> String {
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| match max - min {
+            0 => SPARK_LEVELS[0],
+            range => SPARK_LEVELS[(value - min) * (SPARK_LEVELS.len() - 1) / range],
+        })
+        .collect()
+}
+
+/// Rolling counts by state, rendered as sparklines in the watch header
+#[derive(Debug, Default)]
+pub struct Trends {
+    series: Vec<(String, VecDeque<usize>)>,
+}
+
+impl Trends {
+    /// Add one sample of every state; states missing from `counts` count zero
+    pub fn record(&mut self, counts: Vec<(String, usize)>) {
+        for (_, samples) in &mut self.series {
+            samples.push_back(0);
+        }
+        for (state, count) in counts {
+            match self.series.iter_mut().find(|(s, _)| *s == state) {
+                Some((_, samples)) => *samples.back_mut().unwrap() = count,
+                None => self.series.push((state, VecDeque::from([count]))),
+            }
+        }
+        for (_, samples) in &mut self.series {
+            if samples.len() > TREND_SAMPLES {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// `Running 12 ▁▃▅█ ↑` for every state seen in the window, or `None`
+    /// when there is nothing to show
+    pub fn render(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .series
+            .iter()
+            .filter(|(_, samples)| samples.iter().any(|&count| count > 0))
+            .map(|(state, samples)| {
+                let samples: Vec<usize> = samples.iter().copied().collect();
+                let first = samples[0];
+                let last = samples[samples.len() - 1];
+                let arrow = match last.cmp(&first) {
+                    std::cmp::Ordering::Greater => "↑",
+                    std::cmp::Ordering::Less => "↓",
+                    std::cmp::Ordering::Equal => "→",
+                };
+                format!("{} {} {} {}", state, last, sparkline(&samples), arrow)
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("   "))
+    }
+}
+
+/// What the task following one namespace reports
+enum Update<K> {
+    Listed {
+        namespace: Option<String>,
+        items: Vec<K>,
+    },
+    Event(WatchEvent<K>),
+}
+
+/// List and watch `api` until the receiver goes away or a request fails,
+/// resuming closed watches and listing again when the resource version has
+/// expired. `refreshed` is cleared once a request succeeds.
+async fn follow_api<K: Watched>(
+    api: &Api<K>,
+    namespace: &Option<String>,
+    selector: &Option<String>,
+    resource_version: &mut Option<String>,
+    refreshed: &mut bool,
+    updates: &mpsc::UnboundedSender<Result<Update<K>>>,
+) -> Result<()> {
+    let mut list_params = ListParams::default();
+    let mut watch_params = WatchParams::default();
+    if let Some(selector) = selector {
+        list_params = list_params.labels(selector);
+        watch_params = watch_params.labels(selector);
+    }
+
+    loop {
+        let version = match resource_version.take() {
+            Some(version) => version,
+            None => {
+                let list = api.list(&list_params).await?;
+                *refreshed = false;
+                let version = list.metadata.resource_version.unwrap_or_default();
+                let listed = Update::Listed {
+                    namespace: namespace.clone(),
+                    items: list.items,
+                };
+                if updates.send(Ok(listed)).is_err() {
+                    return Ok(());
+                }
+                version
+            }
+        };
+
+        *resource_version = Some(version.clone());
+        let mut stream = api.watch(&watch_params, &version).await?.boxed();
+        *refreshed = false;
+        while let Some(event) = stream.next().await {
+            match event? {
+                WatchEvent::Bookmark(bookmark) => {
+                    *resource_version = Some(bookmark.metadata.resource_version);
+                }
+                // 410 Gone: the version is too old to resume from
+                WatchEvent::Error(e) if e.code == 410 => {
+                    *resource_version = None;
+                    break;
+                }
+                WatchEvent::Error(e) => return Err(ExplorerError::Kubernetes(kube::Error::Api(e))),
+                event => {
+                    if let WatchEvent::Added(object)
+                    | WatchEvent::Modified(object)
+                    | WatchEvent::Deleted(object) = &event
+                    {
+                        if let Some(version) = object.resource_version() {
+                            *resource_version = Some(version);
+                        }
+                    }
+                    if updates.send(Ok(Update::Event(event))).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Follow `namespace` with the session's client. When the credentials are
+/// rejected the client is rebuilt and the watch resumes from the last
+/// resource version; a rejection of the rebuilt client ends the watch.
+async fn follow<K: Watched>(
+    session: Arc<ClientSession>,
+    namespace: Option<String>,
+    selector: Option<String>,
+    updates: &mpsc::UnboundedSender<Result<Update<K>>>,
+) -> Result<()> {
+    let (mut generation, client) = session.client().await;
+    let mut api = K::api(client, namespace.as_deref());
+    let mut resource_version = None;
+    let mut refreshed = false;
+    loop {
+        let followed = follow_api(
+            &api,
+            &namespace,
+            &selector,
+            &mut resource_version,
+            &mut refreshed,
+            updates,
+        )
+        .await;
+        match followed {
+            Err(e) if e.is_unauthorized() && !refreshed => {
+                let (rebuilt, client) = session.refresh(generation).await?;
+                generation = rebuilt;
+                api = K::api(client, namespace.as_deref());
+                refreshed = true;
+            }
+            followed => return followed,
+        }
+    }
+}
+
+/// Show the objects of a list command and keep them current until Ctrl-C
+/// is pressed. `filter` applies the command's client-side filters and
+/// `print` is the command's usual printer; table output is redrawn with it
+/// after every change, while JSON output is one event per line.
+pub async fn watch_list<K, F, P>(
+    discovery: &DiscoveryEngine,
+    target: &NamespaceTarget,
+    selector: Option<&str>,
+    output: &Output,
+    filter: F,
+    print: P,
+) -> Result<()>
+where
+    K: Watched,
+    K::Info: Serialize,
+    F: Fn(Vec<K::Info>) -> Vec<K::Info>,
+    P: Fn(&[K::Info], &Output) -> Result<()>,
+{
+    if !matches!(
+        output.format,
+        OutputFormat::Table | OutputFormat::Name | OutputFormat::NsName | OutputFormat::Json
+    ) {
+        return Err(ExplorerError::InvalidArgument(format!(
+            "--watch supports table, name, ns-name and json output, not {}",
+            output
+        )));
+    }
+
+    let namespaces: Vec<Option<String>> = match target {
+        NamespaceTarget::Many(namespaces) => namespaces.iter().cloned().map(Some).collect(),
+        target => vec![target.namespace().map(str::to_string)],
+    };
+    // Namespaces named on the command line are watched whatever the scope
+    let scope = match target {
+        NamespaceTarget::All | NamespaceTarget::One(None) => discovery.namespace_scope().clone(),
+        _ => NamespaceScope::default(),
+    };
+    let (sender, mut updates) = mpsc::unbounded_channel();
+    for namespace in namespaces {
+        let session = discovery.session();
+        let selector = selector.map(str::to_string);
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            if let Err(e) = follow(session, namespace, selector, &sender).await {
+                let _ = sender.send(Err(e));
+            }
+        });
+    }
+    drop(sender);
+
+    let kind = K::plural(&());
+    let mut state = WatchState::<K>::default();
+    let mut trends = Trends::default();
+    let mut drawn = false;
+    loop {
+        let update = tokio::select! {
+            update = updates.recv() => update,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        let Some(update) = update else {
+            return Ok(());
+        };
+        let changes = match update? {
+            Update::Listed {
+                namespace,
+                mut items,
+            } => {
+                scope.retain(&mut items, |o: &K| o.meta().namespace.as_deref());
+                state.relist(namespace.as_deref(), items)
+            }
+            Update::Event(
+                WatchEvent::Added(object)
+                | WatchEvent::Modified(object)
+                | WatchEvent::Deleted(object),
+            ) if object
+                .namespace()
+                .is_some_and(|ns: String| !scope.includes(&ns)) =>
+            {
+                Vec::new()
+            }
+            Update::Event(WatchEvent::Added(object) | WatchEvent::Modified(object)) => {
+                state.upsert(object).into_iter().collect()
+            }
+            Update::Event(WatchEvent::Deleted(object)) => {
+                state.remove(object).into_iter().collect()
+            }
+            Update::Event(_) => Vec::new(),
+        };
+        if changes.is_empty() && drawn {
+            continue;
+        }
+        drawn = true;
+
+        if matches!(output.format, OutputFormat::Json) {
+            for (change, object) in changes {
+                if let Some(info) = object.info(discovery).await {
+                    for info in filter(vec![info]) {
+                        println!(
+                            "{}",
+                            serde_json::to_string(&WatchLine {
+                                change,
+                                object: &info,
+                            })?
+                        );
+                    }
+                }
+            }
+            continue;
+        }
+
+        let mut infos = Vec::new();
+        for object in state.objects() {
+            infos.extend(object.clone().info(discovery).await);
+        }
+        if let NamespaceTarget::One(namespace) = target {
+            K::remember(
+                discovery.cache(),
+                namespace.as_deref(),
+                selector,
+                infos.clone(),
+            );
+        }
+
+        if std::io::stdout().is_terminal() {
+            // Clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        } else {
+            println!();
+        }
+        println!(
+            "{}",
+            format!(
+                "Watching {} ({} seen), updated {} - Ctrl-C to stop",
+                kind,
+                infos.len(),
+                chrono::Local::now().format("%H:%M:%S")
+            )
+            .dimmed()
+        );
+        let shown = filter(infos);
+        trends.record(K::tally(&shown));
+        if let Some(trends) = trends.render() {
+            println!("{}", trends);
+        }
+        print(&shown, output)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(namespace: &str, name: &str, version: &str) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {"name": name, "namespace": namespace, "resourceVersion": version},
+        }))
+        .unwrap()
+    }
+
+    fn summary(changes: &[(ChangeType, Pod)]) -> Vec<(ChangeType, String)> {
+        changes
+            .iter()
+            .map(|(change, pod)| (*change, pod.name_any()))
+            .collect()
+    }
+
+    #[test]
+    fn test_watch_state() {
+        let mut state = WatchState::default();
+        let changes = state.relist(Some("a"), vec![pod("a", "web", "1"), pod("a", "db", "1")]);
+        assert_eq!(changes.len(), 2);
+        state.relist(Some("b"), vec![pod("b", "web", "1")]);
+
+        assert!(state.upsert(pod("a", "web", "1")).is_none());
+        assert_eq!(
+            state.upsert(pod("a", "web", "2")).map(|c| c.0),
+            Some(ChangeType::Modified)
+        );
+
+        // A relist of "a" reports what happened while the watch was down,
+        // and leaves namespace "b" alone
+        let changes = state.relist(Some("a"), vec![pod("a", "web", "3"), pod("a", "api", "1")]);
+        assert_eq!(
+            summary(&changes),
+            [
+                (ChangeType::Deleted, "db".to_string()),
+                (ChangeType::Modified, "web".to_string()),
+                (ChangeType::Added, "api".to_string()),
+            ]
+        );
+        assert_eq!(state.objects().count(), 3);
+        assert!(state.remove(pod("b", "gone", "1")).is_none());
+    }
+
+    #[test]
+    fn test_trends() {
+        assert_eq!(sparkline(&[0, 2, 4, 7]), "▁▃▅█");
+        assert_eq!(sparkline(&[5, 5]), "▁▁");
+
+        let counts = |running: usize, pending: usize| {
+            vec![
+                ("Running".to_string(), running),
+                ("Pending".to_string(), pending),
+                ("Failed".to_string(), 0),
+            ]
+        };
+        let mut trends = Trends::default();
+        assert_eq!(trends.render(), None);
+        trends.record(counts(10, 0));
+        trends.record(counts(8, 2));
+        trends.record(counts(6, 4));
+        // States never seen in the window are left out
+        assert_eq!(
+            trends.render().unwrap(),
+            "Running 6 █▄▁ ↓   Pending 4 ▁▄█ ↑"
+        );
+
+        for _ in 0..TREND_SAMPLES {
+            trends.record(counts(10, 0));
+        }
+        assert_eq!(
+            trends.render().unwrap(),
+            format!("Running 10 {} →", "▁".repeat(TREND_SAMPLES))
+        );
+    }
+}
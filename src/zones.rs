@@ -0,0 +1,223 @@
+//! Zone and region distribution of nodes, workloads and services
+
+use crate::discovery::{pod_workload, NodeInfo, PodInfo, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Zone reported for nodes without topology labels
+const UNKNOWN_ZONE: &str = "unknown";
+
+/// Node and pod counts for a single zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneSummary {
+    pub zone: String,
+    pub region: Option<String>,
+    pub nodes: usize,
+    pub pods: usize,
+    pub workloads: usize,
+}
+
+/// Replica placement of a workload across zones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadZoneSpread {
+    pub namespace: String,
+    pub kind: String,
+    pub name: String,
+    pub replicas_by_zone: BTreeMap<String, u32>,
+}
+
+/// A service whose backend pods all run in one zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SingleZoneService {
+    pub namespace: String,
+    pub name: String,
+    pub zone: String,
+    pub replicas: u32,
+}
+
+/// Cluster-wide zone distribution report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneReport {
+    pub zones: Vec<ZoneSummary>,
+    pub workloads: Vec<WorkloadZoneSpread>,
+    pub single_zone_services: Vec<SingleZoneService>,
+}
+
+/// Build the zone report from nodes, scheduled pods and services.
+///
+/// Services are only flagged when the cluster spans more than one zone, since
+/// single-zone clusters cannot spread replicas anyway.
+pub fn zone_report(nodes: &[NodeInfo], pods: &[PodInfo], services: &[ServiceInfo]) -> ZoneReport {
+    let node_zones: HashMap<&str, &str> = nodes
+        .iter()
+        .map(|node| {
+            let zone = node.zone.as_deref().unwrap_or(UNKNOWN_ZONE);
+            (node.name.as_str(), zone)
+        })
+        .collect();
+
+    let mut zones: BTreeMap<String, ZoneSummary> = BTreeMap::new();
+    for node in nodes {
+        let zone = node.zone.as_deref().unwrap_or(UNKNOWN_ZONE);
+        let summary = zones
+            .entry(zone.to_string())
+            .or_insert_with(|| ZoneSummary {
+                zone: zone.to_string(),
+                region: node.region.clone(),
+                nodes: 0,
+                pods: 0,
+                workloads: 0,
+            });
+        summary.nodes += 1;
+    }
+
+    let pod_zone = |pod: &PodInfo| -> Option<String> {
+        let node = pod.node_name.as_deref()?;
+        Some(node_zones.get(node).unwrap_or(&UNKNOWN_ZONE).to_string())
+    };
+
+    let mut workloads: BTreeMap<(String, String, String), WorkloadZoneSpread> = BTreeMap::new();
+    for pod in pods {
+        let Some(zone) = pod_zone(pod) else {
+            continue;
+        };
+
+        let (kind, name) = pod_workload(pod);
+        let spread = workloads
+            .entry((pod.namespace.clone(), kind.clone(), name.clone()))
+            .or_insert_with(|| WorkloadZoneSpread {
+                namespace: pod.namespace.clone(),
+                kind,
+                name,
+                replicas_by_zone: BTreeMap::new(),
+            });
+        *spread.replicas_by_zone.entry(zone.clone()).or_insert(0) += 1;
+
+        if let Some(summary) = zones.get_mut(&zone) {
+            summary.pods += 1;
+        }
+    }
+
+    for spread in workloads.values() {
+        for zone in spread.replicas_by_zone.keys() {
+            if let Some(summary) = zones.get_mut(zone) {
+                summary.workloads += 1;
+            }
+        }
+    }
+
+    let mut single_zone_services = Vec::new();
+    if zones.len() > 1 {
+        for service in services {
+            let Some(selector) = service.selector.as_ref().filter(|s| !s.is_empty()) else {
+                continue;
+            };
+
+            let backend_zones: Vec<String> = pods
+                .iter()
+                .filter(|pod| pod.namespace == service.namespace)
+                .filter(|pod| selector.iter().all(|(k, v)| pod.labels.get(k) == Some(v)))
+                .filter_map(pod_zone)
+                .collect();
+
+            let distinct: BTreeSet<&String> = backend_zones.iter().collect();
+            if let (1, Some(zone)) = (distinct.len(), distinct.first()) {
+                single_zone_services.push(SingleZoneService {
+                    namespace: service.namespace.clone(),
+                    name: service.name.clone(),
+                    zone: zone.to_string(),
+                    replicas: backend_zones.len() as u32,
+                });
+            }
+        }
+    }
+
+    ZoneReport {
+        zones: zones.into_values().collect(),
+        workloads: workloads.into_values().collect(),
+        single_zone_services,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, zone: &str) -> NodeInfo {
+        NodeInfo {
+            name: name.to_string(),
+            zone: Some(zone.to_string()),
+            region: Some("eu-west-1".to_string()),
+            spot: false,
+            taints: vec![],
+            age: "10d".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn pod(name: &str, app: &str, node: &str) -> PodInfo {
+        let mut labels = BTreeMap::new();
+        labels.insert("app".to_string(), app.to_string());
+        PodInfo {
+            node_name: Some(node.to_string()),
+            labels,
+            owner_kind: Some("StatefulSet".to_string()),
+            owner_name: Some(app.to_string()),
+            ..PodInfo::fixture("default", name)
+        }
+    }
+
+    fn service(name: &str, app: &str) -> ServiceInfo {
+        let mut selector = BTreeMap::new();
+        selector.insert("app".to_string(), app.to_string());
+        ServiceInfo {
+            name: name.to_string(),
+            namespace: "default".to_string(),
+            ports: vec![],
+            cluster_ip: None,
+            service_type: "ClusterIP".to_string(),
+            selector: Some(selector),
+            created_at: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_zone_report() {
+        let nodes = vec![node("n1", "a"), node("n2", "a"), node("n3", "b")];
+        let pods = vec![
+            pod("web-0", "web", "n1"),
+            pod("web-1", "web", "n3"),
+            pod("db-0", "db", "n1"),
+            pod("db-1", "db", "n2"),
+        ];
+        let services = vec![service("web", "web"), service("db", "db")];
+
+        let report = zone_report(&nodes, &pods, &services);
+
+        assert_eq!(report.zones.len(), 2);
+        assert_eq!(report.zones[0].zone, "a");
+        assert_eq!(report.zones[0].nodes, 2);
+        assert_eq!(report.zones[0].pods, 3);
+        assert_eq!(report.zones[0].workloads, 2);
+        assert_eq!(report.zones[1].pods, 1);
+
+        let db = &report.workloads[0];
+        assert_eq!(db.name, "db");
+        assert_eq!(db.replicas_by_zone.get("a"), Some(&2));
+
+        assert_eq!(report.single_zone_services.len(), 1);
+        assert_eq!(report.single_zone_services[0].name, "db");
+        assert_eq!(report.single_zone_services[0].replicas, 2);
+    }
+
+    #[test]
+    fn test_single_zone_cluster_flags_nothing() {
+        let nodes = vec![node("n1", "a")];
+        let pods = vec![pod("web-0", "web", "n1")];
+        let services = vec![service("web", "web")];
+
+        let report = zone_report(&nodes, &pods, &services);
+        assert!(report.single_zone_services.is_empty());
+    }
+}
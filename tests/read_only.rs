@@ -0,0 +1,90 @@
+//! `--read-only-verify` refuses the commands that connect to pods and
+//! lets the ones that only read run
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Run kdx against a kubeconfig for a cluster that is never reached, with
+/// a home directory of its own so no cache or loaded snapshot is shared
+fn kdx(name: &str, args: &[&str]) -> Output {
+    let home: PathBuf = std::env::temp_dir().join(format!("kdx-read-only-{}", name));
+    std::fs::create_dir_all(&home).unwrap();
+    let kubeconfig = home.join("kubeconfig");
+    std::fs::write(
+        &kubeconfig,
+        "apiVersion: v1
+kind: Config
+clusters:
+- name: test
+  cluster:
+    server: https://127.0.0.1:1
+contexts:
+- name: test
+  context:
+    cluster: test
+    user: test
+current-context: test
+users:
+- name: test
+  user:
+    token: test
+",
+    )
+    .unwrap();
+    Command::new(env!("CARGO_BIN_EXE_kdx"))
+        .args(args)
+        .env("KUBECONFIG", &kubeconfig)
+        .env("HOME", &home)
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn test_port_forward_refused() {
+    let output = kdx(
+        "port-forward",
+        &["--read-only-verify", "port-forward", "web", "8080:80"],
+    );
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("port-forward is not available with --read-only-verify"));
+}
+
+#[test]
+fn test_probe_matrix_refused() {
+    let output = kdx(
+        "probe-matrix",
+        &[
+            "--read-only-verify",
+            "probe-matrix",
+            "--services",
+            "web,api",
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("probe-matrix is not available with --read-only-verify"));
+}
+
+#[test]
+fn test_wait_allowed() {
+    let output = kdx(
+        "wait",
+        &[
+            "--read-only-verify",
+            "wait",
+            "deployment/web",
+            "--for",
+            "ready",
+            "--timeout",
+            "1s",
+        ],
+    );
+    // Waiting only reads, so it gets as far as a guarded get
+    assert!(!stderr(&output).contains("not available with --read-only-verify"));
+    assert!(stderr(&output).contains("[read-only] get"));
+}